@@ -0,0 +1,87 @@
+import 'mocha';
+import { expect } from 'chai';
+import BigNumber from 'bignumber.js';
+
+import { breakdown, estimate } from './fee';
+import { FeeAlgorithm, FeeConfig } from './types';
+import { TransferTransactionBuilder } from '../transaction/transfer';
+import { KeyPair } from '../key_pair';
+import { transfer, SINGLE_SIGN_ADDRESS } from '../address';
+import { Mainnet } from '../network';
+
+const SAMPLE_FEE_CONFIG: FeeConfig = {
+    algorithm: FeeAlgorithm.LinearFee,
+    constant: new BigNumber('1000'),
+    coefficient: new BigNumber('1001'),
+};
+
+const buildTransferTransaction = (): TransferTransactionBuilder => {
+    const keyPair = KeyPair.generateRandom();
+
+    return new TransferTransactionBuilder({ network: Mainnet })
+        .addInput({
+            prevTxId:
+                '0000000000000000000000000000000000000000000000000000000000000000',
+            prevIndex: 0,
+            prevOutput: {
+                address: transfer({ keyPair, network: Mainnet }),
+                value: new BigNumber('2000'),
+            },
+            addressParams: SINGLE_SIGN_ADDRESS,
+        })
+        .addOutput({
+            address:
+                'cro1p8c38xgv26c0wlzf0m8gugnn3fpaucrf5p98zhfaqvj4xr8mf97sp54ap3',
+            value: new BigNumber('1000'),
+        });
+};
+
+describe('fee', () => {
+    describe('estimate', () => {
+        it('should throw Error when the fee config is missing', () => {
+            expect(() => estimate(100)).to.throw(/`feeConfig`/);
+        });
+
+        it('should throw Error when the transaction size is not an integer', () => {
+            expect(() => estimate(1.5, SAMPLE_FEE_CONFIG)).to.throw(
+                'Expected number `txSize` to be an integer, got 1.5',
+            );
+        });
+
+        it('should return the same fee of transaction size and serialized transaction', () => {
+            expect(estimate(100, SAMPLE_FEE_CONFIG)).to.eq(
+                estimate(Buffer.alloc(100), SAMPLE_FEE_CONFIG),
+            );
+        });
+
+        it('should return the estimated fee of the builder with its fee config', () => {
+            const builder = buildTransferTransaction();
+
+            expect(estimate(builder)).to.eq(builder.estimateFee());
+        });
+    });
+
+    describe('breakdown', () => {
+        it('should break down the fee into its constant and size parts', () => {
+            const result = breakdown(100, SAMPLE_FEE_CONFIG);
+
+            expect(result.txSize).to.eq(100);
+            expect(result.fee).to.eq(estimate(100, SAMPLE_FEE_CONFIG));
+            expect(result.constantFee).to.eq(estimate(0, SAMPLE_FEE_CONFIG));
+            expect(
+                new BigNumber(result.constantFee)
+                    .plus(result.sizeFee)
+                    .toString(10),
+            ).to.eq(result.fee);
+        });
+
+        it('should break down the estimated fee of the builder', () => {
+            const builder = buildTransferTransaction();
+
+            const result = breakdown(builder);
+
+            expect(result.fee).to.eq(builder.estimateFee());
+            expect(result.txSize).to.be.greaterThan(0);
+        });
+    });
+});
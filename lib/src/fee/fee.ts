@@ -0,0 +1,86 @@
+import ow from 'ow';
+
+import {
+    FeeBreakdown,
+    FeeConfig,
+    owFeeConfig,
+    parseFeeConfigForNative,
+} from './types';
+import type { TransferTransactionBuilder } from '../transaction/transfer';
+
+const native = require('../../../native');
+
+/**
+ * Transaction size in bytes, serialized transaction or transfer transaction
+ * builder whose transaction size is estimated
+ */
+export type FeeEstimationTarget = number | Buffer | TransferTransactionBuilder;
+
+const toNativeTarget = (
+    target: FeeEstimationTarget,
+): number | Buffer | object => {
+    if (typeof target === 'number') {
+        ow(target, 'txSize', ow.number.integer.greaterThanOrEqual(0));
+        return target;
+    }
+    if (Buffer.isBuffer(target)) {
+        return target;
+    }
+
+    return {
+        incompleteHex: target.toIncompleteHex(),
+        feeConfig: parseFeeConfigForNative(target.feeConfig),
+    };
+};
+
+const toNativeFeeConfig = (
+    target: FeeEstimationTarget,
+    feeConfig?: FeeConfig,
+): object | undefined => {
+    if (typeof target !== 'number' && !Buffer.isBuffer(target)) {
+        return undefined;
+    }
+    ow(feeConfig, 'feeConfig', owFeeConfig);
+
+    return parseFeeConfigForNative(feeConfig!);
+};
+
+/**
+ * Estimate the fee of a transaction
+ *
+ * @export
+ * @param {FeeEstimationTarget} target transaction size in bytes, serialized
+ * transaction or transfer transaction builder
+ * @param {FeeConfig} [feeConfig] required unless given a builder, which fee
+ * config is used instead
+ * @returns {string} fee in basic unit
+ */
+export const estimate = (
+    target: FeeEstimationTarget,
+    feeConfig?: FeeConfig,
+): string => {
+    return native.fee.estimate(
+        toNativeTarget(target),
+        toNativeFeeConfig(target, feeConfig),
+    );
+};
+
+/**
+ * Break down the fee of a transaction into its constant and size parts
+ *
+ * @export
+ * @param {FeeEstimationTarget} target transaction size in bytes, serialized
+ * transaction or transfer transaction builder
+ * @param {FeeConfig} [feeConfig] required unless given a builder, see
+ * `estimate`
+ * @returns {FeeBreakdown}
+ */
+export const breakdown = (
+    target: FeeEstimationTarget,
+    feeConfig?: FeeConfig,
+): FeeBreakdown => {
+    return native.fee.breakdown(
+        toNativeTarget(target),
+        toNativeFeeConfig(target, feeConfig),
+    );
+};
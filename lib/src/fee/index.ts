@@ -1,2 +1,8 @@
 export { ZERO_LINEAR_FEE } from './constant';
-export { FeeAlgorithm, FeeConfig, LinearFeeConfig } from './types';
+export { estimate, breakdown, FeeEstimationTarget } from './fee';
+export {
+    FeeAlgorithm,
+    FeeBreakdown,
+    FeeConfig,
+    LinearFeeConfig,
+} from './types';
@@ -16,6 +16,23 @@ export type LinearFeeConfig = {
     coefficient: BigNumber;
 };
 
+/**
+ * Fee of a transaction broken down into its constant and size parts
+ * @typedef {object} FeeBreakdown
+ * @property {number} txSize transaction size in bytes
+ * @property {string} constantFee fee in basic unit charged regardless of the
+ * transaction size
+ * @property {string} sizeFee fee in basic unit charged for the transaction
+ * size
+ * @property {string} fee total fee in basic unit
+ */
+export interface FeeBreakdown {
+    txSize: number;
+    constantFee: string;
+    sizeFee: string;
+    fee: string;
+}
+
 /**
  * @internal
  */
@@ -31,6 +31,23 @@ describe('HDWallet', () => {
 
             expect(wallet.toSeed()).to.deep.eq(rustSeed);
         });
+
+        it('should restore the seed with passphrase', () => {
+            const mnemonic =
+                'point shiver hurt flight fun online hub antenna engine pave chef fantasy front interest poem accident catch load frequent praise elite pet remove used';
+            const passphrase = 'youshallnotpass';
+            const wallet = HDWallet.fromMnemonic(mnemonic, passphrase);
+
+            const rustSeed = native.hdWallet.mnemonicToSeed(
+                mnemonic,
+                passphrase,
+            );
+
+            expect(wallet.toSeed()).to.deep.eq(rustSeed);
+            expect(wallet.toSeed()).not.to.deep.eq(
+                HDWallet.fromMnemonic(mnemonic).toSeed(),
+            );
+        });
     });
 
     describe('toSeed', () => {
@@ -66,7 +83,7 @@ describe('HDWallet', () => {
 
             expect(() => {
                 wallet.derive('invalid');
-            }).to.throw('Expected BIP32Path, got String "invalid');
+            }).to.throw('Unable to derive key from path');
         });
 
         it('should return KeyPair', () => {
@@ -91,6 +108,43 @@ describe('HDWallet', () => {
 
             expect(keyPair).to.deep.eq(rustKeyPair);
         });
+
+        it('should derive the same key as the native derivePrivateKey', () => {
+            const wallet = createWallet();
+            const path = "m/44'/394'/0'/0/1";
+
+            const keyPair = wallet.derive(path);
+            const rustKeyPair = native.keyPair.derivePrivateKey(
+                wallet.toSeed(),
+                path,
+            );
+
+            expect(keyPair.toObject()).to.deep.eq(rustKeyPair);
+        });
+    });
+
+    describe('native deriveKeyAt', () => {
+        it('should derive at the coin type of the network', () => {
+            const wallet = createWallet();
+
+            const keyPair = native.hdWallet.deriveKeyAt(
+                wallet.toSeed(),
+                NetworkEnum.Testnet,
+                0,
+                0,
+                3,
+                'Viewkey',
+            );
+            const rustKeyPair = native.hdWallet.deriveKeyPairFromSeed(
+                wallet.toSeed(),
+                NetworkEnum.Testnet,
+                2,
+                3,
+            );
+
+            expect(keyPair.path).to.eq("m/44'/1'/2'/0/3");
+            expect(keyPair.privateKey).to.deep.eq(rustKeyPair.privateKey);
+        });
     });
 
     describe('derivef', () => {
@@ -139,5 +193,27 @@ describe('HDWallet', () => {
 
             expect(firstMnemonic).not.to.deep.eq(secondMnemonic);
         });
+
+        it('should generate valid 24 words Mnemonic', () => {
+            const mnemonic = HDWallet.generateMnemonic();
+
+            expect(mnemonic.split(' ')).to.have.lengthOf(24);
+            expect(() => {
+                HDWallet.fromMnemonic(mnemonic);
+            }).not.to.throw();
+        });
+
+        it('should throw INVALID_ARGUMENT when the native strength is not an integer', () => {
+            [128.9, NaN].forEach((strength) => {
+                let error: any;
+                try {
+                    native.hdWallet.generateMnemonic(strength);
+                } catch (err) {
+                    error = err;
+                }
+
+                expect(error?.code).to.eq('INVALID_ARGUMENT');
+            });
+        });
     });
 });
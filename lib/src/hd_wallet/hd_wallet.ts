@@ -1,8 +1,8 @@
 import ow from 'ow';
-import * as bip32 from 'bip32';
-import * as bip39 from 'bip39';
 import { KeyPair } from '../key_pair';
 
+const native = require('../../../native');
+
 export class HDWallet {
     private seed: Buffer;
 
@@ -15,10 +15,9 @@ export class HDWallet {
     public derive(path: string): KeyPair {
         ow(path, 'path', ow.string);
 
-        const root = bip32.fromSeed(this.seed);
-        const derivedResult = root.derivePath(path);
+        const derivedResult = native.keyPair.derivePrivateKey(this.seed, path);
 
-        return KeyPair.fromPrivateKey(derivedResult.privateKey!);
+        return KeyPair.fromPrivateKey(derivedResult.privateKey);
     }
 
     public derivef(formatPath: string, ...args: number[]): KeyPair {
@@ -52,22 +51,17 @@ export class HDWallet {
         ow(mnemonic, 'mnemonic', ow.string);
         ow(passphrase, 'passphrase', ow.optional.string);
 
-        if (!bip39.validateMnemonic(mnemonic)) {
+        if (!native.hdWallet.validateMnemonic(mnemonic).isValid) {
             throw new Error('Invalid mnemonic words');
         }
 
-        let seed: Buffer;
-        if (passphrase) {
-            seed = bip39.mnemonicToSeedSync(mnemonic, passphrase);
-        } else {
-            seed = bip39.mnemonicToSeedSync(mnemonic);
-        }
+        const seed = native.hdWallet.mnemonicToSeed(mnemonic, passphrase);
 
         return new HDWallet(seed);
     }
 
     public static generateMnemonic(): string {
-        return bip39.generateMnemonic(256);
+        return native.hdWallet.generateMnemonic(256);
     }
 
     public static AccountType = {
@@ -1,12 +1,27 @@
 import * as address from './address';
 import * as fee from './fee';
+import * as keystore from './keystore';
 import * as network from './network';
+import * as rpc from './rpc';
+import * as sync from './sync';
 import * as transaction from './transaction';
+import * as txQuery from './tx_query';
 import * as utils from './utils';
 
 export { Input, Output, Timespec } from './types';
+export { NativeTask } from './native_task';
 
-export { address, fee, network, transaction, utils };
+export {
+    address,
+    fee,
+    keystore,
+    network,
+    rpc,
+    sync,
+    transaction,
+    txQuery,
+    utils,
+};
 
 export { HDWallet } from './hd_wallet';
 export { KeyPair } from './key_pair';
@@ -2,6 +2,8 @@ import 'mocha';
 import { expect } from 'chai';
 import { KeyPair } from './key_pair';
 
+const native = require('../../../native');
+
 describe('KeyPair', () => {
     const validPublicKey = (): Buffer =>
         Buffer.from(
@@ -18,12 +20,12 @@ describe('KeyPair', () => {
         it('should throw Error when Public Key length is neither 65 nor 33', () => {
             const thirtyTwoBytesBuffer = Buffer.alloc(32, 0);
             expect(() => KeyPair.fromPublicKey(thirtyTwoBytesBuffer)).to.throw(
-                'Deserialization error: Unable to deserialize public key from bytes',
+                'argument 0 (public key): expected 33-byte compressed or 65-byte uncompressed public key Buffer, got 32 bytes',
             );
 
             const sixtySixBytesBuffer = Buffer.alloc(66, 0);
             expect(() => KeyPair.fromPublicKey(sixtySixBytesBuffer)).to.throw(
-                'Deserialization error: Unable to deserialize public key from bytes',
+                'argument 0 (public key): expected 33-byte compressed or 65-byte uncompressed public key Buffer, got 66 bytes',
             );
         });
 
@@ -69,7 +71,7 @@ describe('KeyPair', () => {
     describe('fromPrivateKey', () => {
         it('should throw Error when Private Key length is not 32', () => {
             expect(() => KeyPair.fromPrivateKey(Buffer.alloc(33, 0))).to.throw(
-                'Deserialization error: Unable to deserialize secret key',
+                'argument 0 (private key): expected 32-byte Buffer, got 33 bytes',
             );
         });
 
@@ -133,4 +135,77 @@ describe('KeyPair', () => {
             expect(obj.privateKey).to.deep.eq(keyPair.privateKey);
         });
     });
+    describe('sign', () => {
+        const digest = Buffer.alloc(32, 1);
+
+        it('should throw Error when the digest is not 32 bytes long', () => {
+            const keyPair = KeyPair.generateRandom();
+
+            expect(() => keyPair.sign(Buffer.from('message'))).to.throw(
+                'Expected digest to be 32 bytes long, got 7 bytes',
+            );
+        });
+
+        it('should throw Error when the KeyPair does not have private key', () => {
+            const keyPair = KeyPair.fromPublicKey(validPublicKey());
+
+            expect(() => keyPair.sign(digest)).to.throw(
+                'KeyPair does not have private key',
+            );
+        });
+
+        it('should return signature verifiable by the public key', () => {
+            const keyPair = KeyPair.generateRandom();
+
+            const signature = keyPair.sign(digest);
+
+            expect(signature.length).to.eq(64);
+            expect(keyPair.verify(digest, signature)).to.eq(true);
+            expect(keyPair.verify(Buffer.alloc(32, 2), signature)).to.eq(
+                false,
+            );
+        });
+
+        it('should throw INVALID_ARGUMENT natively when the digest is not 32 bytes long', () => {
+            const keyPair = KeyPair.generateRandom();
+
+            let error: any;
+            try {
+                native.keyPair.sign(Buffer.alloc(31), keyPair.privateKey);
+            } catch (err) {
+                error = err;
+            }
+            expect(error?.code).to.eq('INVALID_ARGUMENT');
+        });
+    });
+
+    describe('signRecoverable', () => {
+        it('should return signature whose public key is recoverable', () => {
+            const keyPair = KeyPair.generateRandom();
+            const digest = Buffer.alloc(32, 1);
+
+            const signature = keyPair.signRecoverable(digest);
+
+            expect(signature.length).to.eq(65);
+            expect(keyPair.verify(digest, signature)).to.eq(true);
+            expect(
+                KeyPair.recoverPublicKey(digest, signature).publicKey,
+            ).to.deep.eq(keyPair.publicKey);
+        });
+    });
+
+    describe('schnorrSign', () => {
+        it('should return Schnorr signature verifiable by the public key', () => {
+            const keyPair = KeyPair.generateRandom();
+            const digest = Buffer.alloc(32, 1);
+
+            const signature = keyPair.schnorrSign(digest);
+
+            expect(signature.length).to.eq(64);
+            expect(keyPair.schnorrVerify(digest, signature)).to.eq(true);
+            expect(
+                KeyPair.generateRandom().schnorrVerify(digest, signature),
+            ).to.eq(false);
+        });
+    });
 });
@@ -2,6 +2,11 @@ import ow from 'ow';
 
 const native = require('../../../native');
 
+const owDigest = ow.buffer.validate((value: Buffer) => ({
+    validator: value.length === 32,
+    message: `Expected digest to be 32 bytes long, got ${value.length} bytes`,
+}));
+
 /**
  * Represents a private and public key pair
  * @class KeyPair
@@ -114,6 +119,104 @@ export class KeyPair {
         return this.innerPrivateKey;
     }
 
+    /**
+     * ECDSA sign the message digest with the private key. The digest is
+     * signed as is, hash the message first, e.g. with SHA-256
+     *
+     * @param {Buffer} digest 32 bytes digest of the message
+     * @returns {Buffer} 64 bytes compact ECDSA signature
+     */
+    public sign(digest: Buffer): Buffer {
+        ow(digest, 'digest', owDigest);
+
+        return native.keyPair.sign(digest, this.requirePrivateKey());
+    }
+
+    /**
+     * ECDSA sign the message digest with the private key into a recoverable
+     * signature, see `sign`
+     *
+     * @param {Buffer} digest 32 bytes digest of the message
+     * @returns {Buffer} 65 bytes recoverable signature (r, s, recovery id)
+     */
+    public signRecoverable(digest: Buffer): Buffer {
+        ow(digest, 'digest', owDigest);
+
+        return native.keyPair.signRecoverable(digest, this.requirePrivateKey());
+    }
+
+    /**
+     * Schnorr sign the message digest with the private key, see `sign`
+     *
+     * @param {Buffer} digest 32 bytes digest of the message
+     * @returns {Buffer} 64 bytes Schnorr signature
+     */
+    public schnorrSign(digest: Buffer): Buffer {
+        ow(digest, 'digest', owDigest);
+
+        return native.keyPair.schnorrSign(digest, this.requirePrivateKey());
+    }
+
+    /**
+     * Verify the ECDSA signature of the message digest against the public key
+     *
+     * @param {Buffer} digest 32 bytes digest of the message
+     * @param {Buffer} signature 64 bytes compact or 65 bytes recoverable
+     * ECDSA signature
+     * @returns {boolean}
+     */
+    public verify(digest: Buffer, signature: Buffer): boolean {
+        ow(digest, 'digest', owDigest);
+        ow(signature, 'signature', ow.buffer);
+
+        return native.keyPair.verify(digest, signature, this.publicKey);
+    }
+
+    /**
+     * Verify the Schnorr signature of the message digest against the public
+     * key
+     *
+     * @param {Buffer} digest 32 bytes digest of the message
+     * @param {Buffer} signature 64 bytes Schnorr signature
+     * @returns {boolean}
+     */
+    public schnorrVerify(digest: Buffer, signature: Buffer): boolean {
+        ow(digest, 'digest', owDigest);
+        ow(signature, 'signature', ow.buffer);
+
+        return native.keyPair.schnorrVerify(digest, signature, this.publicKey);
+    }
+
+    /**
+     * Create a KeyPair of the public key recovered from the recoverable
+     * ECDSA signature of the message digest
+     *
+     * @param {Buffer} digest 32 bytes digest of the message
+     * @param {Buffer} signature 65 bytes recoverable signature
+     */
+    public static recoverPublicKey(digest: Buffer, signature: Buffer): KeyPair {
+        ow(digest, 'digest', owDigest);
+        ow(signature, 'signature', ow.buffer);
+
+        const {
+            publicKey,
+            compressedPublicKey,
+        } = native.keyPair.recoverPublicKey(digest, signature);
+
+        const keyPair = new KeyPair();
+        keyPair.addPublicKey(publicKey, compressedPublicKey);
+
+        return keyPair;
+    }
+
+    private requirePrivateKey(): Buffer {
+        if (!this.innerPrivateKey) {
+            throw new Error('KeyPair does not have private key');
+        }
+
+        return this.innerPrivateKey;
+    }
+
     /**
      * Transform the KeyPair into an object of private and public key
      */
@@ -0,0 +1,2 @@
+export { encrypt, decrypt } from './keystore';
+export { KeystoreOptions } from './types';
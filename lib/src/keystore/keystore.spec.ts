@@ -0,0 +1,115 @@
+import 'mocha';
+import { expect } from 'chai';
+
+import { decrypt, encrypt } from './keystore';
+import { KeystoreOptions } from './types';
+
+const ANY_PRIVATE_KEY = Buffer.alloc(32, 1);
+const ANY_PASSWORD = 'youshallnotpass';
+// Work factors of the minimum memory to keep the specs fast
+const FAST_ARGON2ID_OPTIONS: KeystoreOptions = {
+    memoryCost: 32,
+    timeCost: 1,
+    parallelism: 1,
+};
+
+describe('keystore', () => {
+    describe('encrypt', () => {
+        it('should throw Error when the private key is not a Buffer', () => {
+            expect(() => {
+                encrypt('private key' as any, ANY_PASSWORD);
+            }).to.throw(
+                'Expected `privateKey` to be of type `Buffer` but received type `string`',
+            );
+        });
+
+        it('should throw Error when the kdf is unsupported', () => {
+            expect(() => {
+                encrypt(ANY_PRIVATE_KEY, ANY_PASSWORD, {
+                    kdf: 'pbkdf2' as any,
+                });
+            }).to.throw(/to be one of/);
+        });
+
+        it('should reject when the work factor is out of bounds', async () => {
+            let error: any;
+            try {
+                await encrypt(ANY_PRIVATE_KEY, ANY_PASSWORD, {
+                    memoryCost: 1024 * 1024,
+                });
+            } catch (err) {
+                error = err;
+            }
+
+            expect(error?.code).to.eq('INVALID_ARGUMENT');
+        });
+
+        it('should record the KDF options in the keystore', async () => {
+            const keystore = JSON.parse(
+                await encrypt(
+                    ANY_PRIVATE_KEY,
+                    ANY_PASSWORD,
+                    FAST_ARGON2ID_OPTIONS,
+                ),
+            );
+
+            expect(keystore.crypto.kdf).to.eq('argon2id');
+            expect(keystore.crypto.kdfParams.memoryCost).to.eq(32);
+            expect(keystore.crypto.kdfParams.timeCost).to.eq(1);
+            expect(keystore.crypto.kdfParams.parallelism).to.eq(1);
+        });
+    });
+
+    describe('decrypt', () => {
+        it('should restore the private key encrypted with argon2id', async () => {
+            const keystore = await encrypt(
+                ANY_PRIVATE_KEY,
+                ANY_PASSWORD,
+                FAST_ARGON2ID_OPTIONS,
+            );
+
+            expect(await decrypt(keystore, ANY_PASSWORD)).to.deep.eq(
+                ANY_PRIVATE_KEY,
+            );
+        });
+
+        it('should restore the private key encrypted with scrypt', async () => {
+            const keystore = await encrypt(ANY_PRIVATE_KEY, ANY_PASSWORD, {
+                kdf: 'scrypt',
+                n: 16,
+            });
+
+            expect(await decrypt(keystore, ANY_PASSWORD)).to.deep.eq(
+                ANY_PRIVATE_KEY,
+            );
+        });
+
+        it('should reject with DECRYPTION_ERROR when the password is incorrect', async () => {
+            const keystore = await encrypt(
+                ANY_PRIVATE_KEY,
+                ANY_PASSWORD,
+                FAST_ARGON2ID_OPTIONS,
+            );
+
+            let error: any;
+            try {
+                await decrypt(keystore, 'incorrect');
+            } catch (err) {
+                error = err;
+            }
+
+            expect(error?.code).to.eq('DECRYPTION_ERROR');
+        });
+
+        it('should reject with DESERIALIZATION_ERROR when the keystore is malformed', async () => {
+            let error: any;
+            try {
+                await decrypt('{}', ANY_PASSWORD);
+            } catch (err) {
+                error = err;
+            }
+
+            expect(error?.code).to.eq('DESERIALIZATION_ERROR');
+        });
+    });
+});
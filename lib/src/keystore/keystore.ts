@@ -0,0 +1,58 @@
+import ow from 'ow';
+
+import { KeystoreOptions, owKeystoreOptions } from './types';
+import { NativeTask, runNativeTask } from '../native_task';
+
+const native = require('../../../native');
+
+/**
+ * Encrypt the private key into a JSON keystore with a key derived from the
+ * password. The key derivation runs on background thread
+ *
+ * @export
+ * @param {Buffer} privateKey
+ * @param {string} password
+ * @param {KeystoreOptions} [options] KDF and work factor, argon2id with 64 MiB
+ * of memory when omitted
+ * @returns {NativeTask<string>} keystore JSON string
+ */
+export const encrypt = (
+    privateKey: Buffer,
+    password: string,
+    options?: KeystoreOptions,
+): NativeTask<string> => {
+    ow(privateKey, 'privateKey', ow.buffer);
+    ow(password, 'password', ow.string);
+    ow(options, 'options', owKeystoreOptions);
+
+    return runNativeTask((callback) =>
+        native.keystore.encrypt(
+            privateKey,
+            password,
+            options ?? null,
+            callback,
+        ),
+    );
+};
+
+/**
+ * Decrypt the private key from the JSON keystore with a key derived from
+ * the password. The key derivation runs on background thread
+ *
+ * @export
+ * @param {string} keystore keystore JSON string
+ * @param {string} password
+ * @returns {NativeTask<Buffer>} private key, rejects with DECRYPTION_ERROR
+ * when the password is incorrect
+ */
+export const decrypt = (
+    keystore: string,
+    password: string,
+): NativeTask<Buffer> => {
+    ow(keystore, 'keystore', ow.string.nonEmpty);
+    ow(password, 'password', ow.string);
+
+    return runNativeTask((callback) =>
+        native.keystore.decrypt(keystore, password, callback),
+    );
+};
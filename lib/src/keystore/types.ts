@@ -0,0 +1,38 @@
+import ow from 'ow';
+
+/**
+ * KDF and work factor of the keystore, recorded in its kdfParams. The memory
+ * is at most 256 MiB and memory times passes at most 4 passes over 256 MiB
+ * @typedef {object} KeystoreOptions
+ * @property {string} [kdf] argon2id or scrypt, default argon2id
+ * @property {number} [memoryCost] argon2id memory in KiB, between
+ * 8 * parallelism and 262144, default 65536
+ * @property {number} [timeCost] argon2id number of iterations, between 1 and
+ * 64, default 3
+ * @property {number} [parallelism] argon2id number of lanes, between 1 and
+ * 64, default 4
+ * @property {number} [n] scrypt cost, a power of 2, default 32768
+ * @property {number} [r] scrypt block size, default 8
+ * @property {number} [p] scrypt parallelization, between 1 and 64, default 1
+ */
+export interface KeystoreOptions {
+    kdf?: 'argon2id' | 'scrypt';
+    memoryCost?: number;
+    timeCost?: number;
+    parallelism?: number;
+    n?: number;
+    r?: number;
+    p?: number;
+}
+
+const owPositiveInteger = ow.optional.number.integer.greaterThan(0);
+
+export const owKeystoreOptions = ow.optional.object.exactShape({
+    kdf: ow.optional.string.oneOf(['argon2id', 'scrypt']),
+    memoryCost: owPositiveInteger,
+    timeCost: owPositiveInteger,
+    parallelism: owPositiveInteger,
+    n: owPositiveInteger,
+    r: owPositiveInteger,
+    p: owPositiveInteger,
+});
@@ -1,2 +1,2 @@
 export { MultiSigBuilder } from './multi_sig_builder';
-export { MultiSigSession } from './multi_sig_session';
+export { MultiSigSession, MultiSigSessionHandle } from './multi_sig_session';
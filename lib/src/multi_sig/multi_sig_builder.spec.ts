@@ -95,7 +95,7 @@ describe('MultiSigBuilder', () => {
             message,
         );
 
-        expect(session.getRawSession()).to.be.instanceof(Buffer);
+        expect(session.getRawSession()).to.have.property('destroy');
     });
 
     // Message format should be 64 bytes hex string
@@ -104,7 +104,7 @@ describe('MultiSigBuilder', () => {
             const message = 'ffffffffff';
             customerBuilder.createNewSession(message);
         }).to.throw(
-            'input hash should be a hex string of 32 bytes, [255, 255, 255, 255, 255] is 5 bytes',
+            'argument 0 (hash): expected hex string of 32 bytes, got 5 bytes',
         );
     });
 
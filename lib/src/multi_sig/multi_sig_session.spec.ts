@@ -131,7 +131,7 @@ describe('MultiSigBuilder', () => {
             customerSession.nonce!,
         );
 
-        expect(session).to.be.instanceof(Buffer);
+        expect(session).to.eq(customerSession.getRawSession());
     });
 
     // Cannot add a signer's nonce multiple times
@@ -167,7 +167,7 @@ describe('MultiSigBuilder', () => {
             customerSession.partialSignature!,
         );
 
-        expect(session).to.be.instanceof(Buffer);
+        expect(session).to.eq(customerSession.getRawSession());
     });
 
     // Cannot add a signer's partial signature multiple times
@@ -207,4 +207,31 @@ describe('MultiSigBuilder', () => {
         const b = customerSession.verify(merchantSession.signature);
         expect(b).eq(true);
     });
+
+    step('should release the session after the final signature', () => {
+        expect(() => {
+            customerSession.partialSign();
+        }).to.throw(
+            'Multisig session is signed or destroyed, create a new session',
+        );
+        expect(customerSession.destroy()).to.eq(false);
+    });
+
+    step('should not use the session after it is destroyed', () => {
+        const session = new MultiSigBuilder(
+            customerKeyPair,
+            [merchantKeyPair.publicKey!],
+            network,
+            1,
+        ).createNewSession(
+            'efefefefefefefefefefefefefefefefefefefefefefefefefefefefefefefef',
+        );
+
+        expect(session.destroy()).to.eq(true);
+        expect(() => {
+            session.generateNonceCommitment();
+        }).to.throw(
+            'Multisig session is signed or destroyed, create a new session',
+        );
+    });
 });
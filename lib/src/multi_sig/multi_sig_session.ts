@@ -2,11 +2,21 @@ import ow from 'ow';
 
 import { KeyPair } from '../key_pair';
 import { owKeyPair } from '../key_pair/types';
-import { owMultiSigSessionNotEmpty } from './owTypes';
 
 const native = require('../../../native');
 
 type PublicKey = Buffer;
+
+/**
+ * Session handle kept in native memory. The private key and nonce of the
+ * signer never leave the native side, and are released once the final
+ * signature is created, the session is destroyed, or the handle is garbage
+ * collected
+ */
+export interface MultiSigSessionHandle {
+    destroy(): boolean;
+}
+
 /**
  * MultiSig session and its methods
  * @class MultiSigSession
@@ -21,8 +31,8 @@ export class MultiSigSession {
     // all parties' public keys, self ones and coSigners' public keys
     private signerPublicKeys: Array<PublicKey> = [];
 
-    // current session, could be complete or incomplete
-    private session: Buffer;
+    // native session handle, released after the final signature
+    private session: MultiSigSessionHandle;
 
     // self nonce commitment
     public nonceCommitment?: Buffer;
@@ -59,7 +69,7 @@ export class MultiSigSession {
         ow(signerPublicKeys, ow.array.minLength(2).ofType(ow.buffer));
         this.signerPublicKeys = signerPublicKeys;
 
-        this.session = native.multiSig.newSession(
+        this.session = native.multiSig.createSession(
             this.message,
             this.signerPublicKeys,
             this.keyPair.publicKey,
@@ -68,18 +78,26 @@ export class MultiSigSession {
     }
 
     /**
-     * return current multi-sig session
+     * return the native handle of current multi-sig session
      *
-     * @param message message for new session, e.g. fund tx
-     * @returns {session}
-     * @throws session to be not emtpy
+     * @returns {MultiSigSessionHandle}
      * @memberof MultiSigSession
      */
-    public getRawSession(): Buffer {
-        ow(this.session, 'session', owMultiSigSessionNotEmpty);
+    public getRawSession(): MultiSigSessionHandle {
         return this.session;
     }
 
+    /**
+     * destroy the session and release the private key and nonce from native
+     * memory, the session cannot be used afterwards
+     *
+     * @returns {boolean} whether the session was not signed or destroyed yet
+     * @memberof MultiSigSession
+     */
+    public destroy(): boolean {
+        return this.session.destroy();
+    }
+
     /**
      * return the message in current session
      *
@@ -100,13 +118,9 @@ export class MultiSigSession {
      * @memberof MultiSigSession
      */
     public generateNonceCommitment(): Buffer {
-        const {
-            nonceCommitment,
-            incompleteSession,
-        } = native.multiSig.generateNonceCommitment(this.session);
-
-        this.nonceCommitment = nonceCommitment;
-        this.session = incompleteSession;
+        this.nonceCommitment = native.multiSig.sessionNonceCommitment(
+            this.session,
+        );
 
         return this.nonceCommitment!;
     }
@@ -117,13 +131,13 @@ export class MultiSigSession {
      * @param coSignerPublicKey external co-signer's publickey
      * @param coSignerNonceCommitment external co-signer's nonce commitment
      * @throws coSignerNonceCommitment should be Buffer
-     * @returns {session} session after add other nonce commitment
+     * @returns {MultiSigSessionHandle} session after add other nonce commitment
      * @memberof MultiSigSession
      */
     public addNonceCommitment(
         coSignerPublicKey: Buffer,
         coSignerNonceCommitment: Buffer,
-    ): Buffer {
+    ): MultiSigSessionHandle {
         ow(coSignerNonceCommitment, ow.buffer);
 
         if (this.hasThisSignerNonceCommitment(coSignerNonceCommitment)) {
@@ -132,14 +146,14 @@ export class MultiSigSession {
             );
         }
 
-        this.session = native.multiSig.addNonceCommitment(
+        native.multiSig.sessionAddNonceCommitment(
             this.session,
             coSignerPublicKey,
             coSignerNonceCommitment,
         );
         this.coSignerNonceCommitments.push(coSignerNonceCommitment);
 
-        return this.session!;
+        return this.session;
     }
 
     /**
@@ -156,12 +170,7 @@ export class MultiSigSession {
             );
         }
 
-        const { nonce, incompleteSession } = native.multiSig.generateNonce(
-            this.session,
-        );
-
-        this.nonce = nonce;
-        this.session = incompleteSession;
+        this.nonce = native.multiSig.sessionNonce(this.session);
 
         return this.nonce!;
     }
@@ -173,10 +182,13 @@ export class MultiSigSession {
      * @param coSignerNonce external co-signer's nonce
      * @throws public key and nonce should be buffer
      * @throws the nonce has already been added
-     * @returns {session} session after add other nonce commitment
+     * @returns {MultiSigSessionHandle} session after add other nonce
      * @memberof MultiSigSession
      */
-    public addNonce(coSignerPublicKey: Buffer, coSignerNonce: Buffer): Buffer {
+    public addNonce(
+        coSignerPublicKey: Buffer,
+        coSignerNonce: Buffer,
+    ): MultiSigSessionHandle {
         ow(coSignerPublicKey, ow.buffer);
         ow(coSignerNonce, ow.buffer);
 
@@ -184,14 +196,14 @@ export class MultiSigSession {
             throw new Error("This co-signer's nonce has already been added");
         }
 
-        this.session = native.multiSig.addNonce(
+        native.multiSig.sessionAddNonce(
             this.session,
             coSignerPublicKey,
             coSignerNonce,
         );
         this.coSignerNonces.push(coSignerNonce);
 
-        return this.session!;
+        return this.session;
     }
 
     /**
@@ -208,13 +220,9 @@ export class MultiSigSession {
             );
         }
 
-        const {
-            partialSignature,
-            incompleteSession,
-        } = native.multiSig.partialSign(this.session);
-
-        this.partialSignature = partialSignature;
-        this.session = incompleteSession;
+        this.partialSignature = native.multiSig.sessionPartialSign(
+            this.session,
+        );
 
         return this.partialSignature!;
     }
@@ -226,13 +234,14 @@ export class MultiSigSession {
      * @param coSignerSignature external co-signer's partial signature
      * @throws public key and signature should be buffer
      * @throws the partial signature has already been added
-     * @returns {session} session after add other nonce commitment
+     * @returns {MultiSigSessionHandle} session after add other partial
+     * signature
      * @memberof MultiSigSession
      */
     public addPartialSignature(
         coSignerPublicKey: Buffer,
         coSignerSignature: Buffer,
-    ): Buffer {
+    ): MultiSigSessionHandle {
         ow(coSignerPublicKey, ow.buffer);
         ow(coSignerSignature, ow.buffer);
 
@@ -242,18 +251,19 @@ export class MultiSigSession {
             );
         }
 
-        this.session = native.multiSig.addPartialSignature(
+        native.multiSig.sessionAddPartialSignature(
             this.session,
             coSignerPublicKey,
             coSignerSignature,
         );
         this.coSignerSignatures.push(coSignerSignature);
 
-        return this.session!;
+        return this.session;
     }
 
     /**
-     * generate final signature, noted that it can only be taken after collected all partial signatures
+     * generate final signature, noted that it can only be taken after collected all partial signatures.
+     * The session is released afterwards
      *
      * @returns {signature}
      * @throws should collect all partial signature before calling sign
@@ -266,7 +276,7 @@ export class MultiSigSession {
             );
         }
 
-        this.signature = native.multiSig.sign(this.session);
+        this.signature = native.multiSig.sessionSignature(this.session);
 
         return this.signature!;
     }
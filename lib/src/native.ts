@@ -5,6 +5,12 @@ export enum Features {
     AllDefault = 'AllDefault',
     MockAbci = 'MockAbci',
     MockObfuscation = 'MockObfuscation',
+    PlainTransactions = 'PlainTransactions',
+    /**
+     * Mock tx-query backend, only available in the native module built with
+     * the `mock-tx-query` feature, e.g. by `npm run build:neon:mock-tx-query`
+     */
+    MockTxQuery = 'MockTxQuery',
 }
 
 /**
@@ -16,6 +22,8 @@ export const getRustFeaturesFromEnv = (nodeEnv?: string): Features => {
             return Features.MockObfuscation;
         case 'integration-test':
             return Features.AllDefault;
+        case 'mock-tx-query-test':
+            return Features.MockTxQuery;
         default:
             return Features.AllDefault;
     }
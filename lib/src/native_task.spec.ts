@@ -0,0 +1,62 @@
+import 'mocha';
+import { expect } from 'chai';
+
+import { NativeCallback, runNativeTask } from './native_task';
+
+describe('runNativeTask', () => {
+    it('should resolve with the result passed to the callback', async () => {
+        const result = await runNativeTask(
+            (callback: NativeCallback<number>) => {
+                setImmediate(() => callback(null, 1));
+            },
+        );
+
+        expect(result).to.eq(1);
+    });
+
+    it('should reject with the error passed to the callback', async () => {
+        let error: Error | undefined;
+        try {
+            await runNativeTask((callback: NativeCallback<number>) => {
+                setImmediate(() => callback(new Error('Task failed'), 0));
+            });
+        } catch (err) {
+            error = err;
+        }
+
+        expect(error?.message).to.eq('Task failed');
+    });
+
+    it('should reject when the native function throws', async () => {
+        let error: Error | undefined;
+        try {
+            await runNativeTask(() => {
+                throw new Error('Invalid argument');
+            });
+        } catch (err) {
+            error = err;
+        }
+
+        expect(error?.message).to.eq('Invalid argument');
+    });
+
+    it('should cancel through the task handle of the native function', () => {
+        let cancelled = false;
+        const task = runNativeTask(() => ({
+            id: 1,
+            cancel: () => {
+                cancelled = true;
+                return true;
+            },
+        }));
+
+        expect(task.cancel()).to.eq(true);
+        expect(cancelled).to.eq(true);
+    });
+
+    it('should not cancel when the native function returns no task handle', () => {
+        const task = runNativeTask(() => undefined);
+
+        expect(task.cancel()).to.eq(false);
+    });
+});
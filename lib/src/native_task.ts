@@ -0,0 +1,59 @@
+/**
+ * Node-style callback of the native functions running on background thread
+ *
+ * @internal
+ */
+export type NativeCallback<T> = (err: Error | null, result: T) => void;
+
+/**
+ * Task handle { id, cancel() } returned by the native functions scheduled
+ * with a callback
+ *
+ * @internal
+ */
+interface NativeTaskHandle {
+    id: number;
+    cancel(): boolean;
+}
+
+/**
+ * Promise of the result of a native function running on background thread
+ */
+export interface NativeTask<T> extends Promise<T> {
+    /**
+     * Cancel the task, the Promise rejects with a CANCELLED error
+     *
+     * @returns {boolean} whether the task was still running
+     */
+    cancel(): boolean;
+}
+
+/**
+ * Start the native function running on background thread with a callback
+ * and return a Promise of its result, which can be cancelled. Errors thrown
+ * by the native function before the work is scheduled, e.g. argument
+ * errors, reject the Promise
+ *
+ * @internal
+ * @param {Function} start calls the native function with the callback and
+ * returns its task handle
+ * @returns {NativeTask<T>} result passed to the callback
+ */
+export const runNativeTask = <T>(
+    start: (callback: NativeCallback<T>) => unknown,
+): NativeTask<T> => {
+    let handle: NativeTaskHandle | undefined;
+    const promise = new Promise<T>((resolve, reject) => {
+        handle = start((err: Error | null, result: T) => {
+            if (err) {
+                reject(err);
+            } else {
+                resolve(result);
+            }
+        }) as NativeTaskHandle | undefined;
+    });
+
+    return Object.assign(promise, {
+        cancel: (): boolean => handle?.cancel() ?? false,
+    });
+};
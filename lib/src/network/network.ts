@@ -95,3 +95,18 @@ export const fromChainId = (chainHexId: Buffer): NetworkEnum => {
             return NetworkEnum.Devnet;
     }
 };
+
+/**
+ * Convert network config to the inline network accepted by native functions,
+ * so that the call uses the network config instead of the network
+ * registered in native with the same name
+ *
+ * @internal
+ * @param {NetworkConfig} network network config
+ * @returns {object} inline network of native functions
+ */
+export const toNativeNetwork = (network: Readonly<NetworkConfig>) => ({
+    name: network.name,
+    chainHexId: network.chainHexId,
+    bech32Hrp: network.addressPrefix,
+});
@@ -0,0 +1,17 @@
+export {
+    broadcastTxSync,
+    broadcastTxCommit,
+    abciQuery,
+    abciQueryBatch,
+    getTxStatus,
+    waitForConfirmation,
+} from './rpc';
+export {
+    AbciQuery,
+    AbciQueryOptions,
+    AbciQueryResponse,
+    BroadcastTxCommitResult,
+    BroadcastTxSyncResult,
+    ConfirmationOptions,
+    TxStatus,
+} from './types';
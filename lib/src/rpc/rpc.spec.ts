@@ -0,0 +1,86 @@
+import 'mocha';
+import { expect } from 'chai';
+
+import { abciQuery, broadcastTxSync, getTxStatus } from './rpc';
+
+const native = require('../../../native');
+
+const UNREACHABLE_RPC_URL = 'http://127.0.0.1:1';
+const ANY_TX_ID =
+    '0000000000000000000000000000000000000000000000000000000000000000';
+
+describe('rpc', () => {
+    describe('broadcastTxSync', () => {
+        it('should throw Error when the Tendermint RPC endpoint is invalid', () => {
+            expect(() => {
+                broadcastTxSync('ws://127.0.0.1:26657', Buffer.alloc(1));
+            }).to.throw(
+                'Expected value to be HTTP, HTTPS or TCP Tendermint RPC endpoint',
+            );
+        });
+
+        it('should throw Error when the transaction is not a Buffer', () => {
+            expect(() => {
+                broadcastTxSync(UNREACHABLE_RPC_URL, 'tx' as any);
+            }).to.throw(
+                'Expected `txAux` to be of type `Buffer` but received type `string`',
+            );
+        });
+
+        it('should reject with CONNECTION_ERROR when the endpoint is unreachable', async () => {
+            let error: any;
+            try {
+                await broadcastTxSync(UNREACHABLE_RPC_URL, Buffer.alloc(1));
+            } catch (err) {
+                error = err;
+            }
+
+            expect(error?.code).to.eq('CONNECTION_ERROR');
+        });
+
+        it('should reject with CONNECTION_ERROR when the https endpoint is unreachable', async () => {
+            let error: any;
+            try {
+                await broadcastTxSync('https://localhost:1', Buffer.alloc(1));
+            } catch (err) {
+                error = err;
+            }
+
+            expect(error?.code).to.eq('CONNECTION_ERROR');
+        });
+
+        it('should not run synchronously without a callback', () => {
+            expect(() => {
+                native.rpc.broadcastTxSync(
+                    UNREACHABLE_RPC_URL,
+                    Buffer.alloc(1),
+                );
+            }).to.throw('network calls only run on background thread');
+        });
+    });
+
+    describe('abciQuery', () => {
+        it('should throw Error when the height is not a non-negative integer', () => {
+            expect(() => {
+                abciQuery(UNREACHABLE_RPC_URL, 'account', Buffer.alloc(1), {
+                    height: -1,
+                });
+            }).to.throw(/to be greater than or equal to 0/);
+        });
+    });
+
+    describe('getTxStatus', () => {
+        it('should throw Error when the transaction id is invalid', () => {
+            expect(() => {
+                getTxStatus(UNREACHABLE_RPC_URL, 'invalid');
+            }).to.throw(/Expected string `txId` to match/);
+        });
+
+        it('should return a Promise', () => {
+            const status = getTxStatus(UNREACHABLE_RPC_URL, ANY_TX_ID);
+            status.catch(() => undefined);
+
+            expect(status).to.be.instanceOf(Promise);
+        });
+    });
+});
@@ -0,0 +1,167 @@
+import ow from 'ow';
+
+import {
+    AbciQuery,
+    AbciQueryResponse,
+    BroadcastTxCommitResult,
+    BroadcastTxSyncResult,
+    owAbciQuery,
+    owAbciQueryOptions,
+    owConfirmationOptions,
+    owTendermintRpcUrl,
+    AbciQueryOptions,
+    ConfirmationOptions,
+    TxStatus,
+} from './types';
+import { owTxId } from '../types';
+import { NativeTask, runNativeTask } from '../native_task';
+
+const native = require('../../../native');
+
+/**
+ * Broadcast the transaction and return its CheckTx result
+ *
+ * @export
+ * @param {string} tendermintRpcUrl Tendermint RPC endpoint, e.g.
+ * http://127.0.0.1:26657
+ * @param {Buffer} txAux broadcast-able transaction
+ * @returns {NativeTask<BroadcastTxSyncResult>}
+ */
+export const broadcastTxSync = (
+    tendermintRpcUrl: string,
+    txAux: Buffer,
+): NativeTask<BroadcastTxSyncResult> => {
+    ow(tendermintRpcUrl, 'tendermintRpcUrl', owTendermintRpcUrl);
+    ow(txAux, 'txAux', ow.buffer);
+
+    return runNativeTask((callback) =>
+        native.rpc.broadcastTxSync(tendermintRpcUrl, txAux, callback),
+    );
+};
+
+/**
+ * Broadcast the transaction and wait for it to be committed in a block
+ *
+ * @export
+ * @param {string} tendermintRpcUrl Tendermint RPC endpoint
+ * @param {Buffer} txAux broadcast-able transaction
+ * @returns {NativeTask<BroadcastTxCommitResult>}
+ */
+export const broadcastTxCommit = (
+    tendermintRpcUrl: string,
+    txAux: Buffer,
+): NativeTask<BroadcastTxCommitResult> => {
+    ow(tendermintRpcUrl, 'tendermintRpcUrl', owTendermintRpcUrl);
+    ow(txAux, 'txAux', ow.buffer);
+
+    return runNativeTask((callback) =>
+        native.rpc.broadcastTxCommit(tendermintRpcUrl, txAux, callback),
+    );
+};
+
+/**
+ * Query the ABCI application
+ *
+ * @export
+ * @param {string} tendermintRpcUrl Tendermint RPC endpoint
+ * @param {string} path query path, e.g. "account"
+ * @param {Buffer} data query data
+ * @param {AbciQueryOptions} [options] height to query at and whether to
+ * include proof
+ * @returns {NativeTask<AbciQueryResponse>} response with key and value decoded
+ */
+export const abciQuery = (
+    tendermintRpcUrl: string,
+    path: string,
+    data: Buffer,
+    options: AbciQueryOptions = {},
+): NativeTask<AbciQueryResponse> => {
+    ow(tendermintRpcUrl, 'tendermintRpcUrl', owTendermintRpcUrl);
+    ow(path, 'path', ow.string);
+    ow(data, 'data', ow.buffer);
+    ow(options, 'options', owAbciQueryOptions);
+
+    return runNativeTask((callback) =>
+        native.rpc.abciQuery(
+            tendermintRpcUrl,
+            path,
+            data,
+            options.height ?? null,
+            options.prove ?? false,
+            callback,
+        ),
+    );
+};
+
+/**
+ * Query the ABCI application with multiple queries in a single request
+ *
+ * @export
+ * @param {string} tendermintRpcUrl Tendermint RPC endpoint
+ * @param {AbciQuery[]} queries
+ * @returns {NativeTask<AbciQueryResponse[]>} responses in the order of the
+ * queries
+ */
+export const abciQueryBatch = (
+    tendermintRpcUrl: string,
+    queries: AbciQuery[],
+): NativeTask<AbciQueryResponse[]> => {
+    ow(tendermintRpcUrl, 'tendermintRpcUrl', owTendermintRpcUrl);
+    ow(queries, 'queries', ow.array.ofType(owAbciQuery));
+
+    return runNativeTask((callback) =>
+        native.rpc.abciQueryBatch(tendermintRpcUrl, queries, callback),
+    );
+};
+
+/**
+ * Returns the status of the committed transaction
+ *
+ * @export
+ * @param {string} tendermintRpcUrl Tendermint RPC endpoint
+ * @param {string} txId transaction id, or Tendermint hash of the transaction.
+ * Failed transactions are only found by their Tendermint hash
+ * @returns {NativeTask<TxStatus | null>} null when the transaction is not
+ * committed yet
+ */
+export const getTxStatus = (
+    tendermintRpcUrl: string,
+    txId: string,
+): NativeTask<TxStatus | null> => {
+    ow(tendermintRpcUrl, 'tendermintRpcUrl', owTendermintRpcUrl);
+    ow(txId, 'txId', owTxId);
+
+    return runNativeTask((callback) =>
+        native.rpc.getTxStatus(tendermintRpcUrl, txId, callback),
+    );
+};
+
+/**
+ * Wait for the transaction to be committed and confirmed by the blocks.
+ * Failed transactions are returned as soon as committed, check the code
+ * before crediting the payment
+ *
+ * @export
+ * @param {string} tendermintRpcUrl Tendermint RPC endpoint
+ * @param {string} txId transaction id, or Tendermint hash of the transaction
+ * @param {ConfirmationOptions} [options]
+ * @returns {NativeTask<TxStatus>}
+ */
+export const waitForConfirmation = (
+    tendermintRpcUrl: string,
+    txId: string,
+    options: ConfirmationOptions = {},
+): NativeTask<TxStatus> => {
+    ow(tendermintRpcUrl, 'tendermintRpcUrl', owTendermintRpcUrl);
+    ow(txId, 'txId', owTxId);
+    ow(options, 'options', owConfirmationOptions);
+
+    return runNativeTask((callback) =>
+        native.rpc.waitForConfirmation(
+            tendermintRpcUrl,
+            txId,
+            options,
+            callback,
+        ),
+    );
+};
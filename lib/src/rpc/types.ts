@@ -0,0 +1,120 @@
+import ow from 'ow';
+
+export const owTendermintRpcUrl = ow.string.validate((value: string) => ({
+    validator: /^(https?|tcp):\/\/[^/]+/.test(value),
+    message: 'Expected value to be HTTP, HTTPS or TCP Tendermint RPC endpoint',
+}));
+
+/**
+ * CheckTx result of a broadcast transaction
+ * @typedef {object} BroadcastTxSyncResult
+ * @property {number} code CheckTx result code, 0 when the transaction is
+ * accepted
+ * @property {string} data
+ * @property {string} log
+ * @property {string} hash Tendermint hash of the transaction
+ */
+export interface BroadcastTxSyncResult {
+    code: number;
+    data: string;
+    log: string;
+    hash: string;
+}
+
+/**
+ * Result of a transaction committed in a block
+ * @typedef {object} BroadcastTxCommitResult
+ */
+export interface BroadcastTxCommitResult {
+    check_tx: object;
+    deliver_tx: object;
+    hash: string;
+    height: string;
+}
+
+export interface AbciQueryOptions {
+    height?: number;
+    prove?: boolean;
+}
+
+export const owAbciQueryOptions = ow.object.exactShape({
+    height: ow.optional.number.integer.greaterThanOrEqual(0),
+    prove: ow.optional.boolean,
+});
+
+/**
+ * ABCI query of a batch
+ * @typedef {object} AbciQuery
+ * @property {string} path query path, e.g. "account"
+ * @property {Buffer} data query data
+ * @property {number} [height] block height to query at, latest when omitted
+ * @property {boolean} [prove] whether to include proof, default false
+ */
+export interface AbciQuery extends AbciQueryOptions {
+    path: string;
+    data: Buffer;
+}
+
+export const owAbciQuery = ow.object.exactShape({
+    path: ow.string,
+    data: ow.buffer,
+    height: ow.optional.number.integer.greaterThanOrEqual(0),
+    prove: ow.optional.boolean,
+});
+
+/**
+ * ABCI query response, key and value are decoded
+ */
+export interface AbciQueryResponse {
+    code: number;
+    log: string;
+    info: string;
+    index: string;
+    key: Buffer;
+    value: Buffer;
+    height: string;
+    codespace: string;
+    [field: string]: any;
+}
+
+/**
+ * @typedef {object} ConfirmationOptions
+ * @property {number} [confirmations] number of blocks committed since the
+ * transaction, counting its block, default 1
+ * @property {number} [timeoutMs] default 60000, rejects with RPC_TIMEOUT
+ * once elapsed
+ * @property {number} [pollIntervalMs] interval between the status queries,
+ * default 1000
+ */
+export interface ConfirmationOptions {
+    confirmations?: number;
+    timeoutMs?: number;
+    pollIntervalMs?: number;
+}
+
+export const owConfirmationOptions = ow.object.exactShape({
+    confirmations: ow.optional.number.integer.greaterThanOrEqual(1),
+    timeoutMs: ow.optional.number.integer.greaterThanOrEqual(0),
+    pollIntervalMs: ow.optional.number.integer.greaterThanOrEqual(0),
+});
+
+/**
+ * Status of a committed transaction
+ * @typedef {object} TxStatus
+ * @property {string} hash Tendermint hash of the transaction
+ * @property {number} height
+ * @property {number} index
+ * @property {number} code DeliverTx result code, 0 when the transaction is
+ * valid
+ * @property {string} log
+ * @property {number} confirmations number of blocks committed since the
+ * transaction, counting its block
+ */
+export interface TxStatus {
+    hash: string;
+    height: number;
+    index: number;
+    code: number;
+    log: string;
+    confirmations: number;
+}
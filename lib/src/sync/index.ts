@@ -0,0 +1,10 @@
+export { syncBlocks, resume, loadCheckpoint, deleteCheckpoint } from './sync';
+export {
+    StorageHandle,
+    SyncBlocksOptions,
+    SyncCheckpoint,
+    SyncHistoryEntry,
+    SyncProgress,
+    SyncResult,
+    SyncUtxo,
+} from './types';
@@ -0,0 +1,60 @@
+import 'mocha';
+import { expect } from 'chai';
+
+import { syncBlocks } from './sync';
+import { SyncBlocksOptions } from './types';
+import { Mainnet } from '../network';
+
+const native = require('../../../native');
+
+const ANY_SYNC_OPTIONS: SyncBlocksOptions = {
+    tendermintRpcUrl: 'http://127.0.0.1:1',
+    tendermintAddress: 'ws://127.0.0.1:1/websocket',
+    fromHeight: 1,
+    network: Mainnet,
+};
+
+describe('sync', () => {
+    describe('syncBlocks', () => {
+        it('should throw Error when fromHeight is missing', () => {
+            expect(() => {
+                syncBlocks({
+                    ...ANY_SYNC_OPTIONS,
+                    fromHeight: undefined,
+                });
+            }).to.throw(
+                'Expected `fromHeight` to be of type `number` but received type `undefined`',
+            );
+        });
+
+        it('should throw Error when the batch size is not positive', () => {
+            expect(() => {
+                syncBlocks({
+                    ...ANY_SYNC_OPTIONS,
+                    batchSize: 0,
+                });
+            }).to.throw(/to be greater than 0/);
+        });
+
+        it('should reject with CONNECTION_ERROR when the Tendermint RPC is unreachable', async () => {
+            let error: any;
+            try {
+                await syncBlocks(ANY_SYNC_OPTIONS);
+            } catch (err) {
+                error = err;
+            }
+
+            expect(error?.code).to.eq('CONNECTION_ERROR');
+        });
+
+        it('should not run synchronously without a callback', () => {
+            expect(() => {
+                native.sync.syncBlocks({
+                    ...ANY_SYNC_OPTIONS,
+                    network: 'Mainnet',
+                    features: 'AllDefault',
+                });
+            }).to.throw('network calls only run on background thread');
+        });
+    });
+});
@@ -0,0 +1,107 @@
+import ow from 'ow';
+
+import {
+    owStorageHandle,
+    owSyncBlocksOptions,
+    StorageHandle,
+    SyncBlocksOptions,
+    SyncCheckpoint,
+    SyncResult,
+} from './types';
+import { getRustFeaturesFromEnv } from '../native';
+import { toNativeNetwork } from '../network/network';
+import { NativeTask, runNativeTask } from '../native_task';
+
+const native = require('../../../native');
+
+const toNativeSyncOptions = (options: SyncBlocksOptions) => ({
+    ...options,
+    network: toNativeNetwork(options.network),
+    features: getRustFeaturesFromEnv(process.env.NODE_ENV),
+});
+
+/**
+ * Synchronize blocks on background thread and pick out the transactions
+ * relevant to the wallet
+ *
+ * @export
+ * @param {SyncBlocksOptions} options fromHeight is required
+ * @returns {NativeTask<SyncResult>}
+ */
+export const syncBlocks = (
+    options: SyncBlocksOptions,
+): NativeTask<SyncResult> => {
+    ow(options, 'options', owSyncBlocksOptions);
+    ow(options.fromHeight, 'fromHeight', ow.number);
+
+    return runNativeTask((callback) =>
+        native.sync.syncBlocks(toNativeSyncOptions(options), callback),
+    );
+};
+
+/**
+ * Resume the sync of the wallet after the last block synced by `resume`. The
+ * sync checkpoint is saved in the storage as the results are handed over
+ *
+ * @export
+ * @param {StorageHandle} storage
+ * @param {string} walletName
+ * @param {SyncBlocksOptions} options fromHeight is only used before the first
+ * checkpoint, default 1, and utxos are replaced by the ones of the checkpoint
+ * @returns {NativeTask<SyncResult>}
+ */
+export const resume = (
+    storage: StorageHandle,
+    walletName: string,
+    options: SyncBlocksOptions,
+): NativeTask<SyncResult> => {
+    ow(storage, 'storage', owStorageHandle);
+    ow(walletName, 'walletName', ow.string.nonEmpty);
+    ow(options, 'options', owSyncBlocksOptions);
+
+    return runNativeTask((callback) =>
+        native.sync.resume(
+            storage,
+            walletName,
+            toNativeSyncOptions(options),
+            callback,
+        ),
+    );
+};
+
+/**
+ * Returns the sync checkpoint of the wallet saved by `resume`
+ *
+ * @export
+ * @param {StorageHandle} storage
+ * @param {string} walletName
+ * @returns {SyncCheckpoint | null} null when the wallet has not synced
+ */
+export const loadCheckpoint = (
+    storage: StorageHandle,
+    walletName: string,
+): SyncCheckpoint | null => {
+    ow(storage, 'storage', owStorageHandle);
+    ow(walletName, 'walletName', ow.string.nonEmpty);
+
+    return native.sync.loadCheckpoint(storage, walletName);
+};
+
+/**
+ * Delete the sync checkpoint of the wallet, the next `resume` starts from
+ * its fromHeight option
+ *
+ * @export
+ * @param {StorageHandle} storage
+ * @param {string} walletName
+ * @returns {boolean} whether the checkpoint existed
+ */
+export const deleteCheckpoint = (
+    storage: StorageHandle,
+    walletName: string,
+): boolean => {
+    ow(storage, 'storage', owStorageHandle);
+    ow(walletName, 'walletName', ow.string.nonEmpty);
+
+    return native.sync.deleteCheckpoint(storage, walletName);
+};
@@ -0,0 +1,170 @@
+import ow from 'ow';
+
+import { NetworkConfig, owNetworkConfig } from '../network/types';
+import { owTendermintAddress, owTxId } from '../types';
+import { owTendermintRpcUrl } from '../rpc/types';
+
+/**
+ * Storage handle returned by the native `storage.open`
+ */
+export type StorageHandle = object;
+
+export const owStorageHandle = ow.object.hasKeys('path', 'id');
+
+/**
+ * Wallet UTXO
+ * @typedef {object} SyncUtxo
+ * @property {string} prevTxId transaction id of the output
+ * @property {number} prevIndex index of the output in the transaction
+ * @property {string} value amount in basic unit
+ */
+export interface SyncUtxo {
+    prevTxId: string;
+    prevIndex: number;
+    value: string;
+}
+
+const owSyncUtxo = ow.object.exactShape({
+    prevTxId: owTxId,
+    prevIndex: ow.number.integer.greaterThanOrEqual(0),
+    value: ow.string.numeric,
+});
+
+/**
+ * Sync progress
+ * @typedef {object} SyncProgress
+ * @property {number} current height of the last synced block
+ * @property {number} target height the sync stops at
+ * @property {number} percent
+ */
+export interface SyncProgress {
+    current: number;
+    target: number;
+    percent: number;
+}
+
+/**
+ * @typedef {object} SyncBlocksOptions
+ * @property {string} tendermintRpcUrl Tendermint RPC endpoint
+ * @property {string} tendermintAddress Tendermint websocket address, used to
+ * look up the tx-query enclave address
+ * @property {number} [fromHeight] block height to start from, required by
+ * `syncBlocks`
+ * @property {number} [toHeight] last block height to sync, latest when
+ * omitted
+ * @property {Buffer[]} [viewPrivateKeys] view private keys to decrypt
+ * transfer and withdraw transactions
+ * @property {string[]} [transferAddresses] transfer addresses whose outputs
+ * are returned
+ * @property {string[]} [stakingAddresses] staking addresses whose staking
+ * transactions are returned
+ * @property {SyncUtxo[]} [utxos] wallet UTXOs before fromHeight
+ * @property {NetworkConfig} network network of the addresses
+ * @property {object} [trustedCheckpoint] { height, hash } of a trusted block
+ * at or before fromHeight, the blocks are verified from it
+ * @property {Function} [onProgress] called with the SyncProgress
+ * @property {number} [progressIntervalMs] minimum interval between progress
+ * reports, default 500
+ * @property {Function} [onBlocks] called with the SyncResult of every batch
+ * but the last one
+ * @property {number} [batchSize] number of blocks of a batch, default 100
+ * @property {boolean} [useBlockFilter] skip the blocks whose block filter
+ * matches none of the view keys
+ */
+export interface SyncBlocksOptions {
+    tendermintRpcUrl: string;
+    tendermintAddress: string;
+    fromHeight?: number;
+    toHeight?: number;
+    viewPrivateKeys?: Buffer[];
+    transferAddresses?: string[];
+    stakingAddresses?: string[];
+    utxos?: SyncUtxo[];
+    network: NetworkConfig;
+    trustedCheckpoint?: { height: number; hash: string };
+    onProgress?: (progress: SyncProgress) => void;
+    progressIntervalMs?: number;
+    onBlocks?: (result: SyncResult) => void;
+    batchSize?: number;
+    useBlockFilter?: boolean;
+}
+
+export const owSyncBlocksOptions = ow.object.exactShape({
+    tendermintRpcUrl: owTendermintRpcUrl,
+    tendermintAddress: owTendermintAddress,
+    fromHeight: ow.optional.number.integer.greaterThanOrEqual(0),
+    toHeight: ow.optional.number.integer.greaterThanOrEqual(0),
+    viewPrivateKeys: ow.optional.array.ofType(ow.buffer),
+    transferAddresses: ow.optional.array.ofType(ow.string),
+    stakingAddresses: ow.optional.array.ofType(ow.string),
+    utxos: ow.optional.array.ofType(owSyncUtxo),
+    network: owNetworkConfig,
+    trustedCheckpoint: ow.optional.object.exactShape({
+        height: ow.number.integer.greaterThanOrEqual(0),
+        hash: owTxId,
+    }),
+    onProgress: ow.optional.function,
+    progressIntervalMs: ow.optional.number.integer.greaterThanOrEqual(0),
+    onBlocks: ow.optional.function,
+    batchSize: ow.optional.number.integer.greaterThan(0),
+    useBlockFilter: ow.optional.boolean,
+});
+
+/**
+ * @typedef {object} SyncedTransaction
+ * @property {string} txId
+ * @property {string} txType
+ * @property {object[]} inputs { prevTxId, prevIndex } spent by the
+ * transaction
+ * @property {object[]} outputs wallet outputs of the transaction
+ * @property {string} [stakingAddress]
+ */
+export interface SyncedTransaction {
+    txId: string;
+    txType: string;
+    inputs: { prevTxId: string; prevIndex: number }[];
+    outputs: object[];
+    stakingAddress?: string;
+}
+
+/**
+ * @typedef {object} SyncHistoryEntry
+ */
+export interface SyncHistoryEntry {
+    txId: string;
+    txType: string;
+    direction: string;
+    amount: string;
+    fee?: string;
+    blockHeight: number;
+    time: string;
+    counterparties: string[];
+}
+
+/**
+ * Sync result, only blocks with relevant transactions are returned
+ * @typedef {object} SyncResult
+ * @property {number} lastHeight
+ * @property {object[]} blocks { height, time, transactions }
+ * @property {SyncHistoryEntry[]} history
+ * @property {SyncUtxo[]} utxos wallet UTXOs after lastHeight
+ */
+export interface SyncResult {
+    lastHeight: number;
+    blocks: {
+        height: number;
+        time: string;
+        transactions: SyncedTransaction[];
+    }[];
+    history: SyncHistoryEntry[];
+    utxos: SyncUtxo[];
+}
+
+/**
+ * Sync checkpoint saved by `resume`
+ * @typedef {object} SyncCheckpoint
+ */
+export interface SyncCheckpoint {
+    lastHeight: number;
+    utxos: SyncUtxo[];
+}
@@ -3,3 +3,4 @@ import * as staking from './staking';
 import * as transfer from './transfer';
 
 export { councilNode, staking, transfer };
+export { ExternalSigner, SignatureScheme, SigningPayload } from './signer';
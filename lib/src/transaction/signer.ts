@@ -0,0 +1,72 @@
+import ow from 'ow';
+
+const native = require('../../../native');
+
+/**
+ * Signature scheme expected from the external signer
+ */
+export enum SignatureScheme {
+    // 64 bytes Schnorr signature, used to unlock transfer inputs
+    Schnorr = 'Schnorr',
+    // 65 bytes recoverable ECDSA signature (r, s, recovery id), used to
+    // authorize staking account operations
+    RecoverableEcdsa = 'RecoverableEcdsa',
+}
+
+/**
+ * Payload passed to the external signer
+ * @typedef {object} SigningPayload
+ * @property {Buffer} txId 32 bytes transaction Id to be signed
+ * @property {SignatureScheme} scheme signature scheme expected
+ * @property {Buffer} [unsignedTx] unsigned raw transaction, for signers
+ *  which display the transaction for confirmation before signing. Missing
+ *  for transfer transaction
+ */
+export interface SigningPayload {
+    txId: Buffer;
+    scheme: SignatureScheme;
+    unsignedTx?: Buffer;
+}
+
+/**
+ * Asynchronous signer outside of the library, e.g. Ledger or HSM. Receives
+ * the signing payload and resolves to the signature
+ */
+export type ExternalSigner = (payload: SigningPayload) => Promise<Buffer>;
+
+/**
+ * @internal
+ */
+export const owExternalSigner = ow.function;
+
+/**
+ * @internal
+ */
+export const signWithExternalSigner = async (
+    signer: ExternalSigner,
+    payload: SigningPayload,
+): Promise<Buffer> => {
+    const signature = await signer(payload);
+    if (!Buffer.isBuffer(signature)) {
+        throw new Error('External signer should resolve to a signature Buffer');
+    }
+
+    return signature;
+};
+
+/**
+ * @internal
+ */
+export const schnorrSignatureToWitness = (
+    signature: Buffer,
+    publicKey: Buffer,
+): Buffer => {
+    return native.signer.treeSigWitness(signature, [publicKey], [publicKey], 1);
+};
+
+/**
+ * @internal
+ */
+export const recoverableSignatureToWitness = (signature: Buffer): Buffer => {
+    return native.signer.stakedStateOpWitness(signature);
+};
@@ -7,6 +7,9 @@ import { KeyPair } from '../../key_pair';
 import { PrevOutputPointer } from './types';
 import { FeeConfig, FeeAlgorithm } from '../../fee';
 import { BigNumber } from '../../utils';
+import { SignatureScheme, SigningPayload } from '../signer';
+
+const native = require('../../../../native/index.node');
 
 describe('DepositTransactionBuilder', () => {
     const SAMPLE_FEE_CONFIG: FeeConfig = {
@@ -341,6 +344,80 @@ describe('DepositTransactionBuilder', () => {
         });
     });
 
+    describe('signInputWithSigner', () => {
+        it('should throw Error when the signer does not resolve to a Buffer', async () => {
+            const builder = new DepositTransactionBuilder({
+                stakingAddress: SAMPLE_STAKING_ADDRESS,
+            });
+            builder.addInput(SAMPLE_INPUT);
+
+            let error: Error | undefined;
+            try {
+                await builder.signInputWithSigner(
+                    0,
+                    SAMPLE_KEY_PAIR.publicKey,
+                    async () => 'signature' as any,
+                );
+            } catch (err) {
+                error = err;
+            }
+
+            expect(error?.message).to.eq(
+                'External signer should resolve to a signature Buffer',
+            );
+            expect(builder.hasWitness(0)).to.eq(false);
+        });
+
+        it('should throw Error when the input index does not exist', async () => {
+            const builder = new DepositTransactionBuilder({
+                stakingAddress: SAMPLE_STAKING_ADDRESS,
+            });
+            builder.addInput(SAMPLE_INPUT);
+
+            let error: Error | undefined;
+            try {
+                await builder.signInputWithSigner(
+                    5,
+                    SAMPLE_KEY_PAIR.publicKey,
+                    async ({ txId }) =>
+                        native.keyPair.schnorrSign(
+                            txId,
+                            SAMPLE_KEY_PAIR.privateKey,
+                        ),
+                );
+            } catch (err) {
+                error = err;
+            }
+
+            expect(error?.message).to.eq('Input index out of bound');
+        });
+
+        it('should sign the input with the Schnorr signature from signer', async () => {
+            const builder = new DepositTransactionBuilder({
+                stakingAddress: SAMPLE_STAKING_ADDRESS,
+            });
+            builder.addInput(SAMPLE_INPUT);
+
+            let payload: SigningPayload | undefined;
+            await builder.signInputWithSigner(
+                0,
+                SAMPLE_KEY_PAIR.publicKey,
+                async (signingPayload) => {
+                    payload = signingPayload;
+
+                    return native.keyPair.schnorrSign(
+                        signingPayload.txId,
+                        SAMPLE_KEY_PAIR.privateKey,
+                    );
+                },
+            );
+
+            expect(payload?.scheme).to.eq(SignatureScheme.Schnorr);
+            expect(payload?.txId.toString('hex')).to.eq(builder.txId());
+            expect(builder.hasWitness(0)).to.eq(true);
+        });
+    });
+
     describe('addWitness', () => {
         it('should throw Error when the input index is missing', () => {
             const builder = new DepositTransactionBuilder({
@@ -611,41 +688,51 @@ describe('DepositTransactionBuilder', () => {
             }).to.throw('Transaction builder is not completed');
         });
 
-        it('should return completed Hex', () => {
+        it('should return completed Hex', async () => {
             const builder = new DepositTransactionBuilder({
                 stakingAddress: SAMPLE_STAKING_ADDRESS,
             });
 
             builder.addInput(SAMPLE_INPUT).signInput(0, SAMPLE_KEY_PAIR);
 
-            const txHex = builder.toHex();
+            const txHex = await builder.toHex();
             expect(txHex.toString('hex')).to.deep.eq(
                 '0001040123456789abcdef0123456789abcdef0123456789abcdef0123456789abcdef020000b5698ee21f69a6184afbe59b3626ed9d4bd755b0002a01000000000000000000000000000000000000000000000000000000990201040123456789abcdef0123456789abcdef0123456789abcdef0123456789abcdef020000b5698ee21f69a6184afbe59b3626ed9d4bd755b0002a0100000000000000040027251329bd77bdc7f5ac98d4adfe84a0a866066b9d7c1ac45c1d4aa38e11332c9b3616ff4aabf42defa743ac57b833c495124e22e75d95df33a851e310bd10aa001b84c5567b126440995d3ed5aaba0565d71e1834604819ff9c17f5e9d5dd078fe77d35ddec2b4ba14809d2d229a687a24e30f1122663d4ede67a0e5dad767d3b',
             );
         });
 
-        it('should return completed Hex given correct tendermint address', () => {
+        it('should return completed Hex given correct tendermint address', async () => {
             const builder = new DepositTransactionBuilder({
                 stakingAddress: SAMPLE_STAKING_ADDRESS,
             });
 
             builder.addInput(SAMPLE_INPUT).signInput(0, SAMPLE_KEY_PAIR);
 
-            expect(builder.toHex('ws://127.0.0.1:26657').toString('hex')).to.eq(
+            expect(
+
+                (await builder.toHex('ws://127.0.0.1:26657')).toString('hex'),
+
+            ).to.eq(
                 '0001040123456789abcdef0123456789abcdef0123456789abcdef0123456789abcdef020000b5698ee21f69a6184afbe59b3626ed9d4bd755b0002a01000000000000000000000000000000000000000000000000000000990201040123456789abcdef0123456789abcdef0123456789abcdef0123456789abcdef020000b5698ee21f69a6184afbe59b3626ed9d4bd755b0002a0100000000000000040027251329bd77bdc7f5ac98d4adfe84a0a866066b9d7c1ac45c1d4aa38e11332c9b3616ff4aabf42defa743ac57b833c495124e22e75d95df33a851e310bd10aa001b84c5567b126440995d3ed5aaba0565d71e1834604819ff9c17f5e9d5dd078fe77d35ddec2b4ba14809d2d229a687a24e30f1122663d4ede67a0e5dad767d3b',
             );
-            expect(builder.toHex('ws://localhost:26657').toString('hex')).to.eq(
+            expect(
+                (await builder.toHex('ws://localhost:26657')).toString('hex'),
+            ).to.eq(
                 '0001040123456789abcdef0123456789abcdef0123456789abcdef0123456789abcdef020000b5698ee21f69a6184afbe59b3626ed9d4bd755b0002a01000000000000000000000000000000000000000000000000000000990201040123456789abcdef0123456789abcdef0123456789abcdef0123456789abcdef020000b5698ee21f69a6184afbe59b3626ed9d4bd755b0002a0100000000000000040027251329bd77bdc7f5ac98d4adfe84a0a866066b9d7c1ac45c1d4aa38e11332c9b3616ff4aabf42defa743ac57b833c495124e22e75d95df33a851e310bd10aa001b84c5567b126440995d3ed5aaba0565d71e1834604819ff9c17f5e9d5dd078fe77d35ddec2b4ba14809d2d229a687a24e30f1122663d4ede67a0e5dad767d3b',
             );
             expect(
-                builder
-                    .toHex('ws://tendermint-zerofee:26657/websocket')
-                    .toString('hex'),
+                (
+                    await builder.toHex(
+                        'ws://tendermint-zerofee:26657/websocket',
+                    )
+                ).toString('hex'),
             ).to.eq(
                 '0001040123456789abcdef0123456789abcdef0123456789abcdef0123456789abcdef020000b5698ee21f69a6184afbe59b3626ed9d4bd755b0002a01000000000000000000000000000000000000000000000000000000990201040123456789abcdef0123456789abcdef0123456789abcdef0123456789abcdef020000b5698ee21f69a6184afbe59b3626ed9d4bd755b0002a0100000000000000040027251329bd77bdc7f5ac98d4adfe84a0a866066b9d7c1ac45c1d4aa38e11332c9b3616ff4aabf42defa743ac57b833c495124e22e75d95df33a851e310bd10aa001b84c5567b126440995d3ed5aaba0565d71e1834604819ff9c17f5e9d5dd078fe77d35ddec2b4ba14809d2d229a687a24e30f1122663d4ede67a0e5dad767d3b',
             );
             expect(
-                builder.toHex('wss://localhost/websocket').toString('hex'),
+                (
+                    await builder.toHex('wss://localhost/websocket')
+                ).toString('hex'),
             ).to.eq(
                 '0001040123456789abcdef0123456789abcdef0123456789abcdef0123456789abcdef020000b5698ee21f69a6184afbe59b3626ed9d4bd755b0002a01000000000000000000000000000000000000000000000000000000990201040123456789abcdef0123456789abcdef0123456789abcdef0123456789abcdef020000b5698ee21f69a6184afbe59b3626ed9d4bd755b0002a0100000000000000040027251329bd77bdc7f5ac98d4adfe84a0a866066b9d7c1ac45c1d4aa38e11332c9b3616ff4aabf42defa743ac57b833c495124e22e75d95df33a851e310bd10aa001b84c5567b126440995d3ed5aaba0565d71e1834604819ff9c17f5e9d5dd078fe77d35ddec2b4ba14809d2d229a687a24e30f1122663d4ede67a0e5dad767d3b',
             );
@@ -8,10 +8,18 @@ import {
     WitnessedPrevOutputPointer,
 } from './types';
 import { TransactionBuilder } from '../transaction_builder';
+import {
+    ExternalSigner,
+    owExternalSigner,
+    schnorrSignatureToWitness,
+    SignatureScheme,
+    signWithExternalSigner,
+} from '../signer';
 import { KeyPair } from '../../key_pair';
 import { owKeyPair } from '../../key_pair/types';
 import { owTendermintAddress } from '../../types';
 import { getRustFeaturesFromEnv } from '../../native';
+import { NativeTask, runNativeTask } from '../../native_task';
 
 const native = require('../../../../native');
 
@@ -136,6 +144,49 @@ export class DepositTransactionBuilder extends TransactionBuilder {
         return this;
     }
 
+    /**
+     * Sign a particular input with an external signer, e.g. hardware wallet
+     *
+     * @param {number} index input index
+     * @param {Buffer} publicKey public key of the external signer
+     * @param {ExternalSigner} signer external signer which can unlock the input
+     * @throws {Error} error when input index does not exist
+     * @memberof DepositTransactionBuilder
+     */
+    public async signInputWithSigner(
+        index: number,
+        publicKey: Buffer,
+        signer: ExternalSigner,
+    ): Promise<DepositTransactionBuilder> {
+        ow(index, 'index', ow.number);
+        ow(publicKey, 'publicKey', ow.buffer);
+        ow(signer, 'signer', owExternalSigner);
+
+        if (!this.isRawTxPrepared()) {
+            this.verifyTxIsSignable();
+            this.prepareRawTx();
+        }
+        this.verifyInputIndex(index);
+
+        const txId = this.innertTxId!;
+        const signature = await signWithExternalSigner(signer, {
+            txId: Buffer.from(txId, 'hex'),
+            scheme: SignatureScheme.Schnorr,
+            unsignedTx: this.unsignedRawTx!,
+        });
+        if (this.innertTxId !== txId) {
+            throw new Error(
+                'Builder has been modified during external signing',
+            );
+        }
+        this.inputs[index].witness = schnorrSignatureToWitness(
+            signature,
+            publicKey,
+        );
+
+        return this;
+    }
+
     /**
      * Add witness data to input
      *
@@ -219,12 +270,13 @@ export class DepositTransactionBuilder extends TransactionBuilder {
      *
      * @param {string} [tendermintAddress='ws://localhost:26657/websocket']
      * @throws {Error} error when transaction is not completed
-     * @returns {Buffer}
+     * @returns {NativeTask<Buffer>} resolves once the transaction is
+     *  obfuscated, which runs on a background thread
      * @memberof DepositTransactionBuilder
      */
     public toHex(
         tendermintAddress: string = 'ws://localhost:26657/websocket',
-    ): Buffer {
+    ): NativeTask<Buffer> {
         ow(tendermintAddress, 'tendermintAddress', owTendermintAddress);
 
         if (!this.isRawTxPrepared()) {
@@ -237,11 +289,14 @@ export class DepositTransactionBuilder extends TransactionBuilder {
 
         const txInWitnesses = this.inputs.map((input) => input.witness);
 
-        return native.stakingTransaction.depositTransactionToHex(
-            this.unsignedRawTx,
-            txInWitnesses,
-            tendermintAddress,
-            getRustFeaturesFromEnv(process.env.NODE_ENV),
+        return runNativeTask((callback) =>
+            native.stakingTransaction.depositTransactionToHex(
+                this.unsignedRawTx,
+                txInWitnesses,
+                tendermintAddress,
+                getRustFeaturesFromEnv(process.env.NODE_ENV),
+                callback,
+            ),
         );
     }
 
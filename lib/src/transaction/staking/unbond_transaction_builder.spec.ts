@@ -7,6 +7,9 @@ import { Mainnet, Devnet } from '../../network';
 import { MAX_COIN_BN } from '../../init';
 import { KeyPair } from '../../key_pair';
 import { FeeAlgorithm, FeeConfig } from '../../fee';
+import { SignatureScheme, SigningPayload } from '../signer';
+
+const native = require('../../../../native/index.node');
 
 describe('UnbondTransactionBuilder', () => {
     const SAMPLE_FEE_CONFIG: FeeConfig = {
@@ -188,6 +191,73 @@ describe('UnbondTransactionBuilder', () => {
         });
     });
 
+    describe('signWithSigner', () => {
+        it('should throw Error when the signer is missing', async () => {
+            const builder = new UnbondTransactionBuilder({
+                stakingAddress: SAMPLE_STAKING_ADDRESS,
+                nonce: SAMPLE_NONCE,
+                amount: new BigNumber('1000'),
+                network: Mainnet,
+            });
+
+            let error: Error | undefined;
+            try {
+                await (builder.signWithSigner as any)();
+            } catch (err) {
+                error = err;
+            }
+
+            expect(error?.message).to.eq(
+                'Expected `signer` to be of type `Function` but received type `undefined`',
+            );
+        });
+
+        it('should throw Error when the signer does not resolve to a Buffer', async () => {
+            const builder = new UnbondTransactionBuilder({
+                stakingAddress: SAMPLE_STAKING_ADDRESS,
+                nonce: SAMPLE_NONCE,
+                amount: new BigNumber('1000'),
+                network: Mainnet,
+            });
+
+            let error: Error | undefined;
+            try {
+                await builder.signWithSigner(async () => undefined as any);
+            } catch (err) {
+                error = err;
+            }
+
+            expect(error?.message).to.eq(
+                'External signer should resolve to a signature Buffer',
+            );
+            expect(builder.isCompleted()).to.eq(false);
+        });
+
+        it('should sign the transaction with the recoverable signature from signer', async () => {
+            const builder = new UnbondTransactionBuilder({
+                stakingAddress: SAMPLE_STAKING_ADDRESS,
+                nonce: SAMPLE_NONCE,
+                amount: new BigNumber('1000'),
+                network: Mainnet,
+            });
+            const keyPair = KeyPair.generateRandom();
+
+            let payload: SigningPayload | undefined;
+            await builder.signWithSigner(async (signingPayload) => {
+                payload = signingPayload;
+
+                return native.keyPair.signRecoverable(
+                    signingPayload.txId,
+                    keyPair.privateKey,
+                );
+            });
+
+            expect(payload?.scheme).to.eq(SignatureScheme.RecoverableEcdsa);
+            expect(payload?.txId.toString('hex')).to.eq(builder.txId());
+            expect(builder.isCompleted()).to.eq(true);
+        });
+    });
+
     describe('isCompleted', () => {
         it('should return false when the transaction is not signed', () => {
             const builder = new UnbondTransactionBuilder({
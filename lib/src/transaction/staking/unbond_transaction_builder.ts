@@ -6,6 +6,13 @@ import {
     owUnbondTransactionBuilderOptions,
 } from './types';
 import { TransactionBuilder } from '../transaction_builder';
+import {
+    ExternalSigner,
+    owExternalSigner,
+    recoverableSignatureToWitness,
+    SignatureScheme,
+    signWithExternalSigner,
+} from '../signer';
 import { KeyPair } from '../../key_pair';
 import { owKeyPair } from '../../key_pair/types';
 import { ENCODING_PREFIX } from '../encoding';
@@ -28,6 +35,8 @@ export class UnbondTransactionBuilder extends TransactionBuilder {
 
     private keyPair?: KeyPair;
 
+    private witness?: Buffer;
+
     /**
      * Creates an instance of DepositTransactionBuilder.
      * @param {UnbondTransactionBuilderOptions} [options] Builder options
@@ -103,6 +112,31 @@ export class UnbondTransactionBuilder extends TransactionBuilder {
         // RecoverableSignature does not support Encode/Decode
         // https://github.com/crypto-com/chain/blob/release/v0.3/client-common/src/key/private_key.rs#L40
         this.keyPair = keyPair;
+        this.witness = undefined;
+
+        return this;
+    }
+
+    /**
+     * Sign the transaction with an external signer, e.g. hardware wallet. The
+     * signer receives the transaction Id and resolves to the recoverable
+     * ECDSA signature of the staking address
+     * @param {ExternalSigner} signer external signer of the staking address
+     * @returns {Promise<UnbondTransactionBuilder>}
+     * @memberof UnbondTransactionBuilder
+     */
+    public async signWithSigner(
+        signer: ExternalSigner,
+    ): Promise<UnbondTransactionBuilder> {
+        ow(signer, 'signer', owExternalSigner);
+
+        const signature = await signWithExternalSigner(signer, {
+            txId: Buffer.from(this.innertTxId, 'hex'),
+            scheme: SignatureScheme.RecoverableEcdsa,
+            unsignedTx: this.toUnsignedHex(),
+        });
+        this.witness = recoverableSignatureToWitness(signature);
+        this.keyPair = undefined;
 
         return this;
     }
@@ -114,7 +148,7 @@ export class UnbondTransactionBuilder extends TransactionBuilder {
      * @memberof UnbondTransactionBuilder
      */
     public isCompleted(): boolean {
-        return !!this.keyPair;
+        return !!this.keyPair || !!this.witness;
     }
 
     /**
@@ -148,6 +182,13 @@ export class UnbondTransactionBuilder extends TransactionBuilder {
             throw new Error('Transaction builder is not completed');
         }
 
+        if (this.witness) {
+            return native.stakingTransaction.unbondTransactionWithWitnessToHex(
+                this.unsignedRawTx,
+                this.witness,
+            );
+        }
+
         return native.stakingTransaction.unbondTransactionToHex(
             this.unsignedRawTx,
             this.keyPair!.toObject(),
@@ -8,6 +8,9 @@ import { WithdrawUnbondedOutput } from './types';
 import { Mainnet, Testnet } from '../../network';
 import { KeyPair } from '../../key_pair';
 import { Timespec } from '../../types';
+import { SignatureScheme, SigningPayload } from '../signer';
+
+const native = require('../../../../native/index.node');
 
 describe('WithdrawUnbondedTransactionBuilder', () => {
     const SAMPLE_NONCE = new BigNumber(1);
@@ -365,6 +368,94 @@ describe('WithdrawUnbondedTransactionBuilder', () => {
         });
     });
 
+    describe('signWithSigner', () => {
+        it('should throw Error when the signer is missing', async () => {
+            const builder = new WithdrawUnbondedTransactionBuilder({
+                nonce: SAMPLE_NONCE,
+                network: Mainnet,
+            }).addOutput(SAMPLE_OUTPUT);
+
+            let error: Error | undefined;
+            try {
+                await (builder.signWithSigner as any)();
+            } catch (err) {
+                error = err;
+            }
+
+            expect(error?.message).to.eq(
+                'Expected `signer` to be of type `Function` but received type `undefined`',
+            );
+        });
+
+        it('should throw Error when there is no output', async () => {
+            const builder = new WithdrawUnbondedTransactionBuilder({
+                nonce: SAMPLE_NONCE,
+                network: Mainnet,
+            });
+
+            let error: Error | undefined;
+            try {
+                await builder.signWithSigner(async ({ txId }) =>
+                    native.keyPair.signRecoverable(
+                        txId,
+                        SAMPLE_KEY_PAIR.privateKey,
+                    ),
+                );
+            } catch (err) {
+                error = err;
+            }
+
+            expect(error?.message).to.eq('Builder has no output');
+        });
+
+        it('should throw Error when the builder is modified during signing', async () => {
+            const builder = new WithdrawUnbondedTransactionBuilder({
+                nonce: SAMPLE_NONCE,
+                network: Mainnet,
+            }).addOutput(SAMPLE_OUTPUT);
+
+            let error: Error | undefined;
+            try {
+                await builder.signWithSigner(async ({ txId }) => {
+                    builder.addOutput(SAMPLE_OUTPUT);
+
+                    return native.keyPair.signRecoverable(
+                        txId,
+                        SAMPLE_KEY_PAIR.privateKey,
+                    );
+                });
+            } catch (err) {
+                error = err;
+            }
+
+            expect(error?.message).to.eq(
+                'Builder has been modified during external signing',
+            );
+            expect(builder.isSigned()).to.eq(false);
+        });
+
+        it('should sign the transaction with the recoverable signature from signer', async () => {
+            const builder = new WithdrawUnbondedTransactionBuilder({
+                nonce: SAMPLE_NONCE,
+                network: Mainnet,
+            }).addOutput(SAMPLE_OUTPUT);
+
+            let payload: SigningPayload | undefined;
+            await builder.signWithSigner(async (signingPayload) => {
+                payload = signingPayload;
+
+                return native.keyPair.signRecoverable(
+                    signingPayload.txId,
+                    SAMPLE_KEY_PAIR.privateKey,
+                );
+            });
+
+            expect(payload?.scheme).to.eq(SignatureScheme.RecoverableEcdsa);
+            expect(payload?.txId.toString('hex')).to.eq(builder.txId());
+            expect(builder.isSigned()).to.eq(true);
+        });
+    });
+
     describe('addWitness', () => {
         it('should throw Error when witness is missing', () => {
             const builder = new WithdrawUnbondedTransactionBuilder({
@@ -605,7 +696,7 @@ describe('WithdrawUnbondedTransactionBuilder', () => {
             }).to.throw('Builder is not signed');
         });
 
-        it('should return completed Hex when Tendermint address is not provided', () => {
+        it('should return completed Hex when Tendermint address is not provided', async () => {
             const builder = new WithdrawUnbondedTransactionBuilder({
                 nonce: SAMPLE_NONCE,
                 network: Mainnet,
@@ -613,13 +704,13 @@ describe('WithdrawUnbondedTransactionBuilder', () => {
 
             builder.addOutput(SAMPLE_OUTPUT).sign(SAMPLE_KEY_PAIR);
 
-            const txHex = builder.toHex();
+            const txHex = await builder.toHex();
             expect(txHex.toString('hex')).to.deep.eq(
                 '00020100000167463afac53c8fe80e2724c3d1c40678a3e852adeada94d87d6670fb95043ab11200429a46fc03c3f0b95d8c6d60de61c69d3fa66fbbc4603e903bfc76b4208800000000000000000000000000000000000000002502020100000000000000040009f113990c56b0f77c497ece8e22738a43de6069a04a715d3d0325530cfb497de80300000000000001d0ffd45d00000000002a000100000000000000000167463afac53c8fe80e2724c3d1c40678a3e852adeada94d87d6670fb95043ab11200429a46fc03c3f0b95d8c6d60de61c69d3fa66fbbc4603e903bfc76b420888089fc12aab571120c46acc3e1f34e532ea64d8a9099a1d4d91cdffeace82cf9',
             );
         });
 
-        it('should return completed Hex given correct tendermint address', () => {
+        it('should return completed Hex given correct tendermint address', async () => {
             const builder = new WithdrawUnbondedTransactionBuilder({
                 nonce: SAMPLE_NONCE,
                 network: Mainnet,
@@ -627,27 +718,37 @@ describe('WithdrawUnbondedTransactionBuilder', () => {
 
             builder.addOutput(SAMPLE_OUTPUT).sign(SAMPLE_KEY_PAIR);
 
-            expect(builder.toHex('ws://127.0.0.1:26657').toString('hex')).to.eq(
+            expect(
+
+                (await builder.toHex('ws://127.0.0.1:26657')).toString('hex'),
+
+            ).to.eq(
                 '00020100000167463afac53c8fe80e2724c3d1c40678a3e852adeada94d87d6670fb95043ab11200429a46fc03c3f0b95d8c6d60de61c69d3fa66fbbc4603e903bfc76b4208800000000000000000000000000000000000000002502020100000000000000040009f113990c56b0f77c497ece8e22738a43de6069a04a715d3d0325530cfb497de80300000000000001d0ffd45d00000000002a000100000000000000000167463afac53c8fe80e2724c3d1c40678a3e852adeada94d87d6670fb95043ab11200429a46fc03c3f0b95d8c6d60de61c69d3fa66fbbc4603e903bfc76b420888089fc12aab571120c46acc3e1f34e532ea64d8a9099a1d4d91cdffeace82cf9',
             );
-            expect(builder.toHex('ws://localhost:26657').toString('hex')).to.eq(
+            expect(
+                (await builder.toHex('ws://localhost:26657')).toString('hex'),
+            ).to.eq(
                 '00020100000167463afac53c8fe80e2724c3d1c40678a3e852adeada94d87d6670fb95043ab11200429a46fc03c3f0b95d8c6d60de61c69d3fa66fbbc4603e903bfc76b4208800000000000000000000000000000000000000002502020100000000000000040009f113990c56b0f77c497ece8e22738a43de6069a04a715d3d0325530cfb497de80300000000000001d0ffd45d00000000002a000100000000000000000167463afac53c8fe80e2724c3d1c40678a3e852adeada94d87d6670fb95043ab11200429a46fc03c3f0b95d8c6d60de61c69d3fa66fbbc4603e903bfc76b420888089fc12aab571120c46acc3e1f34e532ea64d8a9099a1d4d91cdffeace82cf9',
             );
             expect(
-                builder
-                    .toHex('ws://tendermint-zerofee:26657/websocket')
-                    .toString('hex'),
+                (
+                    await builder.toHex(
+                        'ws://tendermint-zerofee:26657/websocket',
+                    )
+                ).toString('hex'),
             ).to.eq(
                 '00020100000167463afac53c8fe80e2724c3d1c40678a3e852adeada94d87d6670fb95043ab11200429a46fc03c3f0b95d8c6d60de61c69d3fa66fbbc4603e903bfc76b4208800000000000000000000000000000000000000002502020100000000000000040009f113990c56b0f77c497ece8e22738a43de6069a04a715d3d0325530cfb497de80300000000000001d0ffd45d00000000002a000100000000000000000167463afac53c8fe80e2724c3d1c40678a3e852adeada94d87d6670fb95043ab11200429a46fc03c3f0b95d8c6d60de61c69d3fa66fbbc4603e903bfc76b420888089fc12aab571120c46acc3e1f34e532ea64d8a9099a1d4d91cdffeace82cf9',
             );
             expect(
-                builder.toHex('wss://localhost/websocket').toString('hex'),
+                (
+                    await builder.toHex('wss://localhost/websocket')
+                ).toString('hex'),
             ).to.eq(
                 '00020100000167463afac53c8fe80e2724c3d1c40678a3e852adeada94d87d6670fb95043ab11200429a46fc03c3f0b95d8c6d60de61c69d3fa66fbbc4603e903bfc76b4208800000000000000000000000000000000000000002502020100000000000000040009f113990c56b0f77c497ece8e22738a43de6069a04a715d3d0325530cfb497de80300000000000001d0ffd45d00000000002a000100000000000000000167463afac53c8fe80e2724c3d1c40678a3e852adeada94d87d6670fb95043ab11200429a46fc03c3f0b95d8c6d60de61c69d3fa66fbbc4603e903bfc76b420888089fc12aab571120c46acc3e1f34e532ea64d8a9099a1d4d91cdffeace82cf9',
             );
         });
 
-        it('should return completed Hex given witness is added using addWitness()', () => {
+        it('should return completed Hex given witness is added using addWitness()', async () => {
             const builder = new WithdrawUnbondedTransactionBuilder({
                 nonce: SAMPLE_NONCE,
                 network: Mainnet,
@@ -655,7 +756,11 @@ describe('WithdrawUnbondedTransactionBuilder', () => {
 
             builder.addOutput(SAMPLE_OUTPUT).addWitness(SAMPLE_WITNESS);
 
-            expect(builder.toHex('ws://127.0.0.1:26657').toString('hex')).to.eq(
+            expect(
+
+                (await builder.toHex('ws://127.0.0.1:26657')).toString('hex'),
+
+            ).to.eq(
                 '00020100000167463afac53c8fe80e2724c3d1c40678a3e852adeada94d87d6670fb95043ab11200429a46fc03c3f0b95d8c6d60de61c69d3fa66fbbc4603e903bfc76b4208800000000000000000000000000000000000000002502020100000000000000040009f113990c56b0f77c497ece8e22738a43de6069a04a715d3d0325530cfb497de80300000000000001d0ffd45d00000000002a000100000000000000000167463afac53c8fe80e2724c3d1c40678a3e852adeada94d87d6670fb95043ab11200429a46fc03c3f0b95d8c6d60de61c69d3fa66fbbc4603e903bfc76b420888089fc12aab571120c46acc3e1f34e532ea64d8a9099a1d4d91cdffeace82cf9',
             );
         });
@@ -3,6 +3,13 @@ import cloneDeep from 'lodash/cloneDeep';
 import BigNumber from 'bignumber.js';
 
 import { TransactionBuilder } from '../transaction_builder';
+import {
+    ExternalSigner,
+    owExternalSigner,
+    recoverableSignatureToWitness,
+    SignatureScheme,
+    signWithExternalSigner,
+} from '../signer';
 import {
     WithdrawUnbondedTransactionBuilderOptions,
     owWithdrawUnbondedTransactionBuilderOptions,
@@ -20,7 +27,9 @@ import { KeyPair } from '../../key_pair';
 import { owKeyPair } from '../../key_pair/types';
 import { FeeConfig } from '../../fee';
 import { parseFeeConfigForNative } from '../../fee/types';
+import { toNativeNetwork } from '../../network/network';
 import { getRustFeaturesFromEnv } from '../../native';
+import { NativeTask, runNativeTask } from '../../native_task';
 
 const native = require('../../../../native');
 
@@ -125,7 +134,7 @@ export class WithdrawUnbondedTransactionBuilder extends TransactionBuilder {
     private isTransferAddressInNetwork(address: string): boolean {
         return native.address.isTransferAddressValid(
             address,
-            this.getNetwork().name,
+            toNativeNetwork(this.getNetwork()),
         );
     }
 
@@ -271,6 +280,33 @@ export class WithdrawUnbondedTransactionBuilder extends TransactionBuilder {
         return this;
     }
 
+    /**
+     * Sign the transaction with an external signer, e.g. hardware wallet. The
+     * signer receives the transaction Id and resolves to the recoverable
+     * ECDSA signature of the staking address
+     * @param {ExternalSigner} signer external signer of the staking address
+     * @memberof WithdrawUnbondedTransactionBuilder
+     */
+    public async signWithSigner(
+        signer: ExternalSigner,
+    ): Promise<WithdrawUnbondedTransactionBuilder> {
+        ow(signer, 'signer', owExternalSigner);
+
+        const txId = this.txId();
+        const signature = await signWithExternalSigner(signer, {
+            txId: Buffer.from(txId, 'hex'),
+            scheme: SignatureScheme.RecoverableEcdsa,
+            unsignedTx: this.toUnsignedHex(),
+        });
+        if (this.txId() !== txId) {
+            throw new Error(
+                'Builder has been modified during external signing',
+            );
+        }
+
+        return this.addWitness(recoverableSignatureToWitness(signature));
+    }
+
     /**
      * Add signature to the builder
      *
@@ -342,12 +378,13 @@ export class WithdrawUnbondedTransactionBuilder extends TransactionBuilder {
      * Output broadcast-able transaction in hex
      *
      * @param {string} [tendermintAddress='ws://localhost:26657/websocket']
-     * @returns {Buffer} transaction hex
+     * @returns {NativeTask<Buffer>} transaction hex, resolves once the
+     *  transaction is obfuscated, which runs on a background thread
      * @memberof WithdrawUnbondedTransactionBuilder
      */
     public toHex(
         tendermintAddress: string = 'ws://localhost:26657/websocket',
-    ): Buffer {
+    ): NativeTask<Buffer> {
         ow(tendermintAddress, 'tendermintAddress', owTendermintAddress);
 
         if (!this.isRawTxPrepared()) {
@@ -362,11 +399,14 @@ export class WithdrawUnbondedTransactionBuilder extends TransactionBuilder {
         }
 
         // TODO: Refactor into object options
-        return native.stakingTransaction.withdrawUnbondedTransactionToObfuscatedHex(
-            this.unsignedRawTx,
-            this.witness!,
-            tendermintAddress,
-            getRustFeaturesFromEnv(process.env.NODE_ENV),
+        return runNativeTask((callback) =>
+            native.stakingTransaction.withdrawUnbondedTransactionToObfuscatedHex(
+                this.unsignedRawTx,
+                this.witness!,
+                tendermintAddress,
+                getRustFeaturesFromEnv(process.env.NODE_ENV),
+                callback,
+            ),
         );
     }
 
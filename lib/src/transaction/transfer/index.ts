@@ -1,2 +1,11 @@
-export { TransferTransactionBuilder } from './transfer_transaction_builder';
+import { TransferTransactionBuilder } from './transfer_transaction_builder';
+
+export { TransferTransactionBuilder };
 export { TransferTransactionBuilderOptions } from './types';
+
+/**
+ * Sign all inputs of the transfer transaction builders with the provided
+ * KeyPair on background threads
+ * @see TransferTransactionBuilder.signBatchAsync
+ */
+export const signBatchAsync = TransferTransactionBuilder.signBatchAsync;
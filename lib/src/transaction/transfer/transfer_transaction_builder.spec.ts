@@ -828,6 +828,195 @@ describe('TransferTransactionBuilder', () => {
         });
     });
 
+    describe('signInputWithSigner', () => {
+        it('should throw Error when the signer is unable to sign the input', async () => {
+            const builder = new TransferTransactionBuilder();
+
+            const keyPair = KeyPair.fromPrivateKey(Buffer.alloc(32, 1));
+            builder
+                .addInput({
+                    prevTxId:
+                        '0000000000000000000000000000000000000000000000000000000000000000',
+                    prevIndex: 0,
+                    prevOutput: {
+                        address: transfer({
+                            keyPair,
+                            network: Mainnet,
+                        }),
+                        value: new BigNumber('1000'),
+                    },
+                    addressParams: SINGLE_SIGN_ADDRESS,
+                })
+                .addOutput({
+                    address:
+                        'cro1p8c38xgv26c0wlzf0m8gugnn3fpaucrf5p98zhfaqvj4xr8mf97sp54ap3',
+                    value: new BigNumber('2500'),
+                });
+
+            const anotherKeyPair = KeyPair.fromPrivateKey(Buffer.alloc(32, 2));
+            let error: Error | undefined;
+            try {
+                await builder.signInputWithSigner(
+                    0,
+                    anotherKeyPair.publicKey,
+                    async ({ txId }) =>
+                        native.keyPair.schnorrSign(
+                            txId,
+                            anotherKeyPair.privateKey,
+                        ),
+                );
+            } catch (err) {
+                error = err;
+            }
+
+            expect(error?.message).to.eq(
+                'Input address is not signable by the signer',
+            );
+        });
+
+        it('should sign the input with the signature from signer', async () => {
+            const builder = new TransferTransactionBuilder();
+
+            const keyPair = KeyPair.fromPrivateKey(Buffer.alloc(32, 1));
+            builder
+                .addInput({
+                    prevTxId:
+                        '0000000000000000000000000000000000000000000000000000000000000000',
+                    prevIndex: 0,
+                    prevOutput: {
+                        address: transfer({
+                            keyPair,
+                            network: Mainnet,
+                        }),
+                        value: new BigNumber('1000'),
+                    },
+                    addressParams: SINGLE_SIGN_ADDRESS,
+                })
+                .addOutput({
+                    address:
+                        'cro1p8c38xgv26c0wlzf0m8gugnn3fpaucrf5p98zhfaqvj4xr8mf97sp54ap3',
+                    value: new BigNumber('2500'),
+                });
+
+            expect(builder.isCompleted()).to.eq(false);
+
+            await builder.signInputWithSigner(
+                0,
+                keyPair.publicKey,
+                async ({ txId }) =>
+                    native.keyPair.schnorrSign(txId, keyPair.privateKey),
+            );
+
+            expect(builder.isCompleted()).to.eq(true);
+        });
+    });
+
+    describe('signBatchAsync', () => {
+        const buildSignableBuilder = (
+            keyPair: KeyPair,
+        ): TransferTransactionBuilder =>
+            new TransferTransactionBuilder()
+                .addInput({
+                    prevTxId:
+                        '0000000000000000000000000000000000000000000000000000000000000000',
+                    prevIndex: 0,
+                    prevOutput: {
+                        address: transfer({
+                            keyPair,
+                            network: Mainnet,
+                        }),
+                        value: new BigNumber('1000'),
+                    },
+                    addressParams: SINGLE_SIGN_ADDRESS,
+                })
+                .addInput({
+                    prevTxId:
+                        '0000000000000000000000000000000000000000000000000000000000000000',
+                    prevIndex: 1,
+                    prevOutput: {
+                        address: transfer({
+                            keyPair,
+                            network: Mainnet,
+                        }),
+                        value: new BigNumber('1000'),
+                    },
+                    addressParams: SINGLE_SIGN_ADDRESS,
+                })
+                .addOutput({
+                    address:
+                        'cro1p8c38xgv26c0wlzf0m8gugnn3fpaucrf5p98zhfaqvj4xr8mf97sp54ap3',
+                    value: new BigNumber('1500'),
+                });
+
+        it('should throw Error when KeyPair is unable to sign the inputs', async () => {
+            const keyPair = KeyPair.fromPrivateKey(Buffer.alloc(32, 1));
+            const anotherKeyPair = KeyPair.fromPrivateKey(Buffer.alloc(32, 2));
+            const builders = [
+                buildSignableBuilder(keyPair),
+                buildSignableBuilder(anotherKeyPair),
+            ];
+
+            let error: Error | undefined;
+            try {
+                await TransferTransactionBuilder.signBatchAsync(
+                    builders,
+                    keyPair,
+                );
+            } catch (err) {
+                error = err;
+            }
+
+            expect(error?.message).to.eq(
+                'Inputs of builder 1 are not signable by the key pair',
+            );
+        });
+
+        it('should sign all inputs of the builders', async () => {
+            const keyPair = KeyPair.fromPrivateKey(Buffer.alloc(32, 1));
+            const builders = Array.from({ length: 10 }, () =>
+                buildSignableBuilder(keyPair),
+            );
+
+            const signedIncompleteHexes = await TransferTransactionBuilder.signBatchAsync(
+                builders,
+                keyPair,
+            );
+
+            expect(signedIncompleteHexes.length).to.eq(10);
+            builders.forEach((builder, i) => {
+                expect(builder.isCompleted()).to.eq(true);
+                expect(builder.toIncompleteHex()).to.deep.eq(
+                    signedIncompleteHexes[i],
+                );
+            });
+        });
+
+        it('should reject with CANCELLED and leave the builders unsigned when cancelled', async () => {
+            const keyPair = KeyPair.fromPrivateKey(Buffer.alloc(32, 1));
+            const builders = Array.from({ length: 10 }, () =>
+                buildSignableBuilder(keyPair),
+            );
+
+            const task = TransferTransactionBuilder.signBatchAsync(
+                builders,
+                keyPair,
+            );
+            expect(task.cancel()).to.eq(true);
+
+            let error: any;
+            try {
+                await task;
+            } catch (err) {
+                error = err;
+            }
+
+            expect(error?.code).to.eq('CANCELLED');
+            builders.forEach((builder) => {
+                expect(builder.isCompleted()).to.eq(false);
+            });
+        });
+    });
+
     describe('addWitness', () => {
         it('should throw Error when the input index is negative', () => {
             const builder = new TransferTransactionBuilder();
@@ -1407,7 +1596,7 @@ describe('TransferTransactionBuilder', () => {
             );
         });
 
-        it('should return completed Hex', () => {
+        it('should return completed Hex', async () => {
             const builder = new TransferTransactionBuilder({
                 network: ZERO_FEE_DEVNET,
             });
@@ -1455,12 +1644,12 @@ describe('TransferTransactionBuilder', () => {
             builder.signInput(0, keyPair);
             builder.signInput(1, keyPair);
 
-            expect(builder.toHex().toString('hex')).to.eq(
+            expect((await builder.toHex()).toString('hex')).to.eq(
                 '0000080000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000100010000000000000000000000000000000000000000008d05000800000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000001000400059d3549b956014ceb29d49af869e455586c144d653320bec4279ba5b7d3559adc050000000000000000ab040248b7c5f2325a7ef7dcd68066368fd63a7aad8c4a894414fcd81b227b2178322c00010000000000000008009e0400eb47cb60e896d9876f85823d8d4b9b685f7fa4193e3508f9f08004b10caae91ba2000cb48b35a2333b8a9dc5c90849b82147b3e791c8d7ff323d47faff001b84c5567b126440995d3ed5aaba0565d71e1834604819ff9c17f5e9d5dd078f009e0400eb47cb60e896d9876f85823d8d4b9b685f7fa4193e3508f9f08004b10caae91ba2000cb48b35a2333b8a9dc5c90849b82147b3e791c8d7ff323d47faff001b84c5567b126440995d3ed5aaba0565d71e1834604819ff9c17f5e9d5dd078fd1d2c971db2e1016dfd67209d62675e82b329a9362f0e39e11218f441ddbbabe',
             );
         });
 
-        it('should return completed Hex given correct tendermint address', () => {
+        it('should return completed Hex given correct tendermint address', async () => {
             const builder = new TransferTransactionBuilder({
                 network: ZERO_FEE_DEVNET,
             });
@@ -1495,30 +1684,144 @@ describe('TransferTransactionBuilder', () => {
             builder.signInput(0, keyPair);
 
             expect(
-                builder.toHex('ws://127.0.0.1/websocket').toString('hex'),
+                (
+                    await builder.toHex('ws://127.0.0.1/websocket')
+                ).toString('hex'),
             ).to.eq(
                 '00000400000000000000000000000000000000000000000000000000000000000000000000010000000000000000000000000000000000000000007d030004000000000000000000000000000000000000000000000000000000000000000000000400059d3549b956014ceb29d49af869e455586c144d653320bec4279ba5b7d3559adc050000000000000000ab040248b7c5f2325a7ef7dcd68066368fd63a7aad8c4a894414fcd81b227b2178322c00010000000000000004006913d40ceca57ae9330c493b4e9d38ef0beebf638ca86df187b000832d6e3f8bc8e766c61a8fc7c611b3b6ef0e9094346af856c983864f6bfccf217c5dd6b7d4001b84c5567b126440995d3ed5aaba0565d71e1834604819ff9c17f5e9d5dd078f93ccef4bfff2fe0ce9b0d4276ce45d1aa91be72fb8c1f479ba04e7bd3ed2f04b',
             );
             expect(
-                builder.toHex('ws://localhost/websocket').toString('hex'),
+                (
+                    await builder.toHex('ws://localhost/websocket')
+                ).toString('hex'),
             ).to.eq(
                 '00000400000000000000000000000000000000000000000000000000000000000000000000010000000000000000000000000000000000000000007d030004000000000000000000000000000000000000000000000000000000000000000000000400059d3549b956014ceb29d49af869e455586c144d653320bec4279ba5b7d3559adc050000000000000000ab040248b7c5f2325a7ef7dcd68066368fd63a7aad8c4a894414fcd81b227b2178322c00010000000000000004006913d40ceca57ae9330c493b4e9d38ef0beebf638ca86df187b000832d6e3f8bc8e766c61a8fc7c611b3b6ef0e9094346af856c983864f6bfccf217c5dd6b7d4001b84c5567b126440995d3ed5aaba0565d71e1834604819ff9c17f5e9d5dd078f93ccef4bfff2fe0ce9b0d4276ce45d1aa91be72fb8c1f479ba04e7bd3ed2f04b',
             );
             expect(
-                builder
-                    .toHex('ws://tendermint-zerofee:26657/websocket')
-                    .toString('hex'),
+                (
+                    await builder.toHex(
+                        'ws://tendermint-zerofee:26657/websocket',
+                    )
+                ).toString('hex'),
             ).to.eq(
                 '00000400000000000000000000000000000000000000000000000000000000000000000000010000000000000000000000000000000000000000007d030004000000000000000000000000000000000000000000000000000000000000000000000400059d3549b956014ceb29d49af869e455586c144d653320bec4279ba5b7d3559adc050000000000000000ab040248b7c5f2325a7ef7dcd68066368fd63a7aad8c4a894414fcd81b227b2178322c00010000000000000004006913d40ceca57ae9330c493b4e9d38ef0beebf638ca86df187b000832d6e3f8bc8e766c61a8fc7c611b3b6ef0e9094346af856c983864f6bfccf217c5dd6b7d4001b84c5567b126440995d3ed5aaba0565d71e1834604819ff9c17f5e9d5dd078f93ccef4bfff2fe0ce9b0d4276ce45d1aa91be72fb8c1f479ba04e7bd3ed2f04b',
             );
             expect(
-                builder.toHex('wss://localhost/websocket').toString('hex'),
+                (
+                    await builder.toHex('wss://localhost/websocket')
+                ).toString('hex'),
             ).to.eq(
                 '00000400000000000000000000000000000000000000000000000000000000000000000000010000000000000000000000000000000000000000007d030004000000000000000000000000000000000000000000000000000000000000000000000400059d3549b956014ceb29d49af869e455586c144d653320bec4279ba5b7d3559adc050000000000000000ab040248b7c5f2325a7ef7dcd68066368fd63a7aad8c4a894414fcd81b227b2178322c00010000000000000004006913d40ceca57ae9330c493b4e9d38ef0beebf638ca86df187b000832d6e3f8bc8e766c61a8fc7c611b3b6ef0e9094346af856c983864f6bfccf217c5dd6b7d4001b84c5567b126440995d3ed5aaba0565d71e1834604819ff9c17f5e9d5dd078f93ccef4bfff2fe0ce9b0d4276ce45d1aa91be72fb8c1f479ba04e7bd3ed2f04b',
             );
         });
     });
 
+    describe('toHex with MockTxQuery', () => {
+        const ZERO_FEE_DEVNET = Devnet({
+            feeConfig: ZERO_LINEAR_FEE,
+            chainHexId: 'AB',
+        });
+        let nodeEnv: string | undefined;
+
+        // The mock tx-query backend only exists in the native module built
+        // with the mock-tx-query feature, see npm run test:mock-tx-query
+        before(function () {
+            if (typeof native.unsafeResetMockTxQuery !== 'function') {
+                this.skip();
+            }
+        });
+
+        beforeEach(() => {
+            nodeEnv = process.env.NODE_ENV;
+            process.env.NODE_ENV = 'mock-tx-query-test';
+            native.unsafeResetMockTxQuery();
+        });
+
+        afterEach(() => {
+            process.env.NODE_ENV = nodeEnv;
+        });
+
+        const completedBuilder = (network: typeof ZERO_FEE_DEVNET) => {
+            const builder = new TransferTransactionBuilder({ network });
+
+            const keyPair = KeyPair.fromPrivateKey(Buffer.alloc(32, 1));
+            builder
+                .addInput({
+                    prevTxId:
+                        '0000000000000000000000000000000000000000000000000000000000000000',
+                    prevIndex: 0,
+                    prevOutput: {
+                        address: transfer({
+                            keyPair,
+                            network,
+                        }),
+                        value: new BigNumber('2000'),
+                    },
+                    addressParams: SINGLE_SIGN_ADDRESS,
+                })
+                .addOutput({
+                    address: transfer({
+                        keyPair,
+                        network,
+                    }),
+                    value: new BigNumber('1500'),
+                })
+                .addViewKey(
+                    Buffer.from(
+                        '0248b7c5f2325a7ef7dcd68066368fd63a7aad8c4a894414fcd81b227b2178322c',
+                        'hex',
+                    ),
+                );
+            builder.signInput(0, keyPair);
+
+            return builder;
+        };
+
+        it('should obfuscate the transaction with the mock tx-query backend offline', async () => {
+            const builder = completedBuilder(ZERO_FEE_DEVNET);
+
+            const hex = await builder.toHex('ws://127.0.0.1:1/websocket');
+
+            expect(hex).to.be.an.instanceOf(Buffer);
+            expect(hex.toString('hex')).to.include(builder.txId());
+            expect(native.unsafeResetMockTxQuery()).to.eq(1);
+        });
+
+        it('should reject the network which does not accept plain transactions', async () => {
+            // Registered once per process, the network registry is global
+            const isRegistered = native.network
+                .list()
+                .some(
+                    ({ name }: { name: string }) => name === 'NoPlainDevnet',
+                );
+            if (!isRegistered) {
+                native.network.registerDevnet({
+                    name: 'NoPlainDevnet',
+                    chainHexId: Buffer.from('AC', 'hex'),
+                    plainTransactions: false,
+                });
+            }
+            const builder = completedBuilder(
+                Devnet({
+                    feeConfig: ZERO_LINEAR_FEE,
+                    chainHexId: 'AC',
+                }),
+            );
+
+            let error: Error | undefined;
+            try {
+                await builder.toHex('ws://127.0.0.1:1/websocket');
+            } catch (err) {
+                error = err;
+            }
+
+            expect(error?.message).to.include(
+                'does not accept plain transactions without obfuscation',
+            );
+            expect(native.unsafeResetMockTxQuery()).to.eq(0);
+        });
+    });
+
     describe('toIncompleteHex', () => {
         it('should return Hex when transaction does not have input', () => {
             const builder = new TransferTransactionBuilder();
@@ -20,7 +20,20 @@ import { owKeyPair } from '../../key_pair/types';
 import { getRustFeaturesFromEnv } from '../../native';
 import { FeeConfig, FeeAlgorithm } from '../../fee';
 import { parseFeeConfigForNative } from '../../fee/types';
+import { toNativeNetwork } from '../../network/network';
 import { TransactionBuilder } from '../transaction_builder';
+import {
+    NativeCallback,
+    NativeTask,
+    runNativeTask,
+} from '../../native_task';
+import {
+    ExternalSigner,
+    owExternalSigner,
+    schnorrSignatureToWitness,
+    SignatureScheme,
+    signWithExternalSigner,
+} from '../signer';
 import { transfer } from '../../address/transfer';
 
 const native = require('../../../../native');
@@ -114,7 +127,7 @@ export class TransferTransactionBuilder extends TransactionBuilder {
     private isTransferAddressInNetwork(address: string): boolean {
         return native.address.isTransferAddressValid(
             address,
-            this.getNetwork().name,
+            toNativeNetwork(this.getNetwork()),
         );
     }
 
@@ -259,6 +272,151 @@ export class TransferTransactionBuilder extends TransactionBuilder {
         this.incompleteHex = updatedIncompleteSigningHex;
     }
 
+    /**
+     * Sign all inputs of the builders with the provided KeyPair on background
+     * threads, e.g. for the transactions of a payout run. The builders are
+     * updated when signing completes
+     *
+     * @static
+     * @param {TransferTransactionBuilder[]} builders builders whose inputs are
+     * all unlocked by the key pair
+     * @param {KeyPair} keyPair key pair which can unlock the inputs
+     * @returns {NativeTask<Buffer[]>} incomplete hex of the signed builders
+     * in order. Cancelling the task leaves the builders unsigned
+     * @memberof TransferTransactionBuilder
+     */
+    public static signBatchAsync(
+        builders: TransferTransactionBuilder[],
+        keyPair: KeyPair,
+    ): NativeTask<Buffer[]> {
+        return runNativeTask((callback) =>
+            TransferTransactionBuilder.startSignBatch(
+                builders,
+                keyPair,
+                callback,
+            ),
+        );
+    }
+
+    private static startSignBatch(
+        builders: TransferTransactionBuilder[],
+        keyPair: KeyPair,
+        callback: NativeCallback<Buffer[]>,
+    ): unknown {
+        ow(builders, 'builders', ow.array);
+        ow(keyPair, 'KeyPair', owKeyPair);
+
+        if (!keyPair.hasPrivateKey()) {
+            throw new Error('KeyPair does not have private key');
+        }
+
+        const jobs = builders.map((builder, i) => {
+            if (!(builder instanceof TransferTransactionBuilder)) {
+                throw new Error(
+                    `Builder ${i} is not a TransferTransactionBuilder`,
+                );
+            }
+            if (builder.feeConfig.algorithm !== FeeAlgorithm.LinearFee) {
+                throw new Error(
+                    `Unsupported fee algorithm ${builder.feeConfig.algorithm}`,
+                );
+            }
+            builder.verifyHasInput();
+
+            const signingAddress = transfer({
+                keyPair,
+                network: builder.getNetwork(),
+            });
+            if (
+                builder.inputs.some(
+                    (input) => input.prevOutput.address !== signingAddress,
+                )
+            ) {
+                throw new Error(
+                    `Inputs of builder ${i} are not signable by the key pair`,
+                );
+            }
+
+            return {
+                incompleteHex: builder.prepareIncompleteSigningHex(),
+                feeConfig: parseFeeConfigForNative(builder.feeConfig),
+                inputIndexes: builder.inputs.map((_, index) => index),
+            };
+        });
+        const txIds = builders.map((builder) => builder.txId());
+
+        return native.transferTransaction.signBatchLinearFee(
+            jobs,
+            keyPair.toObject(),
+            (err: Error | null, signedIncompleteHexes: Buffer[]) => {
+                if (err) {
+                    callback(err, signedIncompleteHexes);
+                    return;
+                }
+
+                const modifiedIndex = builders.findIndex(
+                    (builder, i) => builder.txId() !== txIds[i],
+                );
+                if (modifiedIndex !== -1) {
+                    callback(
+                        new Error(
+                            `Builder ${modifiedIndex} has been modified during batch signing`,
+                        ),
+                        signedIncompleteHexes,
+                    );
+                    return;
+                }
+                builders.forEach((builder, i) => {
+                    // eslint-disable-next-line no-param-reassign
+                    builder.incompleteHex = signedIncompleteHexes[i];
+                });
+
+                callback(null, signedIncompleteHexes);
+            },
+        );
+    }
+
+    /**
+     * Sign a particular input with an external signer, e.g. hardware wallet.
+     * The signer receives the transaction Id and resolves to the Schnorr
+     * signature, the witness is then assembled and added to the input
+     *
+     * @param {number} index input index
+     * @param {Buffer} publicKey public key of the external signer
+     * @param {ExternalSigner} signer external signer which can unlock the input
+     * @memberof TransferTransactionBuilder
+     */
+    public async signInputWithSigner(
+        index: number,
+        publicKey: Buffer,
+        signer: ExternalSigner,
+    ): Promise<void> {
+        ow(index, 'index', this.owIndex());
+        ow(publicKey, 'publicKey', ow.buffer);
+        ow(signer, 'signer', owExternalSigner);
+
+        const signingAddress = transfer({
+            keyPair: KeyPair.fromPublicKey(publicKey),
+            network: this.getNetwork(),
+        });
+        if (this.inputs[index].prevOutput.address !== signingAddress) {
+            throw new Error('Input address is not signable by the signer');
+        }
+
+        const txId = this.txId();
+        const signature = await signWithExternalSigner(signer, {
+            txId: Buffer.from(txId, 'hex'),
+            scheme: SignatureScheme.Schnorr,
+        });
+        if (this.txId() !== txId) {
+            throw new Error(
+                'Builder has been modified during external signing',
+            );
+        }
+
+        this.addWitness(index, schnorrSignatureToWitness(signature, publicKey));
+    }
+
     /**
      * Add witness to particular input
      *
@@ -359,12 +517,13 @@ export class TransferTransactionBuilder extends TransactionBuilder {
      * Output broadcast-able transaction in hex
      *
      * @param {string} [tendermintAddress='ws://localhost:26657/websocket']
-     * @returns {Buffer}
+     * @returns {NativeTask<Buffer>} resolves once the transaction is
+     *  obfuscated, which runs on a background thread
      * @memberof TransferTransactionBuilder
      */
     public toHex(
         tendermintAddress: string = 'ws://localhost:26657/websocket',
-    ): Buffer {
+    ): NativeTask<Buffer> {
         ow(tendermintAddress, owTendermintAddress);
 
         this.verifyHasInput();
@@ -377,15 +536,18 @@ export class TransferTransactionBuilder extends TransactionBuilder {
         // FIXME: divide this.verify() suite to more specific error checks
         this.verify();
 
-        return native.transferTransaction.toHexLinearFee(
-            {
-                incompleteHex: this.incompleteHex,
-                feeConfig: parseFeeConfigForNative(this.feeConfig),
-            },
-            tendermintAddress,
-            // TODO: Use feature conditional compilation when ready
-            // https://github.com/neon-bindings/neon/issues/471
-            getRustFeaturesFromEnv(process.env.NODE_ENV),
+        return runNativeTask((callback) =>
+            native.transferTransaction.toHexLinearFee(
+                {
+                    incompleteHex: this.incompleteHex,
+                    feeConfig: parseFeeConfigForNative(this.feeConfig),
+                },
+                tendermintAddress,
+                // TODO: Use feature conditional compilation when ready
+                // https://github.com/neon-bindings/neon/issues/471
+                getRustFeaturesFromEnv(process.env.NODE_ENV),
+                callback,
+            ),
         );
     }
 
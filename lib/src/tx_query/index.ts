@@ -0,0 +1,6 @@
+export { setAttestationPolicy, attest, fetchTransactions } from './tx_query';
+export {
+    AttestationPolicy,
+    AttestationVerification,
+    FetchedTransaction,
+} from './types';
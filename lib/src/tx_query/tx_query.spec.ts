@@ -0,0 +1,63 @@
+import 'mocha';
+import { expect } from 'chai';
+
+import { attest, fetchTransactions } from './tx_query';
+
+const native = require('../../../native');
+
+const UNREACHABLE_TENDERMINT_ADDRESS = 'ws://127.0.0.1:1/websocket';
+
+describe('txQuery', () => {
+    describe('attest', () => {
+        it('should throw Error when the Tendermint address is invalid', () => {
+            expect(() => {
+                attest('http://127.0.0.1:26657');
+            }).to.throw('Expected value to be HTTP or WS tendermint address');
+        });
+
+        it('should reject when no attestation policy is set for the address', async () => {
+            let error: any;
+            try {
+                await attest(UNREACHABLE_TENDERMINT_ADDRESS);
+            } catch (err) {
+                error = err;
+            }
+
+            expect(error?.code).to.eq('INVALID_ARGUMENT');
+            expect(error?.message).to.eq(
+                'No attestation policy is set for the Tendermint address',
+            );
+        });
+
+        it('should not run synchronously without a callback', () => {
+            expect(() => {
+                native.txQuery.attest(UNREACHABLE_TENDERMINT_ADDRESS, {
+                    caCertificate: Buffer.alloc(1),
+                });
+            }).to.throw('network calls only run on background thread');
+        });
+    });
+
+    describe('fetchTransactions', () => {
+        it('should throw Error when a transaction id is invalid', () => {
+            expect(() => {
+                fetchTransactions(
+                    UNREACHABLE_TENDERMINT_ADDRESS,
+                    ['invalid'],
+                    Buffer.alloc(32, 1),
+                );
+            }).to.throw(/to match/);
+        });
+
+        it('should return a Promise', () => {
+            const transactions = fetchTransactions(
+                UNREACHABLE_TENDERMINT_ADDRESS,
+                [],
+                Buffer.alloc(32, 1),
+            );
+            transactions.catch(() => undefined);
+
+            expect(transactions).to.be.instanceOf(Promise);
+        });
+    });
+});
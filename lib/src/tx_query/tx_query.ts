@@ -0,0 +1,86 @@
+import ow from 'ow';
+
+import {
+    AttestationPolicy,
+    AttestationVerification,
+    FetchedTransaction,
+    owOptionalAttestationPolicy,
+} from './types';
+import { owTendermintAddress, owTxId } from '../types';
+import { getRustFeaturesFromEnv } from '../native';
+import { NativeTask, runNativeTask } from '../native_task';
+
+const native = require('../../../native');
+
+/**
+ * Set the policy the tx-query enclave of the Tendermint websocket address is
+ * attested against, tx-query connections of the address fail when the
+ * attestation is invalid
+ *
+ * @export
+ * @param {string} tendermintAddress Tendermint websocket address
+ * @param {AttestationPolicy} [policy] removes the policy of the address when
+ * omitted
+ */
+export const setAttestationPolicy = (
+    tendermintAddress: string,
+    policy?: AttestationPolicy,
+): void => {
+    ow(tendermintAddress, 'tendermintAddress', owTendermintAddress);
+    ow(policy, 'policy', owOptionalAttestationPolicy);
+
+    native.txQuery.setAttestationPolicy(tendermintAddress, policy);
+};
+
+/**
+ * Connect to the tx-query enclave of the Tendermint websocket address and
+ * verify its attestation
+ *
+ * @export
+ * @param {string} tendermintAddress Tendermint websocket address
+ * @param {AttestationPolicy} [policy] the registered policy of the address
+ * when omitted
+ * @returns {NativeTask<AttestationVerification>}
+ */
+export const attest = (
+    tendermintAddress: string,
+    policy?: AttestationPolicy,
+): NativeTask<AttestationVerification> => {
+    ow(tendermintAddress, 'tendermintAddress', owTendermintAddress);
+    ow(policy, 'policy', owOptionalAttestationPolicy);
+
+    return runNativeTask((callback) =>
+        native.txQuery.attest(tendermintAddress, policy ?? null, callback),
+    );
+};
+
+/**
+ * Fetch the transactions of the ids from the tx-query enclave, decrypted
+ * with the view key
+ *
+ * @export
+ * @param {string} tendermintAddress Tendermint websocket address
+ * @param {string[]} txIds transaction ids
+ * @param {Buffer} viewPrivateKey view private key
+ * @returns {NativeTask<FetchedTransaction[]>} transactions the view key has
+ * access to, the others are left out
+ */
+export const fetchTransactions = (
+    tendermintAddress: string,
+    txIds: string[],
+    viewPrivateKey: Buffer,
+): NativeTask<FetchedTransaction[]> => {
+    ow(tendermintAddress, 'tendermintAddress', owTendermintAddress);
+    ow(txIds, 'txIds', ow.array.ofType(owTxId));
+    ow(viewPrivateKey, 'viewPrivateKey', ow.buffer);
+
+    return runNativeTask((callback) =>
+        native.txQuery.fetchTransactions(
+            tendermintAddress,
+            txIds,
+            viewPrivateKey,
+            getRustFeaturesFromEnv(process.env.NODE_ENV),
+            callback,
+        ),
+    );
+};
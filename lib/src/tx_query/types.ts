@@ -0,0 +1,66 @@
+import ow from 'ow';
+
+/**
+ * Policy the tx-query enclave is attested against
+ * @typedef {object} AttestationPolicy
+ * @property {Buffer | string} caCertificate DER Buffer or PEM string of the
+ * CA of the attestation report signing certificate
+ * @property {string[]} [mrEnclaves] accepted measurement hex strings, any
+ * when empty
+ * @property {string[]} [mrSigners] accepted measurement hex strings, any
+ * when empty
+ * @property {string[]} [quoteStatuses] accepted enclave quote statuses,
+ * default ["OK"]
+ * @property {number} [maxReportAgeSecs] maximum age of the attestation
+ * report
+ * @property {string} [txQueryAddress] host:port of the enclave, looked up
+ * from the node when omitted
+ */
+export interface AttestationPolicy {
+    caCertificate: Buffer | string;
+    mrEnclaves?: string[];
+    mrSigners?: string[];
+    quoteStatuses?: string[];
+    maxReportAgeSecs?: number;
+    txQueryAddress?: string;
+}
+
+const owMeasurement = ow.string.matches(/^[0-9A-Fa-f]{64}$/);
+
+export const owAttestationPolicy = ow.object.exactShape({
+    caCertificate: ow.any(ow.buffer, ow.string),
+    mrEnclaves: ow.optional.array.ofType(owMeasurement),
+    mrSigners: ow.optional.array.ofType(owMeasurement),
+    quoteStatuses: ow.optional.array.ofType(ow.string),
+    maxReportAgeSecs: ow.optional.number.integer.greaterThanOrEqual(0),
+    txQueryAddress: ow.optional.string,
+});
+
+export const owOptionalAttestationPolicy = ow.optional.any(
+    owAttestationPolicy,
+);
+
+/**
+ * Attestation result, the fields are returned as far as the verification
+ * goes
+ * @typedef {object} AttestationVerification
+ */
+export interface AttestationVerification {
+    isValid: boolean;
+    reason?: string;
+    mrEnclave?: string;
+    mrSigner?: string;
+    quoteStatus?: string;
+    reportTimestamp?: string;
+}
+
+/**
+ * Transaction decrypted by the tx-query enclave
+ * @typedef {object} FetchedTransaction
+ */
+export interface FetchedTransaction {
+    txId: string;
+    txType: string;
+    inputs: object[];
+    outputs: object[];
+}
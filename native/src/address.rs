@@ -1,31 +1,91 @@
 use std::str::FromStr;
 
+use bech32::{FromBase32, ToBase32};
 use chain_core::init::address::{CroAddress, RedeemAddress};
+use chain_core::init::coin::Coin;
+use chain_core::init::network::Network;
 use chain_core::state::account::StakedStateAddress;
 use chain_core::tx::data::address::ExtendedAddr;
 use client_common::MultiSigAddress;
 use neon::prelude::*;
+use tiny_keccak::{Hasher, Keccak};
 
-use crate::error::ClientErrorNeonExt;
+use crate::common::percent_decode;
+use crate::error::{throw_coded_error, ClientErrorNeonExt, ErrorCode};
 use crate::function_types::*;
 
+/// derive single signer bech32 transfer address from public key
+/// @arguments
+/// - public_key: compressed or uncompressed public key of the owner
+/// - network: network handle or name of the address, decides the
+///     cro/tcro/dcro or custom devnet prefix
+/// @return cro_address
 pub fn get_transfer_address_from_public_key(mut ctx: FunctionContext) -> JsResult<JsString> {
     let public_key = public_key_argument(&mut ctx, 0)?;
-    let network = network_argument(&mut ctx, 1)?;
+    let network_config = network_config_argument(&mut ctx, 1)?;
 
     let required_signers = 1;
     let multi_sig_address =
         MultiSigAddress::new(vec![public_key.clone()], public_key, required_signers)
-            .chain_neon(&mut ctx, "Unable to create MultiSig address")?;
+            .chain_neon_with_code(
+                &mut ctx,
+                ErrorCode::InvalidAddress,
+                "Unable to create MultiSig address",
+            )?;
 
     let extended_address = ExtendedAddr::from(multi_sig_address);
-    let cro_address = extended_address
-        .to_cro(network)
-        .chain_neon(&mut ctx, "Unable to convert to CRO address")?;
+    let cro_address = encode_transfer_address(&extended_address, &network_config.bech32_hrp)
+        .chain_neon_with_code(
+            &mut ctx,
+            ErrorCode::InvalidAddress,
+            "Unable to convert to CRO address",
+        )?;
 
     Ok(ctx.string(cro_address))
 }
 
+/// create m-of-n multisig transfer address
+/// @arguments
+/// - public_keys: public keys of all the signers
+/// - required_signers: min number of signers to spend from the address
+/// - self_public_key: public key of the signer creating the address
+/// - network: network of the address
+/// @return cro_address
+pub fn create_multi_sig_transfer_address(mut ctx: FunctionContext) -> JsResult<JsString> {
+    let public_keys = public_key_vector_argument(&mut ctx, 0)?;
+    let required_signers = required_signers_argument(&mut ctx, 1)?;
+    let self_public_key = public_key_argument(&mut ctx, 2)?;
+    let network_config = network_config_argument(&mut ctx, 3)?;
+
+    if required_signers > public_keys.len() {
+        return ctx.throw_error("Required signers should not exceed number of public keys");
+    }
+    if !public_keys.contains(&self_public_key) {
+        return ctx.throw_error("Self public key should be one of the signer public keys");
+    }
+
+    let multi_sig_address = MultiSigAddress::new(public_keys, self_public_key, required_signers)
+        .chain_neon_with_code(
+            &mut ctx,
+            ErrorCode::InvalidAddress,
+            "Unable to create MultiSig address",
+        )?;
+
+    let extended_address = ExtendedAddr::from(multi_sig_address);
+    let cro_address = encode_transfer_address(&extended_address, &network_config.bech32_hrp)
+        .chain_neon_with_code(
+            &mut ctx,
+            ErrorCode::InvalidAddress,
+            "Unable to convert to CRO address",
+        )?;
+
+    Ok(ctx.string(cro_address))
+}
+
+/// derive 0x-prefixed staking address from public key
+/// @arguments
+/// - public_key: compressed or uncompressed public key of the owner
+/// @return staking_address
 pub fn get_staking_address_from_public_key(mut ctx: FunctionContext) -> JsResult<JsString> {
     let public_key = public_key_argument(&mut ctx, 0)?;
 
@@ -36,9 +96,9 @@ pub fn get_staking_address_from_public_key(mut ctx: FunctionContext) -> JsResult
 
 pub fn is_transfer_address_valid(mut ctx: FunctionContext) -> JsResult<JsBoolean> {
     let address = ctx.argument::<JsString>(0)?.value();
-    let network = network_argument(&mut ctx, 1)?;
+    let network_config = network_config_argument(&mut ctx, 1)?;
 
-    let is_valid = ExtendedAddr::from_cro(&address, network).is_ok();
+    let is_valid = decode_transfer_address(&address, &network_config.bech32_hrp).is_ok();
 
     Ok(ctx.boolean(is_valid))
 }
@@ -46,11 +106,676 @@ pub fn is_transfer_address_valid(mut ctx: FunctionContext) -> JsResult<JsBoolean
 pub fn is_staking_address_valid(mut ctx: FunctionContext) -> JsResult<JsBoolean> {
     let address = ctx.argument::<JsString>(0)?.value();
 
-    let is_valid = StakedStateAddress::from_str(&address).is_ok();
+    let is_valid = StakedStateAddress::from_str(&address).is_ok()
+        && validate_staking_address_str(&address).is_ok();
 
     Ok(ctx.boolean(is_valid))
 }
 
+/// Reason of an address failing validation
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum AddressValidationError {
+    InvalidPrefix,
+    InvalidEncoding,
+    InvalidChecksum,
+    NetworkMismatch,
+    InvalidLength,
+}
+
+impl AddressValidationError {
+    /// Returns the stable code of the validation error
+    pub fn code(self) -> &'static str {
+        match self {
+            AddressValidationError::InvalidPrefix => "INVALID_PREFIX",
+            AddressValidationError::InvalidEncoding => "INVALID_ENCODING",
+            AddressValidationError::InvalidChecksum => "INVALID_CHECKSUM",
+            AddressValidationError::NetworkMismatch => "NETWORK_MISMATCH",
+            AddressValidationError::InvalidLength => "INVALID_LENGTH",
+        }
+    }
+
+    /// Returns the human readable message of the validation error
+    pub fn message(self) -> &'static str {
+        match self {
+            AddressValidationError::InvalidPrefix => "Address has unrecognized prefix",
+            AddressValidationError::InvalidEncoding => "Address is not correctly encoded",
+            AddressValidationError::InvalidChecksum => "Address checksum mismatch",
+            AddressValidationError::NetworkMismatch => "Address belongs to another network",
+            AddressValidationError::InvalidLength => "Address payload has invalid length",
+        }
+    }
+}
+
+const TRANSFER_ADDRESS_PAYLOAD_LENGTH: usize = 32;
+const STAKING_ADDRESS_PAYLOAD_LENGTH: usize = 20;
+/// Bech32 strings are at most 90 characters, of which a transfer address
+/// takes 52 for the payload, 1 for the separator and 6 for the checksum
+const MAX_BECH32_HRP_LENGTH: usize = 31;
+
+#[inline]
+pub fn bech32_hrp_of_network(network: Network) -> &'static str {
+    match network {
+        Network::Mainnet => "cro",
+        Network::Testnet => "tcro",
+        Network::Devnet => "dcro",
+    }
+}
+
+/// Returns the network of the bech32 human readable part
+#[inline]
+pub fn network_of_bech32_hrp(hrp: &str) -> Option<Network> {
+    [Network::Mainnet, Network::Testnet, Network::Devnet]
+        .iter()
+        .cloned()
+        .find(|&network| bech32_hrp_of_network(network) == hrp)
+}
+
+/// Whether the bech32 human readable part is usable as a custom prefix:
+/// 1 to 31 lowercase printable ASCII characters, without the separator
+pub fn is_valid_bech32_hrp(hrp: &str) -> bool {
+    !hrp.is_empty()
+        && hrp.len() <= MAX_BECH32_HRP_LENGTH
+        && hrp
+            .bytes()
+            .all(|byte| (33..=126).contains(&byte) && !byte.is_ascii_uppercase() && byte != b'1')
+}
+
+/// Encode the transfer address with the bech32 human readable part
+pub fn encode_transfer_address(address: &ExtendedAddr, hrp: &str) -> Result<String, bech32::Error> {
+    match address {
+        ExtendedAddr::OrTree(root) => bech32::encode(hrp, root.to_base32()),
+    }
+}
+
+/// Decode the transfer address of the bech32 human readable part after
+/// validating it
+pub fn decode_transfer_address(
+    address: &str,
+    hrp: &str,
+) -> Result<ExtendedAddr, AddressValidationError> {
+    let payload = validate_transfer_address_with_hrp(address, hrp)?;
+
+    let mut root = [0u8; TRANSFER_ADDRESS_PAYLOAD_LENGTH];
+    root.copy_from_slice(&payload);
+
+    Ok(ExtendedAddr::OrTree(root))
+}
+
+/// Validate bech32 human readable part, checksum and payload length of
+/// transfer address against the network
+pub fn validate_transfer_address_str(
+    address: &str,
+    network: Network,
+) -> Result<(), AddressValidationError> {
+    validate_transfer_address_with_hrp(address, bech32_hrp_of_network(network)).map(|_| ())
+}
+
+/// Validate transfer address against the bech32 human readable part, which
+/// may be a custom devnet prefix, returns the payload
+fn validate_transfer_address_with_hrp(
+    address: &str,
+    expected_hrp: &str,
+) -> Result<Vec<u8>, AddressValidationError> {
+    let (hrp, data) = bech32::decode(address).map_err(bech32_validation_error)?;
+
+    if hrp != expected_hrp {
+        let is_known_hrp = [Network::Mainnet, Network::Testnet, Network::Devnet]
+            .iter()
+            .any(|&network| hrp == bech32_hrp_of_network(network));
+        return if is_known_hrp {
+            Err(AddressValidationError::NetworkMismatch)
+        } else {
+            Err(AddressValidationError::InvalidPrefix)
+        };
+    }
+
+    let payload =
+        Vec::<u8>::from_base32(&data).map_err(|_| AddressValidationError::InvalidEncoding)?;
+    if payload.len() != TRANSFER_ADDRESS_PAYLOAD_LENGTH {
+        return Err(AddressValidationError::InvalidLength);
+    }
+
+    Ok(payload)
+}
+
+fn bech32_validation_error(err: bech32::Error) -> AddressValidationError {
+    match err {
+        bech32::Error::InvalidChecksum => AddressValidationError::InvalidChecksum,
+        bech32::Error::MissingSeparator => AddressValidationError::InvalidPrefix,
+        _ => AddressValidationError::InvalidEncoding,
+    }
+}
+
+/// Validate prefix, encoding and payload length of staking address
+pub fn validate_staking_address_str(address: &str) -> Result<(), AddressValidationError> {
+    if !address.starts_with("0x") {
+        return Err(AddressValidationError::InvalidPrefix);
+    }
+
+    let payload =
+        hex::decode(&address[2..]).map_err(|_| AddressValidationError::InvalidEncoding)?;
+    if payload.len() != STAKING_ADDRESS_PAYLOAD_LENGTH {
+        return Err(AddressValidationError::InvalidLength);
+    }
+
+    let hex_address = &address[2..];
+    let is_mixed_case = hex_address.chars().any(|c| c.is_ascii_lowercase())
+        && hex_address.chars().any(|c| c.is_ascii_uppercase());
+    if is_mixed_case && hex_address != staking_address_checksum(hex_address) {
+        return Err(AddressValidationError::InvalidChecksum);
+    }
+
+    Ok(())
+}
+
+/// EIP-55 checksum casing of the hex staking address without 0x prefix: a
+/// letter is uppercase when the nibble at its position in the keccak256 hash
+/// of the lowercase address is 8 or above
+fn staking_address_checksum(hex_address: &str) -> String {
+    let hex_address = hex_address.to_ascii_lowercase();
+
+    let mut hash = [0u8; 32];
+    let mut keccak = Keccak::v256();
+    keccak.update(hex_address.as_bytes());
+    keccak.finalize(&mut hash);
+
+    hex_address
+        .chars()
+        .enumerate()
+        .map(|(i, c)| {
+            let nibble = if i % 2 == 0 {
+                hash[i / 2] >> 4
+            } else {
+                hash[i / 2] & 0x0f
+            };
+            if nibble >= 8 {
+                c.to_ascii_uppercase()
+            } else {
+                c
+            }
+        })
+        .collect()
+}
+
+/// Returns the EIP-55 checksummed form of the valid staking address
+pub fn to_checksum_staking_address_str(address: &str) -> Result<String, AddressValidationError> {
+    validate_staking_address_str(address)?;
+
+    Ok(format!("0x{}", staking_address_checksum(&address[2..])))
+}
+
+/// validate transfer address and describe why it is invalid
+/// @arguments
+/// - address: bech32 transfer address
+/// - network: network the address should belong to
+/// @return {
+///     isValid: boolean,
+///     reason?: string,
+///     message?: string
+/// }
+pub fn validate_transfer_address(mut ctx: FunctionContext) -> JsResult<JsObject> {
+    let address = ctx.argument::<JsString>(0)?.value();
+    let network_config = network_config_argument(&mut ctx, 1)?;
+
+    let result =
+        validate_transfer_address_with_hrp(&address, &network_config.bech32_hrp).map(|_| ());
+
+    address_validation_result_to_js_object(&mut ctx, result)
+}
+
+/// format staking address with EIP-55 mixed-case checksum, so that a
+/// mistyped or transposed character is caught by the validator
+/// @arguments
+/// - address: 0x-prefixed staking address, lowercase, uppercase or
+///     checksummed
+/// @return checksummed staking address
+pub fn to_checksum_staking_address(mut ctx: FunctionContext) -> JsResult<JsString> {
+    let address = ctx.argument::<JsString>(0)?.value();
+
+    match to_checksum_staking_address_str(&address) {
+        Ok(checksum_address) => Ok(ctx.string(checksum_address)),
+        Err(err) => throw_coded_error(&mut ctx, ErrorCode::InvalidArgument, err.message()),
+    }
+}
+
+/// validate staking address and describe why it is invalid. Mixed-case
+/// address should match its EIP-55 checksum, all lowercase or all uppercase
+/// address carries no checksum
+/// @arguments
+/// - address: 0x-prefixed staking address
+/// @return {
+///     isValid: boolean,
+///     reason?: string,
+///     message?: string
+/// }
+pub fn validate_staking_address(mut ctx: FunctionContext) -> JsResult<JsObject> {
+    let address = ctx.argument::<JsString>(0)?.value();
+
+    let result = validate_staking_address_str(&address);
+
+    address_validation_result_to_js_object(&mut ctx, result)
+}
+
+fn address_validation_result_to_js_object<'a>(
+    ctx: &mut FunctionContext<'a>,
+    result: Result<(), AddressValidationError>,
+) -> JsResult<'a, JsObject> {
+    let js_object = JsObject::new(ctx);
+
+    let is_valid = ctx.boolean(result.is_ok());
+    js_object.set(ctx, "isValid", is_valid)?;
+
+    if let Err(err) = result {
+        let reason = ctx.string(err.code());
+        js_object.set(ctx, "reason", reason)?;
+        let message = ctx.string(err.message());
+        js_object.set(ctx, "message", message)?;
+    }
+
+    Ok(js_object)
+}
+
+/// Warning of an address converted to another network
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum NetworkConversionWarning {
+    SameNetwork,
+    FromMainnet,
+    ToMainnet,
+    CustomPrefix,
+}
+
+impl NetworkConversionWarning {
+    fn code(self) -> &'static str {
+        match self {
+            NetworkConversionWarning::SameNetwork => "SAME_NETWORK",
+            NetworkConversionWarning::FromMainnet => "FROM_MAINNET",
+            NetworkConversionWarning::ToMainnet => "TO_MAINNET",
+            NetworkConversionWarning::CustomPrefix => "CUSTOM_PREFIX",
+        }
+    }
+
+    fn message(self) -> &'static str {
+        match self {
+            NetworkConversionWarning::SameNetwork => {
+                "Address already belongs to the target network"
+            }
+            NetworkConversionWarning::FromMainnet => {
+                "Address is a Mainnet address, the converted address is controlled by the same keys"
+            }
+            NetworkConversionWarning::ToMainnet => {
+                "Converted address is a Mainnet address, funds sent to it are real"
+            }
+            NetworkConversionWarning::CustomPrefix => "Address has a prefix of no built-in network",
+        }
+    }
+}
+
+/// re-encode transfer address under the prefix of another network, e.g. to
+/// map Mainnet test fixtures onto Testnet. The payload, and so the keys
+/// controlling the address, stays the same
+/// @arguments
+/// - address: bech32 transfer address of any network
+/// - target_network: network handle or name to convert the address to
+/// @return {
+///     address: string,
+///     fromBech32Hrp: string,
+///     toBech32Hrp: string,
+///     warnings: [{ code, message }]
+/// }
+pub fn convert_network(mut ctx: FunctionContext) -> JsResult<JsObject> {
+    let address = ctx.argument::<JsString>(0)?.value();
+    let target_network_config = network_config_argument(&mut ctx, 1)?;
+
+    let from_hrp = match bech32::decode(&address) {
+        Ok((hrp, _)) => hrp,
+        Err(err) => {
+            let err = bech32_validation_error(err);
+            return throw_coded_error(
+                &mut ctx,
+                ErrorCode::InvalidAddress,
+                format!("{} ({})", err.message(), err.code()),
+            );
+        }
+    };
+    let extended_address = match decode_transfer_address(&address, &from_hrp) {
+        Ok(extended_address) => extended_address,
+        Err(err) => {
+            return throw_coded_error(
+                &mut ctx,
+                ErrorCode::InvalidAddress,
+                format!("{} ({})", err.message(), err.code()),
+            )
+        }
+    };
+    let to_hrp = target_network_config.bech32_hrp;
+    let converted_address = encode_transfer_address(&extended_address, &to_hrp)
+        .chain_neon_with_code(
+            &mut ctx,
+            ErrorCode::InvalidAddress,
+            "Unable to convert to CRO address",
+        )?;
+
+    let mut warnings = Vec::new();
+    if from_hrp == to_hrp {
+        warnings.push(NetworkConversionWarning::SameNetwork);
+    }
+    match network_of_bech32_hrp(&from_hrp) {
+        Some(Network::Mainnet) if from_hrp != to_hrp => {
+            warnings.push(NetworkConversionWarning::FromMainnet)
+        }
+        None => warnings.push(NetworkConversionWarning::CustomPrefix),
+        _ => {}
+    }
+    if from_hrp != to_hrp && network_of_bech32_hrp(&to_hrp) == Some(Network::Mainnet) {
+        warnings.push(NetworkConversionWarning::ToMainnet);
+    }
+
+    let js_object = JsObject::new(&mut ctx);
+    let js_address = ctx.string(converted_address);
+    js_object.set(&mut ctx, "address", js_address)?;
+    let js_from_hrp = ctx.string(from_hrp);
+    js_object.set(&mut ctx, "fromBech32Hrp", js_from_hrp)?;
+    let js_to_hrp = ctx.string(to_hrp);
+    js_object.set(&mut ctx, "toBech32Hrp", js_to_hrp)?;
+
+    let js_warnings = JsArray::new(&mut ctx, warnings.len() as u32);
+    for (i, warning) in warnings.iter().enumerate() {
+        let js_warning = JsObject::new(&mut ctx);
+        let code = ctx.string(warning.code());
+        js_warning.set(&mut ctx, "code", code)?;
+        let message = ctx.string(warning.message());
+        js_warning.set(&mut ctx, "message", message)?;
+        js_warnings.set(&mut ctx, i as u32, js_warning)?;
+    }
+    js_object.set(&mut ctx, "warnings", js_warnings)?;
+
+    Ok(js_object)
+}
+
+/// Scheme of the payment request URI, e.g.
+/// `cro:cro1...?amount=100000000&memo=Coffee%20beans`
+pub const PAYMENT_URI_SCHEME: &str = "cro";
+/// Max length in bytes of the payment request memo
+pub const MAX_PAYMENT_MEMO_LENGTH: usize = 256;
+
+/// Payment request of the payment URI
+#[derive(Debug, Clone, PartialEq)]
+pub struct PaymentRequest {
+    pub address: String,
+    pub network: Network,
+    /// Amount in basic unit
+    pub amount: Option<Coin>,
+    pub memo: Option<String>,
+}
+
+/// Reason of a payment URI failing to parse or encode
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PaymentUriError {
+    InvalidScheme,
+    InvalidAddress(AddressValidationError),
+    InvalidAmount,
+    InvalidMemo,
+    InvalidEncoding,
+    UnknownParameter,
+    DuplicateParameter,
+}
+
+impl PaymentUriError {
+    /// Returns the stable code of the payment URI error
+    pub fn code(self) -> &'static str {
+        match self {
+            PaymentUriError::InvalidScheme => "INVALID_SCHEME",
+            PaymentUriError::InvalidAddress(err) => err.code(),
+            PaymentUriError::InvalidAmount => "INVALID_AMOUNT",
+            PaymentUriError::InvalidMemo => "INVALID_MEMO",
+            PaymentUriError::InvalidEncoding => "INVALID_ENCODING",
+            PaymentUriError::UnknownParameter => "UNKNOWN_PARAMETER",
+            PaymentUriError::DuplicateParameter => "DUPLICATE_PARAMETER",
+        }
+    }
+
+    /// Returns the human readable message of the payment URI error
+    pub fn message(self) -> &'static str {
+        match self {
+            PaymentUriError::InvalidScheme => "Payment URI should start with cro:",
+            PaymentUriError::InvalidAddress(err) => err.message(),
+            PaymentUriError::InvalidAmount => {
+                "Amount should be a positive integer in basic unit not exceeding max supply"
+            }
+            PaymentUriError::InvalidMemo => "Memo should not exceed 256 bytes",
+            PaymentUriError::InvalidEncoding => "Payment URI is not correctly percent-encoded",
+            PaymentUriError::UnknownParameter => "Payment URI has unknown parameter",
+            PaymentUriError::DuplicateParameter => "Payment URI has duplicate parameter",
+        }
+    }
+
+    fn error_code(self) -> ErrorCode {
+        match self {
+            PaymentUriError::InvalidAddress(_) => ErrorCode::InvalidAddress,
+            _ => ErrorCode::InvalidArgument,
+        }
+    }
+}
+
+/// Returns the network of the transfer address after validating it
+fn transfer_address_network(address: &str) -> Result<Network, AddressValidationError> {
+    let network = [Network::Mainnet, Network::Testnet, Network::Devnet]
+        .iter()
+        .cloned()
+        .find(|&network| address.starts_with(&format!("{}1", bech32_hrp_of_network(network))))
+        .ok_or(AddressValidationError::InvalidPrefix)?;
+
+    validate_transfer_address_str(address, network)?;
+    ExtendedAddr::from_cro(address, network)
+        .map_err(|_| AddressValidationError::InvalidEncoding)?;
+
+    Ok(network)
+}
+
+/// Parse amount in basic unit, which should be a positive integer without
+/// leading zeros
+fn parse_payment_amount(amount: &str) -> Result<Coin, PaymentUriError> {
+    if amount.is_empty()
+        || !amount.bytes().all(|byte| byte.is_ascii_digit())
+        || amount.starts_with('0')
+    {
+        return Err(PaymentUriError::InvalidAmount);
+    }
+
+    Coin::from_str(amount).map_err(|_| PaymentUriError::InvalidAmount)
+}
+
+fn is_unreserved_uri_byte(byte: u8) -> bool {
+    byte.is_ascii_alphanumeric() || byte == b'-' || byte == b'.' || byte == b'_' || byte == b'~'
+}
+
+fn percent_encode(value: &str) -> String {
+    let mut encoded = String::with_capacity(value.len());
+    for &byte in value.as_bytes() {
+        if is_unreserved_uri_byte(byte) {
+            encoded.push(byte as char);
+        } else {
+            encoded.push_str(&format!("%{:02X}", byte));
+        }
+    }
+
+    encoded
+}
+
+/// Decode percent-encoded UTF-8 value. Every byte other than the unreserved
+/// ones has to be percent-encoded
+fn decode_query_value(value: &str) -> Result<String, PaymentUriError> {
+    if !value
+        .bytes()
+        .all(|byte| byte == b'%' || is_unreserved_uri_byte(byte))
+    {
+        return Err(PaymentUriError::InvalidEncoding);
+    }
+    let decoded = percent_decode(value).ok_or(PaymentUriError::InvalidEncoding)?;
+
+    String::from_utf8(decoded).map_err(|_| PaymentUriError::InvalidEncoding)
+}
+
+/// Encode payment request as payment URI. The address is validated against
+/// the network of its prefix
+pub fn encode_payment_uri_str(
+    address: &str,
+    amount: Option<Coin>,
+    memo: Option<&str>,
+) -> Result<String, PaymentUriError> {
+    transfer_address_network(address).map_err(PaymentUriError::InvalidAddress)?;
+
+    let mut params = Vec::new();
+    if let Some(amount) = amount {
+        if u64::from(amount) == 0 {
+            return Err(PaymentUriError::InvalidAmount);
+        }
+        params.push(format!("amount={}", u64::from(amount)));
+    }
+    if let Some(memo) = memo {
+        if memo.len() > MAX_PAYMENT_MEMO_LENGTH {
+            return Err(PaymentUriError::InvalidMemo);
+        }
+        params.push(format!("memo={}", percent_encode(memo)));
+    }
+
+    let mut uri = format!("{}:{}", PAYMENT_URI_SCHEME, address);
+    if !params.is_empty() {
+        uri.push('?');
+        uri.push_str(&params.join("&"));
+    }
+
+    Ok(uri)
+}
+
+/// Parse payment URI strictly: the scheme should be lowercase, the address
+/// should be a valid transfer address and only amount and memo parameters
+/// are accepted, each at most once
+pub fn parse_payment_uri_str(uri: &str) -> Result<PaymentRequest, PaymentUriError> {
+    let prefix = format!("{}:", PAYMENT_URI_SCHEME);
+    if !uri.starts_with(&prefix) {
+        return Err(PaymentUriError::InvalidScheme);
+    }
+
+    let rest = &uri[prefix.len()..];
+    let (address, query) = match rest.find('?') {
+        Some(index) => (&rest[..index], Some(&rest[index + 1..])),
+        None => (rest, None),
+    };
+    let network = transfer_address_network(address).map_err(PaymentUriError::InvalidAddress)?;
+
+    let mut amount = None;
+    let mut memo = None;
+    if let Some(query) = query {
+        for param in query.split('&') {
+            let mut key_value = param.splitn(2, '=');
+            let key = key_value.next().unwrap_or_default();
+            let value = key_value.next().ok_or(PaymentUriError::InvalidEncoding)?;
+            match key {
+                "amount" => {
+                    if amount.is_some() {
+                        return Err(PaymentUriError::DuplicateParameter);
+                    }
+                    amount = Some(parse_payment_amount(value)?);
+                }
+                "memo" => {
+                    if memo.is_some() {
+                        return Err(PaymentUriError::DuplicateParameter);
+                    }
+                    let value = decode_query_value(value)?;
+                    if value.len() > MAX_PAYMENT_MEMO_LENGTH {
+                        return Err(PaymentUriError::InvalidMemo);
+                    }
+                    memo = Some(value);
+                }
+                _ => return Err(PaymentUriError::UnknownParameter),
+            }
+        }
+    }
+
+    Ok(PaymentRequest {
+        address: address.to_owned(),
+        network,
+        amount,
+        memo,
+    })
+}
+
+fn throw_payment_uri_error<'a, T>(
+    ctx: &mut FunctionContext<'a>,
+    err: PaymentUriError,
+) -> NeonResult<T> {
+    throw_coded_error(
+        ctx,
+        err.error_code(),
+        format!("{} ({})", err.message(), err.code()),
+    )
+}
+
+/// encode payment request as payment URI
+/// @arguments
+/// - address: bech32 transfer address to pay to
+/// - amount: (optional) amount string in basic unit
+/// - memo: (optional) memo of the payment, at most 256 bytes
+/// @return payment URI string
+pub fn encode_payment_uri(mut ctx: FunctionContext) -> JsResult<JsString> {
+    let address = ctx.argument::<JsString>(0)?.value();
+    let amount = optional_string_argument(&mut ctx, 1)?;
+    let memo = optional_string_argument(&mut ctx, 2)?;
+
+    let amount = match amount {
+        Some(amount) => match parse_payment_amount(&amount) {
+            Ok(amount) => Some(amount),
+            Err(err) => return throw_payment_uri_error(&mut ctx, err),
+        },
+        None => None,
+    };
+
+    match encode_payment_uri_str(&address, amount, memo.as_deref()) {
+        Ok(uri) => Ok(ctx.string(uri)),
+        Err(err) => throw_payment_uri_error(&mut ctx, err),
+    }
+}
+
+/// parse payment URI strictly, throws when the URI is malformed, the address
+/// is invalid or there is unknown or duplicate parameter
+/// @arguments
+/// - uri: payment URI string
+/// @return {
+///     address: string,
+///     networkType: string,
+///     amount?: string,
+///     memo?: string
+/// }
+pub fn parse_payment_uri(mut ctx: FunctionContext) -> JsResult<JsObject> {
+    let uri = ctx.argument::<JsString>(0)?.value();
+
+    let payment_request = match parse_payment_uri_str(&uri) {
+        Ok(payment_request) => payment_request,
+        Err(err) => return throw_payment_uri_error(&mut ctx, err),
+    };
+
+    let js_object = JsObject::new(&mut ctx);
+    let address = ctx.string(&payment_request.address);
+    js_object.set(&mut ctx, "address", address)?;
+    let network_type = ctx.string(match payment_request.network {
+        Network::Mainnet => "Mainnet",
+        Network::Testnet => "Testnet",
+        Network::Devnet => "Devnet",
+    });
+    js_object.set(&mut ctx, "networkType", network_type)?;
+    if let Some(amount) = payment_request.amount {
+        let amount = ctx.string(u64::from(amount).to_string());
+        js_object.set(&mut ctx, "amount", amount)?;
+    }
+    if let Some(memo) = payment_request.memo {
+        let memo = ctx.string(memo);
+        js_object.set(&mut ctx, "memo", memo)?;
+    }
+
+    Ok(js_object)
+}
+
 pub fn register_address_module(ctx: &mut ModuleContext) -> NeonResult<()> {
     let js_object = JsObject::new(ctx);
 
@@ -62,6 +787,14 @@ pub fn register_address_module(ctx: &mut ModuleContext) -> NeonResult<()> {
         get_transfer_address_from_public_key_fn,
     )?;
 
+    let create_multi_sig_transfer_address_fn =
+        JsFunction::new(ctx, create_multi_sig_transfer_address)?;
+    js_object.set(
+        ctx,
+        "createMultiSigTransferAddress",
+        create_multi_sig_transfer_address_fn,
+    )?;
+
     let get_staking_address_from_public_key_fn =
         JsFunction::new(ctx, get_staking_address_from_public_key)?;
     js_object.set(
@@ -76,5 +809,27 @@ pub fn register_address_module(ctx: &mut ModuleContext) -> NeonResult<()> {
     let is_staking_address_valid_fn = JsFunction::new(ctx, is_staking_address_valid)?;
     js_object.set(ctx, "isStakingAddressValid", is_staking_address_valid_fn)?;
 
+    let validate_transfer_address_fn = JsFunction::new(ctx, validate_transfer_address)?;
+    js_object.set(ctx, "validateTransferAddress", validate_transfer_address_fn)?;
+
+    let validate_staking_address_fn = JsFunction::new(ctx, validate_staking_address)?;
+    js_object.set(ctx, "validateStakingAddress", validate_staking_address_fn)?;
+
+    let to_checksum_staking_address_fn = JsFunction::new(ctx, to_checksum_staking_address)?;
+    js_object.set(
+        ctx,
+        "toChecksumStakingAddress",
+        to_checksum_staking_address_fn,
+    )?;
+
+    let convert_network_fn = JsFunction::new(ctx, convert_network)?;
+    js_object.set(ctx, "convertNetwork", convert_network_fn)?;
+
+    let encode_payment_uri_fn = JsFunction::new(ctx, encode_payment_uri)?;
+    js_object.set(ctx, "encodePaymentUri", encode_payment_uri_fn)?;
+
+    let parse_payment_uri_fn = JsFunction::new(ctx, parse_payment_uri)?;
+    js_object.set(ctx, "parsePaymentUri", parse_payment_uri_fn)?;
+
     ctx.export_value("address", js_object)
 }
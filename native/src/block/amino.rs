@@ -0,0 +1,134 @@
+//! The subset of the Tendermint amino binary encoding and simple Merkle tree
+//! needed to hash block headers and validator sets and to build the sign
+//! bytes of votes. As amino, fields of default value are omitted
+use chain_core::common::{H256, HASH_SIZE_256};
+use sha2::{Digest, Sha256};
+
+const WIRE_TYPE_VARINT: u8 = 0;
+const WIRE_TYPE_FIXED64: u8 = 1;
+const WIRE_TYPE_LENGTH_DELIMITED: u8 = 2;
+
+const LEAF_PREFIX: u8 = 0;
+const INNER_PREFIX: u8 = 1;
+
+pub fn encode_uvarint(mut value: u64, buffer: &mut Vec<u8>) {
+    while value >= 0x80 {
+        buffer.push((value as u8) | 0x80);
+        value >>= 7;
+    }
+    buffer.push(value as u8);
+}
+
+#[inline]
+fn encode_field_key(field: u8, wire_type: u8, buffer: &mut Vec<u8>) {
+    buffer.push((field << 3) | wire_type);
+}
+
+/// Bare encoding of an integer
+pub fn encode_varint(value: u64) -> Vec<u8> {
+    let mut buffer = Vec::new();
+    encode_uvarint(value, &mut buffer);
+    buffer
+}
+
+/// Bare encoding of bytes, empty when there is no byte
+pub fn encode_bytes(bytes: &[u8]) -> Vec<u8> {
+    if bytes.is_empty() {
+        return Vec::new();
+    }
+
+    let mut buffer = Vec::with_capacity(bytes.len() + 2);
+    encode_uvarint(bytes.len() as u64, &mut buffer);
+    buffer.extend_from_slice(bytes);
+    buffer
+}
+
+/// Bare encoding with the length prefix, as the sign bytes of a vote
+pub fn length_prefixed(bytes: Vec<u8>) -> Vec<u8> {
+    let mut buffer = Vec::with_capacity(bytes.len() + 2);
+    encode_uvarint(bytes.len() as u64, &mut buffer);
+    buffer.extend(bytes);
+    buffer
+}
+
+/// Builder of an amino struct
+#[derive(Debug, Default)]
+pub struct StructEncoder {
+    buffer: Vec<u8>,
+}
+
+impl StructEncoder {
+    pub fn uvarint(mut self, field: u8, value: u64) -> Self {
+        if value != 0 {
+            encode_field_key(field, WIRE_TYPE_VARINT, &mut self.buffer);
+            encode_uvarint(value, &mut self.buffer);
+        }
+        self
+    }
+
+    pub fn fixed64(mut self, field: u8, value: i64) -> Self {
+        if value != 0 {
+            encode_field_key(field, WIRE_TYPE_FIXED64, &mut self.buffer);
+            self.buffer.extend_from_slice(&value.to_le_bytes());
+        }
+        self
+    }
+
+    /// Bytes, string or embedded struct field
+    pub fn bytes(mut self, field: u8, value: &[u8]) -> Self {
+        if !value.is_empty() {
+            encode_field_key(field, WIRE_TYPE_LENGTH_DELIMITED, &mut self.buffer);
+            encode_uvarint(value.len() as u64, &mut self.buffer);
+            self.buffer.extend_from_slice(value);
+        }
+        self
+    }
+
+    pub fn finish(self) -> Vec<u8> {
+        self.buffer
+    }
+}
+
+/// Bare encoding of a timestamp
+pub fn encode_time(seconds: i64, nanos: u32) -> Vec<u8> {
+    StructEncoder::default()
+        .uvarint(1, seconds as u64)
+        .uvarint(2, u64::from(nanos))
+        .finish()
+}
+
+/// Root of the Tendermint simple Merkle tree of the items
+pub fn simple_hash_from_byte_vectors(items: &[Vec<u8>]) -> H256 {
+    match items.len() {
+        // Tendermint has no hash for an empty tree, callers never hash one
+        0 => [0; HASH_SIZE_256],
+        1 => sha256(&[&[LEAF_PREFIX], items[0].as_slice()]),
+        len => {
+            let split_point = split_point(len);
+            let left = simple_hash_from_byte_vectors(&items[..split_point]);
+            let right = simple_hash_from_byte_vectors(&items[split_point..]);
+            sha256(&[&[INNER_PREFIX], &left, &right])
+        }
+    }
+}
+
+/// Largest power of 2 less than the length
+#[inline]
+fn split_point(len: usize) -> usize {
+    let mut split_point = 1;
+    while split_point * 2 < len {
+        split_point *= 2;
+    }
+    split_point
+}
+
+fn sha256(parts: &[&[u8]]) -> H256 {
+    let mut hasher = Sha256::new();
+    for part in parts {
+        hasher.input(part);
+    }
+
+    let mut hash = [0; HASH_SIZE_256];
+    hash.copy_from_slice(&hasher.result());
+    hash
+}
@@ -0,0 +1,331 @@
+//! Verification of the commit signatures of a block against a validator set
+use std::collections::HashSet;
+
+use client_common::{Error, ErrorKind, Result, ResultExt};
+use ed25519_dalek::{PublicKey, Signature};
+use serde::Serialize;
+use serde_json::Value;
+
+use super::amino::{length_prefixed, simple_hash_from_byte_vectors, StructEncoder};
+use super::header::{hex_field, string_field, u64_field, BlockHeader, BlockId, Timestamp};
+use crate::consensus_key::{
+    validator_address_bytes, AMINO_PUB_KEY_ED25519_PREFIX, PUB_KEY_ED25519_TYPE,
+};
+
+/// Vote type of precommit
+const PRECOMMIT_TYPE: u64 = 2;
+/// Block id flag of the signature for the committed block, absent and nil
+/// votes do not count
+const BLOCK_ID_FLAG_COMMIT: u64 = 2;
+const BLOCK_ID_FLAG_ABSENT: u64 = 1;
+
+#[derive(Debug, Clone)]
+pub struct Validator {
+    pub address: Vec<u8>,
+    pub pub_key: PublicKey,
+    pub voting_power: u64,
+}
+
+impl Validator {
+    fn parse(validator: &Value, i: usize) -> Result<Validator> {
+        let name = format!("validator {}", i);
+        let pub_key = validator.get("pub_key").unwrap_or(&Value::Null);
+        if string_field(pub_key, "type", &name)? != PUB_KEY_ED25519_TYPE {
+            return Err(Error::new(
+                ErrorKind::DeserializationError,
+                format!("Public key of {} should be {}", name, PUB_KEY_ED25519_TYPE),
+            ));
+        }
+        let pub_key = base64::decode(string_field(pub_key, "value", &name)?).chain(|| {
+            (
+                ErrorKind::DeserializationError,
+                format!("Public key of {} should be base64 string", name),
+            )
+        })?;
+        let pub_key = PublicKey::from_bytes(&pub_key).chain(|| {
+            (
+                ErrorKind::DeserializationError,
+                format!("Invalid ed25519 public key of {}", name),
+            )
+        })?;
+
+        let address = validator_address_bytes(pub_key.as_bytes());
+        let claimed_address = hex_field(validator, "address", &name)?;
+        if !claimed_address.is_empty() && claimed_address != address {
+            return Err(Error::new(
+                ErrorKind::DeserializationError,
+                format!("Address of {} does not match its public key", name),
+            ));
+        }
+
+        Ok(Validator {
+            address,
+            pub_key,
+            voting_power: u64_field(validator, "voting_power", &name)?,
+        })
+    }
+
+    /// Bare encoding of the public key and voting power, the leaf of the
+    /// validator set hash
+    fn encode(&self) -> Vec<u8> {
+        let mut pub_key = AMINO_PUB_KEY_ED25519_PREFIX.to_vec();
+        pub_key.extend_from_slice(self.pub_key.as_bytes());
+
+        StructEncoder::default()
+            .bytes(1, &pub_key)
+            .uvarint(2, self.voting_power)
+            .finish()
+    }
+}
+
+/// Returns the validators of an RPC `validators` result, or of an array of
+/// validators in the Tendermint JSON format
+pub fn parse_validators(validators: &Value) -> Result<Vec<Validator>> {
+    let validators = match validators {
+        Value::Array(validators) => validators,
+        _ => validators
+            .pointer("/result/validators")
+            .or_else(|| validators.get("validators"))
+            .and_then(Value::as_array)
+            .chain(|| {
+                (
+                    ErrorKind::DeserializationError,
+                    "Validators should be an array or a validators result",
+                )
+            })?,
+    };
+    if validators.is_empty() {
+        return Err(Error::new(
+            ErrorKind::DeserializationError,
+            "Validator set should not be empty",
+        ));
+    }
+
+    validators
+        .iter()
+        .enumerate()
+        .map(|(i, validator)| Validator::parse(validator, i))
+        .collect()
+}
+
+/// Hash of the validator set in order, as the validators hash of the header
+pub fn validators_hash(validators: &[Validator]) -> Vec<u8> {
+    let leaves: Vec<Vec<u8>> = validators.iter().map(Validator::encode).collect();
+
+    simple_hash_from_byte_vectors(&leaves).to_vec()
+}
+
+#[derive(Debug)]
+struct CommitSig {
+    block_id_flag: u64,
+    validator_address: Vec<u8>,
+    timestamp: Option<Timestamp>,
+    signature: Vec<u8>,
+}
+
+#[derive(Debug)]
+pub struct Commit {
+    pub height: u64,
+    pub round: u64,
+    pub block_id: BlockId,
+    signatures: Vec<CommitSig>,
+}
+
+impl Commit {
+    pub fn parse(commit: &Value) -> Result<Commit> {
+        let name = "commit";
+        let block_id = commit.get("block_id").chain(|| {
+            (
+                ErrorKind::DeserializationError,
+                "block_id of commit is missing",
+            )
+        })?;
+        let signatures = commit
+            .get("signatures")
+            .and_then(Value::as_array)
+            .chain(|| {
+                (
+                    ErrorKind::DeserializationError,
+                    "signatures of commit should be an array",
+                )
+            })?;
+
+        Ok(Commit {
+            height: u64_field(commit, "height", name)?,
+            round: match commit.get("round") {
+                None | Some(Value::Null) => 0,
+                Some(_) => u64_field(commit, "round", name)?,
+            },
+            block_id: BlockId::parse(block_id, "block_id of commit")?,
+            signatures: signatures
+                .iter()
+                .enumerate()
+                .map(|(i, signature)| CommitSig::parse(signature, i))
+                .collect::<Result<Vec<CommitSig>>>()?,
+        })
+    }
+}
+
+impl CommitSig {
+    fn parse(signature: &Value, i: usize) -> Result<CommitSig> {
+        let name = format!("commit signature {}", i);
+        let block_id_flag = u64_field(signature, "block_id_flag", &name)?;
+        if block_id_flag == BLOCK_ID_FLAG_ABSENT {
+            return Ok(CommitSig {
+                block_id_flag,
+                validator_address: Vec::new(),
+                timestamp: None,
+                signature: Vec::new(),
+            });
+        }
+
+        let raw_signature = string_field(signature, "signature", &name)?;
+        Ok(CommitSig {
+            block_id_flag,
+            validator_address: hex_field(signature, "validator_address", &name)?,
+            timestamp: Some(Timestamp::parse(string_field(
+                signature,
+                "timestamp",
+                &name,
+            )?)?),
+            signature: base64::decode(raw_signature).chain(|| {
+                (
+                    ErrorKind::DeserializationError,
+                    format!("Signature of {} should be base64 string", name),
+                )
+            })?,
+        })
+    }
+}
+
+/// Sign bytes of the precommit vote of the signature
+fn vote_sign_bytes(chain_id: &str, commit: &Commit, timestamp: &Timestamp) -> Vec<u8> {
+    let canonical_vote = StructEncoder::default()
+        .uvarint(1, PRECOMMIT_TYPE)
+        .fixed64(2, commit.height as i64)
+        .fixed64(3, commit.round as i64)
+        .bytes(4, &commit.block_id.encode_canonical())
+        .bytes(5, &timestamp.encode())
+        .bytes(6, chain_id.as_bytes())
+        .finish();
+
+    length_prefixed(canonical_vote)
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CommitVerification {
+    pub is_valid: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+    pub height: u64,
+    pub hash: String,
+    pub signed_voting_power: String,
+    pub total_voting_power: String,
+}
+
+/// Verify the commit is for the header and signed by more than 2/3 of the
+/// voting power of the validator set, which the header should commit to. A
+/// validator set which total voting power overflows is an error
+pub fn verify_commit(
+    header: &BlockHeader,
+    commit: &Commit,
+    validators: &[Validator],
+) -> Result<CommitVerification> {
+    let hash = header.hash();
+    let total_voting_power = validators
+        .iter()
+        .try_fold(0u64, |total, validator| {
+            total.checked_add(validator.voting_power)
+        })
+        .ok_or_else(|| {
+            Error::new(
+                ErrorKind::VerifyError,
+                "Total voting power of the validator set overflows",
+            )
+        })?;
+    let mut verification = CommitVerification {
+        is_valid: false,
+        reason: None,
+        height: header.height,
+        hash: hex::encode_upper(hash),
+        signed_voting_power: "0".to_owned(),
+        total_voting_power: total_voting_power.to_string(),
+    };
+
+    let failure = if commit.height != header.height {
+        Some(format!(
+            "Commit height {} does not match header height {}",
+            commit.height, header.height
+        ))
+    } else if commit.block_id.hash != hash {
+        Some("Commit is not for the block of the header".to_owned())
+    } else if validators_hash(validators) != header.validators_hash {
+        Some("Validator set does not match the validators hash of the header".to_owned())
+    } else {
+        None
+    };
+    if failure.is_some() {
+        verification.reason = failure;
+        return Ok(verification);
+    }
+
+    let mut signed_voting_power = 0u64;
+    let mut signers = HashSet::new();
+    for (i, signature) in commit.signatures.iter().enumerate() {
+        if signature.block_id_flag != BLOCK_ID_FLAG_COMMIT {
+            continue;
+        }
+        // A validator signing twice must not count twice
+        if !signers.insert(signature.validator_address.as_slice()) {
+            verification.reason = Some(format!(
+                "Signer of commit signature {} has already signed",
+                i
+            ));
+            return Ok(verification);
+        }
+        let validator = match validators
+            .iter()
+            .find(|validator| validator.address == signature.validator_address)
+        {
+            Some(validator) => validator,
+            None => {
+                verification.reason = Some(format!(
+                    "Signer of commit signature {} is not in the validator set",
+                    i
+                ));
+                return Ok(verification);
+            }
+        };
+
+        let sign_bytes = match &signature.timestamp {
+            Some(timestamp) => vote_sign_bytes(&header.chain_id, commit, timestamp),
+            None => Vec::new(),
+        };
+        let is_signature_valid = Signature::from_bytes(&signature.signature)
+            .map(|ed25519_signature| {
+                validator
+                    .pub_key
+                    .verify(&sign_bytes, &ed25519_signature)
+                    .is_ok()
+            })
+            .unwrap_or(false);
+        if !is_signature_valid {
+            verification.reason = Some(format!("Commit signature {} is invalid", i));
+            return Ok(verification);
+        }
+
+        // Bounded by the total voting power, which does not overflow
+        signed_voting_power += validator.voting_power;
+    }
+
+    verification.signed_voting_power = signed_voting_power.to_string();
+    if u128::from(signed_voting_power) * 3 > u128::from(total_voting_power) * 2 {
+        verification.is_valid = true;
+    } else {
+        verification.reason =
+            Some("Commit is signed by less than 2/3 of the voting power".to_owned());
+    }
+
+    Ok(verification)
+}
@@ -0,0 +1,36 @@
+//! Hashes with which a header commits to the transactions of its block and
+//! to the results of the transactions of the previous block
+use sha2::{Digest, Sha256};
+
+use super::amino::{simple_hash_from_byte_vectors, StructEncoder};
+
+/// Data hash of the header of the block of the transactions, empty for a
+/// block without transaction
+pub fn data_hash(txs: &[Vec<u8>]) -> Vec<u8> {
+    if txs.is_empty() {
+        return Vec::new();
+    }
+
+    let leaves: Vec<Vec<u8>> = txs.iter().map(|tx| Sha256::digest(tx).to_vec()).collect();
+    simple_hash_from_byte_vectors(&leaves).to_vec()
+}
+
+/// Last results hash of the header of the next block of the DeliverTx
+/// results, which are the code and data of each transaction. Empty for a
+/// block without transaction
+pub fn results_hash(results: &[(u32, Vec<u8>)]) -> Vec<u8> {
+    if results.is_empty() {
+        return Vec::new();
+    }
+
+    let leaves: Vec<Vec<u8>> = results
+        .iter()
+        .map(|(code, data)| {
+            StructEncoder::default()
+                .uvarint(1, u64::from(*code))
+                .bytes(2, data)
+                .finish()
+        })
+        .collect();
+    simple_hash_from_byte_vectors(&leaves).to_vec()
+}
@@ -0,0 +1,279 @@
+//! Tendermint block header as returned by the RPC, e.g. `block` and `commit`
+use chain_core::common::H256;
+use chrono::DateTime;
+use client_common::{Error, ErrorKind, Result, ResultExt};
+use serde::Serialize;
+use serde_json::Value;
+
+use super::amino::{
+    encode_bytes, encode_time, encode_varint, simple_hash_from_byte_vectors, StructEncoder,
+};
+
+#[derive(Debug, Clone)]
+pub struct Timestamp {
+    pub seconds: i64,
+    pub nanos: u32,
+    /// RFC3339 time as in the RPC response
+    pub raw: String,
+}
+
+impl Timestamp {
+    pub fn parse(raw: &str) -> Result<Timestamp> {
+        let time = DateTime::parse_from_rfc3339(raw).chain(|| {
+            (
+                ErrorKind::DeserializationError,
+                format!("Invalid RFC3339 time {}", raw),
+            )
+        })?;
+
+        Ok(Timestamp {
+            seconds: time.timestamp(),
+            nanos: time.timestamp_subsec_nanos(),
+            raw: raw.to_owned(),
+        })
+    }
+
+    pub fn encode(&self) -> Vec<u8> {
+        encode_time(self.seconds, self.nanos)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BlockId {
+    pub hash: Vec<u8>,
+    pub parts_total: u64,
+    pub parts_hash: Vec<u8>,
+}
+
+impl BlockId {
+    pub fn parse(block_id: &Value, name: &str) -> Result<BlockId> {
+        Ok(BlockId {
+            hash: hex_field(block_id, "hash", name)?,
+            parts_total: block_id
+                .get("parts")
+                .map(|parts| u64_field(parts, "total", name))
+                .transpose()?
+                .unwrap_or(0),
+            parts_hash: block_id
+                .get("parts")
+                .map(|parts| hex_field(parts, "hash", name))
+                .transpose()?
+                .unwrap_or_default(),
+        })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.hash.is_empty() && self.parts_total == 0 && self.parts_hash.is_empty()
+    }
+
+    /// Bare encoding of BlockID, as in the header
+    pub fn encode(&self) -> Vec<u8> {
+        let parts_header = StructEncoder::default()
+            .uvarint(1, self.parts_total)
+            .bytes(2, &self.parts_hash)
+            .finish();
+
+        StructEncoder::default()
+            .bytes(1, &self.hash)
+            .bytes(2, &parts_header)
+            .finish()
+    }
+
+    /// Bare encoding of CanonicalBlockID, as in the sign bytes of a vote
+    pub fn encode_canonical(&self) -> Vec<u8> {
+        let parts_header = StructEncoder::default()
+            .bytes(1, &self.parts_hash)
+            .uvarint(2, self.parts_total)
+            .finish();
+
+        StructEncoder::default()
+            .bytes(1, &self.hash)
+            .bytes(2, &parts_header)
+            .finish()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct BlockHeader {
+    pub version_block: u64,
+    pub version_app: u64,
+    pub chain_id: String,
+    pub height: u64,
+    pub time: Timestamp,
+    pub last_block_id: Option<BlockId>,
+    pub last_commit_hash: Vec<u8>,
+    pub data_hash: Vec<u8>,
+    pub validators_hash: Vec<u8>,
+    pub next_validators_hash: Vec<u8>,
+    pub consensus_hash: Vec<u8>,
+    pub app_hash: Vec<u8>,
+    pub last_results_hash: Vec<u8>,
+    pub evidence_hash: Vec<u8>,
+    pub proposer_address: Vec<u8>,
+}
+
+impl BlockHeader {
+    pub fn parse(header: &Value) -> Result<BlockHeader> {
+        let name = "block header";
+        let version = header.get("version").unwrap_or(&Value::Null);
+        let last_block_id = match header.get("last_block_id") {
+            Some(last_block_id) if !last_block_id.is_null() => {
+                let last_block_id = BlockId::parse(last_block_id, "last_block_id")?;
+                if last_block_id.is_empty() {
+                    None
+                } else {
+                    Some(last_block_id)
+                }
+            }
+            _ => None,
+        };
+
+        Ok(BlockHeader {
+            version_block: optional_u64_field(version, "block", name)?,
+            version_app: optional_u64_field(version, "app", name)?,
+            chain_id: string_field(header, "chain_id", name)?.to_owned(),
+            height: u64_field(header, "height", name)?,
+            time: Timestamp::parse(string_field(header, "time", name)?)?,
+            last_block_id,
+            last_commit_hash: hex_field(header, "last_commit_hash", name)?,
+            data_hash: hex_field(header, "data_hash", name)?,
+            validators_hash: hex_field(header, "validators_hash", name)?,
+            next_validators_hash: hex_field(header, "next_validators_hash", name)?,
+            consensus_hash: hex_field(header, "consensus_hash", name)?,
+            app_hash: hex_field(header, "app_hash", name)?,
+            last_results_hash: hex_field(header, "last_results_hash", name)?,
+            evidence_hash: hex_field(header, "evidence_hash", name)?,
+            proposer_address: hex_field(header, "proposer_address", name)?,
+        })
+    }
+
+    /// Hash of the header, i.e. the block hash
+    pub fn hash(&self) -> H256 {
+        let version = StructEncoder::default()
+            .uvarint(1, self.version_block)
+            .uvarint(2, self.version_app)
+            .finish();
+        let last_block_id = self
+            .last_block_id
+            .as_ref()
+            .map(BlockId::encode)
+            .unwrap_or_default();
+
+        simple_hash_from_byte_vectors(&[
+            version,
+            encode_bytes(self.chain_id.as_bytes()),
+            encode_varint(self.height),
+            self.time.encode(),
+            last_block_id,
+            encode_bytes(&self.last_commit_hash),
+            encode_bytes(&self.data_hash),
+            encode_bytes(&self.validators_hash),
+            encode_bytes(&self.next_validators_hash),
+            encode_bytes(&self.consensus_hash),
+            encode_bytes(&self.app_hash),
+            encode_bytes(&self.last_results_hash),
+            encode_bytes(&self.evidence_hash),
+            encode_bytes(&self.proposer_address),
+        ])
+    }
+}
+
+/// Decoded header returned to JS, hashes and addresses are uppercase hex
+/// as in the RPC
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HeaderDetails {
+    pub hash: String,
+    pub chain_id: String,
+    pub height: u64,
+    pub time: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_block_hash: Option<String>,
+    pub app_hash: String,
+    pub proposer_address: String,
+    pub validators_hash: String,
+    pub next_validators_hash: String,
+}
+
+impl From<&BlockHeader> for HeaderDetails {
+    fn from(header: &BlockHeader) -> HeaderDetails {
+        HeaderDetails {
+            hash: hex::encode_upper(header.hash()),
+            chain_id: header.chain_id.clone(),
+            height: header.height,
+            time: header.time.raw.clone(),
+            last_block_hash: header
+                .last_block_id
+                .as_ref()
+                .map(|last_block_id| hex::encode_upper(&last_block_id.hash)),
+            app_hash: hex::encode_upper(&header.app_hash),
+            proposer_address: hex::encode_upper(&header.proposer_address),
+            validators_hash: hex::encode_upper(&header.validators_hash),
+            next_validators_hash: hex::encode_upper(&header.next_validators_hash),
+        }
+    }
+}
+
+/// Returns the header of an RPC result: the header itself, a signed header,
+/// a block, or a `block` or `commit` result, with or without the JSON-RPC
+/// envelope
+pub fn find_header(value: &Value) -> Option<&Value> {
+    if value.get("chain_id").is_some() {
+        return Some(value);
+    }
+
+    ["result", "signed_header", "block"]
+        .iter()
+        .filter_map(|key| value.get(key))
+        .chain(value.get("header"))
+        .find_map(find_header)
+}
+
+pub fn string_field<'a>(value: &'a Value, key: &str, name: &str) -> Result<&'a str> {
+    value.get(key).and_then(Value::as_str).chain(|| {
+        (
+            ErrorKind::DeserializationError,
+            format!("{} of {} should be a string", key, name),
+        )
+    })
+}
+
+/// Integers are strings in Tendermint JSON, numbers are accepted as well
+pub fn u64_field(value: &Value, key: &str, name: &str) -> Result<u64> {
+    let parsed = match value.get(key) {
+        Some(Value::String(integer)) => integer.parse::<u64>().ok(),
+        Some(Value::Number(integer)) => integer.as_u64(),
+        _ => None,
+    };
+
+    parsed.chain(|| {
+        (
+            ErrorKind::DeserializationError,
+            format!("{} of {} should be a non-negative integer", key, name),
+        )
+    })
+}
+
+fn optional_u64_field(value: &Value, key: &str, name: &str) -> Result<u64> {
+    match value.get(key) {
+        None | Some(Value::Null) => Ok(0),
+        Some(_) => u64_field(value, key, name),
+    }
+}
+
+/// Hex field, an empty or missing field is empty bytes
+pub fn hex_field(value: &Value, key: &str, name: &str) -> Result<Vec<u8>> {
+    match value.get(key) {
+        None | Some(Value::Null) => Ok(Vec::new()),
+        Some(Value::String(hex_str)) => hex::decode(hex_str).map_err(|err| {
+            Error::new(
+                ErrorKind::DeserializationError,
+                format!("{} of {} should be hex string: {}", key, name, err),
+            )
+        }),
+        Some(_) => Err(Error::new(
+            ErrorKind::DeserializationError,
+            format!("{} of {} should be hex string", key, name),
+        )),
+    }
+}
@@ -0,0 +1,118 @@
+//! Tendermint block headers and commits as returned by the RPC, for
+//! integrations following the chain without trusting the RPC node
+mod amino;
+mod commit;
+mod data;
+mod header;
+
+use client_common::{ErrorKind, Result, ResultExt};
+use neon::prelude::*;
+use serde_json::Value;
+
+use crate::common::serialize_to_js;
+use crate::error::{ClientErrorNeonExt, ErrorCode};
+use crate::function_types::*;
+
+pub use commit::{parse_validators, validators_hash, verify_commit, Commit};
+pub use data::{data_hash, results_hash};
+pub use header::BlockHeader;
+use header::{find_header, HeaderDetails};
+
+/// decode Tendermint block header
+/// @arguments
+/// - header: JSON string of the header, or of the result of RPC block or
+///     commit which contains the header
+/// @return { hash, chainId, height, time, lastBlockHash?, appHash,
+///     proposerAddress, validatorsHash, nextValidatorsHash }, hashes and
+///     addresses are uppercase hex
+pub fn decode_header(mut ctx: FunctionContext) -> JsResult<JsValue> {
+    let header = json_argument(&mut ctx, 0, "header")?;
+
+    let header = parse_header(&header).chain_neon_with_code(
+        &mut ctx,
+        ErrorCode::DeserializationError,
+        "Unable to decode block header",
+    )?;
+
+    serialize_to_js(&mut ctx, &HeaderDetails::from(&header))
+}
+
+/// verify the commit signatures of a block against a known validator set
+/// @arguments
+/// - signed_header: JSON string of the signed header { header, commit }, or
+///     of the result of RPC commit
+/// - validators: JSON string of the validators, or of the result of RPC
+///     validators at the height of the header
+/// @return { isValid, reason?, height, hash, signedVotingPower,
+///     totalVotingPower }, the commit is valid when it is for the header,
+///     the validator set matches the validators hash of the header and more
+///     than 2/3 of the voting power signed
+pub fn verify_commit_signatures(mut ctx: FunctionContext) -> JsResult<JsValue> {
+    let signed_header = json_argument(&mut ctx, 0, "signed header")?;
+    let validators = json_argument(&mut ctx, 1, "validators")?;
+
+    let (header, commit) = parse_signed_header(&signed_header).chain_neon_with_code(
+        &mut ctx,
+        ErrorCode::DeserializationError,
+        "Unable to decode signed header",
+    )?;
+    let validators = parse_validators(&validators).chain_neon_with_code(
+        &mut ctx,
+        ErrorCode::DeserializationError,
+        "Unable to decode validators",
+    )?;
+
+    let verification = verify_commit(&header, &commit, &validators)
+        .chain_neon(&mut ctx, "Unable to verify commit")?;
+
+    serialize_to_js(&mut ctx, &verification)
+}
+
+pub fn parse_header(value: &Value) -> Result<BlockHeader> {
+    let header = find_header(value).chain(|| {
+        (
+            ErrorKind::DeserializationError,
+            "Unable to find block header in JSON",
+        )
+    })?;
+
+    BlockHeader::parse(header)
+}
+
+/// Returns the header and commit of an RPC commit result or signed header
+pub fn parse_signed_header(value: &Value) -> Result<(BlockHeader, Commit)> {
+    let signed_header = value
+        .pointer("/result/signed_header")
+        .or_else(|| value.get("signed_header"))
+        .unwrap_or(value);
+    let commit = signed_header.get("commit").chain(|| {
+        (
+            ErrorKind::DeserializationError,
+            "commit of signed header is missing",
+        )
+    })?;
+
+    Ok((parse_header(signed_header)?, Commit::parse(commit)?))
+}
+
+fn json_argument(ctx: &mut FunctionContext, i: i32, name: &str) -> NeonResult<Value> {
+    let json = typed_argument::<JsString>(ctx, i, name, "JSON string")?.value();
+
+    serde_json::from_str(&json).chain_neon_with_code(
+        ctx,
+        ErrorCode::DeserializationError,
+        format!("Unable to deserialize {} JSON", name),
+    )
+}
+
+pub fn register_block_module(ctx: &mut ModuleContext) -> NeonResult<()> {
+    let js_object = JsObject::new(ctx);
+
+    let decode_header_fn = JsFunction::new(ctx, decode_header)?;
+    js_object.set(ctx, "decodeHeader", decode_header_fn)?;
+
+    let verify_commit_fn = JsFunction::new(ctx, verify_commit_signatures)?;
+    js_object.set(ctx, "verifyCommit", verify_commit_fn)?;
+
+    ctx.export_value("block", js_object)
+}
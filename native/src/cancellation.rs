@@ -0,0 +1,154 @@
+//! Cancellation of the tasks running on background threads. Tasks scheduled
+//! with a callback return a handle { id, cancel() }, cancelling stops the work
+//! at its next check and the callback receives a CANCELLED error instead of
+//! the result
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use std::sync::{mpsc, Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+use client_common::{ErrorKind, Result};
+use lazy_static::lazy_static;
+use neon::prelude::*;
+
+use crate::error::{coded_client_error, ClientErrorNeonExt, ErrorCode, NativeError};
+
+pub const CANCELLED_MESSAGE: &str = "Task is cancelled";
+/// Interval to check whether the task is cancelled while waiting for a
+/// blocking operation
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+lazy_static! {
+    static ref TOKENS: Mutex<HashMap<u32, CancellationToken>> = Mutex::new(HashMap::new());
+}
+static NEXT_TASK_ID: AtomicU32 = AtomicU32::new(1);
+
+/// Cancellation flag shared by the JS handle and the background task. The
+/// default token belongs to a synchronous call and is never cancelled
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken {
+    id: Option<u32>,
+    cancelled: Arc<AtomicBool>,
+}
+
+impl CancellationToken {
+    /// Register a new token of a scheduled task, cancelled through the
+    /// handle returned by `to_js`
+    pub fn register() -> CancellationToken {
+        let id = NEXT_TASK_ID.fetch_add(1, Ordering::SeqCst);
+        let token = CancellationToken {
+            id: Some(id),
+            cancelled: Arc::new(AtomicBool::new(false)),
+        };
+        if let Ok(mut tokens) = TOKENS.lock() {
+            tokens.insert(id, token.clone());
+        }
+
+        token
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::SeqCst)
+    }
+
+    /// Fails with the cancelled error once cancelled, to be called between
+    /// the steps of the work
+    pub fn check(&self) -> Result<()> {
+        if self.is_cancelled() {
+            return Err(coded_client_error(
+                ErrorKind::InternalError,
+                ErrorCode::Cancelled,
+                CANCELLED_MESSAGE,
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Run the blocking operation, e.g. a network call, on a worker thread
+    /// which is abandoned when the token is cancelled. Synchronous calls run
+    /// it on the current thread
+    pub fn run<T, F>(&self, operation: F) -> std::result::Result<T, NativeError>
+    where
+        T: Send + 'static,
+        F: FnOnce() -> std::result::Result<T, NativeError> + Send + 'static,
+    {
+        if self.id.is_none() {
+            return operation();
+        }
+
+        let (sender, receiver) = mpsc::channel();
+        thread::spawn(move || {
+            let _ = sender.send(operation());
+        });
+        loop {
+            if self.is_cancelled() {
+                return Err(cancelled_error());
+            }
+            match receiver.recv_timeout(POLL_INTERVAL) {
+                Ok(result) => return result,
+                Err(mpsc::RecvTimeoutError::Timeout) => continue,
+                Err(mpsc::RecvTimeoutError::Disconnected) => {
+                    return Err(NativeError::new(
+                        ErrorCode::InternalError,
+                        "Background task panicked",
+                    ))
+                }
+            }
+        }
+    }
+
+    /// Release the token of the finished task. The result of a task
+    /// cancelled after its work is done is discarded all the same
+    pub fn complete<T>(
+        &self,
+        result: std::result::Result<T, NativeError>,
+    ) -> std::result::Result<T, NativeError> {
+        if let (Some(id), Ok(mut tokens)) = (self.id, TOKENS.lock()) {
+            tokens.remove(&id);
+        }
+        if self.is_cancelled() {
+            return Err(cancelled_error());
+        }
+
+        result
+    }
+
+    /// Returns the task handle { id, cancel() }
+    pub fn to_js<'a, C: Context<'a>>(&self, ctx: &mut C) -> JsResult<'a, JsObject> {
+        let handle = JsObject::new(ctx);
+        let id = ctx.number(self.id.unwrap_or_default());
+        handle.set(ctx, "id", id)?;
+        let cancel_fn = JsFunction::new(ctx, cancel)?;
+        handle.set(ctx, "cancel", cancel_fn)?;
+
+        Ok(handle)
+    }
+}
+
+fn cancelled_error() -> NativeError {
+    NativeError::new(ErrorCode::Cancelled, CANCELLED_MESSAGE)
+}
+
+/// cancel the task the handle is called on, the callback of the task is
+/// called with a CANCELLED error
+/// @return boolean: whether the task was still running
+pub fn cancel(mut ctx: FunctionContext) -> JsResult<JsBoolean> {
+    let handle = ctx.this();
+    let id = handle
+        .get(&mut ctx, "id")?
+        .downcast_or_throw::<JsNumber, FunctionContext>(&mut ctx)
+        .chain_neon(&mut ctx, "Unable to downcast id of task handle")?
+        .value() as u32;
+
+    let token = TOKENS
+        .lock()
+        .chain_neon(&mut ctx, "Unable to acquire task lock")?
+        .remove(&id);
+    if let Some(token) = &token {
+        token.cancelled.store(true, Ordering::SeqCst);
+    }
+
+    Ok(ctx.boolean(token.is_some()))
+}
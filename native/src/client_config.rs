@@ -0,0 +1,212 @@
+//! Timeout, retry and backoff configuration of the network clients, by
+//! endpoint, so that flaky infrastructure is retried instead of failing hard
+use std::cmp;
+use std::collections::HashMap;
+use std::sync::{mpsc, Arc, RwLock};
+use std::thread;
+use std::time::Duration;
+
+use client_common::{Error, ErrorKind, Result};
+use lazy_static::lazy_static;
+use neon::prelude::*;
+
+use crate::common::does_js_object_has_prop;
+use crate::error::{coded_client_error, ClientErrorNeonExt, ErrorCode};
+use crate::function_types::optional_integer_option;
+use crate::proxy::ProxyConfig;
+
+const TIMEOUT_MESSAGE: &str = "Request timed out";
+const DEFAULT_BACKOFF: Duration = Duration::from_millis(200);
+const DEFAULT_MAX_BACKOFF: Duration = Duration::from_secs(5);
+
+lazy_static! {
+    static ref CLIENT_CONFIGS: RwLock<HashMap<String, ClientConfig>> = RwLock::new(HashMap::new());
+}
+
+#[derive(Debug, Clone)]
+pub struct ClientConfig {
+    /// Timeout of each attempt, the client default when not provided
+    pub timeout: Option<Duration>,
+    /// Number of retries after the first attempt fails to connect
+    pub retries: u32,
+    /// Delay before the first retry, doubled for every following retry
+    pub backoff: Duration,
+    pub max_backoff: Duration,
+    /// Proxy to connect through
+    pub proxy: Option<ProxyConfig>,
+}
+
+impl Default for ClientConfig {
+    fn default() -> Self {
+        ClientConfig {
+            timeout: None,
+            retries: 0,
+            backoff: DEFAULT_BACKOFF,
+            max_backoff: DEFAULT_MAX_BACKOFF,
+            proxy: None,
+        }
+    }
+}
+
+impl ClientConfig {
+    /// Returns the configuration of the endpoint, default when it is not
+    /// configured
+    pub fn of(endpoint: &str) -> ClientConfig {
+        CLIENT_CONFIGS
+            .read()
+            .ok()
+            .and_then(|configs| configs.get(endpoint).cloned())
+            .unwrap_or_default()
+    }
+
+    /// Run the operation and retry it with exponential backoff when it fails
+    /// to connect. The operation enforces the timeout itself
+    pub fn retry<T, F>(&self, mut operation: F) -> Result<T>
+    where
+        F: FnMut() -> Result<T>,
+    {
+        let mut backoff = self.backoff;
+        let mut attempt = 0;
+        loop {
+            match operation() {
+                Err(ref err) if attempt < self.retries && is_retryable(err) => {
+                    log::warn!(
+                        "Attempt {} of {} failed, retrying in {}ms: {}",
+                        attempt + 1,
+                        self.retries + 1,
+                        backoff.as_millis(),
+                        err
+                    );
+                    thread::sleep(backoff);
+                    backoff = cmp::min(backoff * 2, self.max_backoff);
+                    attempt += 1;
+                }
+                result => return result,
+            }
+        }
+    }
+
+    /// Same as `retry` for clients without timeout of their own, each attempt
+    /// runs on a worker thread which is abandoned when it exceeds the timeout.
+    /// An abandoned worker keeps the operation until it returns, so secrets
+    /// the operation owns have to be zeroized on drop
+    pub fn run<T, F>(&self, operation: F) -> Result<T>
+    where
+        T: Send + 'static,
+        F: Fn() -> Result<T> + Send + Sync + 'static,
+    {
+        let timeout = match self.timeout {
+            Some(timeout) => timeout,
+            None => return self.retry(operation),
+        };
+
+        let operation = Arc::new(operation);
+        self.retry(|| {
+            let (sender, receiver) = mpsc::channel();
+            let operation = Arc::clone(&operation);
+            thread::spawn(move || {
+                let _ = sender.send(operation());
+            });
+
+            receiver.recv_timeout(timeout).unwrap_or_else(|_| {
+                Err(coded_client_error(
+                    ErrorKind::ConnectionError,
+                    ErrorCode::RpcTimeout,
+                    TIMEOUT_MESSAGE,
+                ))
+            })
+        })
+    }
+}
+
+/// Connection failures and timeouts are retried, errors returned by the
+/// remote end are not
+fn is_retryable(err: &Error) -> bool {
+    match err.kind() {
+        ErrorKind::ConnectionError => true,
+        _ => false,
+    }
+}
+
+/// configure the timeout, retries and backoff of the clients of the
+/// endpoint. Applies to the clients created afterwards
+/// @arguments
+/// - endpoint: Tendermint RPC endpoint, e.g. http://127.0.0.1:26657, or
+///     Tendermint websocket address of the tx-query client
+/// - config: (optional) { timeoutMs?, retries?, backoffMs?, maxBackoffMs?,
+///     proxyUrl? }, resets the endpoint to the default configuration when
+///     omitted. Retries only happen on connection failures and timeouts, and
+///     broadcasts are only retried when they fail to connect as a timed out
+///     broadcast may have reached the node. By default there is no retry,
+///     backoff starts at 200ms and is capped at 5s. proxyUrl is
+///     http://[user:password@]host:port of an HTTP proxy, with the user and
+///     password percent-encoded. The tx-query client only goes through the
+///     proxy with an attestation policy set by setAttestationPolicy, as the
+///     attested connection is tunnelled with CONNECT
+pub fn configure_client(mut ctx: FunctionContext) -> JsResult<JsUndefined> {
+    let endpoint = ctx.argument::<JsString>(0)?.value();
+    let config = match ctx.argument_opt(1) {
+        Some(config) if config.is_a::<JsObject>() => {
+            let config = config
+                .downcast_or_throw::<JsObject, FunctionContext>(&mut ctx)
+                .chain_neon(&mut ctx, "Unable to downcast client config")?;
+            Some(parse_client_config(&mut ctx, config)?)
+        }
+        Some(config) if !config.is_a::<JsUndefined>() && !config.is_a::<JsNull>() => {
+            return ctx.throw_error("Client config should be an object");
+        }
+        _ => None,
+    };
+
+    let mut configs = CLIENT_CONFIGS
+        .write()
+        .chain_neon(&mut ctx, "Unable to acquire client config lock")?;
+    match config {
+        Some(config) => configs.insert(endpoint, config),
+        None => configs.remove(&endpoint),
+    };
+
+    Ok(ctx.undefined())
+}
+
+fn parse_client_config(
+    ctx: &mut FunctionContext,
+    config: Handle<JsObject>,
+) -> NeonResult<ClientConfig> {
+    let default_config = ClientConfig::default();
+    let backoff = optional_integer_option(ctx, config, "backoffMs")?
+        .map(Duration::from_millis)
+        .unwrap_or(default_config.backoff);
+    let max_backoff = optional_integer_option(ctx, config, "maxBackoffMs")?
+        .map(Duration::from_millis)
+        .unwrap_or(default_config.max_backoff);
+    if backoff > max_backoff {
+        return ctx.throw_error("backoffMs should not be greater than maxBackoffMs");
+    }
+
+    let timeout = optional_integer_option(ctx, config, "timeoutMs")?;
+    if timeout == Some(0) {
+        return ctx.throw_error("timeoutMs should be positive");
+    }
+
+    let proxy = if does_js_object_has_prop(ctx, config, "proxyUrl")? {
+        let proxy_url = config
+            .get(ctx, "proxyUrl")?
+            .downcast_or_throw::<JsString, FunctionContext>(ctx)
+            .chain_neon(ctx, "Unable to downcast proxyUrl")?
+            .value();
+        Some(ProxyConfig::parse(&proxy_url).chain_neon(ctx, "Unable to parse proxy URL")?)
+    } else {
+        None
+    };
+
+    Ok(ClientConfig {
+        timeout: timeout.map(Duration::from_millis),
+        retries: optional_integer_option(ctx, config, "retries")?
+            .map(|retries| cmp::min(retries, u64::from(u32::max_value())) as u32)
+            .unwrap_or(default_config.retries),
+        backoff,
+        max_backoff,
+        proxy,
+    })
+}
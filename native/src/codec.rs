@@ -0,0 +1,344 @@
+//! SCALE encoding and decoding of chain types, for integrators constructing
+//! or inspecting pieces of transactions without going through the builders
+use std::str::FromStr;
+
+use neon::prelude::*;
+
+use chain_core::common::Timespec;
+use chain_core::init::address::CroAddress;
+use chain_core::init::coin::Coin;
+use chain_core::state::account::{StakedState, StakedStateAddress, StakedStateOpWitness};
+use chain_core::tx::data::access::{TxAccess, TxAccessPolicy};
+use chain_core::tx::data::attribute::TxAttributes;
+use chain_core::tx::data::input::TxoPointer;
+use chain_core::tx::data::output::TxOut;
+use chain_core::tx::witness::TxInWitness;
+use parity_scale_codec::Encode;
+use serde::Serialize;
+
+use crate::common::serialize_to_js;
+use crate::error::{ClientErrorNeonExt, ErrorCode};
+use crate::function_types::*;
+use crate::metrics;
+use crate::staking::staked_state_object;
+use crate::strict_decode::{decode_bytes, decode_options_argument, StrictDecode};
+
+/// SCALE-encode transaction output pointer
+/// @arguments
+/// - pointer: { prevTxId, prevIndex }
+/// @return Buffer
+pub fn encode_txo_pointer(mut ctx: FunctionContext) -> JsResult<JsBuffer> {
+    metrics::measure_js("codec.encodeTxoPointer", || {
+        let txo_pointer = txo_pointer_argument(&mut ctx, 0)?;
+
+        encode_to_buffer(&mut ctx, &txo_pointer)
+    })
+}
+
+/// decode SCALE-encoded transaction output pointer
+/// @arguments
+/// - encoded: Buffer
+/// - options: (optional) { strict?, maxSize?, maxVectorLength? }, see
+///     transaction.decode
+/// @return { prevTxId, prevIndex }
+pub fn decode_txo_pointer(mut ctx: FunctionContext) -> JsResult<JsObject> {
+    let txo_pointer: TxoPointer = decode_argument(&mut ctx, 0, "TxoPointer", 1)?;
+
+    let js_txo_pointer = ctx.empty_object();
+    let prev_tx_id = ctx.string(hex::encode(txo_pointer.id));
+    js_txo_pointer.set(&mut ctx, "prevTxId", prev_tx_id)?;
+    let prev_index = ctx.number(f64::from(txo_pointer.index));
+    js_txo_pointer.set(&mut ctx, "prevIndex", prev_index)?;
+
+    Ok(js_txo_pointer)
+}
+
+/// SCALE-encode transaction output
+/// @arguments
+/// - output: { address, value, validFrom? }
+/// - network: network handle or name of the output address
+/// @return Buffer
+pub fn encode_tx_out(mut ctx: FunctionContext) -> JsResult<JsBuffer> {
+    metrics::measure_js("codec.encodeTxOut", || {
+        let output = ctx.argument::<JsObject>(0)?;
+        let network = network_argument(&mut ctx, 1)?;
+
+        let tx_out = parse_output(&mut ctx, output, network)?;
+
+        encode_to_buffer(&mut ctx, &tx_out)
+    })
+}
+
+/// decode SCALE-encoded transaction output
+/// @arguments
+/// - encoded: Buffer
+/// - network: network handle or name to serialize the output address
+/// - options: (optional) { strict?, maxSize?, maxVectorLength? }, see
+///     transaction.decode
+/// @return { address, value, validFrom? }
+pub fn decode_tx_out(mut ctx: FunctionContext) -> JsResult<JsObject> {
+    let tx_out: TxOut = decode_argument(&mut ctx, 0, "TxOut", 2)?;
+    let network = network_argument(&mut ctx, 1)?;
+
+    let address = tx_out.address.to_cro(network).chain_neon_with_code(
+        &mut ctx,
+        ErrorCode::InvalidAddress,
+        "Unable to serialize output address",
+    )?;
+
+    let js_tx_out = ctx.empty_object();
+    let address = ctx.string(address);
+    js_tx_out.set(&mut ctx, "address", address)?;
+    let value = ctx.string(u64::from(tx_out.value).to_string());
+    js_tx_out.set(&mut ctx, "value", value)?;
+    if let Some(valid_from) = tx_out.valid_from {
+        let valid_from = ctx.number(valid_from as f64);
+        js_tx_out.set(&mut ctx, "validFrom", valid_from)?;
+    }
+
+    Ok(js_tx_out)
+}
+
+/// SCALE-encode transaction attributes, the view keys are granted access to
+/// all data of the transaction
+/// @arguments
+/// - attributes: { network | chainHexId, viewKeys }
+/// @return Buffer
+pub fn encode_tx_attributes(mut ctx: FunctionContext) -> JsResult<JsBuffer> {
+    metrics::measure_js("codec.encodeTxAttributes", || {
+        let attributes = ctx.argument::<JsObject>(0)?;
+
+        let chain_hex_id = chain_hex_id_option(&mut ctx, *attributes)?;
+        let view_keys = attributes
+            .get(&mut ctx, "viewKeys")?
+            .downcast_or_throw::<JsArray, FunctionContext>(&mut ctx)
+            .chain_neon(&mut ctx, "Unable to downcast viewKeys")?
+            .to_vec(&mut ctx)?;
+        let mut access_policies: Vec<TxAccessPolicy> = Vec::new();
+        for &view_key in view_keys.iter() {
+            let view_key = parse_view_key(&mut ctx, view_key)?;
+            access_policies.push(TxAccessPolicy {
+                view_key: view_key.into(),
+                access: TxAccess::AllData,
+            });
+        }
+
+        let attributes = TxAttributes::new_with_access(chain_hex_id, access_policies);
+
+        encode_to_buffer(&mut ctx, &attributes)
+    })
+}
+
+/// decode SCALE-encoded transaction attributes
+/// @arguments
+/// - encoded: Buffer
+/// - options: (optional) { strict?, maxSize?, maxVectorLength? }, see
+///     transaction.decode
+/// @return { chainHexId, viewKeys }
+pub fn decode_tx_attributes(mut ctx: FunctionContext) -> JsResult<JsObject> {
+    let attributes: TxAttributes = decode_argument(&mut ctx, 0, "TxAttributes", 1)?;
+
+    let js_view_keys = JsArray::new(&mut ctx, attributes.allowed_view.len() as u32);
+    for (i, access_policy) in attributes.allowed_view.iter().enumerate() {
+        let view_key = bytes_to_buffer(&mut ctx, &access_policy.view_key.serialize())?;
+        js_view_keys.set(&mut ctx, i as u32, view_key)?;
+    }
+
+    let js_attributes = ctx.empty_object();
+    let chain_hex_id = bytes_to_buffer(&mut ctx, &[attributes.chain_hex_id])?;
+    js_attributes.set(&mut ctx, "chainHexId", chain_hex_id)?;
+    js_attributes.set(&mut ctx, "viewKeys", js_view_keys)?;
+
+    Ok(js_attributes)
+}
+
+/// SCALE-encode staked state. Council node information is not supported and
+/// the staked state is encoded without validator or slash record
+/// @arguments
+/// - state: { address, nonce, bonded, unbonded, unbondedFrom }
+/// @return Buffer
+pub fn encode_staked_state(mut ctx: FunctionContext) -> JsResult<JsBuffer> {
+    metrics::measure_js("codec.encodeStakedState", || {
+        let state = ctx.argument::<JsObject>(0)?;
+
+        let address = state_string(&mut ctx, state, "address")?;
+        let address = StakedStateAddress::from_str(&address).chain_neon_with_code(
+            &mut ctx,
+            ErrorCode::InvalidAddress,
+            "Unable to deserialize staking address",
+        )?;
+        let nonce = state_string(&mut ctx, state, "nonce")?;
+        let nonce = parse_account_nonce(&mut ctx, nonce)?;
+        let bonded = state_string(&mut ctx, state, "bonded")?;
+        let bonded = Coin::from_str(&bonded).chain_neon_with_code(
+            &mut ctx,
+            ErrorCode::DeserializationError,
+            "Unable to deserialize bonded",
+        )?;
+        let unbonded = state_string(&mut ctx, state, "unbonded")?;
+        let unbonded = Coin::from_str(&unbonded).chain_neon_with_code(
+            &mut ctx,
+            ErrorCode::DeserializationError,
+            "Unable to deserialize unbonded",
+        )?;
+        let unbonded_from = state
+            .get(&mut ctx, "unbondedFrom")?
+            .downcast_or_throw::<JsNumber, FunctionContext>(&mut ctx)
+            .chain_neon(&mut ctx, "Unable to downcast unbondedFrom")?
+            .value();
+        if unbonded_from < 0.0 || unbonded_from.fract() != 0.0 {
+            return ctx.throw_error("unbondedFrom should be a non-negative integer");
+        }
+
+        let mut staked_state = StakedState::default(address);
+        staked_state.nonce = nonce;
+        staked_state.bonded = bonded;
+        staked_state.unbonded = unbonded;
+        staked_state.unbonded_from = unbonded_from as Timespec;
+
+        encode_to_buffer(&mut ctx, &staked_state)
+    })
+}
+
+/// decode SCALE-encoded staked state
+/// @arguments
+/// - encoded: Buffer
+/// - options: (optional) { strict?, maxSize?, maxVectorLength? }, see
+///     transaction.decode
+/// @return { address, nonce, bonded, unbonded, unbondedFrom, jailedUntil?,
+///     lastSlash? }
+pub fn decode_staked_state(mut ctx: FunctionContext) -> JsResult<JsObject> {
+    let staked_state: StakedState = decode_argument(&mut ctx, 0, "StakedState", 1)?;
+
+    staked_state_object(&mut ctx, &staked_state)
+}
+
+/// Decoded transaction input or staked state operation witness
+#[derive(Debug, Serialize)]
+#[serde(tag = "type")]
+pub enum WitnessDetails {
+    /// Schnorr signature with the Merkle proof of the signing public key
+    #[serde(rename_all = "camelCase")]
+    TreeSig { signature: String, proof: String },
+    /// Recoverable ECDSA signature
+    #[serde(rename_all = "camelCase")]
+    BasicRedeem { recovery_id: i32, signature: String },
+}
+
+impl From<&TxInWitness> for WitnessDetails {
+    fn from(witness: &TxInWitness) -> WitnessDetails {
+        match witness {
+            TxInWitness::TreeSig(signature, proof) => WitnessDetails::TreeSig {
+                signature: hex::encode(signature.serialize_default().to_vec()),
+                proof: hex::encode(proof.encode()),
+            },
+        }
+    }
+}
+
+impl From<&StakedStateOpWitness> for WitnessDetails {
+    fn from(witness: &StakedStateOpWitness) -> WitnessDetails {
+        match witness {
+            StakedStateOpWitness::BasicRedeem(signature) => {
+                let (recovery_id, signature) = signature.serialize_compact();
+                WitnessDetails::BasicRedeem {
+                    recovery_id: recovery_id.to_i32(),
+                    signature: hex::encode(signature.to_vec()),
+                }
+            }
+        }
+    }
+}
+
+/// decode SCALE-encoded transaction input witness
+/// @arguments
+/// - encoded: Buffer
+/// - options: (optional) { strict?, maxSize?, maxVectorLength? }, see
+///     transaction.decode
+/// @return { type: "TreeSig", signature, proof } with hex encoded Schnorr
+///     signature and SCALE-encoded Merkle proof
+pub fn decode_tx_in_witness(mut ctx: FunctionContext) -> JsResult<JsValue> {
+    let witness: TxInWitness = decode_argument(&mut ctx, 0, "transaction input witness", 1)?;
+
+    serialize_to_js(&mut ctx, &WitnessDetails::from(&witness))
+}
+
+/// decode SCALE-encoded staked state operation witness
+/// @arguments
+/// - encoded: Buffer
+/// - options: (optional) { strict?, maxSize?, maxVectorLength? }, see
+///     transaction.decode
+/// @return { type: "BasicRedeem", recoveryId, signature } with hex encoded
+///     64 bytes compact ECDSA signature
+pub fn decode_staked_state_op_witness(mut ctx: FunctionContext) -> JsResult<JsValue> {
+    let witness: StakedStateOpWitness =
+        decode_argument(&mut ctx, 0, "staked state operation witness", 1)?;
+
+    serialize_to_js(&mut ctx, &WitnessDetails::from(&witness))
+}
+
+fn decode_argument<T: StrictDecode>(
+    ctx: &mut FunctionContext,
+    i: i32,
+    name: &str,
+    options_index: i32,
+) -> NeonResult<T> {
+    let encoded = u8_buffer_argument(ctx, i)?;
+    let limits = decode_options_argument(ctx, options_index)?;
+
+    decode_bytes(ctx, &encoded, name, limits)
+}
+
+fn state_string(
+    ctx: &mut FunctionContext,
+    state: Handle<JsObject>,
+    key: &str,
+) -> NeonResult<String> {
+    let value = state
+        .get(ctx, key)?
+        .downcast_or_throw::<JsString, FunctionContext>(ctx)
+        .chain_neon(ctx, format!("Unable to downcast {}", key))?
+        .value();
+
+    Ok(value)
+}
+
+pub fn register_codec_module(ctx: &mut ModuleContext) -> NeonResult<()> {
+    let js_object = JsObject::new(ctx);
+
+    let encode_txo_pointer_fn = JsFunction::new(ctx, encode_txo_pointer)?;
+    js_object.set(ctx, "encodeTxoPointer", encode_txo_pointer_fn)?;
+
+    let decode_txo_pointer_fn = JsFunction::new(ctx, decode_txo_pointer)?;
+    js_object.set(ctx, "decodeTxoPointer", decode_txo_pointer_fn)?;
+
+    let encode_tx_out_fn = JsFunction::new(ctx, encode_tx_out)?;
+    js_object.set(ctx, "encodeTxOut", encode_tx_out_fn)?;
+
+    let decode_tx_out_fn = JsFunction::new(ctx, decode_tx_out)?;
+    js_object.set(ctx, "decodeTxOut", decode_tx_out_fn)?;
+
+    let encode_tx_attributes_fn = JsFunction::new(ctx, encode_tx_attributes)?;
+    js_object.set(ctx, "encodeTxAttributes", encode_tx_attributes_fn)?;
+
+    let decode_tx_attributes_fn = JsFunction::new(ctx, decode_tx_attributes)?;
+    js_object.set(ctx, "decodeTxAttributes", decode_tx_attributes_fn)?;
+
+    let encode_staked_state_fn = JsFunction::new(ctx, encode_staked_state)?;
+    js_object.set(ctx, "encodeStakedState", encode_staked_state_fn)?;
+
+    let decode_staked_state_fn = JsFunction::new(ctx, decode_staked_state)?;
+    js_object.set(ctx, "decodeStakedState", decode_staked_state_fn)?;
+
+    let decode_tx_in_witness_fn = JsFunction::new(ctx, decode_tx_in_witness)?;
+    js_object.set(ctx, "decodeTxInWitness", decode_tx_in_witness_fn)?;
+
+    let decode_staked_state_op_witness_fn = JsFunction::new(ctx, decode_staked_state_op_witness)?;
+    js_object.set(
+        ctx,
+        "decodeStakedStateOpWitness",
+        decode_staked_state_op_witness_fn,
+    )?;
+
+    ctx.export_value("codec", js_object)
+}
@@ -0,0 +1,176 @@
+use std::cmp::Ordering;
+
+use chain_core::init::coin::Coin;
+use neon::prelude::*;
+
+use crate::error::{throw_coded_error, ErrorCode};
+use crate::function_types::*;
+
+/// Number of decimal places of CRO, 1 CRO is 10^8 basic unit
+const CRO_DECIMALS: usize = 8;
+const BASIC_UNITS_PER_CRO: u64 = 100_000_000;
+
+/// Format amount in basic unit as CRO decimal string without trailing zeros
+pub fn coin_to_cro_string(coin: Coin) -> String {
+    let amount = u64::from(coin);
+    let integer = amount / BASIC_UNITS_PER_CRO;
+    let fraction = amount % BASIC_UNITS_PER_CRO;
+    if fraction == 0 {
+        return integer.to_string();
+    }
+
+    let fraction = format!("{:0width$}", fraction, width = CRO_DECIMALS);
+
+    format!("{}.{}", integer, fraction.trim_end_matches('0'))
+}
+
+/// Parse CRO decimal string, e.g. "1.5", into amount in basic unit. Signs,
+/// exponents and more than 8 decimal places are rejected
+pub fn coin_from_cro_str(cro: &str) -> Result<Coin, String> {
+    let mut parts = cro.splitn(2, '.');
+    let integer = parts.next().unwrap_or_default();
+    let fraction = parts.next().unwrap_or_default();
+
+    let is_digits = |value: &str| value.bytes().all(|byte| byte.is_ascii_digit());
+    if integer.is_empty() || !is_digits(integer) || !is_digits(fraction) {
+        return Err(format!("{} is not a CRO decimal string", cro));
+    }
+    if cro.ends_with('.') {
+        return Err(format!("{} has no decimal places after the point", cro));
+    }
+    if fraction.len() > CRO_DECIMALS {
+        return Err(format!("{} has more than 8 decimal places", cro));
+    }
+
+    let integer = integer
+        .parse::<u64>()
+        .ok()
+        .and_then(|integer| integer.checked_mul(BASIC_UNITS_PER_CRO));
+    let fraction = format!("{:0<width$}", fraction, width = CRO_DECIMALS)
+        .parse::<u64>()
+        .map_err(|err| err.to_string())?;
+    let amount = integer
+        .and_then(|integer| integer.checked_add(fraction))
+        .ok_or_else(|| format!("{} exceeds max supply", cro))?;
+
+    Coin::new(amount).map_err(|err| format!("{} ({})", cro, err))
+}
+
+/// max supply of CRO
+/// @return max supply string in basic unit
+pub fn max_supply(mut ctx: FunctionContext) -> JsResult<JsString> {
+    Ok(ctx.string(u64::from(Coin::max()).to_string()))
+}
+
+/// add two amounts, throws when the sum exceeds max supply
+/// @arguments
+/// - a: amount string or BigInt in basic unit
+/// - b: amount string or BigInt in basic unit
+/// @return sum string in basic unit
+pub fn add(mut ctx: FunctionContext) -> JsResult<JsString> {
+    let a = coin_argument(&mut ctx, 0, "a")?;
+    let b = coin_argument(&mut ctx, 1, "b")?;
+
+    let sum = u64::from(a)
+        .checked_add(u64::from(b))
+        .and_then(|sum| Coin::new(sum).ok());
+    match sum {
+        Some(sum) => Ok(ctx.string(u64::from(sum).to_string())),
+        None => throw_coded_error(
+            &mut ctx,
+            ErrorCode::InvalidArgument,
+            "Sum of amounts exceeds max supply",
+        ),
+    }
+}
+
+/// subtract amount b from amount a, throws when b is greater than a
+/// @arguments
+/// - a: amount string or BigInt in basic unit
+/// - b: amount string or BigInt in basic unit
+/// @return difference string in basic unit
+pub fn sub(mut ctx: FunctionContext) -> JsResult<JsString> {
+    let a = coin_argument(&mut ctx, 0, "a")?;
+    let b = coin_argument(&mut ctx, 1, "b")?;
+
+    match u64::from(a).checked_sub(u64::from(b)) {
+        Some(difference) => Ok(ctx.string(difference.to_string())),
+        None => throw_coded_error(
+            &mut ctx,
+            ErrorCode::InvalidArgument,
+            "Amount to subtract is greater than the amount",
+        ),
+    }
+}
+
+/// compare two amounts
+/// @arguments
+/// - a: amount string or BigInt in basic unit
+/// - b: amount string or BigInt in basic unit
+/// @return -1 when a is less than b, 0 when equal and 1 otherwise
+pub fn compare(mut ctx: FunctionContext) -> JsResult<JsNumber> {
+    let a = coin_argument(&mut ctx, 0, "a")?;
+    let b = coin_argument(&mut ctx, 1, "b")?;
+
+    let ordering = match u64::from(a).cmp(&u64::from(b)) {
+        Ordering::Less => -1,
+        Ordering::Equal => 0,
+        Ordering::Greater => 1,
+    };
+
+    Ok(ctx.number(ordering))
+}
+
+/// convert amount in basic unit to CRO decimal string
+/// @arguments
+/// - amount: amount string or BigInt in basic unit
+/// @return CRO decimal string, e.g. "1.5"
+pub fn to_cro(mut ctx: FunctionContext) -> JsResult<JsString> {
+    let amount = coin_argument(&mut ctx, 0, "amount")?;
+
+    Ok(ctx.string(coin_to_cro_string(amount)))
+}
+
+/// convert CRO decimal string to amount in basic unit
+/// @arguments
+/// - cro: CRO decimal string of at most 8 decimal places, e.g. "1.5"
+/// @return amount string in basic unit
+pub fn from_cro(mut ctx: FunctionContext) -> JsResult<JsString> {
+    let cro = typed_argument::<JsString>(&mut ctx, 0, "cro", "CRO decimal string")?.value();
+
+    match coin_from_cro_str(&cro) {
+        Ok(amount) => Ok(ctx.string(u64::from(amount).to_string())),
+        Err(got) => throw_invalid_argument(
+            &mut ctx,
+            ErrorCode::InvalidArgument,
+            0,
+            "cro",
+            "CRO decimal string",
+            got,
+        ),
+    }
+}
+
+pub fn register_coin_module(ctx: &mut ModuleContext) -> NeonResult<()> {
+    let js_object = JsObject::new(ctx);
+
+    let max_supply_fn = JsFunction::new(ctx, max_supply)?;
+    js_object.set(ctx, "maxSupply", max_supply_fn)?;
+
+    let add_fn = JsFunction::new(ctx, add)?;
+    js_object.set(ctx, "add", add_fn)?;
+
+    let sub_fn = JsFunction::new(ctx, sub)?;
+    js_object.set(ctx, "sub", sub_fn)?;
+
+    let compare_fn = JsFunction::new(ctx, compare)?;
+    js_object.set(ctx, "compare", compare_fn)?;
+
+    let to_cro_fn = JsFunction::new(ctx, to_cro)?;
+    js_object.set(ctx, "toCro", to_cro_fn)?;
+
+    let from_cro_fn = JsFunction::new(ctx, from_cro)?;
+    js_object.set(ctx, "fromCro", from_cro_fn)?;
+
+    ctx.export_value("coin", js_object)
+}
@@ -1,21 +1,82 @@
+use lazy_static::lazy_static;
 use neon::prelude::*;
+use secp256k1::{All, Secp256k1};
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::error::{ClientErrorNeonExt, ErrorCode};
+
+lazy_static! {
+    /// secp256k1 context shared by all the calls and threads. The
+    /// thread-local context of client-common is created again on each thread
+    /// of the pool running the background tasks
+    static ref SECP: Secp256k1<All> = Secp256k1::new();
+}
+
+/// Calls the function with the shared secp256k1 context
+#[inline]
+pub fn with_secp<F, T>(f: F) -> T
+where
+    F: FnOnce(&Secp256k1<All>) -> T,
+{
+    f(&SECP)
+}
+
+/// Decode the %XX escapes of an URL component. Returns None when a `%` is
+/// not followed by two hex digits
+pub fn percent_decode(component: &str) -> Option<Vec<u8>> {
+    let bytes = component.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] != b'%' {
+            decoded.push(bytes[i]);
+            i += 1;
+            continue;
+        }
+
+        let hex = bytes.get(i + 1..i + 3)?;
+        if !hex.iter().all(u8::is_ascii_hexdigit) {
+            return None;
+        }
+        let hex = std::str::from_utf8(hex).ok()?;
+        decoded.push(u8::from_str_radix(hex, 16).ok()?);
+        i += 3;
+    }
+
+    Some(decoded)
+}
 
 // TODO: Use feature conditional compilation when ready
 // https://github.com/neon-bindings/neon/issues/471
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy)]
 pub enum Features {
     AllDefault,
     MockAbci,
     MockObfuscation,
+    /// Transactions encoded without enclave obfuscation, accepted by the
+    /// devnets configured for plain transactions only
+    PlainTransactions,
+    /// Mock tx-query backend of the `mock-tx-query` feature, for offline tests
+    #[cfg(feature = "mock-tx-query")]
+    MockTxQuery,
 }
 
 impl Features {
     pub fn argument(ctx: &mut FunctionContext, i: i32) -> NeonResult<Features> {
         let features = ctx.argument::<JsString>(i)?.value();
-        match features.as_str() {
+
+        Features::parse(ctx, &features)
+    }
+
+    pub fn parse(ctx: &mut FunctionContext, features: &str) -> NeonResult<Features> {
+        match features {
             "AllDefault" => Ok(Features::AllDefault),
             "MockAbci" => Ok(Features::MockAbci),
             "MockObfuscation" => Ok(Features::MockObfuscation),
+            "PlainTransactions" => Ok(Features::PlainTransactions),
+            #[cfg(feature = "mock-tx-query")]
+            "MockTxQuery" => Ok(Features::MockTxQuery),
             _ => ctx.throw_error("Unrecognized features"),
         }
     }
@@ -40,3 +101,44 @@ pub fn does_js_object_has_prop(
 
     Ok(false)
 }
+
+/// Convert JSON value into the equivalent JS value
+pub fn json_to_js<'a, C: Context<'a>>(ctx: &mut C, value: &Value) -> JsResult<'a, JsValue> {
+    match value {
+        Value::Null => Ok(ctx.null().upcast()),
+        Value::Bool(value) => Ok(ctx.boolean(*value).upcast()),
+        Value::Number(value) => Ok(ctx.number(value.as_f64().unwrap_or_default()).upcast()),
+        Value::String(value) => Ok(ctx.string(value).upcast()),
+        Value::Array(values) => {
+            let js_array = JsArray::new(ctx, values.len() as u32);
+            for (i, value) in values.iter().enumerate() {
+                let js_value = json_to_js(ctx, value)?;
+                js_array.set(ctx, i as u32, js_value)?;
+            }
+            Ok(js_array.upcast())
+        }
+        Value::Object(values) => {
+            let js_object = ctx.empty_object();
+            for (key, value) in values.iter() {
+                let js_value = json_to_js(ctx, value)?;
+                js_object.set(ctx, key.as_str(), js_value)?;
+            }
+            Ok(js_object.upcast())
+        }
+    }
+}
+
+/// Convert serializable value into the equivalent JS value, so that decoded
+/// structures are returned as plain JS objects
+pub fn serialize_to_js<'a, C: Context<'a>, T: Serialize>(
+    ctx: &mut C,
+    value: &T,
+) -> JsResult<'a, JsValue> {
+    let value = serde_json::to_value(value).chain_neon_with_code(
+        ctx,
+        ErrorCode::SerializationError,
+        "Unable to serialize value",
+    )?;
+
+    json_to_js(ctx, &value)
+}
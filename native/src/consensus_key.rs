@@ -0,0 +1,252 @@
+//! Tendermint validator consensus key, an ed25519 key pair. The public key is
+//! base64 encoded in the Tendermint JSON formats, e.g. the consensus public
+//! key of the council node, and bech32 encoded with the amino prefix of the
+//! Tendermint ed25519 public key for display
+use bech32::{FromBase32, ToBase32};
+use chain_core::init::network::Network;
+use client_common::{Error, ErrorKind, Result};
+use ed25519_dalek::{Keypair, PublicKey, SecretKey, PUBLIC_KEY_LENGTH, SECRET_KEY_LENGTH};
+use neon::prelude::*;
+use serde_json::json;
+use sha2::{Digest, Sha256};
+use zeroize::Zeroize;
+
+use crate::error::{ClientErrorNeonExt, ErrorCode};
+use crate::function_types::*;
+use crate::rng::fill_random_bytes;
+
+/// Amino prefix of Tendermint ed25519 public key
+pub const AMINO_PUB_KEY_ED25519_PREFIX: [u8; 5] = [0x16, 0x24, 0xDE, 0x64, 0x20];
+pub const PUB_KEY_ED25519_TYPE: &str = "tendermint/PubKeyEd25519";
+const PRIV_KEY_ED25519_TYPE: &str = "tendermint/PrivKeyEd25519";
+/// Validator address is the first 20 bytes of SHA256 of the public key
+const VALIDATOR_ADDRESS_LENGTH: usize = 20;
+
+#[inline]
+fn consensus_pub_key_hrp_of_network(network: Network) -> &'static str {
+    match network {
+        Network::Mainnet => "crocnclconspub",
+        Network::Testnet => "tcrocnclconspub",
+        Network::Devnet => "dcrocnclconspub",
+    }
+}
+
+/// Decode base64 or bech32 consensus public key
+fn decode_consensus_pub_key(pub_key: &str) -> Result<[u8; PUBLIC_KEY_LENGTH]> {
+    let is_bech32 = [Network::Mainnet, Network::Testnet, Network::Devnet]
+        .iter()
+        .any(|&network| {
+            pub_key.starts_with(&format!("{}1", consensus_pub_key_hrp_of_network(network)))
+        });
+    let bytes = if is_bech32 {
+        decode_bech32_consensus_pub_key(pub_key)?
+    } else {
+        base64::decode(pub_key).map_err(|_| {
+            Error::new(
+                ErrorKind::DeserializationError,
+                "Consensus public key should be base64 or bech32 string",
+            )
+        })?
+    };
+
+    if bytes.len() != PUBLIC_KEY_LENGTH {
+        return Err(Error::new(
+            ErrorKind::DeserializationError,
+            format!(
+                "Consensus public key should be {} bytes long",
+                PUBLIC_KEY_LENGTH
+            ),
+        ));
+    }
+
+    let mut pub_key = [0u8; PUBLIC_KEY_LENGTH];
+    pub_key.copy_from_slice(&bytes);
+
+    Ok(pub_key)
+}
+
+fn decode_bech32_consensus_pub_key(pub_key: &str) -> Result<Vec<u8>> {
+    let (_, data) = bech32::decode(pub_key).map_err(|err| {
+        Error::new(
+            ErrorKind::DeserializationError,
+            format!("Invalid bech32 consensus public key: {}", err),
+        )
+    })?;
+    let payload = Vec::<u8>::from_base32(&data).map_err(|err| {
+        Error::new(
+            ErrorKind::DeserializationError,
+            format!("Invalid bech32 consensus public key: {}", err),
+        )
+    })?;
+
+    if !payload.starts_with(&AMINO_PUB_KEY_ED25519_PREFIX) {
+        return Err(Error::new(
+            ErrorKind::DeserializationError,
+            "Bech32 consensus public key is not an ed25519 public key",
+        ));
+    }
+
+    Ok(payload[AMINO_PUB_KEY_ED25519_PREFIX.len()..].to_vec())
+}
+
+fn encode_bech32_consensus_pub_key(pub_key: &[u8], network: Network) -> Result<String> {
+    let mut payload = AMINO_PUB_KEY_ED25519_PREFIX.to_vec();
+    payload.extend_from_slice(pub_key);
+
+    bech32::encode(
+        consensus_pub_key_hrp_of_network(network),
+        payload.to_base32(),
+    )
+    .map_err(|err| {
+        Error::new(
+            ErrorKind::SerializationError,
+            format!("Unable to encode bech32 consensus public key: {}", err),
+        )
+    })
+}
+
+/// Key pair of a secret key drawn from the nodelib RNG. ed25519-dalek takes
+/// an RNG of an older rand_core than the nodelib RNG implements, so the secret
+/// key bytes are drawn directly
+fn generate_key_pair() -> Result<Keypair> {
+    let mut secret_key_bytes = [0u8; SECRET_KEY_LENGTH];
+    fill_random_bytes(&mut secret_key_bytes)?;
+    let secret = SecretKey::from_bytes(&secret_key_bytes);
+    secret_key_bytes.zeroize();
+    let secret = secret.map_err(|err| {
+        Error::new(
+            ErrorKind::InternalError,
+            format!("Unable to create ed25519 secret key: {}", err),
+        )
+    })?;
+    let public = PublicKey::from(&secret);
+
+    Ok(Keypair { secret, public })
+}
+
+#[inline]
+pub fn validator_address_bytes(pub_key: &[u8]) -> Vec<u8> {
+    Sha256::digest(pub_key)[..VALIDATOR_ADDRESS_LENGTH].to_vec()
+}
+
+#[inline]
+fn validator_address(pub_key: &[u8]) -> String {
+    hex::encode_upper(validator_address_bytes(pub_key))
+}
+
+fn consensus_pub_key_argument(
+    ctx: &mut FunctionContext,
+    i: i32,
+) -> NeonResult<[u8; PUBLIC_KEY_LENGTH]> {
+    let pub_key =
+        typed_argument::<JsString>(ctx, i, "consensus public key", "base64 or bech32 string")?
+            .value();
+
+    decode_consensus_pub_key(&pub_key).chain_neon_with_code(
+        ctx,
+        ErrorCode::InvalidPublicKey,
+        "Unable to decode consensus public key",
+    )
+}
+
+/// generate a new ed25519 consensus key pair
+/// @return { address, publicKey, privateKey, privValidatorKey }, publicKey
+///     and privateKey are base64 encoded, privValidatorKey is the JSON string
+///     of Tendermint priv_validator_key.json
+pub fn generate(mut ctx: FunctionContext) -> JsResult<JsObject> {
+    let key_pair = generate_key_pair().chain_neon(&mut ctx, "Unable to generate key pair")?;
+    let address = validator_address(key_pair.public.as_bytes());
+    let public_key = base64::encode(key_pair.public.as_bytes());
+    let private_key = base64::encode(&key_pair.to_bytes()[..]);
+
+    let priv_validator_key = json!({
+        "address": address,
+        "pub_key": {
+            "type": PUB_KEY_ED25519_TYPE,
+            "value": public_key,
+        },
+        "priv_key": {
+            "type": PRIV_KEY_ED25519_TYPE,
+            "value": private_key,
+        },
+    });
+    let priv_validator_key = serde_json::to_string_pretty(&priv_validator_key)
+        .chain_neon_with_code(
+            &mut ctx,
+            ErrorCode::SerializationError,
+            "Unable to serialize priv_validator_key",
+        )?;
+
+    let return_object = ctx.empty_object();
+    for (key, value) in &[
+        ("address", address),
+        ("publicKey", public_key),
+        ("privateKey", private_key),
+        ("privValidatorKey", priv_validator_key),
+    ] {
+        let value = ctx.string(value);
+        return_object
+            .set(&mut ctx, *key, value)
+            .chain_neon(&mut ctx, format!("Unable to set {} of return object", key))?;
+    }
+
+    Ok(return_object)
+}
+
+/// convert consensus public key to bech32 string
+/// @arguments
+/// - public_key: base64 or bech32 consensus public key
+/// - network: network handle or name, decides the crocnclconspub,
+///     tcrocnclconspub or dcrocnclconspub prefix
+/// @return bech32 consensus public key
+pub fn to_bech32(mut ctx: FunctionContext) -> JsResult<JsString> {
+    let pub_key = consensus_pub_key_argument(&mut ctx, 0)?;
+    let network = network_argument(&mut ctx, 1)?;
+
+    let bech32 = encode_bech32_consensus_pub_key(&pub_key, network).chain_neon_with_code(
+        &mut ctx,
+        ErrorCode::InvalidPublicKey,
+        "Unable to convert consensus public key",
+    )?;
+
+    Ok(ctx.string(bech32))
+}
+
+/// convert consensus public key to base64 string, as the consensus public key
+/// of node metadata
+/// @arguments
+/// - public_key: base64 or bech32 consensus public key
+/// @return base64 consensus public key
+pub fn to_base64(mut ctx: FunctionContext) -> JsResult<JsString> {
+    let pub_key = consensus_pub_key_argument(&mut ctx, 0)?;
+
+    Ok(ctx.string(base64::encode(&pub_key)))
+}
+
+/// compute Tendermint validator address of the consensus public key
+/// @arguments
+/// - public_key: base64 or bech32 consensus public key
+/// @return uppercase hex validator address
+pub fn to_validator_address(mut ctx: FunctionContext) -> JsResult<JsString> {
+    let pub_key = consensus_pub_key_argument(&mut ctx, 0)?;
+
+    Ok(ctx.string(validator_address(&pub_key)))
+}
+
+pub fn register_consensus_key_module(ctx: &mut ModuleContext) -> NeonResult<()> {
+    let js_object = JsObject::new(ctx);
+
+    let generate_fn = JsFunction::new(ctx, generate)?;
+    js_object.set(ctx, "generate", generate_fn)?;
+
+    let to_bech32_fn = JsFunction::new(ctx, to_bech32)?;
+    js_object.set(ctx, "toBech32", to_bech32_fn)?;
+
+    let to_base64_fn = JsFunction::new(ctx, to_base64)?;
+    js_object.set(ctx, "toBase64", to_base64_fn)?;
+
+    let to_validator_address_fn = JsFunction::new(ctx, to_validator_address)?;
+    js_object.set(ctx, "toValidatorAddress", to_validator_address_fn)?;
+
+    ctx.export_value("consensusKey", js_object)
+}
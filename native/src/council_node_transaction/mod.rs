@@ -1,11 +1,17 @@
 use neon::prelude::*;
 
 mod node_join_transaction;
+mod node_metadata;
 mod unjail_transaction;
 
-use node_join_transaction::{build_raw_node_join_transaction, node_join_transaction_to_hex};
+use node_join_transaction::{
+    build_node_join_tx, build_raw_node_join_transaction, node_join_transaction_to_hex,
+    node_join_transaction_with_witness_to_hex,
+};
+use node_metadata::validate_node_metadata;
 use unjail_transaction::{
-    build_raw_unjail_transaction, unjail_transaction_to_hex, verify_unjail_tx_aux,
+    build_raw_unjail_transaction, build_unjail_tx, unjail_transaction_to_hex,
+    unjail_transaction_with_witness_to_hex, verify_unjail_tx_aux,
 };
 
 pub fn register_council_node_transaction_module(ctx: &mut ModuleContext) -> NeonResult<()> {
@@ -25,6 +31,20 @@ pub fn register_council_node_transaction_module(ctx: &mut ModuleContext) -> Neon
         node_join_transaction_to_hex_fn,
     )?;
 
+    let build_node_join_tx_fn = JsFunction::new(ctx, build_node_join_tx)?;
+    js_object.set(ctx, "buildNodeJoinTx", build_node_join_tx_fn)?;
+
+    let node_join_transaction_with_witness_to_hex_fn =
+        JsFunction::new(ctx, node_join_transaction_with_witness_to_hex)?;
+    js_object.set(
+        ctx,
+        "nodeJoinTransactionWithWitnessToHex",
+        node_join_transaction_with_witness_to_hex_fn,
+    )?;
+
+    let validate_node_metadata_fn = JsFunction::new(ctx, validate_node_metadata)?;
+    js_object.set(ctx, "validateNodeMetadata", validate_node_metadata_fn)?;
+
     let build_raw_unjail_transaction_fn = JsFunction::new(ctx, build_raw_unjail_transaction)?;
     js_object.set(
         ctx,
@@ -35,6 +55,17 @@ pub fn register_council_node_transaction_module(ctx: &mut ModuleContext) -> Neon
     let unjail_transaction_to_hex_fn = JsFunction::new(ctx, unjail_transaction_to_hex)?;
     js_object.set(ctx, "unjailTransactionToHex", unjail_transaction_to_hex_fn)?;
 
+    let build_unjail_tx_fn = JsFunction::new(ctx, build_unjail_tx)?;
+    js_object.set(ctx, "buildUnjailTx", build_unjail_tx_fn)?;
+
+    let unjail_transaction_with_witness_to_hex_fn =
+        JsFunction::new(ctx, unjail_transaction_with_witness_to_hex)?;
+    js_object.set(
+        ctx,
+        "unjailTransactionWithWitnessToHex",
+        unjail_transaction_with_witness_to_hex_fn,
+    )?;
+
     let verify_unjail_tx_aux_fn = JsFunction::new(ctx, verify_unjail_tx_aux)?;
     js_object.set(ctx, "verifyUnjailTxAux", verify_unjail_tx_aux_fn)?;
 
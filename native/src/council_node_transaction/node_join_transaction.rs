@@ -7,9 +7,11 @@ use chain_core::state::account::{
 };
 use chain_core::state::validator::NodeJoinRequestTx;
 use chain_core::tx::{TransactionId, TxAux, TxPublicAux};
-use parity_scale_codec::{Decode, Encode};
+use chain_tx_validation::witness::verify_tx_recover_address;
+use client_common::{PrivateKey, PublicKey};
+use parity_scale_codec::Decode;
 
-use crate::error::ClientErrorNeonExt;
+use crate::error::{ClientErrorNeonExt, ErrorCode};
 use crate::function_types::*;
 use crate::signer::KeyPairSigner;
 use crate::tx_aux::tx_aux_to_hex;
@@ -25,12 +27,7 @@ pub fn build_raw_node_join_transaction(mut ctx: FunctionContext) -> JsResult<JsO
         options.council_node,
     );
 
-    let raw_tx = tx.encode();
-    let mut raw_tx_buffer = ctx.buffer(raw_tx.len() as u32)?;
-    ctx.borrow_mut(&mut raw_tx_buffer, |data| {
-        let data = data.as_mut_slice();
-        data.copy_from_slice(&raw_tx)
-    });
+    let raw_tx_buffer = encode_to_buffer(&mut ctx, &tx)?;
 
     let tx_id = tx.id();
     let tx_id = ctx.string(hex::encode(tx_id));
@@ -49,17 +46,74 @@ pub fn build_raw_node_join_transaction(mut ctx: FunctionContext) -> JsResult<JsO
 pub fn node_join_transaction_to_hex(mut ctx: FunctionContext) -> JsResult<JsBuffer> {
     let node_join_request_tx = node_join_request_tx_argument(&mut ctx, 0)?;
     let key_pair = key_pair_argument(&mut ctx, 1)?;
+
+    let tx_aux = sign_node_join_request_tx(&mut ctx, node_join_request_tx, key_pair)?;
+
+    tx_aux_to_hex(&mut ctx, tx_aux)
+}
+
+/// build and sign node join transaction in one go
+/// @arguments
+/// - options: { stakingAddress, nonce, nodeMetaData, network | chainHexId },
+///     where nodeMetaData is the JSON string of the council node (validator
+///     name, security contact, consensus public key and enclave certificate)
+/// - key_pair: key pair of the staking address
+/// @return broadcast-able NodeJoinTx
+pub fn build_node_join_tx(mut ctx: FunctionContext) -> JsResult<JsBuffer> {
+    let options = BuildNodeJoinTransactionOptions::parse(&mut ctx)?;
+    let key_pair = key_pair_argument(&mut ctx, 1)?;
+    let attributes = StakedStateOpAttributes::new(options.chain_hex_id);
+
+    let node_join_request_tx = NodeJoinRequestTx::new(
+        options.nonce,
+        options.staking_address,
+        attributes,
+        options.council_node,
+    );
+
+    let tx_aux = sign_node_join_request_tx(&mut ctx, node_join_request_tx, key_pair)?;
+
+    tx_aux_to_hex(&mut ctx, tx_aux)
+}
+
+/// attach externally produced witness to node join transaction
+/// @arguments
+/// - unsigned_raw_tx: raw NodeJoinRequestTx Buffer
+/// - witness: StakedStateOpWitness of the transaction id
+/// @return broadcast-able NodeJoinTx
+pub fn node_join_transaction_with_witness_to_hex(mut ctx: FunctionContext) -> JsResult<JsBuffer> {
+    let node_join_request_tx = node_join_request_tx_argument(&mut ctx, 0)?;
+    let witness = ctx.argument::<JsValue>(1)?;
+    let witness = parse_staked_state_op_witness(&mut ctx, witness)?;
+
+    let address = verify_tx_recover_address(&witness, &node_join_request_tx.id())
+        .chain_neon(&mut ctx, "Invalid witness")?;
+    if address != node_join_request_tx.address {
+        return ctx.throw_error("Witness is not signed by the joining staking address");
+    }
+
+    let tx_aux = TxAux::PublicTx(TxPublicAux::NodeJoinTx(node_join_request_tx, witness));
+
+    tx_aux_to_hex(&mut ctx, tx_aux)
+}
+
+fn sign_node_join_request_tx(
+    ctx: &mut FunctionContext,
+    node_join_request_tx: NodeJoinRequestTx,
+    key_pair: (PrivateKey, PublicKey),
+) -> NeonResult<TxAux> {
     let signer = KeyPairSigner::new(key_pair.0, key_pair.1)
-        .chain_neon(&mut ctx, "Unable to create KeyPair signer")?;
+        .chain_neon(ctx, "Unable to create KeyPair signer")?;
 
     let signature = signer
         .sign(&node_join_request_tx.id())
         .map(StakedStateOpWitness::new)
-        .chain_neon(&mut ctx, "Error when signing transaction")?;
+        .chain_neon(ctx, "Error when signing transaction")?;
 
-    let tx_aux = TxAux::PublicTx(TxPublicAux::NodeJoinTx(node_join_request_tx, signature));
-
-    tx_aux_to_hex(&mut ctx, tx_aux)
+    Ok(TxAux::PublicTx(TxPublicAux::NodeJoinTx(
+        node_join_request_tx,
+        signature,
+    )))
 }
 
 fn node_join_request_tx_argument(
@@ -69,8 +123,11 @@ fn node_join_request_tx_argument(
     let node_join_request_tx = ctx.argument::<JsBuffer>(i)?;
     let mut node_join_request_tx = node_join_request_tx.borrow(&ctx.lock()).as_slice();
 
-    NodeJoinRequestTx::decode(&mut node_join_request_tx)
-        .chain_neon(ctx, "Unable to decode raw transaction bytes")
+    NodeJoinRequestTx::decode(&mut node_join_request_tx).chain_neon_with_code(
+        ctx,
+        ErrorCode::DeserializationError,
+        "Unable to decode raw transaction bytes",
+    )
 }
 
 struct BuildNodeJoinTransactionOptions {
@@ -82,22 +139,27 @@ struct BuildNodeJoinTransactionOptions {
 
 impl BuildNodeJoinTransactionOptions {
     fn parse(ctx: &mut FunctionContext) -> NeonResult<BuildNodeJoinTransactionOptions> {
-        let options = ctx
-            .argument::<JsObject>(0)
-            .chain_neon(ctx, "Unable to deserialize options object")?;
+        let options = ctx.argument::<JsObject>(0).chain_neon_with_code(
+            ctx,
+            ErrorCode::DeserializationError,
+            "Unable to deserialize options object",
+        )?;
 
         let staking_address = options
             .get(ctx, "stakingAddress")?
             .downcast_or_throw::<JsString, FunctionContext>(ctx)
             .chain_neon(ctx, "Unable to downcast stakingAddress")?
             .value();
-        let staking_address = StakedStateAddress::from_str(&staking_address)
-            .chain_neon(ctx, "Unable to deserialize stakingAddress")?;
+        let staking_address = StakedStateAddress::from_str(&staking_address).chain_neon_with_code(
+            ctx,
+            ErrorCode::InvalidAddress,
+            "Unable to deserialize stakingAddress",
+        )?;
 
         let nonce = options
             .get(ctx, "nonce")?
             .downcast_or_throw::<JsString, FunctionContext>(ctx)
-            .chain_neon(ctx, "Unable to downcast stakingAddress")?
+            .chain_neon(ctx, "Unable to downcast nonce")?
             .value();
         let nonce = parse_account_nonce(ctx, nonce)?;
 
@@ -107,14 +169,13 @@ impl BuildNodeJoinTransactionOptions {
             .chain_neon(ctx, "Unable to downcast nodeMetaData")?
             .value();
         let council_node = serde_json::from_str::<CouncilNode>(&council_node)
-            .chain_neon(ctx, "Unable to deserialize nodeMetaData")?;
-
-        let chain_hex_id = options
-            .get(ctx, "chainHexId")?
-            .downcast_or_throw::<JsBuffer, FunctionContext>(ctx)
-            .chain_neon(ctx, "Unable to downcast chainHexId")?;
-        let chain_hex_id = chain_hex_id.borrow(&ctx.lock()).as_slice().to_vec();
-        let chain_hex_id = chain_hex_id_from_vec(ctx, chain_hex_id)?;
+            .chain_neon_with_code(
+                ctx,
+                ErrorCode::DeserializationError,
+                "Unable to deserialize nodeMetaData",
+            )?;
+
+        let chain_hex_id = chain_hex_id_option(ctx, *options)?;
 
         Ok(BuildNodeJoinTransactionOptions {
             staking_address,
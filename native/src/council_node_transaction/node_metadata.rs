@@ -0,0 +1,278 @@
+//! Validation of council node metadata before crafting node join transaction.
+//! Every field is checked so that all the mistakes are reported at once
+use chain_core::state::account::CouncilNode;
+use ed25519_dalek::PUBLIC_KEY_LENGTH;
+use neon::prelude::*;
+use serde::Serialize;
+use serde_json::{Map, Value};
+
+use crate::common::serialize_to_js;
+use crate::consensus_key::PUB_KEY_ED25519_TYPE;
+use crate::function_types::*;
+
+/// Max length of validator name in bytes
+const MAX_NAME_LENGTH: usize = 64;
+/// Max length of security contact in bytes
+const MAX_SECURITY_CONTACT_LENGTH: usize = 128;
+/// Max length of enclave certificate in bytes
+const MAX_CERT_LENGTH: usize = 64 * 1024;
+/// ASN.1 DER tag of SEQUENCE, the outermost structure of a certificate
+const DER_SEQUENCE_TAG: u8 = 0x30;
+
+#[derive(Debug, Serialize)]
+struct FieldError {
+    field: String,
+    message: String,
+}
+
+impl FieldError {
+    fn new<F: Into<String>, M: Into<String>>(field: F, message: M) -> FieldError {
+        FieldError {
+            field: field.into(),
+            message: message.into(),
+        }
+    }
+}
+
+/// validate council node metadata
+/// @arguments
+/// - node_meta_data: JSON string of the council node, as nodeMetaData of
+///     buildRawNodeJoinTransaction
+/// @return [{ field, message }] errors of the fields, empty when the metadata
+///     is valid
+pub fn validate_node_metadata(mut ctx: FunctionContext) -> JsResult<JsValue> {
+    let node_meta_data =
+        typed_argument::<JsString>(&mut ctx, 0, "node metadata", "JSON string")?.value();
+
+    let errors = match serde_json::from_str::<Value>(&node_meta_data) {
+        Ok(Value::Object(node_meta_data)) => validate_node_metadata_object(&node_meta_data),
+        Ok(_) => vec![FieldError::new("", "Node metadata should be a JSON object")],
+        Err(err) => vec![FieldError::new(
+            "",
+            format!("Node metadata is not valid JSON: {}", err),
+        )],
+    };
+
+    serialize_to_js(&mut ctx, &errors)
+}
+
+fn validate_node_metadata_object(node_meta_data: &Map<String, Value>) -> Vec<FieldError> {
+    let mut errors = Vec::new();
+
+    validate_name(node_meta_data.get("name"), &mut errors);
+    validate_security_contact(node_meta_data.get("security_contact"), &mut errors);
+    validate_consensus_pubkey(node_meta_data.get("consensus_pubkey"), &mut errors);
+    validate_confidential_init(node_meta_data.get("confidential_init"), &mut errors);
+
+    // Catch what the field checks do not cover, e.g. unknown fields
+    if errors.is_empty() {
+        let node_meta_data = Value::Object(node_meta_data.clone());
+        if let Err(err) = serde_json::from_value::<CouncilNode>(node_meta_data) {
+            errors.push(FieldError::new(
+                "",
+                format!("Node metadata is not a valid council node: {}", err),
+            ));
+        }
+    }
+
+    errors
+}
+
+fn validate_name(name: Option<&Value>, errors: &mut Vec<FieldError>) {
+    let name = match name {
+        Some(Value::String(name)) => name,
+        Some(_) => return errors.push(FieldError::new("name", "Name should be a string")),
+        None => return errors.push(FieldError::new("name", "Name is missing")),
+    };
+
+    if name.trim().is_empty() {
+        errors.push(FieldError::new("name", "Name should not be empty"));
+    } else if name.len() > MAX_NAME_LENGTH {
+        errors.push(FieldError::new(
+            "name",
+            format!("Name should be at most {} bytes long", MAX_NAME_LENGTH),
+        ));
+    } else if name.chars().any(char::is_control) {
+        errors.push(FieldError::new(
+            "name",
+            "Name should not contain control characters",
+        ));
+    }
+}
+
+fn validate_security_contact(security_contact: Option<&Value>, errors: &mut Vec<FieldError>) {
+    let security_contact = match security_contact {
+        Some(Value::String(security_contact)) => security_contact,
+        Some(Value::Null) | None => return,
+        Some(_) => {
+            return errors.push(FieldError::new(
+                "security_contact",
+                "Security contact should be a string",
+            ))
+        }
+    };
+
+    if security_contact.len() > MAX_SECURITY_CONTACT_LENGTH {
+        errors.push(FieldError::new(
+            "security_contact",
+            format!(
+                "Security contact should be at most {} bytes long",
+                MAX_SECURITY_CONTACT_LENGTH
+            ),
+        ));
+    } else if !is_email_address(security_contact) {
+        errors.push(FieldError::new(
+            "security_contact",
+            "Security contact should be an email address",
+        ));
+    }
+}
+
+/// Loose email address check: a local part and a dotted domain without
+/// whitespace around a single @
+fn is_email_address(value: &str) -> bool {
+    let mut parts = value.split('@');
+    let (local, domain) = match (parts.next(), parts.next(), parts.next()) {
+        (Some(local), Some(domain), None) => (local, domain),
+        _ => return false,
+    };
+
+    !local.is_empty()
+        && !value.chars().any(char::is_whitespace)
+        && domain
+            .split('.')
+            .all(|label| !label.is_empty() && !label.starts_with('-') && !label.ends_with('-'))
+        && domain.contains('.')
+}
+
+fn validate_consensus_pubkey(consensus_pubkey: Option<&Value>, errors: &mut Vec<FieldError>) {
+    let consensus_pubkey = match consensus_pubkey {
+        Some(Value::Object(consensus_pubkey)) => consensus_pubkey,
+        Some(_) => {
+            return errors.push(FieldError::new(
+                "consensus_pubkey",
+                "Consensus public key should be an object of type and value",
+            ))
+        }
+        None => {
+            return errors.push(FieldError::new(
+                "consensus_pubkey",
+                "Consensus public key is missing",
+            ))
+        }
+    };
+
+    match consensus_pubkey.get("type") {
+        Some(Value::String(key_type)) if key_type == PUB_KEY_ED25519_TYPE => {}
+        _ => errors.push(FieldError::new(
+            "consensus_pubkey.type",
+            format!(
+                "Consensus public key type should be {}",
+                PUB_KEY_ED25519_TYPE
+            ),
+        )),
+    }
+
+    let value = match consensus_pubkey.get("value") {
+        Some(Value::String(value)) => value,
+        _ => {
+            return errors.push(FieldError::new(
+                "consensus_pubkey.value",
+                "Consensus public key value should be a base64 string",
+            ))
+        }
+    };
+    match base64::decode(value) {
+        Ok(pub_key) if pub_key.len() == PUBLIC_KEY_LENGTH => {}
+        Ok(_) => errors.push(FieldError::new(
+            "consensus_pubkey.value",
+            format!(
+                "Consensus public key should be {} bytes long",
+                PUBLIC_KEY_LENGTH
+            ),
+        )),
+        Err(_) => errors.push(FieldError::new(
+            "consensus_pubkey.value",
+            "Consensus public key value is not valid base64",
+        )),
+    }
+}
+
+fn validate_confidential_init(confidential_init: Option<&Value>, errors: &mut Vec<FieldError>) {
+    let cert = match confidential_init {
+        Some(Value::Object(confidential_init)) => confidential_init.get("cert"),
+        Some(_) => {
+            return errors.push(FieldError::new(
+                "confidential_init",
+                "Confidential init should be an object of cert",
+            ))
+        }
+        None => {
+            return errors.push(FieldError::new(
+                "confidential_init",
+                "Confidential init is missing",
+            ))
+        }
+    };
+    let cert = match cert {
+        Some(Value::String(cert)) => cert,
+        _ => {
+            return errors.push(FieldError::new(
+                "confidential_init.cert",
+                "Enclave certificate should be a base64 string",
+            ))
+        }
+    };
+
+    let cert = match base64::decode(cert) {
+        Ok(cert) => cert,
+        Err(_) => {
+            return errors.push(FieldError::new(
+                "confidential_init.cert",
+                "Enclave certificate is not valid base64",
+            ))
+        }
+    };
+    if cert.is_empty() {
+        errors.push(FieldError::new(
+            "confidential_init.cert",
+            "Enclave certificate should not be empty",
+        ));
+    } else if cert.len() > MAX_CERT_LENGTH {
+        errors.push(FieldError::new(
+            "confidential_init.cert",
+            format!(
+                "Enclave certificate should be at most {} bytes long",
+                MAX_CERT_LENGTH
+            ),
+        ));
+    } else if !is_der_sequence(&cert) {
+        errors.push(FieldError::new(
+            "confidential_init.cert",
+            "Enclave certificate should be a DER encoded certificate",
+        ));
+    }
+}
+
+/// Whether the bytes are exactly one DER SEQUENCE, the outermost structure of
+/// an X.509 certificate
+fn is_der_sequence(bytes: &[u8]) -> bool {
+    if bytes.len() < 2 || bytes[0] != DER_SEQUENCE_TAG {
+        return false;
+    }
+
+    let (header_length, content_length) = if bytes[1] < 0x80 {
+        (2, bytes[1] as usize)
+    } else {
+        let length_octets = (bytes[1] & 0x7F) as usize;
+        if length_octets == 0 || length_octets > 4 || bytes.len() < 2 + length_octets {
+            return false;
+        }
+        let content_length = bytes[2..2 + length_octets]
+            .iter()
+            .fold(0usize, |length, &byte| (length << 8) | byte as usize);
+        (2 + length_octets, content_length)
+    };
+
+    header_length + content_length == bytes.len()
+}
@@ -7,9 +7,10 @@ use chain_core::state::account::{
 };
 use chain_core::tx::{TransactionId, TxAux, TxPublicAux};
 use chain_tx_validation::witness::verify_tx_recover_address;
-use parity_scale_codec::{Decode, Encode};
+use client_common::{PrivateKey, PublicKey};
+use parity_scale_codec::Decode;
 
-use crate::error::ClientErrorNeonExt;
+use crate::error::{ClientErrorNeonExt, ErrorCode};
 use crate::function_types::*;
 use crate::signer::KeyPairSigner;
 use crate::tx_aux::tx_aux_to_hex;
@@ -20,12 +21,7 @@ pub fn build_raw_unjail_transaction(mut ctx: FunctionContext) -> JsResult<JsObje
 
     let tx = UnjailTx::new(options.0.nonce, options.0.staking_address, attributes);
 
-    let raw_tx = tx.encode();
-    let mut raw_tx_buffer = ctx.buffer(raw_tx.len() as u32)?;
-    ctx.borrow_mut(&mut raw_tx_buffer, |data| {
-        let data = data.as_mut_slice();
-        data.copy_from_slice(&raw_tx)
-    });
+    let raw_tx_buffer = encode_to_buffer(&mut ctx, &tx)?;
 
     let tx_id = tx.id();
     let tx_id = ctx.string(hex::encode(tx_id));
@@ -44,24 +40,76 @@ pub fn build_raw_unjail_transaction(mut ctx: FunctionContext) -> JsResult<JsObje
 pub fn unjail_transaction_to_hex(mut ctx: FunctionContext) -> JsResult<JsBuffer> {
     let unjail_tx = unjail_tx_argument(&mut ctx, 0)?;
     let key_pair = key_pair_argument(&mut ctx, 1)?;
+
+    let tx_aux = sign_unjail_tx(&mut ctx, unjail_tx, key_pair)?;
+
+    tx_aux_to_hex(&mut ctx, tx_aux)
+}
+
+/// build and sign unjail transaction in one go
+/// @arguments
+/// - options: { stakingAddress, nonce, network | chainHexId }, where nonce is
+///     the current nonce of the jailed validator staking account
+/// - key_pair: key pair of the staking address
+/// @return broadcast-able UnjailTx
+pub fn build_unjail_tx(mut ctx: FunctionContext) -> JsResult<JsBuffer> {
+    let options = BuildNodeJoinTransactionOptions::argument(&mut ctx, 0)?;
+    let key_pair = key_pair_argument(&mut ctx, 1)?;
+    let attributes = StakedStateOpAttributes::new(options.0.chain_hex_id);
+
+    let unjail_tx = UnjailTx::new(options.0.nonce, options.0.staking_address, attributes);
+
+    let tx_aux = sign_unjail_tx(&mut ctx, unjail_tx, key_pair)?;
+
+    tx_aux_to_hex(&mut ctx, tx_aux)
+}
+
+/// attach externally produced witness to unjail transaction
+/// @arguments
+/// - unsigned_raw_tx: raw UnjailTx Buffer
+/// - witness: StakedStateOpWitness of the transaction id
+/// @return broadcast-able UnjailTx
+pub fn unjail_transaction_with_witness_to_hex(mut ctx: FunctionContext) -> JsResult<JsBuffer> {
+    let unjail_tx = unjail_tx_argument(&mut ctx, 0)?;
+    let witness = ctx.argument::<JsValue>(1)?;
+    let witness = parse_staked_state_op_witness(&mut ctx, witness)?;
+
+    let address = verify_tx_recover_address(&witness, &unjail_tx.id())
+        .chain_neon(&mut ctx, "Invalid witness")?;
+    if address != unjail_tx.address {
+        return ctx.throw_error("Witness is not signed by the jailed staking address");
+    }
+
+    let tx_aux = TxAux::PublicTx(TxPublicAux::UnjailTx(unjail_tx, witness));
+
+    tx_aux_to_hex(&mut ctx, tx_aux)
+}
+
+fn sign_unjail_tx(
+    ctx: &mut FunctionContext,
+    unjail_tx: UnjailTx,
+    key_pair: (PrivateKey, PublicKey),
+) -> NeonResult<TxAux> {
     let signer = KeyPairSigner::new(key_pair.0, key_pair.1)
-        .chain_neon(&mut ctx, "Unable to create KeyPair signer")?;
+        .chain_neon(ctx, "Unable to create KeyPair signer")?;
 
     let signature = signer
         .sign(&unjail_tx.id())
         .map(StakedStateOpWitness::new)
-        .chain_neon(&mut ctx, "Error when signing transaction")?;
+        .chain_neon(ctx, "Error when signing transaction")?;
 
-    let tx_aux = TxAux::PublicTx(TxPublicAux::UnjailTx(unjail_tx, signature));
-
-    tx_aux_to_hex(&mut ctx, tx_aux)
+    Ok(TxAux::PublicTx(TxPublicAux::UnjailTx(unjail_tx, signature)))
 }
 
 fn unjail_tx_argument(ctx: &mut FunctionContext, i: i32) -> NeonResult<UnjailTx> {
     let unjail_tx = ctx.argument::<JsBuffer>(i)?;
     let mut unjail_tx = unjail_tx.borrow(&ctx.lock()).as_slice();
 
-    UnjailTx::decode(&mut unjail_tx).chain_neon(ctx, "Unable to decode raw transaction bytes")
+    UnjailTx::decode(&mut unjail_tx).chain_neon_with_code(
+        ctx,
+        ErrorCode::DeserializationError,
+        "Unable to decode raw transaction bytes",
+    )
 }
 
 pub fn verify_unjail_tx_aux(mut ctx: FunctionContext) -> JsResult<JsUndefined> {
@@ -78,8 +126,11 @@ pub fn verify_unjail_tx_aux(mut ctx: FunctionContext) -> JsResult<JsUndefined> {
         return ctx.throw_error("Mismatch chain hex id");
     }
 
-    let address = verify_tx_recover_address(&witness, &unjail_tx.id())
-        .chain_neon(&mut ctx, "Invalid signature")?;
+    let address = verify_tx_recover_address(&witness, &unjail_tx.id()).chain_neon_with_code(
+        &mut ctx,
+        ErrorCode::InvalidSignature,
+        "Invalid signature",
+    )?;
     if address != assertions.0.staking_address {
         return ctx.throw_error("Incorrect signature");
     }
@@ -94,8 +145,11 @@ fn unjail_tx_aux_argument(
     let unjail_tx_aux = ctx.argument::<JsBuffer>(i)?;
     let mut unjail_tx_aux = unjail_tx_aux.borrow(&ctx.lock()).as_slice();
 
-    let tx_aux =
-        TxAux::decode(&mut unjail_tx_aux).chain_neon(ctx, "Unable to decode transaction bytes")?;
+    let tx_aux = TxAux::decode(&mut unjail_tx_aux).chain_neon_with_code(
+        ctx,
+        ErrorCode::DeserializationError,
+        "Unable to decode transaction bytes",
+    )?;
     match tx_aux {
         TxAux::PublicTx(TxPublicAux::UnjailTx(unjail_tx, staked_state_op_witness)) => {
             Ok((unjail_tx, staked_state_op_witness))
@@ -108,9 +162,11 @@ struct UnjailTxAssertion(UnjailTxProps);
 
 impl UnjailTxAssertion {
     fn argument(ctx: &mut FunctionContext, i: i32) -> NeonResult<UnjailTxAssertion> {
-        let assertion = ctx
-            .argument::<JsObject>(i)
-            .chain_neon(ctx, "Unable to deserialize assertion object")?;
+        let assertion = ctx.argument::<JsObject>(i).chain_neon_with_code(
+            ctx,
+            ErrorCode::DeserializationError,
+            "Unable to deserialize assertion object",
+        )?;
 
         let unjail_tx_props = UnjailTxProps::parse(ctx, assertion)?;
 
@@ -122,9 +178,11 @@ struct BuildNodeJoinTransactionOptions(UnjailTxProps);
 
 impl BuildNodeJoinTransactionOptions {
     fn argument(ctx: &mut FunctionContext, i: i32) -> NeonResult<BuildNodeJoinTransactionOptions> {
-        let options = ctx
-            .argument::<JsObject>(i)
-            .chain_neon(ctx, "Unable to deserialize options object")?;
+        let options = ctx.argument::<JsObject>(i).chain_neon_with_code(
+            ctx,
+            ErrorCode::DeserializationError,
+            "Unable to deserialize options object",
+        )?;
 
         let unjail_tx_props = UnjailTxProps::parse(ctx, options)?;
 
@@ -148,8 +206,11 @@ impl UnjailTxProps {
             .downcast_or_throw::<JsString, FunctionContext>(ctx)
             .chain_neon(ctx, "Unable to downcast stakingAddress")?
             .value();
-        let staking_address = StakedStateAddress::from_str(&staking_address)
-            .chain_neon(ctx, "Unable to deserialize stakingAddress")?;
+        let staking_address = StakedStateAddress::from_str(&staking_address).chain_neon_with_code(
+            ctx,
+            ErrorCode::InvalidAddress,
+            "Unable to deserialize stakingAddress",
+        )?;
 
         let nonce = prop_object
             .get(ctx, "nonce")?
@@ -158,12 +219,7 @@ impl UnjailTxProps {
             .value();
         let nonce = parse_account_nonce(ctx, nonce)?;
 
-        let chain_hex_id = prop_object
-            .get(ctx, "chainHexId")?
-            .downcast_or_throw::<JsBuffer, FunctionContext>(ctx)
-            .chain_neon(ctx, "Unable to downcast chainHexId")?;
-        let chain_hex_id = chain_hex_id.borrow(&ctx.lock()).as_slice().to_vec();
-        let chain_hex_id = chain_hex_id_from_vec(ctx, chain_hex_id)?;
+        let chain_hex_id = chain_hex_id_option(ctx, *prop_object)?;
 
         Ok(UnjailTxProps {
             staking_address,
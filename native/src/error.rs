@@ -1,18 +1,202 @@
-use std::fmt::Display;
+use std::any::Any;
+use std::fmt::{self, Display};
 
+use client_common::{Error, ErrorKind};
 use neon::prelude::*;
+use neon::result::Throw;
+
+/// Stable code of the errors thrown to JS, exposed as the `code` property of
+/// the Error so that callers do not depend on the error message wording
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorCode {
+    InvalidArgument,
+    InvalidPrivateKey,
+    InvalidPublicKey,
+    InvalidSignature,
+    InvalidAddress,
+    DeserializationError,
+    SerializationError,
+    EncryptionError,
+    DecryptionError,
+    RpcError,
+    RpcTimeout,
+    ConnectionError,
+    VerificationError,
+    Cancelled,
+    NotFound,
+    InternalError,
+}
+
+impl ErrorCode {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ErrorCode::InvalidArgument => "INVALID_ARGUMENT",
+            ErrorCode::InvalidPrivateKey => "INVALID_PRIVATE_KEY",
+            ErrorCode::InvalidPublicKey => "INVALID_PUBLIC_KEY",
+            ErrorCode::InvalidSignature => "INVALID_SIGNATURE",
+            ErrorCode::InvalidAddress => "INVALID_ADDRESS",
+            ErrorCode::DeserializationError => "DESERIALIZATION_ERROR",
+            ErrorCode::SerializationError => "SERIALIZATION_ERROR",
+            ErrorCode::EncryptionError => "ENCRYPTION_ERROR",
+            ErrorCode::DecryptionError => "DECRYPTION_ERROR",
+            ErrorCode::RpcError => "RPC_ERROR",
+            ErrorCode::RpcTimeout => "RPC_TIMEOUT",
+            ErrorCode::ConnectionError => "CONNECTION_ERROR",
+            ErrorCode::VerificationError => "VERIFICATION_ERROR",
+            ErrorCode::Cancelled => "CANCELLED",
+            ErrorCode::NotFound => "NOT_FOUND",
+            ErrorCode::InternalError => "INTERNAL_ERROR",
+        }
+    }
+}
+
+impl From<ErrorKind> for ErrorCode {
+    fn from(kind: ErrorKind) -> ErrorCode {
+        match kind {
+            ErrorKind::InvalidInput => ErrorCode::InvalidArgument,
+            ErrorKind::DeserializationError => ErrorCode::DeserializationError,
+            ErrorKind::SerializationError => ErrorCode::SerializationError,
+            ErrorKind::EncryptionError => ErrorCode::EncryptionError,
+            ErrorKind::DecryptionError => ErrorCode::DecryptionError,
+            ErrorKind::TendermintRpcError => ErrorCode::RpcError,
+            ErrorKind::ConnectionError => ErrorCode::ConnectionError,
+            ErrorKind::VerifyError => ErrorCode::VerificationError,
+            _ => ErrorCode::InternalError,
+        }
+    }
+}
+
+/// Source of the client errors which carry the code they are thrown to JS
+/// with, e.g. timeouts and cancellations which have no error kind of their own
+#[derive(Debug, Clone, Copy)]
+pub struct CodedError(pub ErrorCode);
+
+impl Display for CodedError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.0.as_str())
+    }
+}
+
+impl std::error::Error for CodedError {}
+
+/// Client error of the kind, thrown to JS with the code
+pub fn coded_client_error<M: Display>(kind: ErrorKind, code: ErrorCode, message: M) -> Error {
+    Error::new_with_source(kind, message.to_string(), Box::new(CodedError(code)))
+}
+
+/// Returns the code carried by the client error or its sources
+pub fn carried_error_code(err: &Error) -> Option<ErrorCode> {
+    let mut source = std::error::Error::source(err);
+    while let Some(err) = source {
+        if let Some(CodedError(code)) = err.downcast_ref::<CodedError>() {
+            return Some(*code);
+        }
+        source = err.source();
+    }
+
+    None
+}
+
+/// Error thrown to JS as an Error object with `code` and `details`
+/// properties. `details` is the message of the source error, if any
+#[derive(Debug, Clone)]
+pub struct NativeError {
+    pub code: ErrorCode,
+    pub message: String,
+    pub details: Option<String>,
+}
+
+impl NativeError {
+    pub fn new<M: Display>(code: ErrorCode, message: M) -> NativeError {
+        NativeError {
+            code,
+            message: message.to_string(),
+            details: None,
+        }
+    }
+
+    /// Adds given context message to the client error. The code is the one
+    /// carried by the error, or the one of its kind
+    pub fn from_client_error<M: Display>(message: M, err: &Error) -> NativeError {
+        let code = carried_error_code(err).unwrap_or_else(|| ErrorCode::from(err.kind()));
+
+        NativeError::with_source(message.to_string(), code, err.to_string())
+    }
+
+    fn with_source(message: String, code: ErrorCode, details: String) -> NativeError {
+        NativeError {
+            code,
+            message: format!("{}: {}", message, details),
+            details: Some(details),
+        }
+    }
+
+    pub fn with_code(mut self, code: ErrorCode) -> NativeError {
+        self.code = code;
+        self
+    }
+
+    pub fn throw<'a, C, T>(&self, ctx: &mut C) -> NeonResult<T>
+    where
+        C: Context<'a>,
+    {
+        let error = self.to_js_error(ctx)?;
+
+        ctx.throw(error)
+    }
+
+    /// Returns the Error object without throwing it, e.g. to pass it to a
+    /// callback
+    pub fn to_js_error<'a, C>(&self, ctx: &mut C) -> JsResult<'a, JsError>
+    where
+        C: Context<'a>,
+    {
+        let error = JsError::error(ctx, &self.message)?;
+        let code = ctx.string(self.code.as_str());
+        error.set(ctx, "code", code)?;
+        if let Some(details) = &self.details {
+            let details = ctx.string(details);
+            error.set(ctx, "details", details)?;
+        }
+
+        Ok(error)
+    }
+}
+
+/// Throws an Error with the given code and message
+pub fn throw_coded_error<'a, C, M, T>(ctx: &mut C, code: ErrorCode, message: M) -> NeonResult<T>
+where
+    C: Context<'a>,
+    M: Display,
+{
+    NativeError::new(code, message).throw(ctx)
+}
 
 pub trait ClientErrorNeonExt<T> {
-    /// Adds given error kind and message to source error
+    /// Adds given error kind and message to source error. The code is the
+    /// one of the client error, INVALID_ARGUMENT for the errors of reading
+    /// JS values and missing values, INTERNAL_ERROR otherwise
     fn chain_neon<'a, C, M>(self, ctx: &mut C, message: M) -> NeonResult<T>
     where
         C: Context<'a>,
         M: Display;
+
+    /// Same as `chain_neon`, thrown with the code unless the client error
+    /// carries one
+    fn chain_neon_with_code<'a, C, M>(
+        self,
+        ctx: &mut C,
+        code: ErrorCode,
+        message: M,
+    ) -> NeonResult<T>
+    where
+        C: Context<'a>,
+        M: Display;
 }
 
 impl<T, E> ClientErrorNeonExt<T> for Result<T, E>
 where
-    E: Display,
+    E: Display + 'static,
 {
     #[inline]
     fn chain_neon<'a, C, M>(self, ctx: &mut C, message: M) -> NeonResult<T>
@@ -21,7 +205,44 @@ where
         M: Display,
     {
         match self {
-            Err(err) => ctx.throw_error(format!("{}: {}", message, err)),
+            Err(err) => {
+                if let Some(err) = (&err as &dyn Any).downcast_ref::<Error>() {
+                    return NativeError::from_client_error(message, err).throw(ctx);
+                }
+                let code = if (&err as &dyn Any).is::<Throw>() {
+                    ErrorCode::InvalidArgument
+                } else {
+                    ErrorCode::InternalError
+                };
+
+                NativeError::with_source(message.to_string(), code, err.to_string()).throw(ctx)
+            }
+            Ok(v) => Ok(v),
+        }
+    }
+
+    #[inline]
+    fn chain_neon_with_code<'a, C, M>(
+        self,
+        ctx: &mut C,
+        code: ErrorCode,
+        message: M,
+    ) -> NeonResult<T>
+    where
+        C: Context<'a>,
+        M: Display,
+    {
+        match self {
+            Err(err) => {
+                // Timeouts and cancellations carry their code whatever the
+                // subject of the context
+                let code = (&err as &dyn Any)
+                    .downcast_ref::<Error>()
+                    .and_then(carried_error_code)
+                    .unwrap_or(code);
+
+                NativeError::with_source(message.to_string(), code, err.to_string()).throw(ctx)
+            }
             Ok(v) => Ok(v),
         }
     }
@@ -30,12 +251,26 @@ where
 impl<T> ClientErrorNeonExt<T> for Option<T> {
     #[inline]
     fn chain_neon<'a, C, M>(self, ctx: &mut C, message: M) -> NeonResult<T>
+    where
+        C: Context<'a>,
+        M: Display,
+    {
+        self.chain_neon_with_code(ctx, ErrorCode::InvalidArgument, message)
+    }
+
+    #[inline]
+    fn chain_neon_with_code<'a, C, M>(
+        self,
+        ctx: &mut C,
+        code: ErrorCode,
+        message: M,
+    ) -> NeonResult<T>
     where
         C: Context<'a>,
         M: Display,
     {
         match self {
-            None => ctx.throw_error(format!("{}", message)),
+            None => throw_coded_error(ctx, code, message),
             Some(v) => Ok(v),
         }
     }
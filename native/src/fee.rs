@@ -0,0 +1,145 @@
+use chain_core::tx::fee::{FeeAlgorithm, LinearFee};
+use neon::prelude::*;
+use serde::Serialize;
+
+use crate::common::serialize_to_js;
+use crate::error::{ClientErrorNeonExt, ErrorCode};
+use crate::function_types::*;
+use crate::transfer_transaction::{
+    estimate_tx_size, incomplete_builder_linear_fee_argument,
+    incomplete_builder_linear_fee_config_argument,
+};
+
+/// estimate LinearFee of a transaction
+/// @arguments
+/// - tx_size_or_builder: transaction size in bytes, serialized transaction
+///     Buffer or incomplete transfer builder object
+///     ({ incompleteHex, feeConfig })
+/// - fee_config: LinearFee configuration, the feeConfig of the builder object
+///     is used instead when given a builder
+/// @return fee in basic unit
+pub fn estimate(mut ctx: FunctionContext) -> JsResult<JsString> {
+    let tx_size_or_builder = ctx.argument::<JsValue>(0)?;
+
+    let fee = if tx_size_or_builder.is_a::<JsBuffer>() || tx_size_or_builder.is_a::<JsNumber>() {
+        let tx_size = tx_size_argument(&mut ctx, 0)?;
+        let linear_fee = linear_fee_argument(&mut ctx, 1)?;
+        linear_fee
+            .calculate_fee(tx_size)
+            .chain_neon(&mut ctx, "Unable to calculate fee")?
+    } else if tx_size_or_builder.is_a::<JsObject>() {
+        let builder = incomplete_builder_linear_fee_argument(&mut ctx, 0)?;
+        builder
+            .estimate_fee()
+            .chain_neon(&mut ctx, "Unable to estimate transaction fee")?
+    } else {
+        return ctx.throw_error(
+            "Expected transaction size number, serialized transaction Buffer or builder object",
+        );
+    };
+
+    let fee = serde_json::to_string(&fee).chain_neon_with_code(
+        &mut ctx,
+        ErrorCode::SerializationError,
+        "Unable to serialize estimated fee to string",
+    )?;
+
+    Ok(ctx.string(fee.trim_matches('"')))
+}
+
+/// LinearFee of a transaction broken down into its constant and size parts
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct FeeBreakdown {
+    tx_size: usize,
+    /// Fee in basic unit charged regardless of the transaction size
+    constant_fee: String,
+    /// Fee in basic unit charged for the transaction size
+    size_fee: String,
+    fee: String,
+}
+
+/// break down LinearFee of a transaction into its constant and size parts
+/// @arguments
+/// - tx_size_or_builder: transaction size in bytes, serialized transaction
+///     Buffer or incomplete transfer builder object
+///     ({ incompleteHex, feeConfig }) whose transaction size is estimated
+/// - fee_config: LinearFee configuration, see `estimate`
+/// @return { txSize, constantFee, sizeFee, fee } with fees in basic unit
+pub fn breakdown(mut ctx: FunctionContext) -> JsResult<JsValue> {
+    let tx_size_or_builder = ctx.argument::<JsValue>(0)?;
+
+    let (tx_size, linear_fee) =
+        if tx_size_or_builder.is_a::<JsBuffer>() || tx_size_or_builder.is_a::<JsNumber>() {
+            let tx_size = tx_size_argument(&mut ctx, 0)?;
+            (tx_size, linear_fee_argument(&mut ctx, 1)?)
+        } else if tx_size_or_builder.is_a::<JsObject>() {
+            let builder = incomplete_builder_linear_fee_argument(&mut ctx, 0)?;
+            let tx_size = estimate_tx_size(&builder)
+                .chain_neon(&mut ctx, "Unable to estimate transaction size")?;
+            (
+                tx_size,
+                incomplete_builder_linear_fee_config_argument(&mut ctx, 0)?,
+            )
+        } else {
+            return ctx.throw_error(
+                "Expected transaction size number, serialized transaction Buffer or builder object",
+            );
+        };
+
+    let constant_fee = u64::from(
+        linear_fee
+            .calculate_fee(0)
+            .chain_neon(&mut ctx, "Unable to calculate fee")?
+            .to_coin(),
+    );
+    let fee = u64::from(
+        linear_fee
+            .calculate_fee(tx_size)
+            .chain_neon(&mut ctx, "Unable to calculate fee")?
+            .to_coin(),
+    );
+
+    let fee_breakdown = FeeBreakdown {
+        tx_size,
+        constant_fee: constant_fee.to_string(),
+        size_fee: (fee - constant_fee).to_string(),
+        fee: fee.to_string(),
+    };
+
+    serialize_to_js(&mut ctx, &fee_breakdown)
+}
+
+/// Returns the transaction size in bytes of the size number or serialized
+/// transaction Buffer at argument `i`
+fn tx_size_argument(ctx: &mut FunctionContext, i: i32) -> NeonResult<usize> {
+    if ctx.argument::<JsValue>(i)?.is_a::<JsBuffer>() {
+        return Ok(u8_buffer_argument(ctx, i)?.len());
+    }
+
+    let tx_size = ctx.argument::<JsNumber>(i)?.value();
+    if tx_size < 0.0 || tx_size.fract() != 0.0 {
+        return ctx.throw_error("Transaction size should be a non-negative integer");
+    }
+
+    Ok(tx_size as usize)
+}
+
+#[inline]
+fn linear_fee_argument(ctx: &mut FunctionContext, i: i32) -> NeonResult<LinearFee> {
+    let fee_config = ctx.argument::<JsObject>(i)?;
+
+    parse_linear_fee_config(ctx, fee_config)
+}
+
+pub fn register_fee_module(ctx: &mut ModuleContext) -> NeonResult<()> {
+    let js_object = JsObject::new(ctx);
+
+    let estimate_fn = JsFunction::new(ctx, estimate)?;
+    js_object.set(ctx, "estimate", estimate_fn)?;
+
+    let breakdown_fn = JsFunction::new(ctx, breakdown)?;
+    js_object.set(ctx, "breakdown", breakdown_fn)?;
+
+    ctx.export_value("fee", js_object)
+}
@@ -1,4 +1,6 @@
+use std::fmt::Display;
 use std::str::FromStr;
+use std::time::{SystemTime, UNIX_EPOCH};
 
 use lazy_static::lazy_static;
 use neon::prelude::*;
@@ -14,40 +16,475 @@ use chain_core::tx::data::output::TxOut;
 use chain_core::tx::data::TxId;
 use chain_core::tx::fee::{LinearFee, Milli};
 use client_common::{PrivateKey, PublicKey};
-use parity_scale_codec::Decode;
+use parity_scale_codec::{Decode, Encode};
+use zeroize::Zeroizing;
 
 use crate::common::does_js_object_has_prop;
-use crate::error::ClientErrorNeonExt;
+use crate::error::{throw_coded_error, ClientErrorNeonExt, ErrorCode};
+use crate::network::{builtin_network_config_from_value, network_config_from_value, NetworkConfig};
 
 lazy_static! {
     static ref MAINNET_CHAIN_HEX_ID: u8 = hex::decode("2A").unwrap()[0];
     static ref TESTNET_CHAIN_HEX_ID: u8 = hex::decode("42").unwrap()[0];
 }
 
+/// SCALE-encode the value directly into a new Buffer of the encoded size,
+/// without going through an intermediate Vec
+#[inline]
+pub fn encode_to_buffer<'a, C: Context<'a>, T: Encode>(
+    ctx: &mut C,
+    value: &T,
+) -> JsResult<'a, JsBuffer> {
+    let mut buffer = ctx.buffer(value.encoded_size() as u32)?;
+    ctx.borrow_mut(&mut buffer, |data| {
+        let mut slice = data.as_mut_slice::<u8>();
+        value.encode_to(&mut slice);
+    });
+
+    Ok(buffer)
+}
+
+/// Copy the bytes into a new Buffer
+#[inline]
+pub fn bytes_to_buffer<'a, C: Context<'a>>(ctx: &mut C, value: &[u8]) -> JsResult<'a, JsBuffer> {
+    let mut buffer = ctx.buffer(value.len() as u32)?;
+    ctx.borrow_mut(&mut buffer, |data| {
+        let slice = data.as_mut_slice();
+        slice.copy_from_slice(value);
+    });
+
+    Ok(buffer)
+}
+
+/// JS array of the strings
+pub fn strings_to_js<'a, C: Context<'a>>(ctx: &mut C, values: &[String]) -> JsResult<'a, JsArray> {
+    let js_values = JsArray::new(ctx, values.len() as u32);
+    for (i, value) in values.iter().enumerate() {
+        let js_value = ctx.string(value);
+        js_values.set(ctx, i as u32, js_value)?;
+    }
+
+    Ok(js_values)
+}
+
+/// SCALE-encode the value into the start of the caller-provided Buffer, so
+/// that encoding in bulk can reuse one Buffer instead of allocating one for
+/// each value. Returns the number of bytes written
+#[inline]
+pub fn encode_into_buffer<'a, C: Context<'a>, T: Encode>(
+    ctx: &mut C,
+    value: &T,
+    mut buffer: Handle<JsBuffer>,
+) -> NeonResult<usize> {
+    let size = value.encoded_size();
+    let capacity = ctx.borrow(&buffer, |data| data.len());
+    if capacity < size {
+        return ctx.throw_error(format!(
+            "Output buffer of {} bytes is too small for {} bytes",
+            capacity, size
+        ));
+    }
+
+    ctx.borrow_mut(&mut buffer, |data| {
+        let mut slice = &mut data.as_mut_slice::<u8>()[..size];
+        value.encode_to(&mut slice);
+    });
+
+    Ok(size)
+}
+
+/// Returns the argument when it is a Buffer, for the functions accepting an
+/// optional output Buffer to encode into
+#[inline]
+pub fn output_buffer_argument<'a>(
+    ctx: &mut FunctionContext<'a>,
+    i: i32,
+) -> NeonResult<Option<Handle<'a, JsBuffer>>> {
+    match ctx.argument_opt(i) {
+        Some(output) if output.is_a::<JsBuffer>() => output
+            .downcast_or_throw::<JsBuffer, FunctionContext>(ctx)
+            .map(Some),
+        _ => Ok(None),
+    }
+}
+
+/// Throws an error naming the argument and what is expected of it, e.g.
+/// "argument 0 (private key): expected 32-byte Buffer, got 31 bytes". The
+/// name is omitted when empty
+pub fn throw_invalid_argument<T, G: Display>(
+    ctx: &mut FunctionContext,
+    code: ErrorCode,
+    i: i32,
+    name: &str,
+    expected: &str,
+    got: G,
+) -> NeonResult<T> {
+    let argument = if name.is_empty() {
+        format!("argument {}", i)
+    } else {
+        format!("argument {} ({})", i, name)
+    };
+
+    throw_coded_error(
+        ctx,
+        code,
+        format!("{}: expected {}, got {}", argument, expected, got),
+    )
+}
+
+/// Describes the type of the value for argument validation errors
+pub fn describe_value(ctx: &mut FunctionContext, value: Handle<JsValue>) -> NeonResult<String> {
+    if let Ok(buffer) = value.downcast::<JsBuffer>() {
+        let len = buffer.borrow(&ctx.lock()).as_slice::<u8>().len();
+        return Ok(format!("{}-byte Buffer", len));
+    }
+
+    let type_name = if value.is_a::<JsUndefined>() {
+        "undefined"
+    } else if value.is_a::<JsNull>() {
+        "null"
+    } else if value.is_a::<JsString>() {
+        "string"
+    } else if value.is_a::<JsNumber>() {
+        "number"
+    } else if value.is_a::<JsBoolean>() {
+        "boolean"
+    } else if value.is_a::<JsArray>() {
+        "array"
+    } else if value.is_a::<JsFunction>() {
+        "function"
+    } else if is_bigint(ctx, value)? {
+        "bigint"
+    } else {
+        "object"
+    };
+
+    Ok(type_name.to_owned())
+}
+
+/// Neon has no BigInt type. BigInt and Symbol are the primitives left after
+/// ruling out the other types, they are told apart by the tag of
+/// Object.prototype.toString
+fn is_bigint(ctx: &mut FunctionContext, value: Handle<JsValue>) -> NeonResult<bool> {
+    if value.is_a::<JsUndefined>()
+        || value.is_a::<JsNull>()
+        || value.is_a::<JsString>()
+        || value.is_a::<JsNumber>()
+        || value.is_a::<JsBoolean>()
+        || value.is_a::<JsObject>()
+    {
+        return Ok(false);
+    }
+
+    let global = ctx.global();
+    let object = global
+        .get(ctx, "Object")?
+        .downcast_or_throw::<JsObject, FunctionContext>(ctx)?;
+    let to_string = object
+        .get(ctx, "prototype")?
+        .downcast_or_throw::<JsObject, FunctionContext>(ctx)?
+        .get(ctx, "toString")?
+        .downcast_or_throw::<JsFunction, FunctionContext>(ctx)?;
+    let args: Vec<Handle<JsValue>> = Vec::new();
+    let tag = to_string
+        .call(ctx, value, args)?
+        .downcast_or_throw::<JsString, FunctionContext>(ctx)?;
+
+    Ok(tag.value() == "[object BigInt]")
+}
+
+/// Returns argument `i` downcasted to `V`, throws an invalid argument error
+/// describing the expected and actual type otherwise
+#[inline]
+pub fn typed_argument<'a, V: Value>(
+    ctx: &mut FunctionContext<'a>,
+    i: i32,
+    name: &str,
+    expected: &str,
+) -> JsResult<'a, V> {
+    let value = match ctx.argument_opt(i) {
+        Some(value) => value,
+        None => {
+            return throw_invalid_argument(
+                ctx,
+                ErrorCode::InvalidArgument,
+                i,
+                name,
+                expected,
+                "nothing",
+            )
+        }
+    };
+    if !value.is_a::<V>() {
+        let got = describe_value(ctx, value)?;
+        return throw_invalid_argument(ctx, ErrorCode::InvalidArgument, i, name, expected, got);
+    }
+
+    value.downcast_or_throw::<V, FunctionContext>(ctx)
+}
+
+/// Decodes bare or 0x-prefixed hex string
+#[inline]
+pub fn decode_hex(value: &str) -> Result<Vec<u8>, hex::FromHexError> {
+    if value.starts_with("0x") || value.starts_with("0X") {
+        hex::decode(&value[2..])
+    } else {
+        hex::decode(value)
+    }
+}
+
+/// Returns the bytes of the Buffer or hex string value, so that keys and
+/// signatures stored as hex can be passed as is. Describes the value when it
+/// is neither
+pub fn bytes_from_value(
+    ctx: &mut FunctionContext,
+    value: Handle<JsValue>,
+) -> NeonResult<Result<Vec<u8>, String>> {
+    if let Ok(buffer) = value.downcast::<JsBuffer>() {
+        return Ok(Ok(buffer.borrow(&ctx.lock()).as_slice::<u8>().to_vec()));
+    }
+    if let Ok(hex_str) = value.downcast::<JsString>() {
+        return Ok(
+            decode_hex(&hex_str.value()).map_err(|err| format!("invalid hex string ({})", err))
+        );
+    }
+
+    Ok(Err(describe_value(ctx, value)?))
+}
+
+/// Returns the bytes of the Buffer or hex string argument
+#[inline]
+pub fn bytes_argument(
+    ctx: &mut FunctionContext,
+    i: i32,
+    name: &str,
+    expected: &str,
+) -> NeonResult<Vec<u8>> {
+    let value = typed_argument::<JsValue>(ctx, i, name, expected)?;
+
+    match bytes_from_value(ctx, value)? {
+        Ok(bytes) => Ok(bytes),
+        Err(got) => throw_invalid_argument(ctx, ErrorCode::InvalidArgument, i, name, expected, got),
+    }
+}
+
+/// Returns the bytes of the Buffer or hex string property value
+#[inline]
+pub fn parse_bytes(
+    ctx: &mut FunctionContext,
+    value: Handle<JsValue>,
+    name: &str,
+) -> NeonResult<Vec<u8>> {
+    match bytes_from_value(ctx, value)? {
+        Ok(bytes) => Ok(bytes),
+        Err(got) => throw_coded_error(
+            ctx,
+            ErrorCode::InvalidArgument,
+            format!("{}: expected Buffer or hex string, got {}", name, got),
+        ),
+    }
+}
+
+/// Returns the bytes of the Buffer or hex string argument, throws with
+/// `code` when it is not exactly `len` bytes long
+#[inline]
+pub fn fixed_buffer_argument(
+    ctx: &mut FunctionContext,
+    i: i32,
+    name: &str,
+    code: ErrorCode,
+    len: usize,
+) -> NeonResult<Vec<u8>> {
+    let expected = format!("{}-byte Buffer", len);
+    let buffer = bytes_argument(ctx, i, name, &expected)?;
+    if buffer.len() != len {
+        return throw_invalid_argument(
+            ctx,
+            code,
+            i,
+            name,
+            &expected,
+            format!("{} bytes", buffer.len()),
+        );
+    }
+
+    Ok(buffer)
+}
+
+/// Returns the bytes of the Buffer argument. Strings are rejected rather than
+/// taken as hex, arguments which are often kept as hex, i.e. keys,
+/// signatures and transaction ids, opt in with `hex_or_buffer_argument`
 #[inline]
 pub fn u8_buffer_argument(ctx: &mut FunctionContext, i: i32) -> NeonResult<Vec<u8>> {
-    let buffer = ctx.argument::<JsBuffer>(i)?;
+    let buffer = typed_argument::<JsBuffer>(ctx, i, "", "Buffer")?;
+    let bytes = buffer.borrow(&ctx.lock()).as_slice::<u8>().to_vec();
 
-    Ok(ctx.borrow(&buffer, |data| data.as_slice::<u8>().to_vec()))
+    Ok(bytes)
 }
 
+/// Returns the bytes of the Buffer or hex string argument
 #[inline]
-pub fn h256_str_argument(ctx: &mut FunctionContext, i: i32) -> NeonResult<H256> {
-    let hash_str = ctx
-        .argument::<JsString>(i)?
-        .downcast_or_throw::<JsString, FunctionContext>(ctx)
-        .chain_neon(ctx, "Unable to downcast h256 argument")?
-        .value();
+pub fn hex_or_buffer_argument(
+    ctx: &mut FunctionContext,
+    i: i32,
+    name: &str,
+) -> NeonResult<Vec<u8>> {
+    bytes_argument(ctx, i, name, "Buffer or hex string")
+}
+
+#[inline]
+pub fn optional_string_argument(ctx: &mut FunctionContext, i: i32) -> NeonResult<Option<String>> {
+    match ctx.argument_opt(i) {
+        Some(value) if value.is_a::<JsString>() => {
+            let value = value
+                .downcast_or_throw::<JsString, FunctionContext>(ctx)
+                .chain_neon(ctx, "Unable to downcast string argument")?
+                .value();
+            Ok(Some(value))
+        }
+        Some(value) if value.is_a::<JsUndefined>() || value.is_a::<JsNull>() => Ok(None),
+        Some(value) => {
+            let got = describe_value(ctx, value)?;
+            throw_invalid_argument(ctx, ErrorCode::InvalidArgument, i, "", "string", got)
+        }
+        None => Ok(None),
+    }
+}
+
+/// Largest integer a JS number represents exactly, Number.MAX_SAFE_INTEGER
+pub const MAX_SAFE_INTEGER: f64 = 9_007_199_254_740_991.0;
+
+const EXPECTED_AMOUNT: &str = "amount string or BigInt in basic unit";
+
+/// Returns the Coin of the amount in basic unit, which can be a decimal
+/// string, a BigInt or a safe integer number. Numbers beyond
+/// Number.MAX_SAFE_INTEGER are rejected as they may have lost precision.
+/// Describes the value when it is not a valid amount
+fn coin_from_value(
+    ctx: &mut FunctionContext,
+    value: Handle<JsValue>,
+) -> NeonResult<Result<Coin, String>> {
+    let amount = if let Ok(amount) = value.downcast::<JsString>() {
+        amount.value()
+    } else if let Ok(amount) = value.downcast::<JsNumber>() {
+        let amount = amount.value();
+        if amount < 0.0 || amount.fract() != 0.0 || amount > MAX_SAFE_INTEGER {
+            return Ok(Err(format!(
+                "number {} which is not a safe integer",
+                amount
+            )));
+        }
+        (amount as u64).to_string()
+    } else if is_bigint(ctx, value)? {
+        value.to_string(ctx)?.value()
+    } else {
+        return Ok(Err(describe_value(ctx, value)?));
+    };
+
+    if amount.is_empty() || !amount.bytes().all(|byte| byte.is_ascii_digit()) {
+        return Ok(Err(format!("\"{}\"", amount)));
+    }
+
+    Ok(Coin::from_str(&amount).map_err(|err| format!("{} ({})", amount, err)))
+}
+
+/// Returns the Coin of the amount argument in basic unit, see
+/// `coin_from_value` for the accepted values
+pub fn coin_argument(ctx: &mut FunctionContext, i: i32, name: &str) -> NeonResult<Coin> {
+    let value = match ctx.argument_opt(i) {
+        Some(value) => value,
+        None => {
+            return throw_invalid_argument(
+                ctx,
+                ErrorCode::InvalidArgument,
+                i,
+                name,
+                EXPECTED_AMOUNT,
+                "nothing",
+            )
+        }
+    };
+
+    match coin_from_value(ctx, value)? {
+        Ok(coin) => Ok(coin),
+        Err(got) => throw_invalid_argument(
+            ctx,
+            ErrorCode::InvalidArgument,
+            i,
+            name,
+            EXPECTED_AMOUNT,
+            got,
+        ),
+    }
+}
 
-    let decoded_hash_array =
-        hex::decode(&hash_str).chain_neon(ctx, "input hash is not a valid hex string")?;
+/// Returns the Coin of the amount property value in basic unit, see
+/// `coin_from_value` for the accepted values
+pub fn coin_property(
+    ctx: &mut FunctionContext,
+    object: Handle<JsObject>,
+    key: &str,
+) -> NeonResult<Coin> {
+    let value = object.get(ctx, key)?;
+
+    match coin_from_value(ctx, value)? {
+        Ok(coin) => Ok(coin),
+        Err(got) => throw_coded_error(
+            ctx,
+            ErrorCode::InvalidArgument,
+            format!("{}: expected {}, got {}", key, EXPECTED_AMOUNT, got),
+        ),
+    }
+}
+
+/// Returns the last argument when it is a callback function. Functions that
+/// accept a trailing callback run on a background thread when it is provided
+#[inline]
+pub fn callback_argument(ctx: &mut FunctionContext) -> NeonResult<Option<Handle<JsFunction>>> {
+    let len = ctx.len();
+    if len == 0 {
+        return Ok(None);
+    }
+
+    let callback = ctx.argument::<JsValue>(len - 1)?;
+    if !callback.is_a::<JsFunction>() {
+        return Ok(None);
+    }
+
+    callback
+        .downcast_or_throw::<JsFunction, FunctionContext>(ctx)
+        .map(Some)
+}
+
+/// Returns the callback function of the last argument, required by the
+/// functions which only run on a background thread, e.g. the network calls
+#[inline]
+pub fn required_callback_argument(ctx: &mut FunctionContext) -> NeonResult<Handle<JsFunction>> {
+    match callback_argument(ctx)? {
+        Some(callback) => Ok(callback),
+        None => throw_coded_error(
+            ctx,
+            ErrorCode::InvalidArgument,
+            "callback: expected node-style callback function as the last argument, \
+             network calls only run on background thread",
+        ),
+    }
+}
+
+#[inline]
+pub fn h256_str_argument(ctx: &mut FunctionContext, i: i32) -> NeonResult<H256> {
+    let expected = "hex string of 32 bytes";
+    let decoded_hash_array = bytes_argument(ctx, i, "hash", expected)?;
 
     if decoded_hash_array.len() != HASH_SIZE_256 {
-        return ctx.throw_error(format!(
-            "input hash should be a hex string of 32 bytes, {:?} is {} bytes",
-            decoded_hash_array,
-            decoded_hash_array.len()
-        ));
+        return throw_invalid_argument(
+            ctx,
+            ErrorCode::InvalidArgument,
+            i,
+            "hash",
+            expected,
+            format!("{} bytes", decoded_hash_array.len()),
+        );
     }
 
     let mut h256_hash: H256 = [0; HASH_SIZE_256];
@@ -58,16 +495,7 @@ pub fn h256_str_argument(ctx: &mut FunctionContext, i: i32) -> NeonResult<H256>
 
 #[inline]
 pub fn h256_buffer_argument(ctx: &mut FunctionContext, i: i32) -> NeonResult<H256> {
-    let buffer = ctx.argument::<JsBuffer>(i)?;
-    let buffer = ctx.borrow(&buffer, |data| data.as_slice::<u8>().to_vec());
-
-    if buffer.len() != HASH_SIZE_256 {
-        return ctx.throw_error(format!(
-            "input hash should be a hex string of 32 bytes, {:?} is {} bytes",
-            buffer,
-            buffer.len()
-        ));
-    }
+    let buffer = fixed_buffer_argument(ctx, i, "hash", ErrorCode::InvalidArgument, HASH_SIZE_256)?;
 
     let mut h256_hash: H256 = [0; HASH_SIZE_256];
     h256_hash.copy_from_slice(&buffer);
@@ -77,55 +505,113 @@ pub fn h256_buffer_argument(ctx: &mut FunctionContext, i: i32) -> NeonResult<H25
 
 #[inline]
 pub fn public_key_argument(ctx: &mut FunctionContext, i: i32) -> NeonResult<PublicKey> {
-    let public_key = ctx.argument::<JsBuffer>(i)?;
-    let public_key = ctx.borrow(&public_key, |data| data.as_slice::<u8>());
+    let public_key = public_key_bytes_argument(ctx, i, "public key")?;
 
-    PublicKey::deserialize_from(public_key).chain_neon(ctx, "Unable to deserialize public key")
+    PublicKey::deserialize_from(&public_key).chain_neon_with_code(
+        ctx,
+        ErrorCode::InvalidPublicKey,
+        format!("Unable to deserialize public key of argument {}", i),
+    )
+}
+
+/// Returns the bytes of the public key Buffer argument, throws when it is not
+/// 33 bytes compressed or 65 bytes uncompressed
+#[inline]
+fn public_key_bytes_argument(ctx: &mut FunctionContext, i: i32, name: &str) -> NeonResult<Vec<u8>> {
+    let expected = "33-byte compressed or 65-byte uncompressed public key Buffer";
+    let public_key = bytes_argument(ctx, i, name, expected)?;
+    if public_key.len() != 33 && public_key.len() != 65 {
+        return throw_invalid_argument(
+            ctx,
+            ErrorCode::InvalidPublicKey,
+            i,
+            name,
+            expected,
+            format!("{} bytes", public_key.len()),
+        );
+    }
+
+    Ok(public_key)
 }
 
 #[inline]
 pub fn public_key_vector_argument(ctx: &mut FunctionContext, i: i32) -> NeonResult<Vec<PublicKey>> {
-    let public_key_vector_handle: Handle<JsArray> = ctx.argument::<JsArray>(i)?;
-    let public_key_vector = public_key_vector_handle
-        .downcast_or_throw::<JsArray, FunctionContext>(ctx)
-        .chain_neon(ctx, "Unable to downcast public key array")?
-        .to_vec(ctx)?;
+    let public_key_vector =
+        typed_argument::<JsArray>(ctx, i, "public keys", "array of public key Buffer")?
+            .to_vec(ctx)?;
 
     public_key_vector
         .iter()
-        .map(|&public_key| {
-            let public_key = public_key
-                .downcast_or_throw::<JsBuffer, FunctionContext>(ctx)
-                .chain_neon(ctx, "Unable to downcast public key")?;
-            let public_key = public_key.borrow(&ctx.lock()).as_slice();
+        .enumerate()
+        .map(|(j, &public_key)| {
+            let public_key = match bytes_from_value(ctx, public_key)? {
+                Ok(public_key) => public_key,
+                Err(got) => {
+                    return throw_invalid_argument(
+                        ctx,
+                        ErrorCode::InvalidArgument,
+                        i,
+                        "public keys",
+                        &format!("public key Buffer at index {}", j),
+                        got,
+                    )
+                }
+            };
             let public_key = hex::encode_upper(public_key);
-            PublicKey::from_str(&public_key).chain_neon(ctx, "Unable to deserialize public key")
+            PublicKey::from_str(&public_key).chain_neon_with_code(
+                ctx,
+                ErrorCode::InvalidPublicKey,
+                "Unable to deserialize public key",
+            )
         })
         .collect::<NeonResult<Vec<PublicKey>>>()
 }
 
+/// Returns the required signers of a multisig address at argument `i`,
+/// which should be a positive integer
+pub fn required_signers_argument(ctx: &mut FunctionContext, i: i32) -> NeonResult<usize> {
+    let required_signers = ctx.argument::<JsNumber>(i)?.value();
+    if required_signers < 1.0 || required_signers.fract() != 0.0 {
+        return ctx.throw_error("Required signers should be a positive integer");
+    }
+
+    Ok(required_signers as usize)
+}
+
 #[allow(dead_code)]
 #[inline]
 pub fn view_key_argument(ctx: &mut FunctionContext, i: i32) -> NeonResult<PublicKey> {
-    let view_key = ctx.argument::<JsBuffer>(i)?;
-    let view_key = ctx.borrow(&view_key, |data| data.as_slice::<u8>());
+    let view_key = public_key_bytes_argument(ctx, i, "view key")?;
 
     let view_key = hex::encode_upper(view_key);
 
-    PublicKey::from_str(&view_key).chain_neon(ctx, "Unable to deserialize view key")
+    PublicKey::from_str(&view_key).chain_neon_with_code(
+        ctx,
+        ErrorCode::InvalidPublicKey,
+        "Unable to deserialize view key",
+    )
 }
 
 #[inline]
 pub fn private_key_argument(ctx: &mut FunctionContext, i: i32) -> NeonResult<PrivateKey> {
-    let private_key = ctx.argument::<JsBuffer>(i)?;
-    let private_key = ctx.borrow(&private_key, |data| data.as_slice::<u8>());
+    let private_key = Zeroizing::new(fixed_buffer_argument(
+        ctx,
+        i,
+        "private key",
+        ErrorCode::InvalidPrivateKey,
+        32,
+    )?);
 
-    PrivateKey::deserialize_from(private_key).chain_neon(ctx, "Unable to deserialize private key")
+    PrivateKey::deserialize_from(&private_key).chain_neon_with_code(
+        ctx,
+        ErrorCode::InvalidPrivateKey,
+        format!("Unable to deserialize private key of argument {}", i),
+    )
 }
 
 #[inline]
 pub fn key_pair_argument(ctx: &mut FunctionContext, i: i32) -> NeonResult<(PrivateKey, PublicKey)> {
-    let key_pair = ctx.argument::<JsObject>(i)?;
+    let key_pair = typed_argument::<JsObject>(ctx, i, "key pair", "KeyPair object")?;
 
     parse_key_pair(ctx, key_pair)
 }
@@ -135,21 +621,21 @@ pub fn parse_key_pair(
     ctx: &mut FunctionContext,
     key_pair: Handle<JsObject>,
 ) -> NeonResult<(PrivateKey, PublicKey)> {
-    let public_key = key_pair
-        .get(ctx, "publicKey")?
-        .downcast_or_throw::<JsBuffer, FunctionContext>(ctx)
-        .chain_neon(ctx, "Unable to downcast public key in KeyPair")?;
-    let public_key = public_key.borrow(&ctx.lock()).as_slice();
-    let public_key = PublicKey::deserialize_from(public_key)
-        .chain_neon(ctx, "Unable to deserialize public key in KeyPair")?;
+    let public_key = key_pair.get(ctx, "publicKey")?;
+    let public_key = parse_bytes(ctx, public_key, "publicKey in KeyPair")?;
+    let public_key = PublicKey::deserialize_from(&public_key).chain_neon_with_code(
+        ctx,
+        ErrorCode::InvalidPublicKey,
+        "Unable to deserialize public key in KeyPair",
+    )?;
 
-    let private_key = key_pair
-        .get(ctx, "privateKey")?
-        .downcast_or_throw::<JsBuffer, FunctionContext>(ctx)
-        .chain_neon(ctx, "Unable to downcast private key in KeyPair")?;
-    let private_key = private_key.borrow(&ctx.lock()).as_slice();
-    let private_key = PrivateKey::deserialize_from(private_key)
-        .chain_neon(ctx, "Unable to deserialize private key in KeyPair")?;
+    let private_key = key_pair.get(ctx, "privateKey")?;
+    let private_key = Zeroizing::new(parse_bytes(ctx, private_key, "privateKey in KeyPair")?);
+    let private_key = PrivateKey::deserialize_from(&private_key).chain_neon_with_code(
+        ctx,
+        ErrorCode::InvalidPrivateKey,
+        "Unable to deserialize private key in KeyPair",
+    )?;
 
     Ok((private_key, public_key))
 }
@@ -160,27 +646,32 @@ pub fn staking_address_argument(
     ctx: &mut FunctionContext,
     i: i32,
 ) -> NeonResult<StakedStateAddress> {
-    let staked_state_address = ctx.argument::<JsString>(i)?.value();
+    let staked_state_address =
+        typed_argument::<JsString>(ctx, i, "staking address", "staking address string")?.value();
 
-    StakedStateAddress::from_str(&staked_state_address)
-        .chain_neon(ctx, "Unable to deserialize staking address")
+    StakedStateAddress::from_str(&staked_state_address).chain_neon_with_code(
+        ctx,
+        ErrorCode::InvalidAddress,
+        "Unable to deserialize staking address",
+    )
 }
 
+/// Returns the network from network handle or network name argument, which
+/// should have the built-in prefix of its network type
 #[inline]
 pub fn network_argument(ctx: &mut FunctionContext, i: i32) -> NeonResult<Network> {
-    let network = ctx.argument::<JsString>(i)?.value();
+    let network = typed_argument::<JsValue>(ctx, i, "network", "network handle or name")?;
 
-    network_from_str(ctx, network.as_str())
+    builtin_network_config_from_value(ctx, network).map(|network_config| network_config.network)
 }
 
+/// Returns the network config, including the bech32 human readable part,
+/// from network handle or network name argument
 #[inline]
-pub fn network_from_str(ctx: &mut FunctionContext, network_str: &str) -> NeonResult<Network> {
-    match network_str {
-        "Mainnet" => Ok(Network::Mainnet),
-        "Testnet" => Ok(Network::Testnet),
-        "Devnet" => Ok(Network::Devnet),
-        network => ctx.throw_error(format!("Unrecognized network {}", network)),
-    }
+pub fn network_config_argument(ctx: &mut FunctionContext, i: i32) -> NeonResult<NetworkConfig> {
+    let network = typed_argument::<JsValue>(ctx, i, "network", "network handle or name")?;
+
+    network_config_from_value(ctx, network)
 }
 
 #[inline]
@@ -194,13 +685,60 @@ pub fn network_from_chain_hex_id(chain_hex_id: u8) -> Network {
     }
 }
 
+/// Returns the chain hex id from chain hex id Buffer, network handle or
+/// network name argument
 #[inline]
-#[allow(dead_code)]
 pub fn chain_hex_id_argument(ctx: &mut FunctionContext, i: i32) -> NeonResult<u8> {
-    let chain_hex_id = ctx.argument::<JsBuffer>(i)?;
-    let chain_hex_id = ctx.borrow(&chain_hex_id, |data| data.as_slice::<u8>());
+    let chain_hex_id = typed_argument::<JsValue>(
+        ctx,
+        i,
+        "chain hex id",
+        "1-byte Buffer, network handle or name",
+    )?;
+    if !chain_hex_id.is_a::<JsBuffer>() {
+        return builtin_network_config_from_value(ctx, chain_hex_id)
+            .map(|network_config| network_config.chain_hex_id);
+    }
 
-    chain_hex_id_from_vec(ctx, chain_hex_id.to_vec())
+    let chain_hex_id =
+        fixed_buffer_argument(ctx, i, "chain hex id", ErrorCode::InvalidArgument, 1)?;
+
+    Ok(chain_hex_id[0])
+}
+
+/// Returns the chain hex id from the network handle in the `network` option,
+/// or from the `chainHexId` Buffer option when network is not provided
+#[inline]
+pub fn chain_hex_id_option(ctx: &mut FunctionContext, options: JsObject) -> NeonResult<u8> {
+    let network = options.get(ctx, "network")?;
+    if !network.is_a::<JsUndefined>() {
+        return builtin_network_config_from_value(ctx, network)
+            .map(|network_config| network_config.chain_hex_id);
+    }
+
+    let chain_hex_id = options
+        .get(ctx, "chainHexId")?
+        .downcast_or_throw::<JsBuffer, FunctionContext>(ctx)
+        .chain_neon(ctx, "Unable to downcast chainHexId")?;
+    let chain_hex_id = chain_hex_id.borrow(&ctx.lock()).as_slice().to_vec();
+
+    chain_hex_id_from_vec(ctx, chain_hex_id)
+}
+
+/// Returns the chain hex id and network of the `network` option, or of the
+/// `chainHexId` Buffer option when network is not provided. The network of
+/// an inline network option overrides the one implied by its chain hex id
+#[inline]
+pub fn network_option(ctx: &mut FunctionContext, options: JsObject) -> NeonResult<(u8, Network)> {
+    let network = options.get(ctx, "network")?;
+    if !network.is_a::<JsUndefined>() {
+        return builtin_network_config_from_value(ctx, network)
+            .map(|network_config| (network_config.chain_hex_id, network_config.network));
+    }
+
+    let chain_hex_id = chain_hex_id_option(ctx, options)?;
+
+    Ok((chain_hex_id, network_from_chain_hex_id(chain_hex_id)))
 }
 
 #[inline]
@@ -215,10 +753,14 @@ pub fn chain_hex_id_from_vec(ctx: &mut FunctionContext, chain_hex_id: Vec<u8>) -
 #[inline]
 #[allow(dead_code)]
 pub fn txo_pointer_vec_argument(ctx: &mut FunctionContext, i: i32) -> NeonResult<Vec<TxoPointer>> {
-    let inputs = ctx.argument::<JsArray>(i)?.to_vec(ctx).chain_neon(
-        ctx,
-        "Unable to deserialize transaction output pointer vector",
-    )?;
+    let inputs =
+        typed_argument::<JsArray>(ctx, i, "inputs", "array of transaction output pointer")?
+            .to_vec(ctx)
+            .chain_neon_with_code(
+                ctx,
+                ErrorCode::DeserializationError,
+                "Unable to deserialize transaction output pointer vector",
+            )?;
 
     parse_prev_output_pointer_vec(ctx, inputs)
 }
@@ -240,9 +782,13 @@ pub fn parse_prev_output_pointer_vec(
 }
 
 #[inline]
-#[allow(dead_code)]
 pub fn txo_pointer_argument(ctx: &mut FunctionContext, i: i32) -> NeonResult<TxoPointer> {
-    let input = ctx.argument::<JsObject>(i)?;
+    let input = typed_argument::<JsObject>(
+        ctx,
+        i,
+        "transaction output pointer",
+        "{ prevTxId, prevIndex } object",
+    )?;
 
     parse_prev_output_pointer(ctx, input)
 }
@@ -276,7 +822,11 @@ pub fn parse_prev_output_pointer(
 
 #[inline]
 pub fn txid_from_str(ctx: &mut FunctionContext, tx_id: &str) -> NeonResult<TxId> {
-    let txid = hex::decode(tx_id).chain_neon(ctx, "Unable to deserialize TxId")?;
+    let txid = decode_hex(tx_id).chain_neon_with_code(
+        ctx,
+        ErrorCode::DeserializationError,
+        "Unable to deserialize TxId",
+    )?;
 
     if txid.len() != HASH_SIZE_256 {
         return ctx.throw_error("TxId should be 32 bytes long");
@@ -299,16 +849,13 @@ pub fn parse_output(
         .downcast_or_throw::<JsString, FunctionContext>(ctx)
         .chain_neon(ctx, "Unable to downcast address in output")?
         .value();
-    let address = ExtendedAddr::from_cro(&address, network)
-        .chain_neon(ctx, "Unable to deserialize output address to CRO address")?;
+    let address = ExtendedAddr::from_cro(&address, network).chain_neon_with_code(
+        ctx,
+        ErrorCode::InvalidAddress,
+        "Unable to deserialize output address to CRO address",
+    )?;
 
-    let value = output
-        .get(ctx, "value")?
-        .downcast_or_throw::<JsString, FunctionContext>(ctx)
-        .chain_neon(ctx, "Unable to downcast value in output")?
-        .value();
-    let value =
-        Coin::from_str(&value).chain_neon(ctx, "Unable to deserialize output Coin value")?;
+    let value = coin_property(ctx, output, "value")?;
 
     let valid_from = if does_js_object_has_prop(ctx, output, "validFrom")? {
         let value = output
@@ -316,6 +863,9 @@ pub fn parse_output(
             .downcast_or_throw::<JsNumber, FunctionContext>(ctx)
             .chain_neon(ctx, "Unable to downcast validFrom in output")?
             .value();
+        if value < 0.0 || value.fract() != 0.0 || value > MAX_SAFE_INTEGER {
+            return ctx.throw_error("validFrom in output should be a UNIX timestamp in seconds");
+        }
         Some(value as Timespec)
     } else {
         None
@@ -331,13 +881,17 @@ pub fn parse_output(
 #[inline]
 pub fn parse_view_key(
     ctx: &mut FunctionContext,
-    view_key: Handle<JsBuffer>,
+    view_key: Handle<JsValue>,
 ) -> NeonResult<PublicKey> {
-    let view_key = view_key.borrow(&ctx.lock()).as_slice();
+    let view_key = parse_bytes(ctx, view_key, "viewKey")?;
 
     let view_key = hex::encode_upper(view_key);
 
-    PublicKey::from_str(&view_key).chain_neon(ctx, "Unable to deserialize view key")
+    PublicKey::from_str(&view_key).chain_neon_with_code(
+        ctx,
+        ErrorCode::InvalidPublicKey,
+        "Unable to deserialize view key",
+    )
 }
 
 #[inline]
@@ -348,7 +902,11 @@ pub fn parse_linear_fee_config(
     let algorithm = fee_config
         .get(ctx, "algorithm")?
         .downcast_or_throw::<JsString, FunctionContext>(ctx)
-        .chain_neon(ctx, "Unable to deserialize algorithm in feeConfig")?
+        .chain_neon_with_code(
+            ctx,
+            ErrorCode::DeserializationError,
+            "Unable to deserialize algorithm in feeConfig",
+        )?
         .value();
 
     if algorithm != "LinearFee" {
@@ -358,14 +916,22 @@ pub fn parse_linear_fee_config(
     let constant = fee_config
         .get(ctx, "constant")?
         .downcast_or_throw::<JsString, FunctionContext>(ctx)
-        .chain_neon(ctx, "Unable to deserialize constant in LinearFee config")?
+        .chain_neon_with_code(
+            ctx,
+            ErrorCode::DeserializationError,
+            "Unable to deserialize constant in LinearFee config",
+        )?
         .value();
     let constant =
         Milli::from_str(&constant).chain_neon(ctx, "Invalid constant config in LinearFee")?;
     let coefficient = fee_config
         .get(ctx, "coefficient")?
         .downcast_or_throw::<JsString, FunctionContext>(ctx)
-        .chain_neon(ctx, "Unable to deserialize coefficient in LinearFee config")?
+        .chain_neon_with_code(
+            ctx,
+            ErrorCode::DeserializationError,
+            "Unable to deserialize coefficient in LinearFee config",
+        )?
         .value();
     let coefficient =
         Milli::from_str(&coefficient).chain_neon(ctx, "Invalid coefficient config in LinearFee")?;
@@ -381,11 +947,91 @@ pub fn parse_account_nonce(ctx: &mut FunctionContext, nonce: String) -> NeonResu
 #[inline]
 pub fn parse_staked_state_op_witness(
     ctx: &mut FunctionContext,
-    witness: Handle<JsBuffer>,
+    witness: Handle<JsValue>,
 ) -> NeonResult<StakedStateOpWitness> {
-    let mut witness = witness.borrow(&ctx.lock()).as_slice::<u8>();
-    let witness = StakedStateOpWitness::decode(&mut witness)
-        .chain_neon(ctx, "Unable to decode staked state op witness")?;
+    let witness = parse_bytes(ctx, witness, "witness")?;
+    let witness = StakedStateOpWitness::decode(&mut witness.as_slice()).chain_neon_with_code(
+        ctx,
+        ErrorCode::DeserializationError,
+        "Unable to decode staked state op witness",
+    )?;
 
     Ok(witness)
 }
+
+/// Reads the non-negative integer property of the options object, None when
+/// the property is absent
+pub fn optional_integer_option(
+    ctx: &mut FunctionContext,
+    options: Handle<JsObject>,
+    key: &str,
+) -> NeonResult<Option<u64>> {
+    if !does_js_object_has_prop(ctx, options, key)? {
+        return Ok(None);
+    }
+
+    let value = options
+        .get(ctx, key)?
+        .downcast_or_throw::<JsNumber, FunctionContext>(ctx)
+        .chain_neon(ctx, format!("Unable to downcast {}", key))?
+        .value();
+    if value < 0.0 || value.fract() != 0.0 {
+        return ctx.throw_error(format!("{} should be a non-negative integer", key));
+    }
+
+    Ok(Some(value as u64))
+}
+
+/// Reads the currentTime option, UNIX timestamp in seconds the timelocks are
+/// checked against, the system time when the property is absent
+pub fn current_time_option(
+    ctx: &mut FunctionContext,
+    options: Handle<JsObject>,
+) -> NeonResult<Timespec> {
+    match optional_integer_option(ctx, options, "currentTime")? {
+        Some(current_time) => Ok(current_time as Timespec),
+        None => system_time(ctx),
+    }
+}
+
+/// Returns the current time argument at `i`, UNIX timestamp in seconds the
+/// timelocks are checked against, the system time when it is absent
+pub fn current_time_argument(ctx: &mut FunctionContext, i: i32) -> NeonResult<Timespec> {
+    match ctx.argument_opt(i) {
+        Some(value) if value.is_a::<JsUndefined>() || value.is_a::<JsNull>() => system_time(ctx),
+        Some(value) => {
+            let current_time = value
+                .downcast::<JsNumber>()
+                .map(|current_time| current_time.value())
+                .ok()
+                .filter(|current_time| {
+                    *current_time >= 0.0
+                        && current_time.fract() == 0.0
+                        && *current_time <= MAX_SAFE_INTEGER
+                });
+            match current_time {
+                Some(current_time) => Ok(current_time as Timespec),
+                None => {
+                    let got = describe_value(ctx, value)?;
+                    throw_invalid_argument(
+                        ctx,
+                        ErrorCode::InvalidArgument,
+                        i,
+                        "currentTime",
+                        "non-negative integer UNIX timestamp in seconds",
+                        got,
+                    )
+                }
+            }
+        }
+        None => system_time(ctx),
+    }
+}
+
+/// Returns the current UNIX timestamp in seconds of the system time
+pub fn system_time(ctx: &mut FunctionContext) -> NeonResult<Timespec> {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .chain_neon(ctx, "Unable to get current time")
+        .map(|duration| duration.as_secs())
+}
@@ -0,0 +1,62 @@
+//! Hash functions the chain computes its identifiers with, so that external
+//! systems can reproduce them. Transaction ids are the plain blake3 hash of
+//! the SCALE-encoded transaction without witness, there is no domain
+//! separation prefix. Staking addresses are the last 20 bytes of the
+//! keccak256 hash of the uncompressed public key without its 0x04 prefix
+use chain_core::init::address::RedeemAddress;
+use chain_core::state::account::StakedStateAddress;
+use neon::prelude::*;
+
+use crate::function_types::*;
+
+/// blake3 hash of the data, the hash function of transaction ids and the
+/// app hash
+/// @arguments
+/// - data: Buffer
+/// @return 32 bytes hash Buffer
+pub fn blake3_hash(mut ctx: FunctionContext) -> JsResult<JsBuffer> {
+    let data = u8_buffer_argument(&mut ctx, 0)?;
+
+    let hash = blake3::hash(&data);
+
+    bytes_to_buffer(&mut ctx, hash.as_bytes())
+}
+
+/// compute the transaction id of the transaction
+/// @arguments
+/// - tx: SCALE-encoded transaction without witness Buffer, e.g. Tx,
+///     DepositBondTx or UnbondTx
+/// @return transaction id hex string
+pub fn txid(mut ctx: FunctionContext) -> JsResult<JsString> {
+    let tx = u8_buffer_argument(&mut ctx, 0)?;
+
+    Ok(ctx.string(blake3::hash(&tx).to_hex().as_str()))
+}
+
+/// derive the staking address of the public key, same as
+/// address.getStakingAddressFromPublicKey
+/// @arguments
+/// - public_key: compressed or uncompressed public key
+/// @return 0x-prefixed staking address
+pub fn to_staking_address(mut ctx: FunctionContext) -> JsResult<JsString> {
+    let public_key = public_key_argument(&mut ctx, 0)?;
+
+    let staked_state_address = StakedStateAddress::BasicRedeem(RedeemAddress::from(&public_key));
+
+    Ok(ctx.string(staked_state_address.to_string()))
+}
+
+pub fn register_hash_module(ctx: &mut ModuleContext) -> NeonResult<()> {
+    let js_object = JsObject::new(ctx);
+
+    let blake3_fn = JsFunction::new(ctx, blake3_hash)?;
+    js_object.set(ctx, "blake3", blake3_fn)?;
+
+    let txid_fn = JsFunction::new(ctx, txid)?;
+    js_object.set(ctx, "txid", txid_fn)?;
+
+    let to_staking_address_fn = JsFunction::new(ctx, to_staking_address)?;
+    js_object.set(ctx, "toStakingAddress", to_staking_address_fn)?;
+
+    ctx.export_value("hash", js_object)
+}
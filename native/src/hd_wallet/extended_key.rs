@@ -0,0 +1,402 @@
+//! BIP32 extended keys and child key derivation
+use std::fmt;
+use std::str::FromStr;
+
+use client_common::{Error, ErrorKind, Result, ResultExt};
+use hmac::{Hmac, Mac};
+use ripemd160::Ripemd160;
+use secp256k1::{PublicKey, SecretKey};
+use sha2::{Digest, Sha256, Sha512};
+
+use crate::common::with_secp;
+
+type HmacSha512 = Hmac<Sha512>;
+
+/// Index offset of hardened child keys
+pub const HARDENED_OFFSET: u32 = 0x8000_0000;
+
+const MASTER_KEY_HMAC_KEY: &[u8] = b"Bitcoin seed";
+const XPRV_VERSION: [u8; 4] = [0x04, 0x88, 0xAD, 0xE4];
+const XPUB_VERSION: [u8; 4] = [0x04, 0x88, 0xB2, 0x1E];
+const EXTENDED_KEY_LENGTH: usize = 78;
+
+/// BIP32 extended private key
+#[derive(Clone)]
+pub struct ExtendedPrivateKey {
+    depth: u8,
+    parent_fingerprint: [u8; 4],
+    child_number: u32,
+    chain_code: [u8; 32],
+    private_key: SecretKey,
+}
+
+impl ExtendedPrivateKey {
+    /// Create the master extended private key from a BIP39 seed
+    pub fn from_seed(seed: &[u8]) -> Result<Self> {
+        if seed.len() < 16 || seed.len() > 64 {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "Seed should be between 16 and 64 bytes long",
+            ));
+        }
+
+        let (private_key, chain_code) = hmac_sha512_split(MASTER_KEY_HMAC_KEY, seed)?;
+
+        Ok(ExtendedPrivateKey {
+            depth: 0,
+            parent_fingerprint: [0; 4],
+            child_number: 0,
+            chain_code,
+            private_key,
+        })
+    }
+
+    /// Derive the extended private key at the provided path (e.g. m/44'/394'/0'/0/0)
+    pub fn derive_path(&self, path: &str) -> Result<Self> {
+        let indexes = parse_derivation_path(path)?;
+
+        indexes
+            .into_iter()
+            .try_fold(self.clone(), |key, index| key.derive_child(index))
+    }
+
+    /// Derive a child extended private key. Indexes at or above `HARDENED_OFFSET`
+    /// are derived as hardened children
+    pub fn derive_child(&self, index: u32) -> Result<Self> {
+        let mut data = Vec::with_capacity(37);
+        if index >= HARDENED_OFFSET {
+            data.push(0);
+            data.extend_from_slice(&self.private_key[..]);
+        } else {
+            data.extend_from_slice(&self.public_key().serialize());
+        }
+        data.extend_from_slice(&index.to_be_bytes());
+
+        let (tweak, chain_code) = hmac_sha512_split(&self.chain_code, &data)?;
+        let mut private_key = self.private_key.clone();
+        private_key.add_assign(&tweak[..]).chain(|| {
+            (
+                ErrorKind::InternalError,
+                "Derived private key is invalid, try the next index",
+            )
+        })?;
+
+        Ok(ExtendedPrivateKey {
+            depth: self
+                .depth
+                .checked_add(1)
+                .chain(|| (ErrorKind::InvalidInput, "Derivation depth exceeds 255"))?,
+            parent_fingerprint: self.fingerprint(),
+            child_number: index,
+            chain_code,
+            private_key,
+        })
+    }
+
+    /// Returns the private key of the extended key
+    #[inline]
+    pub fn private_key(&self) -> &SecretKey {
+        &self.private_key
+    }
+
+    /// Returns the public key of the extended key
+    #[inline]
+    pub fn public_key(&self) -> PublicKey {
+        with_secp(|secp| PublicKey::from_secret_key(secp, &self.private_key))
+    }
+
+    /// Returns the first 4 bytes of HASH160 of the public key
+    pub fn fingerprint(&self) -> [u8; 4] {
+        fingerprint(&self.public_key())
+    }
+
+    /// Returns the extended public key of the same node, which derives the
+    /// same non-hardened child public keys without the private key
+    pub fn to_extended_public_key(&self) -> ExtendedPublicKey {
+        ExtendedPublicKey {
+            depth: self.depth,
+            parent_fingerprint: self.parent_fingerprint,
+            child_number: self.child_number,
+            chain_code: self.chain_code,
+            public_key: self.public_key(),
+        }
+    }
+}
+
+/// Redacts the private key and chain code, so that the key does not end up
+/// in the logs and error messages
+impl fmt::Debug for ExtendedPrivateKey {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("ExtendedPrivateKey")
+            .field("depth", &self.depth)
+            .field("parent_fingerprint", &self.parent_fingerprint)
+            .field("child_number", &self.child_number)
+            .field("chain_code", &"<redacted>")
+            .field("private_key", &"<redacted>")
+            .finish()
+    }
+}
+
+/// BIP32 extended public key, for watch-only derivation of non-hardened
+/// child public keys
+#[derive(Debug, Clone)]
+pub struct ExtendedPublicKey {
+    depth: u8,
+    parent_fingerprint: [u8; 4],
+    child_number: u32,
+    chain_code: [u8; 32],
+    public_key: PublicKey,
+}
+
+impl ExtendedPublicKey {
+    /// Derive the extended public key at the path relative to this key
+    /// (e.g. m/0/0). Hardened indexes cannot be derived from public key
+    pub fn derive_path(&self, path: &str) -> Result<Self> {
+        let indexes = parse_derivation_path(path)?;
+
+        indexes
+            .into_iter()
+            .try_fold(self.clone(), |key, index| key.derive_child(index))
+    }
+
+    /// Derive a non-hardened child extended public key
+    pub fn derive_child(&self, index: u32) -> Result<Self> {
+        if index >= HARDENED_OFFSET {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "Hardened child key cannot be derived from extended public key",
+            ));
+        }
+
+        let mut data = Vec::with_capacity(37);
+        data.extend_from_slice(&self.public_key.serialize());
+        data.extend_from_slice(&index.to_be_bytes());
+
+        let (tweak, chain_code) = hmac_sha512_split(&self.chain_code, &data)?;
+        let mut public_key = self.public_key;
+        with_secp(|secp| public_key.add_exp_assign(secp, &tweak[..])).chain(|| {
+            (
+                ErrorKind::InternalError,
+                "Derived public key is invalid, try the next index",
+            )
+        })?;
+
+        Ok(ExtendedPublicKey {
+            depth: self
+                .depth
+                .checked_add(1)
+                .chain(|| (ErrorKind::InvalidInput, "Derivation depth exceeds 255"))?,
+            parent_fingerprint: fingerprint(&self.public_key),
+            child_number: index,
+            chain_code,
+            public_key,
+        })
+    }
+
+    /// Returns the public key of the extended key
+    #[inline]
+    pub fn public_key(&self) -> &PublicKey {
+        &self.public_key
+    }
+}
+
+impl FromStr for ExtendedPrivateKey {
+    type Err = Error;
+
+    /// Deserialize a base58check encoded extended private key (xprv)
+    fn from_str(xprv: &str) -> Result<Self> {
+        let data = bs58::decode(xprv).with_check(None).into_vec().chain(|| {
+            (
+                ErrorKind::DeserializationError,
+                "Unable to decode base58check extended private key",
+            )
+        })?;
+
+        if data.len() != EXTENDED_KEY_LENGTH {
+            return Err(Error::new(
+                ErrorKind::DeserializationError,
+                "Extended private key should be 78 bytes long",
+            ));
+        }
+        if data[0..4] != XPRV_VERSION {
+            return Err(Error::new(
+                ErrorKind::DeserializationError,
+                "Unsupported extended private key version",
+            ));
+        }
+        if data[45] != 0 {
+            return Err(Error::new(
+                ErrorKind::DeserializationError,
+                "Extended private key has invalid key prefix",
+            ));
+        }
+
+        let mut parent_fingerprint = [0; 4];
+        parent_fingerprint.copy_from_slice(&data[5..9]);
+        let mut child_number = [0; 4];
+        child_number.copy_from_slice(&data[9..13]);
+        let mut chain_code = [0; 32];
+        chain_code.copy_from_slice(&data[13..45]);
+        let private_key = SecretKey::from_slice(&data[46..78]).chain(|| {
+            (
+                ErrorKind::DeserializationError,
+                "Unable to deserialize private key in extended private key",
+            )
+        })?;
+
+        Ok(ExtendedPrivateKey {
+            depth: data[4],
+            parent_fingerprint,
+            child_number: u32::from_be_bytes(child_number),
+            chain_code,
+            private_key,
+        })
+    }
+}
+
+impl FromStr for ExtendedPublicKey {
+    type Err = Error;
+
+    /// Deserialize a base58check encoded extended public key (xpub)
+    fn from_str(xpub: &str) -> Result<Self> {
+        let data = bs58::decode(xpub).with_check(None).into_vec().chain(|| {
+            (
+                ErrorKind::DeserializationError,
+                "Unable to decode base58check extended public key",
+            )
+        })?;
+
+        if data.len() != EXTENDED_KEY_LENGTH {
+            return Err(Error::new(
+                ErrorKind::DeserializationError,
+                "Extended public key should be 78 bytes long",
+            ));
+        }
+        if data[0..4] != XPUB_VERSION {
+            return Err(Error::new(
+                ErrorKind::DeserializationError,
+                "Unsupported extended public key version",
+            ));
+        }
+
+        let mut parent_fingerprint = [0; 4];
+        parent_fingerprint.copy_from_slice(&data[5..9]);
+        let mut child_number = [0; 4];
+        child_number.copy_from_slice(&data[9..13]);
+        let mut chain_code = [0; 32];
+        chain_code.copy_from_slice(&data[13..45]);
+        let public_key = PublicKey::from_slice(&data[45..78]).chain(|| {
+            (
+                ErrorKind::DeserializationError,
+                "Unable to deserialize public key in extended public key",
+            )
+        })?;
+
+        Ok(ExtendedPublicKey {
+            depth: data[4],
+            parent_fingerprint,
+            child_number: u32::from_be_bytes(child_number),
+            chain_code,
+            public_key,
+        })
+    }
+}
+
+impl ToString for ExtendedPublicKey {
+    /// Serialize the extended public key to base58check encoded xpub
+    fn to_string(&self) -> String {
+        let mut data = Vec::with_capacity(EXTENDED_KEY_LENGTH);
+        data.extend_from_slice(&XPUB_VERSION);
+        data.push(self.depth);
+        data.extend_from_slice(&self.parent_fingerprint);
+        data.extend_from_slice(&self.child_number.to_be_bytes());
+        data.extend_from_slice(&self.chain_code);
+        data.extend_from_slice(&self.public_key.serialize());
+
+        bs58::encode(data).with_check().into_string()
+    }
+}
+
+impl ToString for ExtendedPrivateKey {
+    /// Serialize the extended private key to base58check encoded xprv
+    fn to_string(&self) -> String {
+        let mut data = Vec::with_capacity(EXTENDED_KEY_LENGTH);
+        data.extend_from_slice(&XPRV_VERSION);
+        data.push(self.depth);
+        data.extend_from_slice(&self.parent_fingerprint);
+        data.extend_from_slice(&self.child_number.to_be_bytes());
+        data.extend_from_slice(&self.chain_code);
+        data.push(0);
+        data.extend_from_slice(&self.private_key[..]);
+
+        bs58::encode(data).with_check().into_string()
+    }
+}
+
+/// Parse a derivation path (e.g. m/44'/394'/0'/0/0) into child indexes.
+/// Hardened indexes are marked by a trailing `'` or `h`
+pub fn parse_derivation_path(path: &str) -> Result<Vec<u32>> {
+    let mut segments = path.split('/');
+    if segments.next() != Some("m") {
+        return Err(Error::new(
+            ErrorKind::InvalidInput,
+            "Derivation path should start with m",
+        ));
+    }
+
+    segments
+        .map(|segment| {
+            let (index, hardened) = if segment.ends_with('\'') || segment.ends_with('h') {
+                (&segment[..segment.len() - 1], true)
+            } else {
+                (segment, false)
+            };
+
+            let index = index.parse::<u32>().chain(|| {
+                (
+                    ErrorKind::InvalidInput,
+                    format!("Invalid derivation path segment {}", segment),
+                )
+            })?;
+            if index >= HARDENED_OFFSET {
+                return Err(Error::new(
+                    ErrorKind::InvalidInput,
+                    format!("Derivation path index {} is out of range", index),
+                ));
+            }
+
+            if hardened {
+                Ok(index + HARDENED_OFFSET)
+            } else {
+                Ok(index)
+            }
+        })
+        .collect()
+}
+
+/// Returns the first 4 bytes of HASH160 of the compressed public key
+fn fingerprint(public_key: &PublicKey) -> [u8; 4] {
+    let hash = Ripemd160::digest(&Sha256::digest(&public_key.serialize()));
+
+    let mut fingerprint = [0; 4];
+    fingerprint.copy_from_slice(&hash[..4]);
+    fingerprint
+}
+
+fn hmac_sha512_split(key: &[u8], data: &[u8]) -> Result<(SecretKey, [u8; 32])> {
+    let mut mac = HmacSha512::new_varkey(key)
+        .map_err(|_| Error::new(ErrorKind::InternalError, "Invalid HMAC key length"))?;
+    mac.input(data);
+    let result = mac.result().code();
+
+    let private_key = SecretKey::from_slice(&result[..32]).chain(|| {
+        (
+            ErrorKind::InternalError,
+            "Derived private key is invalid, try the next index",
+        )
+    })?;
+    let mut chain_code = [0; 32];
+    chain_code.copy_from_slice(&result[32..]);
+
+    Ok((private_key, chain_code))
+}
@@ -0,0 +1,391 @@
+mod extended_key;
+
+use std::str::FromStr;
+
+use bip39::{Language, MnemonicType, Seed};
+use chain_core::init::network::{get_bip44_coin_type_from_network, Network};
+use client_common::PrivateKey;
+use client_core::{HDSeed, Mnemonic};
+use neon::prelude::*;
+use secstr::SecUtf8;
+use zeroize::Zeroizing;
+
+use crate::error::{ClientErrorNeonExt, ErrorCode};
+use crate::function_types::*;
+use crate::key_pair::key_pair_to_js;
+use crate::metrics;
+use crate::rng::{entropy_argument, fill_random_bytes_with_entropy};
+
+pub use extended_key::{ExtendedPrivateKey, ExtendedPublicKey};
+
+/// BIP44 accounts of the key kinds in client-core HD wallets
+const TRANSFER_ACCOUNT_TYPE: u32 = 0;
+const STAKING_ACCOUNT_TYPE: u32 = 1;
+const VIEW_KEY_ACCOUNT_TYPE: u32 = 2;
+
+fn get_seed_from_mnemonic(mut ctx: FunctionContext) -> JsResult<JsBuffer> {
+    let mnemonic = ctx.argument::<JsString>(0)?.value();
+    let mnemonic = SecUtf8::from(mnemonic.as_str());
+
+    let mnemonic = Mnemonic::from_secstr(&mnemonic).chain_neon_with_code(
+        &mut ctx,
+        ErrorCode::DeserializationError,
+        "Unable to deserialize mnemonic",
+    )?;
+
+    let value = mnemonic.seed();
+
+    bytes_to_buffer(&mut ctx, &value)
+}
+
+/// generate new BIP39 mnemonic phrase
+/// @arguments
+/// - strength: entropy bits of the mnemonic (128, 160, 192, 224 or 256)
+/// - entropy: (optional) Buffer or hex string of at least 16 bytes of extra
+///     entropy, e.g. from a hardware RNG, mixed with the OS RNG
+/// @return mnemonic
+fn generate_mnemonic(mut ctx: FunctionContext) -> JsResult<JsString> {
+    metrics::measure_js("hdWallet.generateMnemonic", || {
+        let strength = ctx.argument::<JsNumber>(0)?.value();
+        if !strength.is_finite() || strength < 0.0 || strength.fract() != 0.0 {
+            return throw_invalid_argument(
+                &mut ctx,
+                ErrorCode::InvalidArgument,
+                0,
+                "strength",
+                "integer entropy bits",
+                strength,
+            );
+        }
+        let strength = strength as usize;
+        let external_entropy = entropy_argument(&mut ctx, 1)?;
+
+        let mnemonic_type = MnemonicType::for_key_size(strength)
+            .chain_neon(&mut ctx, "Unsupported mnemonic strength")?;
+        let mut entropy = Zeroizing::new(vec![0u8; mnemonic_type.entropy_bits() / 8]);
+        fill_random_bytes_with_entropy(
+            &mut entropy,
+            external_entropy.as_ref().map(|entropy| &entropy[..]),
+        )
+        .chain_neon(&mut ctx, "Unable to generate mnemonic entropy")?;
+        let mnemonic = bip39::Mnemonic::from_entropy(&entropy, Language::English)
+            .chain_neon(&mut ctx, "Unable to generate mnemonic")?;
+
+        Ok(ctx.string(mnemonic.phrase()))
+    })
+}
+
+/// restore BIP39 seed from mnemonic phrase
+/// @arguments
+/// - mnemonic: mnemonic phrase
+/// - passphrase: optional BIP39 passphrase, defaults to empty string
+/// @return seed
+fn mnemonic_to_seed(mut ctx: FunctionContext) -> JsResult<JsBuffer> {
+    let mnemonic = ctx.argument::<JsString>(0)?.value();
+    let passphrase = optional_string_argument(&mut ctx, 1)?.unwrap_or_default();
+
+    let mnemonic = bip39::Mnemonic::from_phrase(&mnemonic, Language::English)
+        .chain_neon_with_code(
+            &mut ctx,
+            ErrorCode::DeserializationError,
+            "Unable to deserialize mnemonic",
+        )?;
+    let seed = Seed::new(&mnemonic, &passphrase);
+    let value = seed.as_bytes();
+
+    bytes_to_buffer(&mut ctx, value)
+}
+
+/// validate BIP39 mnemonic phrase against the wordlist of the language
+/// @arguments
+/// - mnemonic: mnemonic phrase
+/// - language: (optional) English, Japanese, Korean or ChineseSimplified,
+///     defaults to English
+/// @return {
+///     isValid: boolean, whether all words are in the wordlist and the
+///         checksum is correct
+///     normalizedMnemonic: mnemonic with surrounding whitespaces trimmed,
+///         words lowercased and separated by single space
+/// }
+fn validate_mnemonic(mut ctx: FunctionContext) -> JsResult<JsObject> {
+    let mnemonic = ctx.argument::<JsString>(0)?.value();
+    let language = language_argument(&mut ctx, 1)?;
+
+    // Japanese mnemonic may be separated by ideographic space, which is
+    // also treated as whitespace here
+    let normalized_mnemonic = mnemonic
+        .split_whitespace()
+        .map(str::to_lowercase)
+        .collect::<Vec<String>>()
+        .join(" ");
+    let is_valid = bip39::Mnemonic::validate(&normalized_mnemonic, language).is_ok();
+
+    let js_object = JsObject::new(&mut ctx);
+    let is_valid = ctx.boolean(is_valid);
+    js_object.set(&mut ctx, "isValid", is_valid)?;
+    let normalized_mnemonic = ctx.string(normalized_mnemonic);
+    js_object.set(&mut ctx, "normalizedMnemonic", normalized_mnemonic)?;
+
+    Ok(js_object)
+}
+
+fn language_argument(ctx: &mut FunctionContext, i: i32) -> NeonResult<Language> {
+    let language = optional_string_argument(ctx, i)?;
+
+    match language.as_deref() {
+        None | Some("English") => Ok(Language::English),
+        Some("Japanese") => Ok(Language::Japanese),
+        Some("Korean") => Ok(Language::Korean),
+        Some("ChineseSimplified") => Ok(Language::ChineseSimplified),
+        Some(language) => ctx.throw_error(format!("Unsupported mnemonic language {}", language)),
+    }
+}
+
+fn derive_key_pair_from_seed(mut ctx: FunctionContext) -> JsResult<JsObject> {
+    let seed = u8_buffer_argument(&mut ctx, 0)?;
+    let network = network_config_argument(&mut ctx, 1)?.network;
+    let account = ctx.argument::<JsNumber>(2)?.to_string(&mut ctx)?.value();
+    let index = ctx.argument::<JsNumber>(3)?.to_string(&mut ctx)?.value();
+
+    let account = account.parse::<u32>().chain_neon_with_code(
+        &mut ctx,
+        ErrorCode::DeserializationError,
+        "Unable to deserialize account",
+    )?;
+    let index = index.parse::<u32>().chain_neon_with_code(
+        &mut ctx,
+        ErrorCode::DeserializationError,
+        "Unable to deserialize index",
+    )?;
+
+    hd_seed_key_pair_to_js(&mut ctx, seed, network, account, index)
+}
+
+/// derive view key pair from seed at the view key account of client-core
+/// wallets, m/44'/{coin type}'/2'/0/{index}, so that view keys are
+/// reproducible from the mnemonic alone
+/// @arguments
+/// - seed: seed Buffer
+/// - network: network handle or name
+/// - index: index of the view key
+/// @return { publicKey, compressedPublicKey, privateKey }
+fn derive_view_key_pair_from_seed(mut ctx: FunctionContext) -> JsResult<JsObject> {
+    let seed = u8_buffer_argument(&mut ctx, 0)?;
+    let network = network_config_argument(&mut ctx, 1)?.network;
+    let index = ctx.argument::<JsNumber>(2)?.to_string(&mut ctx)?.value();
+
+    let index = index.parse::<u32>().chain_neon_with_code(
+        &mut ctx,
+        ErrorCode::DeserializationError,
+        "Unable to deserialize index",
+    )?;
+
+    hd_seed_key_pair_to_js(&mut ctx, seed, network, VIEW_KEY_ACCOUNT_TYPE, index)
+}
+
+/// derive key pair of the kind from seed at the BIP44 path of client-cli
+/// wallets, m/44'/{coin type}'/{kind}'/{change}/{index}, with the coin type
+/// of the network as `deriveKeyPairFromSeed`. client-core wallets place the
+/// key kind at the BIP44 account level, so only account 0 is supported
+/// @arguments
+/// - seed: seed Buffer
+/// - network: network handle or name
+/// - account: BIP44 account, must be 0
+/// - change: 0 for external chain or 1 for internal chain
+/// - index: address index
+/// - key_kind: Transfer, Staking or Viewkey
+/// @return { publicKey, compressedPublicKey, privateKey, path }
+fn derive_key_at(mut ctx: FunctionContext) -> JsResult<JsObject> {
+    let seed = Zeroizing::new(u8_buffer_argument(&mut ctx, 0)?);
+    let network = network_config_argument(&mut ctx, 1)?.network;
+    let account = u32_number_argument(&mut ctx, 2, "account")?;
+    let change = u32_number_argument(&mut ctx, 3, "change")?;
+    let index = u32_number_argument(&mut ctx, 4, "index")?;
+    let key_kind = ctx.argument::<JsString>(5)?.value();
+
+    if account != 0 {
+        return ctx.throw_error("client-cli wallets only have account 0");
+    }
+    if change > 1 {
+        return ctx.throw_error("Change should be 0 or 1");
+    }
+    let key_kind = match key_kind.as_str() {
+        "Transfer" => TRANSFER_ACCOUNT_TYPE,
+        "Staking" => STAKING_ACCOUNT_TYPE,
+        "Viewkey" => VIEW_KEY_ACCOUNT_TYPE,
+        _ => return ctx.throw_error(format!("Unsupported key kind {}", key_kind)),
+    };
+
+    let path = format!(
+        "m/44'/{}'/{}'/{}/{}",
+        get_bip44_coin_type_from_network(network),
+        key_kind,
+        change,
+        index
+    );
+    let derived_key = ExtendedPrivateKey::from_seed(&seed)
+        .and_then(|root_key| root_key.derive_path(&path))
+        .chain_neon(&mut ctx, "Unable to derive key from seed")?;
+    let private_key = PrivateKey::deserialize_from(&derived_key.private_key()[..])
+        .chain_neon_with_code(
+            &mut ctx,
+            ErrorCode::InvalidPrivateKey,
+            "Unable to deserialize derived private key",
+        )?;
+
+    let js_object = key_pair_to_js(&mut ctx, &private_key)?;
+    let path = ctx.string(path);
+    js_object.set(&mut ctx, "path", path)?;
+
+    Ok(js_object)
+}
+
+/// export the extended public key at the BIP32 derivation path, for
+/// watch-only derivation of receive addresses on an online machine
+/// @arguments
+/// - seed_or_xprv: seed Buffer or base58check encoded extended private key
+/// - path: derivation path (e.g. m/44'/394'/0'/0)
+/// @return base58check encoded extended public key (xpub)
+fn to_extended_public_key(mut ctx: FunctionContext) -> JsResult<JsString> {
+    let root_key = extended_private_key_argument(&mut ctx, 0)?;
+    let path = ctx.argument::<JsString>(1)?.value();
+
+    let xpub = root_key
+        .derive_path(&path)
+        .chain_neon(&mut ctx, "Unable to derive key from path")?
+        .to_extended_public_key()
+        .to_string();
+
+    Ok(ctx.string(xpub))
+}
+
+/// derive child public key from the extended public key alone. Only
+/// non-hardened paths can be derived
+/// @arguments
+/// - xpub: base58check encoded extended public key
+/// - path: derivation path relative to the xpub (e.g. m/0/1)
+/// @return { publicKey, compressedPublicKey, xpub }
+fn derive_public_key_from_xpub(mut ctx: FunctionContext) -> JsResult<JsObject> {
+    let xpub = ctx.argument::<JsString>(0)?.value();
+    let path = ctx.argument::<JsString>(1)?.value();
+
+    let xpub = ExtendedPublicKey::from_str(&xpub).chain_neon_with_code(
+        &mut ctx,
+        ErrorCode::InvalidPublicKey,
+        "Unable to deserialize extended public key",
+    )?;
+    let derived_key = xpub.derive_path(&path).chain_neon_with_code(
+        &mut ctx,
+        ErrorCode::InvalidPublicKey,
+        "Unable to derive public key from path",
+    )?;
+    let public_key = derived_key.public_key();
+
+    let compressed_public_key = public_key.serialize();
+    let serialized_public_key = public_key.serialize_uncompressed();
+    let public_key_buffer = bytes_to_buffer(&mut ctx, &serialized_public_key)?;
+
+    let compressed_public_key_buffer = bytes_to_buffer(&mut ctx, &compressed_public_key)?;
+
+    let js_object = JsObject::new(&mut ctx);
+    js_object.set(&mut ctx, "publicKey", public_key_buffer)?;
+    js_object.set(
+        &mut ctx,
+        "compressedPublicKey",
+        compressed_public_key_buffer,
+    )?;
+    let xpub = ctx.string(derived_key.to_string());
+    js_object.set(&mut ctx, "xpub", xpub)?;
+
+    Ok(js_object)
+}
+
+/// Returns the master key from the seed Buffer or the extended private key
+/// string at argument `i`
+pub fn extended_private_key_argument(
+    ctx: &mut FunctionContext,
+    i: i32,
+) -> NeonResult<ExtendedPrivateKey> {
+    let seed_or_xprv = ctx.argument::<JsValue>(i)?;
+
+    if seed_or_xprv.is_a::<JsBuffer>() {
+        let seed = Zeroizing::new(u8_buffer_argument(ctx, i)?);
+        ExtendedPrivateKey::from_seed(&seed)
+            .chain_neon(ctx, "Unable to create master key from seed")
+    } else if seed_or_xprv.is_a::<JsString>() {
+        let xprv = ctx.argument::<JsString>(i)?.value();
+        ExtendedPrivateKey::from_str(&xprv).chain_neon_with_code(
+            ctx,
+            ErrorCode::InvalidPrivateKey,
+            "Unable to deserialize extended private key",
+        )
+    } else {
+        ctx.throw_error("Expected seed Buffer or extended private key string")
+    }
+}
+
+fn u32_number_argument(ctx: &mut FunctionContext, i: i32, name: &str) -> NeonResult<u32> {
+    let value = ctx.argument::<JsNumber>(i)?.value();
+    if value < 0.0 || value > f64::from(u32::max_value()) || value.fract() != 0.0 {
+        return ctx.throw_error(format!("{} should be an unsigned 32-bit integer", name));
+    }
+
+    Ok(value as u32)
+}
+
+fn hd_seed_key_pair_to_js<'a>(
+    ctx: &mut FunctionContext<'a>,
+    seed: Vec<u8>,
+    network: Network,
+    account: u32,
+    index: u32,
+) -> JsResult<'a, JsObject> {
+    let hd_seed = HDSeed::new(seed);
+
+    let (_, private_key) = hd_seed
+        .derive_key_pair(network, account, index)
+        .chain_neon(ctx, "Unable to derive key pair")?;
+
+    key_pair_to_js(ctx, &private_key)
+}
+
+pub fn register_hd_wallet_module(ctx: &mut ModuleContext) -> NeonResult<()> {
+    let js_object = JsObject::new(ctx);
+
+    let get_seed_from_mnemonic_fn = JsFunction::new(ctx, get_seed_from_mnemonic)?;
+    js_object.set(ctx, "getSeedFromMnemonic", get_seed_from_mnemonic_fn)?;
+
+    let generate_mnemonic_fn = JsFunction::new(ctx, generate_mnemonic)?;
+    js_object.set(ctx, "generateMnemonic", generate_mnemonic_fn)?;
+
+    let mnemonic_to_seed_fn = JsFunction::new(ctx, mnemonic_to_seed)?;
+    js_object.set(ctx, "mnemonicToSeed", mnemonic_to_seed_fn)?;
+
+    let validate_mnemonic_fn = JsFunction::new(ctx, validate_mnemonic)?;
+    js_object.set(ctx, "validateMnemonic", validate_mnemonic_fn)?;
+
+    let derive_key_pair_from_seed_fn = JsFunction::new(ctx, derive_key_pair_from_seed)?;
+    js_object.set(ctx, "deriveKeyPairFromSeed", derive_key_pair_from_seed_fn)?;
+
+    let derive_key_at_fn = JsFunction::new(ctx, derive_key_at)?;
+    js_object.set(ctx, "deriveKeyAt", derive_key_at_fn)?;
+
+    let to_extended_public_key_fn = JsFunction::new(ctx, to_extended_public_key)?;
+    js_object.set(ctx, "toExtendedPublicKey", to_extended_public_key_fn)?;
+
+    let derive_public_key_from_xpub_fn = JsFunction::new(ctx, derive_public_key_from_xpub)?;
+    js_object.set(
+        ctx,
+        "derivePublicKeyFromXpub",
+        derive_public_key_from_xpub_fn,
+    )?;
+
+    let derive_view_key_pair_from_seed_fn = JsFunction::new(ctx, derive_view_key_pair_from_seed)?;
+    js_object.set(
+        ctx,
+        "deriveViewKeyPairFromSeed",
+        derive_view_key_pair_from_seed_fn,
+    )?;
+
+    ctx.export_value("hdWallet", js_object)
+}
@@ -0,0 +1,193 @@
+//! Verification of many signatures in one call, e.g. all witnesses of a block
+use client_common::PublicKey;
+use neon::prelude::*;
+use secp256k1::schnorrsig::{schnorr_verify, SchnorrSignature};
+use secp256k1::{Message, Signature};
+
+use crate::cancellation::CancellationToken;
+use crate::common::with_secp;
+use crate::error::{ClientErrorNeonExt, NativeError};
+use crate::function_types::*;
+
+/// Verify the signatures of a batch of messages. Signatures and public keys
+/// which cannot be deserialized are treated as invalid instead of aborting
+/// the whole batch
+/// @arguments
+/// - items: [{ message, signature, publicKey, scheme? }], message is 32 bytes
+///     Buffer, scheme is ECDSA (default) or Schnorr. ECDSA signature is 64
+///     bytes compact or 65 bytes recoverable, Schnorr signature is 64 bytes
+/// - callback: (optional) node-style callback, verify on background thread
+///     and pass the result to callback when provided
+/// @return [boolean] validity of each item in order, or the task handle
+///     { id, cancel() } with callback
+pub fn verify_batch(mut ctx: FunctionContext) -> JsResult<JsValue> {
+    let items = ctx.argument::<JsArray>(0)?.to_vec(&mut ctx)?;
+    let callback = callback_argument(&mut ctx)?;
+
+    let mut batch = Vec::with_capacity(items.len());
+    for (i, item) in items.iter().enumerate() {
+        let item = item
+            .downcast_or_throw::<JsObject, FunctionContext>(&mut ctx)
+            .chain_neon(&mut ctx, format!("Unable to downcast item {}", i))?;
+        let message = item_buffer(&mut ctx, item, i, "message")?;
+        let message = Message::from_slice(&message).chain_neon(
+            &mut ctx,
+            format!("Message of item {} should be 32 bytes long", i),
+        )?;
+        let signature = item_buffer(&mut ctx, item, i, "signature")?;
+        let public_key = item_buffer(&mut ctx, item, i, "publicKey")?;
+        let scheme = item_scheme(&mut ctx, item, i)?;
+
+        batch.push(BatchItem {
+            scheme,
+            message,
+            signature,
+            public_key,
+        });
+    }
+
+    let cancellation = match callback {
+        Some(_) => CancellationToken::register(),
+        None => CancellationToken::default(),
+    };
+    let task = VerifyBatchTask {
+        batch,
+        cancellation: cancellation.clone(),
+    };
+    match callback {
+        Some(callback) => {
+            task.schedule(callback);
+            Ok(cancellation.to_js(&mut ctx)?.upcast())
+        }
+        None => {
+            let result = task.perform();
+            verify_batch_result_to_js(&mut ctx, result)
+        }
+    }
+}
+
+enum VerifyScheme {
+    Ecdsa,
+    Schnorr,
+}
+
+struct BatchItem {
+    scheme: VerifyScheme,
+    message: Message,
+    signature: Vec<u8>,
+    public_key: Vec<u8>,
+}
+
+impl BatchItem {
+    fn verify(&self) -> bool {
+        let public_key: secp256k1::PublicKey = match PublicKey::deserialize_from(&self.public_key) {
+            Ok(public_key) => public_key.into(),
+            Err(_) => return false,
+        };
+
+        match self.scheme {
+            VerifyScheme::Ecdsa => {
+                let signature = match self.signature.len() {
+                    64 | 65 => Signature::from_compact(&self.signature[..64]),
+                    _ => return false,
+                };
+                match signature {
+                    Ok(signature) => with_secp(|secp| {
+                        secp.verify(&self.message, &signature, &public_key).is_ok()
+                    }),
+                    Err(_) => false,
+                }
+            }
+            VerifyScheme::Schnorr => match SchnorrSignature::from_default(&self.signature) {
+                Ok(signature) => with_secp(|secp| {
+                    schnorr_verify(secp, &self.message, &signature, &public_key).is_ok()
+                }),
+                Err(_) => false,
+            },
+        }
+    }
+}
+
+struct VerifyBatchTask {
+    batch: Vec<BatchItem>,
+    cancellation: CancellationToken,
+}
+
+impl Task for VerifyBatchTask {
+    type Output = Vec<bool>;
+    type Error = NativeError;
+    type JsEvent = JsValue;
+
+    fn perform(&self) -> std::result::Result<Vec<bool>, NativeError> {
+        // The result of a cancelled task is discarded, so verification stops
+        // at the first item after the cancel
+        Ok(self
+            .batch
+            .iter()
+            .take_while(|_| !self.cancellation.is_cancelled())
+            .map(BatchItem::verify)
+            .collect())
+    }
+
+    fn complete(
+        self,
+        mut ctx: TaskContext,
+        result: std::result::Result<Vec<bool>, NativeError>,
+    ) -> JsResult<JsValue> {
+        let result = self.cancellation.complete(result);
+        verify_batch_result_to_js(&mut ctx, result)
+    }
+}
+
+fn verify_batch_result_to_js<'a, C: Context<'a>>(
+    ctx: &mut C,
+    result: std::result::Result<Vec<bool>, NativeError>,
+) -> JsResult<'a, JsValue> {
+    let result = match result {
+        Ok(result) => result,
+        Err(err) => return err.throw(ctx),
+    };
+
+    let js_result = JsArray::new(ctx, result.len() as u32);
+    for (i, is_valid) in result.into_iter().enumerate() {
+        let is_valid = ctx.boolean(is_valid);
+        js_result.set(ctx, i as u32, is_valid)?;
+    }
+
+    Ok(js_result.upcast())
+}
+
+fn item_buffer(
+    ctx: &mut FunctionContext,
+    item: Handle<JsObject>,
+    i: usize,
+    key: &str,
+) -> NeonResult<Vec<u8>> {
+    let value = item.get(ctx, key)?;
+
+    parse_bytes(ctx, value, &format!("{} of item {}", key, i))
+}
+
+fn item_scheme(
+    ctx: &mut FunctionContext,
+    item: Handle<JsObject>,
+    i: usize,
+) -> NeonResult<VerifyScheme> {
+    let scheme = item.get(ctx, "scheme")?;
+    if scheme.is_a::<JsUndefined>() {
+        return Ok(VerifyScheme::Ecdsa);
+    }
+
+    let scheme = scheme
+        .downcast_or_throw::<JsString, FunctionContext>(ctx)
+        .chain_neon(ctx, format!("Unable to downcast scheme of item {}", i))?
+        .value();
+    match scheme.as_str() {
+        "ECDSA" => Ok(VerifyScheme::Ecdsa),
+        "Schnorr" => Ok(VerifyScheme::Schnorr),
+        _ => ctx.throw_error(format!(
+            "Unsupported signature scheme {} of item {}",
+            scheme, i
+        )),
+    }
+}
@@ -0,0 +1,134 @@
+//! Private keys kept in native memory and referenced by opaque handles so
+//! that the key bytes never cross into V8-managed memory. A handle is an
+//! instance of a native class which owns its key, so the key is zeroized and
+//! released as soon as the handle is destroyed, or when garbage collection
+//! drops a handle which is no longer referenced
+use client_common::{PrivateKey, PublicKey};
+use neon::declare_types;
+use neon::prelude::*;
+use secp256k1::SecretKey;
+use zeroize::Zeroizing;
+
+use crate::error::{throw_coded_error, ClientErrorNeonExt, ErrorCode};
+use crate::function_types::*;
+use crate::metrics;
+use crate::rng::{entropy_argument, random_private_key};
+
+use super::derived_private_key_argument;
+
+/// Private key of a key handle, None once destroyed. Not Debug as it holds
+/// the private key
+pub struct KeyHandle {
+    private_key: Option<Zeroizing<Vec<u8>>>,
+}
+
+declare_types! {
+    pub class JsKeyHandle for KeyHandle {
+        init(_ctx) {
+            Ok(KeyHandle { private_key: None })
+        }
+
+        // destroy the key handle and release the private key from native
+        // memory. Signing with the handle afterwards throws
+        // @return boolean: whether the key handle was not destroyed yet
+        method destroy(mut ctx) {
+            let mut this = ctx.this();
+            let existed = {
+                let guard = ctx.lock();
+                let mut key_handle = this.borrow_mut(&guard);
+                key_handle.private_key.take().is_some()
+            };
+
+            Ok(ctx.boolean(existed).upcast())
+        }
+    }
+}
+
+/// create new private key kept in native memory
+/// @arguments
+/// - entropy: (optional) extra entropy mixed with the OS RNG, see
+///     `newPrivateKey`
+/// @return key handle { publicKey, compressedPublicKey, destroy() }
+pub fn new_private_key_handle(mut ctx: FunctionContext) -> JsResult<JsKeyHandle> {
+    metrics::measure_js("keyPair.newPrivateKeyHandle", || {
+        let entropy = entropy_argument(&mut ctx, 0)?;
+        let private_key = random_private_key(entropy.as_ref().map(|entropy| &entropy[..]))
+            .chain_neon(&mut ctx, "Unable to generate private key")?;
+
+        key_handle_to_js(&mut ctx, private_key)
+    })
+}
+
+/// derive private key at the BIP32 derivation path and keep it in native
+/// memory
+/// @arguments
+/// - seed_or_xprv: seed Buffer or base58check encoded extended private key
+/// - path: derivation path (e.g. m/44'/394'/0'/0/0)
+/// @return key handle { publicKey, compressedPublicKey, destroy() }
+pub fn derive_private_key_handle(mut ctx: FunctionContext) -> JsResult<JsKeyHandle> {
+    metrics::measure_js("keyPair.derivePrivateKeyHandle", || {
+        let private_key = derived_private_key_argument(&mut ctx, 0)?;
+
+        key_handle_to_js(&mut ctx, private_key)
+    })
+}
+
+/// Returns the secret key of private key Buffer or key handle at argument `i`
+#[inline]
+pub fn secret_key_argument(ctx: &mut FunctionContext, i: i32) -> NeonResult<SecretKey> {
+    let private_key_or_handle = ctx.argument::<JsValue>(i)?;
+    if private_key_or_handle.is_a::<JsBuffer>() {
+        let private_key = private_key_argument(ctx, i)?;
+        return Ok(SecretKey::from(&private_key));
+    }
+
+    let handle = match private_key_or_handle.downcast::<JsKeyHandle>() {
+        Ok(handle) => handle,
+        Err(_) => {
+            return throw_coded_error(
+                ctx,
+                ErrorCode::InvalidPrivateKey,
+                "Private key should be a Buffer or key handle",
+            )
+        }
+    };
+    let secret_key = {
+        let guard = ctx.lock();
+        let key_handle = handle.borrow(&guard);
+        key_handle
+            .private_key
+            .as_ref()
+            .map(|private_key| SecretKey::from_slice(&private_key[..]))
+    };
+
+    secret_key
+        .chain_neon_with_code(ctx, ErrorCode::InvalidPrivateKey, "Key handle is destroyed")?
+        .chain_neon_with_code(
+            ctx,
+            ErrorCode::InvalidPrivateKey,
+            "Unable to restore private key of key handle",
+        )
+}
+
+fn key_handle_to_js<'a>(
+    ctx: &mut FunctionContext<'a>,
+    private_key: PrivateKey,
+) -> JsResult<'a, JsKeyHandle> {
+    let public_key = PublicKey::from(&private_key);
+    let compressed_public_key = public_key.serialize_compressed();
+    let public_key = public_key.serialize();
+
+    let no_args: Vec<Handle<JsValue>> = Vec::new();
+    let mut handle = JsKeyHandle::new(ctx, no_args)?;
+    {
+        let guard = ctx.lock();
+        handle.borrow_mut(&guard).private_key = Some(Zeroizing::new(private_key.serialize()));
+    }
+
+    let public_key_buf = bytes_to_buffer(ctx, &public_key)?;
+    handle.set(ctx, "publicKey", public_key_buf)?;
+    let compressed_public_key_buf = bytes_to_buffer(ctx, &compressed_public_key)?;
+    handle.set(ctx, "compressedPublicKey", compressed_public_key_buf)?;
+
+    Ok(handle)
+}
@@ -0,0 +1,601 @@
+mod batch;
+mod handle;
+mod wif;
+
+use std::str::FromStr;
+
+use client_common::{PrivateKey, PublicKey};
+use neon::prelude::*;
+use secp256k1::recovery::{RecoverableSignature, RecoveryId};
+use secp256k1::schnorrsig::{schnorr_sign, schnorr_verify, SchnorrSignature};
+use secp256k1::{Message, SecretKey, Signature};
+use zeroize::{Zeroize, Zeroizing};
+
+use crate::common::with_secp;
+use crate::error::{ClientErrorNeonExt, ErrorCode};
+use crate::function_types::*;
+use crate::hd_wallet::extended_private_key_argument;
+use crate::metrics;
+use crate::rng::{entropy_argument, random_private_key};
+
+use batch::verify_batch;
+use handle::{derive_private_key_handle, new_private_key_handle, secret_key_argument};
+use wif::{from_hex, from_wif, to_hex, to_wif};
+
+/// Upper bound of key pairs generated in one call, to bound the blocking time
+/// of the JS thread
+const MAX_KEY_PAIRS_COUNT: u32 = 10000;
+
+pub fn verify_public_key(mut ctx: FunctionContext) -> JsResult<JsUndefined> {
+    let _ = public_key_argument(&mut ctx, 0)?;
+
+    Ok(ctx.undefined())
+}
+
+pub fn verify_private_key(mut ctx: FunctionContext) -> JsResult<JsUndefined> {
+    let _ = private_key_argument(&mut ctx, 0)?;
+
+    Ok(ctx.undefined())
+}
+
+pub fn get_public_keys_from_private_key(mut ctx: FunctionContext) -> JsResult<JsObject> {
+    let private_key = private_key_argument(&mut ctx, 0)?;
+
+    let public_key = PublicKey::from(&private_key);
+    let compressed_public_key = public_key.serialize_compressed();
+    let public_key = public_key.serialize();
+
+    let public_key_buf = bytes_to_buffer(&mut ctx, &public_key)?;
+
+    let compressed_public_key_buf = bytes_to_buffer(&mut ctx, &compressed_public_key)?;
+
+    let js_object = JsObject::new(&mut ctx);
+    js_object.set(&mut ctx, "publicKey", public_key_buf)?;
+    js_object.set(&mut ctx, "compressedPublicKey", compressed_public_key_buf)?;
+
+    Ok(js_object)
+}
+
+pub fn get_public_keys_from_any_public_key(mut ctx: FunctionContext) -> JsResult<JsObject> {
+    let public_key = public_key_argument(&mut ctx, 0)?;
+
+    let compressed_public_key = public_key.serialize_compressed();
+    let public_key = public_key.serialize();
+
+    let public_key_buf = bytes_to_buffer(&mut ctx, &public_key)?;
+
+    let compressed_public_key_buf = bytes_to_buffer(&mut ctx, &compressed_public_key)?;
+
+    let js_object = JsObject::new(&mut ctx);
+    js_object.set(&mut ctx, "publicKey", public_key_buf)?;
+    js_object.set(&mut ctx, "compressedPublicKey", compressed_public_key_buf)?;
+
+    Ok(js_object)
+}
+
+/// Compress the public key
+/// @arguments
+/// - public_key: 33 bytes compressed or 65 bytes uncompressed public key
+/// @return 33 bytes compressed public key
+pub fn compress_public_key(mut ctx: FunctionContext) -> JsResult<JsBuffer> {
+    let public_key = public_key_argument(&mut ctx, 0)?;
+
+    public_key_to_buffer(&mut ctx, &public_key.serialize_compressed())
+}
+
+/// Decompress the public key
+/// @arguments
+/// - public_key: 33 bytes compressed or 65 bytes uncompressed public key
+/// @return 65 bytes uncompressed public key
+pub fn decompress_public_key(mut ctx: FunctionContext) -> JsResult<JsBuffer> {
+    let public_key = public_key_argument(&mut ctx, 0)?;
+
+    public_key_to_buffer(&mut ctx, &public_key.serialize())
+}
+
+/// Combine the public keys by EC point addition
+/// @arguments
+/// - public_keys: array of public keys, at least one
+/// @return {
+///     publicKey: Buffer,
+///     compressedPublicKey: Buffer
+/// }
+pub fn combine_public_keys(mut ctx: FunctionContext) -> JsResult<JsObject> {
+    let public_keys = public_key_vector_argument(&mut ctx, 0)?;
+
+    let mut public_keys = public_keys.into_iter().map(secp256k1::PublicKey::from);
+    let first_public_key = public_keys.next().chain_neon_with_code(
+        &mut ctx,
+        ErrorCode::InvalidPublicKey,
+        "At least one public key is required",
+    )?;
+    let mut combined_public_key = first_public_key;
+    for public_key in public_keys {
+        combined_public_key = combined_public_key
+            .combine(&public_key)
+            .chain_neon_with_code(
+                &mut ctx,
+                ErrorCode::InvalidPublicKey,
+                "Unable to combine public keys",
+            )?;
+    }
+
+    let public_key_buf =
+        public_key_to_buffer(&mut ctx, &combined_public_key.serialize_uncompressed())?;
+    let compressed_public_key_buf =
+        public_key_to_buffer(&mut ctx, &combined_public_key.serialize())?;
+
+    let js_object = JsObject::new(&mut ctx);
+    js_object.set(&mut ctx, "publicKey", public_key_buf)?;
+    js_object.set(&mut ctx, "compressedPublicKey", compressed_public_key_buf)?;
+
+    Ok(js_object)
+}
+
+/// Add tweak times the generator point to the public key, e.g. to derive a
+/// pay-to-contract payment key from a public key and a commitment
+/// @arguments
+/// - public_key: 33 bytes compressed or 65 bytes uncompressed public key
+/// - tweak: 32 bytes tweak Buffer, should be below the curve order
+/// @return {
+///     publicKey: Buffer,
+///     compressedPublicKey: Buffer
+/// }
+pub fn tweak_add_public_key(mut ctx: FunctionContext) -> JsResult<JsObject> {
+    let public_key = public_key_argument(&mut ctx, 0)?;
+    let tweak = tweak_argument(&mut ctx, 1)?;
+
+    let mut public_key: secp256k1::PublicKey = public_key.into();
+    with_secp(|secp| public_key.add_exp_assign(secp, &tweak)).chain_neon_with_code(
+        &mut ctx,
+        ErrorCode::InvalidPublicKey,
+        "Unable to tweak public key",
+    )?;
+
+    let public_key_buf = public_key_to_buffer(&mut ctx, &public_key.serialize_uncompressed())?;
+    let compressed_public_key_buf = public_key_to_buffer(&mut ctx, &public_key.serialize())?;
+
+    let js_object = JsObject::new(&mut ctx);
+    js_object.set(&mut ctx, "publicKey", public_key_buf)?;
+    js_object.set(&mut ctx, "compressedPublicKey", compressed_public_key_buf)?;
+
+    Ok(js_object)
+}
+
+/// Add tweak to the private key modulo the curve order, the private key of
+/// the public key tweaked by `tweakAddPublicKey`. Key handles are not
+/// accepted as the tweaked key and the tweak would reveal the handle key
+/// @arguments
+/// - private_key: private key Buffer
+/// - tweak: 32 bytes tweak Buffer, should be below the curve order
+/// @return private key Buffer
+pub fn tweak_add_private_key(mut ctx: FunctionContext) -> JsResult<JsBuffer> {
+    let private_key = private_key_argument(&mut ctx, 0)?;
+    let tweak = tweak_argument(&mut ctx, 1)?;
+
+    let mut secret_key = SecretKey::from(&private_key);
+    secret_key.add_assign(&tweak).chain_neon_with_code(
+        &mut ctx,
+        ErrorCode::InvalidPrivateKey,
+        "Unable to tweak private key",
+    )?;
+    let private_key = PrivateKey::deserialize_from(&secret_key[..]).chain_neon_with_code(
+        &mut ctx,
+        ErrorCode::InvalidPrivateKey,
+        "Unable to deserialize tweaked private key",
+    )?;
+
+    private_key_to_buffer(&mut ctx, &private_key)
+}
+
+fn tweak_argument(ctx: &mut FunctionContext, i: i32) -> NeonResult<Zeroizing<Vec<u8>>> {
+    let tweak = Zeroizing::new(hex_or_buffer_argument(ctx, i, "tweak")?);
+    if tweak.len() != 32 {
+        return ctx.throw_error("Tweak should be 32 bytes long");
+    }
+
+    Ok(tweak)
+}
+
+fn public_key_to_buffer<'a>(
+    ctx: &mut FunctionContext<'a>,
+    public_key: &[u8],
+) -> JsResult<'a, JsBuffer> {
+    bytes_to_buffer(ctx, public_key)
+}
+
+/// generate new private key
+/// @arguments
+/// - entropy: (optional) Buffer or hex string of at least 16 bytes of extra
+///     entropy, e.g. from a hardware RNG, mixed with the OS RNG
+/// @return private key Buffer
+pub fn new_private_key(mut ctx: FunctionContext) -> JsResult<JsBuffer> {
+    metrics::measure_js("keyPair.newPrivateKey", || {
+        let entropy = entropy_argument(&mut ctx, 0)?;
+        let private_key = random_private_key(entropy.as_ref().map(|entropy| &entropy[..]))
+            .chain_neon(&mut ctx, "Unable to generate private key")?;
+
+        private_key_to_buffer(&mut ctx, &private_key)
+    })
+}
+
+/// Serialize private key into a new Buffer. The intermediate serialized
+/// bytes are wiped once copied into the Buffer
+fn private_key_to_buffer<'a>(
+    ctx: &mut FunctionContext<'a>,
+    private_key: &PrivateKey,
+) -> JsResult<'a, JsBuffer> {
+    let private_key = Zeroizing::new(private_key.serialize());
+
+    bytes_to_buffer(ctx, &private_key)
+}
+
+/// Overwrite the Buffer with zeros, e.g. to wipe a private key or seed
+/// which is no longer needed
+/// @arguments
+/// - buffer: Buffer to wipe
+pub fn wipe(mut ctx: FunctionContext) -> JsResult<JsUndefined> {
+    let mut buffer = ctx.argument::<JsBuffer>(0)?;
+    ctx.borrow_mut(&mut buffer, |data| {
+        data.as_mut_slice::<u8>().zeroize();
+    });
+
+    Ok(ctx.undefined())
+}
+
+/// Derive the key pair at the BIP32 derivation path
+/// @arguments
+/// - seed_or_xprv: seed Buffer or base58check encoded extended private key
+/// - path: derivation path (e.g. m/44'/394'/0'/0/0)
+/// @return {
+///     privateKey: Buffer,
+///     publicKey: Buffer,
+///     compressedPublicKey: Buffer
+/// }
+pub fn derive_private_key(mut ctx: FunctionContext) -> JsResult<JsObject> {
+    metrics::measure_js("keyPair.derivePrivateKey", || {
+        let private_key = derived_private_key_argument(&mut ctx, 0)?;
+
+        key_pair_to_js(&mut ctx, &private_key)
+    })
+}
+
+/// Generate new random key pairs in one call
+/// @arguments
+/// - count: number of key pairs to generate, up to 10000
+/// - entropy: (optional) extra entropy mixed with the OS RNG into every key,
+///     see `newPrivateKey`
+/// @return [{
+///     privateKey: Buffer,
+///     publicKey: Buffer,
+///     compressedPublicKey: Buffer
+/// }]
+pub fn new_key_pairs(mut ctx: FunctionContext) -> JsResult<JsArray> {
+    metrics::measure_js("keyPair.newKeyPairs", || {
+        let count = ctx.argument::<JsNumber>(0)?.value();
+        if count < 1.0 || count > MAX_KEY_PAIRS_COUNT as f64 || count.fract() != 0.0 {
+            return ctx.throw_error(format!(
+                "Count should be an integer between 1 and {}",
+                MAX_KEY_PAIRS_COUNT
+            ));
+        }
+        let count = count as u32;
+        let entropy = entropy_argument(&mut ctx, 1)?;
+
+        let key_pairs = JsArray::new(&mut ctx, count);
+        for i in 0..count {
+            let private_key = random_private_key(entropy.as_ref().map(|entropy| &entropy[..]))
+                .chain_neon(&mut ctx, "Unable to generate private key")?;
+            let key_pair = key_pair_to_js(&mut ctx, &private_key)?;
+            key_pairs.set(&mut ctx, i, key_pair)?;
+        }
+
+        Ok(key_pairs)
+    })
+}
+
+/// Returns { privateKey, publicKey, compressedPublicKey } of the private key
+pub fn key_pair_to_js<'a>(
+    ctx: &mut FunctionContext<'a>,
+    private_key: &PrivateKey,
+) -> JsResult<'a, JsObject> {
+    let public_key = PublicKey::from(private_key);
+    let compressed_public_key = public_key.serialize_compressed();
+    let public_key = public_key.serialize();
+    let private_key_buf = private_key_to_buffer(ctx, private_key)?;
+
+    let public_key_buf = bytes_to_buffer(ctx, &public_key)?;
+
+    let compressed_public_key_buf = bytes_to_buffer(ctx, &compressed_public_key)?;
+
+    let js_object = JsObject::new(ctx);
+    js_object.set(ctx, "privateKey", private_key_buf)?;
+    js_object.set(ctx, "publicKey", public_key_buf)?;
+    js_object.set(ctx, "compressedPublicKey", compressed_public_key_buf)?;
+
+    Ok(js_object)
+}
+
+/// Derive private key from the seed Buffer or extended private key at
+/// argument `i` and the derivation path at argument `i + 1`
+fn derived_private_key_argument(ctx: &mut FunctionContext, i: i32) -> NeonResult<PrivateKey> {
+    let root_key = extended_private_key_argument(ctx, i)?;
+    let path = ctx.argument::<JsString>(i + 1)?.value();
+
+    let derived_key = root_key
+        .derive_path(&path)
+        .chain_neon(ctx, "Unable to derive key from path")?;
+
+    PrivateKey::deserialize_from(&derived_key.private_key()[..]).chain_neon_with_code(
+        ctx,
+        ErrorCode::InvalidPrivateKey,
+        "Unable to deserialize derived private key",
+    )
+}
+
+/// ECDSA sign the message digest with private key
+/// @arguments
+/// - digest: 32 bytes digest Buffer of the message, e.g. its SHA-256 hash.
+///     The digest is signed as is, messages are not hashed
+/// - private_key: private key Buffer or key handle to sign with
+/// @return signature: 64 bytes compact ECDSA signature
+pub fn sign_message(mut ctx: FunctionContext) -> JsResult<JsBuffer> {
+    metrics::measure_js("keyPair.signMessage", || {
+        let message = digest_argument(&mut ctx, 0)?;
+        let secret_key = secret_key_argument(&mut ctx, 1)?;
+
+        let signature = with_secp(|secp| secp.sign(&message, &secret_key)).serialize_compact();
+
+        bytes_to_buffer(&mut ctx, &signature)
+    })
+}
+
+/// Verify the ECDSA signature of the message digest
+/// @arguments
+/// - digest: 32 bytes digest Buffer of the message, see `sign`
+/// - signature: 64 bytes compact or 65 bytes recoverable ECDSA signature
+/// - public_key: public key of the signer
+/// @return boolean
+pub fn verify_message(mut ctx: FunctionContext) -> JsResult<JsBoolean> {
+    let message = digest_argument(&mut ctx, 0)?;
+    let signature = hex_or_buffer_argument(&mut ctx, 1, "signature")?;
+    let public_key = public_key_argument(&mut ctx, 2)?;
+
+    let signature = match signature.len() {
+        64 | 65 => Signature::from_compact(&signature[..64]).chain_neon_with_code(
+            &mut ctx,
+            ErrorCode::InvalidSignature,
+            "Unable to deserialize ECDSA signature",
+        )?,
+        _ => return ctx.throw_error("Signature should be 64 or 65 bytes long"),
+    };
+    let public_key: secp256k1::PublicKey = public_key.into();
+
+    let is_valid = with_secp(|secp| secp.verify(&message, &signature, &public_key).is_ok());
+
+    Ok(ctx.boolean(is_valid))
+}
+
+/// ECDSA sign the message digest with private key into a recoverable
+/// signature
+/// @arguments
+/// - digest: 32 bytes digest Buffer of the message, see `sign`
+/// - private_key: private key Buffer or key handle to sign with
+/// @return signature: 65 bytes recoverable signature (r, s, recovery id)
+pub fn sign_recoverable(mut ctx: FunctionContext) -> JsResult<JsBuffer> {
+    metrics::measure_js("keyPair.signRecoverable", || {
+        let message = digest_argument(&mut ctx, 0)?;
+        let secret_key = secret_key_argument(&mut ctx, 1)?;
+
+        let (recovery_id, signature) =
+            with_secp(|secp| secp.sign_recoverable(&message, &secret_key)).serialize_compact();
+        let mut recoverable_signature = signature.to_vec();
+        recoverable_signature.push(recovery_id.to_i32() as u8);
+
+        bytes_to_buffer(&mut ctx, &recoverable_signature)
+    })
+}
+
+/// Recover the public key of the signer from recoverable ECDSA signature
+/// @arguments
+/// - digest: 32 bytes digest Buffer of the message, see `sign`
+/// - signature: 65 bytes recoverable signature (r, s, recovery id)
+/// @return {
+///     publicKey: Buffer,
+///     compressedPublicKey: Buffer
+/// }
+pub fn recover_public_key(mut ctx: FunctionContext) -> JsResult<JsObject> {
+    let message = digest_argument(&mut ctx, 0)?;
+    let signature = hex_or_buffer_argument(&mut ctx, 1, "signature")?;
+
+    if signature.len() != 65 {
+        return ctx.throw_error("Recoverable signature should be 65 bytes long");
+    }
+    let recovery_id = RecoveryId::from_i32(i32::from(signature[64])).chain_neon_with_code(
+        &mut ctx,
+        ErrorCode::InvalidSignature,
+        "Invalid signature recovery id",
+    )?;
+    let signature = RecoverableSignature::from_compact(&signature[..64], recovery_id)
+        .chain_neon_with_code(
+            &mut ctx,
+            ErrorCode::InvalidSignature,
+            "Unable to deserialize recoverable signature",
+        )?;
+
+    let public_key = with_secp(|secp| secp.recover(&message, &signature)).chain_neon_with_code(
+        &mut ctx,
+        ErrorCode::InvalidPublicKey,
+        "Unable to recover public key from signature",
+    )?;
+    let compressed_public_key = public_key.serialize();
+    let public_key = public_key.serialize_uncompressed();
+
+    let public_key_buf = bytes_to_buffer(&mut ctx, &public_key)?;
+
+    let compressed_public_key_buf = bytes_to_buffer(&mut ctx, &compressed_public_key)?;
+
+    let js_object = JsObject::new(&mut ctx);
+    js_object.set(&mut ctx, "publicKey", public_key_buf)?;
+    js_object.set(&mut ctx, "compressedPublicKey", compressed_public_key_buf)?;
+
+    Ok(js_object)
+}
+
+/// Schnorr sign the message digest with private key
+/// @arguments
+/// - digest: 32 bytes digest Buffer of the message, see `sign`
+/// - private_key: private key Buffer or key handle to sign with
+/// @return signature: 64 bytes Schnorr signature
+pub fn schnorr_sign_message(mut ctx: FunctionContext) -> JsResult<JsBuffer> {
+    metrics::measure_js("keyPair.schnorrSignMessage", || {
+        let message = digest_argument(&mut ctx, 0)?;
+        let secret_key = secret_key_argument(&mut ctx, 1)?;
+
+        let signature =
+            with_secp(|secp| schnorr_sign(secp, &message, &secret_key)).serialize_default();
+
+        bytes_to_buffer(&mut ctx, &signature)
+    })
+}
+
+/// Verify the Schnorr signature of the message digest
+/// @arguments
+/// - digest: 32 bytes digest Buffer of the message, see `sign`
+/// - signature: 64 bytes Schnorr signature
+/// - public_key: public key of the signer
+/// @return boolean
+pub fn schnorr_verify_message(mut ctx: FunctionContext) -> JsResult<JsBoolean> {
+    let message = digest_argument(&mut ctx, 0)?;
+    let signature = hex_or_buffer_argument(&mut ctx, 1, "signature")?;
+    let public_key = public_key_argument(&mut ctx, 2)?;
+
+    let signature = SchnorrSignature::from_default(&signature).chain_neon_with_code(
+        &mut ctx,
+        ErrorCode::InvalidSignature,
+        "Unable to deserialize Schnorr signature",
+    )?;
+    let public_key: secp256k1::PublicKey = public_key.into();
+
+    let is_valid =
+        with_secp(|secp| schnorr_verify(secp, &message, &signature, &public_key).is_ok());
+
+    Ok(ctx.boolean(is_valid))
+}
+
+/// Returns the 32 bytes message digest at argument `i`
+fn digest_argument(ctx: &mut FunctionContext, i: i32) -> NeonResult<Message> {
+    let digest = fixed_buffer_argument(ctx, i, "digest", ErrorCode::InvalidArgument, 32)?;
+
+    Message::from_slice(&digest).chain_neon(ctx, "Unable to form message from digest")
+}
+
+pub fn is_valid_view_key(mut ctx: FunctionContext) -> JsResult<JsBoolean> {
+    let view_key = hex_or_buffer_argument(&mut ctx, 0, "view key")?;
+
+    let view_key = hex::encode_upper(view_key);
+
+    let is_valid = match PublicKey::from_str(&view_key) {
+        Ok(_) => true,
+        _ => false,
+    };
+
+    Ok(ctx.boolean(is_valid))
+}
+
+pub fn register_key_pair_module(ctx: &mut ModuleContext) -> NeonResult<()> {
+    let js_object = JsObject::new(ctx);
+
+    let verify_private_key_fn = JsFunction::new(ctx, verify_private_key)?;
+    js_object.set(ctx, "verifyPrivateKey", verify_private_key_fn)?;
+
+    let verify_public_key_fn = JsFunction::new(ctx, verify_public_key)?;
+    js_object.set(ctx, "verifyPublicKey", verify_public_key_fn)?;
+
+    let get_public_keys_from_private_key_fn =
+        JsFunction::new(ctx, get_public_keys_from_private_key)?;
+    js_object.set(
+        ctx,
+        "getPublicKeysFromPrivateKey",
+        get_public_keys_from_private_key_fn,
+    )?;
+
+    let get_public_keys_from_any_public_key_fn =
+        JsFunction::new(ctx, get_public_keys_from_any_public_key)?;
+    js_object.set(
+        ctx,
+        "getPublicKeysFromAnyPublicKey",
+        get_public_keys_from_any_public_key_fn,
+    )?;
+
+    let compress_public_key_fn = JsFunction::new(ctx, compress_public_key)?;
+    js_object.set(ctx, "compressPublicKey", compress_public_key_fn)?;
+
+    let decompress_public_key_fn = JsFunction::new(ctx, decompress_public_key)?;
+    js_object.set(ctx, "decompressPublicKey", decompress_public_key_fn)?;
+
+    let combine_public_keys_fn = JsFunction::new(ctx, combine_public_keys)?;
+    js_object.set(ctx, "combinePublicKeys", combine_public_keys_fn)?;
+
+    let tweak_add_public_key_fn = JsFunction::new(ctx, tweak_add_public_key)?;
+    js_object.set(ctx, "tweakAddPublicKey", tweak_add_public_key_fn)?;
+
+    let tweak_add_private_key_fn = JsFunction::new(ctx, tweak_add_private_key)?;
+    js_object.set(ctx, "tweakAddPrivateKey", tweak_add_private_key_fn)?;
+
+    let new_private_key_fn = JsFunction::new(ctx, new_private_key)?;
+    js_object.set(ctx, "newPrivateKey", new_private_key_fn)?;
+
+    let new_key_pairs_fn = JsFunction::new(ctx, new_key_pairs)?;
+    js_object.set(ctx, "newKeyPairs", new_key_pairs_fn)?;
+
+    let derive_private_key_fn = JsFunction::new(ctx, derive_private_key)?;
+    js_object.set(ctx, "derivePrivateKey", derive_private_key_fn)?;
+
+    let sign_message_fn = JsFunction::new(ctx, sign_message)?;
+    js_object.set(ctx, "sign", sign_message_fn)?;
+
+    let verify_message_fn = JsFunction::new(ctx, verify_message)?;
+    js_object.set(ctx, "verify", verify_message_fn)?;
+
+    let verify_batch_fn = JsFunction::new(ctx, verify_batch)?;
+    js_object.set(ctx, "verifyBatch", verify_batch_fn)?;
+
+    let sign_recoverable_fn = JsFunction::new(ctx, sign_recoverable)?;
+    js_object.set(ctx, "signRecoverable", sign_recoverable_fn)?;
+
+    let recover_public_key_fn = JsFunction::new(ctx, recover_public_key)?;
+    js_object.set(ctx, "recoverPublicKey", recover_public_key_fn)?;
+
+    let schnorr_sign_message_fn = JsFunction::new(ctx, schnorr_sign_message)?;
+    js_object.set(ctx, "schnorrSign", schnorr_sign_message_fn)?;
+
+    let schnorr_verify_message_fn = JsFunction::new(ctx, schnorr_verify_message)?;
+    js_object.set(ctx, "schnorrVerify", schnorr_verify_message_fn)?;
+
+    let is_valid_view_key_fn = JsFunction::new(ctx, is_valid_view_key)?;
+    js_object.set(ctx, "isValidViewKey", is_valid_view_key_fn)?;
+
+    let wipe_fn = JsFunction::new(ctx, wipe)?;
+    js_object.set(ctx, "wipe", wipe_fn)?;
+
+    let to_wif_fn = JsFunction::new(ctx, to_wif)?;
+    js_object.set(ctx, "toWIF", to_wif_fn)?;
+
+    let from_wif_fn = JsFunction::new(ctx, from_wif)?;
+    js_object.set(ctx, "fromWIF", from_wif_fn)?;
+
+    let to_hex_fn = JsFunction::new(ctx, to_hex)?;
+    js_object.set(ctx, "toHex", to_hex_fn)?;
+
+    let from_hex_fn = JsFunction::new(ctx, from_hex)?;
+    js_object.set(ctx, "fromHex", from_hex_fn)?;
+
+    let new_private_key_handle_fn = JsFunction::new(ctx, new_private_key_handle)?;
+    js_object.set(ctx, "newPrivateKeyHandle", new_private_key_handle_fn)?;
+
+    let derive_private_key_handle_fn = JsFunction::new(ctx, derive_private_key_handle)?;
+    js_object.set(ctx, "derivePrivateKeyHandle", derive_private_key_handle_fn)?;
+
+    ctx.export_value("keyPair", js_object)
+}
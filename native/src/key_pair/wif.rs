@@ -0,0 +1,157 @@
+//! Wallet Import Format and hex encoding of private keys, for keys migrating
+//! from other secp256k1 tooling
+use chain_core::init::network::Network;
+use client_common::{Error, ErrorKind, PrivateKey, Result, ResultExt};
+use neon::prelude::*;
+use zeroize::Zeroizing;
+
+use crate::error::{ClientErrorNeonExt, ErrorCode};
+use crate::function_types::*;
+
+use super::private_key_to_buffer;
+
+const WIF_MAINNET_VERSION: u8 = 0x80;
+const WIF_TESTNET_VERSION: u8 = 0xEF;
+const WIF_COMPRESSED_FLAG: u8 = 0x01;
+const PRIVATE_KEY_LENGTH: usize = 32;
+
+/// encode private key in Wallet Import Format with compressed public key
+/// flag. Devnet uses the testnet version byte
+/// @arguments
+/// - private_key: private key Buffer
+/// - network: network handle or name
+/// @return WIF string
+pub fn to_wif(mut ctx: FunctionContext) -> JsResult<JsString> {
+    let private_key = private_key_argument(&mut ctx, 0)?;
+    let network = network_config_argument(&mut ctx, 1)?.network;
+
+    let version = match network {
+        Network::Mainnet => WIF_MAINNET_VERSION,
+        Network::Testnet | Network::Devnet => WIF_TESTNET_VERSION,
+    };
+    let private_key = Zeroizing::new(private_key.serialize());
+    let mut data = Zeroizing::new(Vec::with_capacity(PRIVATE_KEY_LENGTH + 2));
+    data.push(version);
+    data.extend_from_slice(&private_key);
+    data.push(WIF_COMPRESSED_FLAG);
+
+    let wif = Zeroizing::new(bs58::encode(data.as_slice()).with_check().into_string());
+
+    Ok(ctx.string(wif.as_str()))
+}
+
+/// decode private key from Wallet Import Format
+/// @arguments
+/// - wif: WIF string
+/// @return {
+///     privateKey: Buffer,
+///     compressed: boolean, whether the key is for compressed public key
+///     networkType: Mainnet or Testnet, Devnet keys decode as Testnet
+/// }
+pub fn from_wif(mut ctx: FunctionContext) -> JsResult<JsObject> {
+    let wif = Zeroizing::new(ctx.argument::<JsString>(0)?.value());
+
+    let (private_key, network_type, compressed) = decode_wif(&wif).chain_neon_with_code(
+        &mut ctx,
+        ErrorCode::InvalidPrivateKey,
+        "Unable to decode WIF private key",
+    )?;
+
+    let private_key_buf = private_key_to_buffer(&mut ctx, &private_key)?;
+    let compressed = ctx.boolean(compressed);
+    let network_type = ctx.string(network_type);
+
+    let js_object = JsObject::new(&mut ctx);
+    js_object.set(&mut ctx, "privateKey", private_key_buf)?;
+    js_object.set(&mut ctx, "compressed", compressed)?;
+    js_object.set(&mut ctx, "networkType", network_type)?;
+
+    Ok(js_object)
+}
+
+/// decode private key from hex string. Only exactly 64 hex characters with
+/// optional 0x prefix are accepted
+/// @arguments
+/// - hex: hex encoded private key
+/// @return private key Buffer
+pub fn from_hex(mut ctx: FunctionContext) -> JsResult<JsBuffer> {
+    let private_key_hex = Zeroizing::new(ctx.argument::<JsString>(0)?.value());
+
+    let private_key = decode_private_key_hex(&private_key_hex).chain_neon_with_code(
+        &mut ctx,
+        ErrorCode::InvalidPrivateKey,
+        "Unable to decode hex private key",
+    )?;
+
+    private_key_to_buffer(&mut ctx, &private_key)
+}
+
+/// encode private key in lowercase hex without prefix
+/// @arguments
+/// - private_key: private key Buffer
+/// @return hex string
+pub fn to_hex(mut ctx: FunctionContext) -> JsResult<JsString> {
+    let private_key = private_key_argument(&mut ctx, 0)?;
+
+    let private_key = Zeroizing::new(private_key.serialize());
+    let private_key_hex = Zeroizing::new(hex::encode(private_key.as_slice()));
+
+    Ok(ctx.string(private_key_hex.as_str()))
+}
+
+fn decode_wif(wif: &str) -> Result<(PrivateKey, &'static str, bool)> {
+    let data = Zeroizing::new(bs58::decode(wif).with_check(None).into_vec().chain(|| {
+        (
+            ErrorKind::DeserializationError,
+            "Unable to decode base58check WIF",
+        )
+    })?);
+
+    let network_type = match data.first() {
+        Some(&WIF_MAINNET_VERSION) => "Mainnet",
+        Some(&WIF_TESTNET_VERSION) => "Testnet",
+        _ => {
+            return Err(Error::new(
+                ErrorKind::DeserializationError,
+                "Unrecognized WIF version byte",
+            ))
+        }
+    };
+    let compressed = match data.len() {
+        len if len == PRIVATE_KEY_LENGTH + 1 => false,
+        len if len == PRIVATE_KEY_LENGTH + 2 && data[len - 1] == WIF_COMPRESSED_FLAG => true,
+        _ => {
+            return Err(Error::new(
+                ErrorKind::DeserializationError,
+                "Invalid WIF payload length",
+            ))
+        }
+    };
+
+    let private_key = PrivateKey::deserialize_from(&data[1..=PRIVATE_KEY_LENGTH])?;
+
+    Ok((private_key, network_type, compressed))
+}
+
+fn decode_private_key_hex(private_key_hex: &str) -> Result<PrivateKey> {
+    let private_key_hex = if private_key_hex.starts_with("0x") {
+        &private_key_hex[2..]
+    } else {
+        private_key_hex
+    };
+    if private_key_hex.len() != PRIVATE_KEY_LENGTH * 2 {
+        return Err(Error::new(
+            ErrorKind::DeserializationError,
+            "Hex private key should be 64 characters long",
+        ));
+    }
+
+    let private_key = Zeroizing::new(hex::decode(private_key_hex).chain(|| {
+        (
+            ErrorKind::DeserializationError,
+            "Hex private key contains non-hex character",
+        )
+    })?);
+
+    PrivateKey::deserialize_from(&private_key)
+}
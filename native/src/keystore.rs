@@ -0,0 +1,703 @@
+//! Password encrypted JSON keystore of private key
+use aes_gcm::aead::generic_array::GenericArray;
+use aes_gcm::aead::{Aead, NewAead};
+use aes_gcm::Aes256Gcm;
+use std::cmp;
+
+use argon2::{Config, ThreadMode, Variant, Version};
+use client_common::{Error, ErrorKind, PrivateKey, Result, ResultExt};
+use neon::prelude::*;
+use secstr::SecUtf8;
+use serde::{Deserialize, Serialize};
+use zeroize::Zeroizing;
+
+use crate::cancellation::CancellationToken;
+use crate::common::does_js_object_has_prop;
+use crate::error::{throw_coded_error, ClientErrorNeonExt, ErrorCode, NativeError};
+use crate::function_types::*;
+use crate::rng::fill_random_bytes;
+
+const KEYSTORE_VERSION: u32 = 1;
+const CIPHER_AES_256_GCM: &str = "aes-256-gcm";
+const KDF_ARGON2ID: &str = "argon2id";
+const KDF_SCRYPT: &str = "scrypt";
+const DERIVED_KEY_LENGTH: u32 = 32;
+const SALT_LENGTH: usize = 32;
+const NONCE_LENGTH: usize = 12;
+/// Upper bounds of the KDF parameters, also guard decryption against keystores
+/// crafted to exhaust memory or CPU. Memory is capped at 256 MiB and the work,
+/// memory times passes over it, at 4 passes over that memory
+const MAX_MEMORY_COST: u64 = 256 * 1024;
+const MAX_WORK: u64 = 4 * MAX_MEMORY_COST;
+const MAX_TIME_COST: u32 = 64;
+const MAX_PARALLELISM: u32 = 64;
+/// Default work factors, 64 MiB of memory for argon2id and 32 MiB for scrypt
+const DEFAULT_MEMORY_COST: u32 = 65536;
+const DEFAULT_TIME_COST: u32 = 3;
+const DEFAULT_PARALLELISM: u32 = 4;
+const DEFAULT_SCRYPT_N: u64 = 1 << 15;
+const DEFAULT_SCRYPT_R: u32 = 8;
+const DEFAULT_SCRYPT_P: u32 = 1;
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Keystore {
+    pub version: u32,
+    pub crypto: KeystoreCrypto,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KeystoreCrypto {
+    pub cipher: String,
+    pub cipher_text: String,
+    pub cipher_params: CipherParams,
+    pub kdf: String,
+    pub kdf_params: KdfParams,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CipherParams {
+    pub nonce: String,
+}
+
+/// Parameters of the kdf of the keystore, told apart by their fields
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum KdfParams {
+    Argon2id(Argon2idParams),
+    Scrypt(ScryptParams),
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Argon2idParams {
+    pub memory_cost: u32,
+    pub time_cost: u32,
+    pub parallelism: u32,
+    pub key_length: u32,
+    pub salt: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScryptParams {
+    pub n: u64,
+    pub r: u32,
+    pub p: u32,
+    pub key_length: u32,
+    pub salt: String,
+}
+
+/// Work factor of the key derivation
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KdfOptions {
+    Argon2id {
+        /// Memory cost in KiB
+        memory_cost: u32,
+        /// Number of iterations
+        time_cost: u32,
+        /// Number of lanes
+        parallelism: u32,
+    },
+    Scrypt {
+        /// CPU and memory cost, a power of 2
+        n: u64,
+        /// Block size
+        r: u32,
+        /// Parallelization
+        p: u32,
+    },
+}
+
+impl Default for KdfOptions {
+    fn default() -> Self {
+        KdfOptions::Argon2id {
+            memory_cost: DEFAULT_MEMORY_COST,
+            time_cost: DEFAULT_TIME_COST,
+            parallelism: DEFAULT_PARALLELISM,
+        }
+    }
+}
+
+impl KdfOptions {
+    /// Check the parameters are accepted by the kdf and within the bounds
+    pub fn validate(&self) -> Result<()> {
+        match *self {
+            KdfOptions::Argon2id {
+                memory_cost,
+                time_cost,
+                parallelism,
+            } => {
+                if parallelism == 0 || parallelism > MAX_PARALLELISM {
+                    return Err(Error::new(
+                        ErrorKind::InvalidInput,
+                        format!(
+                            "KDF parallelism should be between 1 and {}",
+                            MAX_PARALLELISM
+                        ),
+                    ));
+                }
+                if time_cost == 0 || time_cost > MAX_TIME_COST {
+                    return Err(Error::new(
+                        ErrorKind::InvalidInput,
+                        format!("KDF time cost should be between 1 and {}", MAX_TIME_COST),
+                    ));
+                }
+                // argon2 requires at least 8 KiB of memory per lane
+                let min_memory_cost = 8 * parallelism;
+                if memory_cost < min_memory_cost || u64::from(memory_cost) > MAX_MEMORY_COST {
+                    return Err(Error::new(
+                        ErrorKind::InvalidInput,
+                        format!(
+                            "KDF memory cost should be between {} and {} KiB",
+                            min_memory_cost, MAX_MEMORY_COST
+                        ),
+                    ));
+                }
+
+                validate_work(u64::from(memory_cost), u64::from(time_cost))
+            }
+            KdfOptions::Scrypt { n, r, p } => {
+                if n < 2 || !n.is_power_of_two() {
+                    return Err(Error::new(
+                        ErrorKind::InvalidInput,
+                        "KDF n should be a power of 2 greater than 1",
+                    ));
+                }
+                if r == 0 || p == 0 || p > MAX_PARALLELISM {
+                    return Err(Error::new(
+                        ErrorKind::InvalidInput,
+                        format!(
+                            "KDF r should be positive and p between 1 and {}",
+                            MAX_PARALLELISM
+                        ),
+                    ));
+                }
+                // scrypt uses 128 * r * n bytes of memory, which each of the
+                // p passes goes over
+                let memory_cost = n.saturating_mul(u64::from(r)).saturating_mul(128) / 1024;
+                if memory_cost > MAX_MEMORY_COST {
+                    return Err(Error::new(
+                        ErrorKind::InvalidInput,
+                        format!(
+                            "KDF memory cost 128 * r * n should be at most {} KiB",
+                            MAX_MEMORY_COST
+                        ),
+                    ));
+                }
+
+                validate_work(memory_cost, u64::from(p))
+            }
+        }
+    }
+
+    fn kdf(&self) -> &'static str {
+        match self {
+            KdfOptions::Argon2id { .. } => KDF_ARGON2ID,
+            KdfOptions::Scrypt { .. } => KDF_SCRYPT,
+        }
+    }
+
+    fn params(&self, salt: String) -> KdfParams {
+        match *self {
+            KdfOptions::Argon2id {
+                memory_cost,
+                time_cost,
+                parallelism,
+            } => KdfParams::Argon2id(Argon2idParams {
+                memory_cost,
+                time_cost,
+                parallelism,
+                key_length: DERIVED_KEY_LENGTH,
+                salt,
+            }),
+            KdfOptions::Scrypt { n, r, p } => KdfParams::Scrypt(ScryptParams {
+                n,
+                r,
+                p,
+                key_length: DERIVED_KEY_LENGTH,
+                salt,
+            }),
+        }
+    }
+}
+
+fn validate_work(memory_cost: u64, passes: u64) -> Result<()> {
+    if memory_cost.saturating_mul(passes) > MAX_WORK {
+        return Err(Error::new(
+            ErrorKind::InvalidInput,
+            format!(
+                "KDF work, memory cost in KiB times passes, should be at most {}",
+                MAX_WORK
+            ),
+        ));
+    }
+
+    Ok(())
+}
+
+impl KdfParams {
+    fn options(&self) -> KdfOptions {
+        match self {
+            KdfParams::Argon2id(params) => KdfOptions::Argon2id {
+                memory_cost: params.memory_cost,
+                time_cost: params.time_cost,
+                parallelism: params.parallelism,
+            },
+            KdfParams::Scrypt(params) => KdfOptions::Scrypt {
+                n: params.n,
+                r: params.r,
+                p: params.p,
+            },
+        }
+    }
+
+    fn key_length(&self) -> u32 {
+        match self {
+            KdfParams::Argon2id(params) => params.key_length,
+            KdfParams::Scrypt(params) => params.key_length,
+        }
+    }
+
+    fn salt(&self) -> &str {
+        match self {
+            KdfParams::Argon2id(params) => &params.salt,
+            KdfParams::Scrypt(params) => &params.salt,
+        }
+    }
+}
+
+impl Keystore {
+    /// Encrypt the private key with a key derived from the password
+    pub fn encrypt(private_key: &PrivateKey, password: &SecUtf8) -> Result<Keystore> {
+        Keystore::encrypt_with(private_key, password, KdfOptions::default())
+    }
+
+    /// Encrypt the private key with a key derived from the password using the
+    /// KDF options, which are recorded in the keystore
+    pub fn encrypt_with(
+        private_key: &PrivateKey,
+        password: &SecUtf8,
+        kdf_options: KdfOptions,
+    ) -> Result<Keystore> {
+        let plain_text = Zeroizing::new(private_key.serialize());
+
+        Ok(Keystore {
+            version: KEYSTORE_VERSION,
+            crypto: KeystoreCrypto::encrypt_with(&plain_text, password, kdf_options)?,
+        })
+    }
+
+    /// Decrypt the private key with a key derived from the password
+    pub fn decrypt(&self, password: &SecUtf8) -> Result<PrivateKey> {
+        if self.version != KEYSTORE_VERSION {
+            return Err(Error::new(
+                ErrorKind::DeserializationError,
+                format!("Unsupported keystore version {}", self.version),
+            ));
+        }
+
+        let plain_text = Zeroizing::new(self.crypto.decrypt(password)?);
+
+        PrivateKey::deserialize_from(&plain_text)
+    }
+}
+
+impl KeystoreCrypto {
+    /// Encrypt the plain text with a key derived from the password
+    pub fn encrypt(plain_text: &[u8], password: &SecUtf8) -> Result<KeystoreCrypto> {
+        KeystoreCrypto::encrypt_with(plain_text, password, KdfOptions::default())
+    }
+
+    /// Encrypt the plain text with a key derived from the password using the
+    /// KDF options
+    pub fn encrypt_with(
+        plain_text: &[u8],
+        password: &SecUtf8,
+        kdf_options: KdfOptions,
+    ) -> Result<KeystoreCrypto> {
+        kdf_options.validate()?;
+
+        let mut salt = [0u8; SALT_LENGTH];
+        fill_random_bytes(&mut salt)?;
+        let mut nonce = [0u8; NONCE_LENGTH];
+        fill_random_bytes(&mut nonce)?;
+
+        let kdf_params = kdf_options.params(hex::encode(salt));
+        let derived_key = derive_key(password, &kdf_params)?;
+
+        let cipher = Aes256Gcm::new(*GenericArray::from_slice(&derived_key[..]));
+        let cipher_text = cipher
+            .encrypt(GenericArray::from_slice(&nonce), plain_text)
+            .map_err(|_| Error::new(ErrorKind::EncryptionError, "Unable to encrypt keystore"))?;
+
+        Ok(KeystoreCrypto {
+            cipher: CIPHER_AES_256_GCM.to_owned(),
+            cipher_text: hex::encode(cipher_text),
+            cipher_params: CipherParams {
+                nonce: hex::encode(nonce),
+            },
+            kdf: kdf_options.kdf().to_owned(),
+            kdf_params,
+        })
+    }
+
+    /// Decrypt the plain text with a key derived from the password. The KDF
+    /// params are checked against the bounds before any key derivation
+    pub fn decrypt(&self, password: &SecUtf8) -> Result<Vec<u8>> {
+        if self.cipher != CIPHER_AES_256_GCM {
+            return Err(Error::new(
+                ErrorKind::DeserializationError,
+                format!("Unsupported keystore cipher {}", self.cipher),
+            ));
+        }
+        let kdf_options = self.kdf_params.options();
+        if self.kdf != kdf_options.kdf() {
+            return Err(Error::new(
+                ErrorKind::DeserializationError,
+                format!("Unsupported keystore kdf {}", self.kdf),
+            ));
+        }
+
+        let nonce = hex::decode(&self.cipher_params.nonce)
+            .chain(|| (ErrorKind::DeserializationError, "Invalid keystore nonce"))?;
+        if nonce.len() != NONCE_LENGTH {
+            return Err(Error::new(
+                ErrorKind::DeserializationError,
+                "Keystore nonce should be 12 bytes long",
+            ));
+        }
+        let cipher_text = hex::decode(&self.cipher_text).chain(|| {
+            (
+                ErrorKind::DeserializationError,
+                "Invalid keystore cipher text",
+            )
+        })?;
+
+        kdf_options.validate().chain(|| {
+            (
+                ErrorKind::DeserializationError,
+                "Unsupported keystore kdf params",
+            )
+        })?;
+        let derived_key = derive_key(password, &self.kdf_params)?;
+
+        let cipher = Aes256Gcm::new(*GenericArray::from_slice(&derived_key[..]));
+        cipher
+            .decrypt(GenericArray::from_slice(&nonce), cipher_text.as_slice())
+            .map_err(|_| {
+                Error::new(
+                    ErrorKind::DecryptionError,
+                    "Unable to decrypt keystore, incorrect password",
+                )
+            })
+    }
+}
+
+fn derive_key(password: &SecUtf8, kdf_params: &KdfParams) -> Result<Zeroizing<Vec<u8>>> {
+    if kdf_params.key_length() != DERIVED_KEY_LENGTH {
+        return Err(Error::new(
+            ErrorKind::DeserializationError,
+            "Keystore derived key should be 32 bytes long",
+        ));
+    }
+    let salt = hex::decode(kdf_params.salt())
+        .chain(|| (ErrorKind::DeserializationError, "Invalid keystore salt"))?;
+
+    match kdf_params {
+        KdfParams::Argon2id(params) => {
+            let config = Config {
+                variant: Variant::Argon2id,
+                version: Version::Version13,
+                mem_cost: params.memory_cost,
+                time_cost: params.time_cost,
+                lanes: params.parallelism,
+                thread_mode: ThreadMode::Parallel,
+                secret: &[],
+                ad: &[],
+                hash_length: params.key_length,
+            };
+
+            argon2::hash_raw(password.unsecure().as_bytes(), &salt, &config)
+                .map(Zeroizing::new)
+                .chain(|| {
+                    (
+                        ErrorKind::InternalError,
+                        "Unable to derive key from password",
+                    )
+                })
+        }
+        KdfParams::Scrypt(params) => {
+            let log_n = params.n.trailing_zeros() as u8;
+            let scrypt_params =
+                scrypt::ScryptParams::new(log_n, params.r, params.p).map_err(|_| {
+                    Error::new(
+                        ErrorKind::DeserializationError,
+                        "Unsupported keystore kdf params",
+                    )
+                })?;
+
+            let mut derived_key = Zeroizing::new(vec![0u8; params.key_length as usize]);
+            scrypt::scrypt(
+                password.unsecure().as_bytes(),
+                &salt,
+                &scrypt_params,
+                &mut derived_key,
+            )
+            .map_err(|_| {
+                Error::new(
+                    ErrorKind::InternalError,
+                    "Unable to derive key from password",
+                )
+            })?;
+
+            Ok(derived_key)
+        }
+    }
+}
+
+/// encrypt private key into JSON keystore
+/// @arguments
+/// - private_key: private key to encrypt
+/// - password: password to encrypt with
+/// - options: (optional) KDF and work factor { kdf?, memoryCost?, timeCost?,
+///     parallelism?, n?, r?, p? }, recorded in the keystore kdfParams. The
+///     memory is at most 256 MiB and memory times passes at most 4 passes
+///     over 256 MiB, which decryption enforces too
+///     - kdf: argon2id or scrypt, default argon2id
+///     - memoryCost: argon2id memory in KiB, between 8 * parallelism and
+///         262144, default 65536
+///     - timeCost: argon2id number of iterations, between 1 and 64, default 3
+///     - parallelism: argon2id number of lanes, between 1 and 64, default 4
+///     - n: scrypt cost, a power of 2, default 32768
+///     - r: scrypt block size, default 8
+///     - p: scrypt parallelization, between 1 and 64, default 1
+/// - callback: (optional) node-style callback, the key derivation runs on
+///     background thread and the keystore is passed to callback when provided
+/// @return keystore JSON string, or the task handle { id, cancel() } with
+///     callback
+pub fn encrypt(mut ctx: FunctionContext) -> JsResult<JsValue> {
+    let private_key = private_key_argument(&mut ctx, 0)?;
+    let password = SecUtf8::from(ctx.argument::<JsString>(1)?.value());
+    let callback = callback_argument(&mut ctx)?;
+    let kdf_options = match ctx.argument_opt(2) {
+        Some(options)
+            if !options.is_a::<JsUndefined>()
+                && !options.is_a::<JsNull>()
+                && !options.is_a::<JsFunction>() =>
+        {
+            let options = options
+                .downcast_or_throw::<JsObject, FunctionContext>(&mut ctx)
+                .chain_neon(&mut ctx, "Unable to downcast keystore options")?;
+            parse_kdf_options(&mut ctx, options)?
+        }
+        _ => KdfOptions::default(),
+    };
+
+    let cancellation = match callback {
+        Some(_) => CancellationToken::register(),
+        None => CancellationToken::default(),
+    };
+    let task = EncryptKeystoreTask {
+        private_key,
+        password,
+        kdf_options,
+        cancellation: cancellation.clone(),
+    };
+    match callback {
+        Some(callback) => {
+            task.schedule(callback);
+            Ok(cancellation.to_js(&mut ctx)?.upcast())
+        }
+        None => {
+            let result = task.perform();
+            keystore_to_js(&mut ctx, result)
+        }
+    }
+}
+
+/// decrypt private key from JSON keystore
+/// @arguments
+/// - keystore: keystore JSON string
+/// - password: password to decrypt with
+/// - callback: (optional) node-style callback, the key derivation runs on
+///     background thread and the private key is passed to callback when
+///     provided
+/// @return private_key, or the task handle { id, cancel() } with callback
+pub fn decrypt(mut ctx: FunctionContext) -> JsResult<JsValue> {
+    let keystore = ctx.argument::<JsString>(0)?.value();
+    let password = SecUtf8::from(ctx.argument::<JsString>(1)?.value());
+    let callback = callback_argument(&mut ctx)?;
+
+    let keystore = serde_json::from_str::<Keystore>(&keystore).chain_neon_with_code(
+        &mut ctx,
+        ErrorCode::DeserializationError,
+        "Unable to deserialize keystore",
+    )?;
+
+    let cancellation = match callback {
+        Some(_) => CancellationToken::register(),
+        None => CancellationToken::default(),
+    };
+    let task = DecryptKeystoreTask {
+        keystore,
+        password,
+        cancellation: cancellation.clone(),
+    };
+    match callback {
+        Some(callback) => {
+            task.schedule(callback);
+            Ok(cancellation.to_js(&mut ctx)?.upcast())
+        }
+        None => {
+            let result = task.perform();
+            private_key_to_js(&mut ctx, result)
+        }
+    }
+}
+
+struct EncryptKeystoreTask {
+    private_key: PrivateKey,
+    password: SecUtf8,
+    kdf_options: KdfOptions,
+    cancellation: CancellationToken,
+}
+
+impl Task for EncryptKeystoreTask {
+    type Output = String;
+    type Error = NativeError;
+    type JsEvent = JsValue;
+
+    fn perform(&self) -> std::result::Result<String, NativeError> {
+        let keystore = Keystore::encrypt_with(&self.private_key, &self.password, self.kdf_options)
+            .map_err(|err| NativeError::from_client_error("Unable to encrypt keystore", &err))?;
+
+        serde_json::to_string(&keystore).map_err(|err| {
+            NativeError::new(
+                ErrorCode::SerializationError,
+                format!("Unable to serialize keystore: {}", err),
+            )
+        })
+    }
+
+    fn complete(
+        self,
+        mut ctx: TaskContext,
+        result: std::result::Result<String, NativeError>,
+    ) -> JsResult<JsValue> {
+        let result = self.cancellation.complete(result);
+        keystore_to_js(&mut ctx, result)
+    }
+}
+
+struct DecryptKeystoreTask {
+    keystore: Keystore,
+    password: SecUtf8,
+    cancellation: CancellationToken,
+}
+
+impl Task for DecryptKeystoreTask {
+    type Output = PrivateKey;
+    type Error = NativeError;
+    type JsEvent = JsValue;
+
+    fn perform(&self) -> std::result::Result<PrivateKey, NativeError> {
+        self.keystore
+            .decrypt(&self.password)
+            .map_err(|err| NativeError::from_client_error("Unable to decrypt keystore", &err))
+    }
+
+    fn complete(
+        self,
+        mut ctx: TaskContext,
+        result: std::result::Result<PrivateKey, NativeError>,
+    ) -> JsResult<JsValue> {
+        let result = self.cancellation.complete(result);
+        private_key_to_js(&mut ctx, result)
+    }
+}
+
+fn keystore_to_js<'a, C: Context<'a>>(
+    ctx: &mut C,
+    result: std::result::Result<String, NativeError>,
+) -> JsResult<'a, JsValue> {
+    match result {
+        Ok(keystore) => Ok(ctx.string(keystore).upcast()),
+        Err(err) => err.throw(ctx),
+    }
+}
+
+fn private_key_to_js<'a, C: Context<'a>>(
+    ctx: &mut C,
+    result: std::result::Result<PrivateKey, NativeError>,
+) -> JsResult<'a, JsValue> {
+    let private_key = match result {
+        Ok(private_key) => private_key,
+        Err(err) => return err.throw(ctx),
+    };
+
+    let value = Zeroizing::new(private_key.serialize());
+    Ok(bytes_to_buffer(ctx, &value)?.upcast())
+}
+
+fn parse_kdf_options(
+    ctx: &mut FunctionContext,
+    options: Handle<JsObject>,
+) -> NeonResult<KdfOptions> {
+    let kdf = if does_js_object_has_prop(ctx, options, "kdf")? {
+        options
+            .get(ctx, "kdf")?
+            .downcast_or_throw::<JsString, FunctionContext>(ctx)
+            .chain_neon(ctx, "Unable to downcast kdf")?
+            .value()
+    } else {
+        KDF_ARGON2ID.to_owned()
+    };
+    let u32_option = |ctx: &mut FunctionContext, key: &str, default: u32| {
+        optional_integer_option(ctx, options, key).map(|value| {
+            value
+                .map(|value| cmp::min(value, u64::from(u32::max_value())) as u32)
+                .unwrap_or(default)
+        })
+    };
+
+    let kdf_options = match kdf.as_str() {
+        KDF_ARGON2ID => KdfOptions::Argon2id {
+            memory_cost: u32_option(ctx, "memoryCost", DEFAULT_MEMORY_COST)?,
+            time_cost: u32_option(ctx, "timeCost", DEFAULT_TIME_COST)?,
+            parallelism: u32_option(ctx, "parallelism", DEFAULT_PARALLELISM)?,
+        },
+        KDF_SCRYPT => KdfOptions::Scrypt {
+            n: optional_integer_option(ctx, options, "n")?.unwrap_or(DEFAULT_SCRYPT_N),
+            r: u32_option(ctx, "r", DEFAULT_SCRYPT_R)?,
+            p: u32_option(ctx, "p", DEFAULT_SCRYPT_P)?,
+        },
+        _ => {
+            return throw_coded_error(
+                ctx,
+                ErrorCode::InvalidArgument,
+                format!(
+                    "Unsupported keystore kdf {}, expected argon2id or scrypt",
+                    kdf
+                ),
+            )
+        }
+    };
+    kdf_options
+        .validate()
+        .chain_neon(ctx, "Invalid keystore KDF options")?;
+
+    Ok(kdf_options)
+}
+
+pub fn register_keystore_module(ctx: &mut ModuleContext) -> NeonResult<()> {
+    let js_object = JsObject::new(ctx);
+
+    let encrypt_fn = JsFunction::new(ctx, encrypt)?;
+    js_object.set(ctx, "encrypt", encrypt_fn)?;
+
+    let decrypt_fn = JsFunction::new(ctx, decrypt)?;
+    js_object.set(ctx, "decrypt", decrypt_fn)?;
+
+    ctx.export_value("keystore", js_object)
+}
@@ -1,36 +1,104 @@
 use neon::register_module;
 
 mod address;
+mod block;
+mod cancellation;
+mod client_config;
+mod codec;
+mod coin;
 mod common;
+mod consensus_key;
 mod council_node_transaction;
 mod error;
+mod fee;
 mod function_types;
+mod hash;
 mod hd_wallet;
 mod key_pair;
+mod keystore;
+mod logger;
+mod metrics;
+#[cfg(feature = "mock-tx-query")]
+mod mock_tx_query;
 mod multi_sig;
+mod network;
+mod offline;
+mod proof;
+mod proxy;
+mod rng;
+mod rpc;
 mod signer;
+mod staking;
 mod staking_transaction;
+mod storage;
+mod strict_decode;
+mod sync;
+mod transaction;
 mod transfer_transaction;
 mod tx_aux;
+mod tx_query;
+mod wallet;
 
 use address::register_address_module;
+use block::register_block_module;
+use codec::register_codec_module;
+use coin::register_coin_module;
+use consensus_key::register_consensus_key_module;
 use council_node_transaction::register_council_node_transaction_module;
+use fee::register_fee_module;
+use hash::register_hash_module;
 use hd_wallet::register_hd_wallet_module;
 use key_pair::register_key_pair_module;
+use keystore::register_keystore_module;
+use logger::register_logger_module;
+use metrics::register_metrics_module;
+#[cfg(feature = "mock-tx-query")]
+use mock_tx_query::register_mock_tx_query_module;
 use multi_sig::register_multi_sig_module;
+use network::register_network_module;
+use proof::register_proof_module;
+use rng::register_rng_module;
+use rpc::register_rpc_module;
 use signer::register_signer_module;
+use staking::register_staking_module;
 use staking_transaction::register_staking_transaction_module;
+use storage::register_storage_module;
+use sync::register_sync_module;
+use transaction::register_transaction_module;
 use transfer_transaction::register_transfer_transaction_module;
+use tx_query::register_tx_query_module;
+use wallet::register_wallet_module;
 
 register_module!(mut ctx, {
     register_address_module(&mut ctx)?;
+    register_block_module(&mut ctx)?;
+    register_codec_module(&mut ctx)?;
+    register_coin_module(&mut ctx)?;
+    register_consensus_key_module(&mut ctx)?;
     register_council_node_transaction_module(&mut ctx)?;
+    register_fee_module(&mut ctx)?;
+    register_hash_module(&mut ctx)?;
     register_hd_wallet_module(&mut ctx)?;
     register_key_pair_module(&mut ctx)?;
+    register_keystore_module(&mut ctx)?;
+    register_logger_module(&mut ctx)?;
+    register_metrics_module(&mut ctx)?;
+    #[cfg(feature = "mock-tx-query")]
+    register_mock_tx_query_module(&mut ctx)?;
     register_signer_module(&mut ctx)?;
+    register_staking_module(&mut ctx)?;
     register_staking_transaction_module(&mut ctx)?;
     register_multi_sig_module(&mut ctx)?;
+    register_network_module(&mut ctx)?;
+    register_proof_module(&mut ctx)?;
+    register_rng_module(&mut ctx)?;
+    register_rpc_module(&mut ctx)?;
+    register_storage_module(&mut ctx)?;
+    register_sync_module(&mut ctx)?;
+    register_transaction_module(&mut ctx)?;
     register_transfer_transaction_module(&mut ctx)?;
+    register_tx_query_module(&mut ctx)?;
+    register_wallet_module(&mut ctx)?;
 
     Ok(())
 });
@@ -0,0 +1,148 @@
+//! Bridge of the log records of nodelib and its dependencies to the logger
+//! of the host application. Records are delivered to the JS callback through
+//! an event handler, nothing is logged until `setLogger` is called
+use std::str::FromStr;
+use std::sync::{Once, RwLock};
+
+use chrono::Utc;
+use lazy_static::lazy_static;
+use log::{LevelFilter, Log, Metadata, Record};
+use neon::event::EventHandler;
+use neon::prelude::*;
+use serde::Serialize;
+
+use crate::common::serialize_to_js;
+use crate::error::ClientErrorNeonExt;
+
+const DEFAULT_LEVEL: LevelFilter = LevelFilter::Info;
+
+lazy_static! {
+    static ref LOG_HANDLER: RwLock<Option<EventHandler>> = RwLock::new(None);
+}
+static INSTALL_LOGGER: Once = Once::new();
+static LOGGER: JsLogger = JsLogger;
+
+/// Logger forwarding the records to the handler set by `setLogger`
+struct JsLogger;
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct LogRecord {
+    level: String,
+    target: String,
+    message: String,
+    timestamp: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    module_path: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    file: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    line: Option<u32>,
+}
+
+impl Log for JsLogger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= log::max_level()
+    }
+
+    fn log(&self, record: &Record) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+        let handler = match LOG_HANDLER.read() {
+            Ok(handler) => handler,
+            Err(_) => return,
+        };
+        let handler = match handler.as_ref() {
+            Some(handler) => handler,
+            None => return,
+        };
+
+        let record = LogRecord {
+            level: record.level().to_string().to_lowercase(),
+            target: record.target().to_owned(),
+            message: record.args().to_string(),
+            timestamp: Utc::now().to_rfc3339(),
+            module_path: record.module_path().map(ToOwned::to_owned),
+            file: record.file().map(ToOwned::to_owned),
+            line: record.line(),
+        };
+        handler.schedule_with(move |ctx, this, callback| {
+            if let Ok(record) = serialize_to_js(ctx, &record) {
+                let _ = callback.call(ctx, this, vec![record]);
+            }
+        });
+    }
+
+    fn flush(&self) {}
+}
+
+/// set the callback receiving the native log records, e.g. to forward them
+/// to the logger of the application. The callback is called on the JS thread
+/// some time after the record is emitted. The callback keeps the process
+/// alive, so call setLogger(null) before exiting. The previous callback is
+/// released when the logger is replaced
+/// @arguments
+/// - callback: function called with each record { level, target, message,
+///     timestamp, modulePath?, file?, line? }, stops logging when null
+/// - level: (optional) most verbose level to deliver, one of "error", "warn",
+///     "info", "debug" or "trace", default "info"
+pub fn set_logger(mut ctx: FunctionContext) -> JsResult<JsUndefined> {
+    let callback = match ctx.argument_opt(0) {
+        Some(callback) if !callback.is_a::<JsUndefined>() && !callback.is_a::<JsNull>() => Some(
+            callback
+                .downcast_or_throw::<JsFunction, FunctionContext>(&mut ctx)
+                .chain_neon(&mut ctx, "Unable to downcast logger callback")?,
+        ),
+        _ => None,
+    };
+    let level = match ctx.argument_opt(1) {
+        Some(level) if level.is_a::<JsString>() => {
+            let level = ctx.argument::<JsString>(1)?.value();
+            match LevelFilter::from_str(&level) {
+                Ok(level) if level != LevelFilter::Off => level,
+                _ => {
+                    return ctx.throw_error(format!(
+                        "Unsupported log level {}, expected error, warn, info, debug or trace",
+                        level
+                    ))
+                }
+            }
+        }
+        _ => DEFAULT_LEVEL,
+    };
+
+    let handler = callback.map(|callback| {
+        let this = ctx.undefined();
+        EventHandler::new(&ctx, this, callback)
+    });
+    // Nothing is formatted for the records of the disabled levels
+    let level = if handler.is_some() {
+        level
+    } else {
+        LevelFilter::Off
+    };
+    let replaced_handler = std::mem::replace(
+        &mut *LOG_HANDLER
+            .write()
+            .chain_neon(&mut ctx, "Unable to acquire logger lock")?,
+        handler,
+    );
+    // Release the previous callback so that it no longer keeps the process
+    // alive
+    drop(replaced_handler);
+
+    INSTALL_LOGGER.call_once(|| {
+        // Another logger of the process, if any, keeps receiving the records
+        let _ = log::set_logger(&LOGGER);
+    });
+    log::set_max_level(level);
+
+    Ok(ctx.undefined())
+}
+
+pub fn register_logger_module(ctx: &mut ModuleContext) -> NeonResult<()> {
+    ctx.export_function("setLogger", set_logger)?;
+
+    Ok(())
+}
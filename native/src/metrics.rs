@@ -0,0 +1,217 @@
+//! Call counts and durations of the native operations, e.g. key generation,
+//! signing, encoding and RPC calls. Nothing is recorded until
+//! `metrics.enable()` is called so the instrumentation costs one atomic load
+//! otherwise
+use std::collections::{BTreeMap, HashMap};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use client_common::Result;
+use lazy_static::lazy_static;
+use neon::prelude::*;
+use serde::Serialize;
+
+use crate::common::serialize_to_js;
+use crate::error::ClientErrorNeonExt;
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+lazy_static! {
+    static ref METRICS: Mutex<HashMap<String, OperationMetrics>> = Mutex::new(HashMap::new());
+}
+
+#[derive(Debug, Clone, Copy)]
+struct OperationMetrics {
+    count: u64,
+    error_count: u64,
+    total: Duration,
+    min: Duration,
+    max: Duration,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct OperationSnapshot {
+    count: u64,
+    error_count: u64,
+    total_ms: f64,
+    mean_ms: f64,
+    min_ms: f64,
+    max_ms: f64,
+}
+
+impl From<&OperationMetrics> for OperationSnapshot {
+    fn from(metrics: &OperationMetrics) -> Self {
+        OperationSnapshot {
+            count: metrics.count,
+            error_count: metrics.error_count,
+            total_ms: duration_ms(metrics.total),
+            mean_ms: duration_ms(metrics.total) / metrics.count as f64,
+            min_ms: duration_ms(metrics.min),
+            max_ms: duration_ms(metrics.max),
+        }
+    }
+}
+
+/// Timer of an operation, records the elapsed time under the name when
+/// dropped
+pub struct Timer {
+    started: Option<(String, Instant)>,
+    failed: bool,
+}
+
+impl Timer {
+    /// Count the operation as failed
+    pub fn fail(&mut self) {
+        self.failed = true;
+    }
+}
+
+impl Drop for Timer {
+    fn drop(&mut self) {
+        if let Some((name, started)) = self.started.take() {
+            record(name, started.elapsed(), self.failed);
+        }
+    }
+}
+
+/// Start timing the operation, a no-op when metrics are disabled
+pub fn timer(name: &str) -> Timer {
+    let started = if ENABLED.load(Ordering::Relaxed) {
+        Some((name.to_owned(), Instant::now()))
+    } else {
+        None
+    };
+
+    Timer {
+        started,
+        failed: false,
+    }
+}
+
+/// Time the operation, failed results are counted as errors
+pub fn measure<T, F>(name: &str, operation: F) -> Result<T>
+where
+    F: FnOnce() -> Result<T>,
+{
+    let mut timer = timer(name);
+    let result = operation();
+    if result.is_err() {
+        timer.fail();
+    }
+
+    result
+}
+
+/// Time the JS function, thrown errors are counted as errors
+pub fn measure_js<'a, T, F>(name: &str, operation: F) -> JsResult<'a, T>
+where
+    T: Value,
+    F: FnOnce() -> JsResult<'a, T>,
+{
+    let mut timer = timer(name);
+    let result = operation();
+    if result.is_err() {
+        timer.fail();
+    }
+
+    result
+}
+
+fn record(name: String, elapsed: Duration, failed: bool) {
+    let mut metrics = match METRICS.lock() {
+        Ok(metrics) => metrics,
+        Err(_) => return,
+    };
+    let metrics = metrics.entry(name).or_insert(OperationMetrics {
+        count: 0,
+        error_count: 0,
+        total: Duration::from_secs(0),
+        min: elapsed,
+        max: elapsed,
+    });
+    metrics.count += 1;
+    if failed {
+        metrics.error_count += 1;
+    }
+    metrics.total += elapsed;
+    metrics.min = metrics.min.min(elapsed);
+    metrics.max = metrics.max.max(elapsed);
+}
+
+#[inline]
+fn duration_ms(duration: Duration) -> f64 {
+    duration.as_secs_f64() * 1000.0
+}
+
+/// start recording the call counts and durations
+pub fn enable(mut ctx: FunctionContext) -> JsResult<JsUndefined> {
+    ENABLED.store(true, Ordering::SeqCst);
+
+    Ok(ctx.undefined())
+}
+
+/// stop recording, the recorded metrics are kept until reset
+pub fn disable(mut ctx: FunctionContext) -> JsResult<JsUndefined> {
+    ENABLED.store(false, Ordering::SeqCst);
+
+    Ok(ctx.undefined())
+}
+
+/// returns the metrics recorded since enabled or last reset
+/// @arguments
+/// - reset: (optional) boolean, clear the metrics once taken, default false
+/// @return { [operation]: { count, errorCount, totalMs, meanMs, minMs, maxMs } },
+///     operations are named after the JS functions, e.g.
+///     keyPair.newPrivateKey, and rpc.<method> for Tendermint RPC calls
+pub fn snapshot(mut ctx: FunctionContext) -> JsResult<JsValue> {
+    let reset = match ctx.argument_opt(0) {
+        Some(reset) if reset.is_a::<JsBoolean>() => ctx.argument::<JsBoolean>(0)?.value(),
+        _ => false,
+    };
+
+    let snapshot = {
+        let mut metrics = METRICS
+            .lock()
+            .chain_neon(&mut ctx, "Unable to acquire metrics lock")?;
+        let snapshot = metrics
+            .iter()
+            .map(|(name, metrics)| (name.clone(), OperationSnapshot::from(metrics)))
+            .collect::<BTreeMap<String, OperationSnapshot>>();
+        if reset {
+            metrics.clear();
+        }
+        snapshot
+    };
+
+    serialize_to_js(&mut ctx, &snapshot)
+}
+
+/// clear the recorded metrics
+pub fn reset(mut ctx: FunctionContext) -> JsResult<JsUndefined> {
+    METRICS
+        .lock()
+        .chain_neon(&mut ctx, "Unable to acquire metrics lock")?
+        .clear();
+
+    Ok(ctx.undefined())
+}
+
+pub fn register_metrics_module(ctx: &mut ModuleContext) -> NeonResult<()> {
+    let js_object = JsObject::new(ctx);
+
+    let enable_fn = JsFunction::new(ctx, enable)?;
+    js_object.set(ctx, "enable", enable_fn)?;
+
+    let disable_fn = JsFunction::new(ctx, disable)?;
+    js_object.set(ctx, "disable", disable_fn)?;
+
+    let snapshot_fn = JsFunction::new(ctx, snapshot)?;
+    js_object.set(ctx, "snapshot", snapshot_fn)?;
+
+    let reset_fn = JsFunction::new(ctx, reset)?;
+    js_object.set(ctx, "reset", reset_fn)?;
+
+    ctx.export_value("metrics", js_object)
+}
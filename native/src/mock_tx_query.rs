@@ -0,0 +1,136 @@
+//! Mock tx-query backend of the MockTxQuery features, only built with the
+//! `mock-tx-query` feature for offline and deterministic tests without an
+//! enclave. The obfuscated payload is the signed transaction XORed with a
+//! BLAKE3 keystream of the transaction id, so it is reversible by anyone and
+//! must never be used with real funds. Obfuscated payloads are kept in memory
+//! so that they can be fetched by id as from tx-query
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use chain_core::tx::data::TxId;
+use chain_core::tx::{TxAux, TxEnclaveAux, TxObfuscated};
+use client_common::{
+    Error, ErrorKind, PrivateKey, PublicKey, Result, SignedTransaction, Transaction,
+};
+use client_core::cipher::TransactionObfuscation;
+use lazy_static::lazy_static;
+use neon::prelude::*;
+
+use crate::error::ClientErrorNeonExt;
+use crate::tx_aux::{
+    enclave_tx_payload, ensure_plain_transactions_allowed, plain_payload_transaction,
+    MockTransactionCipher,
+};
+
+/// BLAKE3 key derivation context of the payload keystream
+const MOCK_TX_QUERY_CONTEXT: &str = "crypto-com/chain-nodelib 2020 mock tx-query";
+
+lazy_static! {
+    /// Obfuscated payloads by transaction id, as the blocks would have them
+    static ref MOCK_PAYLOADS: Mutex<HashMap<TxId, Vec<u8>>> = Mutex::new(HashMap::new());
+}
+
+/// Obfuscate the signed transaction and keep its payload for `decrypt`. The
+/// payload is as readable as a plain transaction, so only the networks which
+/// accept plain transactions are obfuscated
+pub fn encrypt(signed_transaction: SignedTransaction) -> Result<TxAux> {
+    ensure_plain_transactions_allowed(&signed_transaction)?;
+
+    let mut tx_aux = MockTransactionCipher.encrypt(signed_transaction)?;
+    if let TxAux::EnclaveTx(enclave_tx) = &mut tx_aux {
+        let payload = enclave_tx_payload_mut(enclave_tx);
+        apply_keystream(&payload.txid, &mut payload.txpayload);
+
+        mock_payloads()?.insert(payload.txid, payload.txpayload.clone());
+    }
+
+    Ok(tx_aux)
+}
+
+/// Decrypt the transactions of the ids obfuscated by `encrypt`. Transactions
+/// which are unknown or which the view key has no access to are left out of
+/// the result
+pub fn decrypt(tx_ids: &[TxId], view_private_key: &PrivateKey) -> Result<Vec<Transaction>> {
+    let view_key = PublicKey::from(view_private_key);
+    let payloads = mock_payloads()?;
+
+    tx_ids
+        .iter()
+        .filter_map(|tx_id| {
+            let mut txpayload = payloads.get(tx_id)?.clone();
+            apply_keystream(tx_id, &mut txpayload);
+            plain_payload_transaction(&txpayload, &view_key).transpose()
+        })
+        .collect()
+}
+
+/// Decrypt the obfuscated payloads of the enclave transactions of a block.
+/// Transactions which the view key has no access to are left out of the
+/// result
+pub fn decrypt_enclave_txs(
+    enclave_txs: &[TxEnclaveAux],
+    view_private_key: &PrivateKey,
+) -> Result<Vec<Transaction>> {
+    let view_key = PublicKey::from(view_private_key);
+
+    enclave_txs
+        .iter()
+        .filter_map(|enclave_tx| {
+            let payload = enclave_tx_payload(enclave_tx);
+            let mut txpayload = payload.txpayload.clone();
+            apply_keystream(&payload.txid, &mut txpayload);
+            plain_payload_transaction(&txpayload, &view_key).transpose()
+        })
+        .collect()
+}
+
+/// XOR the payload with the keystream of the transaction id, which encodes
+/// and decodes alike
+fn apply_keystream(tx_id: &TxId, txpayload: &mut [u8]) {
+    let mut keystream = vec![0u8; txpayload.len()];
+    let mut hasher = blake3::Hasher::new_derive_key(MOCK_TX_QUERY_CONTEXT);
+    hasher.update(tx_id);
+    hasher.finalize_xof().fill(&mut keystream);
+
+    for (byte, key) in txpayload.iter_mut().zip(keystream) {
+        *byte ^= key;
+    }
+}
+
+fn enclave_tx_payload_mut(enclave_tx: &mut TxEnclaveAux) -> &mut TxObfuscated {
+    match enclave_tx {
+        TxEnclaveAux::TransferTx { payload, .. } => payload,
+        TxEnclaveAux::DepositStakeTx { payload, .. } => payload,
+        TxEnclaveAux::WithdrawUnbondedStakeTx { payload, .. } => payload,
+    }
+}
+
+fn mock_payloads() -> Result<std::sync::MutexGuard<'static, HashMap<TxId, Vec<u8>>>> {
+    MOCK_PAYLOADS.lock().map_err(|_| {
+        Error::new(
+            ErrorKind::InternalError,
+            "Unable to acquire mock tx-query lock",
+        )
+    })
+}
+
+/// UNSAFE, for tests only. Forget the payloads obfuscated by the mock
+/// tx-query backend, so that tests do not see each other's transactions
+/// @return number of forgotten payloads
+pub fn unsafe_reset_mock_tx_query(mut ctx: FunctionContext) -> JsResult<JsNumber> {
+    let mut payloads = MOCK_PAYLOADS
+        .lock()
+        .chain_neon(&mut ctx, "Unable to acquire mock tx-query lock")?;
+    let forgotten = payloads.len();
+    payloads.clear();
+
+    Ok(ctx.number(forgotten as f64))
+}
+
+/// Register the mock tx-query functions, which only exist when built with the
+/// `mock-tx-query` feature
+pub fn register_mock_tx_query_module(ctx: &mut ModuleContext) -> NeonResult<()> {
+    ctx.export_function("unsafeResetMockTxQuery", unsafe_reset_mock_tx_query)?;
+
+    Ok(())
+}
@@ -1,6 +1,10 @@
+mod psct;
+mod session;
+mod witness;
+
 use chain_core::init::address::CroAddress;
 use chain_core::tx::data::address::ExtendedAddr;
-use client_common::{MultiSigAddress, PublicKey, SECP};
+use client_common::{MultiSigAddress, PublicKey};
 use neon::prelude::*;
 
 use chain_core::common::H256;
@@ -8,15 +12,27 @@ use client_core::multi_sig::MultiSigBuilder;
 use secp256k1::Message;
 use secp256k1::{schnorrsig::schnorr_verify, schnorrsig::SchnorrSignature};
 
-use crate::error::ClientErrorNeonExt;
+use crate::common::with_secp;
+use crate::error::{ClientErrorNeonExt, ErrorCode};
 use crate::function_types::*;
 
+use psct::{
+    psct_add_nonce, psct_add_nonce_commitment, psct_add_partial_signature, psct_create,
+    psct_finalize, psct_merge, psct_new_session, psct_status,
+};
+use session::{
+    create_session, destroy_session, session_add_nonce, session_add_nonce_commitment,
+    session_add_partial_signature, session_nonce, session_nonce_commitment, session_partial_sign,
+    session_signature,
+};
+use witness::{tree_sig_proof, tree_sig_witness};
+
 /// create new multisig address
 /// @arguments
 /// - public_keys: vector of public key, consist of all signers
 /// - self_public_key: public key of use who init the multisig address
 /// - required_signers: min number of signers to activate the tx
-/// - network: netword argument to identify network env (testnet, devnet, etc)
+/// - network: network handle or name to identify network env (testnet, devnet, etc)
 /// @return cro_address
 pub fn create_address(mut ctx: FunctionContext) -> JsResult<JsString> {
     let public_keys = public_key_vector_argument(&mut ctx, 0)?;
@@ -29,17 +45,26 @@ pub fn create_address(mut ctx: FunctionContext) -> JsResult<JsString> {
     let network = network_argument(&mut ctx, 3)?;
 
     let multi_sig_address = MultiSigAddress::new(public_keys, self_public_key, required_signers)
-        .chain_neon(&mut ctx, "Unable to create MultiSig address")?;
+        .chain_neon_with_code(
+            &mut ctx,
+            ErrorCode::InvalidAddress,
+            "Unable to create MultiSig address",
+        )?;
 
     let extended_address = ExtendedAddr::from(multi_sig_address);
-    let cro_address = extended_address
-        .to_cro(network)
-        .chain_neon(&mut ctx, "Unable to convert to CRO address")?;
+    let cro_address = extended_address.to_cro(network).chain_neon_with_code(
+        &mut ctx,
+        ErrorCode::InvalidAddress,
+        "Unable to convert to CRO address",
+    )?;
 
     Ok(ctx.string(cro_address))
 }
 
-/// create new multisig session
+/// create new multisig session. The incomplete session Buffer carries the
+///     private key and nonce of current signer in V8-managed memory, kept for
+///     the callers which persist the session between the rounds. Prefer the
+///     `createSession` session handle which keeps them in native memory
 /// @arguments
 /// - message:  H256 format of msg to be signed
 /// - signer_public_keys: public keys of all the signers (including current signer)
@@ -61,13 +86,7 @@ pub fn new_session(mut ctx: FunctionContext) -> JsResult<JsBuffer> {
     .chain_neon(&mut ctx, "Unable to create new MultiSigBuilder")?;
 
     let incomplete_session = session.to_incomplete();
-    let mut incomplete_session_buffer = ctx.buffer(incomplete_session.len() as u32)?;
-    ctx.borrow_mut(&mut incomplete_session_buffer, |data| {
-        let slice = data.as_mut_slice();
-        slice.copy_from_slice(&incomplete_session);
-    });
-
-    Ok(incomplete_session_buffer)
+    bytes_to_buffer(&mut ctx, &incomplete_session)
 }
 
 /// generate nonce commitment
@@ -90,17 +109,9 @@ pub fn generate_nonce_commitment(mut ctx: FunctionContext) -> JsResult<JsObject>
     let object = JsObject::new(&mut ctx);
 
     let incomplete_session = session.to_incomplete();
-    let mut incomplete_session_buffer = ctx.buffer(incomplete_session.len() as u32)?;
-    ctx.borrow_mut(&mut incomplete_session_buffer, |data| {
-        let slice = data.as_mut_slice();
-        slice.copy_from_slice(&incomplete_session);
-    });
+    let incomplete_session_buffer = bytes_to_buffer(&mut ctx, &incomplete_session)?;
 
-    let mut nonce_commitment_buffer = ctx.buffer(nonce_commitment.len() as u32)?;
-    ctx.borrow_mut(&mut nonce_commitment_buffer, |data| {
-        let slice = data.as_mut_slice();
-        slice.copy_from_slice(&nonce_commitment);
-    });
+    let nonce_commitment_buffer = bytes_to_buffer(&mut ctx, &nonce_commitment)?;
 
     object
         .set(&mut ctx, "incompleteSession", incomplete_session_buffer)
@@ -134,13 +145,7 @@ pub fn add_nonce_commitment(mut ctx: FunctionContext) -> JsResult<JsBuffer> {
         .chain_neon(&mut ctx, "Unable to add nonce commitment")?;
 
     let incomplete_session = session.to_incomplete();
-    let mut incomplete_session_buffer = ctx.buffer(incomplete_session.len() as u32)?;
-    ctx.borrow_mut(&mut incomplete_session_buffer, |data| {
-        let slice = data.as_mut_slice();
-        slice.copy_from_slice(&incomplete_session);
-    });
-
-    Ok(incomplete_session_buffer)
+    bytes_to_buffer(&mut ctx, &incomplete_session)
 }
 
 /// generate nonce: notice that it will throw error if missing any required
@@ -164,17 +169,9 @@ pub fn generate_nonce(mut ctx: FunctionContext) -> JsResult<JsObject> {
     // prepare return object
     let object = JsObject::new(&mut ctx);
     let incomplete_session = session.to_incomplete();
-    let mut incomplete_session_buffer = ctx.buffer(incomplete_session.len() as u32)?;
-    ctx.borrow_mut(&mut incomplete_session_buffer, |data| {
-        let slice = data.as_mut_slice();
-        slice.copy_from_slice(&incomplete_session);
-    });
+    let incomplete_session_buffer = bytes_to_buffer(&mut ctx, &incomplete_session)?;
 
-    let mut nonce_buffer = ctx.buffer(nonce.len() as u32)?;
-    ctx.borrow_mut(&mut nonce_buffer, |data| {
-        let slice = data.as_mut_slice();
-        slice.copy_from_slice(&nonce);
-    });
+    let nonce_buffer = bytes_to_buffer(&mut ctx, &nonce)?;
 
     object
         .set(&mut ctx, "incompleteSession", incomplete_session_buffer)
@@ -208,13 +205,7 @@ pub fn add_nonce(mut ctx: FunctionContext) -> JsResult<JsBuffer> {
         .chain_neon(&mut ctx, "Unable to add nonce")?;
 
     let incomplete_session = session.to_incomplete();
-    let mut incomplete_session_buffer = ctx.buffer(incomplete_session.len() as u32)?;
-    ctx.borrow_mut(&mut incomplete_session_buffer, |data| {
-        let slice = data.as_mut_slice();
-        slice.copy_from_slice(&incomplete_session);
-    });
-
-    Ok(incomplete_session_buffer)
+    bytes_to_buffer(&mut ctx, &incomplete_session)
 }
 
 /// generate partial signature
@@ -236,17 +227,9 @@ pub fn partial_sign(mut ctx: FunctionContext) -> JsResult<JsObject> {
     // prepare return object
     let object = JsObject::new(&mut ctx);
     let incomplete_session = session.to_incomplete();
-    let mut incomplete_session_buffer = ctx.buffer(incomplete_session.len() as u32)?;
-    ctx.borrow_mut(&mut incomplete_session_buffer, |data| {
-        let slice = data.as_mut_slice();
-        slice.copy_from_slice(&incomplete_session);
-    });
+    let incomplete_session_buffer = bytes_to_buffer(&mut ctx, &incomplete_session)?;
 
-    let mut partial_signature_buffer = ctx.buffer(partial_signature.len() as u32)?;
-    ctx.borrow_mut(&mut partial_signature_buffer, |data| {
-        let slice = data.as_mut_slice();
-        slice.copy_from_slice(&partial_signature);
-    });
+    let partial_signature_buffer = bytes_to_buffer(&mut ctx, &partial_signature)?;
 
     object
         .set(&mut ctx, "incompleteSession", incomplete_session_buffer)
@@ -256,8 +239,9 @@ pub fn partial_sign(mut ctx: FunctionContext) -> JsResult<JsObject> {
         )?;
     object
         .set(&mut ctx, "partialSignature", partial_signature_buffer)
-        .chain_neon(
+        .chain_neon_with_code(
             &mut ctx,
+            ErrorCode::InvalidSignature,
             "Unable to create object with key partialSignature",
         )?;
 
@@ -280,16 +264,14 @@ pub fn add_partial_signature(mut ctx: FunctionContext) -> JsResult<JsBuffer> {
 
     session
         .add_partial_signature(&public_key, partial_signature)
-        .chain_neon(&mut ctx, "Unable to add partial signature")?;
+        .chain_neon_with_code(
+            &mut ctx,
+            ErrorCode::InvalidSignature,
+            "Unable to add partial signature",
+        )?;
 
     let incomplete_session = session.to_incomplete();
-    let mut incomplete_session_buffer = ctx.buffer(incomplete_session.len() as u32)?;
-    ctx.borrow_mut(&mut incomplete_session_buffer, |data| {
-        let slice = data.as_mut_slice();
-        slice.copy_from_slice(&incomplete_session);
-    });
-
-    Ok(incomplete_session_buffer)
+    bytes_to_buffer(&mut ctx, &incomplete_session)
 }
 
 /// sign the ready session that gathered all co-signers' signatures
@@ -303,17 +285,15 @@ pub fn sign(mut ctx: FunctionContext) -> JsResult<JsBuffer> {
 
     let signature = session
         .signature()
-        .chain_neon(&mut ctx, "Unable to create signature")?
+        .chain_neon_with_code(
+            &mut ctx,
+            ErrorCode::InvalidSignature,
+            "Unable to create signature",
+        )?
         .serialize_default()
         .to_vec();
 
-    let mut signature_buffer = ctx.buffer(signature.len() as u32)?;
-    ctx.borrow_mut(&mut signature_buffer, |data| {
-        let slice = data.as_mut_slice();
-        slice.copy_from_slice(&signature);
-    });
-
-    Ok(signature_buffer)
+    bytes_to_buffer(&mut ctx, &signature)
 }
 
 /// verify the final signature valid or not
@@ -323,9 +303,12 @@ pub fn sign(mut ctx: FunctionContext) -> JsResult<JsBuffer> {
 /// - public_keys: all co-signers' public keys
 /// @return boolean
 pub fn verify(mut ctx: FunctionContext) -> JsResult<JsBoolean> {
-    let raw = u8_buffer_argument(&mut ctx, 0)?;
-    let signature =
-        SchnorrSignature::from_default(&raw).chain_neon(&mut ctx, "Unable to restore signature")?;
+    let raw = hex_or_buffer_argument(&mut ctx, 0, "signature")?;
+    let signature = SchnorrSignature::from_default(&raw).chain_neon_with_code(
+        &mut ctx,
+        ErrorCode::InvalidSignature,
+        "Unable to restore signature",
+    )?;
 
     let message = h256_str_argument(&mut ctx, 1)?;
     let message = Message::from_slice(&message).chain_neon(&mut ctx, "Unable to form message")?;
@@ -334,10 +317,14 @@ pub fn verify(mut ctx: FunctionContext) -> JsResult<JsBoolean> {
     public_keys.sort(); // sort the public keys to keep the order consistency
 
     let combined_public_key = PublicKey::combine(&public_keys)
-        .chain_neon(&mut ctx, "Unable combine public keys")?
+        .chain_neon_with_code(
+            &mut ctx,
+            ErrorCode::InvalidPublicKey,
+            "Unable combine public keys",
+        )?
         .0;
 
-    let verify_passed = SECP.with(|secp| {
+    let verify_passed = with_secp(|secp| {
         schnorr_verify(&secp, &message, &signature, &combined_public_key.into()).is_ok()
     });
 
@@ -369,5 +356,61 @@ pub fn register_multi_sig_module(ctx: &mut ModuleContext) -> NeonResult<()> {
     js_object.set(ctx, "sign", sign_fn)?;
     js_object.set(ctx, "verify", verify_fn)?;
 
+    let create_session_fn = JsFunction::new(ctx, create_session)?;
+    let session_nonce_commitment_fn = JsFunction::new(ctx, session_nonce_commitment)?;
+    let session_add_nonce_commitment_fn = JsFunction::new(ctx, session_add_nonce_commitment)?;
+    let session_nonce_fn = JsFunction::new(ctx, session_nonce)?;
+    let session_add_nonce_fn = JsFunction::new(ctx, session_add_nonce)?;
+    let session_partial_sign_fn = JsFunction::new(ctx, session_partial_sign)?;
+    let session_add_partial_signature_fn = JsFunction::new(ctx, session_add_partial_signature)?;
+    let session_signature_fn = JsFunction::new(ctx, session_signature)?;
+    let destroy_session_fn = JsFunction::new(ctx, destroy_session)?;
+
+    js_object.set(ctx, "createSession", create_session_fn)?;
+    js_object.set(ctx, "sessionNonceCommitment", session_nonce_commitment_fn)?;
+    js_object.set(
+        ctx,
+        "sessionAddNonceCommitment",
+        session_add_nonce_commitment_fn,
+    )?;
+    js_object.set(ctx, "sessionNonce", session_nonce_fn)?;
+    js_object.set(ctx, "sessionAddNonce", session_add_nonce_fn)?;
+    js_object.set(ctx, "sessionPartialSign", session_partial_sign_fn)?;
+    js_object.set(
+        ctx,
+        "sessionAddPartialSignature",
+        session_add_partial_signature_fn,
+    )?;
+    js_object.set(ctx, "sessionSignature", session_signature_fn)?;
+    js_object.set(ctx, "destroySession", destroy_session_fn)?;
+
+    let psct_create_fn = JsFunction::new(ctx, psct_create)?;
+    let psct_merge_fn = JsFunction::new(ctx, psct_merge)?;
+    let psct_status_fn = JsFunction::new(ctx, psct_status)?;
+    let psct_new_session_fn = JsFunction::new(ctx, psct_new_session)?;
+    let psct_add_nonce_commitment_fn = JsFunction::new(ctx, psct_add_nonce_commitment)?;
+    let psct_add_nonce_fn = JsFunction::new(ctx, psct_add_nonce)?;
+    let psct_add_partial_signature_fn = JsFunction::new(ctx, psct_add_partial_signature)?;
+    let psct_finalize_fn = JsFunction::new(ctx, psct_finalize)?;
+
+    js_object.set(ctx, "psctCreate", psct_create_fn)?;
+    js_object.set(ctx, "psctMerge", psct_merge_fn)?;
+    js_object.set(ctx, "psctStatus", psct_status_fn)?;
+    js_object.set(ctx, "psctNewSession", psct_new_session_fn)?;
+    js_object.set(ctx, "psctAddNonceCommitment", psct_add_nonce_commitment_fn)?;
+    js_object.set(ctx, "psctAddNonce", psct_add_nonce_fn)?;
+    js_object.set(
+        ctx,
+        "psctAddPartialSignature",
+        psct_add_partial_signature_fn,
+    )?;
+    js_object.set(ctx, "psctFinalize", psct_finalize_fn)?;
+
+    let tree_sig_proof_fn = JsFunction::new(ctx, tree_sig_proof)?;
+    let tree_sig_witness_fn = JsFunction::new(ctx, tree_sig_witness)?;
+
+    js_object.set(ctx, "treeSigProof", tree_sig_proof_fn)?;
+    js_object.set(ctx, "treeSigWitness", tree_sig_witness_fn)?;
+
     ctx.export_value("multiSig", js_object)
 }
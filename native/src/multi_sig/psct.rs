@@ -0,0 +1,744 @@
+//! Partially signed transaction container. The container carries the public
+//! nonce commitments, nonces and partial signatures of the co-signers so that
+//! each co-signer can add its part on its own machine with its own incomplete
+//! session, and the containers of different co-signers can be merged until
+//! all the parts are gathered. The incomplete session of a co-signer is a
+//! native class instance bound to the message and signers of the container it
+//! was created for, so its private key and nonce stay in native memory until
+//! it is finalized, destroyed or garbage collected. A session nonce signs
+//! once only, as signing two messages with the same nonce reveals the private
+//! key
+use std::collections::BTreeMap;
+
+use chain_core::common::H256;
+use client_common::{Error, ErrorKind, PublicKey, Result};
+use client_core::multi_sig::MultiSigBuilder;
+use neon::declare_types;
+use neon::prelude::*;
+use serde::{Deserialize, Serialize};
+use zeroize::Zeroizing;
+
+use crate::error::{ClientErrorNeonExt, ErrorCode};
+use crate::function_types::*;
+use crate::offline::UnsignedTransaction;
+
+const PSCT_VERSION: u32 = 1;
+
+/// Incomplete session of current signer with the partially signed
+/// transaction it is bound to. Not Debug as the session holds the private key
+#[derive(Clone)]
+struct PsctSession {
+    message: H256,
+    /// Sorted hex encoded public keys of all the signers
+    signer_public_keys: Vec<String>,
+    self_public_key: String,
+    /// Nonce commitment of current signer once generated
+    nonce_commitment: Option<H256>,
+    /// Whether the nonce of the session has partially signed
+    partially_signed: bool,
+    session: Zeroizing<Vec<u8>>,
+}
+
+/// Session of a session handle, None once finalized or destroyed
+pub struct PsctSessionHandle(Option<PsctSession>);
+
+declare_types! {
+    pub class JsPsctSession for PsctSessionHandle {
+        init(_ctx) {
+            Ok(PsctSessionHandle(None))
+        }
+
+        // destroy the session and release its secrets from native memory
+        // @return boolean: whether the session was not finalized or
+        //     destroyed yet
+        method destroy(mut ctx) {
+            let mut this = ctx.this();
+            let existed = {
+                let guard = ctx.lock();
+                let mut session_handle = this.borrow_mut(&guard);
+                session_handle.0.take().is_some()
+            };
+
+            Ok(ctx.boolean(existed).upcast())
+        }
+    }
+}
+
+impl PsctSession {
+    /// Check the session is created for the message and signers of the
+    /// partially signed transaction
+    fn check_bound_to(
+        &self,
+        psct: &PartiallySignedTransaction,
+        self_public_key: &str,
+    ) -> Result<()> {
+        let mut signer_public_keys = psct.signer_public_keys.clone();
+        signer_public_keys.sort();
+        if hex::encode(self.message) != psct.message.to_lowercase()
+            || self.signer_public_keys != signer_public_keys
+            || self.self_public_key != self_public_key
+        {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "Session is not created for the message and signers of the partially signed transaction",
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Check the nonce commitment of current signer in the partially signed
+    /// transaction is the one of the session
+    fn check_nonce_commitment(&self, psct: &PartiallySignedTransaction) -> Result<H256> {
+        let nonce_commitment = self.nonce_commitment.ok_or_else(|| {
+            Error::new(
+                ErrorKind::InvalidInput,
+                "Session has not generated its nonce commitment",
+            )
+        })?;
+        match psct.nonce_commitments.get(&self.self_public_key) {
+            Some(value) if *value == hex::encode(nonce_commitment) => Ok(nonce_commitment),
+            _ => Err(Error::new(
+                ErrorKind::InvalidInput,
+                "Nonce commitment of current signer does not match the session",
+            )),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PartiallySignedTransaction {
+    version: u32,
+    /// Hex encoded H256 message to be signed
+    message: String,
+    /// Hex encoded public keys of all the signers
+    signer_public_keys: Vec<String>,
+    /// Exported unsigned transaction JSON string which id is the message
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    unsigned_transaction: Option<String>,
+    /// Hex encoded parts keyed by the hex encoded signer public key
+    #[serde(default)]
+    nonce_commitments: BTreeMap<String, String>,
+    #[serde(default)]
+    nonces: BTreeMap<String, String>,
+    #[serde(default)]
+    partial_signatures: BTreeMap<String, String>,
+}
+
+impl PartiallySignedTransaction {
+    fn check_signer(&self, public_key: &str) -> Result<()> {
+        if self.signer_public_keys.iter().any(|key| key == public_key) {
+            Ok(())
+        } else {
+            Err(Error::new(
+                ErrorKind::InvalidInput,
+                format!("{} is not a signer of the transaction", public_key),
+            ))
+        }
+    }
+
+    /// Check every part is of a signer of the transaction
+    fn check_parts(&self) -> Result<()> {
+        self.nonce_commitments
+            .keys()
+            .chain(self.nonces.keys())
+            .chain(self.partial_signatures.keys())
+            .try_for_each(|public_key| self.check_signer(public_key))
+    }
+
+    fn missing_signers(&self, parts: &BTreeMap<String, String>) -> Vec<String> {
+        self.signer_public_keys
+            .iter()
+            .filter(|public_key| !parts.contains_key(*public_key))
+            .cloned()
+            .collect()
+    }
+
+    fn check_complete(&self, parts: &BTreeMap<String, String>, name: &str) -> Result<()> {
+        let missing_signers = self.missing_signers(parts);
+        if missing_signers.is_empty() {
+            Ok(())
+        } else {
+            Err(Error::new(
+                ErrorKind::InvalidInput,
+                format!(
+                    "Missing {} of signers: {}",
+                    name,
+                    missing_signers.join(", ")
+                ),
+            ))
+        }
+    }
+
+    fn merge(&mut self, other: PartiallySignedTransaction) -> Result<()> {
+        let mut signer_public_keys = self.signer_public_keys.clone();
+        let mut other_signer_public_keys = other.signer_public_keys.clone();
+        signer_public_keys.sort();
+        other_signer_public_keys.sort();
+        if self.message != other.message || signer_public_keys != other_signer_public_keys {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "Partially signed transactions are of different message or signers",
+            ));
+        }
+
+        if let Some(unsigned_transaction) = other.unsigned_transaction {
+            match self.unsigned_transaction {
+                None => self.unsigned_transaction = Some(unsigned_transaction),
+                Some(ref current) if *current != unsigned_transaction => {
+                    return Err(Error::new(
+                        ErrorKind::InvalidInput,
+                        "Partially signed transactions are of different unsigned transaction",
+                    ));
+                }
+                _ => {}
+            }
+        }
+
+        other.check_parts()?;
+        for (public_key, value) in other.nonce_commitments {
+            insert_part(
+                &mut self.nonce_commitments,
+                public_key,
+                value,
+                "nonce commitment",
+            )?;
+        }
+        for (public_key, value) in other.nonces {
+            insert_part(&mut self.nonces, public_key, value, "nonce")?;
+        }
+        for (public_key, value) in other.partial_signatures {
+            insert_part(
+                &mut self.partial_signatures,
+                public_key,
+                value,
+                "partial signature",
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Inserts the part of the signer, throws when the signer already has a
+/// different one
+fn insert_part(
+    parts: &mut BTreeMap<String, String>,
+    public_key: String,
+    value: String,
+    name: &str,
+) -> Result<()> {
+    let value = value.to_lowercase();
+    match parts.get(&public_key) {
+        Some(existing) if *existing != value => Err(Error::new(
+            ErrorKind::InvalidInput,
+            format!("Conflicting {} of signer {}", name, public_key),
+        )),
+        Some(_) => Ok(()),
+        None => {
+            parts.insert(public_key, value);
+            Ok(())
+        }
+    }
+}
+
+/// Adds the parts of the other signers to the session
+fn add_parts_to_session<F>(
+    session: &mut MultiSigBuilder,
+    parts: &BTreeMap<String, String>,
+    self_public_key: &str,
+    mut add: F,
+) -> Result<()>
+where
+    F: FnMut(&mut MultiSigBuilder, &PublicKey, H256) -> Result<()>,
+{
+    for (public_key, value) in parts.iter() {
+        if public_key == self_public_key {
+            continue;
+        }
+
+        let public_key = public_key_from_hex(public_key)?;
+        add(session, &public_key, h256_from_hex(value)?)?;
+    }
+
+    Ok(())
+}
+
+fn public_key_to_hex(public_key: &PublicKey) -> String {
+    hex::encode(public_key.serialize())
+}
+
+fn public_key_from_hex(public_key: &str) -> Result<PublicKey> {
+    let bytes = hex::decode(public_key).map_err(|err| {
+        Error::new(
+            ErrorKind::DeserializationError,
+            format!("Unable to decode signer public key hex: {}", err),
+        )
+    })?;
+
+    PublicKey::deserialize_from(&bytes)
+}
+
+fn h256_from_hex(value: &str) -> Result<H256> {
+    let bytes = hex::decode(value).map_err(|err| {
+        Error::new(
+            ErrorKind::DeserializationError,
+            format!("Unable to decode hex: {}", err),
+        )
+    })?;
+    if bytes.len() != 32 {
+        return Err(Error::new(
+            ErrorKind::DeserializationError,
+            "Expected hex string of 32 bytes",
+        ));
+    }
+
+    let mut h256: H256 = [0; 32];
+    h256.copy_from_slice(&bytes);
+
+    Ok(h256)
+}
+
+/// create new partially signed transaction for the co-signers to pass around
+/// @arguments
+/// - message: H256 format of msg to be signed
+/// - signer_public_keys: public keys of all the signers
+/// - unsigned_transaction: (optional) exported unsigned transaction JSON
+///     string which txId is the message, for the co-signers to review
+/// @return partially signed transaction JSON string
+pub fn psct_create(mut ctx: FunctionContext) -> JsResult<JsString> {
+    let message = hex::encode(h256_str_argument(&mut ctx, 0)?);
+    let signer_public_keys = public_key_vector_argument(&mut ctx, 1)?;
+    let unsigned_transaction = optional_string_argument(&mut ctx, 2)?;
+
+    if let Some(ref unsigned_transaction) = unsigned_transaction {
+        let unsigned_transaction: UnsignedTransaction<serde_json::Value> =
+            serde_json::from_str(unsigned_transaction).chain_neon_with_code(
+                &mut ctx,
+                ErrorCode::DeserializationError,
+                "Unable to deserialize unsigned transaction",
+            )?;
+        if unsigned_transaction.tx_id.to_lowercase() != message {
+            return ctx.throw_error("Message is not the id of the unsigned transaction");
+        }
+    }
+
+    let mut signer_public_keys: Vec<String> =
+        signer_public_keys.iter().map(public_key_to_hex).collect();
+    signer_public_keys.sort();
+    signer_public_keys.dedup();
+
+    let psct = PartiallySignedTransaction {
+        version: PSCT_VERSION,
+        message,
+        signer_public_keys,
+        unsigned_transaction,
+        nonce_commitments: BTreeMap::new(),
+        nonces: BTreeMap::new(),
+        partial_signatures: BTreeMap::new(),
+    };
+
+    psct_to_js(&mut ctx, &psct)
+}
+
+/// create the incomplete session of current signer for the partially signed
+/// transaction, bound to its message and signers
+/// @arguments
+/// - psct: partially signed transaction JSON string
+/// - self_public_key: public key of current signer
+/// - self_private_key: private key of current signer
+/// @return incompleteSession: session handle { destroy() }, passed to the
+///     psct functions of current signer which update it in place
+pub fn psct_new_session(mut ctx: FunctionContext) -> JsResult<JsPsctSession> {
+    let psct = psct_argument(&mut ctx, 0)?;
+    let self_public_key = public_key_argument(&mut ctx, 1)?;
+    let self_private_key = private_key_argument(&mut ctx, 2)?;
+
+    let self_public_key_hex = public_key_to_hex(&self_public_key);
+    psct.check_signer(&self_public_key_hex)
+        .chain_neon_with_code(
+            &mut ctx,
+            ErrorCode::InvalidPublicKey,
+            "Invalid self public key",
+        )?;
+    let message = h256_from_hex(&psct.message).chain_neon(&mut ctx, "Invalid psct message")?;
+    let mut signer_public_keys = psct.signer_public_keys.clone();
+    signer_public_keys.sort();
+    let public_keys = signer_public_keys
+        .iter()
+        .map(|public_key| public_key_from_hex(public_key))
+        .collect::<Result<Vec<PublicKey>>>()
+        .chain_neon_with_code(
+            &mut ctx,
+            ErrorCode::InvalidPublicKey,
+            "Invalid signer public key",
+        )?;
+
+    let session = MultiSigBuilder::new(message, public_keys, self_public_key, self_private_key)
+        .chain_neon(&mut ctx, "Unable to create new MultiSigBuilder")?;
+    let psct_session = PsctSession {
+        message,
+        signer_public_keys,
+        self_public_key: self_public_key_hex,
+        nonce_commitment: None,
+        partially_signed: false,
+        session: Zeroizing::new(session.to_incomplete()),
+    };
+
+    let no_args: Vec<Handle<JsValue>> = Vec::new();
+    let mut handle = JsPsctSession::new(&mut ctx, no_args)?;
+    {
+        let guard = ctx.lock();
+        handle.borrow_mut(&guard).0 = Some(psct_session);
+    }
+
+    Ok(handle)
+}
+
+/// merge the parts gathered in two partially signed transactions of the same
+/// message and signers
+/// @arguments
+/// - psct: partially signed transaction JSON string
+/// - other_psct: partially signed transaction JSON string to merge
+/// @return merged partially signed transaction JSON string
+pub fn psct_merge(mut ctx: FunctionContext) -> JsResult<JsString> {
+    let mut psct = psct_argument(&mut ctx, 0)?;
+    let other_psct = psct_argument(&mut ctx, 1)?;
+
+    psct.merge(other_psct)
+        .chain_neon(&mut ctx, "Unable to merge partially signed transactions")?;
+
+    psct_to_js(&mut ctx, &psct)
+}
+
+/// status of the partially signed transaction
+/// @arguments
+/// - psct: partially signed transaction JSON string
+/// @return {
+///     message: string,
+///     missingNonceCommitments: [string],
+///     missingNonces: [string],
+///     missingPartialSignatures: [string],
+///     complete: boolean
+/// } where missing signers are hex encoded public keys
+pub fn psct_status(mut ctx: FunctionContext) -> JsResult<JsObject> {
+    let psct = psct_argument(&mut ctx, 0)?;
+
+    let missing_nonce_commitments = psct.missing_signers(&psct.nonce_commitments);
+    let missing_nonces = psct.missing_signers(&psct.nonces);
+    let missing_partial_signatures = psct.missing_signers(&psct.partial_signatures);
+    let complete = missing_partial_signatures.is_empty();
+
+    let status = ctx.empty_object();
+    let message = ctx.string(&psct.message);
+    status.set(&mut ctx, "message", message)?;
+    let missing_nonce_commitments = strings_to_js(&mut ctx, &missing_nonce_commitments)?;
+    status.set(
+        &mut ctx,
+        "missingNonceCommitments",
+        missing_nonce_commitments,
+    )?;
+    let missing_nonces = strings_to_js(&mut ctx, &missing_nonces)?;
+    status.set(&mut ctx, "missingNonces", missing_nonces)?;
+    let missing_partial_signatures = strings_to_js(&mut ctx, &missing_partial_signatures)?;
+    status.set(
+        &mut ctx,
+        "missingPartialSignatures",
+        missing_partial_signatures,
+    )?;
+    let complete = ctx.boolean(complete);
+    status.set(&mut ctx, "complete", complete)?;
+
+    Ok(status)
+}
+
+/// generate nonce commitment of current signer and add it to the partially
+/// signed transaction
+/// @arguments
+/// - psct: partially signed transaction JSON string
+/// - incomplete_session: session handle of current signer created by
+///     psctNewSession
+/// - self_public_key: public key of current signer
+/// @return updated partially signed transaction JSON string
+pub fn psct_add_nonce_commitment(mut ctx: FunctionContext) -> JsResult<JsString> {
+    psct_session_step(
+        &mut ctx,
+        |ctx, psct, psct_session, session, self_public_key| {
+            let nonce_commitment = session
+                .nonce_commitment()
+                .chain_neon(ctx, "Unable to process nonce commitment")?;
+            psct_session.nonce_commitment = Some(nonce_commitment);
+            insert_part(
+                &mut psct.nonce_commitments,
+                self_public_key.to_owned(),
+                hex::encode(nonce_commitment),
+                "nonce commitment",
+            )
+            .chain_neon(ctx, "Unable to add nonce commitment")?;
+
+            psct_to_js(ctx, psct)
+        },
+    )
+}
+
+/// add nonce commitments of the other signers to the session, generate nonce
+/// of current signer and add it to the partially signed transaction. Throws
+/// when missing any signer's nonce commitment
+/// @arguments
+/// - psct: partially signed transaction JSON string
+/// - incomplete_session: session handle of current signer, after
+///     psctAddNonceCommitment
+/// - self_public_key: public key of current signer
+/// @return updated partially signed transaction JSON string
+pub fn psct_add_nonce(mut ctx: FunctionContext) -> JsResult<JsString> {
+    psct_session_step(
+        &mut ctx,
+        |ctx, psct, psct_session, session, self_public_key| {
+            psct_session
+                .check_nonce_commitment(psct)
+                .chain_neon(ctx, "Unable to process nonce")?;
+            psct.check_complete(&psct.nonce_commitments, "nonce commitments")
+                .chain_neon(ctx, "Unable to process nonce")?;
+            add_parts_to_session(
+                session,
+                &psct.nonce_commitments,
+                self_public_key,
+                |session, public_key, nonce_commitment| {
+                    session.add_nonce_commitment(public_key, nonce_commitment)
+                },
+            )
+            .chain_neon(ctx, "Unable to add nonce commitment")?;
+
+            let nonce = session.nonce().chain_neon(ctx, "Unable to process nonce")?;
+            insert_part(
+                &mut psct.nonces,
+                self_public_key.to_owned(),
+                hex::encode(nonce),
+                "nonce",
+            )
+            .chain_neon(ctx, "Unable to add nonce")?;
+
+            psct_to_js(ctx, psct)
+        },
+    )
+}
+
+/// add nonces of the other signers to the session, generate partial signature
+/// of current signer and add it to the partially signed transaction. Throws
+/// when missing any signer's nonce
+/// @arguments
+/// - psct: partially signed transaction JSON string
+/// - incomplete_session: session handle of current signer, after
+///     psctAddNonce
+/// - self_public_key: public key of current signer
+/// @return updated partially signed transaction JSON string
+pub fn psct_add_partial_signature(mut ctx: FunctionContext) -> JsResult<JsString> {
+    psct_session_step(
+        &mut ctx,
+        |ctx, psct, psct_session, session, self_public_key| {
+            psct_session
+                .check_nonce_commitment(psct)
+                .chain_neon(ctx, "Unable to partially sign")?;
+            if psct_session.partially_signed {
+                return ctx
+                    .throw_error("Nonce of the session has already signed, create a new session");
+            }
+            psct.check_complete(&psct.nonces, "nonces")
+                .chain_neon(ctx, "Unable to partially sign")?;
+            add_parts_to_session(
+                session,
+                &psct.nonces,
+                self_public_key,
+                |session, public_key, nonce| session.add_nonce(public_key, &nonce),
+            )
+            .chain_neon(ctx, "Unable to add nonce")?;
+
+            let partial_signature = session
+                .partial_signature()
+                .chain_neon(ctx, "Unable to partially sign")?;
+            psct_session.partially_signed = true;
+            insert_part(
+                &mut psct.partial_signatures,
+                self_public_key.to_owned(),
+                hex::encode(partial_signature),
+                "partial signature",
+            )
+            .chain_neon_with_code(
+                ctx,
+                ErrorCode::InvalidSignature,
+                "Unable to add partial signature",
+            )?;
+
+            psct_to_js(ctx, psct)
+        },
+    )
+}
+
+/// add partial signatures of the other signers to the session and create the
+/// final signature. Throws when missing any signer's partial signature. The
+/// session is released once the signature is created
+/// @arguments
+/// - psct: partially signed transaction JSON string
+/// - incomplete_session: session handle of current signer, after
+///     psctAddPartialSignature
+/// - self_public_key: public key of current signer
+/// @return signature:SchnorrSignature
+pub fn psct_finalize(mut ctx: FunctionContext) -> JsResult<JsBuffer> {
+    let signature = psct_session_step(
+        &mut ctx,
+        |ctx, psct, psct_session, session, self_public_key| {
+            if !psct_session.partially_signed {
+                return ctx.throw_error("Session has not partially signed");
+            }
+            psct.check_complete(&psct.partial_signatures, "partial signatures")
+                .chain_neon_with_code(
+                    ctx,
+                    ErrorCode::InvalidSignature,
+                    "Unable to create signature",
+                )?;
+            add_parts_to_session(
+                session,
+                &psct.partial_signatures,
+                self_public_key,
+                |session, public_key, partial_signature| {
+                    session.add_partial_signature(public_key, partial_signature)
+                },
+            )
+            .chain_neon_with_code(
+                ctx,
+                ErrorCode::InvalidSignature,
+                "Unable to add partial signature",
+            )?;
+
+            let signature = session
+                .signature()
+                .chain_neon_with_code(
+                    ctx,
+                    ErrorCode::InvalidSignature,
+                    "Unable to create signature",
+                )?
+                .serialize_default()
+                .to_vec();
+
+            bytes_to_buffer(ctx, &signature)
+        },
+    )?;
+
+    let mut handle = psct_session_argument(&mut ctx, 1)?;
+    {
+        let guard = ctx.lock();
+        handle.borrow_mut(&guard).0 = None;
+    }
+
+    Ok(signature)
+}
+
+fn psct_argument(ctx: &mut FunctionContext, i: i32) -> NeonResult<PartiallySignedTransaction> {
+    let json = typed_argument::<JsString>(ctx, i, "psct", "JSON string")?.value();
+    let psct: PartiallySignedTransaction = serde_json::from_str(&json).chain_neon_with_code(
+        ctx,
+        ErrorCode::DeserializationError,
+        "Unable to deserialize partially signed transaction",
+    )?;
+
+    if psct.version != PSCT_VERSION {
+        return ctx.throw_error(format!(
+            "Unsupported partially signed transaction version {}",
+            psct.version
+        ));
+    }
+    psct.check_parts()
+        .chain_neon(ctx, "Invalid partially signed transaction")?;
+
+    Ok(psct)
+}
+
+fn psct_session_argument<'a>(
+    ctx: &mut FunctionContext<'a>,
+    i: i32,
+) -> NeonResult<Handle<'a, JsPsctSession>> {
+    let handle = ctx.argument::<JsValue>(i)?;
+    match handle.downcast::<JsPsctSession>() {
+        Ok(handle) => Ok(handle),
+        Err(_) => {
+            let got = describe_value(ctx, handle)?;
+            throw_invalid_argument(
+                ctx,
+                ErrorCode::InvalidArgument,
+                i,
+                "incomplete_session",
+                "session handle of psctNewSession",
+                got,
+            )
+        }
+    }
+}
+
+/// Runs the step of current signer with the partially signed transaction at
+/// argument 0, the session handle at argument 1 bound to it and the public
+/// key of current signer at argument 2. The session handle is updated once
+/// the step succeeds, and left as it was when the step throws
+fn psct_session_step<'a, T, F>(ctx: &mut FunctionContext<'a>, step: F) -> NeonResult<T>
+where
+    F: FnOnce(
+        &mut FunctionContext<'a>,
+        &mut PartiallySignedTransaction,
+        &mut PsctSession,
+        &mut MultiSigBuilder,
+        &str,
+    ) -> NeonResult<T>,
+{
+    let mut psct = psct_argument(ctx, 0)?;
+    let mut handle = psct_session_argument(ctx, 1)?;
+    let self_public_key = public_key_to_hex(&public_key_argument(ctx, 2)?);
+
+    psct.check_signer(&self_public_key).chain_neon_with_code(
+        ctx,
+        ErrorCode::InvalidPublicKey,
+        "Invalid self public key",
+    )?;
+    let psct_session = {
+        let guard = ctx.lock();
+        let session_handle = handle.borrow(&guard);
+        session_handle.0.clone()
+    };
+    let mut psct_session = psct_session.chain_neon(
+        ctx,
+        "Session is finalized or destroyed, create a new session",
+    )?;
+    psct_session
+        .check_bound_to(&psct, &self_public_key)
+        .chain_neon(ctx, "Invalid incomplete session")?;
+    let mut session = MultiSigBuilder::from_incomplete_insecure(psct_session.session.to_vec())
+        .chain_neon(ctx, "Unable to restore the incomplete session")?;
+
+    let result = step(
+        ctx,
+        &mut psct,
+        &mut psct_session,
+        &mut session,
+        &self_public_key,
+    )?;
+
+    psct_session.session = Zeroizing::new(session.to_incomplete());
+    {
+        let guard = ctx.lock();
+        handle.borrow_mut(&guard).0 = Some(psct_session);
+    }
+
+    Ok(result)
+}
+
+fn psct_to_js<'a>(
+    ctx: &mut FunctionContext<'a>,
+    psct: &PartiallySignedTransaction,
+) -> JsResult<'a, JsString> {
+    let json = serde_json::to_string(psct).chain_neon_with_code(
+        ctx,
+        ErrorCode::SerializationError,
+        "Unable to serialize partially signed transaction",
+    )?;
+
+    Ok(ctx.string(json))
+}
@@ -0,0 +1,255 @@
+//! Stateful MuSig sessions kept in native memory so that the signer's private
+//! key and nonce never leave the native side. A session is a native class
+//! instance which owns its multisig builder, so the secrets are released once
+//! the final signature is created, the session is destroyed, or garbage
+//! collection drops a session which is no longer referenced
+use chain_core::common::H256;
+use client_core::multi_sig::MultiSigBuilder;
+use neon::declare_types;
+use neon::prelude::*;
+
+use crate::error::{ClientErrorNeonExt, ErrorCode};
+use crate::function_types::*;
+
+/// Multisig builder of a session handle, None once signed or destroyed. Not
+/// Debug as the builder holds the private key
+pub struct MultiSigSessionHandle(Option<MultiSigBuilder>);
+
+declare_types! {
+    pub class JsMultiSigSession for MultiSigSessionHandle {
+        init(_ctx) {
+            Ok(MultiSigSessionHandle(None))
+        }
+
+        // destroy the session and release its secrets from native memory
+        // @return boolean: whether the session was not signed or destroyed
+        //     yet
+        method destroy(mut ctx) {
+            let mut this = ctx.this();
+            let existed = {
+                let guard = ctx.lock();
+                let mut session_handle = this.borrow_mut(&guard);
+                session_handle.0.take().is_some()
+            };
+
+            Ok(ctx.boolean(existed).upcast())
+        }
+    }
+}
+
+/// create new stateful multisig session
+/// @arguments
+/// - message:  H256 format of msg to be signed
+/// - signer_public_keys: public keys of all the signers (including current signer)
+/// - self_public_key: public key of current signer
+/// - self_private_key: private key of current signer
+/// @return session handle { destroy() }
+pub fn create_session(mut ctx: FunctionContext) -> JsResult<JsMultiSigSession> {
+    let message = h256_str_argument(&mut ctx, 0)?;
+    let signer_public_keys = public_key_vector_argument(&mut ctx, 1)?;
+    let self_public_key = public_key_argument(&mut ctx, 2)?;
+    let self_private_key = private_key_argument(&mut ctx, 3)?;
+
+    let session = MultiSigBuilder::new(
+        message,
+        signer_public_keys,
+        self_public_key,
+        self_private_key,
+    )
+    .chain_neon(&mut ctx, "Unable to create new MultiSigBuilder")?;
+
+    let no_args: Vec<Handle<JsValue>> = Vec::new();
+    let mut handle = JsMultiSigSession::new(&mut ctx, no_args)?;
+    {
+        let guard = ctx.lock();
+        handle.borrow_mut(&guard).0 = Some(session);
+    }
+
+    Ok(handle)
+}
+
+/// generate nonce commitment of current signer
+/// @arguments
+/// - session: session handle of createSession
+/// @return nonceCommitment
+pub fn session_nonce_commitment(mut ctx: FunctionContext) -> JsResult<JsBuffer> {
+    let handle = session_argument(&mut ctx, 0)?;
+
+    let nonce_commitment: H256 =
+        with_session(&mut ctx, handle, |session| session.nonce_commitment())?
+            .chain_neon(&mut ctx, "Unable to process nonce commitment")?;
+
+    h256_to_buffer(&mut ctx, &nonce_commitment)
+}
+
+/// add nonce commitment of other signer to session
+/// @arguments
+/// - session: session handle of createSession
+/// - public_key: other signer's public key
+/// - nonce_commitment: other signer's nonce_commitment
+pub fn session_add_nonce_commitment(mut ctx: FunctionContext) -> JsResult<JsUndefined> {
+    let handle = session_argument(&mut ctx, 0)?;
+    let public_key = public_key_argument(&mut ctx, 1)?;
+    let nonce_commitment = h256_buffer_argument(&mut ctx, 2)?;
+
+    with_session(&mut ctx, handle, |session| {
+        session.add_nonce_commitment(&public_key, nonce_commitment)
+    })?
+    .chain_neon(&mut ctx, "Unable to add nonce commitment")?;
+
+    Ok(ctx.undefined())
+}
+
+/// generate nonce of current signer: notice that it will throw error if
+///     missing any required signer's nonce commitment
+/// @arguments
+/// - session: session handle of createSession
+/// @return nonce
+pub fn session_nonce(mut ctx: FunctionContext) -> JsResult<JsBuffer> {
+    let handle = session_argument(&mut ctx, 0)?;
+
+    let nonce: H256 = with_session(&mut ctx, handle, |session| session.nonce())?
+        .chain_neon(&mut ctx, "Unable to process nonce")?;
+
+    h256_to_buffer(&mut ctx, &nonce)
+}
+
+/// add nonce of other signer to session
+/// @arguments
+/// - session: session handle of createSession
+/// - public_key: the other signer's public key
+/// - nonce: the other signer's nonce
+pub fn session_add_nonce(mut ctx: FunctionContext) -> JsResult<JsUndefined> {
+    let handle = session_argument(&mut ctx, 0)?;
+    let public_key = public_key_argument(&mut ctx, 1)?;
+    let nonce = h256_buffer_argument(&mut ctx, 2)?;
+
+    with_session(&mut ctx, handle, |session| {
+        session.add_nonce(&public_key, &nonce)
+    })?
+    .chain_neon(&mut ctx, "Unable to add nonce")?;
+
+    Ok(ctx.undefined())
+}
+
+/// generate partial signature of current signer
+/// @arguments
+/// - session: session handle of createSession
+/// @return partialSignature
+pub fn session_partial_sign(mut ctx: FunctionContext) -> JsResult<JsBuffer> {
+    let handle = session_argument(&mut ctx, 0)?;
+
+    let partial_signature: H256 =
+        with_session(&mut ctx, handle, |session| session.partial_signature())?
+            .chain_neon(&mut ctx, "Unable to partially sign")?;
+
+    h256_to_buffer(&mut ctx, &partial_signature)
+}
+
+/// add partial signature of other signer to session
+/// @arguments
+/// - session: session handle of createSession
+/// - public_key: the other signer's public key
+/// - partial_signature: the other signer's H256 partial signature
+pub fn session_add_partial_signature(mut ctx: FunctionContext) -> JsResult<JsUndefined> {
+    let handle = session_argument(&mut ctx, 0)?;
+    let public_key = public_key_argument(&mut ctx, 1)?;
+    let partial_signature = h256_buffer_argument(&mut ctx, 2)?;
+
+    with_session(&mut ctx, handle, |session| {
+        session.add_partial_signature(&public_key, partial_signature)
+    })?
+    .chain_neon_with_code(
+        &mut ctx,
+        ErrorCode::InvalidSignature,
+        "Unable to add partial signature",
+    )?;
+
+    Ok(ctx.undefined())
+}
+
+/// combine the partial signatures of all co-signers into final signature
+/// @arguments
+/// - session: session handle of createSession
+/// @return signature:SchnorrSignature. The session is released once the
+///     signature is created and cannot be used afterwards
+pub fn session_signature(mut ctx: FunctionContext) -> JsResult<JsBuffer> {
+    let mut handle = session_argument(&mut ctx, 0)?;
+
+    let signature = with_session(&mut ctx, handle, |session| session.signature())?
+        .chain_neon_with_code(
+            &mut ctx,
+            ErrorCode::InvalidSignature,
+            "Unable to create signature",
+        )?
+        .serialize_default();
+    {
+        let guard = ctx.lock();
+        handle.borrow_mut(&guard).0 = None;
+    }
+
+    bytes_to_buffer(&mut ctx, &signature)
+}
+
+/// destroy the session and release its secrets from native memory, same as
+///     `destroy()` of the session handle
+/// @arguments
+/// - session: session handle of createSession
+/// @return boolean: whether the session was not signed or destroyed yet
+pub fn destroy_session(mut ctx: FunctionContext) -> JsResult<JsBoolean> {
+    let mut handle = session_argument(&mut ctx, 0)?;
+
+    let existed = {
+        let guard = ctx.lock();
+        let mut session_handle = handle.borrow_mut(&guard);
+        session_handle.0.take().is_some()
+    };
+
+    Ok(ctx.boolean(existed))
+}
+
+fn session_argument<'a>(
+    ctx: &mut FunctionContext<'a>,
+    i: i32,
+) -> NeonResult<Handle<'a, JsMultiSigSession>> {
+    let handle = ctx.argument::<JsValue>(i)?;
+    match handle.downcast::<JsMultiSigSession>() {
+        Ok(handle) => Ok(handle),
+        Err(_) => {
+            let got = describe_value(ctx, handle)?;
+            throw_invalid_argument(
+                ctx,
+                ErrorCode::InvalidArgument,
+                i,
+                "session",
+                "session handle of createSession",
+                got,
+            )
+        }
+    }
+}
+
+/// Runs the function with the multisig builder of the session handle
+fn with_session<T, F>(
+    ctx: &mut FunctionContext,
+    mut handle: Handle<JsMultiSigSession>,
+    f: F,
+) -> NeonResult<T>
+where
+    F: FnOnce(&mut MultiSigBuilder) -> T,
+{
+    let result = {
+        let guard = ctx.lock();
+        let mut session_handle = handle.borrow_mut(&guard);
+        session_handle.0.as_mut().map(f)
+    };
+
+    result.chain_neon(
+        ctx,
+        "Multisig session is signed or destroyed, create a new session",
+    )
+}
+
+fn h256_to_buffer<'a>(ctx: &mut FunctionContext<'a>, value: &H256) -> JsResult<'a, JsBuffer> {
+    bytes_to_buffer(ctx, value)
+}
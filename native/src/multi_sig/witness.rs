@@ -0,0 +1,90 @@
+//! Witness of an input spent from a multisig transfer address. The address is
+//! the root of a Merkle tree whose leaves are the combined public keys of every
+//! signer subset of required signers size, so the witness carries the MuSig
+//! signature of the subset with the Merkle proof of its combined public key
+use client_common::{MultiSigAddress, PublicKey};
+use neon::prelude::*;
+
+use crate::error::{ClientErrorNeonExt, ErrorCode};
+use crate::function_types::*;
+use crate::signer::build_tree_sig_witness;
+
+/// create Merkle proof of the signer subset in the multisig address
+/// @arguments
+/// - public_keys: public keys of all the signers of the address
+/// - self_public_key: public key of current signer
+/// - required_signers: min number of signers of the address
+/// - signer_public_keys: public keys of the signers spending the input,
+///     required_signers of them
+/// @return SCALE-encoded Merkle proof Buffer
+pub fn tree_sig_proof(mut ctx: FunctionContext) -> JsResult<JsBuffer> {
+    let multi_sig_address = multi_sig_address_arguments(&mut ctx, 0)?;
+    let signer_public_keys = signer_public_keys_argument(&mut ctx, 3)?;
+
+    let proof = multi_sig_address
+        .generate_proof(signer_public_keys)
+        .chain_neon(&mut ctx, "Unable to generate merkle proof")?
+        .chain_neon_with_code(
+            &mut ctx,
+            ErrorCode::InvalidPublicKey,
+            "Signer public keys are not a signer subset of the multisig address",
+        )?;
+
+    encode_to_buffer(&mut ctx, &proof)
+}
+
+/// create the witness of an input of the multisig address from the final
+/// MuSig signature of the signer subset, e.g. of sign, sessionSignature or
+/// psctFinalize. The signature is verified against the transaction id before
+/// the witness is created
+/// @arguments
+/// - public_keys: public keys of all the signers of the address
+/// - self_public_key: public key of current signer
+/// - required_signers: min number of signers of the address
+/// - signer_public_keys: public keys of the signers of the session
+/// - signature: final signature Buffer of the session
+/// - tx_id: transaction id hex string, the message of the session
+/// @return SCALE-encoded TxInWitness Buffer, to be passed to
+///     addInputWitnessLinearFee
+pub fn tree_sig_witness(mut ctx: FunctionContext) -> JsResult<JsBuffer> {
+    let multi_sig_address = multi_sig_address_arguments(&mut ctx, 0)?;
+    let signer_public_keys = signer_public_keys_argument(&mut ctx, 3)?;
+    let signature = hex_or_buffer_argument(&mut ctx, 4, "signature")?;
+    let tx_id = h256_str_argument(&mut ctx, 5)?;
+
+    let witness = build_tree_sig_witness(
+        &signature,
+        &multi_sig_address,
+        signer_public_keys,
+        Some(&tx_id),
+    )
+    .chain_neon_with_code(
+        &mut ctx,
+        ErrorCode::InvalidSignature,
+        "Unable to assemble tree signature witness",
+    )?;
+
+    encode_to_buffer(&mut ctx, &witness)
+}
+
+/// Multisig address of the public keys at argument `i`, the self public key
+/// at argument `i + 1` and the required signers at argument `i + 2`
+fn multi_sig_address_arguments(ctx: &mut FunctionContext, i: i32) -> NeonResult<MultiSigAddress> {
+    let public_keys = public_key_vector_argument(ctx, i)?;
+    let self_public_key = public_key_argument(ctx, i + 1)?;
+    let required_signers = required_signers_argument(ctx, i + 2)?;
+
+    MultiSigAddress::new(public_keys, self_public_key, required_signers).chain_neon_with_code(
+        ctx,
+        ErrorCode::InvalidAddress,
+        "Unable to create MultiSig address",
+    )
+}
+
+/// Signer public keys sorted as they are combined by the MuSig session
+fn signer_public_keys_argument(ctx: &mut FunctionContext, i: i32) -> NeonResult<Vec<PublicKey>> {
+    let mut signer_public_keys = public_key_vector_argument(ctx, i)?;
+    signer_public_keys.sort();
+
+    Ok(signer_public_keys)
+}
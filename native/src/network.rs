@@ -0,0 +1,389 @@
+use std::str::FromStr;
+use std::sync::RwLock;
+
+use lazy_static::lazy_static;
+use neon::prelude::*;
+
+use chain_core::init::network::Network;
+use chain_core::tx::fee::Milli;
+
+use crate::address::{bech32_hrp_of_network, is_valid_bech32_hrp, network_of_bech32_hrp};
+use crate::common::does_js_object_has_prop;
+use crate::error::ClientErrorNeonExt;
+use crate::function_types::*;
+
+/// Default LinearFee of the presets, in CRO per transaction and per byte
+const DEFAULT_FEE_CONSTANT: &str = "1.1";
+const DEFAULT_FEE_COEFFICIENT: &str = "1.25";
+/// Name of the network given inline without a name
+const INLINE_NETWORK_NAME: &str = "Custom";
+
+lazy_static! {
+    static ref PRESET_NETWORKS: Vec<NetworkConfig> = vec![
+        NetworkConfig::preset("Mainnet", Network::Mainnet, 0x2A),
+        NetworkConfig::preset("Testnet", Network::Testnet, 0x42),
+        NetworkConfig::preset("Devnet", Network::Devnet, 0xAB),
+    ];
+    static ref CUSTOM_DEVNETS: RwLock<Vec<NetworkConfig>> = RwLock::new(Vec::new());
+}
+
+#[derive(Debug, Clone)]
+pub struct NetworkConfig {
+    pub name: String,
+    pub network: Network,
+    pub chain_hex_id: u8,
+    /// Human readable part of the transfer addresses, the prefix of the
+    /// network type unless a devnet has a custom one
+    pub bech32_hrp: String,
+    pub fee_constant: String,
+    pub fee_coefficient: String,
+    /// Whether the network accepts transactions encoded without enclave
+    /// obfuscation, i.e. a devnet running without tx-query
+    pub plain_transactions: bool,
+}
+
+impl NetworkConfig {
+    fn preset(name: &str, network: Network, chain_hex_id: u8) -> NetworkConfig {
+        NetworkConfig {
+            name: name.to_owned(),
+            network,
+            chain_hex_id,
+            bech32_hrp: bech32_hrp_of_network(network).to_owned(),
+            fee_constant: DEFAULT_FEE_CONSTANT.to_owned(),
+            fee_coefficient: DEFAULT_FEE_COEFFICIENT.to_owned(),
+            plain_transactions: match network {
+                Network::Devnet => true,
+                Network::Mainnet | Network::Testnet => false,
+            },
+        }
+    }
+
+    /// Find the preset or registered custom devnet by name
+    pub fn find(name: &str) -> Option<NetworkConfig> {
+        if let Some(preset) = PRESET_NETWORKS.iter().find(|preset| preset.name == name) {
+            return Some(preset.clone());
+        }
+
+        CUSTOM_DEVNETS
+            .read()
+            .ok()?
+            .iter()
+            .find(|devnet| devnet.name == name)
+            .cloned()
+    }
+
+    /// Network given inline as { name?, chainHexId, bech32Hrp, feeConfig? },
+    /// which is used for the call only without being registered. The chain
+    /// hex id of Mainnet and Testnet is reserved for their prefixes, other
+    /// prefixes are custom devnet prefixes
+    fn from_inline(
+        ctx: &mut FunctionContext,
+        inline: Handle<JsObject>,
+    ) -> NeonResult<NetworkConfig> {
+        let bech32_hrp = inline
+            .get(ctx, "bech32Hrp")?
+            .downcast_or_throw::<JsString, FunctionContext>(ctx)
+            .chain_neon(ctx, "Unable to downcast bech32Hrp of network")?
+            .value();
+        if !is_valid_bech32_hrp(&bech32_hrp) {
+            return ctx.throw_error(format!("Invalid bech32 human readable part {}", bech32_hrp));
+        }
+        let network = network_of_bech32_hrp(&bech32_hrp).unwrap_or(Network::Devnet);
+
+        let chain_hex_id = inline.get(ctx, "chainHexId")?;
+        let chain_hex_id = match bytes_from_value(ctx, chain_hex_id)? {
+            Ok(chain_hex_id) => chain_hex_id,
+            Err(got) => {
+                return ctx.throw_error(format!(
+                    "Expected chainHexId of network to be a Buffer or hex string, got {}",
+                    got
+                ))
+            }
+        };
+        let chain_hex_id = chain_hex_id_from_vec(ctx, chain_hex_id)?;
+        let is_reserved = match (network_from_chain_hex_id(chain_hex_id), network) {
+            (Network::Devnet, _)
+            | (Network::Mainnet, Network::Mainnet)
+            | (Network::Testnet, Network::Testnet) => false,
+            _ => true,
+        };
+        if is_reserved {
+            return ctx.throw_error(format!(
+                "Chain hex id {} is reserved by another network than {}",
+                hex::encode_upper([chain_hex_id]),
+                bech32_hrp
+            ));
+        }
+
+        let name = inline.get(ctx, "name")?;
+        let name = if name.is_a::<JsString>() {
+            name.downcast_or_throw::<JsString, FunctionContext>(ctx)
+                .chain_neon(ctx, "Unable to downcast name of network")?
+                .value()
+        } else {
+            INLINE_NETWORK_NAME.to_owned()
+        };
+
+        let mut network_config = NetworkConfig::preset(&name, network, chain_hex_id);
+        network_config.bech32_hrp = bech32_hrp;
+        let fee_config = inline.get(ctx, "feeConfig")?;
+        if !fee_config.is_a::<JsUndefined>() {
+            let fee_config = fee_config
+                .downcast_or_throw::<JsObject, FunctionContext>(ctx)
+                .chain_neon(ctx, "Unable to downcast feeConfig")?;
+            network_config.fee_constant = fee_config_value(ctx, fee_config, "constant")?;
+            network_config.fee_coefficient = fee_config_value(ctx, fee_config, "coefficient")?;
+        }
+
+        Ok(network_config)
+    }
+
+    fn to_js<'a, C: Context<'a>>(&self, ctx: &mut C) -> JsResult<'a, JsObject> {
+        let handle = ctx.empty_object();
+
+        let name = ctx.string(&self.name);
+        handle.set(ctx, "name", name)?;
+        let network_type = ctx.string(match self.network {
+            Network::Mainnet => "Mainnet",
+            Network::Testnet => "Testnet",
+            Network::Devnet => "Devnet",
+        });
+        handle.set(ctx, "networkType", network_type)?;
+
+        let chain_hex_id = bytes_to_buffer(ctx, &[self.chain_hex_id])?;
+        handle.set(ctx, "chainHexId", chain_hex_id)?;
+
+        let bech32_hrp = ctx.string(&self.bech32_hrp);
+        handle.set(ctx, "bech32Hrp", bech32_hrp)?;
+
+        let fee_config = ctx.empty_object();
+        let algorithm = ctx.string("LinearFee");
+        fee_config.set(ctx, "algorithm", algorithm)?;
+        let constant = ctx.string(&self.fee_constant);
+        fee_config.set(ctx, "constant", constant)?;
+        let coefficient = ctx.string(&self.fee_coefficient);
+        fee_config.set(ctx, "coefficient", coefficient)?;
+        handle.set(ctx, "feeConfig", fee_config)?;
+
+        let plain_transactions = ctx.boolean(self.plain_transactions);
+        handle.set(ctx, "plainTransactions", plain_transactions)?;
+
+        Ok(handle)
+    }
+}
+
+/// Resolve network from a network handle, network name or inline network,
+/// which should have the built-in prefix of its network type. Transaction
+/// building and sync encode the addresses with the built-in prefixes, so a
+/// custom prefix is rejected instead of producing addresses of another prefix
+#[inline]
+pub fn builtin_network_config_from_value(
+    ctx: &mut FunctionContext,
+    value: Handle<JsValue>,
+) -> NeonResult<NetworkConfig> {
+    let network_config = network_config_from_value(ctx, value)?;
+    if network_config.bech32_hrp != bech32_hrp_of_network(network_config.network) {
+        return ctx.throw_error(format!(
+            "Network {} has custom bech32 human readable part {}, which is supported by the address functions only",
+            network_config.name, network_config.bech32_hrp
+        ));
+    }
+
+    Ok(network_config)
+}
+
+/// Resolve network from a network handle, network name or inline network.
+/// Objects with bech32Hrp, such as the network handles, are taken as inline
+/// network so that a call can override the registered networks, other
+/// objects are looked up by name
+#[inline]
+pub fn network_config_from_value(
+    ctx: &mut FunctionContext,
+    value: Handle<JsValue>,
+) -> NeonResult<NetworkConfig> {
+    let name = if value.is_a::<JsString>() {
+        value
+            .downcast_or_throw::<JsString, FunctionContext>(ctx)
+            .chain_neon(ctx, "Unable to downcast network name")?
+            .value()
+    } else {
+        let handle = value
+            .downcast_or_throw::<JsObject, FunctionContext>(ctx)
+            .chain_neon(ctx, "Network should be a network handle or name")?;
+        if does_js_object_has_prop(ctx, handle, "bech32Hrp")? {
+            return NetworkConfig::from_inline(ctx, handle);
+        }
+
+        handle
+            .get(ctx, "name")?
+            .downcast_or_throw::<JsString, FunctionContext>(ctx)
+            .chain_neon(ctx, "Unable to downcast name of network handle")?
+            .value()
+    };
+
+    NetworkConfig::find(&name).chain_neon(ctx, format!("Unrecognized network {}", name))
+}
+
+/// Returns whether the network of the chain hex id accepts plain transactions,
+/// which are encoded without enclave obfuscation. Mainnet and Testnet never
+/// do, devnets unless registered with plainTransactions false
+pub fn allows_plain_transactions(chain_hex_id: u8) -> bool {
+    match network_from_chain_hex_id(chain_hex_id) {
+        Network::Devnet => {}
+        Network::Mainnet | Network::Testnet => return false,
+    }
+
+    match CUSTOM_DEVNETS.read() {
+        Ok(custom_devnets) => custom_devnets
+            .iter()
+            .filter(|devnet| devnet.chain_hex_id == chain_hex_id)
+            .all(|devnet| devnet.plain_transactions),
+        Err(_) => false,
+    }
+}
+
+/// get network handle of preset or registered devnet
+/// @arguments
+/// - name: Mainnet, Testnet, Devnet or name of registered devnet
+/// @return { name, networkType, chainHexId, bech32Hrp, feeConfig,
+///     plainTransactions }
+pub fn get(mut ctx: FunctionContext) -> JsResult<JsObject> {
+    let name = ctx.argument::<JsString>(0)?.value();
+    let network_config = NetworkConfig::find(&name)
+        .chain_neon(&mut ctx, format!("Unrecognized network {}", name))?;
+
+    network_config.to_js(&mut ctx)
+}
+
+/// list network handles of presets and registered devnets
+/// @return [{ name, networkType, chainHexId, bech32Hrp, feeConfig,
+///     plainTransactions }]
+pub fn list(mut ctx: FunctionContext) -> JsResult<JsArray> {
+    let mut network_configs = PRESET_NETWORKS.clone();
+    let custom_devnets = CUSTOM_DEVNETS
+        .read()
+        .chain_neon(&mut ctx, "Unable to read registered devnets")?
+        .clone();
+    network_configs.extend(custom_devnets);
+
+    let js_network_configs = JsArray::new(&mut ctx, network_configs.len() as u32);
+    for (i, network_config) in network_configs.iter().enumerate() {
+        let handle = network_config.to_js(&mut ctx)?;
+        js_network_configs.set(&mut ctx, i as u32, handle)?;
+    }
+
+    Ok(js_network_configs)
+}
+
+/// register custom devnet
+/// @arguments
+/// - config: { name, chainHexId, bech32Hrp?, feeConfig?, plainTransactions? },
+///     feeConfig is LinearFee { constant, coefficient }, defaults to the
+///     preset fee when omitted. bech32Hrp is the custom prefix of the
+///     transfer addresses derived and validated by the address functions,
+///     dcro when omitted. Transaction building and sync reject the network
+///     handle of a devnet with a custom prefix. plainTransactions, true by
+///     default, allows the PlainTransactions features to encode transactions
+///     without enclave obfuscation, for devnets running without tx-query
+/// @return network handle of the devnet
+pub fn register_devnet(mut ctx: FunctionContext) -> JsResult<JsObject> {
+    let config = ctx.argument::<JsObject>(0)?;
+
+    let name = config
+        .get(&mut ctx, "name")?
+        .downcast_or_throw::<JsString, FunctionContext>(&mut ctx)
+        .chain_neon(&mut ctx, "Unable to downcast name")?
+        .value();
+    if name.is_empty() {
+        return ctx.throw_error("Devnet name should not be empty");
+    }
+
+    let chain_hex_id = config
+        .get(&mut ctx, "chainHexId")?
+        .downcast_or_throw::<JsBuffer, FunctionContext>(&mut ctx)
+        .chain_neon(&mut ctx, "Unable to downcast chainHexId")?;
+    let chain_hex_id = chain_hex_id.borrow(&ctx.lock()).as_slice().to_vec();
+    let chain_hex_id = chain_hex_id_from_vec(&mut ctx, chain_hex_id)?;
+    match network_from_chain_hex_id(chain_hex_id) {
+        Network::Devnet => {}
+        _ => return ctx.throw_error("Devnet chain hex id is reserved by Mainnet or Testnet"),
+    }
+
+    let mut devnet = NetworkConfig::preset(&name, Network::Devnet, chain_hex_id);
+    if does_js_object_has_prop(&mut ctx, config, "bech32Hrp")? {
+        let bech32_hrp = config
+            .get(&mut ctx, "bech32Hrp")?
+            .downcast_or_throw::<JsString, FunctionContext>(&mut ctx)
+            .chain_neon(&mut ctx, "Unable to downcast bech32Hrp")?
+            .value();
+        if !is_valid_bech32_hrp(&bech32_hrp) {
+            return ctx.throw_error(format!("Invalid bech32 human readable part {}", bech32_hrp));
+        }
+        match network_of_bech32_hrp(&bech32_hrp) {
+            None | Some(Network::Devnet) => {}
+            _ => return ctx.throw_error("Devnet bech32Hrp is reserved by Mainnet or Testnet"),
+        }
+        devnet.bech32_hrp = bech32_hrp;
+    }
+    let fee_config = config.get(&mut ctx, "feeConfig")?;
+    if !fee_config.is_a::<JsUndefined>() {
+        let fee_config = fee_config
+            .downcast_or_throw::<JsObject, FunctionContext>(&mut ctx)
+            .chain_neon(&mut ctx, "Unable to downcast feeConfig")?;
+        devnet.fee_constant = fee_config_value(&mut ctx, fee_config, "constant")?;
+        devnet.fee_coefficient = fee_config_value(&mut ctx, fee_config, "coefficient")?;
+    }
+    if does_js_object_has_prop(&mut ctx, config, "plainTransactions")? {
+        devnet.plain_transactions = config
+            .get(&mut ctx, "plainTransactions")?
+            .downcast_or_throw::<JsBoolean, FunctionContext>(&mut ctx)
+            .chain_neon(&mut ctx, "Unable to downcast plainTransactions")?
+            .value();
+    }
+
+    {
+        let mut custom_devnets = CUSTOM_DEVNETS
+            .write()
+            .chain_neon(&mut ctx, "Unable to register devnet")?;
+        let is_registered = PRESET_NETWORKS
+            .iter()
+            .chain(custom_devnets.iter())
+            .any(|network_config| network_config.name == name);
+        if is_registered {
+            return ctx.throw_error(format!("Network {} is already registered", name));
+        }
+        custom_devnets.push(devnet.clone());
+    }
+
+    devnet.to_js(&mut ctx)
+}
+
+fn fee_config_value(
+    ctx: &mut FunctionContext,
+    fee_config: Handle<JsObject>,
+    key: &str,
+) -> NeonResult<String> {
+    let value = fee_config
+        .get(ctx, key)?
+        .downcast_or_throw::<JsString, FunctionContext>(ctx)
+        .chain_neon(ctx, format!("Unable to downcast {} in feeConfig", key))?
+        .value();
+    Milli::from_str(&value).chain_neon(ctx, format!("Invalid {} config in LinearFee", key))?;
+
+    Ok(value)
+}
+
+pub fn register_network_module(ctx: &mut ModuleContext) -> NeonResult<()> {
+    let js_object = JsObject::new(ctx);
+
+    let get_fn = JsFunction::new(ctx, get)?;
+    js_object.set(ctx, "get", get_fn)?;
+
+    let list_fn = JsFunction::new(ctx, list)?;
+    js_object.set(ctx, "list", list_fn)?;
+
+    let register_devnet_fn = JsFunction::new(ctx, register_devnet)?;
+    js_object.set(ctx, "registerDevnet", register_devnet_fn)?;
+
+    ctx.export_value("network", js_object)
+}
@@ -0,0 +1,165 @@
+//! Unsigned transaction export format for cold signing. The exported JSON
+//! carries the unsigned transaction, what each signer has to sign and the
+//! human readable details of the transaction, and is accepted back together
+//! with the witnesses to finalize the transaction
+use chain_core::init::address::CroAddress;
+use chain_core::init::network::Network;
+use chain_core::tx::data::output::TxOut;
+use client_common::{Error, ErrorKind, Result};
+use neon::prelude::*;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+use crate::error::{ClientErrorNeonExt, ErrorCode};
+use crate::function_types::*;
+
+pub const UNSIGNED_TRANSACTION_VERSION: u32 = 1;
+
+/// Signing schemes of the signing payloads, named as in `keyPair.verifyBatch`
+pub const SCHNORR_SCHEME: &str = "Schnorr";
+pub const ECDSA_SCHEME: &str = "ECDSA";
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UnsignedTransaction<D> {
+    pub version: u32,
+    pub tx_type: String,
+    pub tx_id: String,
+    pub chain_hex_id: String,
+    /// Hex encoded unsigned transaction
+    pub unsigned_tx: String,
+    /// What to sign, one witness is expected for each payload in order
+    pub signing_payloads: Vec<SigningPayload>,
+    pub details: D,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SigningPayload {
+    /// Description of the signer, e.g. input index or staking address
+    pub signer: String,
+    /// Hex encoded 32 bytes message to sign
+    pub payload: String,
+    pub scheme: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportedOutput {
+    pub address: String,
+    pub value: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub valid_from: Option<u64>,
+}
+
+impl ExportedOutput {
+    pub fn from_tx_out(tx_out: &TxOut, network: Network) -> Result<ExportedOutput> {
+        let address = tx_out.address.to_cro(network).map_err(|err| {
+            Error::new(
+                ErrorKind::SerializationError,
+                format!("Unable to serialize output address: {}", err),
+            )
+        })?;
+
+        Ok(ExportedOutput {
+            address,
+            value: u64::from(tx_out.value).to_string(),
+            valid_from: tx_out.valid_from,
+        })
+    }
+}
+
+/// Serializes the unsigned transaction to JSON string
+pub fn unsigned_transaction_to_js<'a, D: Serialize>(
+    ctx: &mut FunctionContext<'a>,
+    unsigned_transaction: &UnsignedTransaction<D>,
+) -> JsResult<'a, JsString> {
+    let json = serde_json::to_string(unsigned_transaction).chain_neon_with_code(
+        ctx,
+        ErrorCode::SerializationError,
+        "Unable to serialize unsigned transaction",
+    )?;
+
+    Ok(ctx.string(json))
+}
+
+/// Returns the unsigned transaction of the exported JSON string argument.
+/// The version should be supported and the transaction type should be one of
+/// the expected types
+pub fn unsigned_transaction_argument<D: DeserializeOwned>(
+    ctx: &mut FunctionContext,
+    i: i32,
+    tx_types: &[&str],
+) -> NeonResult<UnsignedTransaction<D>> {
+    let json = typed_argument::<JsString>(ctx, i, "unsigned transaction", "JSON string")?.value();
+    let unsigned_transaction: UnsignedTransaction<D> = serde_json::from_str(&json)
+        .chain_neon_with_code(
+            ctx,
+            ErrorCode::DeserializationError,
+            "Unable to deserialize unsigned transaction",
+        )?;
+
+    if unsigned_transaction.version != UNSIGNED_TRANSACTION_VERSION {
+        return ctx.throw_error(format!(
+            "Unsupported unsigned transaction version {}",
+            unsigned_transaction.version
+        ));
+    }
+    if !tx_types.contains(&unsigned_transaction.tx_type.as_str()) {
+        return ctx.throw_error(format!(
+            "Unexpected unsigned transaction type {}",
+            unsigned_transaction.tx_type
+        ));
+    }
+
+    Ok(unsigned_transaction)
+}
+
+/// Returns the decoded bytes of the unsigned transaction
+pub fn unsigned_tx_bytes<D>(
+    ctx: &mut FunctionContext,
+    unsigned_transaction: &UnsignedTransaction<D>,
+) -> NeonResult<Vec<u8>> {
+    hex::decode(&unsigned_transaction.unsigned_tx).chain_neon_with_code(
+        ctx,
+        ErrorCode::DeserializationError,
+        "Unable to decode unsignedTx hex",
+    )
+}
+
+/// Throws when the transaction id does not match the one in the export, e.g.
+/// when the unsigned transaction has been altered
+pub fn check_tx_id<D>(
+    ctx: &mut FunctionContext,
+    unsigned_transaction: &UnsignedTransaction<D>,
+    tx_id: &[u8],
+) -> NeonResult<()> {
+    if hex::encode(tx_id) != unsigned_transaction.tx_id.to_lowercase() {
+        return ctx.throw_error("Transaction id does not match the unsigned transaction");
+    }
+
+    Ok(())
+}
+
+/// Returns the witness Buffers argument, one for each signing payload
+pub fn witnesses_argument<D>(
+    ctx: &mut FunctionContext,
+    i: i32,
+    unsigned_transaction: &UnsignedTransaction<D>,
+) -> NeonResult<Vec<Vec<u8>>> {
+    let witnesses =
+        typed_argument::<JsArray>(ctx, i, "witnesses", "array of witness Buffer")?.to_vec(ctx)?;
+    if witnesses.len() != unsigned_transaction.signing_payloads.len() {
+        return ctx.throw_error(format!(
+            "Expected {} witnesses but got {}",
+            unsigned_transaction.signing_payloads.len(),
+            witnesses.len()
+        ));
+    }
+
+    witnesses
+        .iter()
+        .enumerate()
+        .map(|(i, &witness)| parse_bytes(ctx, witness, &format!("witness {}", i)))
+        .collect()
+}
@@ -0,0 +1,125 @@
+//! Verification of transaction inclusion against the app hash of a block
+//! header. The app hash commits to the root of the Merkle tree of the
+//! transaction ids of the block together with the account, rewards pool and
+//! network parameters states, so a light integration can check the inclusion
+//! without trusting the RPC node. Tendermint commits the app hash of a block
+//! in the header of the next block, so a transaction of block H is proven
+//! against the app hash of header H+1
+use chain_core::common::{Proof, H256, HASH_SIZE_256};
+use chain_core::tx::data::TxId;
+use neon::prelude::*;
+use parity_scale_codec::Decode;
+
+use crate::error::{throw_coded_error, ClientErrorNeonExt, ErrorCode};
+use crate::function_types::*;
+
+/// Compute the app hash as chain-core compute_app_hash, the blake3 hash of
+/// the concatenated roots
+fn compute_app_hash(
+    tx_root: &H256,
+    account_root: &H256,
+    rewards_pool_hash: &H256,
+    network_params_hash: &H256,
+) -> H256 {
+    let mut preimage = Vec::with_capacity(HASH_SIZE_256 * 4);
+    preimage.extend_from_slice(tx_root);
+    preimage.extend_from_slice(account_root);
+    preimage.extend_from_slice(rewards_pool_hash);
+    preimage.extend_from_slice(network_params_hash);
+
+    blake3::hash(&preimage).into()
+}
+
+/// verify a transaction is included in the block committed by the app hash
+/// @arguments
+/// - tx_id: 32 bytes transaction id Buffer or hex string
+/// - proof: { merkleProof, accountRoot, rewardsPoolHash, networkParamsHash },
+///     merkleProof is the SCALE-encoded Merkle proof of the transaction id in
+///     the transaction tree of the block, and the others are the 32 bytes
+///     roots committed to the app hash along with the transaction tree root
+/// - app_hash: 32 bytes app hash, Buffer or hex string, of the header of the
+///     block after the one of the transaction, i.e. of header H+1 for a
+///     transaction in block H, as the app hash of a header is the state
+///     after the previous block
+/// @return boolean, whether the proof is valid for the transaction and the
+///     app hash
+pub fn verify_tx_inclusion(mut ctx: FunctionContext) -> JsResult<JsBoolean> {
+    let tx_id = h256_argument(&mut ctx, 0, "transaction id")?;
+    let proof = typed_argument::<JsObject>(&mut ctx, 1, "proof", "object")?;
+    let app_hash = h256_argument(&mut ctx, 2, "app hash")?;
+
+    let merkle_proof = proof.get(&mut ctx, "merkleProof")?;
+    let merkle_proof = parse_bytes(&mut ctx, merkle_proof, "merkleProof")?;
+    let mut merkle_proof_bytes = merkle_proof.as_slice();
+    let merkle_proof = Proof::<TxId>::decode(&mut merkle_proof_bytes).chain_neon_with_code(
+        &mut ctx,
+        ErrorCode::DeserializationError,
+        "Unable to decode merkleProof",
+    )?;
+    if !merkle_proof_bytes.is_empty() {
+        return throw_coded_error(
+            &mut ctx,
+            ErrorCode::DeserializationError,
+            "Unexpected trailing bytes after merkleProof",
+        );
+    }
+    let account_root = h256_property(&mut ctx, proof, "accountRoot")?;
+    let rewards_pool_hash = h256_property(&mut ctx, proof, "rewardsPoolHash")?;
+    let network_params_hash = h256_property(&mut ctx, proof, "networkParamsHash")?;
+
+    let tx_root = merkle_proof.root_hash();
+    let is_valid = merkle_proof.value() == &tx_id
+        && merkle_proof.verify(&tx_root)
+        && compute_app_hash(
+            &tx_root,
+            &account_root,
+            &rewards_pool_hash,
+            &network_params_hash,
+        ) == app_hash;
+
+    Ok(ctx.boolean(is_valid))
+}
+
+fn h256_argument(ctx: &mut FunctionContext, i: i32, name: &str) -> NeonResult<H256> {
+    let bytes = fixed_buffer_argument(ctx, i, name, ErrorCode::InvalidArgument, HASH_SIZE_256)?;
+
+    let mut hash: H256 = [0; HASH_SIZE_256];
+    hash.copy_from_slice(&bytes);
+
+    Ok(hash)
+}
+
+fn h256_property(
+    ctx: &mut FunctionContext,
+    object: Handle<JsObject>,
+    key: &str,
+) -> NeonResult<H256> {
+    let value = object.get(ctx, key)?;
+    let bytes = parse_bytes(ctx, value, key)?;
+    if bytes.len() != HASH_SIZE_256 {
+        return throw_coded_error(
+            ctx,
+            ErrorCode::InvalidArgument,
+            format!(
+                "{}: expected {}-byte Buffer, got {} bytes",
+                key,
+                HASH_SIZE_256,
+                bytes.len()
+            ),
+        );
+    }
+
+    let mut hash: H256 = [0; HASH_SIZE_256];
+    hash.copy_from_slice(&bytes);
+
+    Ok(hash)
+}
+
+pub fn register_proof_module(ctx: &mut ModuleContext) -> NeonResult<()> {
+    let js_object = JsObject::new(ctx);
+
+    let verify_tx_inclusion_fn = JsFunction::new(ctx, verify_tx_inclusion)?;
+    js_object.set(ctx, "verifyTxInclusion", verify_tx_inclusion_fn)?;
+
+    ctx.export_value("proof", js_object)
+}
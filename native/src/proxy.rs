@@ -0,0 +1,147 @@
+//! HTTP proxy of the network clients, e.g. an egress proxy. Plain HTTP
+//! requests are forwarded by the proxy and other connections are tunnelled
+//! with CONNECT
+use std::io::{Read, Write};
+use std::net::TcpStream;
+use std::time::Duration;
+
+use client_common::{Error, ErrorKind, Result, ResultExt};
+
+use crate::common::percent_decode;
+
+/// Upper bound of the proxy response header to CONNECT
+const MAX_CONNECT_RESPONSE_SIZE: usize = 8 * 1024;
+
+#[derive(Debug, Clone)]
+pub struct ProxyConfig {
+    /// host:port of the proxy
+    pub address: String,
+    /// Value of the Proxy-Authorization header when the proxy URL has
+    /// credentials
+    pub authorization: Option<String>,
+}
+
+impl ProxyConfig {
+    /// Parse proxy URL of the form http://[user:password@]host:port, the
+    /// percent-encoded user and password are decoded for basic
+    /// authentication
+    pub fn parse(proxy_url: &str) -> Result<ProxyConfig> {
+        let mut parts = proxy_url.splitn(2, "://");
+        let authority = match (parts.next(), parts.next()) {
+            (Some("http"), Some(rest)) => rest.split('/').next().unwrap_or_default(),
+            _ => {
+                return Err(Error::new(
+                    ErrorKind::InvalidInput,
+                    "Unsupported proxy protocol, expected http:// proxy URL",
+                ))
+            }
+        };
+
+        let (credentials, address) = match authority.rfind('@') {
+            Some(index) => (Some(&authority[..index]), &authority[index + 1..]),
+            None => (None, authority),
+        };
+        if address.is_empty() {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "Proxy URL is missing host",
+            ));
+        }
+        let address = if address.contains(':') {
+            address.to_owned()
+        } else {
+            format!("{}:80", address)
+        };
+
+        let authorization = match credentials {
+            Some(credentials) => {
+                let mut parts = credentials.splitn(2, ':');
+                let mut user_pass = decode_userinfo(parts.next().unwrap_or_default())?;
+                user_pass.push(b':');
+                user_pass.extend(decode_userinfo(parts.next().unwrap_or_default())?);
+                Some(format!("Basic {}", base64::encode(&user_pass)))
+            }
+            None => None,
+        };
+
+        Ok(ProxyConfig {
+            address,
+            authorization,
+        })
+    }
+
+    /// Open a tunnel to the target host:port through the proxy
+    pub fn connect(&self, target: &str, timeout: Duration) -> Result<TcpStream> {
+        let mut stream = TcpStream::connect(&self.address).chain(|| {
+            (
+                ErrorKind::ConnectionError,
+                format!("Unable to connect to proxy {}", self.address),
+            )
+        })?;
+        stream
+            .set_read_timeout(Some(timeout))
+            .and_then(|_| stream.set_write_timeout(Some(timeout)))
+            .chain(|| {
+                (
+                    ErrorKind::ConnectionError,
+                    "Unable to set proxy connection timeout",
+                )
+            })?;
+
+        let mut request = format!("CONNECT {0} HTTP/1.1\r\nHost: {0}\r\n", target);
+        if let Some(authorization) = &self.authorization {
+            request.push_str(&format!("Proxy-Authorization: {}\r\n", authorization));
+        }
+        request.push_str("\r\n");
+        stream.write_all(request.as_bytes()).chain(|| {
+            (
+                ErrorKind::ConnectionError,
+                "Unable to send CONNECT request to proxy",
+            )
+        })?;
+
+        // Read byte by byte so that nothing after the response header, which
+        // belongs to the tunnelled connection, is consumed
+        let mut response = Vec::new();
+        let mut byte = [0u8; 1];
+        while !response.ends_with(b"\r\n\r\n") {
+            if response.len() >= MAX_CONNECT_RESPONSE_SIZE {
+                return Err(Error::new(
+                    ErrorKind::ConnectionError,
+                    "Proxy response to CONNECT is too large",
+                ));
+            }
+            stream.read_exact(&mut byte).chain(|| {
+                (
+                    ErrorKind::ConnectionError,
+                    "Unable to read proxy response to CONNECT",
+                )
+            })?;
+            response.push(byte[0]);
+        }
+
+        let status_line = String::from_utf8_lossy(&response)
+            .lines()
+            .next()
+            .unwrap_or_default()
+            .to_owned();
+        if status_line.split_whitespace().nth(1) != Some("200") {
+            return Err(Error::new(
+                ErrorKind::ConnectionError,
+                format!("Proxy refuses to connect to {}: {}", target, status_line),
+            ));
+        }
+
+        Ok(stream)
+    }
+}
+
+/// Decode the %XX escapes of the URL userinfo component
+fn decode_userinfo(component: &str) -> Result<Vec<u8>> {
+    percent_decode(component).ok_or_else(|| {
+        Error::new(
+            ErrorKind::InvalidInput,
+            "Proxy URL credentials have an invalid percent-encoding",
+        )
+    })
+}
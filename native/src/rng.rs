@@ -0,0 +1,201 @@
+//! Source of randomness of the keys and nonces generated by nodelib. It is
+//! always the OS RNG unless built with the `deterministic-rng` feature and
+//! `unsafeEnableDeterministicRng` is called, which is meant for reproducible
+//! tests only and must never be enabled with real funds. Key generation can
+//! mix caller-provided entropy, e.g. from a hardware RNG, into the bytes
+//! drawn from it
+#[cfg(feature = "deterministic-rng")]
+use std::sync::Mutex;
+
+use client_common::{Error, ErrorKind, PrivateKey, Result};
+#[cfg(feature = "deterministic-rng")]
+use lazy_static::lazy_static;
+use neon::prelude::*;
+use rand::rngs::{OsRng, StdRng};
+use rand::{RngCore, SeedableRng};
+use zeroize::Zeroizing;
+
+#[cfg(feature = "deterministic-rng")]
+use crate::error::ClientErrorNeonExt;
+use crate::error::{throw_coded_error, ErrorCode};
+use crate::function_types::*;
+
+/// BLAKE3 key derivation context of the entropy mixing, the derived bytes are
+/// domain separated from any other use of the input material
+const ENTROPY_MIXING_CONTEXT: &str = "crypto-com/chain-nodelib 2020 entropy mixing";
+/// Bytes drawn from the RNG for every mixing
+const RNG_ENTROPY_LENGTH: usize = 32;
+/// Caller-provided entropy shorter than this is most likely a mistake
+const MIN_EXTERNAL_ENTROPY_LENGTH: usize = 16;
+
+#[cfg(feature = "deterministic-rng")]
+lazy_static! {
+    static ref DETERMINISTIC_RNG: Mutex<Option<StdRng>> = Mutex::new(None);
+}
+
+/// RNG seeded from the RNG of nodelib, to be passed to the libraries taking an
+/// RNG
+pub fn seeded_rng() -> Result<StdRng> {
+    let mut seed = Zeroizing::new([0u8; RNG_ENTROPY_LENGTH]);
+    fill_random_bytes(&mut seed[..])?;
+
+    Ok(StdRng::from_seed(*seed))
+}
+
+/// Fill the buffer with random bytes
+#[cfg(not(feature = "deterministic-rng"))]
+#[inline]
+pub fn fill_random_bytes(dest: &mut [u8]) -> Result<()> {
+    OsRng.try_fill_bytes(dest).map_err(rng_error)
+}
+
+/// Fill the buffer with random bytes, from the seeded RNG when enabled. A
+/// poisoned lock is an error rather than a fallback to either source
+#[cfg(feature = "deterministic-rng")]
+pub fn fill_random_bytes(dest: &mut [u8]) -> Result<()> {
+    let mut rng = DETERMINISTIC_RNG.lock().map_err(|_| {
+        Error::new(
+            ErrorKind::InternalError,
+            "Deterministic RNG lock is poisoned",
+        )
+    })?;
+    match rng.as_mut() {
+        Some(rng) => rng.try_fill_bytes(dest),
+        None => OsRng.try_fill_bytes(dest),
+    }
+    .map_err(rng_error)
+}
+
+fn rng_error(err: rand::Error) -> Error {
+    Error::new(
+        ErrorKind::InternalError,
+        format!("Unable to draw random bytes: {}", err),
+    )
+}
+
+/// Fill the buffer with random bytes, mixed with the caller-provided
+/// entropy when there is some. The bytes drawn from the RNG and the external
+/// entropy are the key material of the BLAKE3 key derivation, so the output
+/// is at least as unpredictable as either source
+pub fn fill_random_bytes_with_entropy(
+    dest: &mut [u8],
+    external_entropy: Option<&[u8]>,
+) -> Result<()> {
+    let external_entropy = match external_entropy {
+        Some(external_entropy) => external_entropy,
+        None => return fill_random_bytes(dest),
+    };
+
+    let mut rng_entropy = Zeroizing::new([0u8; RNG_ENTROPY_LENGTH]);
+    fill_random_bytes(&mut rng_entropy[..])?;
+
+    let mut hasher = blake3::Hasher::new_derive_key(ENTROPY_MIXING_CONTEXT);
+    hasher.update(&rng_entropy[..]);
+    hasher.update(&(external_entropy.len() as u64).to_le_bytes());
+    hasher.update(external_entropy);
+    hasher.finalize_xof().fill(dest);
+
+    Ok(())
+}
+
+/// Generate a new private key, mixed with the caller-provided entropy when
+/// there is some
+pub fn random_private_key(external_entropy: Option<&[u8]>) -> Result<PrivateKey> {
+    loop {
+        let mut bytes = Zeroizing::new([0u8; 32]);
+        fill_random_bytes_with_entropy(&mut bytes[..], external_entropy)?;
+        // Out of range bytes are astronomically rare, try again with new ones
+        if let Ok(private_key) = PrivateKey::deserialize_from(&bytes[..]) {
+            return Ok(private_key);
+        }
+    }
+}
+
+/// Returns the optional caller-provided entropy argument, a Buffer or hex
+/// string of at least 16 bytes
+pub fn entropy_argument(
+    ctx: &mut FunctionContext,
+    i: i32,
+) -> NeonResult<Option<Zeroizing<Vec<u8>>>> {
+    let entropy = match ctx.argument_opt(i) {
+        Some(entropy) if !entropy.is_a::<JsUndefined>() && !entropy.is_a::<JsNull>() => {
+            Zeroizing::new(bytes_argument(ctx, i, "entropy", "Buffer or hex string")?)
+        }
+        _ => return Ok(None),
+    };
+    if entropy.len() < MIN_EXTERNAL_ENTROPY_LENGTH {
+        return throw_coded_error(
+            ctx,
+            ErrorCode::InvalidArgument,
+            format!(
+                "entropy should be at least {} bytes long",
+                MIN_EXTERNAL_ENTROPY_LENGTH
+            ),
+        );
+    }
+
+    Ok(Some(entropy))
+}
+
+/// UNSAFE, for tests only. Seed the RNG of nodelib so that the generated keys,
+/// handle and session ids are reproducible. The MuSig nonces are derived by
+/// client-core from the session id it draws itself and are not covered
+/// @arguments
+/// - seed: 32 bytes Buffer or hex string, or a safe integer
+#[cfg(feature = "deterministic-rng")]
+pub fn unsafe_enable_deterministic_rng(mut ctx: FunctionContext) -> JsResult<JsUndefined> {
+    let seed = ctx.argument::<JsValue>(0)?;
+    let rng = if seed.is_a::<JsNumber>() {
+        let seed = ctx.argument::<JsNumber>(0)?.value();
+        if seed < 0.0 || seed.fract() != 0.0 || seed > MAX_SAFE_INTEGER {
+            return ctx.throw_error("Seed should be a non-negative safe integer");
+        }
+        StdRng::seed_from_u64(seed as u64)
+    } else {
+        let mut seed_bytes = [0u8; 32];
+        seed_bytes.copy_from_slice(&fixed_buffer_argument(
+            &mut ctx,
+            0,
+            "seed",
+            ErrorCode::InvalidArgument,
+            32,
+        )?);
+        StdRng::from_seed(seed_bytes)
+    };
+
+    *DETERMINISTIC_RNG
+        .lock()
+        .chain_neon(&mut ctx, "Unable to acquire deterministic RNG lock")? = Some(rng);
+
+    Ok(ctx.undefined())
+}
+
+/// switch the RNG of nodelib back to the OS RNG
+#[cfg(feature = "deterministic-rng")]
+pub fn unsafe_disable_deterministic_rng(mut ctx: FunctionContext) -> JsResult<JsUndefined> {
+    *DETERMINISTIC_RNG
+        .lock()
+        .chain_neon(&mut ctx, "Unable to acquire deterministic RNG lock")? = None;
+
+    Ok(ctx.undefined())
+}
+
+/// Register the deterministic RNG functions, which only exist when built with
+/// the `deterministic-rng` feature
+pub fn register_rng_module(ctx: &mut ModuleContext) -> NeonResult<()> {
+    #[cfg(feature = "deterministic-rng")]
+    {
+        ctx.export_function(
+            "unsafeEnableDeterministicRng",
+            unsafe_enable_deterministic_rng,
+        )?;
+        ctx.export_function(
+            "unsafeDisableDeterministicRng",
+            unsafe_disable_deterministic_rng,
+        )?;
+    }
+    #[cfg(not(feature = "deterministic-rng"))]
+    let _ = ctx;
+
+    Ok(())
+}
@@ -0,0 +1,496 @@
+//! Minimal synchronous Tendermint JSON-RPC client over HTTP, or HTTPS with
+//! the server certificate verified against the webpki root certificates
+use std::io::{self, Read, Write};
+use std::net::TcpStream;
+use std::sync::Arc;
+use std::time::Duration;
+
+use client_common::{Error, ErrorKind, Result, ResultExt};
+use lazy_static::lazy_static;
+use rustls::{ClientSession, StreamOwned};
+use serde_json::{json, Value};
+use webpki::DNSNameRef;
+
+use crate::client_config::ClientConfig;
+use crate::error::{coded_client_error, ErrorCode};
+use crate::metrics;
+
+/// Default timeout of a single RPC call. `broadcast_tx_commit` waits for the
+/// transaction to be included in a block so this has to cover a few blocks
+const RPC_TIMEOUT: Duration = Duration::from_secs(60);
+const RPC_REQUEST_ID: &str = "chain-nodelib";
+const RPC_TIMEOUT_MESSAGE: &str = "Tendermint RPC request timed out";
+/// Maximum page size of the Tendermint `validators` RPC
+const VALIDATORS_PER_PAGE: usize = 100;
+
+lazy_static! {
+    /// TLS configuration of the https endpoints, shared by all the clients
+    static ref TLS_CONFIG: Arc<rustls::ClientConfig> = {
+        let mut tls_config = rustls::ClientConfig::new();
+        tls_config
+            .root_store
+            .add_server_trust_anchors(&webpki_roots::TLS_SERVER_ROOTS);
+        Arc::new(tls_config)
+    };
+}
+
+#[derive(Debug, Clone)]
+pub struct TendermintRpcClient {
+    /// host:port of the endpoint
+    address: String,
+    path: String,
+    /// Whether the endpoint is https
+    tls: bool,
+    config: ClientConfig,
+}
+
+/// Connection to the Tendermint RPC, TLS session of https endpoints
+enum RpcStream {
+    Plain(TcpStream),
+    Tls(Box<StreamOwned<ClientSession, TcpStream>>),
+}
+
+impl Read for RpcStream {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        match self {
+            RpcStream::Plain(stream) => stream.read(buf),
+            RpcStream::Tls(stream) => stream.read(buf),
+        }
+    }
+}
+
+impl Write for RpcStream {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match self {
+            RpcStream::Plain(stream) => stream.write(buf),
+            RpcStream::Tls(stream) => stream.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match self {
+            RpcStream::Plain(stream) => stream.flush(),
+            RpcStream::Tls(stream) => stream.flush(),
+        }
+    }
+}
+
+impl TendermintRpcClient {
+    /// Create a client of the Tendermint RPC endpoint, e.g. http://127.0.0.1:26657
+    /// or https://rpc.example.com, with the configuration of the endpoint. The
+    /// port defaults to 80 for http and 443 for https endpoints
+    pub fn new(endpoint: &str) -> Result<Self> {
+        let config = ClientConfig::of(endpoint);
+        let mut parts = endpoint.splitn(2, "://");
+        let (endpoint, tls, default_port) = match (parts.next(), parts.next()) {
+            (Some("http"), Some(endpoint)) => (endpoint, false, Some(80)),
+            (Some("https"), Some(endpoint)) => (endpoint, true, Some(443)),
+            (Some("tcp"), Some(endpoint)) => (endpoint, false, None),
+            _ => {
+                return Err(Error::new(
+                    ErrorKind::InvalidInput,
+                    "Unsupported Tendermint RPC protocol, expected http://, https:// or tcp:// endpoint",
+                ))
+            }
+        };
+
+        let (address, path) = match endpoint.find('/') {
+            Some(index) => (&endpoint[..index], &endpoint[index..]),
+            None => (endpoint, "/"),
+        };
+        if address.is_empty() {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "Tendermint RPC endpoint is missing host",
+            ));
+        }
+
+        let address = match default_port {
+            Some(port) if !address.contains(':') => format!("{}:{}", address, port),
+            _ => address.to_owned(),
+        };
+
+        Ok(TendermintRpcClient {
+            address,
+            path: path.to_owned(),
+            tls,
+            config,
+        })
+    }
+
+    /// Broadcast transaction and wait for CheckTx result
+    pub fn broadcast_tx_sync(&self, tx: &[u8]) -> Result<Value> {
+        self.call("broadcast_tx_sync", json!({ "tx": base64::encode(tx) }))
+    }
+
+    /// Broadcast transaction and wait for it to be committed in a block
+    pub fn broadcast_tx_commit(&self, tx: &[u8]) -> Result<Value> {
+        self.call("broadcast_tx_commit", json!({ "tx": base64::encode(tx) }))
+    }
+
+    /// Query the ABCI application
+    pub fn abci_query(
+        &self,
+        path: &str,
+        data: &[u8],
+        height: Option<u64>,
+        prove: bool,
+    ) -> Result<Value> {
+        self.call("abci_query", abci_query_params(path, data, height, prove))
+    }
+
+    /// Query the ABCI application with the queries of (path, data, height,
+    /// prove) in a single batch request
+    pub fn abci_query_batch(
+        &self,
+        queries: &[(&str, &[u8], Option<u64>, bool)],
+    ) -> Result<Vec<Value>> {
+        let calls = queries
+            .iter()
+            .map(|&(path, data, height, prove)| {
+                ("abci_query", abci_query_params(path, data, height, prove))
+            })
+            .collect::<Vec<(&str, Value)>>();
+
+        self.call_batch(&calls)
+    }
+
+    /// Returns the node status, including the latest block height
+    pub fn status(&self) -> Result<Value> {
+        self.call("status", json!({}))
+    }
+
+    /// Returns the block at the given height
+    pub fn block(&self, height: u64) -> Result<Value> {
+        self.call("block", json!({ "height": height.to_string() }))
+    }
+
+    /// Returns the results of the transactions in the block at the given height
+    pub fn block_results(&self, height: u64) -> Result<Value> {
+        self.call("block_results", json!({ "height": height.to_string() }))
+    }
+
+    /// Search the committed transactions matching the query, e.g.
+    /// "valid_txs.txid='<tx id>'", returns the page of { txs, total_count }
+    pub fn tx_search(&self, query: &str, page: usize, per_page: usize) -> Result<Value> {
+        self.call(
+            "tx_search",
+            json!({
+                "query": query,
+                "prove": false,
+                "page": page.to_string(),
+                "per_page": per_page.to_string(),
+            }),
+        )
+    }
+
+    /// Returns the signed header, i.e. the header and its commit, at the given
+    /// height
+    pub fn commit(&self, height: u64) -> Result<Value> {
+        self.call("commit", json!({ "height": height.to_string() }))
+    }
+
+    /// Returns the validator set at the given height. Tendermint paginates
+    /// the validators so every page is fetched
+    pub fn validators(&self, height: u64) -> Result<Vec<Value>> {
+        let mut validators = Vec::new();
+        for page in 1.. {
+            let mut result = self.call(
+                "validators",
+                json!({
+                    "height": height.to_string(),
+                    "page": page.to_string(),
+                    "per_page": VALIDATORS_PER_PAGE.to_string(),
+                }),
+            )?;
+            let page_validators = match result.get_mut("validators").map(Value::take) {
+                Some(Value::Array(page_validators)) => page_validators,
+                _ => {
+                    return Err(Error::new(
+                        ErrorKind::DeserializationError,
+                        "validators of Tendermint RPC validators result should be an array",
+                    ))
+                }
+            };
+            let is_last_page = page_validators.len() < VALIDATORS_PER_PAGE;
+            validators.extend(page_validators);
+
+            let total = result
+                .get("total")
+                .and_then(Value::as_str)
+                .and_then(|total| total.parse::<usize>().ok());
+            if is_last_page || total.map_or(false, |total| validators.len() >= total) {
+                break;
+            }
+        }
+
+        Ok(validators)
+    }
+
+    /// Make a JSON-RPC call and returns its result
+    pub fn call(&self, method: &str, params: Value) -> Result<Value> {
+        let request = json!({
+            "jsonrpc": "2.0",
+            "id": RPC_REQUEST_ID,
+            "method": method,
+            "params": params,
+        });
+        metrics::measure(&format!("rpc.{}", method), || {
+            let response = self.send(&request, !is_broadcast(method))?;
+
+            take_result(response, method)
+        })
+    }
+
+    /// Make the JSON-RPC calls in a single batch request, returns their
+    /// results in the order of the calls. Fails when any of the calls fails
+    pub fn call_batch(&self, calls: &[(&str, Value)]) -> Result<Vec<Value>> {
+        if calls.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let request = calls
+            .iter()
+            .enumerate()
+            .map(|(i, (method, params))| {
+                json!({
+                    "jsonrpc": "2.0",
+                    "id": i,
+                    "method": method,
+                    "params": params,
+                })
+            })
+            .collect::<Vec<Value>>();
+        let request = Value::Array(request);
+        let is_idempotent = calls.iter().all(|(method, _)| !is_broadcast(method));
+        let response = metrics::measure("rpc.batch", || self.send(&request, is_idempotent))?;
+        let responses = match response {
+            Value::Array(responses) => responses,
+            // Tendermint responds with a single error when the batch itself
+            // is rejected
+            response => {
+                take_result(response, "batch")?;
+                return Err(Error::new(
+                    ErrorKind::DeserializationError,
+                    "Tendermint RPC batch response should be an array",
+                ));
+            }
+        };
+
+        // Calls are identified by their index as the responses of a batch
+        // may be in any order
+        let mut results: Vec<Option<Value>> = vec![None; calls.len()];
+        for response in responses {
+            let index = response
+                .get("id")
+                .and_then(Value::as_u64)
+                .map(|id| id as usize)
+                .filter(|&id| id < calls.len())
+                .chain(|| {
+                    (
+                        ErrorKind::DeserializationError,
+                        "Tendermint RPC batch response has unknown id",
+                    )
+                })?;
+            results[index] = Some(take_result(response, calls[index].0)?);
+        }
+
+        results
+            .into_iter()
+            .enumerate()
+            .map(|(i, result)| {
+                result.chain(|| {
+                    (
+                        ErrorKind::DeserializationError,
+                        format!("Tendermint RPC batch response is missing call {}", i),
+                    )
+                })
+            })
+            .collect()
+    }
+
+    /// Send the request with the retries of the endpoint. Requests which
+    /// are not idempotent, i.e. broadcasts, may have reached the node when
+    /// the response fails or times out, so only the connection is retried
+    fn send(&self, request: &Value, is_idempotent: bool) -> Result<Value> {
+        if is_idempotent {
+            self.config
+                .retry(|| self.post_json(self.connect()?, request))
+        } else {
+            let stream = self.config.retry(|| self.connect())?;
+            self.post_json(stream, request)
+        }
+    }
+
+    /// Connect to the endpoint, through the proxy when configured. The TLS
+    /// session of https endpoints runs in the CONNECT tunnel of the proxy
+    fn connect(&self) -> Result<RpcStream> {
+        let timeout = self.config.timeout.unwrap_or(RPC_TIMEOUT);
+        let stream = match &self.config.proxy {
+            Some(proxy) if self.tls => proxy.connect(&self.address, timeout)?,
+            proxy => {
+                let connect_address = match proxy {
+                    Some(proxy) => proxy.address.as_str(),
+                    None => self.address.as_str(),
+                };
+                TcpStream::connect(connect_address).chain(|| {
+                    (
+                        ErrorKind::ConnectionError,
+                        format!("Unable to connect to Tendermint RPC {}", connect_address),
+                    )
+                })?
+            }
+        };
+        stream
+            .set_read_timeout(Some(timeout))
+            .and_then(|_| stream.set_write_timeout(Some(timeout)))
+            .chain(|| {
+                (
+                    ErrorKind::ConnectionError,
+                    "Unable to set Tendermint RPC connection timeout",
+                )
+            })?;
+        if !self.tls {
+            return Ok(RpcStream::Plain(stream));
+        }
+
+        let host = self.address.rsplitn(2, ':').last().unwrap_or_default();
+        let dns_name = DNSNameRef::try_from_ascii_str(host).chain(|| {
+            (
+                ErrorKind::InvalidInput,
+                "Tendermint RPC https endpoint host should be a DNS name",
+            )
+        })?;
+        let session = ClientSession::new(&TLS_CONFIG, dns_name);
+
+        Ok(RpcStream::Tls(Box::new(StreamOwned::new(session, stream))))
+    }
+
+    fn post_json(&self, stream: RpcStream, request: &Value) -> Result<Value> {
+        let request = serde_json::to_vec(request).chain(|| {
+            (
+                ErrorKind::SerializationError,
+                "Unable to serialize Tendermint RPC request",
+            )
+        })?;
+
+        let (status_line, response) = self.post(stream, &request)?;
+        match serde_json::from_slice(&response) {
+            Ok(response) => Ok(response),
+            Err(_) if !status_line.contains(" 200") => {
+                return Err(Error::new(
+                    ErrorKind::ConnectionError,
+                    format!("Tendermint RPC responds with {}", status_line),
+                ))
+            }
+            Err(err) => Err(Error::new(
+                ErrorKind::DeserializationError,
+                format!("Unable to deserialize Tendermint RPC response: {}", err),
+            )),
+        }
+    }
+
+    /// Send HTTP/1.0 POST request so that the response body is delimited by
+    /// connection close instead of chunked encoding. Returns the status line
+    /// and body of the response. Through a proxy, the request target of http
+    /// endpoints is the absolute URL for the proxy to forward the request
+    fn post(&self, mut stream: RpcStream, body: &[u8]) -> Result<(String, Vec<u8>)> {
+        let is_forwarded = self.config.proxy.is_some() && !self.tls;
+        let request_target = if is_forwarded {
+            format!("http://{}{}", self.address, self.path)
+        } else {
+            self.path.clone()
+        };
+
+        let mut header = format!(
+            "POST {} HTTP/1.0\r\nHost: {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\n",
+            request_target,
+            self.address,
+            body.len()
+        );
+        if let Some(authorization) = self
+            .config
+            .proxy
+            .as_ref()
+            .filter(|_| is_forwarded)
+            .and_then(|proxy| proxy.authorization.as_ref())
+        {
+            header.push_str(&format!("Proxy-Authorization: {}\r\n", authorization));
+        }
+        header.push_str("\r\n");
+        let mut response = Vec::new();
+        let result = stream
+            .write_all(header.as_bytes())
+            .and_then(|_| stream.write_all(body))
+            .and_then(|_| stream.read_to_end(&mut response));
+        match result {
+            Err(ref err)
+                if err.kind() == io::ErrorKind::WouldBlock
+                    || err.kind() == io::ErrorKind::TimedOut =>
+            {
+                return Err(coded_client_error(
+                    ErrorKind::ConnectionError,
+                    ErrorCode::RpcTimeout,
+                    RPC_TIMEOUT_MESSAGE,
+                ))
+            }
+            result => {
+                result.chain(|| {
+                    (
+                        ErrorKind::ConnectionError,
+                        "Unable to send request to Tendermint RPC",
+                    )
+                })?;
+            }
+        }
+
+        let header_end = response
+            .windows(4)
+            .position(|window| window == b"\r\n\r\n")
+            .chain(|| {
+                (
+                    ErrorKind::DeserializationError,
+                    "Malformed Tendermint RPC HTTP response",
+                )
+            })?;
+        let status_line = String::from_utf8_lossy(&response[..header_end])
+            .lines()
+            .next()
+            .unwrap_or_default()
+            .to_owned();
+
+        Ok((status_line, response.split_off(header_end + 4)))
+    }
+}
+
+/// Whether the RPC method broadcasts a transaction
+fn is_broadcast(method: &str) -> bool {
+    method.starts_with("broadcast_tx")
+}
+
+fn abci_query_params(path: &str, data: &[u8], height: Option<u64>, prove: bool) -> Value {
+    json!({
+        "path": path,
+        "data": hex::encode(data),
+        "height": height.unwrap_or(0).to_string(),
+        "prove": prove,
+    })
+}
+
+/// Returns the result of the JSON-RPC response of the method
+fn take_result(mut response: Value, method: &str) -> Result<Value> {
+    if let Some(error) = response.get("error") {
+        return Err(Error::new(
+            ErrorKind::TendermintRpcError,
+            format!("Tendermint RPC {} returns error: {}", method, error),
+        ));
+    }
+
+    match response.get_mut("result") {
+        Some(result) => Ok(result.take()),
+        None => Err(Error::new(
+            ErrorKind::DeserializationError,
+            "Tendermint RPC response is missing result",
+        )),
+    }
+}
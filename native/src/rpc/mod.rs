@@ -0,0 +1,519 @@
+mod client;
+mod subscription;
+mod tx_status;
+
+use std::time::Duration;
+
+use chain_core::tx::data::TxId;
+use neon::prelude::*;
+use serde_json::Value;
+
+use crate::cancellation::CancellationToken;
+use crate::client_config::configure_client;
+use crate::common::json_to_js;
+use crate::error::{ClientErrorNeonExt, ErrorCode, NativeError};
+use crate::function_types::*;
+
+pub use client::TendermintRpcClient;
+use subscription::subscribe_new_blocks;
+use tx_status::{
+    tx_status, wait_for_confirmation, DEFAULT_CONFIRMATIONS, DEFAULT_CONFIRMATION_TIMEOUT,
+    DEFAULT_POLL_INTERVAL,
+};
+
+/// broadcast transaction and return its CheckTx result
+/// @arguments
+/// - tendermint_rpc_url: Tendermint RPC endpoint, e.g. http://127.0.0.1:26657
+/// - tx_aux: broadcast-able transaction Buffer
+/// - callback: node-style callback, the call runs on background thread and
+///     passes the result to callback, the task handle { id, cancel() } is
+///     returned
+/// @return { code, data, log, hash }
+pub fn broadcast_tx_sync(mut ctx: FunctionContext) -> JsResult<JsValue> {
+    let client = rpc_client_argument(&mut ctx, 0)?;
+    let tx_aux = u8_buffer_argument(&mut ctx, 1)?;
+    let callback = required_callback_argument(&mut ctx)?;
+
+    RpcTask {
+        client,
+        request: RpcRequest::BroadcastTxSync(tx_aux),
+        cancellation: CancellationToken::default(),
+    }
+    .run(&mut ctx, callback)
+}
+
+/// broadcast transaction and wait for it to be committed in a block
+/// @arguments
+/// - tendermint_rpc_url: Tendermint RPC endpoint, e.g. http://127.0.0.1:26657
+/// - tx_aux: broadcast-able transaction Buffer
+/// - callback: node-style callback, the call runs on background thread and
+///     passes the result to callback, the task handle { id, cancel() } is
+///     returned
+/// @return { check_tx, deliver_tx, hash, height }
+pub fn broadcast_tx_commit(mut ctx: FunctionContext) -> JsResult<JsValue> {
+    let client = rpc_client_argument(&mut ctx, 0)?;
+    let tx_aux = u8_buffer_argument(&mut ctx, 1)?;
+    let callback = required_callback_argument(&mut ctx)?;
+
+    RpcTask {
+        client,
+        request: RpcRequest::BroadcastTxCommit(tx_aux),
+        cancellation: CancellationToken::default(),
+    }
+    .run(&mut ctx, callback)
+}
+
+/// query the ABCI application
+/// @arguments
+/// - tendermint_rpc_url: Tendermint RPC endpoint, e.g. http://127.0.0.1:26657
+/// - path: query path, e.g. "account"
+/// - data: query data Buffer
+/// - height: (optional) block height to query at, latest when omitted
+/// - prove: (optional) whether to include proof, default false
+/// - callback: node-style callback, the call runs on background thread and
+///     passes the result to callback, the task handle { id, cancel() } is
+///     returned
+/// @return ABCI query response with key and value decoded to Buffer
+pub fn abci_query(mut ctx: FunctionContext) -> JsResult<JsValue> {
+    let client = rpc_client_argument(&mut ctx, 0)?;
+    let path = ctx.argument::<JsString>(1)?.value();
+    let data = u8_buffer_argument(&mut ctx, 2)?;
+    let callback = required_callback_argument(&mut ctx)?;
+    let optional_args_len = ctx.len() - 1;
+
+    let height = match ctx.argument_opt(3) {
+        Some(height) if optional_args_len > 3 && height.is_a::<JsNumber>() => {
+            let height = ctx.argument::<JsNumber>(3)?.value();
+            if height < 0.0 || height.fract() != 0.0 {
+                return ctx.throw_error("Query height should be a non-negative integer");
+            }
+            Some(height as u64)
+        }
+        Some(height)
+            if optional_args_len > 3
+                && !height.is_a::<JsUndefined>()
+                && !height.is_a::<JsNull>() =>
+        {
+            return ctx.throw_error("Query height should be a number");
+        }
+        _ => None,
+    };
+    let prove = match ctx.argument_opt(4) {
+        Some(prove) if optional_args_len > 4 && prove.is_a::<JsBoolean>() => {
+            ctx.argument::<JsBoolean>(4)?.value()
+        }
+        _ => false,
+    };
+
+    RpcTask {
+        client,
+        request: RpcRequest::AbciQuery(AbciQuery {
+            path,
+            data,
+            height,
+            prove,
+        }),
+        cancellation: CancellationToken::default(),
+    }
+    .run(&mut ctx, callback)
+}
+
+/// query the ABCI application with multiple queries in a single HTTP
+/// request, e.g. the staked states of many addresses
+/// @arguments
+/// - tendermint_rpc_url: Tendermint RPC endpoint, e.g. http://127.0.0.1:26657
+/// - queries: [{ path, data, height?, prove? }], data is query data Buffer
+/// - callback: node-style callback, the call runs on background thread and
+///     passes the result to callback, the task handle { id, cancel() } is
+///     returned
+/// @return ABCI query responses in the order of the queries, with key and
+///     value decoded to Buffer
+pub fn abci_query_batch(mut ctx: FunctionContext) -> JsResult<JsValue> {
+    let client = rpc_client_argument(&mut ctx, 0)?;
+    let queries = ctx.argument::<JsArray>(1)?.to_vec(&mut ctx)?;
+    let callback = required_callback_argument(&mut ctx)?;
+
+    let queries = queries
+        .into_iter()
+        .enumerate()
+        .map(|(i, query)| {
+            let query = query
+                .downcast_or_throw::<JsObject, FunctionContext>(&mut ctx)
+                .chain_neon(&mut ctx, format!("Unable to downcast query {}", i))?;
+            parse_abci_query(&mut ctx, query, i)
+        })
+        .collect::<NeonResult<Vec<AbciQuery>>>()?;
+
+    RpcTask {
+        client,
+        request: RpcRequest::AbciQueryBatch(queries),
+        cancellation: CancellationToken::default(),
+    }
+    .run(&mut ctx, callback)
+}
+
+/// returns the status of the committed transaction
+/// @arguments
+/// - tendermint_rpc_url: Tendermint RPC endpoint, e.g. http://127.0.0.1:26657
+/// - tx_id: transaction id hex string, or Tendermint hash of the transaction,
+///     i.e. the hash returned by broadcastTxSync. Failed transactions are only
+///     found by their Tendermint hash
+/// - callback: node-style callback, the call runs on background thread and
+///     passes the result to callback, the task handle { id, cancel() } is
+///     returned
+/// @return { hash, height, index, code, log, confirmations } or null when the
+///     transaction is not committed yet. code is the DeliverTx result code, 0
+///     when the transaction is valid
+pub fn get_tx_status(mut ctx: FunctionContext) -> JsResult<JsValue> {
+    let client = rpc_client_argument(&mut ctx, 0)?;
+    let tx_id = ctx.argument::<JsString>(1)?.value();
+    let tx_id = txid_from_str(&mut ctx, &tx_id)?;
+    let callback = required_callback_argument(&mut ctx)?;
+
+    RpcTask {
+        client,
+        request: RpcRequest::TxStatus(tx_id),
+        cancellation: CancellationToken::default(),
+    }
+    .run(&mut ctx, callback)
+}
+
+/// wait for the transaction to be committed and confirmed by the blocks
+/// @arguments
+/// - tendermint_rpc_url: Tendermint RPC endpoint, e.g. http://127.0.0.1:26657
+/// - tx_id: transaction id hex string, or Tendermint hash of the transaction
+/// - options: (optional)
+///     - confirmations: (optional) number of blocks committed since the
+///         transaction, counting its block, default 1
+///     - timeoutMs: (optional) default 60000, fails with RPC_TIMEOUT once
+///         elapsed
+///     - pollIntervalMs: (optional) interval between the status queries,
+///         default 1000
+/// - callback: node-style callback, the call runs on background thread and
+///     passes the result to callback, the task handle { id, cancel() } is
+///     returned
+/// @return status of `getTxStatus`. Failed transactions are returned as soon
+///     as committed, check the code before crediting the payment
+pub fn wait_for_tx_confirmation(mut ctx: FunctionContext) -> JsResult<JsValue> {
+    let client = rpc_client_argument(&mut ctx, 0)?;
+    let tx_id = ctx.argument::<JsString>(1)?.value();
+    let tx_id = txid_from_str(&mut ctx, &tx_id)?;
+    let callback = required_callback_argument(&mut ctx)?;
+
+    let mut request = ConfirmationRequest {
+        tx_id,
+        confirmations: DEFAULT_CONFIRMATIONS,
+        timeout: DEFAULT_CONFIRMATION_TIMEOUT,
+        poll_interval: DEFAULT_POLL_INTERVAL,
+    };
+    match ctx.argument_opt(2) {
+        Some(options) if options.is_a::<JsObject>() && !options.is_a::<JsFunction>() => {
+            let options = ctx.argument::<JsObject>(2)?;
+            if let Some(confirmations) =
+                optional_integer_option(&mut ctx, options, "confirmations")?
+            {
+                if confirmations == 0 {
+                    return ctx.throw_error("confirmations should be at least 1");
+                }
+                request.confirmations = confirmations;
+            }
+            if let Some(timeout) = optional_integer_option(&mut ctx, options, "timeoutMs")? {
+                request.timeout = Duration::from_millis(timeout);
+            }
+            if let Some(poll_interval) =
+                optional_integer_option(&mut ctx, options, "pollIntervalMs")?
+            {
+                request.poll_interval = Duration::from_millis(poll_interval);
+            }
+        }
+        _ => (),
+    }
+
+    RpcTask {
+        client,
+        request: RpcRequest::WaitForConfirmation(request),
+        cancellation: CancellationToken::default(),
+    }
+    .run(&mut ctx, callback)
+}
+
+fn parse_abci_query(
+    ctx: &mut FunctionContext,
+    query: Handle<JsObject>,
+    i: usize,
+) -> NeonResult<AbciQuery> {
+    let path = query
+        .get(ctx, "path")?
+        .downcast_or_throw::<JsString, FunctionContext>(ctx)
+        .chain_neon(ctx, format!("Unable to downcast path of query {}", i))?
+        .value();
+    let data = query
+        .get(ctx, "data")?
+        .downcast_or_throw::<JsBuffer, FunctionContext>(ctx)
+        .chain_neon(ctx, format!("Unable to downcast data of query {}", i))?;
+    let data = data.borrow(&ctx.lock()).as_slice().to_vec();
+
+    let height = query.get(ctx, "height")?;
+    let height = if height.is_a::<JsUndefined>() || height.is_a::<JsNull>() {
+        None
+    } else {
+        let height = height
+            .downcast_or_throw::<JsNumber, FunctionContext>(ctx)
+            .chain_neon(ctx, format!("Unable to downcast height of query {}", i))?
+            .value();
+        if height < 0.0 || height.fract() != 0.0 {
+            return ctx.throw_error(format!(
+                "Height of query {} should be a non-negative integer",
+                i
+            ));
+        }
+        Some(height as u64)
+    };
+    let prove = query.get(ctx, "prove")?;
+    let prove = match prove.downcast::<JsBoolean>() {
+        Ok(prove) => prove.value(),
+        Err(_) => false,
+    };
+
+    Ok(AbciQuery {
+        path,
+        data,
+        height,
+        prove,
+    })
+}
+
+#[derive(Clone)]
+struct AbciQuery {
+    path: String,
+    data: Vec<u8>,
+    height: Option<u64>,
+    prove: bool,
+}
+
+#[derive(Clone)]
+struct ConfirmationRequest {
+    tx_id: TxId,
+    confirmations: u64,
+    timeout: Duration,
+    poll_interval: Duration,
+}
+
+#[derive(Clone)]
+enum RpcRequest {
+    BroadcastTxSync(Vec<u8>),
+    BroadcastTxCommit(Vec<u8>),
+    AbciQuery(AbciQuery),
+    AbciQueryBatch(Vec<AbciQuery>),
+    TxStatus(TxId),
+    WaitForConfirmation(ConfirmationRequest),
+}
+
+#[derive(Clone)]
+struct RpcTask {
+    client: TendermintRpcClient,
+    request: RpcRequest,
+    cancellation: CancellationToken,
+}
+
+impl RpcTask {
+    /// Schedule the call on background thread and return its task handle
+    fn run<'a>(
+        mut self,
+        ctx: &mut FunctionContext<'a>,
+        callback: Handle<JsFunction>,
+    ) -> JsResult<'a, JsValue> {
+        self.cancellation = CancellationToken::register();
+        let handle = self.cancellation.to_js(ctx)?;
+        self.schedule(callback);
+
+        Ok(handle.upcast())
+    }
+
+    fn call(&self) -> std::result::Result<Value, NativeError> {
+        let result = match &self.request {
+            RpcRequest::BroadcastTxSync(tx_aux) => {
+                self.client.broadcast_tx_sync(tx_aux).map_err(|err| {
+                    NativeError::from_client_error("Unable to broadcast transaction", &err)
+                })?
+            }
+            RpcRequest::BroadcastTxCommit(tx_aux) => {
+                self.client.broadcast_tx_commit(tx_aux).map_err(|err| {
+                    NativeError::from_client_error("Unable to broadcast transaction", &err)
+                })?
+            }
+            RpcRequest::AbciQuery(query) => self
+                .client
+                .abci_query(&query.path, &query.data, query.height, query.prove)
+                .map_err(|err| {
+                    NativeError::from_client_error("Unable to query ABCI application", &err)
+                })?,
+            RpcRequest::AbciQueryBatch(queries) => {
+                let queries = queries
+                    .iter()
+                    .map(|query| {
+                        (
+                            query.path.as_str(),
+                            query.data.as_slice(),
+                            query.height,
+                            query.prove,
+                        )
+                    })
+                    .collect::<Vec<(&str, &[u8], Option<u64>, bool)>>();
+                let results = self.client.abci_query_batch(&queries).map_err(|err| {
+                    NativeError::from_client_error("Unable to query ABCI application", &err)
+                })?;
+
+                Value::Array(results)
+            }
+            RpcRequest::TxStatus(tx_id) => {
+                let status = tx_status(&self.client, tx_id).map_err(|err| {
+                    NativeError::from_client_error("Unable to query transaction status", &err)
+                })?;
+
+                tx_status_to_json(&status)?
+            }
+            RpcRequest::WaitForConfirmation(request) => {
+                let status = wait_for_confirmation(
+                    &self.client,
+                    &request.tx_id,
+                    request.confirmations,
+                    request.timeout,
+                    request.poll_interval,
+                    &self.cancellation,
+                )?;
+
+                tx_status_to_json(&status)?
+            }
+        };
+
+        Ok(result)
+    }
+
+    fn result_to_js<'a, C: Context<'a>>(
+        &self,
+        ctx: &mut C,
+        result: std::result::Result<Value, NativeError>,
+    ) -> JsResult<'a, JsValue> {
+        let result = match result {
+            Ok(result) => result,
+            Err(err) => return err.throw(ctx),
+        };
+
+        match self.request {
+            RpcRequest::AbciQuery(_) => abci_query_response_to_js(ctx, &result),
+            RpcRequest::AbciQueryBatch(_) => {
+                let empty_results = Vec::new();
+                let results = result.as_array().unwrap_or(&empty_results);
+
+                let js_results = JsArray::new(ctx, results.len() as u32);
+                for (i, result) in results.iter().enumerate() {
+                    let js_result = abci_query_response_to_js(ctx, result)?;
+                    js_results.set(ctx, i as u32, js_result)?;
+                }
+
+                Ok(js_results.upcast())
+            }
+            _ => json_to_js(ctx, &result),
+        }
+    }
+}
+
+impl Task for RpcTask {
+    type Output = Value;
+    type Error = NativeError;
+    type JsEvent = JsValue;
+
+    fn perform(&self) -> std::result::Result<Value, NativeError> {
+        // The call runs apart so that cancelling does not wait for the
+        // response or the timeout
+        let task = self.clone();
+        self.cancellation.run(move || task.call())
+    }
+
+    fn complete(
+        self,
+        mut ctx: TaskContext,
+        result: std::result::Result<Value, NativeError>,
+    ) -> JsResult<JsValue> {
+        let result = self.cancellation.complete(result);
+        self.result_to_js(&mut ctx, result)
+    }
+}
+
+fn abci_query_response_to_js<'a, C: Context<'a>>(
+    ctx: &mut C,
+    result: &Value,
+) -> JsResult<'a, JsValue> {
+    let response = result
+        .get("response")
+        .chain_neon(ctx, "ABCI query result is missing response")?;
+
+    let js_response = json_to_js(ctx, response)?;
+    let js_response = js_response
+        .downcast::<JsObject>()
+        .chain_neon(ctx, "Unable to downcast ABCI query response")?;
+    for key in &["key", "value"] {
+        let encoded = response
+            .get(key)
+            .and_then(Value::as_str)
+            .unwrap_or_default();
+        let decoded = base64::decode(encoded).chain_neon_with_code(
+            ctx,
+            ErrorCode::DeserializationError,
+            format!("Unable to decode ABCI query {}", key),
+        )?;
+
+        let buffer = bytes_to_buffer(ctx, &decoded)?;
+        js_response.set(ctx, *key, buffer)?;
+    }
+
+    Ok(js_response.upcast())
+}
+
+fn tx_status_to_json<T: serde::Serialize>(status: &T) -> std::result::Result<Value, NativeError> {
+    serde_json::to_value(status).map_err(|err| {
+        NativeError::new(
+            ErrorCode::SerializationError,
+            format!("Unable to serialize transaction status: {}", err),
+        )
+    })
+}
+
+#[inline]
+fn rpc_client_argument(ctx: &mut FunctionContext, i: i32) -> NeonResult<TendermintRpcClient> {
+    let tendermint_rpc_url = ctx.argument::<JsString>(i)?.value();
+
+    TendermintRpcClient::new(&tendermint_rpc_url)
+        .chain_neon(ctx, "Unable to create Tendermint RPC client")
+}
+
+pub fn register_rpc_module(ctx: &mut ModuleContext) -> NeonResult<()> {
+    let js_object = JsObject::new(ctx);
+
+    let broadcast_tx_sync_fn = JsFunction::new(ctx, broadcast_tx_sync)?;
+    js_object.set(ctx, "broadcastTxSync", broadcast_tx_sync_fn)?;
+
+    let broadcast_tx_commit_fn = JsFunction::new(ctx, broadcast_tx_commit)?;
+    js_object.set(ctx, "broadcastTxCommit", broadcast_tx_commit_fn)?;
+
+    let abci_query_fn = JsFunction::new(ctx, abci_query)?;
+    js_object.set(ctx, "abciQuery", abci_query_fn)?;
+
+    let abci_query_batch_fn = JsFunction::new(ctx, abci_query_batch)?;
+    js_object.set(ctx, "abciQueryBatch", abci_query_batch_fn)?;
+
+    let get_tx_status_fn = JsFunction::new(ctx, get_tx_status)?;
+    js_object.set(ctx, "getTxStatus", get_tx_status_fn)?;
+
+    let wait_for_confirmation_fn = JsFunction::new(ctx, wait_for_tx_confirmation)?;
+    js_object.set(ctx, "waitForConfirmation", wait_for_confirmation_fn)?;
+
+    let configure_client_fn = JsFunction::new(ctx, configure_client)?;
+    js_object.set(ctx, "configureClient", configure_client_fn)?;
+
+    let subscribe_new_blocks_fn = JsFunction::new(ctx, subscribe_new_blocks)?;
+    js_object.set(ctx, "subscribeNewBlocks", subscribe_new_blocks_fn)?;
+
+    ctx.export_value("rpc", js_object)
+}
@@ -0,0 +1,436 @@
+//! Subscription to the new block and transaction events of the Tendermint
+//! WebSocket endpoint. Events are read and decoded on a background thread
+//! and delivered to the JS callback through an event handler
+use std::collections::HashMap;
+use std::io;
+use std::net::TcpStream;
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+use chain_core::tx::data::TxId;
+use chain_core::tx::TxAux;
+use client_common::{Error, ErrorKind, Result, ResultExt};
+use lazy_static::lazy_static;
+use neon::event::EventHandler;
+use neon::prelude::*;
+use parity_scale_codec::Decode;
+use serde_json::{json, Value};
+use tungstenite::{Message, WebSocket};
+
+use crate::block::parse_header;
+use crate::client_config::ClientConfig;
+use crate::error::{ClientErrorNeonExt, NativeError};
+use crate::function_types::bytes_to_buffer;
+
+/// Interval to check whether the subscription is cancelled while waiting
+/// for events
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(10);
+const NEW_BLOCK_QUERY: &str = "tm.event='NewBlock'";
+const TX_QUERY: &str = "tm.event='Tx'";
+
+lazy_static! {
+    static ref SUBSCRIPTIONS: Mutex<HashMap<u32, Arc<AtomicBool>>> = Mutex::new(HashMap::new());
+}
+static NEXT_SUBSCRIPTION_ID: AtomicU32 = AtomicU32::new(1);
+
+/// subscribe to new blocks and transactions of the Tendermint WebSocket
+/// endpoint
+/// @arguments
+/// - url: Tendermint WebSocket endpoint, e.g. ws://127.0.0.1:26657/websocket
+/// - callback: node-style callback called with each event. An error is
+///     passed when the connection fails or is lost, after which the
+///     subscription ends
+/// @return subscription { id, unsubscribe() }. Events are
+///     { type: "NewBlock", height, hash?, time, txs: [{ txId?, tx }] } and
+///     { type: "Tx", height, index, code, log, txId?, tx }, where tx is the
+///     transaction Buffer and txId is present when it can be decoded
+pub fn subscribe_new_blocks(mut ctx: FunctionContext) -> JsResult<JsObject> {
+    let url = ctx.argument::<JsString>(0)?.value();
+    let callback = ctx.argument::<JsFunction>(1)?;
+
+    let this = ctx.undefined();
+    let handler = EventHandler::new(&ctx, this, callback);
+    let stopped = Arc::new(AtomicBool::new(false));
+    let subscription_id = NEXT_SUBSCRIPTION_ID.fetch_add(1, Ordering::SeqCst);
+    SUBSCRIPTIONS
+        .lock()
+        .chain_neon(&mut ctx, "Unable to acquire subscription lock")?
+        .insert(subscription_id, Arc::clone(&stopped));
+
+    thread::spawn(move || {
+        if let Err(err) = read_events(&url, &handler, &stopped) {
+            if !stopped.load(Ordering::SeqCst) {
+                log::warn!("Tendermint WebSocket subscription {} ended: {}", url, err);
+                let err =
+                    NativeError::from_client_error("Tendermint WebSocket subscription ended", &err);
+                handler.schedule_with(move |ctx, this, callback| {
+                    if let Ok(error) = err.to_js_error(ctx) {
+                        let _ = callback.call(ctx, this, vec![error.upcast::<JsValue>()]);
+                    }
+                });
+            }
+        }
+        if let Ok(mut subscriptions) = SUBSCRIPTIONS.lock() {
+            subscriptions.remove(&subscription_id);
+        }
+    });
+
+    let subscription = JsObject::new(&mut ctx);
+    let id = ctx.number(subscription_id);
+    subscription.set(&mut ctx, "id", id)?;
+    let unsubscribe_fn = JsFunction::new(&mut ctx, unsubscribe)?;
+    subscription.set(&mut ctx, "unsubscribe", unsubscribe_fn)?;
+
+    Ok(subscription)
+}
+
+/// stop the subscription it is called on. No event is delivered afterwards
+/// @return boolean: whether the subscription was active
+pub fn unsubscribe(mut ctx: FunctionContext) -> JsResult<JsBoolean> {
+    let subscription = ctx.this();
+    let subscription_id = subscription
+        .get(&mut ctx, "id")?
+        .downcast_or_throw::<JsNumber, FunctionContext>(&mut ctx)
+        .chain_neon(&mut ctx, "Unable to downcast id of subscription")?
+        .value() as u32;
+
+    let stopped = SUBSCRIPTIONS
+        .lock()
+        .chain_neon(&mut ctx, "Unable to acquire subscription lock")?
+        .remove(&subscription_id);
+    if let Some(stopped) = &stopped {
+        stopped.store(true, Ordering::SeqCst);
+    }
+
+    Ok(ctx.boolean(stopped.is_some()))
+}
+
+enum SubscriptionEvent {
+    NewBlock {
+        height: u64,
+        hash: Option<String>,
+        time: String,
+        txs: Vec<EventTx>,
+    },
+    Tx {
+        height: u64,
+        index: u32,
+        code: u32,
+        log: String,
+        tx: EventTx,
+    },
+}
+
+struct EventTx {
+    tx_id: Option<TxId>,
+    raw: Vec<u8>,
+}
+
+impl EventTx {
+    fn parse(tx: &Value) -> Result<EventTx> {
+        let raw = tx
+            .as_str()
+            .and_then(|tx| base64::decode(tx).ok())
+            .chain(|| {
+                (
+                    ErrorKind::DeserializationError,
+                    "Event transaction should be base64 string",
+                )
+            })?;
+        let tx_id = TxAux::decode(&mut raw.as_slice())
+            .ok()
+            .map(|tx_aux| tx_aux.tx_id());
+
+        Ok(EventTx { tx_id, raw })
+    }
+}
+
+/// Connect to the endpoint and deliver the events until the subscription is
+/// cancelled or the connection fails
+fn read_events(url: &str, handler: &EventHandler, stopped: &Arc<AtomicBool>) -> Result<()> {
+    let mut socket = connect(url)?;
+    socket
+        .get_mut()
+        .set_read_timeout(Some(POLL_INTERVAL))
+        .chain(|| {
+            (
+                ErrorKind::ConnectionError,
+                "Unable to set Tendermint WebSocket read timeout",
+            )
+        })?;
+
+    for query in &[NEW_BLOCK_QUERY, TX_QUERY] {
+        let request = json!({
+            "jsonrpc": "2.0",
+            "id": query,
+            "method": "subscribe",
+            "params": { "query": query },
+        });
+        socket
+            .write_message(Message::Text(request.to_string()))
+            .chain(|| {
+                (
+                    ErrorKind::ConnectionError,
+                    "Unable to send Tendermint WebSocket subscribe request",
+                )
+            })?;
+    }
+
+    while !stopped.load(Ordering::SeqCst) {
+        let text = match socket.read_message() {
+            Ok(Message::Text(text)) => text,
+            Ok(_) => continue,
+            Err(tungstenite::Error::Io(ref err))
+                if err.kind() == io::ErrorKind::WouldBlock
+                    || err.kind() == io::ErrorKind::TimedOut =>
+            {
+                continue
+            }
+            Err(err) => {
+                return Err(Error::new(
+                    ErrorKind::ConnectionError,
+                    format!("Unable to read from Tendermint WebSocket: {}", err),
+                ))
+            }
+        };
+
+        let response: Value = serde_json::from_str(&text).chain(|| {
+            (
+                ErrorKind::DeserializationError,
+                "Unable to deserialize Tendermint WebSocket message",
+            )
+        })?;
+        if let Some(error) = response.get("error") {
+            return Err(Error::new(
+                ErrorKind::TendermintRpcError,
+                format!("Tendermint WebSocket returns error: {}", error),
+            ));
+        }
+
+        if let Some(event) = parse_event(&response)? {
+            let stopped = Arc::clone(stopped);
+            handler.schedule_with(move |ctx, this, callback| {
+                // Events read before unsubscribing may still be queued
+                if stopped.load(Ordering::SeqCst) {
+                    return;
+                }
+                let args = match event_to_js(ctx, &event) {
+                    Ok(event) => vec![ctx.null().upcast::<JsValue>(), event],
+                    Err(_) => return,
+                };
+                let _ = callback.call(ctx, this, args);
+            });
+        }
+    }
+
+    close(&mut socket);
+    Ok(())
+}
+
+/// Connect directly or through the proxy configured for the endpoint. Only
+/// ws:// endpoints are supported
+fn connect(url: &str) -> Result<WebSocket<TcpStream>> {
+    let mut parts = url.splitn(2, "://");
+    let authority = match (parts.next(), parts.next()) {
+        (Some("ws"), Some(rest)) => rest.split('/').next().unwrap_or_default(),
+        _ => {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "Unsupported Tendermint WebSocket protocol, expected ws:// endpoint",
+            ))
+        }
+    };
+    let address = if authority.contains(':') {
+        authority.to_owned()
+    } else {
+        format!("{}:80", authority)
+    };
+
+    let config = ClientConfig::of(url);
+    let timeout = config.timeout.unwrap_or(HANDSHAKE_TIMEOUT);
+    let stream = match &config.proxy {
+        Some(proxy) => proxy.connect(&address, timeout)?,
+        None => TcpStream::connect(&address).chain(|| {
+            (
+                ErrorKind::ConnectionError,
+                format!("Unable to connect to Tendermint WebSocket {}", url),
+            )
+        })?,
+    };
+    stream.set_read_timeout(Some(timeout)).chain(|| {
+        (
+            ErrorKind::ConnectionError,
+            "Unable to set Tendermint WebSocket handshake timeout",
+        )
+    })?;
+
+    let (socket, _) = tungstenite::client(url, stream).map_err(|err| {
+        Error::new(
+            ErrorKind::ConnectionError,
+            format!("Unable to connect to Tendermint WebSocket {}: {}", url, err),
+        )
+    })?;
+
+    Ok(socket)
+}
+
+fn close<S: io::Read + io::Write>(socket: &mut WebSocket<S>) {
+    if socket.close(None).is_ok() {
+        let _ = socket.write_pending();
+    }
+}
+
+/// Returns the event of the message, none for the responses to the subscribe
+/// requests and events of other types
+fn parse_event(response: &Value) -> Result<Option<SubscriptionEvent>> {
+    let data = match response.pointer("/result/data") {
+        Some(data) => data,
+        None => return Ok(None),
+    };
+    let value = data.get("value").unwrap_or(&Value::Null);
+
+    match data.get("type").and_then(Value::as_str) {
+        Some("tendermint/event/NewBlock") => {
+            let block = value.get("block").chain(|| {
+                (
+                    ErrorKind::DeserializationError,
+                    "NewBlock event is missing block",
+                )
+            })?;
+            let empty_txs = Vec::new();
+            let txs = block
+                .pointer("/data/txs")
+                .and_then(Value::as_array)
+                .unwrap_or(&empty_txs);
+
+            Ok(Some(SubscriptionEvent::NewBlock {
+                height: integer_field(block.pointer("/header/height"), "height of NewBlock event")?,
+                hash: parse_header(block)
+                    .ok()
+                    .map(|header| hex::encode_upper(header.hash())),
+                time: block
+                    .pointer("/header/time")
+                    .and_then(Value::as_str)
+                    .unwrap_or_default()
+                    .to_owned(),
+                txs: txs
+                    .iter()
+                    .map(EventTx::parse)
+                    .collect::<Result<Vec<EventTx>>>()?,
+            }))
+        }
+        Some("tendermint/event/Tx") => {
+            let tx_result = value.get("TxResult").chain(|| {
+                (
+                    ErrorKind::DeserializationError,
+                    "Tx event is missing TxResult",
+                )
+            })?;
+            let result = tx_result.get("result").unwrap_or(&Value::Null);
+
+            Ok(Some(SubscriptionEvent::Tx {
+                height: integer_field(tx_result.get("height"), "height of Tx event")?,
+                index: integer_field(tx_result.get("index"), "index of Tx event")? as u32,
+                code: integer_field(result.get("code"), "code of Tx event")? as u32,
+                log: result
+                    .get("log")
+                    .and_then(Value::as_str)
+                    .unwrap_or_default()
+                    .to_owned(),
+                tx: EventTx::parse(tx_result.get("tx").unwrap_or(&Value::Null))?,
+            }))
+        }
+        _ => Ok(None),
+    }
+}
+
+/// Integers are strings or numbers in Tendermint JSON, and omitted when zero
+fn integer_field(value: Option<&Value>, name: &str) -> Result<u64> {
+    let parsed = match value {
+        None | Some(Value::Null) => Some(0),
+        Some(Value::String(integer)) => integer.parse::<u64>().ok(),
+        Some(Value::Number(integer)) => integer.as_u64(),
+        _ => None,
+    };
+
+    parsed.chain(|| {
+        (
+            ErrorKind::DeserializationError,
+            format!("{} should be a non-negative integer", name),
+        )
+    })
+}
+
+fn event_to_js<'a, C: Context<'a>>(
+    ctx: &mut C,
+    event: &SubscriptionEvent,
+) -> JsResult<'a, JsValue> {
+    let js_event = ctx.empty_object();
+    match event {
+        SubscriptionEvent::NewBlock {
+            height,
+            hash,
+            time,
+            txs,
+        } => {
+            let event_type = ctx.string("NewBlock");
+            js_event.set(ctx, "type", event_type)?;
+            let height = ctx.number(*height as f64);
+            js_event.set(ctx, "height", height)?;
+            if let Some(hash) = hash {
+                let hash = ctx.string(hash);
+                js_event.set(ctx, "hash", hash)?;
+            }
+            let time = ctx.string(time);
+            js_event.set(ctx, "time", time)?;
+
+            let js_txs = JsArray::new(ctx, txs.len() as u32);
+            for (i, tx) in txs.iter().enumerate() {
+                let js_tx = ctx.empty_object();
+                set_event_tx(ctx, js_tx, tx)?;
+                js_txs.set(ctx, i as u32, js_tx)?;
+            }
+            js_event.set(ctx, "txs", js_txs)?;
+        }
+        SubscriptionEvent::Tx {
+            height,
+            index,
+            code,
+            log,
+            tx,
+        } => {
+            let event_type = ctx.string("Tx");
+            js_event.set(ctx, "type", event_type)?;
+            let height = ctx.number(*height as f64);
+            js_event.set(ctx, "height", height)?;
+            let index = ctx.number(*index);
+            js_event.set(ctx, "index", index)?;
+            let code = ctx.number(*code);
+            js_event.set(ctx, "code", code)?;
+            let log = ctx.string(log);
+            js_event.set(ctx, "log", log)?;
+            set_event_tx(ctx, js_event, tx)?;
+        }
+    }
+
+    Ok(js_event.upcast())
+}
+
+fn set_event_tx<'a, C: Context<'a>>(
+    ctx: &mut C,
+    js_object: Handle<JsObject>,
+    tx: &EventTx,
+) -> NeonResult<()> {
+    if let Some(tx_id) = tx.tx_id {
+        let tx_id = ctx.string(hex::encode(tx_id));
+        js_object.set(ctx, "txId", tx_id)?;
+    }
+
+    let buffer = bytes_to_buffer(ctx, &tx.raw)?;
+    js_object.set(ctx, "tx", buffer)?;
+
+    Ok(())
+}
@@ -0,0 +1,143 @@
+//! Status of the committed transactions, looked up by the transaction id or
+//! the Tendermint hash of the transaction
+use std::thread;
+use std::time::{Duration, Instant};
+
+use chain_core::tx::data::TxId;
+use client_common::{ErrorKind, Result, ResultExt};
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::cancellation::CancellationToken;
+use crate::error::{ErrorCode, NativeError};
+
+use super::TendermintRpcClient;
+
+pub const DEFAULT_CONFIRMATIONS: u64 = 1;
+pub const DEFAULT_CONFIRMATION_TIMEOUT: Duration = Duration::from_secs(60);
+pub const DEFAULT_POLL_INTERVAL: Duration = Duration::from_millis(1000);
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TxStatus {
+    /// Tendermint hash of the transaction bytes
+    hash: String,
+    height: u64,
+    index: u64,
+    /// DeliverTx result code, 0 when the transaction is valid
+    code: u64,
+    log: String,
+    /// Number of blocks committed since the transaction, counting its block
+    confirmations: u64,
+}
+
+/// Returns the status of the committed transaction, None when it is not
+/// committed yet. Valid transactions are indexed by their transaction id,
+/// failed ones only by their Tendermint hash
+pub fn tx_status(client: &TendermintRpcClient, tx_id: &TxId) -> Result<Option<TxStatus>> {
+    let queries = [
+        format!("valid_txs.txid='{}'", hex::encode(tx_id)),
+        format!("tx.hash='{}'", hex::encode_upper(tx_id)),
+    ];
+    for query in queries.iter() {
+        let result = client.tx_search(query, 1, 1)?;
+        let tx = match result
+            .get("txs")
+            .and_then(Value::as_array)
+            .and_then(|txs| txs.first())
+        {
+            Some(tx) => tx,
+            None => continue,
+        };
+
+        return Ok(Some(parse_tx_status(client, tx)?));
+    }
+
+    Ok(None)
+}
+
+/// Poll the status of the transaction until its block has the confirmations,
+/// or until it fails since failed transactions stay failed
+pub fn wait_for_confirmation(
+    client: &TendermintRpcClient,
+    tx_id: &TxId,
+    confirmations: u64,
+    timeout: Duration,
+    poll_interval: Duration,
+    cancellation: &CancellationToken,
+) -> std::result::Result<TxStatus, NativeError> {
+    let started = Instant::now();
+    loop {
+        let status = cancellation
+            .check()
+            .and_then(|_| tx_status(client, tx_id))
+            .map_err(|err| {
+                NativeError::from_client_error("Unable to query transaction status", &err)
+            })?;
+        match status {
+            Some(status) if status.code != 0 || status.confirmations >= confirmations => {
+                return Ok(status)
+            }
+            _ => (),
+        }
+        if started.elapsed() >= timeout {
+            return Err(NativeError::new(
+                ErrorCode::RpcTimeout,
+                format!(
+                    "Transaction is not confirmed by {} blocks within {}ms",
+                    confirmations,
+                    timeout.as_millis()
+                ),
+            ));
+        }
+        thread::sleep(poll_interval);
+    }
+}
+
+fn parse_tx_status(client: &TendermintRpcClient, tx: &Value) -> Result<TxStatus> {
+    let height = tx
+        .get("height")
+        .and_then(Value::as_str)
+        .and_then(|height| height.parse::<u64>().ok())
+        .chain(|| {
+            (
+                ErrorKind::DeserializationError,
+                "Unable to deserialize height of transaction",
+            )
+        })?;
+    let tx_result = tx.get("tx_result");
+    let code = tx_result
+        .and_then(|tx_result| tx_result.get("code"))
+        .and_then(Value::as_u64)
+        .unwrap_or_default();
+    let log = tx_result
+        .and_then(|tx_result| tx_result.get("log"))
+        .and_then(Value::as_str)
+        .unwrap_or_default()
+        .to_owned();
+
+    let latest_height = client
+        .status()?
+        .pointer("/sync_info/latest_block_height")
+        .and_then(Value::as_str)
+        .and_then(|height| height.parse::<u64>().ok())
+        .chain(|| {
+            (
+                ErrorKind::DeserializationError,
+                "Unable to deserialize latest block height from Tendermint status",
+            )
+        })?;
+
+    Ok(TxStatus {
+        hash: tx
+            .get("hash")
+            .and_then(Value::as_str)
+            .unwrap_or_default()
+            .to_owned(),
+        height,
+        index: tx.get("index").and_then(Value::as_u64).unwrap_or_default(),
+        code,
+        log,
+        confirmations: (latest_height + 1).saturating_sub(height),
+    })
+}
@@ -3,11 +3,14 @@ use chain_core::common::Proof;
 use chain_core::tx::data::address::ExtendedAddr;
 use chain_core::tx::witness::tree::RawXOnlyPubkey;
 use chain_core::tx::witness::TxInWitness;
-use client_common::{ErrorKind, MultiSigAddress, PrivateKey, PublicKey, Result, ResultExt, SECP};
+use client_common::{ErrorKind, MultiSigAddress, PrivateKey, PublicKey, Result, ResultExt};
 use secp256k1::recovery::RecoverableSignature;
 use secp256k1::schnorrsig::{schnorr_sign, SchnorrSignature};
 use secp256k1::{Message, SecretKey};
 
+use crate::common::with_secp;
+use crate::metrics;
+
 /// Signer from key pair
 pub struct KeyPairSigner {
     proof: Proof<RawXOnlyPubkey>,
@@ -42,15 +45,17 @@ fn generate_extended_addr_and_proof(
 
 impl KeyPairSigner {
     pub fn sign(&self, message: &[u8]) -> Result<RecoverableSignature> {
-        let sign_message = Message::from_slice(&message).chain(|| {
-            (
-                ErrorKind::DeserializationError,
-                "Unable to deserialize message to sign",
-            )
-        })?;
-        let secret_key = SecretKey::from(&self.private_key);
-        let signature = SECP.with(|secp| secp.sign_recoverable(&sign_message, &secret_key));
-        Ok(signature)
+        metrics::measure("signer.signRecoverable", || {
+            let sign_message = Message::from_slice(&message).chain(|| {
+                (
+                    ErrorKind::DeserializationError,
+                    "Unable to deserialize message to sign",
+                )
+            })?;
+            let secret_key = SecretKey::from(&self.private_key);
+            let signature = with_secp(|secp| secp.sign_recoverable(&sign_message, &secret_key));
+            Ok(signature)
+        })
     }
 
     pub fn schnorr_sign_txid(&self, txid: &[u8]) -> Result<TxInWitness> {
@@ -61,14 +66,16 @@ impl KeyPairSigner {
     }
 
     pub fn schnorr_sign(&self, message: &[u8]) -> Result<SchnorrSignature> {
-        let sign_message = Message::from_slice(&message).chain(|| {
-            (
-                ErrorKind::DeserializationError,
-                "Unable to deserialize message to sign",
-            )
-        })?;
-        let secret_key = SecretKey::from(&self.private_key);
-        let signature = SECP.with(|secp| schnorr_sign(&secp, &sign_message, &secret_key));
-        Ok(signature)
+        metrics::measure("signer.schnorrSign", || {
+            let sign_message = Message::from_slice(&message).chain(|| {
+                (
+                    ErrorKind::DeserializationError,
+                    "Unable to deserialize message to sign",
+                )
+            })?;
+            let secret_key = SecretKey::from(&self.private_key);
+            let signature = with_secp(|secp| schnorr_sign(&secp, &sign_message, &secret_key));
+            Ok(signature)
+        })
     }
 }
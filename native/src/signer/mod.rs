@@ -1,16 +1,17 @@
 mod key_pair_signer;
+mod witness;
 
 use neon::prelude::*;
-use parity_scale_codec::Encode;
 
 use crate::error::ClientErrorNeonExt;
 use crate::function_types::*;
 
 pub use key_pair_signer::KeyPairSigner;
+pub use witness::build_tree_sig_witness;
+use witness::{staked_state_op_witness, tree_sig_witness};
 
 fn schnorr_sign_txid(mut ctx: FunctionContext) -> JsResult<JsBuffer> {
-    let message = ctx.argument::<JsBuffer>(0)?;
-    let message = ctx.borrow(&message, |data| data.as_slice::<u8>());
+    let message = u8_buffer_argument(&mut ctx, 0)?;
     let key_pair = key_pair_argument(&mut ctx, 1)?;
 
     let private_key = key_pair.0;
@@ -20,16 +21,10 @@ fn schnorr_sign_txid(mut ctx: FunctionContext) -> JsResult<JsBuffer> {
         .chain_neon(&mut ctx, "Unable to create signer from KeyPair")?;
 
     let tx_in_witness = signer
-        .schnorr_sign_txid(message)
+        .schnorr_sign_txid(&message)
         .chain_neon(&mut ctx, "Unable to sign message")?;
-    let tx_in_witness = tx_in_witness.encode();
-
-    let mut buffer = ctx.buffer(tx_in_witness.len() as u32)?;
-    ctx.borrow_mut(&mut buffer, |data| {
-        let slice = data.as_mut_slice();
-        slice.copy_from_slice(&tx_in_witness);
-    });
-    Ok(buffer)
+
+    encode_to_buffer(&mut ctx, &tx_in_witness)
 }
 
 pub fn register_signer_module(ctx: &mut ModuleContext) -> NeonResult<()> {
@@ -38,5 +33,11 @@ pub fn register_signer_module(ctx: &mut ModuleContext) -> NeonResult<()> {
     let schnorr_sign_message_fn = JsFunction::new(ctx, schnorr_sign_txid)?;
     js_object.set(ctx, "schnorrSignTxId", schnorr_sign_message_fn)?;
 
+    let tree_sig_witness_fn = JsFunction::new(ctx, tree_sig_witness)?;
+    js_object.set(ctx, "treeSigWitness", tree_sig_witness_fn)?;
+
+    let staked_state_op_witness_fn = JsFunction::new(ctx, staked_state_op_witness)?;
+    js_object.set(ctx, "stakedStateOpWitness", staked_state_op_witness_fn)?;
+
     ctx.export_value("signer", js_object)
 }
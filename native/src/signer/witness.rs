@@ -0,0 +1,133 @@
+//! Witness assembly from externally produced signatures, so that air-gapped
+//! or HSM signers never have to hand their private key to the builder
+use chain_core::common::H256;
+use chain_core::state::account::StakedStateOpWitness;
+use chain_core::tx::witness::TxInWitness;
+use client_common::{Error, ErrorKind, MultiSigAddress, PublicKey, Result, ResultExt};
+use neon::prelude::*;
+use secp256k1::recovery::{RecoverableSignature, RecoveryId};
+use secp256k1::schnorrsig::{schnorr_verify, SchnorrSignature};
+use secp256k1::Message;
+
+use crate::common::with_secp;
+use crate::error::{ClientErrorNeonExt, ErrorCode};
+use crate::function_types::*;
+
+/// assemble tree signature witness of transfer input from Schnorr signature
+/// @arguments
+/// - signature: 64 bytes Schnorr signature of the transaction id
+/// - address_public_keys: public keys of the transfer address
+/// - signer_public_keys: public keys of the signers producing the signature
+/// - required_signers: number of required signers of the transfer address
+/// - tx_id: (optional) transaction id hex string, the signature is verified
+///     against it when provided
+/// @return TxInWitness to be added to the transaction input
+pub fn tree_sig_witness(mut ctx: FunctionContext) -> JsResult<JsBuffer> {
+    let signature = hex_or_buffer_argument(&mut ctx, 0, "signature")?;
+    let address_public_keys = public_key_vector_argument(&mut ctx, 1)?;
+    let mut signer_public_keys = public_key_vector_argument(&mut ctx, 2)?;
+    let required_signers = required_signers_argument(&mut ctx, 3)?;
+    let tx_id = match ctx.argument_opt(4) {
+        Some(tx_id) if !tx_id.is_a::<JsUndefined>() && !tx_id.is_a::<JsNull>() => {
+            Some(h256_str_argument(&mut ctx, 4)?)
+        }
+        _ => None,
+    };
+
+    signer_public_keys.sort();
+    let witness = signer_public_keys
+        .first()
+        .cloned()
+        .chain(|| (ErrorKind::InvalidInput, "Signer public keys are empty"))
+        .and_then(|self_public_key| {
+            MultiSigAddress::new(address_public_keys, self_public_key, required_signers)
+        })
+        .and_then(|multi_sig_address| {
+            build_tree_sig_witness(
+                &signature,
+                &multi_sig_address,
+                signer_public_keys,
+                tx_id.as_ref(),
+            )
+        })
+        .chain_neon_with_code(
+            &mut ctx,
+            ErrorCode::InvalidSignature,
+            "Unable to assemble tree signature witness",
+        )?;
+
+    encode_to_buffer(&mut ctx, &witness)
+}
+
+/// assemble staked state operation witness from recoverable ECDSA signature
+/// @arguments
+/// - signature: 65 bytes recoverable signature (r, s, recovery id) of the
+///     transaction id
+/// @return StakedStateOpWitness
+pub fn staked_state_op_witness(mut ctx: FunctionContext) -> JsResult<JsBuffer> {
+    let signature = hex_or_buffer_argument(&mut ctx, 0, "signature")?;
+    if signature.len() != 65 {
+        return ctx.throw_error("Recoverable signature should be 65 bytes long");
+    }
+
+    let recovery_id = RecoveryId::from_i32(i32::from(signature[64])).chain_neon_with_code(
+        &mut ctx,
+        ErrorCode::InvalidSignature,
+        "Invalid signature recovery id",
+    )?;
+    let signature = RecoverableSignature::from_compact(&signature[..64], recovery_id)
+        .chain_neon_with_code(
+            &mut ctx,
+            ErrorCode::InvalidSignature,
+            "Unable to deserialize recoverable signature",
+        )?;
+
+    let witness = StakedStateOpWitness::new(signature);
+
+    encode_to_buffer(&mut ctx, &witness)
+}
+
+/// Tree signature witness of the Schnorr signature of the signers, whose
+/// combined public key is a leaf of the multisig address. With the
+/// transaction id the signature is verified before the witness is assembled
+pub fn build_tree_sig_witness(
+    signature: &[u8],
+    multi_sig_address: &MultiSigAddress,
+    mut signer_public_keys: Vec<PublicKey>,
+    tx_id: Option<&H256>,
+) -> Result<TxInWitness> {
+    let signature = SchnorrSignature::from_default(signature).chain(|| {
+        (
+            ErrorKind::DeserializationError,
+            "Unable to deserialize Schnorr signature",
+        )
+    })?;
+
+    // Signers are combined in the sorted order of the MuSig session
+    signer_public_keys.sort();
+    if let Some(tx_id) = tx_id {
+        let message = Message::from_slice(tx_id)
+            .chain(|| (ErrorKind::InvalidInput, "Unable to form message"))?;
+        let combined_public_key = PublicKey::combine(&signer_public_keys)?.0;
+        let verify_passed = with_secp(|secp| {
+            schnorr_verify(&secp, &message, &signature, &combined_public_key.into()).is_ok()
+        });
+        if !verify_passed {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "Signature is not a signature of the signers on the transaction id",
+            ));
+        }
+    }
+
+    let proof = multi_sig_address
+        .generate_proof(signer_public_keys)?
+        .chain(|| {
+            (
+                ErrorKind::InvalidInput,
+                "Signer public keys are not a signing combination of the address",
+            )
+        })?;
+
+    Ok(TxInWitness::TreeSig(signature, proof))
+}
@@ -0,0 +1,232 @@
+mod nonce;
+
+use std::str::FromStr;
+
+use neon::prelude::*;
+
+use chain_core::state::account::{StakedState, StakedStateAddress};
+use client_common::{Error, ErrorKind, Result, ResultExt};
+use parity_scale_codec::Decode;
+use serde_json::Value;
+
+use crate::cancellation::CancellationToken;
+use crate::error::{ClientErrorNeonExt, ErrorCode, NativeError};
+use crate::function_types::*;
+use crate::rpc::TendermintRpcClient;
+
+use nonce::{reconcile_nonces, release_nonce, reserve_nonce};
+
+/// ABCI query path of staked state
+const STAKED_STATE_QUERY_PATH: &str = "staking";
+
+/// query staked state of the staking address from the ABCI application
+/// @arguments
+/// - tendermint_rpc_url: Tendermint RPC endpoint, e.g. http://127.0.0.1:26657
+/// - staking_address: staking address to query
+/// - callback: (optional) node-style callback, query on background thread and
+///     pass the result to callback when provided
+/// @return { address, nonce, bonded, unbonded, unbondedFrom, jailedUntil?,
+///     lastSlash? }, or the task handle { id, cancel() } with callback
+pub fn query_staked_state(mut ctx: FunctionContext) -> JsResult<JsValue> {
+    let tendermint_rpc_url = ctx.argument::<JsString>(0)?.value();
+    let client = TendermintRpcClient::new(&tendermint_rpc_url)
+        .chain_neon(&mut ctx, "Unable to create Tendermint RPC client")?;
+    let staking_address = ctx.argument::<JsString>(1)?.value();
+    let staking_address = StakedStateAddress::from_str(&staking_address).chain_neon_with_code(
+        &mut ctx,
+        ErrorCode::InvalidAddress,
+        "Unable to deserialize staking address",
+    )?;
+    let callback = callback_argument(&mut ctx)?;
+
+    let cancellation = match callback {
+        Some(_) => CancellationToken::register(),
+        None => CancellationToken::default(),
+    };
+    let task = QueryStakedStateTask {
+        client,
+        staking_address,
+        cancellation: cancellation.clone(),
+    };
+    match callback {
+        Some(callback) => {
+            task.schedule(callback);
+            Ok(cancellation.to_js(&mut ctx)?.upcast())
+        }
+        None => {
+            let result = task.perform();
+            staked_state_to_js(&mut ctx, result)
+        }
+    }
+}
+
+#[derive(Clone)]
+struct QueryStakedStateTask {
+    client: TendermintRpcClient,
+    staking_address: StakedStateAddress,
+    cancellation: CancellationToken,
+}
+
+impl QueryStakedStateTask {
+    /// Query the staked state apart so that cancelling does not wait for the
+    /// response
+    fn run_query(&self) -> std::result::Result<StakedState, NativeError> {
+        let task = self.clone();
+        self.cancellation.run(move || {
+            task.query()
+                .map_err(|err| NativeError::from_client_error("Unable to query staked state", &err))
+        })
+    }
+
+    fn query(&self) -> Result<StakedState> {
+        let result = self.client.abci_query(
+            STAKED_STATE_QUERY_PATH,
+            self.staking_address.as_ref(),
+            None,
+            false,
+        )?;
+        let response = result.get("response").chain(|| {
+            (
+                ErrorKind::DeserializationError,
+                "ABCI query result is missing response",
+            )
+        })?;
+
+        let code = response
+            .get("code")
+            .and_then(Value::as_u64)
+            .unwrap_or_default();
+        if code != 0 {
+            let log = response
+                .get("log")
+                .and_then(Value::as_str)
+                .unwrap_or_default();
+            return Err(Error::new(
+                ErrorKind::TendermintRpcError,
+                format!("Staked state query fails with code {}: {}", code, log),
+            ));
+        }
+
+        let value = response
+            .get("value")
+            .and_then(Value::as_str)
+            .unwrap_or_default();
+        let value = base64::decode(value).chain(|| {
+            (
+                ErrorKind::DeserializationError,
+                "Unable to decode base64 staked state",
+            )
+        })?;
+        let mut value = value.as_slice();
+        let staked_state = StakedState::decode(&mut value).chain(|| {
+            (
+                ErrorKind::DeserializationError,
+                "Unable to decode staked state",
+            )
+        })?;
+        if !value.is_empty() {
+            return Err(Error::new(
+                ErrorKind::DeserializationError,
+                "Unexpected trailing bytes after staked state",
+            ));
+        }
+        if staked_state.address != self.staking_address {
+            return Err(Error::new(
+                ErrorKind::DeserializationError,
+                "Staked state belongs to another staking address",
+            ));
+        }
+
+        Ok(staked_state)
+    }
+}
+
+impl Task for QueryStakedStateTask {
+    type Output = StakedState;
+    type Error = NativeError;
+    type JsEvent = JsValue;
+
+    fn perform(&self) -> std::result::Result<StakedState, NativeError> {
+        self.run_query()
+    }
+
+    fn complete(
+        self,
+        mut ctx: TaskContext,
+        result: std::result::Result<StakedState, NativeError>,
+    ) -> JsResult<JsValue> {
+        let result = self.cancellation.complete(result);
+        staked_state_to_js(&mut ctx, result)
+    }
+}
+
+fn staked_state_to_js<'a, C: Context<'a>>(
+    ctx: &mut C,
+    result: std::result::Result<StakedState, NativeError>,
+) -> JsResult<'a, JsValue> {
+    let staked_state = match result {
+        Ok(staked_state) => staked_state,
+        Err(err) => return err.throw(ctx),
+    };
+
+    staked_state_object(ctx, &staked_state).map(|js_staked_state| js_staked_state.upcast())
+}
+
+/// Returns { address, nonce, bonded, unbonded, unbondedFrom, jailedUntil?,
+/// lastSlash? } of the staked state
+pub fn staked_state_object<'a, C: Context<'a>>(
+    ctx: &mut C,
+    staked_state: &StakedState,
+) -> JsResult<'a, JsObject> {
+    let js_staked_state = ctx.empty_object();
+    let address = ctx.string(staked_state.address.to_string());
+    js_staked_state.set(ctx, "address", address)?;
+    let nonce = ctx.string(staked_state.nonce.to_string());
+    js_staked_state.set(ctx, "nonce", nonce)?;
+    let bonded = ctx.string(u64::from(staked_state.bonded).to_string());
+    js_staked_state.set(ctx, "bonded", bonded)?;
+    let unbonded = ctx.string(u64::from(staked_state.unbonded).to_string());
+    js_staked_state.set(ctx, "unbonded", unbonded)?;
+    let unbonded_from = ctx.number(staked_state.unbonded_from as f64);
+    js_staked_state.set(ctx, "unbondedFrom", unbonded_from)?;
+
+    let jailed_until = staked_state
+        .validator
+        .as_ref()
+        .and_then(|validator| validator.jailed_until);
+    if let Some(jailed_until) = jailed_until {
+        let jailed_until = ctx.number(jailed_until as f64);
+        js_staked_state.set(ctx, "jailedUntil", jailed_until)?;
+    }
+
+    if let Some(last_slash) = staked_state.last_slash.as_ref() {
+        let js_last_slash = ctx.empty_object();
+        let kind = ctx.string(format!("{:?}", last_slash.kind));
+        js_last_slash.set(ctx, "kind", kind)?;
+        let time = ctx.number(last_slash.time as f64);
+        js_last_slash.set(ctx, "time", time)?;
+        let amount = ctx.string(u64::from(last_slash.amount).to_string());
+        js_last_slash.set(ctx, "amount", amount)?;
+        js_staked_state.set(ctx, "lastSlash", js_last_slash)?;
+    }
+
+    Ok(js_staked_state)
+}
+
+pub fn register_staking_module(ctx: &mut ModuleContext) -> NeonResult<()> {
+    let js_object = JsObject::new(ctx);
+
+    let query_staked_state_fn = JsFunction::new(ctx, query_staked_state)?;
+    js_object.set(ctx, "queryStakedState", query_staked_state_fn)?;
+
+    let reserve_nonce_fn = JsFunction::new(ctx, reserve_nonce)?;
+    js_object.set(ctx, "reserveNonce", reserve_nonce_fn)?;
+
+    let reconcile_nonces_fn = JsFunction::new(ctx, reconcile_nonces)?;
+    js_object.set(ctx, "reconcileNonces", reconcile_nonces_fn)?;
+
+    let release_nonce_fn = JsFunction::new(ctx, release_nonce)?;
+    js_object.set(ctx, "releaseNonce", release_nonce_fn)?;
+
+    ctx.export_value("staking", js_object)
+}
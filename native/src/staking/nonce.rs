@@ -0,0 +1,239 @@
+//! Nonces reserved for the staking transactions in flight. Every transaction
+//! modifying the staked state, deposit included, increments its nonce, so
+//! concurrent transactions of a staking address take the free nonces after
+//! the nonce of the staked state instead of the same one
+use std::collections::{BTreeSet, HashMap};
+use std::sync::Mutex;
+
+use chain_core::state::account::{Nonce, StakedStateAddress};
+use lazy_static::lazy_static;
+use neon::prelude::*;
+
+use crate::cancellation::CancellationToken;
+use crate::error::{ClientErrorNeonExt, ErrorCode, NativeError};
+use crate::function_types::*;
+use crate::rpc::TendermintRpcClient;
+
+use super::QueryStakedStateTask;
+
+lazy_static! {
+    /// Reserved nonces by staking address, the ones below the nonce of the
+    /// staked state are confirmed and dropped on the next reconcile
+    static ref PENDING_NONCES: Mutex<HashMap<StakedStateAddress, BTreeSet<Nonce>>> =
+        Mutex::new(HashMap::new());
+}
+
+/// reserve the nonce of the next staking transaction of the staking address,
+/// i.e. the lowest nonce at or above the nonce of the staked state which is
+/// not reserved by a transaction still in flight
+/// @arguments
+/// - tendermint_rpc_url: Tendermint RPC endpoint, e.g. http://127.0.0.1:26657
+/// - staking_address: staking address of the transaction
+/// - callback: (optional) node-style callback, query on background thread and
+///     pass the result to callback when provided
+/// @return nonce string, or the task handle { id, cancel() } with callback.
+///     Release it with `releaseNonce` when the transaction is rejected,
+///     otherwise the nonces after it are never confirmed
+pub fn reserve_nonce(mut ctx: FunctionContext) -> JsResult<JsValue> {
+    NonceTask::parse(&mut ctx, NonceAction::Reserve)?.run(&mut ctx)
+}
+
+/// query the nonce of the staked state and drop the reserved nonces it has
+/// confirmed
+/// @arguments
+/// - tendermint_rpc_url: Tendermint RPC endpoint, e.g. http://127.0.0.1:26657
+/// - staking_address: staking address to reconcile
+/// - callback: (optional) node-style callback, query on background thread and
+///     pass the result to callback when provided
+/// @return { nonce, pending }, or the task handle { id, cancel() } with
+///     callback. nonce is the nonce of the staked state and pending the nonce
+///     strings reserved but not confirmed yet
+pub fn reconcile_nonces(mut ctx: FunctionContext) -> JsResult<JsValue> {
+    NonceTask::parse(&mut ctx, NonceAction::Reconcile)?.run(&mut ctx)
+}
+
+/// release the reserved nonce of a rejected transaction
+/// @arguments
+/// - staking_address: staking address of the transaction
+/// - nonce: (optional) nonce string to release, every reserved nonce of the
+///     staking address is released when omitted
+/// @return boolean: whether any nonce was reserved
+pub fn release_nonce(mut ctx: FunctionContext) -> JsResult<JsBoolean> {
+    let staking_address = staking_address_argument(&mut ctx, 0)?;
+    let nonce = match optional_string_argument(&mut ctx, 1)? {
+        Some(nonce) => Some(parse_account_nonce(&mut ctx, nonce)?),
+        None => None,
+    };
+
+    let mut pending_nonces = PENDING_NONCES
+        .lock()
+        .chain_neon(&mut ctx, "Unable to acquire nonce lock")?;
+    let released = match nonce {
+        Some(nonce) => {
+            let pending = pending_nonces.get_mut(&staking_address);
+            let released = pending.map_or(false, |pending| pending.remove(&nonce));
+            if pending_nonces
+                .get(&staking_address)
+                .map_or(false, BTreeSet::is_empty)
+            {
+                pending_nonces.remove(&staking_address);
+            }
+            released
+        }
+        None => pending_nonces
+            .remove(&staking_address)
+            .map_or(false, |pending| !pending.is_empty()),
+    };
+
+    Ok(ctx.boolean(released))
+}
+
+#[derive(Clone, Copy)]
+enum NonceAction {
+    Reserve,
+    Reconcile,
+}
+
+struct NonceReservation {
+    /// Reserved nonce, or nonce of the staked state when reconciling
+    nonce: Nonce,
+    pending: Vec<Nonce>,
+}
+
+struct NonceTask {
+    query: QueryStakedStateTask,
+    action: NonceAction,
+}
+
+impl NonceTask {
+    fn parse(ctx: &mut FunctionContext, action: NonceAction) -> NeonResult<NonceTask> {
+        let tendermint_rpc_url = ctx.argument::<JsString>(0)?.value();
+        let client = TendermintRpcClient::new(&tendermint_rpc_url)
+            .chain_neon(ctx, "Unable to create Tendermint RPC client")?;
+        let staking_address = staking_address_argument(ctx, 1)?;
+
+        Ok(NonceTask {
+            query: QueryStakedStateTask {
+                client,
+                staking_address,
+                cancellation: CancellationToken::default(),
+            },
+            action,
+        })
+    }
+
+    fn run<'a>(mut self, ctx: &mut FunctionContext<'a>) -> JsResult<'a, JsValue> {
+        match callback_argument(ctx)? {
+            Some(callback) => {
+                self.query.cancellation = CancellationToken::register();
+                let handle = self.query.cancellation.to_js(ctx)?;
+                self.schedule(callback);
+
+                Ok(handle.upcast())
+            }
+            None => {
+                let result = self.perform();
+                self.result_to_js(ctx, result)
+            }
+        }
+    }
+
+    fn result_to_js<'a, C: Context<'a>>(
+        &self,
+        ctx: &mut C,
+        result: std::result::Result<NonceReservation, NativeError>,
+    ) -> JsResult<'a, JsValue> {
+        let reservation = match result {
+            Ok(reservation) => reservation,
+            Err(err) => return err.throw(ctx),
+        };
+
+        let nonce = ctx.string(reservation.nonce.to_string());
+        if let NonceAction::Reserve = self.action {
+            return Ok(nonce.upcast());
+        }
+
+        let js_pending = JsArray::new(ctx, reservation.pending.len() as u32);
+        for (i, pending_nonce) in reservation.pending.iter().enumerate() {
+            let pending_nonce = ctx.string(pending_nonce.to_string());
+            js_pending.set(ctx, i as u32, pending_nonce)?;
+        }
+        let js_reservation = ctx.empty_object();
+        js_reservation.set(ctx, "nonce", nonce)?;
+        js_reservation.set(ctx, "pending", js_pending)?;
+
+        Ok(js_reservation.upcast())
+    }
+}
+
+impl Task for NonceTask {
+    type Output = NonceReservation;
+    type Error = NativeError;
+    type JsEvent = JsValue;
+
+    fn perform(&self) -> std::result::Result<NonceReservation, NativeError> {
+        let staked_state = self.query.run_query()?;
+
+        let mut pending_nonces = PENDING_NONCES.lock().map_err(|_| {
+            NativeError::new(ErrorCode::InternalError, "Unable to acquire nonce lock")
+        })?;
+        let pending = pending_nonces
+            .entry(self.query.staking_address)
+            .or_insert_with(BTreeSet::new);
+        // Nonces below the one of the staked state are confirmed
+        *pending = pending.split_off(&staked_state.nonce);
+
+        let nonce = match self.action {
+            NonceAction::Reserve => {
+                // Lowest free nonce, so that a released nonce is reused
+                // before the ones after the reserved
+                let mut nonce = staked_state.nonce;
+                for reserved in pending.iter() {
+                    if *reserved != nonce {
+                        break;
+                    }
+                    nonce += 1;
+                }
+                pending.insert(nonce);
+                nonce
+            }
+            NonceAction::Reconcile => staked_state.nonce,
+        };
+        let reservation = NonceReservation {
+            nonce,
+            pending: pending.iter().cloned().collect(),
+        };
+        if pending.is_empty() {
+            pending_nonces.remove(&self.query.staking_address);
+        }
+
+        Ok(reservation)
+    }
+
+    fn complete(
+        self,
+        mut ctx: TaskContext,
+        result: std::result::Result<NonceReservation, NativeError>,
+    ) -> JsResult<JsValue> {
+        // The nonce reserved by a task cancelled after its query is never
+        // handed over, so it is released again
+        if let (NonceAction::Reserve, Ok(reservation), true) =
+            (self.action, &result, self.query.cancellation.is_cancelled())
+        {
+            release_reserved_nonce(self.query.staking_address, reservation.nonce);
+        }
+        let result = self.query.cancellation.complete(result);
+        self.result_to_js(&mut ctx, result)
+    }
+}
+
+fn release_reserved_nonce(staking_address: StakedStateAddress, nonce: Nonce) {
+    if let Ok(mut pending_nonces) = PENDING_NONCES.lock() {
+        if let Some(pending) = pending_nonces.get_mut(&staking_address) {
+            pending.remove(&nonce);
+            if pending.is_empty() {
+                pending_nonces.remove(&staking_address);
+            }
+        }
+    }
+}
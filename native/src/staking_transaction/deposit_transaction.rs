@@ -7,11 +7,12 @@ use chain_core::tx::data::input::TxoPointer;
 use chain_core::tx::witness::TxInWitness;
 use chain_core::tx::TransactionId;
 use client_common::SignedTransaction;
-use parity_scale_codec::{Decode, Encode};
+use parity_scale_codec::Decode;
 
 use crate::common::Features;
-use crate::error::ClientErrorNeonExt;
+use crate::error::{ClientErrorNeonExt, ErrorCode};
 use crate::function_types::*;
+use crate::signer::KeyPairSigner;
 use crate::tx_aux::signed_transaction_to_hex;
 
 pub fn build_raw_deposit_transaction(mut ctx: FunctionContext) -> JsResult<JsObject> {
@@ -20,12 +21,7 @@ pub fn build_raw_deposit_transaction(mut ctx: FunctionContext) -> JsResult<JsObj
 
     let tx = DepositBondTx::new(options.inputs, options.to_address, attributes);
 
-    let raw_tx = tx.encode();
-    let mut raw_tx_buffer = ctx.buffer(raw_tx.len() as u32)?;
-    ctx.borrow_mut(&mut raw_tx_buffer, |data| {
-        let data = data.as_mut_slice();
-        data.copy_from_slice(&raw_tx)
-    });
+    let raw_tx_buffer = encode_to_buffer(&mut ctx, &tx)?;
 
     let tx_id = tx.id();
     let tx_id = ctx.string(hex::encode(tx_id));
@@ -41,24 +37,97 @@ pub fn build_raw_deposit_transaction(mut ctx: FunctionContext) -> JsResult<JsObj
     Ok(return_object)
 }
 
-pub fn deposit_transaction_to_hex(mut ctx: FunctionContext) -> JsResult<JsBuffer> {
+pub fn deposit_transaction_to_hex(mut ctx: FunctionContext) -> JsResult<JsValue> {
     let deposit_bond_tx = deposit_bond_tx_argument(&mut ctx, 0)?;
     let tx_in_witness_vec = tx_in_witness_vec_argument(&mut ctx, 1)?;
     let tendermint_address = ctx.argument::<JsString>(2)?.value();
     let features = Features::argument(&mut ctx, 3)?;
+    let output = output_buffer_argument(&mut ctx, 4)?;
+    let callback = callback_argument(&mut ctx)?;
 
     let witness = tx_in_witness_vec.into();
     let signed_transaction = SignedTransaction::DepositStakeTransaction(deposit_bond_tx, witness);
 
-    signed_transaction_to_hex(&mut ctx, signed_transaction, &tendermint_address, features)
+    signed_transaction_to_hex(
+        &mut ctx,
+        signed_transaction,
+        &tendermint_address,
+        features,
+        output,
+        callback,
+    )
+}
+
+/// build and sign deposit stake transaction in one go
+/// @arguments
+/// - inputs: transaction output pointers to deposit
+/// - to_address: staking address to deposit to
+/// - network: network handle or chain hex id Buffer of the network
+/// - key_pairs: key pair to sign each input, in the same order as inputs
+/// - tendermint_address: Tendermint websocket address for obfuscation
+/// - features: native features
+/// - output: (optional) Buffer to encode the transaction into, the number of
+///     bytes written is returned instead of a new Buffer. Not supported with
+///     callback
+/// - callback: (optional) node-style callback, obfuscate on background thread
+///     and pass the result to callback when provided
+/// @return broadcast-able DepositStakeTx
+pub fn build_deposit_stake(mut ctx: FunctionContext) -> JsResult<JsValue> {
+    let inputs = txo_pointer_vec_argument(&mut ctx, 0)?;
+    let to_address = staking_address_argument(&mut ctx, 1)?;
+    let chain_hex_id = chain_hex_id_argument(&mut ctx, 2)?;
+    let key_pairs = ctx.argument::<JsArray>(3)?.to_vec(&mut ctx)?;
+    let tendermint_address = ctx.argument::<JsString>(4)?.value();
+    let features = Features::argument(&mut ctx, 5)?;
+    let output = output_buffer_argument(&mut ctx, 6)?;
+    let callback = callback_argument(&mut ctx)?;
+
+    if key_pairs.len() != inputs.len() {
+        return ctx.throw_error("Number of key pairs should match number of inputs");
+    }
+
+    let attributes = StakedStateOpAttributes::new(chain_hex_id);
+    let deposit_bond_tx = DepositBondTx::new(inputs, to_address, attributes);
+    let tx_id = deposit_bond_tx.id();
+
+    let tx_in_witness_vec = key_pairs
+        .iter()
+        .map(|&key_pair| {
+            let key_pair = key_pair
+                .downcast_or_throw::<JsObject, FunctionContext>(&mut ctx)
+                .chain_neon(&mut ctx, "Unable to downcast KeyPair")?;
+            let (private_key, public_key) = parse_key_pair(&mut ctx, key_pair)?;
+            let signer = KeyPairSigner::new(private_key, public_key)
+                .chain_neon(&mut ctx, "Unable to create KeyPair signer")?;
+
+            signer
+                .schnorr_sign_txid(&tx_id)
+                .chain_neon(&mut ctx, "Unable to sign transaction")
+        })
+        .collect::<NeonResult<Vec<TxInWitness>>>()?;
+
+    let witness = tx_in_witness_vec.into();
+    let signed_transaction = SignedTransaction::DepositStakeTransaction(deposit_bond_tx, witness);
+
+    signed_transaction_to_hex(
+        &mut ctx,
+        signed_transaction,
+        &tendermint_address,
+        features,
+        output,
+        callback,
+    )
 }
 
 pub fn deposit_bond_tx_argument(ctx: &mut FunctionContext, i: i32) -> NeonResult<DepositBondTx> {
     let deposit_bond_tx = ctx.argument::<JsBuffer>(i)?;
     let mut deposit_bond_tx = deposit_bond_tx.borrow(&ctx.lock()).as_slice();
 
-    DepositBondTx::decode(&mut deposit_bond_tx)
-        .chain_neon(ctx, "Unable to decode raw transaction bytes")
+    DepositBondTx::decode(&mut deposit_bond_tx).chain_neon_with_code(
+        ctx,
+        ErrorCode::DeserializationError,
+        "Unable to decode raw transaction bytes",
+    )
 }
 
 pub fn tx_in_witness_vec_argument(
@@ -69,12 +138,13 @@ pub fn tx_in_witness_vec_argument(
     tx_in_witnesses
         .iter()
         .map(|&tx_in_witness| {
-            let tx_in_witness = tx_in_witness
-                .downcast_or_throw::<JsBuffer, FunctionContext>(ctx)
-                .chain_neon(ctx, "Unable to downcast witness")?;
-            let mut tx_in_witness = tx_in_witness.borrow(&ctx.lock()).as_slice();
+            let tx_in_witness = parse_bytes(ctx, tx_in_witness, "witness")?;
 
-            TxInWitness::decode(&mut tx_in_witness).chain_neon(ctx, "Unable to decode witness")
+            TxInWitness::decode(&mut tx_in_witness.as_slice()).chain_neon_with_code(
+                ctx,
+                ErrorCode::DeserializationError,
+                "Unable to decode witness",
+            )
         })
         .collect()
 }
@@ -87,9 +157,11 @@ struct BuildRawDepositTransactionOption {
 
 impl BuildRawDepositTransactionOption {
     fn parse(ctx: &mut FunctionContext) -> NeonResult<BuildRawDepositTransactionOption> {
-        let options = ctx
-            .argument::<JsObject>(0)
-            .chain_neon(ctx, "Unable to deserialize options object")?;
+        let options = ctx.argument::<JsObject>(0).chain_neon_with_code(
+            ctx,
+            ErrorCode::DeserializationError,
+            "Unable to deserialize options object",
+        )?;
 
         let inputs = options
             .get(ctx, "inputs")?
@@ -103,15 +175,13 @@ impl BuildRawDepositTransactionOption {
             .downcast_or_throw::<JsString, FunctionContext>(ctx)
             .chain_neon(ctx, "Unable to downcast toAddress")?
             .value();
-        let to_address = StakedStateAddress::from_str(&to_address)
-            .chain_neon(ctx, "Unable to deserialize staking address")?;
-
-        let chain_hex_id = options
-            .get(ctx, "chainHexId")?
-            .downcast_or_throw::<JsBuffer, FunctionContext>(ctx)
-            .chain_neon(ctx, "Unable to downcast chainHexId")?;
-        let chain_hex_id = ctx.borrow(&chain_hex_id, |data| data.as_slice::<u8>());
-        let chain_hex_id = chain_hex_id_from_vec(ctx, chain_hex_id.to_vec())?;
+        let to_address = StakedStateAddress::from_str(&to_address).chain_neon_with_code(
+            ctx,
+            ErrorCode::InvalidAddress,
+            "Unable to deserialize staking address",
+        )?;
+
+        let chain_hex_id = chain_hex_id_option(ctx, *options)?;
 
         Ok(BuildRawDepositTransactionOption {
             inputs,
@@ -1,14 +1,21 @@
 mod deposit_transaction;
+mod offline;
 mod unbond_transaction;
 mod withdraw_unbonded_transaction;
 
 use neon::prelude::*;
 
-use deposit_transaction::{build_raw_deposit_transaction, deposit_transaction_to_hex};
-use unbond_transaction::{build_raw_unbond_transaction, unbond_transaction_to_hex};
+use deposit_transaction::{
+    build_deposit_stake, build_raw_deposit_transaction, deposit_transaction_to_hex,
+};
+use offline::{export_unsigned, finalize_unsigned};
+use unbond_transaction::{
+    build_raw_unbond_transaction, build_unbond_stake, unbond_transaction_to_hex,
+    unbond_transaction_with_witness_to_hex,
+};
 use withdraw_unbonded_transaction::{
-    build_raw_withdraw_unbonded_transaction, estimate_withdraw_unbonded_transaction_fee,
-    withdraw_unbonded_transaction_to_obfuscated_hex,
+    build_raw_withdraw_unbonded_transaction, build_withdraw_unbonded,
+    estimate_withdraw_unbonded_transaction_fee, withdraw_unbonded_transaction_to_obfuscated_hex,
     withdraw_unbonded_transaction_to_signed_plain_hex, withdraw_unbonded_transaction_to_witness,
 };
 
@@ -29,6 +36,9 @@ pub fn register_staking_transaction_module(ctx: &mut ModuleContext) -> NeonResul
         deposit_transaction_to_hex_fn,
     )?;
 
+    let build_deposit_stake_fn = JsFunction::new(ctx, build_deposit_stake)?;
+    js_object.set(ctx, "buildDepositStake", build_deposit_stake_fn)?;
+
     let build_raw_unbond_transaction_fn = JsFunction::new(ctx, build_raw_unbond_transaction)?;
     js_object.set(
         ctx,
@@ -39,6 +49,17 @@ pub fn register_staking_transaction_module(ctx: &mut ModuleContext) -> NeonResul
     let unbond_transaction_to_hex_fn = JsFunction::new(ctx, unbond_transaction_to_hex)?;
     js_object.set(ctx, "unbondTransactionToHex", unbond_transaction_to_hex_fn)?;
 
+    let build_unbond_stake_fn = JsFunction::new(ctx, build_unbond_stake)?;
+    js_object.set(ctx, "buildUnbondStake", build_unbond_stake_fn)?;
+
+    let unbond_transaction_with_witness_to_hex_fn =
+        JsFunction::new(ctx, unbond_transaction_with_witness_to_hex)?;
+    js_object.set(
+        ctx,
+        "unbondTransactionWithWitnessToHex",
+        unbond_transaction_with_witness_to_hex_fn,
+    )?;
+
     let build_raw_withdraw_unbonded_transaction_fn =
         JsFunction::new(ctx, build_raw_withdraw_unbonded_transaction)?;
     js_object.set(
@@ -79,5 +100,14 @@ pub fn register_staking_transaction_module(ctx: &mut ModuleContext) -> NeonResul
         withdraw_unbonded_transaction_to_obfuscated_hex_fn,
     )?;
 
+    let build_withdraw_unbonded_fn = JsFunction::new(ctx, build_withdraw_unbonded)?;
+    js_object.set(ctx, "buildWithdrawUnbonded", build_withdraw_unbonded_fn)?;
+
+    let export_unsigned_fn = JsFunction::new(ctx, export_unsigned)?;
+    js_object.set(ctx, "exportUnsigned", export_unsigned_fn)?;
+
+    let finalize_unsigned_fn = JsFunction::new(ctx, finalize_unsigned)?;
+    js_object.set(ctx, "finalizeUnsigned", finalize_unsigned_fn)?;
+
     ctx.export_value("stakingTransaction", js_object)
 }
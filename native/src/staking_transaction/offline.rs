@@ -0,0 +1,359 @@
+//! Export of unsigned staking transactions for cold signing
+use std::str::FromStr;
+
+use chain_core::init::network::Network;
+use chain_core::state::account::{
+    DepositBondTx, StakedStateAddress, StakedStateOpWitness, UnbondTx, WithdrawUnbondedTx,
+};
+use chain_core::tx::data::address::ExtendedAddr;
+use chain_core::tx::data::output::TxOut;
+use chain_core::tx::witness::TxInWitness;
+use chain_core::tx::TransactionId;
+use chain_tx_validation::witness::{verify_tx_address, verify_tx_recover_address};
+use neon::prelude::*;
+use parity_scale_codec::Decode;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+use crate::error::{ClientErrorNeonExt, ErrorCode};
+use crate::function_types::*;
+use crate::offline::{
+    check_tx_id, unsigned_transaction_argument, unsigned_transaction_to_js, unsigned_tx_bytes,
+    witnesses_argument, ExportedOutput, SigningPayload, UnsignedTransaction, ECDSA_SCHEME,
+    SCHNORR_SCHEME, UNSIGNED_TRANSACTION_VERSION,
+};
+
+const DEPOSIT_TX_TYPE: &str = "Deposit";
+const UNBOND_TX_TYPE: &str = "Unbond";
+const WITHDRAW_UNBONDED_TX_TYPE: &str = "WithdrawUnbonded";
+
+#[derive(Debug, Serialize)]
+#[serde(untagged)]
+enum StakingDetails {
+    Deposit(DepositDetails),
+    Unbond(UnbondDetails),
+    WithdrawUnbonded(WithdrawUnbondedDetails),
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct DepositDetails {
+    inputs: Vec<ExportedInput>,
+    to_address: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct UnbondDetails {
+    from_address: String,
+    nonce: String,
+    amount: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct WithdrawUnbondedDetails {
+    from_address: String,
+    nonce: String,
+    outputs: Vec<ExportedOutput>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ExportedInput {
+    prev_tx_id: String,
+    prev_index: u16,
+    prev_output: ExportedOutput,
+}
+
+/// export unsigned staking transaction as JSON with everything needed to sign
+/// the transaction offline
+/// @arguments
+/// - tx_type: Deposit, Unbond or WithdrawUnbonded
+/// - unsigned_raw_tx: unsignedRawTx Buffer of the raw transaction builder
+/// - options: { prevOutputs, stakingAddress }, prevOutputs is the previous
+///     output { address, value, validFrom? } of each input in order for
+///     Deposit, stakingAddress is the unbonded staking address for
+///     WithdrawUnbonded, so that the witnesses can be verified on finalize
+/// @return unsigned transaction JSON string { version, txType, txId,
+///     chainHexId, unsignedTx, signingPayloads: [{ signer, payload, scheme }],
+///     details }
+pub fn export_unsigned(mut ctx: FunctionContext) -> JsResult<JsString> {
+    let tx_type = ctx.argument::<JsString>(0)?.value();
+    let unsigned_raw_tx = u8_buffer_argument(&mut ctx, 1)?;
+
+    let (tx_id, chain_hex_id, signers, details) = match tx_type.as_str() {
+        DEPOSIT_TX_TYPE => {
+            let tx = decode_raw_tx::<DepositBondTx>(&mut ctx, &unsigned_raw_tx)?;
+            let network = network_from_chain_hex_id(tx.attributes.chain_hex_id);
+            let prev_outputs = prev_outputs_option(&mut ctx, 2, network)?;
+            if prev_outputs.len() != tx.inputs.len() {
+                return ctx.throw_error(format!(
+                    "Expected {} previous outputs but got {}",
+                    tx.inputs.len(),
+                    prev_outputs.len()
+                ));
+            }
+
+            let signers = (0..tx.inputs.len())
+                .map(|i| (format!("input {}", i), SCHNORR_SCHEME))
+                .collect();
+            let mut inputs = Vec::with_capacity(tx.inputs.len());
+            for (input, prev_output) in tx.inputs.iter().zip(prev_outputs.iter()) {
+                let prev_output = ExportedOutput::from_tx_out(prev_output, network)
+                    .chain_neon(&mut ctx, "Unable to export input previous output")?;
+                inputs.push(ExportedInput {
+                    prev_tx_id: hex::encode(input.id),
+                    prev_index: input.index,
+                    prev_output,
+                });
+            }
+            let details = StakingDetails::Deposit(DepositDetails {
+                inputs,
+                to_address: tx.to_staked_account.to_string(),
+            });
+
+            (tx.id(), tx.attributes.chain_hex_id, signers, details)
+        }
+        UNBOND_TX_TYPE => {
+            let tx = decode_raw_tx::<UnbondTx>(&mut ctx, &unsigned_raw_tx)?;
+            let signers = vec![(tx.from_staked_account.to_string(), ECDSA_SCHEME)];
+            let details = StakingDetails::Unbond(UnbondDetails {
+                from_address: tx.from_staked_account.to_string(),
+                nonce: tx.nonce.to_string(),
+                amount: u64::from(tx.value).to_string(),
+            });
+
+            (tx.id(), tx.attributes.chain_hex_id, signers, details)
+        }
+        WITHDRAW_UNBONDED_TX_TYPE => {
+            let tx = decode_raw_tx::<WithdrawUnbondedTx>(&mut ctx, &unsigned_raw_tx)?;
+            let network = network_from_chain_hex_id(tx.attributes.chain_hex_id);
+            let staking_address = staking_address_option(&mut ctx, 2)?;
+            let signers = vec![(staking_address.to_string(), ECDSA_SCHEME)];
+            let outputs = tx
+                .outputs
+                .iter()
+                .map(|output| ExportedOutput::from_tx_out(output, network))
+                .collect::<client_common::Result<Vec<ExportedOutput>>>()
+                .chain_neon(&mut ctx, "Unable to export outputs")?;
+            let details = StakingDetails::WithdrawUnbonded(WithdrawUnbondedDetails {
+                from_address: staking_address.to_string(),
+                nonce: tx.nonce.to_string(),
+                outputs,
+            });
+
+            (tx.id(), tx.attributes.chain_hex_id, signers, details)
+        }
+        _ => {
+            return ctx.throw_error(format!("Unsupported staking transaction type {}", tx_type));
+        }
+    };
+
+    let tx_id = hex::encode(tx_id);
+    let signing_payloads = signers
+        .into_iter()
+        .map(|(signer, scheme)| SigningPayload {
+            signer,
+            payload: tx_id.clone(),
+            scheme: scheme.to_owned(),
+        })
+        .collect();
+
+    let unsigned_transaction = UnsignedTransaction {
+        version: UNSIGNED_TRANSACTION_VERSION,
+        tx_type,
+        tx_id,
+        chain_hex_id: hex::encode([chain_hex_id]),
+        unsigned_tx: hex::encode(&unsigned_raw_tx),
+        signing_payloads,
+        details,
+    };
+
+    unsigned_transaction_to_js(&mut ctx, &unsigned_transaction)
+}
+
+/// finalize exported unsigned staking transaction with the witnesses. Each
+/// witness is verified against the address of its signer
+/// @arguments
+/// - unsigned_transaction: unsigned transaction JSON string
+/// - witnesses: [Buffer] in the order of signingPayloads, TxInWitness of each
+///     input for Deposit, StakedStateOpWitness for Unbond and
+///     WithdrawUnbonded
+/// @return { txType, unsignedRawTx, witnesses } for Deposit, to be passed to
+///     depositTransactionToHex, or { txType, unsignedRawTx, witness } for
+///     Unbond and WithdrawUnbonded
+pub fn finalize_unsigned(mut ctx: FunctionContext) -> JsResult<JsObject> {
+    let unsigned_transaction: UnsignedTransaction<serde_json::Value> =
+        unsigned_transaction_argument(
+            &mut ctx,
+            0,
+            &[DEPOSIT_TX_TYPE, UNBOND_TX_TYPE, WITHDRAW_UNBONDED_TX_TYPE],
+        )?;
+    let witnesses = witnesses_argument(&mut ctx, 1, &unsigned_transaction)?;
+    let unsigned_raw_tx = unsigned_tx_bytes(&mut ctx, &unsigned_transaction)?;
+
+    let finalized = ctx.empty_object();
+    let tx_type = ctx.string(&unsigned_transaction.tx_type);
+    finalized.set(&mut ctx, "txType", tx_type)?;
+    let js_unsigned_raw_tx = bytes_to_buffer(&mut ctx, &unsigned_raw_tx)?;
+    finalized.set(&mut ctx, "unsignedRawTx", js_unsigned_raw_tx)?;
+
+    match unsigned_transaction.tx_type.as_str() {
+        DEPOSIT_TX_TYPE => {
+            let tx = decode_raw_tx::<DepositBondTx>(&mut ctx, &unsigned_raw_tx)?;
+            check_tx_id(&mut ctx, &unsigned_transaction, &tx.id())?;
+            let details: DepositDetails = details_of(&mut ctx, &unsigned_transaction)?;
+            let input_matched = details.inputs.len() == tx.inputs.len()
+                && details
+                    .inputs
+                    .iter()
+                    .zip(tx.inputs.iter())
+                    .all(|(exported, input)| {
+                        exported.prev_tx_id.to_lowercase() == hex::encode(input.id)
+                            && exported.prev_index == input.index
+                    });
+            if !input_matched {
+                return ctx.throw_error("Inputs do not match the unsigned transaction");
+            }
+
+            let network = network_from_chain_hex_id(tx.attributes.chain_hex_id);
+            let js_witnesses = JsArray::new(&mut ctx, witnesses.len() as u32);
+            for (i, (witness, input)) in witnesses.iter().zip(details.inputs.iter()).enumerate() {
+                let decoded_witness = decode_witness::<TxInWitness>(&mut ctx, witness)?;
+                let address = ExtendedAddr::from_cro(&input.prev_output.address, network)
+                    .chain_neon_with_code(
+                        &mut ctx,
+                        ErrorCode::InvalidAddress,
+                        format!("Unable to deserialize address of input {}", i),
+                    )?;
+                verify_tx_address(&decoded_witness, &tx.id(), &address).chain_neon_with_code(
+                    &mut ctx,
+                    ErrorCode::InvalidSignature,
+                    format!("Witness {} is not signed by the address of input {}", i, i),
+                )?;
+
+                let js_witness = bytes_to_buffer(&mut ctx, witness)?;
+                js_witnesses.set(&mut ctx, i as u32, js_witness)?;
+            }
+            finalized.set(&mut ctx, "witnesses", js_witnesses)?;
+        }
+        UNBOND_TX_TYPE => {
+            let tx = decode_raw_tx::<UnbondTx>(&mut ctx, &unsigned_raw_tx)?;
+            check_tx_id(&mut ctx, &unsigned_transaction, &tx.id())?;
+
+            let witness = decode_witness::<StakedStateOpWitness>(&mut ctx, &witnesses[0])?;
+            let address = verify_tx_recover_address(&witness, &tx.id())
+                .chain_neon(&mut ctx, "Invalid witness")?;
+            if address != tx.from_staked_account {
+                return ctx.throw_error("Witness is not signed by the unbonding staking address");
+            }
+
+            let js_witness = bytes_to_buffer(&mut ctx, &witnesses[0])?;
+            finalized.set(&mut ctx, "witness", js_witness)?;
+        }
+        _ => {
+            let tx = decode_raw_tx::<WithdrawUnbondedTx>(&mut ctx, &unsigned_raw_tx)?;
+            check_tx_id(&mut ctx, &unsigned_transaction, &tx.id())?;
+            let details: WithdrawUnbondedDetails = details_of(&mut ctx, &unsigned_transaction)?;
+            let staking_address = StakedStateAddress::from_str(&details.from_address)
+                .chain_neon_with_code(
+                    &mut ctx,
+                    ErrorCode::InvalidAddress,
+                    "Unable to deserialize fromAddress",
+                )?;
+
+            let witness = decode_witness::<StakedStateOpWitness>(&mut ctx, &witnesses[0])?;
+            let address = verify_tx_recover_address(&witness, &tx.id())
+                .chain_neon(&mut ctx, "Invalid witness")?;
+            if address != staking_address {
+                return ctx.throw_error("Witness is not signed by the unbonded staking address");
+            }
+
+            let js_witness = bytes_to_buffer(&mut ctx, &witnesses[0])?;
+            finalized.set(&mut ctx, "witness", js_witness)?;
+        }
+    }
+
+    Ok(finalized)
+}
+
+/// Returns the previous outputs of the options argument, in the order of the
+/// inputs
+fn prev_outputs_option(
+    ctx: &mut FunctionContext,
+    i: i32,
+    network: Network,
+) -> NeonResult<Vec<TxOut>> {
+    let options = typed_argument::<JsObject>(ctx, i, "options", "{ prevOutputs } object")?;
+    let prev_outputs = options
+        .get(ctx, "prevOutputs")?
+        .downcast_or_throw::<JsArray, FunctionContext>(ctx)
+        .chain_neon(ctx, "Unable to downcast prevOutputs")?
+        .to_vec(ctx)?;
+
+    prev_outputs
+        .iter()
+        .map(|&prev_output| {
+            let prev_output = prev_output
+                .downcast_or_throw::<JsObject, FunctionContext>(ctx)
+                .chain_neon(ctx, "Unable to downcast previous output")?;
+            parse_output(ctx, prev_output, network)
+        })
+        .collect()
+}
+
+/// Returns the unbonded staking address of the options argument
+fn staking_address_option(ctx: &mut FunctionContext, i: i32) -> NeonResult<StakedStateAddress> {
+    let options = typed_argument::<JsObject>(ctx, i, "options", "{ stakingAddress } object")?;
+    let staking_address = options
+        .get(ctx, "stakingAddress")?
+        .downcast_or_throw::<JsString, FunctionContext>(ctx)
+        .chain_neon(ctx, "Unable to downcast stakingAddress")?
+        .value();
+
+    StakedStateAddress::from_str(&staking_address).chain_neon_with_code(
+        ctx,
+        ErrorCode::InvalidAddress,
+        "Unable to deserialize stakingAddress",
+    )
+}
+
+/// Returns the details of the unsigned transaction as the details of its
+/// transaction type
+fn details_of<D: DeserializeOwned>(
+    ctx: &mut FunctionContext,
+    unsigned_transaction: &UnsignedTransaction<serde_json::Value>,
+) -> NeonResult<D> {
+    serde_json::from_value(unsigned_transaction.details.clone()).chain_neon_with_code(
+        ctx,
+        ErrorCode::DeserializationError,
+        "Unable to deserialize details of unsigned transaction",
+    )
+}
+
+/// Decodes the raw transaction, which should have no bytes left over
+fn decode_raw_tx<T: Decode>(ctx: &mut FunctionContext, raw_tx: &[u8]) -> NeonResult<T> {
+    let mut raw_tx = raw_tx;
+
+    let tx = T::decode(&mut raw_tx).chain_neon_with_code(
+        ctx,
+        ErrorCode::DeserializationError,
+        "Unable to decode raw transaction bytes",
+    )?;
+    if !raw_tx.is_empty() {
+        return ctx.throw_error("Unexpected trailing bytes after transaction");
+    }
+
+    Ok(tx)
+}
+
+fn decode_witness<W: Decode>(ctx: &mut FunctionContext, witness: &[u8]) -> NeonResult<W> {
+    let mut witness = witness;
+
+    W::decode(&mut witness).chain_neon_with_code(
+        ctx,
+        ErrorCode::DeserializationError,
+        "Unable to decode witness",
+    )
+}
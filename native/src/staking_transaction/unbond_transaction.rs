@@ -7,9 +7,11 @@ use chain_core::state::account::{
     Nonce, StakedStateAddress, StakedStateOpAttributes, StakedStateOpWitness, UnbondTx,
 };
 use chain_core::tx::{TransactionId, TxAux, TxPublicAux};
-use parity_scale_codec::{Decode, Encode};
+use chain_tx_validation::witness::verify_tx_recover_address;
+use client_common::{PrivateKey, PublicKey};
+use parity_scale_codec::Decode;
 
-use crate::error::ClientErrorNeonExt;
+use crate::error::{ClientErrorNeonExt, ErrorCode};
 use crate::function_types::*;
 use crate::signer::KeyPairSigner;
 use crate::tx_aux::tx_aux_to_hex;
@@ -25,12 +27,7 @@ pub fn build_raw_unbond_transaction(mut ctx: FunctionContext) -> JsResult<JsObje
         attributes,
     );
 
-    let raw_tx = tx.encode();
-    let mut raw_tx_buffer = ctx.buffer(raw_tx.len() as u32)?;
-    ctx.borrow_mut(&mut raw_tx_buffer, |data| {
-        let data = data.as_mut_slice();
-        data.copy_from_slice(&raw_tx)
-    });
+    let raw_tx_buffer = encode_to_buffer(&mut ctx, &tx)?;
 
     let tx_id = tx.id();
     let tx_id = ctx.string(hex::encode(tx_id));
@@ -49,24 +46,83 @@ pub fn build_raw_unbond_transaction(mut ctx: FunctionContext) -> JsResult<JsObje
 pub fn unbond_transaction_to_hex(mut ctx: FunctionContext) -> JsResult<JsBuffer> {
     let unbond_tx = unbond_tx_argument(&mut ctx, 0)?;
     let key_pair = key_pair_argument(&mut ctx, 1)?;
+
+    let tx_aux = sign_unbond_tx(&mut ctx, unbond_tx, key_pair)?;
+
+    tx_aux_to_hex(&mut ctx, tx_aux)
+}
+
+/// build and sign unbond stake transaction in one go
+/// @arguments
+/// - options: { stakingAddress, nonce, amount, network | chainHexId }, where
+///     nonce is the current nonce of the staking account
+/// - key_pair: key pair of the staking address
+/// @return broadcast-able UnbondStakeTx
+pub fn build_unbond_stake(mut ctx: FunctionContext) -> JsResult<JsBuffer> {
+    let options = BuildUnbondTransactionOptions::parse(&mut ctx)?;
+    let key_pair = key_pair_argument(&mut ctx, 1)?;
+
+    let attributes = StakedStateOpAttributes::new(options.chain_hex_id);
+    let unbond_tx = UnbondTx::new(
+        options.staking_address,
+        options.nonce,
+        options.amount,
+        attributes,
+    );
+
+    let tx_aux = sign_unbond_tx(&mut ctx, unbond_tx, key_pair)?;
+
+    tx_aux_to_hex(&mut ctx, tx_aux)
+}
+
+/// attach externally produced witness to unbond stake transaction
+/// @arguments
+/// - unsigned_raw_tx: raw UnbondTx Buffer
+/// - witness: StakedStateOpWitness of the transaction id
+/// @return broadcast-able UnbondStakeTx
+pub fn unbond_transaction_with_witness_to_hex(mut ctx: FunctionContext) -> JsResult<JsBuffer> {
+    let unbond_tx = unbond_tx_argument(&mut ctx, 0)?;
+    let witness = ctx.argument::<JsValue>(1)?;
+    let witness = parse_staked_state_op_witness(&mut ctx, witness)?;
+
+    let address = verify_tx_recover_address(&witness, &unbond_tx.id())
+        .chain_neon(&mut ctx, "Invalid witness")?;
+    if address != unbond_tx.from_staked_account {
+        return ctx.throw_error("Witness is not signed by the unbonding staking address");
+    }
+
+    let tx_aux = TxAux::PublicTx(TxPublicAux::UnbondStakeTx(unbond_tx, witness));
+
+    tx_aux_to_hex(&mut ctx, tx_aux)
+}
+
+fn sign_unbond_tx(
+    ctx: &mut FunctionContext,
+    unbond_tx: UnbondTx,
+    key_pair: (PrivateKey, PublicKey),
+) -> NeonResult<TxAux> {
     let signer = KeyPairSigner::new(key_pair.0, key_pair.1)
-        .chain_neon(&mut ctx, "Unable to create KeyPair signer")?;
+        .chain_neon(ctx, "Unable to create KeyPair signer")?;
 
     let signature = signer
         .sign(&unbond_tx.id())
         .map(StakedStateOpWitness::new)
-        .chain_neon(&mut ctx, "Error when signing transaction")?;
-
-    let tx_aux = TxAux::PublicTx(TxPublicAux::UnbondStakeTx(unbond_tx, signature));
+        .chain_neon(ctx, "Error when signing transaction")?;
 
-    tx_aux_to_hex(&mut ctx, tx_aux)
+    Ok(TxAux::PublicTx(TxPublicAux::UnbondStakeTx(
+        unbond_tx, signature,
+    )))
 }
 
 fn unbond_tx_argument(ctx: &mut FunctionContext, i: i32) -> NeonResult<UnbondTx> {
     let unbond_tx = ctx.argument::<JsBuffer>(i)?;
     let mut unbond_tx = unbond_tx.borrow(&ctx.lock()).as_slice();
 
-    UnbondTx::decode(&mut unbond_tx).chain_neon(ctx, "Unable to decode raw transaction bytes")
+    UnbondTx::decode(&mut unbond_tx).chain_neon_with_code(
+        ctx,
+        ErrorCode::DeserializationError,
+        "Unable to decode raw transaction bytes",
+    )
 }
 
 struct BuildUnbondTransactionOptions {
@@ -78,22 +134,27 @@ struct BuildUnbondTransactionOptions {
 
 impl BuildUnbondTransactionOptions {
     fn parse(ctx: &mut FunctionContext) -> NeonResult<BuildUnbondTransactionOptions> {
-        let options = ctx
-            .argument::<JsObject>(0)
-            .chain_neon(ctx, "Unable to deserialize options object")?;
+        let options = ctx.argument::<JsObject>(0).chain_neon_with_code(
+            ctx,
+            ErrorCode::DeserializationError,
+            "Unable to deserialize options object",
+        )?;
 
         let staking_address = options
             .get(ctx, "stakingAddress")?
             .downcast_or_throw::<JsString, FunctionContext>(ctx)
             .chain_neon(ctx, "Unable to downcast stakingAddress")?
             .value();
-        let staking_address = StakedStateAddress::from_str(&staking_address)
-            .chain_neon(ctx, "Unable to deserialize stakingAddress")?;
+        let staking_address = StakedStateAddress::from_str(&staking_address).chain_neon_with_code(
+            ctx,
+            ErrorCode::InvalidAddress,
+            "Unable to deserialize stakingAddress",
+        )?;
 
         let nonce = options
             .get(ctx, "nonce")?
             .downcast_or_throw::<JsString, FunctionContext>(ctx)
-            .chain_neon(ctx, "Unable to downcast stakingAddress")?
+            .chain_neon(ctx, "Unable to downcast nonce")?
             .value();
         let nonce = parse_account_nonce(ctx, nonce)?;
 
@@ -102,14 +163,13 @@ impl BuildUnbondTransactionOptions {
             .downcast_or_throw::<JsString, FunctionContext>(ctx)
             .chain_neon(ctx, "Unable to downcast amount")?
             .value();
-        let amount = Coin::from_str(&amount).chain_neon(ctx, "Unable to deserialize amount")?;
-
-        let chain_hex_id = options
-            .get(ctx, "chainHexId")?
-            .downcast_or_throw::<JsBuffer, FunctionContext>(ctx)
-            .chain_neon(ctx, "Unable to downcast chainHexId")?;
-        let chain_hex_id = chain_hex_id.borrow(&ctx.lock()).as_slice().to_vec();
-        let chain_hex_id = chain_hex_id_from_vec(ctx, chain_hex_id)?;
+        let amount = Coin::from_str(&amount).chain_neon_with_code(
+            ctx,
+            ErrorCode::DeserializationError,
+            "Unable to deserialize amount",
+        )?;
+
+        let chain_hex_id = chain_hex_id_option(ctx, *options)?;
 
         Ok(BuildUnbondTransactionOptions {
             staking_address,
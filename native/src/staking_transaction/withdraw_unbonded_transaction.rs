@@ -8,13 +8,13 @@ use chain_core::tx::fee::FeeAlgorithm;
 use chain_core::tx::TransactionId;
 use client_common::{PublicKey, SignedTransaction};
 use client_core::signer::DummySigner;
-use parity_scale_codec::{Decode, Encode};
+use parity_scale_codec::Decode;
 
 use crate::common::Features;
-use crate::error::ClientErrorNeonExt;
+use crate::error::{ClientErrorNeonExt, ErrorCode};
 use crate::function_types::*;
 use crate::signer::KeyPairSigner;
-use crate::tx_aux::{signed_transaction_to_tx_aux, tx_aux_to_hex};
+use crate::tx_aux::signed_transaction_to_hex;
 
 pub fn build_raw_withdraw_unbonded_transaction(mut ctx: FunctionContext) -> JsResult<JsObject> {
     let options = BuildWithdrawUnbondedTransactionOptions::parse(&mut ctx)?;
@@ -29,12 +29,7 @@ pub fn build_raw_withdraw_unbonded_transaction(mut ctx: FunctionContext) -> JsRe
 
     let tx = WithdrawUnbondedTx::new(options.nonce, options.outputs, attributes);
 
-    let raw_tx = tx.encode();
-    let mut raw_tx_buffer = ctx.buffer(raw_tx.len() as u32)?;
-    ctx.borrow_mut(&mut raw_tx_buffer, |data| {
-        let data = data.as_mut_slice();
-        data.copy_from_slice(&raw_tx)
-    });
+    let raw_tx_buffer = encode_to_buffer(&mut ctx, &tx)?;
 
     let tx_id = tx.id();
     let tx_id = ctx.string(hex::encode(tx_id));
@@ -62,8 +57,11 @@ pub fn estimate_withdraw_unbonded_transaction_fee(mut ctx: FunctionContext) -> J
     let estimated_fee = fee_config
         .calculate_for_txaux(&tx_aux)
         .chain_neon(&mut ctx, "Unable to estimate transaction fee")?;
-    let estimated_fee = serde_json::to_string(&estimated_fee)
-        .chain_neon(&mut ctx, "Unable to serialize estimated fee to string")?;
+    let estimated_fee = serde_json::to_string(&estimated_fee).chain_neon_with_code(
+        &mut ctx,
+        ErrorCode::SerializationError,
+        "Unable to serialize estimated fee to string",
+    )?;
 
     Ok(ctx.string(estimated_fee.trim_matches('"')))
 }
@@ -78,15 +76,9 @@ pub fn withdraw_unbonded_transaction_to_witness(mut ctx: FunctionContext) -> JsR
     let witness = signer
         .sign(&withdraw_unbonded_tx.id())
         .map(StakedStateOpWitness::new)
-        .chain_neon(&mut ctx, "Error when signing transaction")?
-        .encode();
-    let mut witness_buffer = ctx.buffer(witness.len() as u32)?;
-    ctx.borrow_mut(&mut witness_buffer, |data| {
-        let data = data.as_mut_slice();
-        data.copy_from_slice(&witness)
-    });
-
-    Ok(witness_buffer)
+        .chain_neon(&mut ctx, "Error when signing transaction")?;
+
+    encode_to_buffer(&mut ctx, &witness)
 }
 
 pub fn withdraw_unbonded_transaction_to_signed_plain_hex(
@@ -94,7 +86,7 @@ pub fn withdraw_unbonded_transaction_to_signed_plain_hex(
 ) -> JsResult<JsBuffer> {
     let withdraw_unbonded_tx = withdraw_unbonded_tx_argument(&mut ctx, 0)?;
 
-    let witness = ctx.argument::<JsBuffer>(1)?;
+    let witness = ctx.argument::<JsValue>(1)?;
     let witness = parse_staked_state_op_witness(&mut ctx, witness)?;
     // let key_pair = key_pair_argument(&mut ctx, 1)?;
     // let signer = KeyPairSigner::new(key_pair.0, key_pair.1)
@@ -107,22 +99,15 @@ pub fn withdraw_unbonded_transaction_to_signed_plain_hex(
     let signed_transaction =
         SignedTransaction::WithdrawUnbondedStakeTransaction(withdraw_unbonded_tx, witness);
 
-    let raw_tx = signed_transaction.encode();
-    let mut raw_tx_buffer = ctx.buffer(raw_tx.len() as u32)?;
-    ctx.borrow_mut(&mut raw_tx_buffer, |data| {
-        let data = data.as_mut_slice();
-        data.copy_from_slice(&raw_tx)
-    });
-
-    Ok(raw_tx_buffer)
+    encode_to_buffer(&mut ctx, &signed_transaction)
 }
 
 pub fn withdraw_unbonded_transaction_to_obfuscated_hex(
     mut ctx: FunctionContext,
-) -> JsResult<JsBuffer> {
+) -> JsResult<JsValue> {
     let withdraw_unbonded_tx = withdraw_unbonded_tx_argument(&mut ctx, 0)?;
 
-    let witness = ctx.argument::<JsBuffer>(1)?;
+    let witness = ctx.argument::<JsValue>(1)?;
     let witness = parse_staked_state_op_witness(&mut ctx, witness)?;
     // let key_pair = key_pair_argument(&mut ctx, 1)?;
     // let signer = KeyPairSigner::new(key_pair.0, key_pair.1)
@@ -130,6 +115,8 @@ pub fn withdraw_unbonded_transaction_to_obfuscated_hex(
 
     let tendermint_address = ctx.argument::<JsString>(2)?.value();
     let features = Features::argument(&mut ctx, 3)?;
+    let output = output_buffer_argument(&mut ctx, 4)?;
+    let callback = callback_argument(&mut ctx)?;
 
     // let signature = signer
     //     .sign(&withdraw_unbonded_tx.id())
@@ -137,11 +124,69 @@ pub fn withdraw_unbonded_transaction_to_obfuscated_hex(
     //     .chain_neon(&mut ctx, "Error when signing transaction")?;
     let signed_transaction =
         SignedTransaction::WithdrawUnbondedStakeTransaction(withdraw_unbonded_tx, witness);
-    let tx_aux =
-        signed_transaction_to_tx_aux(&mut ctx, signed_transaction, &tendermint_address, features)
-            .chain_neon(&mut ctx, "Unable to obfuscate transaction")?;
+    signed_transaction_to_hex(
+        &mut ctx,
+        signed_transaction,
+        &tendermint_address,
+        features,
+        output,
+        callback,
+    )
+}
 
-    tx_aux_to_hex(&mut ctx, tx_aux)
+/// build, sign and obfuscate withdraw unbonded stake transaction in one go
+/// @arguments
+/// - options: { nonce, outputs, viewKeys, network | chainHexId }, outputs can contain
+///     multiple destination outputs
+/// - key_pair: key pair of the staking address
+/// - tendermint_address: Tendermint websocket address for obfuscation
+/// - features: native features
+/// - output: (optional) Buffer to encode the transaction into, the number of
+///     bytes written is returned instead of a new Buffer. Not supported with
+///     callback
+/// - callback: (optional) node-style callback, obfuscate on background thread
+///     and pass the result to callback when provided
+/// @return broadcast-able WithdrawUnbondedStakeTx
+pub fn build_withdraw_unbonded(mut ctx: FunctionContext) -> JsResult<JsValue> {
+    let options = BuildWithdrawUnbondedTransactionOptions::parse(&mut ctx)?;
+    let key_pair = key_pair_argument(&mut ctx, 1)?;
+    let tendermint_address = ctx.argument::<JsString>(2)?.value();
+    let features = Features::argument(&mut ctx, 3)?;
+    let output = output_buffer_argument(&mut ctx, 4)?;
+    let callback = callback_argument(&mut ctx)?;
+
+    if options.outputs.is_empty() {
+        return ctx.throw_error("Withdraw unbonded transaction should have at least one output");
+    }
+
+    let access_policies = options
+        .view_keys
+        .iter()
+        .map(|view_key| TxAccessPolicy {
+            view_key: view_key.into(),
+            access: TxAccess::AllData,
+        })
+        .collect();
+    let attributes = TxAttributes::new_with_access(options.chain_hex_id, access_policies);
+    let withdraw_unbonded_tx = WithdrawUnbondedTx::new(options.nonce, options.outputs, attributes);
+
+    let signer = KeyPairSigner::new(key_pair.0, key_pair.1)
+        .chain_neon(&mut ctx, "Unable to create KeyPair signer")?;
+    let witness = signer
+        .sign(&withdraw_unbonded_tx.id())
+        .map(StakedStateOpWitness::new)
+        .chain_neon(&mut ctx, "Error when signing transaction")?;
+
+    let signed_transaction =
+        SignedTransaction::WithdrawUnbondedStakeTransaction(withdraw_unbonded_tx, witness);
+    signed_transaction_to_hex(
+        &mut ctx,
+        signed_transaction,
+        &tendermint_address,
+        features,
+        output,
+        callback,
+    )
 }
 
 #[inline]
@@ -152,8 +197,11 @@ fn withdraw_unbonded_tx_argument(
     let unbond_tx = ctx.argument::<JsBuffer>(i)?;
     let mut unbond_tx = unbond_tx.borrow(&ctx.lock()).as_slice();
 
-    WithdrawUnbondedTx::decode(&mut unbond_tx)
-        .chain_neon(ctx, "Unable to decode raw transaction bytes")
+    WithdrawUnbondedTx::decode(&mut unbond_tx).chain_neon_with_code(
+        ctx,
+        ErrorCode::DeserializationError,
+        "Unable to decode raw transaction bytes",
+    )
 }
 
 struct BuildWithdrawUnbondedTransactionOptions {
@@ -165,9 +213,11 @@ struct BuildWithdrawUnbondedTransactionOptions {
 
 impl BuildWithdrawUnbondedTransactionOptions {
     fn parse(ctx: &mut FunctionContext) -> NeonResult<BuildWithdrawUnbondedTransactionOptions> {
-        let options = ctx
-            .argument::<JsObject>(0)
-            .chain_neon(ctx, "Unable to deserialize options object")?;
+        let options = ctx.argument::<JsObject>(0).chain_neon_with_code(
+            ctx,
+            ErrorCode::DeserializationError,
+            "Unable to deserialize options object",
+        )?;
 
         let nonce = options
             .get(ctx, "nonce")?
@@ -176,14 +226,8 @@ impl BuildWithdrawUnbondedTransactionOptions {
             .value();
         let nonce = parse_account_nonce(ctx, nonce)?;
 
-        let chain_hex_id = options
-            .get(ctx, "chainHexId")?
-            .downcast_or_throw::<JsBuffer, FunctionContext>(ctx)
-            .chain_neon(ctx, "Unable to downcast chainHexId")?;
-        let chain_hex_id = chain_hex_id.borrow(&ctx.lock()).as_slice().to_vec();
-        let chain_hex_id = chain_hex_id_from_vec(ctx, chain_hex_id)?;
+        let (chain_hex_id, network) = network_option(ctx, *options)?;
 
-        let network = network_from_chain_hex_id(chain_hex_id);
         let outputs = options
             .get(ctx, "outputs")?
             .downcast_or_throw::<JsArray, FunctionContext>(ctx)
@@ -207,12 +251,7 @@ impl BuildWithdrawUnbondedTransactionOptions {
             .to_vec(ctx)?;
         let view_keys = view_keys
             .iter()
-            .map(|&view_key| {
-                let view_key = view_key
-                    .downcast_or_throw::<JsBuffer, FunctionContext>(ctx)
-                    .chain_neon(ctx, "Unable to downcast viewKey")?;
-                parse_view_key(ctx, view_key)
-            })
+            .map(|&view_key| parse_view_key(ctx, view_key))
             .collect::<NeonResult<Vec<PublicKey>>>()?;
 
         Ok(BuildWithdrawUnbondedTransactionOptions {
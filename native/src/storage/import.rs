@@ -0,0 +1,160 @@
+//! Import of the wallets created by client-cli. client-cli keeps its wallets
+//! in a sled storage encrypted with the key derived from the wallet name and
+//! passphrase, the same derivation used by the secure storage
+use chain_core::init::address::CroAddress;
+use chain_core::init::network::Network;
+use client_common::seckey::derive_enckey;
+use client_common::storage::SledStorage;
+use client_common::{Error, ErrorKind, PublicKey, Result, SecKey};
+use client_core::wallet::{DefaultWalletClient, WalletClient};
+use neon::prelude::*;
+use secstr::SecUtf8;
+use serde::Serialize;
+
+use super::secure::{create_wallet_secrets, save_wallet_record};
+use super::{secure_storage_argument, wallet_name_argument, STORAGES};
+use crate::error::{ClientErrorNeonExt, ErrorCode};
+use crate::function_types::*;
+
+/// Wallet record of the imported wallet, saved as JSON
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ImportedWallet {
+    name: String,
+    view_key: ImportedKey,
+    transfer_keys: Vec<ImportedKey>,
+    staking_keys: Vec<ImportedKey>,
+    transfer_addresses: Vec<String>,
+    staking_addresses: Vec<String>,
+}
+
+/// Hex encoded key pair. Private key is absent for watch-only keys
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ImportedKey {
+    public_key: String,
+    private_key: Option<String>,
+}
+
+/// import the wallet of client-cli storage into the storage. The wallet is
+/// saved as secure wallet record with the same name and passphrase, and is
+/// unlocked after import
+/// @arguments
+/// - storage: storage handle to import into
+/// - cliStoragePath: client-cli storage directory, e.g. CRYPTO_CLIENT_STORAGE
+/// - name: wallet name
+/// - passphrase: wallet passphrase
+/// - network: network handle or name of the wallet addresses
+/// @return { name, viewKey, transferAddresses, stakingAddresses }
+pub fn import_client_cli_wallet(mut ctx: FunctionContext) -> JsResult<JsObject> {
+    let (path, storage) = secure_storage_argument(&mut ctx, 0)?;
+    let cli_storage_path = ctx.argument::<JsString>(1)?.value();
+    let name = wallet_name_argument(&mut ctx, 2)?;
+    let passphrase = SecUtf8::from(ctx.argument::<JsString>(3)?.value());
+    let network = network_argument(&mut ctx, 4)?;
+
+    // client-cli storage may have been opened by the caller already
+    let opened_cli_storage = STORAGES
+        .lock()
+        .chain_neon(&mut ctx, "Unable to acquire storage lock")?
+        .get(&cli_storage_path)
+        .map(|opened| opened.storage.clone());
+    let cli_storage = match opened_cli_storage {
+        Some(cli_storage) => cli_storage,
+        None => SledStorage::new(&cli_storage_path)
+            .chain_neon(&mut ctx, "Unable to open client-cli storage")?,
+    };
+
+    let enckey = derive_enckey(&passphrase, &name)
+        .chain_neon(&mut ctx, "Unable to derive encryption key from passphrase")?;
+    let wallet = read_client_cli_wallet(cli_storage, &name, &enckey, network)
+        .chain_neon(&mut ctx, "Unable to read client-cli wallet")?;
+    let record = serde_json::to_vec(&wallet).chain_neon_with_code(
+        &mut ctx,
+        ErrorCode::SerializationError,
+        "Unable to serialize wallet record",
+    )?;
+
+    create_wallet_secrets(&storage, path.clone(), name.clone(), enckey)
+        .chain_neon(&mut ctx, "Unable to set wallet passphrase")?;
+    save_wallet_record(&storage, path, &name, record)
+        .chain_neon(&mut ctx, "Unable to save secure wallet to storage")?;
+
+    let js_wallet = ctx.empty_object();
+    let js_name = ctx.string(&wallet.name);
+    js_wallet.set(&mut ctx, "name", js_name)?;
+    let view_key = ctx.string(&wallet.view_key.public_key);
+    js_wallet.set(&mut ctx, "viewKey", view_key)?;
+    let transfer_addresses = strings_to_js(&mut ctx, &wallet.transfer_addresses)?;
+    js_wallet.set(&mut ctx, "transferAddresses", transfer_addresses)?;
+    let staking_addresses = strings_to_js(&mut ctx, &wallet.staking_addresses)?;
+    js_wallet.set(&mut ctx, "stakingAddresses", staking_addresses)?;
+
+    Ok(js_wallet)
+}
+
+fn read_client_cli_wallet(
+    cli_storage: SledStorage,
+    name: &str,
+    enckey: &SecKey,
+    network: Network,
+) -> Result<ImportedWallet> {
+    let wallet_client = DefaultWalletClient::new_read_only(cli_storage);
+
+    let view_key = ImportedKey {
+        public_key: hex::encode(wallet_client.view_key(name, enckey)?.serialize()),
+        private_key: Some(hex::encode(
+            wallet_client.view_key_private(name, enckey)?.serialize(),
+        )),
+    };
+
+    let mut transfer_keys = Vec::new();
+    for public_key in wallet_client.public_keys(name, enckey)?.iter() {
+        transfer_keys.push(imported_key(&wallet_client, name, enckey, public_key)?);
+    }
+    let mut staking_keys = Vec::new();
+    for public_key in wallet_client.staking_keys(name, enckey)?.iter() {
+        staking_keys.push(imported_key(&wallet_client, name, enckey, public_key)?);
+    }
+
+    let mut transfer_addresses = Vec::new();
+    for address in wallet_client.transfer_addresses(name, enckey)?.iter() {
+        let address = address.to_cro(network).map_err(|err| {
+            Error::new(
+                ErrorKind::SerializationError,
+                format!("Unable to serialize transfer address: {}", err),
+            )
+        })?;
+        transfer_addresses.push(address);
+    }
+    let staking_addresses = wallet_client
+        .staking_addresses(name, enckey)?
+        .iter()
+        .map(ToString::to_string)
+        .collect();
+
+    Ok(ImportedWallet {
+        name: name.to_owned(),
+        view_key,
+        transfer_keys,
+        staking_keys,
+        transfer_addresses,
+        staking_addresses,
+    })
+}
+
+fn imported_key<W: WalletClient>(
+    wallet_client: &W,
+    name: &str,
+    enckey: &SecKey,
+    public_key: &PublicKey,
+) -> Result<ImportedKey> {
+    let private_key = wallet_client
+        .private_key(name, enckey, public_key)?
+        .map(|private_key| hex::encode(private_key.serialize()));
+
+    Ok(ImportedKey {
+        public_key: hex::encode(public_key.serialize()),
+        private_key,
+    })
+}
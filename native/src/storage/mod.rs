@@ -0,0 +1,413 @@
+//! Persistent key-value storage backed by sled, shared with client-common
+//! storage so that records are namespaced by keyspace
+mod import;
+mod secure;
+
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::Mutex;
+
+use client_common::storage::{SledStorage, Storage};
+use lazy_static::lazy_static;
+use neon::prelude::*;
+
+use crate::error::{ClientErrorNeonExt, ErrorCode};
+use crate::function_types::*;
+use import::import_client_cli_wallet;
+use secure::{
+    create_secure_wallet, get_secure, is_unlocked, load_secure_wallet, lock, lock_storage,
+    save_secure_wallet, set_secure, unlock,
+};
+pub use secure::{
+    create_wallet_secrets, remove_wallet_secrets, secure_storage_argument, with_unlocked_enckey,
+};
+
+/// Keyspace of the wallet records saved by `saveWallet`
+const WALLET_KEYSPACE: &str = "nodelib_wallet";
+
+lazy_static! {
+    /// Opened storages by path. sled allows a database to be opened only
+    /// once per process, so opening the same path again reuses the storage
+    static ref STORAGES: Mutex<HashMap<String, OpenedStorage>> = Mutex::new(HashMap::new());
+}
+static NEXT_HANDLE_ID: AtomicU32 = AtomicU32::new(1);
+
+/// Storage shared by the handles opened on its path, closed with the last one
+struct OpenedStorage {
+    storage: SledStorage,
+    handles: HashSet<u32>,
+}
+
+/// open the storage at the path, the directory is created if not exist
+/// @arguments
+/// - path: directory of the storage
+/// @return storage handle { path, close() }. Every call returns a new handle,
+///     the handles of the same path share the storage
+pub fn open(mut ctx: FunctionContext) -> JsResult<JsObject> {
+    let path = ctx.argument::<JsString>(0)?.value();
+    let id = NEXT_HANDLE_ID.fetch_add(1, Ordering::SeqCst);
+
+    {
+        let mut storages = STORAGES
+            .lock()
+            .chain_neon(&mut ctx, "Unable to acquire storage lock")?;
+        if !storages.contains_key(&path) {
+            let storage = SledStorage::new(&path).chain_neon(&mut ctx, "Unable to open storage")?;
+            storages.insert(
+                path.clone(),
+                OpenedStorage {
+                    storage,
+                    handles: HashSet::new(),
+                },
+            );
+        }
+        if let Some(opened) = storages.get_mut(&path) {
+            opened.handles.insert(id);
+        }
+    }
+
+    let handle = ctx.empty_object();
+    let js_path = ctx.string(&path);
+    handle.set(&mut ctx, "path", js_path)?;
+    let js_id = ctx.number(id);
+    handle.set(&mut ctx, "id", js_id)?;
+    let close_fn = JsFunction::new(&mut ctx, close)?;
+    handle.set(&mut ctx, "close", close_fn)?;
+
+    Ok(handle)
+}
+
+/// close the storage handle it is called on. The storage is closed with the
+/// last handle of its path, which locks its unlocked wallets and flushes the
+/// records to disk
+/// @return boolean: whether the handle was opened
+pub fn close(mut ctx: FunctionContext) -> JsResult<JsBoolean> {
+    let handle = ctx.this();
+    let path = storage_path(&mut ctx, handle)?;
+    let id = storage_handle_id(&mut ctx, handle)?;
+
+    let (existed, is_last_handle) = {
+        let mut storages = STORAGES
+            .lock()
+            .chain_neon(&mut ctx, "Unable to acquire storage lock")?;
+        let (existed, is_last_handle) = match storages.get_mut(&path) {
+            Some(opened) => (opened.handles.remove(&id), opened.handles.is_empty()),
+            None => (false, false),
+        };
+        if is_last_handle {
+            storages.remove(&path);
+        }
+
+        (existed, is_last_handle)
+    };
+    if is_last_handle {
+        lock_storage(&path);
+    }
+
+    Ok(ctx.boolean(existed))
+}
+
+/// get the value of the key in the keyspace
+/// @arguments
+/// - storage: storage handle
+/// - keyspace: keyspace string
+/// - key: key string or Buffer
+/// @return Buffer or null when the key does not exist
+pub fn get(mut ctx: FunctionContext) -> JsResult<JsValue> {
+    let storage = storage_argument(&mut ctx, 0)?;
+    let keyspace = ctx.argument::<JsString>(1)?.value();
+    let key = storage_key_argument(&mut ctx, 2)?;
+
+    let value = storage
+        .get(keyspace, key)
+        .chain_neon(&mut ctx, "Unable to get value from storage")?;
+
+    optional_value_to_js(&mut ctx, value)
+}
+
+/// set the value of the key in the keyspace
+/// @arguments
+/// - storage: storage handle
+/// - keyspace: keyspace string
+/// - key: key string or Buffer
+/// - value: value Buffer
+/// @return previous value Buffer or null
+pub fn set(mut ctx: FunctionContext) -> JsResult<JsValue> {
+    let storage = storage_argument(&mut ctx, 0)?;
+    let keyspace = ctx.argument::<JsString>(1)?.value();
+    let key = storage_key_argument(&mut ctx, 2)?;
+    let value = u8_buffer_argument(&mut ctx, 3)?;
+
+    let previous_value = storage
+        .set(keyspace, key, value)
+        .chain_neon(&mut ctx, "Unable to set value to storage")?;
+
+    optional_value_to_js(&mut ctx, previous_value)
+}
+
+/// delete the key in the keyspace
+/// @arguments
+/// - storage: storage handle
+/// - keyspace: keyspace string
+/// - key: key string or Buffer
+/// @return deleted value Buffer or null
+pub fn delete(mut ctx: FunctionContext) -> JsResult<JsValue> {
+    let storage = storage_argument(&mut ctx, 0)?;
+    let keyspace = ctx.argument::<JsString>(1)?.value();
+    let key = storage_key_argument(&mut ctx, 2)?;
+
+    let value = storage
+        .delete(keyspace, key)
+        .chain_neon(&mut ctx, "Unable to delete value from storage")?;
+
+    optional_value_to_js(&mut ctx, value)
+}
+
+/// list the keys in the keyspace
+/// @arguments
+/// - storage: storage handle
+/// - keyspace: keyspace string
+/// @return [Buffer]
+pub fn keys(mut ctx: FunctionContext) -> JsResult<JsArray> {
+    let storage = storage_argument(&mut ctx, 0)?;
+    let keyspace = ctx.argument::<JsString>(1)?.value();
+
+    let keys = storage
+        .keys(keyspace)
+        .chain_neon(&mut ctx, "Unable to list keys in storage")?;
+
+    let js_keys = JsArray::new(&mut ctx, keys.len() as u32);
+    for (i, key) in keys.iter().enumerate() {
+        let js_key = bytes_to_buffer(&mut ctx, key)?;
+        js_keys.set(&mut ctx, i as u32, js_key)?;
+    }
+
+    Ok(js_keys)
+}
+
+/// list the entries in the keyspace
+/// @arguments
+/// - storage: storage handle
+/// - keyspace: keyspace string
+/// @return [{ key: Buffer, value: Buffer }]
+pub fn entries(mut ctx: FunctionContext) -> JsResult<JsArray> {
+    let storage = storage_argument(&mut ctx, 0)?;
+    let keyspace = ctx.argument::<JsString>(1)?.value();
+
+    let keys = storage
+        .keys(&keyspace)
+        .chain_neon(&mut ctx, "Unable to list keys in storage")?;
+
+    let js_entries = JsArray::new(&mut ctx, 0);
+    let mut i = 0;
+    for key in keys.iter() {
+        // Keys may be deleted concurrently by another handle of the storage
+        let value = match storage
+            .get(&keyspace, key)
+            .chain_neon(&mut ctx, "Unable to get value from storage")?
+        {
+            Some(value) => value,
+            None => continue,
+        };
+
+        let js_entry = ctx.empty_object();
+        let js_key = bytes_to_buffer(&mut ctx, key)?;
+        js_entry.set(&mut ctx, "key", js_key)?;
+        let js_value = bytes_to_buffer(&mut ctx, &value)?;
+        js_entry.set(&mut ctx, "value", js_value)?;
+        js_entries.set(&mut ctx, i, js_entry)?;
+        i += 1;
+    }
+
+    Ok(js_entries)
+}
+
+/// delete all keys in the keyspace
+/// @arguments
+/// - storage: storage handle
+/// - keyspace: keyspace string
+pub fn clear(mut ctx: FunctionContext) -> JsResult<JsUndefined> {
+    let storage = storage_argument(&mut ctx, 0)?;
+    let keyspace = ctx.argument::<JsString>(1)?.value();
+
+    storage
+        .clear(keyspace)
+        .chain_neon(&mut ctx, "Unable to clear keyspace in storage")?;
+
+    Ok(ctx.undefined())
+}
+
+/// save the wallet record, e.g. serialized UTXO set and sync state, under
+/// the wallet name. Existing record of the wallet is overwritten
+/// @arguments
+/// - storage: storage handle
+/// - name: wallet name
+/// - record: wallet record Buffer
+pub fn save_wallet(mut ctx: FunctionContext) -> JsResult<JsUndefined> {
+    let storage = storage_argument(&mut ctx, 0)?;
+    let name = wallet_name_argument(&mut ctx, 1)?;
+    let record = u8_buffer_argument(&mut ctx, 2)?;
+
+    storage
+        .set(WALLET_KEYSPACE, name, record)
+        .chain_neon(&mut ctx, "Unable to save wallet to storage")?;
+
+    Ok(ctx.undefined())
+}
+
+/// load the wallet record saved under the wallet name
+/// @arguments
+/// - storage: storage handle
+/// - name: wallet name
+/// @return wallet record Buffer or null when the wallet does not exist
+pub fn load_wallet(mut ctx: FunctionContext) -> JsResult<JsValue> {
+    let storage = storage_argument(&mut ctx, 0)?;
+    let name = wallet_name_argument(&mut ctx, 1)?;
+
+    let record = storage
+        .get(WALLET_KEYSPACE, name)
+        .chain_neon(&mut ctx, "Unable to load wallet from storage")?;
+
+    optional_value_to_js(&mut ctx, record)
+}
+
+/// Returns the opened storage of the storage handle at argument `i`
+pub fn storage_argument(ctx: &mut FunctionContext, i: i32) -> NeonResult<SledStorage> {
+    let handle = ctx.argument::<JsObject>(i)?;
+    let path = storage_path(ctx, handle)?;
+    let id = storage_handle_id(ctx, handle)?;
+
+    let storages = STORAGES
+        .lock()
+        .chain_neon(ctx, "Unable to acquire storage lock")?;
+    let opened = storages
+        .get(&path)
+        .filter(|opened| opened.handles.contains(&id))
+        .chain_neon(ctx, format!("Storage {} is not opened", path))?;
+
+    Ok(opened.storage.clone())
+}
+
+/// Returns the wallet name at argument `i`, which should not be empty
+pub fn wallet_name_argument(ctx: &mut FunctionContext, i: i32) -> NeonResult<String> {
+    let name = ctx.argument::<JsString>(i)?.value();
+    if name.is_empty() {
+        return ctx.throw_error("Wallet name should not be empty");
+    }
+
+    Ok(name)
+}
+
+fn storage_path(ctx: &mut FunctionContext, handle: Handle<JsObject>) -> NeonResult<String> {
+    let path = handle
+        .get(ctx, "path")?
+        .downcast_or_throw::<JsString, FunctionContext>(ctx)
+        .chain_neon(ctx, "Unable to downcast path of storage handle")?
+        .value();
+
+    Ok(path)
+}
+
+fn storage_handle_id(ctx: &mut FunctionContext, handle: Handle<JsObject>) -> NeonResult<u32> {
+    let id = handle
+        .get(ctx, "id")?
+        .downcast_or_throw::<JsNumber, FunctionContext>(ctx)
+        .chain_neon(ctx, "Unable to downcast id of storage handle")?
+        .value();
+
+    Ok(id as u32)
+}
+
+/// Returns the key string as UTF-8 bytes, or the bytes of key Buffer. Key
+/// strings are names rather than hex, so they are never hex decoded
+fn storage_key_argument(ctx: &mut FunctionContext, i: i32) -> NeonResult<Vec<u8>> {
+    const EXPECTED: &str = "string or Buffer";
+
+    let key = typed_argument::<JsValue>(ctx, i, "storage key", EXPECTED)?;
+    if let Ok(key) = key.downcast::<JsString>() {
+        return Ok(key.value().into_bytes());
+    }
+    if let Ok(key) = key.downcast::<JsBuffer>() {
+        return Ok(key.borrow(&ctx.lock()).as_slice::<u8>().to_vec());
+    }
+
+    let got = describe_value(ctx, key)?;
+    throw_invalid_argument(
+        ctx,
+        ErrorCode::InvalidArgument,
+        i,
+        "storage key",
+        EXPECTED,
+        got,
+    )
+}
+
+fn optional_value_to_js<'a>(
+    ctx: &mut FunctionContext<'a>,
+    value: Option<Vec<u8>>,
+) -> JsResult<'a, JsValue> {
+    match value {
+        Some(value) => Ok(bytes_to_buffer(ctx, &value)?.upcast()),
+        None => Ok(ctx.null().upcast()),
+    }
+}
+
+pub fn register_storage_module(ctx: &mut ModuleContext) -> NeonResult<()> {
+    let js_object = JsObject::new(ctx);
+
+    let open_fn = JsFunction::new(ctx, open)?;
+    js_object.set(ctx, "open", open_fn)?;
+
+    let get_fn = JsFunction::new(ctx, get)?;
+    js_object.set(ctx, "get", get_fn)?;
+
+    let set_fn = JsFunction::new(ctx, set)?;
+    js_object.set(ctx, "set", set_fn)?;
+
+    let delete_fn = JsFunction::new(ctx, delete)?;
+    js_object.set(ctx, "delete", delete_fn)?;
+
+    let keys_fn = JsFunction::new(ctx, keys)?;
+    js_object.set(ctx, "keys", keys_fn)?;
+
+    let entries_fn = JsFunction::new(ctx, entries)?;
+    js_object.set(ctx, "entries", entries_fn)?;
+
+    let clear_fn = JsFunction::new(ctx, clear)?;
+    js_object.set(ctx, "clear", clear_fn)?;
+
+    let save_wallet_fn = JsFunction::new(ctx, save_wallet)?;
+    js_object.set(ctx, "saveWallet", save_wallet_fn)?;
+
+    let load_wallet_fn = JsFunction::new(ctx, load_wallet)?;
+    js_object.set(ctx, "loadWallet", load_wallet_fn)?;
+
+    let create_secure_wallet_fn = JsFunction::new(ctx, create_secure_wallet)?;
+    js_object.set(ctx, "createSecureWallet", create_secure_wallet_fn)?;
+
+    let unlock_fn = JsFunction::new(ctx, unlock)?;
+    js_object.set(ctx, "unlock", unlock_fn)?;
+
+    let lock_fn = JsFunction::new(ctx, lock)?;
+    js_object.set(ctx, "lock", lock_fn)?;
+
+    let is_unlocked_fn = JsFunction::new(ctx, is_unlocked)?;
+    js_object.set(ctx, "isUnlocked", is_unlocked_fn)?;
+
+    let get_secure_fn = JsFunction::new(ctx, get_secure)?;
+    js_object.set(ctx, "getSecure", get_secure_fn)?;
+
+    let set_secure_fn = JsFunction::new(ctx, set_secure)?;
+    js_object.set(ctx, "setSecure", set_secure_fn)?;
+
+    let save_secure_wallet_fn = JsFunction::new(ctx, save_secure_wallet)?;
+    js_object.set(ctx, "saveSecureWallet", save_secure_wallet_fn)?;
+
+    let load_secure_wallet_fn = JsFunction::new(ctx, load_secure_wallet)?;
+    js_object.set(ctx, "loadSecureWallet", load_secure_wallet_fn)?;
+
+    let import_client_cli_wallet_fn = JsFunction::new(ctx, import_client_cli_wallet)?;
+    js_object.set(ctx, "importClientCliWallet", import_client_cli_wallet_fn)?;
+
+    ctx.export_value("storage", js_object)
+}
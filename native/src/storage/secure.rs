@@ -0,0 +1,326 @@
+//! Passphrase encrypted wallet records on top of the storage. The encryption
+//! key is derived from the passphrase when the wallet is unlocked and kept in
+//! memory until the wallet is locked or the storage is closed
+use std::collections::HashMap;
+use std::sync::{Mutex, MutexGuard};
+
+use client_common::seckey::derive_enckey;
+use client_common::storage::{SecureStorage, SledStorage, Storage};
+use client_common::{Error, ErrorKind, Result, ResultExt, SecKey};
+use lazy_static::lazy_static;
+use neon::prelude::*;
+use secstr::SecUtf8;
+
+use super::wallet_name_argument;
+use super::{optional_value_to_js, storage_argument, storage_key_argument, storage_path};
+use crate::error::{coded_client_error, ClientErrorNeonExt, ErrorCode};
+use crate::function_types::*;
+
+/// Keyspace of the wallet records saved by `saveSecureWallet`
+const SECURE_WALLET_KEYSPACE: &str = "nodelib_secure_wallet";
+/// Keyspace of the records to verify the passphrase on unlock
+const PASSPHRASE_CHECK_KEYSPACE: &str = "nodelib_passphrase_check";
+const PASSPHRASE_CHECK_VALUE: &[u8] = b"nodelib";
+
+lazy_static! {
+    /// Encryption keys of the unlocked wallets by storage path and wallet name
+    static ref UNLOCKED_WALLETS: Mutex<HashMap<(String, String), SecKey>> =
+        Mutex::new(HashMap::new());
+}
+
+/// create the wallet with the passphrase, the wallet is unlocked after
+/// creation
+/// @arguments
+/// - storage: storage handle
+/// - name: wallet name, which should not be used by another wallet
+/// - passphrase: wallet passphrase
+pub fn create_secure_wallet(mut ctx: FunctionContext) -> JsResult<JsUndefined> {
+    let (path, storage) = secure_storage_argument(&mut ctx, 0)?;
+    let name = wallet_name_argument(&mut ctx, 1)?;
+    let passphrase = SecUtf8::from(ctx.argument::<JsString>(2)?.value());
+
+    let enckey = derive_enckey(&passphrase, &name)
+        .chain_neon(&mut ctx, "Unable to derive encryption key from passphrase")?;
+    create_wallet_secrets(&storage, path, name, enckey)
+        .chain_neon(&mut ctx, "Unable to create wallet")?;
+
+    Ok(ctx.undefined())
+}
+
+/// unlock the wallet with the passphrase. Throws with code NOT_FOUND when
+/// the wallet has not been created by createSecureWallet
+/// @arguments
+/// - storage: storage handle
+/// - name: wallet name
+/// - passphrase: wallet passphrase
+pub fn unlock(mut ctx: FunctionContext) -> JsResult<JsUndefined> {
+    let (path, storage) = secure_storage_argument(&mut ctx, 0)?;
+    let name = wallet_name_argument(&mut ctx, 1)?;
+    let passphrase = SecUtf8::from(ctx.argument::<JsString>(2)?.value());
+
+    let enckey = derive_enckey(&passphrase, &name)
+        .chain_neon(&mut ctx, "Unable to derive encryption key from passphrase")?;
+    unlock_wallet(&storage, path, name, enckey).chain_neon(&mut ctx, "Unable to unlock wallet")?;
+
+    Ok(ctx.undefined())
+}
+
+/// lock the wallet and forget its encryption key
+/// @arguments
+/// - storage: storage handle
+/// - name: wallet name
+/// @return boolean: whether the wallet was unlocked
+pub fn lock(mut ctx: FunctionContext) -> JsResult<JsBoolean> {
+    let handle = ctx.argument::<JsObject>(0)?;
+    let path = storage_path(&mut ctx, handle)?;
+    let name = wallet_name_argument(&mut ctx, 1)?;
+
+    let was_unlocked = UNLOCKED_WALLETS
+        .lock()
+        .chain_neon(&mut ctx, "Unable to acquire unlocked wallets lock")?
+        .remove(&(path, name))
+        .is_some();
+
+    Ok(ctx.boolean(was_unlocked))
+}
+
+/// check if the wallet is unlocked
+/// @arguments
+/// - storage: storage handle
+/// - name: wallet name
+/// @return boolean
+pub fn is_unlocked(mut ctx: FunctionContext) -> JsResult<JsBoolean> {
+    let handle = ctx.argument::<JsObject>(0)?;
+    let path = storage_path(&mut ctx, handle)?;
+    let name = wallet_name_argument(&mut ctx, 1)?;
+
+    let is_unlocked = UNLOCKED_WALLETS
+        .lock()
+        .chain_neon(&mut ctx, "Unable to acquire unlocked wallets lock")?
+        .contains_key(&(path, name));
+
+    Ok(ctx.boolean(is_unlocked))
+}
+
+/// get and decrypt the value of the key in the keyspace of an unlocked wallet
+/// @arguments
+/// - storage: storage handle
+/// - name: wallet name
+/// - keyspace: keyspace string
+/// - key: key string or Buffer
+/// @return Buffer or null when the key does not exist
+pub fn get_secure(mut ctx: FunctionContext) -> JsResult<JsValue> {
+    let (path, storage) = secure_storage_argument(&mut ctx, 0)?;
+    let name = wallet_name_argument(&mut ctx, 1)?;
+    let keyspace = ctx.argument::<JsString>(2)?.value();
+    let key = storage_key_argument(&mut ctx, 3)?;
+
+    let value = with_enckey(&mut ctx, path, &name, |enckey| {
+        storage.get_secure(wallet_keyspace(&keyspace, &name), key, enckey)
+    })?
+    .chain_neon(&mut ctx, "Unable to get secure value from storage")?;
+
+    optional_value_to_js(&mut ctx, value)
+}
+
+/// encrypt and set the value of the key in the keyspace of an unlocked wallet
+/// @arguments
+/// - storage: storage handle
+/// - name: wallet name
+/// - keyspace: keyspace string
+/// - key: key string or Buffer
+/// - value: value Buffer
+pub fn set_secure(mut ctx: FunctionContext) -> JsResult<JsUndefined> {
+    let (path, storage) = secure_storage_argument(&mut ctx, 0)?;
+    let name = wallet_name_argument(&mut ctx, 1)?;
+    let keyspace = ctx.argument::<JsString>(2)?.value();
+    let key = storage_key_argument(&mut ctx, 3)?;
+    let value = u8_buffer_argument(&mut ctx, 4)?;
+
+    with_enckey(&mut ctx, path, &name, |enckey| {
+        storage.set_secure(wallet_keyspace(&keyspace, &name), key, value, enckey)
+    })?
+    .chain_neon(&mut ctx, "Unable to set secure value to storage")?;
+
+    Ok(ctx.undefined())
+}
+
+/// encrypt and save the record of an unlocked wallet
+/// @arguments
+/// - storage: storage handle
+/// - name: wallet name
+/// - record: wallet record Buffer
+pub fn save_secure_wallet(mut ctx: FunctionContext) -> JsResult<JsUndefined> {
+    let (path, storage) = secure_storage_argument(&mut ctx, 0)?;
+    let name = wallet_name_argument(&mut ctx, 1)?;
+    let record = u8_buffer_argument(&mut ctx, 2)?;
+
+    save_wallet_record(&storage, path, &name, record)
+        .chain_neon(&mut ctx, "Unable to save secure wallet to storage")?;
+
+    Ok(ctx.undefined())
+}
+
+/// load and decrypt the record of an unlocked wallet
+/// @arguments
+/// - storage: storage handle
+/// - name: wallet name
+/// @return wallet record Buffer or null when the wallet does not exist
+pub fn load_secure_wallet(mut ctx: FunctionContext) -> JsResult<JsValue> {
+    let (path, storage) = secure_storage_argument(&mut ctx, 0)?;
+    let name = wallet_name_argument(&mut ctx, 1)?;
+
+    let record = with_enckey(&mut ctx, path, &name, |enckey| {
+        storage.get_secure(SECURE_WALLET_KEYSPACE, &name, enckey)
+    })?
+    .chain_neon(&mut ctx, "Unable to load secure wallet from storage")?;
+
+    optional_value_to_js(&mut ctx, record)
+}
+
+/// Sets the passphrase check record of a new wallet and keeps the encryption
+/// key in memory
+pub fn create_wallet_secrets(
+    storage: &SledStorage,
+    path: String,
+    name: String,
+    enckey: SecKey,
+) -> Result<()> {
+    if storage.get(PASSPHRASE_CHECK_KEYSPACE, &name)?.is_some() {
+        return Err(Error::new(
+            ErrorKind::InvalidInput,
+            format!("Wallet {} already exists", name),
+        ));
+    }
+    storage.set_secure(
+        PASSPHRASE_CHECK_KEYSPACE,
+        &name,
+        PASSPHRASE_CHECK_VALUE.to_vec(),
+        &enckey,
+    )?;
+
+    unlocked_wallets()?.insert((path, name), enckey);
+
+    Ok(())
+}
+
+/// Verifies the encryption key with the passphrase check record of the wallet
+/// and keeps the key in memory
+pub fn unlock_wallet(
+    storage: &SledStorage,
+    path: String,
+    name: String,
+    enckey: SecKey,
+) -> Result<()> {
+    let check_value = storage.get_secure(PASSPHRASE_CHECK_KEYSPACE, &name, &enckey)?;
+    match check_value {
+        Some(check_value) if check_value == PASSPHRASE_CHECK_VALUE => (),
+        Some(_) => {
+            return Err(Error::new(
+                ErrorKind::DecryptionError,
+                "Incorrect passphrase",
+            ))
+        }
+        None => {
+            return Err(coded_client_error(
+                ErrorKind::InvalidInput,
+                ErrorCode::NotFound,
+                format!("Wallet {} does not exist", name),
+            ))
+        }
+    }
+
+    unlocked_wallets()?.insert((path, name), enckey);
+
+    Ok(())
+}
+
+/// Encrypts and saves the record of an unlocked wallet
+pub fn save_wallet_record(
+    storage: &SledStorage,
+    path: String,
+    name: &str,
+    record: Vec<u8>,
+) -> Result<()> {
+    with_unlocked_enckey(&path, name, |enckey| {
+        storage.set_secure(SECURE_WALLET_KEYSPACE, name, record, enckey)
+    })?;
+
+    Ok(())
+}
+
+/// Calls the function with the encryption key of the unlocked wallet
+pub fn with_unlocked_enckey<F, T>(path: &str, name: &str, f: F) -> Result<T>
+where
+    F: FnOnce(&SecKey) -> Result<T>,
+{
+    let unlocked_wallets = unlocked_wallets()?;
+    let enckey = unlocked_wallets
+        .get(&(path.to_owned(), name.to_owned()))
+        .chain(|| {
+            (
+                ErrorKind::InvalidInput,
+                format!("Wallet {} is locked", name),
+            )
+        })?;
+
+    f(enckey)
+}
+
+/// Locks the wallet and deletes its passphrase check record and secure wallet
+/// record. Values set by `setSecure` are kept
+pub fn remove_wallet_secrets(storage: &SledStorage, path: String, name: &str) -> Result<()> {
+    unlocked_wallets()?.remove(&(path, name.to_owned()));
+    storage.delete(PASSPHRASE_CHECK_KEYSPACE, name)?;
+    storage.delete(SECURE_WALLET_KEYSPACE, name)?;
+
+    Ok(())
+}
+
+/// Lock all the unlocked wallets of the storage path
+pub fn lock_storage(path: &str) {
+    if let Ok(mut unlocked_wallets) = UNLOCKED_WALLETS.lock() {
+        unlocked_wallets.retain(|(wallet_path, _), _| wallet_path != path);
+    }
+}
+
+/// Returns the path and the opened storage of the storage handle at argument
+/// `i`
+pub fn secure_storage_argument(
+    ctx: &mut FunctionContext,
+    i: i32,
+) -> NeonResult<(String, SledStorage)> {
+    let handle = ctx.argument::<JsObject>(i)?;
+    let path = storage_path(ctx, handle)?;
+    let storage = storage_argument(ctx, i)?;
+
+    Ok((path, storage))
+}
+
+/// Calls the function with the encryption key of the unlocked wallet
+fn with_enckey<F, T>(ctx: &mut FunctionContext, path: String, name: &str, f: F) -> NeonResult<T>
+where
+    F: FnOnce(&SecKey) -> T,
+{
+    let unlocked_wallets = UNLOCKED_WALLETS
+        .lock()
+        .chain_neon(ctx, "Unable to acquire unlocked wallets lock")?;
+    let enckey = unlocked_wallets
+        .get(&(path, name.to_owned()))
+        .chain_neon(ctx, format!("Wallet {} is locked", name))?;
+
+    Ok(f(enckey))
+}
+
+fn unlocked_wallets() -> Result<MutexGuard<'static, HashMap<(String, String), SecKey>>> {
+    UNLOCKED_WALLETS.lock().map_err(|_| {
+        Error::new(
+            ErrorKind::InternalError,
+            "Unable to acquire unlocked wallets lock",
+        )
+    })
+}
+
+fn wallet_keyspace(keyspace: &str, name: &str) -> String {
+    format!("{}_{}", keyspace, name)
+}
@@ -0,0 +1,370 @@
+//! Strict decoding of SCALE-encoded chain types, for consumers of untrusted
+//! payloads such as the mempool. On top of the plain decoding, strict mode
+//! bounds the payload size and the vector lengths, rejects trailing bytes and
+//! non-canonical encodings, and reports why and at which byte decoding failed
+use std::fmt;
+
+use chain_core::state::account::{StakedState, StakedStateOpWitness};
+use chain_core::tx::data::attribute::TxAttributes;
+use chain_core::tx::data::input::TxoPointer;
+use chain_core::tx::data::output::TxOut;
+use chain_core::tx::witness::TxInWitness;
+use chain_core::tx::{TxAux, TxEnclaveAux};
+use neon::prelude::*;
+use parity_scale_codec::{Compact, Decode, Encode};
+
+use crate::common::does_js_object_has_prop;
+use crate::error::{ClientErrorNeonExt, ErrorCode};
+use crate::function_types::*;
+
+/// Tendermint default max_tx_bytes
+const DEFAULT_MAX_SIZE: usize = 1024 * 1024;
+const DEFAULT_MAX_VECTOR_LENGTH: usize = 1024;
+
+/// SCALE indexes of the TxAux and TxEnclaveAux variants starting with the
+/// inputs vector
+const TX_AUX_ENCLAVE_TX_INDEX: u8 = 0;
+const TX_ENCLAVE_AUX_TRANSFER_TX_INDEX: u8 = 0;
+const TX_ENCLAVE_AUX_DEPOSIT_STAKE_TX_INDEX: u8 = 1;
+
+#[derive(Debug, Clone, Copy)]
+pub struct DecodeLimits {
+    pub max_size: usize,
+    pub max_vector_length: usize,
+}
+
+impl Default for DecodeLimits {
+    fn default() -> Self {
+        DecodeLimits {
+            max_size: DEFAULT_MAX_SIZE,
+            max_vector_length: DEFAULT_MAX_VECTOR_LENGTH,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum StrictDecodeError {
+    TooLarge {
+        size: usize,
+        max_size: usize,
+    },
+    TooManyElements {
+        field: &'static str,
+        length: usize,
+        max_length: usize,
+        /// Offset of the length prefix, None when checked after decoding
+        offset: Option<usize>,
+    },
+    InvalidEncoding {
+        offset: usize,
+        reason: String,
+    },
+    TrailingBytes {
+        offset: usize,
+        count: usize,
+    },
+    NonCanonical {
+        offset: usize,
+    },
+}
+
+impl StrictDecodeError {
+    /// Stable name of the failure, exposed as the `reason` property
+    pub fn reason(&self) -> &'static str {
+        match self {
+            StrictDecodeError::TooLarge { .. } => "TooLarge",
+            StrictDecodeError::TooManyElements { .. } => "TooManyElements",
+            StrictDecodeError::InvalidEncoding { .. } => "InvalidEncoding",
+            StrictDecodeError::TrailingBytes { .. } => "TrailingBytes",
+            StrictDecodeError::NonCanonical { .. } => "NonCanonical",
+        }
+    }
+
+    /// Byte offset in the payload where decoding failed, if any
+    pub fn offset(&self) -> Option<usize> {
+        match self {
+            StrictDecodeError::TooLarge { .. } => None,
+            StrictDecodeError::TooManyElements { offset, .. } => *offset,
+            StrictDecodeError::InvalidEncoding { offset, .. }
+            | StrictDecodeError::TrailingBytes { offset, .. }
+            | StrictDecodeError::NonCanonical { offset } => Some(*offset),
+        }
+    }
+
+    /// Throws a DESERIALIZATION_ERROR with `reason` and `offset` properties
+    pub fn throw<'a, C, T>(&self, ctx: &mut C, name: &str) -> NeonResult<T>
+    where
+        C: Context<'a>,
+    {
+        let error = JsError::error(ctx, format!("Unable to decode {}: {}", name, self))?;
+        let code = ctx.string(ErrorCode::DeserializationError.as_str());
+        error.set(ctx, "code", code)?;
+        let details = ctx.string(self.to_string());
+        error.set(ctx, "details", details)?;
+        let reason = ctx.string(self.reason());
+        error.set(ctx, "reason", reason)?;
+        if let Some(offset) = self.offset() {
+            let offset = ctx.number(offset as f64);
+            error.set(ctx, "offset", offset)?;
+        }
+
+        ctx.throw(error)
+    }
+}
+
+impl fmt::Display for StrictDecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            StrictDecodeError::TooLarge { size, max_size } => write!(
+                f,
+                "Payload of {} bytes exceeds the maximum of {} bytes",
+                size, max_size
+            ),
+            StrictDecodeError::TooManyElements {
+                field,
+                length,
+                max_length,
+                ..
+            } => write!(
+                f,
+                "{} has {} elements, more than the maximum of {}",
+                field, length, max_length
+            ),
+            StrictDecodeError::InvalidEncoding { offset, reason } => {
+                write!(f, "{} at byte {}", reason, offset)
+            }
+            StrictDecodeError::TrailingBytes { offset, count } => {
+                write!(f, "Unexpected {} trailing bytes at byte {}", count, offset)
+            }
+            StrictDecodeError::NonCanonical { offset } => {
+                write!(f, "Non-canonical encoding at byte {}", offset)
+            }
+        }
+    }
+}
+
+/// Chain type which can be decoded in strict mode
+pub trait StrictDecode: Decode + Encode {
+    /// Name and offset of the vector length prefix to check before decoding,
+    /// so that a forged length cannot make the decoder allocate unbounded
+    /// memory
+    fn length_prefix(_encoded: &[u8]) -> Option<(&'static str, usize)> {
+        None
+    }
+
+    /// Name and length of the vectors of the decoded value
+    fn vector_lengths(&self) -> Vec<(&'static str, usize)> {
+        Vec::new()
+    }
+}
+
+impl StrictDecode for TxAux {
+    fn length_prefix(encoded: &[u8]) -> Option<(&'static str, usize)> {
+        match encoded {
+            [TX_AUX_ENCLAVE_TX_INDEX, TX_ENCLAVE_AUX_TRANSFER_TX_INDEX, ..]
+            | [TX_AUX_ENCLAVE_TX_INDEX, TX_ENCLAVE_AUX_DEPOSIT_STAKE_TX_INDEX, ..] => {
+                Some(("inputs", 2))
+            }
+            _ => None,
+        }
+    }
+
+    fn vector_lengths(&self) -> Vec<(&'static str, usize)> {
+        match self {
+            TxAux::EnclaveTx(TxEnclaveAux::TransferTx {
+                inputs,
+                no_of_outputs,
+                ..
+            }) => vec![
+                ("inputs", inputs.len()),
+                ("noOfOutputs", usize::from(*no_of_outputs)),
+            ],
+            TxAux::EnclaveTx(TxEnclaveAux::DepositStakeTx { tx, .. }) => {
+                vec![("inputs", tx.inputs.len())]
+            }
+            TxAux::EnclaveTx(TxEnclaveAux::WithdrawUnbondedStakeTx { no_of_outputs, .. }) => {
+                vec![("noOfOutputs", usize::from(*no_of_outputs))]
+            }
+            TxAux::PublicTx(_) => Vec::new(),
+        }
+    }
+}
+
+impl StrictDecode for TxAttributes {
+    fn length_prefix(_encoded: &[u8]) -> Option<(&'static str, usize)> {
+        // allowed_view follows the 1 byte chain_hex_id
+        Some(("viewKeys", 1))
+    }
+
+    fn vector_lengths(&self) -> Vec<(&'static str, usize)> {
+        vec![("viewKeys", self.allowed_view.len())]
+    }
+}
+
+impl StrictDecode for TxoPointer {}
+impl StrictDecode for TxOut {}
+impl StrictDecode for StakedState {}
+impl StrictDecode for TxInWitness {}
+impl StrictDecode for StakedStateOpWitness {}
+
+/// Decode the value in strict mode
+pub fn decode_strict<T: StrictDecode>(
+    encoded: &[u8],
+    limits: DecodeLimits,
+) -> Result<T, StrictDecodeError> {
+    if encoded.len() > limits.max_size {
+        return Err(StrictDecodeError::TooLarge {
+            size: encoded.len(),
+            max_size: limits.max_size,
+        });
+    }
+
+    if let Some((field, offset)) = T::length_prefix(encoded) {
+        // A malformed prefix is reported by the decoding below
+        if let Ok(Compact(length)) =
+            Compact::<u32>::decode(&mut &encoded[offset.min(encoded.len())..])
+        {
+            check_vector_length(field, length as usize, limits, Some(offset))?;
+        }
+    }
+
+    let mut input = encoded;
+    let decoded = T::decode(&mut input).map_err(|err| StrictDecodeError::InvalidEncoding {
+        offset: encoded.len() - input.len(),
+        reason: err.to_string(),
+    })?;
+    let consumed = encoded.len() - input.len();
+    if !input.is_empty() {
+        return Err(StrictDecodeError::TrailingBytes {
+            offset: consumed,
+            count: input.len(),
+        });
+    }
+
+    for (field, length) in decoded.vector_lengths() {
+        check_vector_length(field, length, limits, None)?;
+    }
+
+    // Different bytes decoding to the same value would give the same
+    // transaction different encodings, only the encoder output is accepted
+    let reencoded = decoded.encode();
+    if reencoded != encoded {
+        let offset = reencoded
+            .iter()
+            .zip(encoded.iter())
+            .position(|(a, b)| a != b)
+            .unwrap_or_else(|| reencoded.len().min(encoded.len()));
+        return Err(StrictDecodeError::NonCanonical { offset });
+    }
+
+    Ok(decoded)
+}
+
+#[inline]
+fn check_vector_length(
+    field: &'static str,
+    length: usize,
+    limits: DecodeLimits,
+    offset: Option<usize>,
+) -> Result<(), StrictDecodeError> {
+    if length > limits.max_vector_length {
+        return Err(StrictDecodeError::TooManyElements {
+            field,
+            length,
+            max_length: limits.max_vector_length,
+            offset,
+        });
+    }
+
+    Ok(())
+}
+
+/// Returns the decode limits of the options argument, None when strict mode
+/// is not enabled
+/// @arguments
+/// - options: (optional) { strict?, maxSize?, maxVectorLength? }
+pub fn decode_options_argument(
+    ctx: &mut FunctionContext,
+    i: i32,
+) -> NeonResult<Option<DecodeLimits>> {
+    let options = match ctx.argument_opt(i) {
+        Some(options) if !options.is_a::<JsUndefined>() => {
+            typed_argument::<JsObject>(ctx, i, "decode options", "object")?
+        }
+        _ => return Ok(None),
+    };
+
+    let strict = if does_js_object_has_prop(ctx, options, "strict")? {
+        options
+            .get(ctx, "strict")?
+            .downcast_or_throw::<JsBoolean, FunctionContext>(ctx)
+            .chain_neon(ctx, "Unable to downcast strict")?
+            .value()
+    } else {
+        false
+    };
+    if !strict {
+        return Ok(None);
+    }
+
+    let defaults = DecodeLimits::default();
+    Ok(Some(DecodeLimits {
+        max_size: limit_option(ctx, options, "maxSize", defaults.max_size)?,
+        max_vector_length: limit_option(
+            ctx,
+            options,
+            "maxVectorLength",
+            defaults.max_vector_length,
+        )?,
+    }))
+}
+
+fn limit_option(
+    ctx: &mut FunctionContext,
+    options: Handle<JsObject>,
+    key: &str,
+    default: usize,
+) -> NeonResult<usize> {
+    if !does_js_object_has_prop(ctx, options, key)? {
+        return Ok(default);
+    }
+
+    let limit = options
+        .get(ctx, key)?
+        .downcast_or_throw::<JsNumber, FunctionContext>(ctx)
+        .chain_neon(ctx, format!("Unable to downcast {}", key))?
+        .value();
+    if limit < 0.0 || limit.fract() != 0.0 || limit > MAX_SAFE_INTEGER {
+        return ctx.throw_error(format!("{} should be a non-negative safe integer", key));
+    }
+
+    Ok(limit as usize)
+}
+
+/// Decode the bytes, in strict mode when limits are given. Otherwise only
+/// trailing bytes are rejected
+pub fn decode_bytes<T: StrictDecode>(
+    ctx: &mut FunctionContext,
+    encoded: &[u8],
+    name: &str,
+    limits: Option<DecodeLimits>,
+) -> NeonResult<T> {
+    if let Some(limits) = limits {
+        return match decode_strict(encoded, limits) {
+            Ok(decoded) => Ok(decoded),
+            Err(err) => err.throw(ctx, name),
+        };
+    }
+
+    let mut encoded = encoded;
+    let decoded = T::decode(&mut encoded).chain_neon_with_code(
+        ctx,
+        ErrorCode::DeserializationError,
+        format!("Unable to decode {}", name),
+    )?;
+    if !encoded.is_empty() {
+        return ctx.throw_error(format!("Unexpected trailing bytes after {}", name));
+    }
+
+    Ok(decoded)
+}
@@ -0,0 +1,61 @@
+//! Delivery of the synced blocks to the JS `onBlocks` callback batch by batch.
+//! The sync checkpoint of `resume` is saved once a batch is handed over, so
+//! that a cancelled or failed sync resumes after the last delivered batch
+use chain_core::init::network::Network;
+use neon::event::EventHandler;
+use neon::prelude::*;
+
+use super::checkpoint::CheckpointStorage;
+use super::sync_result_to_js;
+use super::syncer::SyncResult;
+
+pub const DEFAULT_BATCH_SIZE: u64 = 100;
+
+pub struct SyncBatches {
+    handler: EventHandler,
+    size: u64,
+    network: Network,
+    /// Storage the checkpoint of the delivered batches is saved to
+    pub checkpoint_storage: Option<CheckpointStorage>,
+}
+
+impl SyncBatches {
+    pub fn new(handler: EventHandler, size: u64, network: Network) -> SyncBatches {
+        SyncBatches {
+            handler,
+            size,
+            network,
+            checkpoint_storage: None,
+        }
+    }
+
+    /// Number of blocks of a batch
+    pub fn size(&self) -> u64 {
+        self.size
+    }
+
+    /// Hand the result of the batch to the callback, and save its checkpoint
+    /// once the callback returns without throwing
+    pub fn deliver(&self, result: SyncResult) {
+        let network = self.network;
+        let checkpoint_storage = self.checkpoint_storage.clone();
+        self.handler.schedule_with(move |ctx, this, callback| {
+            let js_result = match sync_result_to_js(ctx, &result, network) {
+                Ok(js_result) => js_result,
+                Err(_) => return,
+            };
+            if callback
+                .call(ctx, this, vec![js_result.upcast::<JsValue>()])
+                .is_err()
+            {
+                return;
+            }
+
+            if let Some(checkpoint_storage) = &checkpoint_storage {
+                if let Err(err) = checkpoint_storage.save(result.last_height, &result.utxos) {
+                    log::warn!("Unable to save sync checkpoint: {}", err);
+                }
+            }
+        });
+    }
+}
@@ -0,0 +1,56 @@
+//! Block filter of the `block_filter` end block event, an ethbloom of the view
+//! keys able to decrypt the transactions of the block, which tells the blocks
+//! without transactions for the wallet before fetching them
+use chain_tx_filter::BlockFilter;
+use client_common::{ErrorKind, Result, ResultExt};
+use serde_json::Value;
+
+const BLOCK_FILTER_EVENT_TYPE: &str = "block_filter";
+const ETHBLOOM_ATTRIBUTE_KEY: &str = "ethbloom";
+/// Size of the 2048-bit ethbloom
+const BLOCK_FILTER_SIZE: usize = 256;
+
+/// Returns the block filter of the RPC `block_results` result. Blocks without
+/// transaction accessible by any view key have no filter, which is an empty
+/// filter
+pub fn parse_block_filter(block_results: &Value) -> Result<BlockFilter> {
+    let empty_events = Vec::new();
+    let events = block_results
+        .get("end_block_events")
+        .and_then(Value::as_array)
+        .unwrap_or(&empty_events);
+    let attribute = events
+        .iter()
+        .filter(|event| event.get("type").and_then(Value::as_str) == Some(BLOCK_FILTER_EVENT_TYPE))
+        .filter_map(|event| event.get("attributes").and_then(Value::as_array))
+        .flatten()
+        .find(|attribute| {
+            attribute
+                .get("key")
+                .and_then(Value::as_str)
+                .map_or(false, is_ethbloom_key)
+        });
+    let value = match attribute.and_then(|attribute| attribute.get("value")) {
+        Some(value) => value,
+        None => return Ok(BlockFilter::default()),
+    };
+
+    let bloom = value
+        .as_str()
+        .and_then(|value| base64::decode(value).ok())
+        .filter(|bloom| bloom.len() == BLOCK_FILTER_SIZE)
+        .chain(|| {
+            (
+                ErrorKind::DeserializationError,
+                "Unable to decode ethbloom of block filter",
+            )
+        })?;
+
+    Ok(BlockFilter::from(&bloom[..]))
+}
+
+/// Tendermint base64-encodes the event attribute keys
+fn is_ethbloom_key(key: &str) -> bool {
+    key == ETHBLOOM_ATTRIBUTE_KEY
+        || base64::decode(key).ok().as_deref() == Some(ETHBLOOM_ATTRIBUTE_KEY.as_bytes())
+}
@@ -0,0 +1,137 @@
+//! Sync checkpoints of the wallets persisted in the storage, so that the sync
+//! of a wallet resumes after the last synced block instead of from genesis
+use std::collections::HashMap;
+use std::str::FromStr;
+
+use chain_core::init::coin::Coin;
+use chain_core::tx::data::input::TxoPointer;
+use chain_core::tx::data::TxId;
+use client_common::storage::{SledStorage, Storage};
+use client_common::{Error, ErrorKind, Result, ResultExt};
+use serde::{Deserialize, Serialize};
+
+/// Keyspace of the sync checkpoints, keyed by wallet name
+const SYNC_CHECKPOINT_KEYSPACE: &str = "nodelib_sync_checkpoint";
+
+/// Storage the sync checkpoint of the wallet is saved to
+#[derive(Clone)]
+pub struct CheckpointStorage {
+    pub storage: SledStorage,
+    pub wallet_name: String,
+}
+
+impl CheckpointStorage {
+    /// Save the checkpoint after the block at `last_height`. The checkpoint
+    /// only moves forward, as the batches handed over through the event loop
+    /// may be saved after the sync result
+    pub fn save(&self, last_height: u64, utxos: &HashMap<TxoPointer, Coin>) -> Result<()> {
+        if let Some(checkpoint) = SyncCheckpoint::load(&self.storage, &self.wallet_name)? {
+            if checkpoint.last_height >= last_height {
+                return Ok(());
+            }
+        }
+
+        SyncCheckpoint::new(last_height, utxos).save(&self.storage, &self.wallet_name)
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncCheckpoint {
+    /// Height of the last synced block
+    pub last_height: u64,
+    /// Known wallet UTXOs after the last synced block
+    pub utxos: Vec<CheckpointUtxo>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CheckpointUtxo {
+    pub prev_tx_id: String,
+    pub prev_index: u16,
+    pub value: String,
+}
+
+impl SyncCheckpoint {
+    pub fn new(last_height: u64, utxos: &HashMap<TxoPointer, Coin>) -> SyncCheckpoint {
+        let mut utxos = utxos
+            .iter()
+            .map(|(pointer, value)| CheckpointUtxo {
+                prev_tx_id: hex::encode(pointer.id),
+                prev_index: pointer.index,
+                value: u64::from(*value).to_string(),
+            })
+            .collect::<Vec<CheckpointUtxo>>();
+        // Same UTXO set, same record
+        utxos.sort_by(|a, b| (&a.prev_tx_id, a.prev_index).cmp(&(&b.prev_tx_id, b.prev_index)));
+
+        SyncCheckpoint { last_height, utxos }
+    }
+
+    /// Load the checkpoint of the wallet, None when the wallet has not synced
+    pub fn load(storage: &SledStorage, name: &str) -> Result<Option<SyncCheckpoint>> {
+        let record = match storage.get(SYNC_CHECKPOINT_KEYSPACE, name)? {
+            Some(record) => record,
+            None => return Ok(None),
+        };
+        let checkpoint = serde_json::from_slice(&record).chain(|| {
+            (
+                ErrorKind::DeserializationError,
+                "Unable to deserialize sync checkpoint",
+            )
+        })?;
+
+        Ok(Some(checkpoint))
+    }
+
+    /// Save the checkpoint of the wallet, overwriting the existing one
+    pub fn save(&self, storage: &SledStorage, name: &str) -> Result<()> {
+        let record = serde_json::to_vec(self).chain(|| {
+            (
+                ErrorKind::SerializationError,
+                "Unable to serialize sync checkpoint",
+            )
+        })?;
+        storage.set(SYNC_CHECKPOINT_KEYSPACE, name, record)?;
+
+        Ok(())
+    }
+
+    /// Delete the checkpoint of the wallet, returns whether it existed
+    pub fn delete(storage: &SledStorage, name: &str) -> Result<bool> {
+        Ok(storage.delete(SYNC_CHECKPOINT_KEYSPACE, name)?.is_some())
+    }
+
+    /// Returns the UTXOs to resume the sync with
+    pub fn known_utxos(&self) -> Result<HashMap<TxoPointer, Coin>> {
+        self.utxos
+            .iter()
+            .map(|utxo| {
+                let id = hex::decode(&utxo.prev_tx_id)
+                    .ok()
+                    .filter(|id| id.len() == 32)
+                    .chain(|| {
+                        (
+                            ErrorKind::DeserializationError,
+                            "Invalid transaction id of sync checkpoint UTXO",
+                        )
+                    })?;
+                let mut tx_id: TxId = [0u8; 32];
+                tx_id.copy_from_slice(&id);
+                let value = Coin::from_str(&utxo.value).map_err(|err| {
+                    Error::new(
+                        ErrorKind::DeserializationError,
+                        format!("Invalid value of sync checkpoint UTXO: {}", err),
+                    )
+                })?;
+
+                let pointer = TxoPointer {
+                    id: tx_id,
+                    index: utxo.prev_index,
+                };
+
+                Ok((pointer, value))
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,171 @@
+//! Ready-to-display history entries of synchronized transactions
+use std::collections::HashMap;
+
+use chain_core::init::coin::Coin;
+use chain_core::state::account::StakedStateAddress;
+use chain_core::tx::data::address::ExtendedAddr;
+use chain_core::tx::data::input::TxoPointer;
+use chain_core::tx::data::output::TxOut;
+use client_common::{Error, ErrorKind, Result};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Direction {
+    Incoming,
+    Outgoing,
+    SelfTransfer,
+}
+
+impl Direction {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Direction::Incoming => "Incoming",
+            Direction::Outgoing => "Outgoing",
+            Direction::SelfTransfer => "Self",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Counterparty {
+    Transfer(ExtendedAddr),
+    Staking(StakedStateAddress),
+}
+
+#[derive(Debug, Clone)]
+pub struct HistoryEntry {
+    pub direction: Direction,
+    /// Amount received for incoming transactions, otherwise amount sent to
+    /// the counterparties
+    pub amount: Coin,
+    /// `None` when the value of any input is unknown to the wallet
+    pub fee: Option<Coin>,
+    pub counterparties: Vec<Counterparty>,
+}
+
+impl HistoryEntry {
+    /// History of a decrypted transfer transaction. `known_utxos` are the
+    /// values of the wallet UTXOs, used to tell whether the wallet is the
+    /// sender
+    pub fn transfer(
+        inputs: &[TxoPointer],
+        outputs: &[TxOut],
+        transfer_addresses: &[ExtendedAddr],
+        known_utxos: &HashMap<TxoPointer, Coin>,
+    ) -> Result<HistoryEntry> {
+        let spent_values = inputs
+            .iter()
+            .filter_map(|input| known_utxos.get(input).copied())
+            .collect::<Vec<Coin>>();
+        let spent = sum_coins(spent_values.iter().copied())?;
+
+        let (owned_outputs, other_outputs): (Vec<&TxOut>, Vec<&TxOut>) = outputs
+            .iter()
+            .partition(|output| transfer_addresses.contains(&output.address));
+        let received = sum_coins(owned_outputs.iter().map(|output| output.value))?;
+        let sent = sum_coins(other_outputs.iter().map(|output| output.value))?;
+
+        let fee = if !inputs.is_empty() && spent_values.len() == inputs.len() {
+            (spent - (received + sent).map_err(coin_error)?).ok()
+        } else {
+            None
+        };
+
+        let mut counterparties = Vec::new();
+        for output in other_outputs.iter() {
+            let counterparty = Counterparty::Transfer(output.address.clone());
+            if !counterparties.contains(&counterparty) {
+                counterparties.push(counterparty);
+            }
+        }
+
+        let entry = if spent_values.is_empty() {
+            HistoryEntry {
+                direction: Direction::Incoming,
+                amount: received,
+                fee,
+                counterparties: Vec::new(),
+            }
+        } else if other_outputs.is_empty() {
+            HistoryEntry {
+                direction: Direction::SelfTransfer,
+                amount: received,
+                fee,
+                counterparties,
+            }
+        } else {
+            HistoryEntry {
+                direction: Direction::Outgoing,
+                amount: sent,
+                fee,
+                counterparties,
+            }
+        };
+
+        Ok(entry)
+    }
+
+    /// History of a deposit from the wallet UTXOs to a staking address
+    pub fn deposit(
+        inputs: &[TxoPointer],
+        to_staked_account: StakedStateAddress,
+        known_utxos: &HashMap<TxoPointer, Coin>,
+    ) -> Result<HistoryEntry> {
+        let amount = sum_coins(
+            inputs
+                .iter()
+                .filter_map(|input| known_utxos.get(input).copied()),
+        )?;
+
+        Ok(HistoryEntry {
+            direction: Direction::Outgoing,
+            amount,
+            fee: None,
+            counterparties: vec![Counterparty::Staking(to_staked_account)],
+        })
+    }
+
+    /// History of a withdrawal from the staking address to the wallet outputs
+    pub fn withdraw(
+        owned_outputs: &[TxOut],
+        staking_address: Option<StakedStateAddress>,
+    ) -> Result<HistoryEntry> {
+        let amount = sum_coins(owned_outputs.iter().map(|output| output.value))?;
+
+        Ok(HistoryEntry {
+            direction: Direction::Incoming,
+            amount,
+            fee: None,
+            counterparties: staking_address
+                .map(Counterparty::Staking)
+                .into_iter()
+                .collect(),
+        })
+    }
+
+    /// History of an operation on the staking address itself, e.g. unbond
+    pub fn staking(amount: Coin) -> HistoryEntry {
+        HistoryEntry {
+            direction: Direction::SelfTransfer,
+            amount,
+            fee: None,
+            counterparties: Vec::new(),
+        }
+    }
+}
+
+/// Sum coins with overflow and max supply checks
+fn sum_coins<I>(coins: I) -> Result<Coin>
+where
+    I: Iterator<Item = Coin>,
+{
+    coins.fold(Ok(Coin::zero()), |sum, coin| {
+        sum.and_then(|sum| (sum + coin).map_err(coin_error))
+    })
+}
+
+fn coin_error<E: std::fmt::Display>(err: E) -> Error {
+    Error::new(
+        ErrorKind::InvalidInput,
+        format!("Transaction amount exceeds maximum coin supply: {}", err),
+    )
+}
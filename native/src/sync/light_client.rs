@@ -0,0 +1,174 @@
+//! Light-client verification of the blocks fetched from an untrusted
+//! Tendermint RPC node. Headers are verified sequentially from a trusted
+//! checkpoint, then the transactions and their results are checked against
+//! the verified headers before any of them is credited to the wallet
+use std::collections::BTreeMap;
+
+use client_common::{Error, ErrorKind, Result, ResultExt};
+use serde_json::Value;
+
+use crate::block::{
+    data_hash, parse_header, parse_signed_header, parse_validators, results_hash, validators_hash,
+    verify_commit, BlockHeader,
+};
+use crate::rpc::TendermintRpcClient;
+
+/// Block height and hash obtained from a trusted source, e.g. a trusted
+/// node or a previous verified sync
+#[derive(Debug, Clone)]
+pub struct TrustedCheckpoint {
+    pub height: u64,
+    pub hash: Vec<u8>,
+}
+
+pub struct LightClient<'a> {
+    rpc_client: &'a TendermintRpcClient,
+    /// Verified headers by height. Only the latest two are kept, which is
+    /// what verifying a block and its results needs
+    headers: BTreeMap<u64, BlockHeader>,
+}
+
+impl<'a> LightClient<'a> {
+    /// Create a light client trusting the header of the checkpoint
+    pub fn new(
+        rpc_client: &'a TendermintRpcClient,
+        checkpoint: &TrustedCheckpoint,
+    ) -> Result<LightClient<'a>> {
+        let (header, _) = parse_signed_header(&rpc_client.commit(checkpoint.height)?)?;
+        if header.height != checkpoint.height || header.hash()[..] != checkpoint.hash[..] {
+            return Err(verify_error(format!(
+                "Block at height {} does not match the trusted checkpoint",
+                checkpoint.height
+            )));
+        }
+
+        let mut headers = BTreeMap::new();
+        headers.insert(header.height, header);
+
+        Ok(LightClient {
+            rpc_client,
+            headers,
+        })
+    }
+
+    /// Returns the verified header at the height, verifying every header
+    /// from the latest verified one up to the height
+    pub fn header(&mut self, height: u64) -> Result<&BlockHeader> {
+        loop {
+            let (&latest_height, latest_header) = self.headers.iter().next_back().chain(|| {
+                (
+                    ErrorKind::InternalError,
+                    "Light client has no trusted header",
+                )
+            })?;
+            if latest_height >= height {
+                break;
+            }
+
+            let next_header = self.verify_next(latest_header)?;
+            self.headers.insert(next_header.height, next_header);
+            self.headers = self.headers.split_off(&latest_height);
+        }
+
+        self.headers.get(&height).chain(|| {
+            (
+                ErrorKind::InvalidInput,
+                format!(
+                    "Block at height {} is before the verified blocks of the light client",
+                    height
+                ),
+            )
+        })
+    }
+
+    /// Verify the block of the RPC `block` result is the verified block at
+    /// the height and the transactions are the ones its header commits to
+    pub fn verify_block(&mut self, height: u64, block: &Value, txs: &[Vec<u8>]) -> Result<()> {
+        let header = self.header(height)?;
+
+        if parse_header(block)?.hash() != header.hash() {
+            return Err(verify_error(format!(
+                "Block at height {} is not the verified block",
+                height
+            )));
+        }
+        if data_hash(txs) != header.data_hash {
+            return Err(verify_error(format!(
+                "Transactions of block at height {} do not match its data hash",
+                height
+            )));
+        }
+
+        Ok(())
+    }
+
+    /// Verify the code and data of the DeliverTx results of the block at the
+    /// height against the last results hash of the next verified header
+    pub fn verify_block_results(&mut self, height: u64, results: &[(u32, Vec<u8>)]) -> Result<()> {
+        let next_header = self.header(height + 1)?;
+
+        if results_hash(results) != next_header.last_results_hash {
+            return Err(verify_error(format!(
+                "Transaction results of block at height {} do not match the last results hash",
+                height
+            )));
+        }
+
+        Ok(())
+    }
+
+    /// Verify the header following the trusted header is linked to it,
+    /// signed by its next validator set and committed by more than 2/3 of
+    /// the voting power
+    fn verify_next(&self, trusted_header: &BlockHeader) -> Result<BlockHeader> {
+        let height = trusted_header.height + 1;
+        let (header, commit) = parse_signed_header(&self.rpc_client.commit(height)?)?;
+        let validators = parse_validators(&Value::Array(self.rpc_client.validators(height)?))?;
+
+        if header.height != height {
+            return Err(verify_error(format!(
+                "Tendermint RPC returns block at height {} instead of {}",
+                header.height, height
+            )));
+        }
+        if header.chain_id != trusted_header.chain_id {
+            return Err(verify_error(format!(
+                "Block at height {} is not on chain {}",
+                height, trusted_header.chain_id
+            )));
+        }
+        let is_linked = header
+            .last_block_id
+            .as_ref()
+            .map_or(false, |last_block_id| {
+                last_block_id.hash[..] == trusted_header.hash()[..]
+            });
+        if !is_linked {
+            return Err(verify_error(format!(
+                "Block at height {} does not follow the verified block",
+                height
+            )));
+        }
+        if validators_hash(&validators) != trusted_header.next_validators_hash {
+            return Err(verify_error(format!(
+                "Validator set at height {} is not the next validator set of the verified block",
+                height
+            )));
+        }
+
+        let verification = verify_commit(&header, &commit, &validators)?;
+        if !verification.is_valid {
+            return Err(verify_error(format!(
+                "Commit of block at height {} is invalid: {}",
+                height,
+                verification.reason.unwrap_or_default()
+            )));
+        }
+
+        Ok(header)
+    }
+}
+
+fn verify_error(message: String) -> Error {
+    Error::new(ErrorKind::VerifyError, message)
+}
@@ -0,0 +1,713 @@
+mod batch;
+mod block_filter;
+mod checkpoint;
+mod history;
+mod light_client;
+mod progress;
+mod syncer;
+
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::time::Duration;
+
+use neon::event::EventHandler;
+use neon::prelude::*;
+
+use chain_core::common::HASH_SIZE_256;
+use chain_core::init::address::CroAddress;
+use chain_core::init::coin::Coin;
+use chain_core::init::network::Network;
+use chain_core::state::account::StakedStateAddress;
+use chain_core::tx::data::address::ExtendedAddr;
+use chain_core::tx::data::input::TxoPointer;
+use client_common::PrivateKey;
+
+use crate::cancellation::CancellationToken;
+use crate::common::{does_js_object_has_prop, serialize_to_js, Features};
+use crate::error::{ClientErrorNeonExt, ErrorCode, NativeError};
+use crate::function_types::*;
+use crate::network::builtin_network_config_from_value;
+use crate::rpc::TendermintRpcClient;
+use crate::storage::{storage_argument, wallet_name_argument};
+
+use batch::{SyncBatches, DEFAULT_BATCH_SIZE};
+use checkpoint::{CheckpointStorage, SyncCheckpoint};
+use history::{Counterparty, HistoryEntry};
+use light_client::TrustedCheckpoint;
+use progress::{SyncProgress, DEFAULT_PROGRESS_INTERVAL};
+use syncer::{BlockSyncer, OwnedOutput, SyncResult, SyncedBlock, SyncedTransaction};
+
+/// Height the sync of a wallet starts from without checkpoint
+const DEFAULT_FROM_HEIGHT: u64 = 1;
+
+/// synchronize blocks and pick out the transactions relevant to the wallet
+/// @arguments
+/// - options:
+///     - tendermintRpcUrl: Tendermint RPC endpoint, e.g. http://127.0.0.1:26657
+///     - tendermintAddress: Tendermint websocket address, used to look up the
+///         tx-query enclave address
+///     - fromHeight: block height to start from
+///     - toHeight: (optional) last block height to sync, latest when omitted
+///     - viewPrivateKeys: (optional) view private key Buffers to decrypt
+///         transfer and withdraw transactions
+///     - transferAddresses: (optional) transfer addresses whose outputs are
+///         returned
+///     - stakingAddresses: (optional) staking addresses whose staking
+///         transactions are returned
+///     - utxos: (optional) [{ prevTxId, prevIndex, value }] wallet UTXOs
+///         before fromHeight, used to compute the history of transactions
+///         spending them
+///     - network: network handle or name of the addresses
+///     - features: native features
+///     - trustedCheckpoint: (optional) { height, hash } of a trusted block at
+///         or before fromHeight, hash is hex string. When provided, every
+///         block is verified from the checkpoint against the commits and
+///         validator sets before its transactions are returned, and sync
+///         stops one block before the latest block
+///     - onProgress: (optional) function called with { current, target,
+///         percent } as the blocks are synced, current is the height of the
+///         last synced block. Progress is delivered through the event loop
+///     - progressIntervalMs: (optional) minimum interval between progress
+///         reports, default 500. The last block is always reported
+///     - onBlocks: (optional) function called with the result of every batch
+///         of batchSize blocks but the last one, { lastHeight, blocks,
+///         history, utxos } as the sync result with the blocks of the batch.
+///         The last batch is in the sync result. Batches are delivered
+///         through the event loop
+///     - batchSize: (optional) number of blocks of a batch, default 100
+///     - useBlockFilter: (optional) boolean, default false. Skip the blocks
+///         without transactions, and the blocks whose block filter matches
+///         none of the view keys, before fetching them. Blocks are only
+///         skipped by the filter when no staking address is tracked, as
+///         staking transactions are not in the filter. Cannot be used with
+///         trustedCheckpoint because the filter is not committed by headers
+/// - callback: node-style callback, sync on background thread and pass the
+///     result to callback
+/// @return task handle { id, cancel() }, sync stops before the next block
+///     once cancelled. The result passed to callback is { lastHeight, blocks: [{ height, time, transactions }], history,
+///     utxos }, only blocks with relevant transactions are returned. history
+///     entries are { txId, txType, direction, amount, fee?, blockHeight, time,
+///     counterparties }. utxos are the wallet UTXOs after lastHeight
+///     [{ prevTxId, prevIndex, value }], spent ones are only known for
+///     decrypted transactions
+pub fn sync_blocks(mut ctx: FunctionContext) -> JsResult<JsValue> {
+    let options = SyncBlocksOptions::parse(&mut ctx, 0)?;
+    let callback = required_callback_argument(&mut ctx)?;
+    let from_height = match options.from_height {
+        Some(from_height) => from_height,
+        None => return ctx.throw_error("fromHeight is required"),
+    };
+
+    run_sync(&mut ctx, options, from_height, None, callback)
+}
+
+/// resume the sync of the wallet after the last block synced by `resume`,
+/// the sync checkpoint of the wallet is saved in the storage as the results
+/// are handed over, i.e. after every batch returned by onBlocks and with the
+/// sync result, so that it is picked up after a restart or crash
+/// @arguments
+/// - storage: storage handle
+/// - wallet_name: wallet name
+/// - options: options of `syncBlocks`. fromHeight is only used before the
+///     first checkpoint, default 1, and utxos are replaced by the ones of the
+///     checkpoint
+/// - callback: node-style callback, sync on background thread and pass the
+///     result of `syncBlocks` to callback
+/// @return task handle { id, cancel() }. A cancelled or failed sync leaves
+///     the checkpoint at the last batch handed to onBlocks
+pub fn resume(mut ctx: FunctionContext) -> JsResult<JsValue> {
+    let storage = storage_argument(&mut ctx, 0)?;
+    let wallet_name = wallet_name_argument(&mut ctx, 1)?;
+    let mut options = SyncBlocksOptions::parse(&mut ctx, 2)?;
+    let callback = required_callback_argument(&mut ctx)?;
+
+    let checkpoint = SyncCheckpoint::load(&storage, &wallet_name)
+        .chain_neon(&mut ctx, "Unable to load sync checkpoint")?;
+    let from_height = match checkpoint {
+        Some(checkpoint) => {
+            options.known_utxos = checkpoint
+                .known_utxos()
+                .chain_neon(&mut ctx, "Unable to load sync checkpoint UTXOs")?;
+            checkpoint.last_height + 1
+        }
+        None => options.from_height.unwrap_or(DEFAULT_FROM_HEIGHT),
+    };
+
+    run_sync(
+        &mut ctx,
+        options,
+        from_height,
+        Some(CheckpointStorage {
+            storage,
+            wallet_name,
+        }),
+        callback,
+    )
+}
+
+/// returns the sync checkpoint of the wallet saved by `resume`
+/// @arguments
+/// - storage: storage handle
+/// - wallet_name: wallet name
+/// @return { lastHeight, utxos: [{ prevTxId, prevIndex, value }] } or null
+///     when the wallet has not synced
+pub fn load_checkpoint(mut ctx: FunctionContext) -> JsResult<JsValue> {
+    let storage = storage_argument(&mut ctx, 0)?;
+    let wallet_name = wallet_name_argument(&mut ctx, 1)?;
+
+    let checkpoint = SyncCheckpoint::load(&storage, &wallet_name)
+        .chain_neon(&mut ctx, "Unable to load sync checkpoint")?;
+    match checkpoint {
+        Some(checkpoint) => serialize_to_js(&mut ctx, &checkpoint),
+        None => Ok(ctx.null().upcast()),
+    }
+}
+
+/// delete the sync checkpoint of the wallet, the next `resume` starts from
+/// its fromHeight option
+/// @arguments
+/// - storage: storage handle
+/// - wallet_name: wallet name
+/// @return boolean: whether the checkpoint existed
+pub fn delete_checkpoint(mut ctx: FunctionContext) -> JsResult<JsBoolean> {
+    let storage = storage_argument(&mut ctx, 0)?;
+    let wallet_name = wallet_name_argument(&mut ctx, 1)?;
+
+    let existed = SyncCheckpoint::delete(&storage, &wallet_name)
+        .chain_neon(&mut ctx, "Unable to delete sync checkpoint")?;
+
+    Ok(ctx.boolean(existed))
+}
+
+fn run_sync<'a>(
+    ctx: &mut FunctionContext<'a>,
+    options: SyncBlocksOptions,
+    from_height: u64,
+    checkpoint_storage: Option<CheckpointStorage>,
+    callback: Handle<JsFunction>,
+) -> JsResult<'a, JsValue> {
+    let cancellation = CancellationToken::register();
+
+    let mut batches = options.batches;
+    if let Some(batches) = batches.as_mut() {
+        batches.checkpoint_storage = checkpoint_storage.clone();
+    }
+
+    let task = SyncTask {
+        syncer: BlockSyncer {
+            rpc_client: options.rpc_client,
+            tendermint_address: options.tendermint_address,
+            features: options.features,
+            view_private_keys: options.view_private_keys,
+            transfer_addresses: options.transfer_addresses,
+            staking_addresses: options.staking_addresses,
+            known_utxos: options.known_utxos,
+            checkpoint: options.checkpoint,
+            cancellation: cancellation.clone(),
+            progress: options.progress,
+            batches,
+            use_block_filter: options.use_block_filter,
+        },
+        from_height,
+        to_height: options.to_height,
+        network: options.network,
+        checkpoint_storage,
+    };
+    let handle = cancellation.to_js(ctx)?;
+    task.schedule(callback);
+
+    Ok(handle.upcast())
+}
+
+struct SyncTask {
+    syncer: BlockSyncer,
+    from_height: u64,
+    to_height: Option<u64>,
+    network: Network,
+    checkpoint_storage: Option<CheckpointStorage>,
+}
+
+impl SyncTask {
+    /// Save the checkpoint of the result about to be handed to the caller
+    fn save_checkpoint(
+        &self,
+        result: std::result::Result<SyncResult, NativeError>,
+    ) -> std::result::Result<SyncResult, NativeError> {
+        let result = result?;
+        if let Some(checkpoint_storage) = &self.checkpoint_storage {
+            checkpoint_storage
+                .save(result.last_height, &result.utxos)
+                .map_err(|err| {
+                    NativeError::from_client_error("Unable to save sync checkpoint", &err)
+                })?;
+        }
+
+        Ok(result)
+    }
+
+    fn result_to_js<'a, C: Context<'a>>(
+        &self,
+        ctx: &mut C,
+        result: std::result::Result<SyncResult, NativeError>,
+    ) -> JsResult<'a, JsValue> {
+        match result {
+            Ok(result) => Ok(sync_result_to_js(ctx, &result, self.network)?.upcast()),
+            Err(err) => err.throw(ctx),
+        }
+    }
+}
+
+impl Task for SyncTask {
+    type Output = SyncResult;
+    type Error = NativeError;
+    type JsEvent = JsValue;
+
+    fn perform(&self) -> std::result::Result<SyncResult, NativeError> {
+        self.syncer
+            .sync(self.from_height, self.to_height)
+            .map_err(|err| NativeError::from_client_error("Unable to sync blocks", &err))
+    }
+
+    fn complete(
+        self,
+        mut ctx: TaskContext,
+        result: std::result::Result<SyncResult, NativeError>,
+    ) -> JsResult<JsValue> {
+        let result = self.syncer.cancellation.complete(result);
+        let result = self.save_checkpoint(result);
+        self.result_to_js(&mut ctx, result)
+    }
+}
+
+fn sync_result_to_js<'a, C: Context<'a>>(
+    ctx: &mut C,
+    result: &SyncResult,
+    network: Network,
+) -> JsResult<'a, JsObject> {
+    let js_blocks = JsArray::new(ctx, result.blocks.len() as u32);
+    for (i, block) in result.blocks.iter().enumerate() {
+        let js_block = synced_block_to_js(ctx, block, network)?;
+        js_blocks.set(ctx, i as u32, js_block)?;
+    }
+
+    let js_result = ctx.empty_object();
+    let last_height = ctx.number(result.last_height as f64);
+    js_result.set(ctx, "lastHeight", last_height)?;
+    js_result.set(ctx, "blocks", js_blocks)?;
+
+    let js_history = JsArray::new(ctx, 0);
+    let mut history_len = 0;
+    for block in result.blocks.iter() {
+        for transaction in block.transactions.iter() {
+            let js_entry = history_entry_to_js(ctx, block, transaction, network)?;
+            js_history.set(ctx, history_len, js_entry)?;
+            history_len += 1;
+        }
+    }
+    js_result.set(ctx, "history", js_history)?;
+
+    let js_utxos = serialize_to_js(
+        ctx,
+        &SyncCheckpoint::new(result.last_height, &result.utxos).utxos,
+    )?;
+    js_result.set(ctx, "utxos", js_utxos)?;
+
+    Ok(js_result)
+}
+
+fn synced_block_to_js<'a, C: Context<'a>>(
+    ctx: &mut C,
+    block: &SyncedBlock,
+    network: Network,
+) -> JsResult<'a, JsObject> {
+    let js_transactions = JsArray::new(ctx, block.transactions.len() as u32);
+    for (i, transaction) in block.transactions.iter().enumerate() {
+        let js_transaction = synced_transaction_to_js(ctx, transaction, network)?;
+        js_transactions.set(ctx, i as u32, js_transaction)?;
+    }
+
+    let js_block = ctx.empty_object();
+    let height = ctx.number(block.height as f64);
+    js_block.set(ctx, "height", height)?;
+    let time = ctx.string(&block.time);
+    js_block.set(ctx, "time", time)?;
+    js_block.set(ctx, "transactions", js_transactions)?;
+
+    Ok(js_block)
+}
+
+fn synced_transaction_to_js<'a, C: Context<'a>>(
+    ctx: &mut C,
+    transaction: &SyncedTransaction,
+    network: Network,
+) -> JsResult<'a, JsObject> {
+    let js_transaction = ctx.empty_object();
+    let tx_id = ctx.string(hex::encode(transaction.tx_id));
+    js_transaction.set(ctx, "txId", tx_id)?;
+    let tx_type = ctx.string(transaction.tx_type);
+    js_transaction.set(ctx, "txType", tx_type)?;
+
+    let js_inputs = JsArray::new(ctx, transaction.inputs.len() as u32);
+    for (i, input) in transaction.inputs.iter().enumerate() {
+        let js_input = ctx.empty_object();
+        let prev_tx_id = ctx.string(hex::encode(input.id));
+        js_input.set(ctx, "prevTxId", prev_tx_id)?;
+        let prev_index = ctx.number(f64::from(input.index));
+        js_input.set(ctx, "prevIndex", prev_index)?;
+
+        js_inputs.set(ctx, i as u32, js_input)?;
+    }
+    js_transaction.set(ctx, "inputs", js_inputs)?;
+
+    let js_outputs = JsArray::new(ctx, transaction.outputs.len() as u32);
+    for (i, owned_output) in transaction.outputs.iter().enumerate() {
+        let js_output = owned_output_to_js(ctx, owned_output, network)?;
+        js_outputs.set(ctx, i as u32, js_output)?;
+    }
+    js_transaction.set(ctx, "outputs", js_outputs)?;
+
+    if let Some(staking_address) = transaction.staking_address {
+        let staking_address = ctx.string(staking_address.to_string());
+        js_transaction.set(ctx, "stakingAddress", staking_address)?;
+    }
+
+    Ok(js_transaction)
+}
+
+fn history_entry_to_js<'a, C: Context<'a>>(
+    ctx: &mut C,
+    block: &SyncedBlock,
+    transaction: &SyncedTransaction,
+    network: Network,
+) -> JsResult<'a, JsObject> {
+    let entry: &HistoryEntry = &transaction.history;
+
+    let js_entry = ctx.empty_object();
+    let tx_id = ctx.string(hex::encode(transaction.tx_id));
+    js_entry.set(ctx, "txId", tx_id)?;
+    let tx_type = ctx.string(transaction.tx_type);
+    js_entry.set(ctx, "txType", tx_type)?;
+    let direction = ctx.string(entry.direction.as_str());
+    js_entry.set(ctx, "direction", direction)?;
+    let amount = ctx.string(u64::from(entry.amount).to_string());
+    js_entry.set(ctx, "amount", amount)?;
+    if let Some(fee) = entry.fee {
+        let fee = ctx.string(u64::from(fee).to_string());
+        js_entry.set(ctx, "fee", fee)?;
+    }
+    let block_height = ctx.number(block.height as f64);
+    js_entry.set(ctx, "blockHeight", block_height)?;
+    let time = ctx.string(&block.time);
+    js_entry.set(ctx, "time", time)?;
+
+    let js_counterparties = JsArray::new(ctx, entry.counterparties.len() as u32);
+    for (i, counterparty) in entry.counterparties.iter().enumerate() {
+        let counterparty = match counterparty {
+            Counterparty::Transfer(address) => address.to_cro(network).chain_neon_with_code(
+                ctx,
+                ErrorCode::InvalidAddress,
+                "Unable to serialize counterparty address",
+            )?,
+            Counterparty::Staking(address) => address.to_string(),
+        };
+        let counterparty = ctx.string(counterparty);
+        js_counterparties.set(ctx, i as u32, counterparty)?;
+    }
+    js_entry.set(ctx, "counterparties", js_counterparties)?;
+
+    Ok(js_entry)
+}
+
+fn owned_output_to_js<'a, C: Context<'a>>(
+    ctx: &mut C,
+    owned_output: &OwnedOutput,
+    network: Network,
+) -> JsResult<'a, JsObject> {
+    let output = &owned_output.output;
+    let address = output.address.to_cro(network).chain_neon_with_code(
+        ctx,
+        ErrorCode::InvalidAddress,
+        "Unable to serialize output address",
+    )?;
+
+    let js_output = ctx.empty_object();
+    let index = ctx.number(f64::from(owned_output.index));
+    js_output.set(ctx, "index", index)?;
+    let address = ctx.string(address);
+    js_output.set(ctx, "address", address)?;
+    let value = ctx.string(u64::from(output.value).to_string());
+    js_output.set(ctx, "value", value)?;
+    if let Some(valid_from) = output.valid_from {
+        let valid_from = ctx.number(valid_from as f64);
+        js_output.set(ctx, "validFrom", valid_from)?;
+    }
+
+    Ok(js_output)
+}
+
+struct SyncBlocksOptions {
+    rpc_client: TendermintRpcClient,
+    tendermint_address: String,
+    from_height: Option<u64>,
+    to_height: Option<u64>,
+    view_private_keys: Vec<PrivateKey>,
+    transfer_addresses: Vec<ExtendedAddr>,
+    staking_addresses: Vec<StakedStateAddress>,
+    known_utxos: HashMap<TxoPointer, Coin>,
+    network: Network,
+    features: Features,
+    checkpoint: Option<TrustedCheckpoint>,
+    progress: Option<SyncProgress>,
+    batches: Option<SyncBatches>,
+    use_block_filter: bool,
+}
+
+impl SyncBlocksOptions {
+    fn parse(ctx: &mut FunctionContext, i: i32) -> NeonResult<SyncBlocksOptions> {
+        let options = ctx.argument::<JsObject>(i).chain_neon_with_code(
+            ctx,
+            ErrorCode::DeserializationError,
+            "Unable to deserialize options object",
+        )?;
+
+        let tendermint_rpc_url = string_option(ctx, options, "tendermintRpcUrl")?;
+        let rpc_client = TendermintRpcClient::new(&tendermint_rpc_url)
+            .chain_neon(ctx, "Unable to create Tendermint RPC client")?;
+        let tendermint_address = string_option(ctx, options, "tendermintAddress")?;
+
+        let from_height = if does_js_object_has_prop(ctx, options, "fromHeight")? {
+            Some(height_option(ctx, options, "fromHeight")?)
+        } else {
+            None
+        };
+        let to_height = if does_js_object_has_prop(ctx, options, "toHeight")? {
+            Some(height_option(ctx, options, "toHeight")?)
+        } else {
+            None
+        };
+
+        let network = options.get(ctx, "network")?;
+        let network = builtin_network_config_from_value(ctx, network)?.network;
+        let features = string_option(ctx, options, "features")?;
+        let features = Features::parse(ctx, &features)?;
+
+        let view_private_keys = optional_array_option(ctx, options, "viewPrivateKeys")?
+            .iter()
+            .map(|&view_private_key| {
+                let view_private_key = view_private_key
+                    .downcast_or_throw::<JsBuffer, FunctionContext>(ctx)
+                    .chain_neon(ctx, "Unable to downcast viewPrivateKey")?;
+                let view_private_key = view_private_key.borrow(&ctx.lock()).as_slice();
+
+                PrivateKey::deserialize_from(view_private_key).chain_neon_with_code(
+                    ctx,
+                    ErrorCode::InvalidPrivateKey,
+                    "Unable to deserialize view private key",
+                )
+            })
+            .collect::<NeonResult<Vec<PrivateKey>>>()?;
+
+        let transfer_addresses = optional_array_option(ctx, options, "transferAddresses")?
+            .iter()
+            .map(|&address| {
+                let address = address
+                    .downcast_or_throw::<JsString, FunctionContext>(ctx)
+                    .chain_neon(ctx, "Unable to downcast transfer address")?
+                    .value();
+
+                ExtendedAddr::from_cro(&address, network).chain_neon_with_code(
+                    ctx,
+                    ErrorCode::InvalidAddress,
+                    "Unable to deserialize transfer address",
+                )
+            })
+            .collect::<NeonResult<Vec<ExtendedAddr>>>()?;
+
+        let staking_addresses = optional_array_option(ctx, options, "stakingAddresses")?
+            .iter()
+            .map(|&address| {
+                let address = address
+                    .downcast_or_throw::<JsString, FunctionContext>(ctx)
+                    .chain_neon(ctx, "Unable to downcast staking address")?
+                    .value();
+
+                StakedStateAddress::from_str(&address).chain_neon_with_code(
+                    ctx,
+                    ErrorCode::InvalidAddress,
+                    "Unable to deserialize staking address",
+                )
+            })
+            .collect::<NeonResult<Vec<StakedStateAddress>>>()?;
+
+        let known_utxos = optional_array_option(ctx, options, "utxos")?
+            .iter()
+            .map(|&utxo| {
+                let utxo = utxo
+                    .downcast_or_throw::<JsObject, FunctionContext>(ctx)
+                    .chain_neon(ctx, "Unable to downcast UTXO")?;
+                let pointer = parse_prev_output_pointer(ctx, utxo)?;
+
+                let value = utxo
+                    .get(ctx, "value")?
+                    .downcast_or_throw::<JsString, FunctionContext>(ctx)
+                    .chain_neon(ctx, "Unable to downcast value in UTXO")?
+                    .value();
+                let value = Coin::from_str(&value).chain_neon_with_code(
+                    ctx,
+                    ErrorCode::DeserializationError,
+                    "Unable to deserialize UTXO Coin value",
+                )?;
+
+                Ok((pointer, value))
+            })
+            .collect::<NeonResult<HashMap<TxoPointer, Coin>>>()?;
+
+        let checkpoint = if does_js_object_has_prop(ctx, options, "trustedCheckpoint")? {
+            let checkpoint = options
+                .get(ctx, "trustedCheckpoint")?
+                .downcast_or_throw::<JsObject, FunctionContext>(ctx)
+                .chain_neon(ctx, "Unable to downcast trustedCheckpoint")?;
+            let height = height_option(ctx, checkpoint, "height")?;
+            let hash = string_option(ctx, checkpoint, "hash")?;
+            let hash = hex::decode(&hash).chain_neon_with_code(
+                ctx,
+                ErrorCode::DeserializationError,
+                "Unable to deserialize trusted checkpoint hash",
+            )?;
+            if hash.len() != HASH_SIZE_256 {
+                return ctx.throw_error(format!(
+                    "Trusted checkpoint hash should be {} bytes",
+                    HASH_SIZE_256
+                ));
+            }
+
+            Some(TrustedCheckpoint { height, hash })
+        } else {
+            None
+        };
+
+        let progress = if does_js_object_has_prop(ctx, options, "onProgress")? {
+            let on_progress = options
+                .get(ctx, "onProgress")?
+                .downcast_or_throw::<JsFunction, FunctionContext>(ctx)
+                .chain_neon(ctx, "Unable to downcast onProgress")?;
+            let interval = optional_integer_option(ctx, options, "progressIntervalMs")?
+                .map(Duration::from_millis)
+                .unwrap_or(DEFAULT_PROGRESS_INTERVAL);
+            let this = ctx.undefined();
+            let handler = EventHandler::new(&*ctx, this, on_progress);
+
+            Some(SyncProgress::new(handler, interval))
+        } else {
+            None
+        };
+
+        let batches = if does_js_object_has_prop(ctx, options, "onBlocks")? {
+            let on_blocks = options
+                .get(ctx, "onBlocks")?
+                .downcast_or_throw::<JsFunction, FunctionContext>(ctx)
+                .chain_neon(ctx, "Unable to downcast onBlocks")?;
+            let size =
+                optional_integer_option(ctx, options, "batchSize")?.unwrap_or(DEFAULT_BATCH_SIZE);
+            if size == 0 {
+                return ctx.throw_error("batchSize should be a positive integer");
+            }
+            let this = ctx.undefined();
+            let handler = EventHandler::new(&*ctx, this, on_blocks);
+
+            Some(SyncBatches::new(handler, size, network))
+        } else {
+            None
+        };
+
+        let use_block_filter = if does_js_object_has_prop(ctx, options, "useBlockFilter")? {
+            options
+                .get(ctx, "useBlockFilter")?
+                .downcast_or_throw::<JsBoolean, FunctionContext>(ctx)
+                .chain_neon(ctx, "Unable to downcast useBlockFilter")?
+                .value()
+        } else {
+            false
+        };
+
+        Ok(SyncBlocksOptions {
+            rpc_client,
+            tendermint_address,
+            from_height,
+            to_height,
+            view_private_keys,
+            transfer_addresses,
+            staking_addresses,
+            known_utxos,
+            network,
+            features,
+            checkpoint,
+            progress,
+            batches,
+            use_block_filter,
+        })
+    }
+}
+
+fn string_option(
+    ctx: &mut FunctionContext,
+    options: Handle<JsObject>,
+    key: &str,
+) -> NeonResult<String> {
+    Ok(options
+        .get(ctx, key)?
+        .downcast_or_throw::<JsString, FunctionContext>(ctx)
+        .chain_neon(ctx, format!("Unable to downcast {}", key))?
+        .value())
+}
+
+fn height_option(
+    ctx: &mut FunctionContext,
+    options: Handle<JsObject>,
+    key: &str,
+) -> NeonResult<u64> {
+    let height = options
+        .get(ctx, key)?
+        .downcast_or_throw::<JsNumber, FunctionContext>(ctx)
+        .chain_neon(ctx, format!("Unable to downcast {}", key))?
+        .value();
+    if height < 0.0 || height.fract() != 0.0 {
+        return ctx.throw_error(format!("{} should be a non-negative integer", key));
+    }
+
+    Ok(height as u64)
+}
+
+fn optional_array_option<'a>(
+    ctx: &mut FunctionContext<'a>,
+    options: Handle<JsObject>,
+    key: &str,
+) -> NeonResult<Vec<Handle<'a, JsValue>>> {
+    if !does_js_object_has_prop(ctx, options, key)? {
+        return Ok(Vec::new());
+    }
+
+    options
+        .get(ctx, key)?
+        .downcast_or_throw::<JsArray, FunctionContext>(ctx)
+        .chain_neon(ctx, format!("Unable to downcast {}", key))?
+        .to_vec(ctx)
+}
+
+pub fn register_sync_module(ctx: &mut ModuleContext) -> NeonResult<()> {
+    let js_object = JsObject::new(ctx);
+
+    let sync_blocks_fn = JsFunction::new(ctx, sync_blocks)?;
+    js_object.set(ctx, "syncBlocks", sync_blocks_fn)?;
+
+    let resume_fn = JsFunction::new(ctx, resume)?;
+    js_object.set(ctx, "resume", resume_fn)?;
+
+    let load_checkpoint_fn = JsFunction::new(ctx, load_checkpoint)?;
+    js_object.set(ctx, "loadCheckpoint", load_checkpoint_fn)?;
+
+    let delete_checkpoint_fn = JsFunction::new(ctx, delete_checkpoint)?;
+    js_object.set(ctx, "deleteCheckpoint", delete_checkpoint_fn)?;
+
+    ctx.export_value("sync", js_object)
+}
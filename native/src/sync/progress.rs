@@ -0,0 +1,60 @@
+//! Progress reporting of the block sync to the JS `onProgress` callback,
+//! throttled so that a fast sync does not flood the event loop
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use neon::event::EventHandler;
+use neon::prelude::*;
+
+pub const DEFAULT_PROGRESS_INTERVAL: Duration = Duration::from_millis(500);
+
+pub struct SyncProgress {
+    handler: EventHandler,
+    interval: Duration,
+    last_reported: Mutex<Option<Instant>>,
+}
+
+impl SyncProgress {
+    pub fn new(handler: EventHandler, interval: Duration) -> SyncProgress {
+        SyncProgress {
+            handler,
+            interval,
+            last_reported: Mutex::new(None),
+        }
+    }
+
+    /// Report the block at `current` of the sync from `from_height` to
+    /// `target` as done. Reports within the interval of the last one are
+    /// skipped, except the one of the last block
+    pub fn report(&self, from_height: u64, current: u64, target: u64) {
+        let mut last_reported = match self.last_reported.lock() {
+            Ok(last_reported) => last_reported,
+            Err(_) => return,
+        };
+        let now = Instant::now();
+        let is_due = last_reported.map_or(true, |last_reported: Instant| {
+            now.duration_since(last_reported) >= self.interval
+        });
+        if !is_due && current < target {
+            return;
+        }
+        *last_reported = Some(now);
+
+        let total = target.saturating_sub(from_height) + 1;
+        let done = current.saturating_sub(from_height) + 1;
+        let percent = (done as f64 / total as f64 * 100.0).min(100.0);
+        self.handler.schedule_with(move |ctx, this, callback| {
+            let progress = ctx.empty_object();
+            let current = ctx.number(current as f64);
+            let target = ctx.number(target as f64);
+            let percent = ctx.number(percent);
+            if progress.set(ctx, "current", current).is_err()
+                || progress.set(ctx, "target", target).is_err()
+                || progress.set(ctx, "percent", percent).is_err()
+            {
+                return;
+            }
+            let _ = callback.call(ctx, this, vec![progress.upcast::<JsValue>()]);
+        });
+    }
+}
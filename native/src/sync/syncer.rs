@@ -0,0 +1,579 @@
+//! Block synchronization of transactions relevant to a wallet
+use std::cmp;
+use std::collections::HashMap;
+
+use chain_core::init::coin::Coin;
+use chain_core::state::account::StakedStateAddress;
+use chain_core::tx::data::address::ExtendedAddr;
+use chain_core::tx::data::input::TxoPointer;
+use chain_core::tx::data::output::TxOut;
+use chain_core::tx::data::TxId;
+use chain_core::tx::{TransactionId, TxAux, TxEnclaveAux, TxPublicAux};
+use chain_tx_validation::witness::verify_tx_recover_address;
+use client_common::{Error, ErrorKind, PrivateKey, PublicKey, Result, ResultExt, Transaction};
+use parity_scale_codec::Decode;
+use serde_json::Value;
+
+use crate::cancellation::CancellationToken;
+use crate::common::Features;
+use crate::rpc::TendermintRpcClient;
+use crate::tx_aux::decrypt_transactions;
+
+use super::batch::SyncBatches;
+use super::block_filter::parse_block_filter;
+use super::history::HistoryEntry;
+use super::light_client::{LightClient, TrustedCheckpoint};
+use super::progress::SyncProgress;
+
+/// Walks blocks and picks out the transactions relevant to the tracked view
+/// keys, transfer addresses and staking addresses
+pub struct BlockSyncer {
+    pub rpc_client: TendermintRpcClient,
+    /// Tendermint websocket address, used to look up the tx-query enclave
+    pub tendermint_address: String,
+    pub features: Features,
+    pub view_private_keys: Vec<PrivateKey>,
+    pub transfer_addresses: Vec<ExtendedAddr>,
+    pub staking_addresses: Vec<StakedStateAddress>,
+    /// Values of the wallet UTXOs before `from_height`, used to compute the
+    /// history of transactions spending them
+    pub known_utxos: HashMap<TxoPointer, Coin>,
+    /// Verify the blocks against the checkpoint instead of trusting the RPC
+    /// node when provided
+    pub checkpoint: Option<TrustedCheckpoint>,
+    /// Sync stops before the next block once cancelled
+    pub cancellation: CancellationToken,
+    /// Reports the synced blocks when provided
+    pub progress: Option<SyncProgress>,
+    /// Hands the synced blocks over batch by batch when provided
+    pub batches: Option<SyncBatches>,
+    /// Skip the blocks whose block filter matches none of the view keys
+    /// without fetching them
+    pub use_block_filter: bool,
+}
+
+pub struct SyncResult {
+    /// Height of the last synchronized block
+    pub last_height: u64,
+    /// Blocks with at least one relevant transaction
+    pub blocks: Vec<SyncedBlock>,
+    /// Known wallet UTXOs after the last synchronized block, i.e. the known
+    /// UTXOs before the sync and the received outputs, less the spent ones
+    pub utxos: HashMap<TxoPointer, Coin>,
+}
+
+#[derive(Clone)]
+pub struct SyncedBlock {
+    pub height: u64,
+    pub time: String,
+    pub transactions: Vec<SyncedTransaction>,
+}
+
+#[derive(Clone)]
+pub struct SyncedTransaction {
+    pub tx_id: TxId,
+    pub tx_type: &'static str,
+    /// Spent outputs. Inputs of transfer transactions are only known when the
+    /// transaction can be decrypted
+    pub inputs: Vec<TxoPointer>,
+    /// Outputs belong to the tracked transfer addresses
+    pub outputs: Vec<OwnedOutput>,
+    pub staking_address: Option<StakedStateAddress>,
+    pub history: HistoryEntry,
+}
+
+#[derive(Clone)]
+pub struct OwnedOutput {
+    /// Index of the output in the transaction
+    pub index: u16,
+    pub output: TxOut,
+}
+
+impl BlockSyncer {
+    /// Synchronize blocks from `from_height` to `to_height` inclusively. Sync
+    /// up to the latest block when `to_height` is not provided. With a
+    /// checkpoint, sync stops one block before the latest block because the
+    /// transaction results of a block are committed by the next header
+    pub fn sync(&self, from_height: u64, to_height: Option<u64>) -> Result<SyncResult> {
+        let to_height = match (to_height, &self.checkpoint) {
+            (Some(to_height), None) => to_height,
+            (None, None) => self.latest_height()?,
+            (to_height, Some(_)) => {
+                let last_verifiable_height = self.latest_height()?.saturating_sub(1);
+                to_height.map_or(last_verifiable_height, |to_height| {
+                    cmp::min(to_height, last_verifiable_height)
+                })
+            }
+        };
+        if from_height > to_height {
+            return Ok(SyncResult {
+                last_height: from_height.saturating_sub(1),
+                blocks: Vec::new(),
+                utxos: self.known_utxos.clone(),
+            });
+        }
+
+        let mut light_client = match &self.checkpoint {
+            Some(checkpoint) if checkpoint.height > from_height => {
+                return Err(Error::new(
+                    ErrorKind::InvalidInput,
+                    "Trusted checkpoint should not be after fromHeight",
+                ))
+            }
+            // The header commits to the transaction results but not to the
+            // end block events the filter is in
+            Some(_) if self.use_block_filter => {
+                return Err(Error::new(
+                    ErrorKind::InvalidInput,
+                    "Block filter cannot be verified against trusted checkpoint",
+                ))
+            }
+            Some(checkpoint) => Some(LightClient::new(&self.rpc_client, checkpoint)?),
+            None => None,
+        };
+        let filter_view_keys = if self.use_block_filter {
+            Some(
+                self.view_private_keys
+                    .iter()
+                    .map(|view_private_key| {
+                        secp256k1::PublicKey::from(PublicKey::from(view_private_key))
+                    })
+                    .collect::<Vec<secp256k1::PublicKey>>(),
+            )
+        } else {
+            None
+        };
+
+        log::info!("Syncing blocks {} to {}", from_height, to_height);
+        let mut known_utxos = self.known_utxos.clone();
+        let mut blocks = Vec::new();
+        let mut delivered_blocks = 0;
+        for height in from_height..=to_height {
+            self.cancellation.check()?;
+            let block = self.sync_block(
+                height,
+                &mut known_utxos,
+                light_client.as_mut(),
+                filter_view_keys.as_deref(),
+            )?;
+            log::debug!(
+                "Synced block {} with {} relevant transactions",
+                height,
+                block.transactions.len()
+            );
+            if let Some(progress) = &self.progress {
+                progress.report(from_height, height, to_height);
+            }
+            if !block.transactions.is_empty() {
+                blocks.push(block);
+            }
+            if let Some(batches) = &self.batches {
+                if height < to_height && (height - from_height + 1) % batches.size() == 0 {
+                    batches.deliver(SyncResult {
+                        last_height: height,
+                        blocks: blocks[delivered_blocks..].to_vec(),
+                        utxos: unspent_utxos(&known_utxos, &blocks),
+                    });
+                    delivered_blocks = blocks.len();
+                }
+            }
+        }
+        log::info!(
+            "Synced blocks {} to {}, {} with relevant transactions",
+            from_height,
+            to_height,
+            blocks.len()
+        );
+
+        let utxos = unspent_utxos(&known_utxos, &blocks);
+        Ok(SyncResult {
+            last_height: to_height,
+            blocks,
+            utxos,
+        })
+    }
+
+    fn latest_height(&self) -> Result<u64> {
+        let status = self.rpc_client.status()?;
+
+        status
+            .pointer("/sync_info/latest_block_height")
+            .and_then(Value::as_str)
+            .and_then(|height| height.parse::<u64>().ok())
+            .chain(|| {
+                (
+                    ErrorKind::DeserializationError,
+                    "Unable to deserialize latest block height from Tendermint status",
+                )
+            })
+    }
+
+    fn sync_block(
+        &self,
+        height: u64,
+        known_utxos: &mut HashMap<TxoPointer, Coin>,
+        mut light_client: Option<&mut LightClient>,
+        filter_view_keys: Option<&[secp256k1::PublicKey]>,
+    ) -> Result<SyncedBlock> {
+        // Block results come first with the block filter so that the
+        // skipped blocks are never fetched
+        let mut block_results = None;
+        if let Some(filter_view_keys) = filter_view_keys {
+            let filtered_block_results = self.rpc_client.block_results(height)?;
+            if self.is_filtered_out(&filtered_block_results, filter_view_keys)? {
+                log::debug!("Skipped block {} by block filter", height);
+                return Ok(SyncedBlock {
+                    height,
+                    time: String::new(),
+                    transactions: Vec::new(),
+                });
+            }
+            block_results = Some(filtered_block_results);
+        }
+
+        let block = self.rpc_client.block(height)?;
+        let time = block
+            .pointer("/block/header/time")
+            .and_then(Value::as_str)
+            .unwrap_or_default()
+            .to_owned();
+
+        let txs = match block.pointer("/block/data/txs").and_then(Value::as_array) {
+            Some(txs) => txs
+                .iter()
+                .map(decode_block_tx)
+                .collect::<Result<Vec<Vec<u8>>>>()?,
+            None => Vec::new(),
+        };
+        if let Some(light_client) = &mut light_client {
+            light_client.verify_block(height, &block, &txs)?;
+        }
+        if txs.is_empty() {
+            return Ok(SyncedBlock {
+                height,
+                time,
+                transactions: Vec::new(),
+            });
+        }
+
+        // Transactions failed in DeliverTx are included in the block but
+        // have no effect on the state
+        let block_results = match block_results {
+            Some(block_results) => block_results,
+            None => self.rpc_client.block_results(height)?,
+        };
+        let empty_tx_results = Vec::new();
+        let tx_results = block_results
+            .get("txs_results")
+            .and_then(Value::as_array)
+            .unwrap_or(&empty_tx_results);
+        if let Some(light_client) = &mut light_client {
+            light_client.verify_block_results(height, &deliver_tx_results(tx_results)?)?;
+        }
+
+        let mut tx_auxs = Vec::new();
+        for (i, tx) in txs.iter().enumerate() {
+            let code = tx_results
+                .get(i)
+                .and_then(|tx_result| tx_result.get("code"))
+                .and_then(Value::as_u64)
+                .unwrap_or_default();
+            if code == 0 {
+                tx_auxs.push(decode_tx_aux(tx)?);
+            }
+        }
+
+        let decrypted_txs = self.decrypt_transactions(&tx_auxs)?;
+        let mut transactions = Vec::new();
+        for tx_aux in tx_auxs.iter() {
+            let synced_tx = match self.relevant_transaction(tx_aux, &decrypted_txs, known_utxos)? {
+                Some(synced_tx) => synced_tx,
+                None => continue,
+            };
+
+            // Outputs received earlier in the sync are spendable by the
+            // following transactions
+            for owned_output in synced_tx.outputs.iter() {
+                let pointer = TxoPointer {
+                    id: synced_tx.tx_id,
+                    index: owned_output.index,
+                };
+                known_utxos.insert(pointer, owned_output.output.value);
+            }
+            transactions.push(synced_tx);
+        }
+
+        Ok(SyncedBlock {
+            height,
+            time,
+            transactions,
+        })
+    }
+
+    /// Returns whether the block has no transaction for the wallet according
+    /// to its results and block filter
+    fn is_filtered_out(
+        &self,
+        block_results: &Value,
+        filter_view_keys: &[secp256k1::PublicKey],
+    ) -> Result<bool> {
+        let has_txs = block_results
+            .get("txs_results")
+            .and_then(Value::as_array)
+            .map_or(false, |tx_results| !tx_results.is_empty());
+        if !has_txs {
+            return Ok(true);
+        }
+        // Staking transactions are public so their staking addresses are not
+        // in the filter
+        if !self.staking_addresses.is_empty() {
+            return Ok(false);
+        }
+
+        let block_filter = parse_block_filter(block_results)?;
+        Ok(!filter_view_keys
+            .iter()
+            .any(|view_key| block_filter.check_view_key(view_key)))
+    }
+
+    /// Decrypt the transfer and withdraw transactions with every tracked view
+    /// key, returns the transactions accessible by any of them
+    fn decrypt_transactions(&self, tx_auxs: &[TxAux]) -> Result<HashMap<TxId, Transaction>> {
+        let enclave_txs = tx_auxs
+            .iter()
+            .filter_map(|tx_aux| match tx_aux {
+                TxAux::EnclaveTx(enclave_tx @ TxEnclaveAux::TransferTx { .. })
+                | TxAux::EnclaveTx(enclave_tx @ TxEnclaveAux::WithdrawUnbondedStakeTx { .. }) => {
+                    Some(enclave_tx.clone())
+                }
+                _ => None,
+            })
+            .collect::<Vec<TxEnclaveAux>>();
+
+        let mut decrypted_txs = HashMap::new();
+        if enclave_txs.is_empty() {
+            return Ok(decrypted_txs);
+        }
+        for view_private_key in self.view_private_keys.iter() {
+            let transactions = decrypt_transactions(
+                &enclave_txs,
+                view_private_key,
+                &self.tendermint_address,
+                self.features,
+            )?;
+            for transaction in transactions {
+                let tx_id = match &transaction {
+                    Transaction::TransferTransaction(tx) => tx.id(),
+                    Transaction::WithdrawUnbondedStakeTransaction(tx) => tx.id(),
+                    _ => continue,
+                };
+                decrypted_txs.insert(tx_id, transaction);
+            }
+        }
+
+        Ok(decrypted_txs)
+    }
+
+    fn relevant_transaction(
+        &self,
+        tx_aux: &TxAux,
+        decrypted_txs: &HashMap<TxId, Transaction>,
+        known_utxos: &HashMap<TxoPointer, Coin>,
+    ) -> Result<Option<SyncedTransaction>> {
+        let synced_tx = match tx_aux {
+            TxAux::EnclaveTx(TxEnclaveAux::TransferTx { payload, .. }) => {
+                match decrypted_txs.get(&payload.txid) {
+                    Some(Transaction::TransferTransaction(tx)) => SyncedTransaction {
+                        tx_id: payload.txid,
+                        tx_type: "Transfer",
+                        inputs: tx.inputs.clone(),
+                        outputs: self.owned_outputs(&tx.outputs),
+                        staking_address: None,
+                        history: HistoryEntry::transfer(
+                            &tx.inputs,
+                            &tx.outputs,
+                            &self.transfer_addresses,
+                            known_utxos,
+                        )?,
+                    },
+                    _ => return Ok(None),
+                }
+            }
+            TxAux::EnclaveTx(TxEnclaveAux::DepositStakeTx { tx, .. }) => {
+                if !self.is_tracked_staking_address(Some(tx.to_staked_account)) {
+                    return Ok(None);
+                }
+
+                SyncedTransaction {
+                    tx_id: tx.id(),
+                    tx_type: "DepositStake",
+                    inputs: tx.inputs.clone(),
+                    outputs: Vec::new(),
+                    staking_address: Some(tx.to_staked_account),
+                    history: HistoryEntry::deposit(&tx.inputs, tx.to_staked_account, known_utxos)?,
+                }
+            }
+            TxAux::EnclaveTx(TxEnclaveAux::WithdrawUnbondedStakeTx {
+                witness, payload, ..
+            }) => {
+                let staking_address = verify_tx_recover_address(witness, &payload.txid).ok();
+                let outputs = match decrypted_txs.get(&payload.txid) {
+                    Some(Transaction::WithdrawUnbondedStakeTransaction(tx)) => {
+                        self.owned_outputs(&tx.outputs)
+                    }
+                    _ if self.is_tracked_staking_address(staking_address) => Vec::new(),
+                    _ => return Ok(None),
+                };
+                let owned_outputs = outputs
+                    .iter()
+                    .map(|owned_output| owned_output.output.clone())
+                    .collect::<Vec<TxOut>>();
+
+                SyncedTransaction {
+                    tx_id: payload.txid,
+                    tx_type: "WithdrawUnbondedStake",
+                    inputs: Vec::new(),
+                    outputs,
+                    staking_address,
+                    history: HistoryEntry::withdraw(&owned_outputs, staking_address)?,
+                }
+            }
+            TxAux::PublicTx(TxPublicAux::UnbondStakeTx(tx, _)) => {
+                return Ok(self.relevant_staking_transaction(
+                    tx.id(),
+                    "UnbondStake",
+                    tx.from_staked_account,
+                    tx.value,
+                ))
+            }
+            TxAux::PublicTx(TxPublicAux::UnjailTx(tx, _)) => {
+                return Ok(self.relevant_staking_transaction(
+                    tx.id(),
+                    "Unjail",
+                    tx.address,
+                    Coin::zero(),
+                ))
+            }
+            TxAux::PublicTx(TxPublicAux::NodeJoinTx(tx, _)) => {
+                return Ok(self.relevant_staking_transaction(
+                    tx.id(),
+                    "NodeJoin",
+                    tx.address,
+                    Coin::zero(),
+                ))
+            }
+        };
+
+        Ok(Some(synced_tx))
+    }
+
+    fn relevant_staking_transaction(
+        &self,
+        tx_id: TxId,
+        tx_type: &'static str,
+        staking_address: StakedStateAddress,
+        amount: Coin,
+    ) -> Option<SyncedTransaction> {
+        if !self.is_tracked_staking_address(Some(staking_address)) {
+            return None;
+        }
+
+        Some(SyncedTransaction {
+            tx_id,
+            tx_type,
+            inputs: Vec::new(),
+            outputs: Vec::new(),
+            staking_address: Some(staking_address),
+            history: HistoryEntry::staking(amount),
+        })
+    }
+
+    fn is_tracked_staking_address(&self, staking_address: Option<StakedStateAddress>) -> bool {
+        match staking_address {
+            Some(staking_address) => self.staking_addresses.contains(&staking_address),
+            None => false,
+        }
+    }
+
+    fn owned_outputs(&self, outputs: &[TxOut]) -> Vec<OwnedOutput> {
+        outputs
+            .iter()
+            .enumerate()
+            .filter(|(_, output)| self.transfer_addresses.contains(&output.address))
+            .map(|(index, output)| OwnedOutput {
+                index: index as u16,
+                output: output.clone(),
+            })
+            .collect()
+    }
+}
+
+/// Known UTXOs less the ones spent in the blocks. Received outputs stay in
+/// the known UTXOs during the sync so that the history of the transactions
+/// spending them has their values
+fn unspent_utxos(
+    known_utxos: &HashMap<TxoPointer, Coin>,
+    blocks: &[SyncedBlock],
+) -> HashMap<TxoPointer, Coin> {
+    let mut utxos = known_utxos.clone();
+    for block in blocks.iter() {
+        for transaction in block.transactions.iter() {
+            for input in transaction.inputs.iter() {
+                utxos.remove(input);
+            }
+        }
+    }
+
+    utxos
+}
+
+fn decode_block_tx(tx: &Value) -> Result<Vec<u8>> {
+    let tx = tx.as_str().chain(|| {
+        (
+            ErrorKind::DeserializationError,
+            "Block transaction should be base64 string",
+        )
+    })?;
+
+    base64::decode(tx).chain(|| {
+        (
+            ErrorKind::DeserializationError,
+            "Unable to decode base64 block transaction",
+        )
+    })
+}
+
+/// Code and data of the DeliverTx results, which are what the header of the
+/// next block commits to
+fn deliver_tx_results(tx_results: &[Value]) -> Result<Vec<(u32, Vec<u8>)>> {
+    tx_results
+        .iter()
+        .map(|tx_result| {
+            let code = tx_result
+                .get("code")
+                .and_then(Value::as_u64)
+                .unwrap_or_default() as u32;
+            let data = match tx_result.get("data").and_then(Value::as_str) {
+                Some(data) => base64::decode(data).chain(|| {
+                    (
+                        ErrorKind::DeserializationError,
+                        "Unable to decode base64 data of transaction result",
+                    )
+                })?,
+                None => Vec::new(),
+            };
+
+            Ok((code, data))
+        })
+        .collect()
+}
+
+fn decode_tx_aux(tx: &[u8]) -> Result<TxAux> {
+    let mut tx = tx;
+    TxAux::decode(&mut tx).chain(|| {
+        (
+            ErrorKind::DeserializationError,
+            "Unable to decode block transaction",
+        )
+    })
+}
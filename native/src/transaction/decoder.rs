@@ -0,0 +1,202 @@
+//! Decoder of SCALE-encoded broadcast-able transactions (TxAux)
+use neon::prelude::*;
+
+use chain_core::state::account::{
+    StakedStateAddress, StakedStateOpAttributes, StakedStateOpWitness,
+};
+use chain_core::tx::data::input::TxoPointer;
+use chain_core::tx::data::TxId;
+use chain_core::tx::{TransactionId, TxAux, TxEnclaveAux, TxPublicAux};
+use parity_scale_codec::Encode;
+
+use crate::codec::WitnessDetails;
+use crate::common::serialize_to_js;
+use crate::error::{ClientErrorNeonExt, ErrorCode};
+use crate::function_types::*;
+use crate::strict_decode::{decode_bytes, decode_options_argument};
+
+/// decode broadcast-able transaction into structured object
+/// @arguments
+/// - raw_tx: SCALE-encoded TxAux Buffer
+/// - options: (optional) { strict?, maxSize?, maxVectorLength? }. In strict
+///     mode, for untrusted payloads, the transaction should be at most
+///     maxSize bytes (default 1MiB) with at most maxVectorLength (default
+///     1024) inputs, outputs and view keys, and canonically encoded. Failures
+///     throw DESERIALIZATION_ERROR with a reason of TooLarge,
+///     TooManyElements, InvalidEncoding, TrailingBytes or NonCanonical and
+///     the byte offset when known
+/// @return object with txType, txId and the public fields of the transaction.
+///     Inputs and outputs of enclave transactions stay inside the obfuscated
+///     payload and only their counts are returned. Witness of public
+///     transactions is returned both as Buffer and as decodedWitness object
+pub fn decode(mut ctx: FunctionContext) -> JsResult<JsObject> {
+    let raw_tx = u8_buffer_argument(&mut ctx, 0)?;
+    let limits = decode_options_argument(&mut ctx, 1)?;
+
+    let tx_aux: TxAux = decode_bytes(&mut ctx, &raw_tx, "transaction", limits)?;
+
+    let decoded = ctx.empty_object();
+    match tx_aux {
+        TxAux::EnclaveTx(TxEnclaveAux::TransferTx {
+            inputs,
+            no_of_outputs,
+            payload,
+        }) => {
+            set_tx_type_and_id(&mut ctx, decoded, "Transfer", &payload.txid)?;
+            set_inputs(&mut ctx, decoded, &inputs)?;
+            set_number(&mut ctx, decoded, "noOfOutputs", f64::from(no_of_outputs))?;
+            set_buffer(&mut ctx, decoded, "obfuscatedPayload", &payload.txpayload)?;
+        }
+        TxAux::EnclaveTx(TxEnclaveAux::DepositStakeTx { tx, payload }) => {
+            set_tx_type_and_id(&mut ctx, decoded, "DepositStake", &tx.id())?;
+            set_inputs(&mut ctx, decoded, &tx.inputs)?;
+            set_staking_address(&mut ctx, decoded, "toAddress", &tx.to_staked_account)?;
+            set_attributes(&mut ctx, decoded, &tx.attributes)?;
+            set_buffer(&mut ctx, decoded, "obfuscatedPayload", &payload.txpayload)?;
+        }
+        TxAux::EnclaveTx(TxEnclaveAux::WithdrawUnbondedStakeTx {
+            no_of_outputs,
+            witness,
+            payload,
+        }) => {
+            set_tx_type_and_id(&mut ctx, decoded, "WithdrawUnbondedStake", &payload.txid)?;
+            set_number(&mut ctx, decoded, "noOfOutputs", f64::from(no_of_outputs))?;
+            set_witness(&mut ctx, decoded, &witness)?;
+            set_buffer(&mut ctx, decoded, "obfuscatedPayload", &payload.txpayload)?;
+        }
+        TxAux::PublicTx(TxPublicAux::UnbondStakeTx(tx, witness)) => {
+            set_tx_type_and_id(&mut ctx, decoded, "UnbondStake", &tx.id())?;
+            set_staking_address(&mut ctx, decoded, "fromAddress", &tx.from_staked_account)?;
+            set_string(&mut ctx, decoded, "nonce", tx.nonce.to_string())?;
+            set_string(&mut ctx, decoded, "amount", u64::from(tx.value).to_string())?;
+            set_attributes(&mut ctx, decoded, &tx.attributes)?;
+            set_witness(&mut ctx, decoded, &witness)?;
+        }
+        TxAux::PublicTx(TxPublicAux::UnjailTx(tx, witness)) => {
+            set_tx_type_and_id(&mut ctx, decoded, "Unjail", &tx.id())?;
+            set_staking_address(&mut ctx, decoded, "stakingAddress", &tx.address)?;
+            set_string(&mut ctx, decoded, "nonce", tx.nonce.to_string())?;
+            set_attributes(&mut ctx, decoded, &tx.attributes)?;
+            set_witness(&mut ctx, decoded, &witness)?;
+        }
+        TxAux::PublicTx(TxPublicAux::NodeJoinTx(tx, witness)) => {
+            set_tx_type_and_id(&mut ctx, decoded, "NodeJoin", &tx.id())?;
+            set_staking_address(&mut ctx, decoded, "stakingAddress", &tx.address)?;
+            set_string(&mut ctx, decoded, "nonce", tx.nonce.to_string())?;
+            let node_meta_data = serde_json::to_string(&tx.node_meta).chain_neon_with_code(
+                &mut ctx,
+                ErrorCode::SerializationError,
+                "Unable to serialize nodeMetaData",
+            )?;
+            set_string(&mut ctx, decoded, "nodeMetaData", node_meta_data)?;
+            set_attributes(&mut ctx, decoded, &tx.attributes)?;
+            set_witness(&mut ctx, decoded, &witness)?;
+        }
+    }
+
+    Ok(decoded)
+}
+
+fn set_tx_type_and_id<'a>(
+    ctx: &mut FunctionContext<'a>,
+    decoded: Handle<'a, JsObject>,
+    tx_type: &str,
+    tx_id: &TxId,
+) -> NeonResult<()> {
+    set_string(ctx, decoded, "txType", tx_type.to_owned())?;
+    set_string(ctx, decoded, "txId", hex::encode(tx_id))
+}
+
+fn set_inputs<'a>(
+    ctx: &mut FunctionContext<'a>,
+    decoded: Handle<'a, JsObject>,
+    inputs: &[TxoPointer],
+) -> NeonResult<()> {
+    let js_inputs = JsArray::new(ctx, inputs.len() as u32);
+    for (i, input) in inputs.iter().enumerate() {
+        let js_input = ctx.empty_object();
+        set_string(ctx, js_input, "prevTxId", hex::encode(input.id))?;
+        set_number(ctx, js_input, "prevIndex", f64::from(input.index))?;
+
+        js_inputs
+            .set(ctx, i as u32, js_input)
+            .chain_neon(ctx, "Unable to set input of decoded transaction")?;
+    }
+
+    set_value(ctx, decoded, "inputs", js_inputs)
+}
+
+fn set_staking_address<'a>(
+    ctx: &mut FunctionContext<'a>,
+    decoded: Handle<'a, JsObject>,
+    key: &str,
+    address: &StakedStateAddress,
+) -> NeonResult<()> {
+    set_string(ctx, decoded, key, address.to_string())
+}
+
+fn set_attributes<'a>(
+    ctx: &mut FunctionContext<'a>,
+    decoded: Handle<'a, JsObject>,
+    attributes: &StakedStateOpAttributes,
+) -> NeonResult<()> {
+    let js_attributes = ctx.empty_object();
+    set_buffer(ctx, js_attributes, "chainHexId", &[attributes.chain_hex_id])?;
+
+    set_value(ctx, decoded, "attributes", js_attributes)
+}
+
+fn set_witness<'a>(
+    ctx: &mut FunctionContext<'a>,
+    decoded: Handle<'a, JsObject>,
+    witness: &StakedStateOpWitness,
+) -> NeonResult<()> {
+    set_buffer(ctx, decoded, "witness", &witness.encode())?;
+
+    let decoded_witness = serialize_to_js(ctx, &WitnessDetails::from(witness))?;
+    set_value(ctx, decoded, "decodedWitness", decoded_witness)
+}
+
+fn set_string<'a>(
+    ctx: &mut FunctionContext<'a>,
+    object: Handle<'a, JsObject>,
+    key: &str,
+    value: String,
+) -> NeonResult<()> {
+    let value = ctx.string(value);
+    set_value(ctx, object, key, value)
+}
+
+fn set_number<'a>(
+    ctx: &mut FunctionContext<'a>,
+    object: Handle<'a, JsObject>,
+    key: &str,
+    value: f64,
+) -> NeonResult<()> {
+    let value = ctx.number(value);
+    set_value(ctx, object, key, value)
+}
+
+fn set_buffer<'a>(
+    ctx: &mut FunctionContext<'a>,
+    object: Handle<'a, JsObject>,
+    key: &str,
+    value: &[u8],
+) -> NeonResult<()> {
+    let buffer = bytes_to_buffer(ctx, value)?;
+
+    set_value(ctx, object, key, buffer)
+}
+
+fn set_value<'a, V: Value>(
+    ctx: &mut FunctionContext<'a>,
+    object: Handle<'a, JsObject>,
+    key: &str,
+    value: Handle<'a, V>,
+) -> NeonResult<()> {
+    object
+        .set(ctx, key, value)
+        .chain_neon(ctx, format!("Unable to set {} of decoded transaction", key))?;
+
+    Ok(())
+}
@@ -0,0 +1,235 @@
+use neon::prelude::*;
+
+use chain_core::init::address::CroAddress;
+use chain_core::tx::data::access::{TxAccess, TxAccessPolicy};
+use chain_core::tx::data::input::TxoPointer;
+use chain_core::tx::data::output::TxOut;
+use chain_core::tx::{TransactionId, TxAux, TxEnclaveAux};
+use client_common::{PrivateKey, Transaction};
+use parity_scale_codec::Decode;
+
+use crate::cancellation::CancellationToken;
+use crate::common::Features;
+use crate::error::{ClientErrorNeonExt, ErrorCode, NativeError};
+use crate::function_types::*;
+use crate::tx_aux::decrypt_transactions;
+
+/// decrypt obfuscated transaction with view key through the tx-query enclave
+/// of the connected node
+/// @arguments
+/// - obfuscated_tx: SCALE-encoded transfer or withdraw unbonded TxAux Buffer
+/// - view_private_key: view private key Buffer
+/// - tendermint_address: Tendermint websocket address, used to look up the
+///     tx-query enclave address
+/// - features: native features
+/// - callback: (optional) node-style callback, decrypt on background thread
+///     and pass the result to callback when provided
+/// @return { txId, txType, inputs, outputs, accessPolicies } with the plaintext
+///     outputs and the view keys allowed to decrypt the transaction, or
+///     null when the view key has no access to the transaction. The task
+///     handle { id, cancel() } is returned with callback
+pub fn decrypt_with_view_key(mut ctx: FunctionContext) -> JsResult<JsValue> {
+    let obfuscated_tx = u8_buffer_argument(&mut ctx, 0)?;
+    let view_private_key = private_key_argument(&mut ctx, 1)?;
+    let tendermint_address = ctx.argument::<JsString>(2)?.value();
+    let features = Features::argument(&mut ctx, 3)?;
+    let callback = callback_argument(&mut ctx)?;
+
+    let mut obfuscated_tx = obfuscated_tx.as_slice();
+    let tx_aux = TxAux::decode(&mut obfuscated_tx).chain_neon_with_code(
+        &mut ctx,
+        ErrorCode::DeserializationError,
+        "Unable to decode obfuscated transaction bytes",
+    )?;
+    if !obfuscated_tx.is_empty() {
+        return ctx.throw_error("Unexpected trailing bytes after obfuscated transaction");
+    }
+    let enclave_tx = match tx_aux {
+        TxAux::EnclaveTx(TxEnclaveAux::DepositStakeTx { .. }) => {
+            return ctx.throw_error("Deposit stake transaction has no outputs to decrypt")
+        }
+        TxAux::EnclaveTx(enclave_tx) => enclave_tx,
+        TxAux::PublicTx(_) => return ctx.throw_error("Public transaction is not obfuscated"),
+    };
+
+    let cancellation = match callback {
+        Some(_) => CancellationToken::register(),
+        None => CancellationToken::default(),
+    };
+    let task = DecryptWithViewKeyTask {
+        enclave_txs: vec![enclave_tx],
+        view_private_key,
+        tendermint_address,
+        features,
+        cancellation: cancellation.clone(),
+    };
+    match callback {
+        Some(callback) => {
+            task.schedule(callback);
+            Ok(cancellation.to_js(&mut ctx)?.upcast())
+        }
+        None => {
+            let result = task.perform();
+            decrypted_transaction_to_js(&mut ctx, result)
+        }
+    }
+}
+
+#[derive(Clone)]
+struct DecryptWithViewKeyTask {
+    enclave_txs: Vec<TxEnclaveAux>,
+    view_private_key: PrivateKey,
+    tendermint_address: String,
+    features: Features,
+    cancellation: CancellationToken,
+}
+
+impl Task for DecryptWithViewKeyTask {
+    type Output = Option<Transaction>;
+    type Error = NativeError;
+    type JsEvent = JsValue;
+
+    fn perform(&self) -> std::result::Result<Option<Transaction>, NativeError> {
+        let task = self.clone();
+        self.cancellation.run(move || {
+            decrypt_transactions(
+                &task.enclave_txs,
+                &task.view_private_key,
+                &task.tendermint_address,
+                task.features,
+            )
+            .map(|transactions| transactions.into_iter().next())
+            .map_err(|err| NativeError::from_client_error("Unable to decrypt transaction", &err))
+        })
+    }
+
+    fn complete(
+        self,
+        mut ctx: TaskContext,
+        result: std::result::Result<Option<Transaction>, NativeError>,
+    ) -> JsResult<JsValue> {
+        let result = self.cancellation.complete(result);
+        decrypted_transaction_to_js(&mut ctx, result)
+    }
+}
+
+fn decrypted_transaction_to_js<'a, C: Context<'a>>(
+    ctx: &mut C,
+    result: std::result::Result<Option<Transaction>, NativeError>,
+) -> JsResult<'a, JsValue> {
+    match result {
+        Ok(Some(transaction)) => transaction_to_js(ctx, &transaction),
+        Ok(None) => Ok(ctx.null().upcast()),
+        Err(err) => err.throw(ctx),
+    }
+}
+
+/// Returns { txId, txType, inputs, outputs, accessPolicies } of the decrypted
+/// transfer or withdraw unbonded stake transaction, accessPolicies are the
+/// { viewKey, access } of the view keys allowed to decrypt it
+pub fn transaction_to_js<'a, C: Context<'a>>(
+    ctx: &mut C,
+    transaction: &Transaction,
+) -> JsResult<'a, JsValue> {
+    let (tx_type, tx_id, inputs, outputs, chain_hex_id, allowed_view) = match transaction {
+        Transaction::TransferTransaction(tx) => (
+            "Transfer",
+            tx.id(),
+            tx.inputs.as_slice(),
+            tx.outputs.as_slice(),
+            tx.attributes.chain_hex_id,
+            tx.attributes.allowed_view.as_slice(),
+        ),
+        Transaction::WithdrawUnbondedStakeTransaction(tx) => (
+            "WithdrawUnbondedStake",
+            tx.id(),
+            &[][..],
+            tx.outputs.as_slice(),
+            tx.attributes.chain_hex_id,
+            tx.attributes.allowed_view.as_slice(),
+        ),
+        _ => return ctx.throw_error("Decrypted transaction has no outputs"),
+    };
+
+    let decrypted = ctx.empty_object();
+    let js_tx_type = ctx.string(tx_type);
+    decrypted.set(ctx, "txType", js_tx_type)?;
+    let js_tx_id = ctx.string(hex::encode(tx_id));
+    decrypted.set(ctx, "txId", js_tx_id)?;
+    let js_inputs = inputs_to_js(ctx, inputs)?;
+    decrypted.set(ctx, "inputs", js_inputs)?;
+    let js_outputs = outputs_to_js(ctx, outputs, chain_hex_id)?;
+    decrypted.set(ctx, "outputs", js_outputs)?;
+    let js_access_policies = access_policies_to_js(ctx, allowed_view)?;
+    decrypted.set(ctx, "accessPolicies", js_access_policies)?;
+
+    Ok(decrypted.upcast())
+}
+
+fn access_policies_to_js<'a, C: Context<'a>>(
+    ctx: &mut C,
+    access_policies: &[TxAccessPolicy],
+) -> JsResult<'a, JsArray> {
+    let js_access_policies = JsArray::new(ctx, access_policies.len() as u32);
+    for (i, access_policy) in access_policies.iter().enumerate() {
+        let js_access_policy = ctx.empty_object();
+        let view_key = ctx.string(hex::encode(&access_policy.view_key.serialize()[..]));
+        js_access_policy.set(ctx, "viewKey", view_key)?;
+        let access = match access_policy.access {
+            TxAccess::AllData => "AllData",
+        };
+        let access = ctx.string(access);
+        js_access_policy.set(ctx, "access", access)?;
+        js_access_policies.set(ctx, i as u32, js_access_policy)?;
+    }
+
+    Ok(js_access_policies)
+}
+
+fn inputs_to_js<'a, C: Context<'a>>(ctx: &mut C, inputs: &[TxoPointer]) -> JsResult<'a, JsArray> {
+    let js_inputs = JsArray::new(ctx, inputs.len() as u32);
+    for (i, input) in inputs.iter().enumerate() {
+        let js_input = ctx.empty_object();
+        let prev_tx_id = ctx.string(hex::encode(input.id));
+        js_input.set(ctx, "prevTxId", prev_tx_id)?;
+        let prev_index = ctx.number(f64::from(input.index));
+        js_input.set(ctx, "prevIndex", prev_index)?;
+
+        js_inputs.set(ctx, i as u32, js_input)?;
+    }
+
+    Ok(js_inputs)
+}
+
+fn outputs_to_js<'a, C: Context<'a>>(
+    ctx: &mut C,
+    outputs: &[TxOut],
+    chain_hex_id: u8,
+) -> JsResult<'a, JsArray> {
+    let network = network_from_chain_hex_id(chain_hex_id);
+
+    let js_outputs = JsArray::new(ctx, outputs.len() as u32);
+    for (i, output) in outputs.iter().enumerate() {
+        let address = output.address.to_cro(network).chain_neon_with_code(
+            ctx,
+            ErrorCode::InvalidAddress,
+            "Unable to serialize output address",
+        )?;
+
+        let js_output = ctx.empty_object();
+        let index = ctx.number(i as f64);
+        js_output.set(ctx, "index", index)?;
+        let address = ctx.string(address);
+        js_output.set(ctx, "address", address)?;
+        let value = ctx.string(u64::from(output.value).to_string());
+        js_output.set(ctx, "value", value)?;
+        if let Some(valid_from) = output.valid_from {
+            let valid_from = ctx.number(valid_from as f64);
+            js_output.set(ctx, "validFrom", valid_from)?;
+        }
+
+        js_outputs.set(ctx, i as u32, js_output)?;
+    }
+
+    Ok(js_outputs)
+}
@@ -0,0 +1,46 @@
+mod decoder;
+mod decryption;
+mod obfuscation;
+mod send;
+mod tx_id;
+mod validation;
+mod witness;
+
+use neon::prelude::*;
+
+use decoder::decode;
+use decryption::decrypt_with_view_key;
+use obfuscation::obfuscate;
+use send::send_transfer;
+use tx_id::compute_tx_id;
+use validation::validate_locally;
+use witness::verify_witnesses;
+
+pub use decryption::transaction_to_js;
+
+pub fn register_transaction_module(ctx: &mut ModuleContext) -> NeonResult<()> {
+    let js_object = JsObject::new(ctx);
+
+    let decode_fn = JsFunction::new(ctx, decode)?;
+    js_object.set(ctx, "decode", decode_fn)?;
+
+    let obfuscate_fn = JsFunction::new(ctx, obfuscate)?;
+    js_object.set(ctx, "obfuscate", obfuscate_fn)?;
+
+    let decrypt_with_view_key_fn = JsFunction::new(ctx, decrypt_with_view_key)?;
+    js_object.set(ctx, "decryptWithViewKey", decrypt_with_view_key_fn)?;
+
+    let send_transfer_fn = JsFunction::new(ctx, send_transfer)?;
+    js_object.set(ctx, "sendTransfer", send_transfer_fn)?;
+
+    let compute_tx_id_fn = JsFunction::new(ctx, compute_tx_id)?;
+    js_object.set(ctx, "computeTxId", compute_tx_id_fn)?;
+
+    let verify_witnesses_fn = JsFunction::new(ctx, verify_witnesses)?;
+    js_object.set(ctx, "verifyWitnesses", verify_witnesses_fn)?;
+
+    let validate_locally_fn = JsFunction::new(ctx, validate_locally)?;
+    js_object.set(ctx, "validateLocally", validate_locally_fn)?;
+
+    ctx.export_value("transaction", js_object)
+}
@@ -0,0 +1,49 @@
+use neon::prelude::*;
+
+use client_common::SignedTransaction;
+use parity_scale_codec::Decode;
+
+use crate::common::Features;
+use crate::error::{ClientErrorNeonExt, ErrorCode};
+use crate::function_types::*;
+use crate::tx_aux::signed_transaction_to_hex;
+
+/// obfuscate signed plain transaction through the tx-query enclave of the
+/// connected node
+/// @arguments
+/// - signed_plain_tx: SCALE-encoded SignedTransaction Buffer
+/// - tendermint_address: Tendermint websocket address, used to look up the
+///     tx-query enclave address
+/// - features: native features
+/// - output: (optional) Buffer to encode the transaction into, the number of
+///     bytes written is returned instead of a new Buffer. Not supported with
+///     callback
+/// - callback: (optional) node-style callback, obfuscate on background thread
+///     and pass the result to callback when provided
+/// @return broadcast-able obfuscated TxAux
+pub fn obfuscate(mut ctx: FunctionContext) -> JsResult<JsValue> {
+    let signed_plain_tx = u8_buffer_argument(&mut ctx, 0)?;
+    let tendermint_address = ctx.argument::<JsString>(1)?.value();
+    let features = Features::argument(&mut ctx, 2)?;
+    let output = output_buffer_argument(&mut ctx, 3)?;
+    let callback = callback_argument(&mut ctx)?;
+
+    let mut signed_plain_tx = signed_plain_tx.as_slice();
+    let signed_transaction = SignedTransaction::decode(&mut signed_plain_tx).chain_neon_with_code(
+        &mut ctx,
+        ErrorCode::DeserializationError,
+        "Unable to decode signed plain transaction bytes",
+    )?;
+    if !signed_plain_tx.is_empty() {
+        return ctx.throw_error("Unexpected trailing bytes after signed plain transaction");
+    }
+
+    signed_transaction_to_hex(
+        &mut ctx,
+        signed_transaction,
+        &tendermint_address,
+        features,
+        output,
+        callback,
+    )
+}
@@ -0,0 +1,220 @@
+use neon::prelude::*;
+
+use chain_core::tx::TxAux;
+use client_common::{Error, ErrorKind, Result, ResultExt, SignedTransaction};
+use parity_scale_codec::{Decode, Encode};
+use serde_json::Value;
+
+use crate::cancellation::CancellationToken;
+use crate::common::Features;
+use crate::error::{ClientErrorNeonExt, ErrorCode, NativeError};
+use crate::function_types::*;
+use crate::rpc::TendermintRpcClient;
+use crate::transfer_transaction::{
+    incomplete_builder_linear_fee_argument, to_tx_aux_linear_fee,
+    LinearFeeRawTransferTransactionBuilder,
+};
+use crate::tx_aux::signed_transaction_to_tx_aux;
+
+/// obfuscate the transfer transaction through the tx-query enclave, broadcast
+/// it and wait for it to be committed in a block
+/// @arguments
+/// - transfer: completed transfer transaction builder, or SCALE-encoded
+///     SignedTransaction Buffer
+/// - tendermint_address: Tendermint websocket address, used to look up the
+///     tx-query enclave address
+/// - tendermint_rpc_url: Tendermint RPC endpoint, e.g. http://127.0.0.1:26657
+/// - features: (optional) native features, default AllDefault
+/// - callback: (optional) node-style callback, send on background thread and
+///     pass the result to callback when provided
+/// @return { txId, blockHeight, hash }, or the task handle { id, cancel() }
+///     with callback. Throws when the transaction is rejected by CheckTx or
+///     DeliverTx
+pub fn send_transfer(mut ctx: FunctionContext) -> JsResult<JsValue> {
+    let transfer = ctx.argument::<JsValue>(0)?;
+    let transfer = if let Ok(signed_plain_tx) = transfer.downcast::<JsBuffer>() {
+        let signed_plain_tx = signed_plain_tx.borrow(&ctx.lock()).as_slice().to_vec();
+        let mut signed_plain_tx = signed_plain_tx.as_slice();
+        let signed_transaction = SignedTransaction::decode(&mut signed_plain_tx)
+            .chain_neon_with_code(
+                &mut ctx,
+                ErrorCode::DeserializationError,
+                "Unable to decode signed plain transaction bytes",
+            )?;
+        if !signed_plain_tx.is_empty() {
+            return ctx.throw_error("Unexpected trailing bytes after signed plain transaction");
+        }
+        Transfer::Signed(signed_transaction)
+    } else {
+        Transfer::Builder(incomplete_builder_linear_fee_argument(&mut ctx, 0)?)
+    };
+    let tendermint_address = ctx.argument::<JsString>(1)?.value();
+    let rpc_url = ctx.argument::<JsString>(2)?.value();
+    let rpc_client =
+        TendermintRpcClient::new(&rpc_url).chain_neon(&mut ctx, "Invalid Tendermint RPC URL")?;
+    let callback = callback_argument(&mut ctx)?;
+    let features = match ctx.argument_opt(3) {
+        Some(features) if features.is_a::<JsString>() => Features::argument(&mut ctx, 3)?,
+        _ => Features::AllDefault,
+    };
+
+    let cancellation = match callback {
+        Some(_) => CancellationToken::register(),
+        None => CancellationToken::default(),
+    };
+    let task = SendTransferTask {
+        transfer,
+        tendermint_address,
+        rpc_client,
+        features,
+        cancellation: cancellation.clone(),
+    };
+    match callback {
+        Some(callback) => {
+            task.schedule(callback);
+            Ok(cancellation.to_js(&mut ctx)?.upcast())
+        }
+        None => {
+            let result = task.perform();
+            sent_transfer_to_js(&mut ctx, result)
+        }
+    }
+}
+
+enum Transfer {
+    Builder(LinearFeeRawTransferTransactionBuilder),
+    Signed(SignedTransaction),
+}
+
+struct SentTransfer {
+    tx_id: String,
+    block_height: u64,
+    hash: String,
+}
+
+struct SendTransferTask {
+    transfer: Transfer,
+    tendermint_address: String,
+    rpc_client: TendermintRpcClient,
+    features: Features,
+    cancellation: CancellationToken,
+}
+
+impl SendTransferTask {
+    fn obfuscate(&self) -> Result<TxAux> {
+        match &self.transfer {
+            Transfer::Builder(builder) => {
+                to_tx_aux_linear_fee(builder, &self.tendermint_address, self.features)
+            }
+            Transfer::Signed(signed_transaction) => signed_transaction_to_tx_aux(
+                signed_transaction.clone(),
+                &self.tendermint_address,
+                self.features,
+            ),
+        }
+    }
+}
+
+impl Task for SendTransferTask {
+    type Output = SentTransfer;
+    type Error = NativeError;
+    type JsEvent = JsValue;
+
+    fn perform(&self) -> std::result::Result<SentTransfer, NativeError> {
+        let tx_aux = self.obfuscate().map_err(|err| {
+            NativeError::from_client_error("Unable to obfuscate transaction", &err)
+        })?;
+        self.cancellation.check().map_err(|err| {
+            NativeError::from_client_error("Unable to broadcast transaction", &err)
+        })?;
+        // The broadcast runs apart so that cancelling does not wait for the
+        // block commit
+        let rpc_client = self.rpc_client.clone();
+        let encoded_tx_aux = tx_aux.encode();
+        let result = self.cancellation.run(move || {
+            rpc_client
+                .broadcast_tx_commit(&encoded_tx_aux)
+                .map_err(|err| {
+                    NativeError::from_client_error("Unable to broadcast transaction", &err)
+                })
+        })?;
+        let block_height = committed_height(&result)
+            .map_err(|err| NativeError::from_client_error("Transaction is not committed", &err))?;
+
+        Ok(SentTransfer {
+            tx_id: hex::encode(tx_aux.tx_id()),
+            block_height,
+            hash: result
+                .get("hash")
+                .and_then(Value::as_str)
+                .unwrap_or_default()
+                .to_owned(),
+        })
+    }
+
+    fn complete(
+        self,
+        mut ctx: TaskContext,
+        result: std::result::Result<SentTransfer, NativeError>,
+    ) -> JsResult<JsValue> {
+        let result = self.cancellation.complete(result);
+        sent_transfer_to_js(&mut ctx, result)
+    }
+}
+
+/// Returns the height of the block the transaction is committed in, fails
+/// when the transaction is rejected
+fn committed_height(result: &Value) -> Result<u64> {
+    for phase in &["check_tx", "deliver_tx"] {
+        let code = result
+            .get(phase)
+            .and_then(|response| response.get("code"))
+            .and_then(Value::as_u64)
+            .unwrap_or(0);
+        if code != 0 {
+            let log = result
+                .get(phase)
+                .and_then(|response| response.get("log"))
+                .and_then(Value::as_str)
+                .unwrap_or_default();
+            return Err(Error::new(
+                ErrorKind::TendermintRpcError,
+                format!(
+                    "Transaction is rejected by {} with code {}: {}",
+                    phase, code, log
+                ),
+            ));
+        }
+    }
+
+    result
+        .get("height")
+        .and_then(Value::as_str)
+        .and_then(|height| height.parse::<u64>().ok())
+        .chain(|| {
+            (
+                ErrorKind::DeserializationError,
+                "Tendermint RPC broadcast_tx_commit result is missing height",
+            )
+        })
+}
+
+fn sent_transfer_to_js<'a, C: Context<'a>>(
+    ctx: &mut C,
+    result: std::result::Result<SentTransfer, NativeError>,
+) -> JsResult<'a, JsValue> {
+    let sent = match result {
+        Ok(sent) => sent,
+        Err(err) => return err.throw(ctx),
+    };
+
+    let js_sent = ctx.empty_object();
+    let tx_id = ctx.string(sent.tx_id);
+    js_sent.set(ctx, "txId", tx_id)?;
+    let block_height = ctx.number(sent.block_height as f64);
+    js_sent.set(ctx, "blockHeight", block_height)?;
+    let hash = ctx.string(sent.hash);
+    js_sent.set(ctx, "hash", hash)?;
+
+    Ok(js_sent.upcast())
+}
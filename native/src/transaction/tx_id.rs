@@ -0,0 +1,58 @@
+use neon::prelude::*;
+
+use chain_core::tx::data::TxId;
+use chain_core::tx::{TransactionId, TxAux};
+use client_common::SignedTransaction;
+use parity_scale_codec::Decode;
+
+use crate::transfer_transaction::incomplete_builder_linear_fee_argument;
+
+/// compute the transaction id without broadcasting, e.g. to store it or to
+/// derive an idempotency key before sending the transaction
+/// @arguments
+/// - tx: transfer transaction builder, or Buffer of SCALE-encoded
+///     SignedTransaction or broadcast-able TxAux. The obfuscated TxAux has
+///     the same id as its signed plain transaction
+/// @return transaction id hex string
+pub fn compute_tx_id(mut ctx: FunctionContext) -> JsResult<JsString> {
+    let tx = ctx.argument::<JsValue>(0)?;
+    let tx_id = match tx.downcast::<JsBuffer>() {
+        Ok(tx) => {
+            let tx = tx.borrow(&ctx.lock()).as_slice().to_vec();
+            match tx_id_of_bytes(&tx) {
+                Some(tx_id) => tx_id,
+                None => {
+                    return ctx.throw_error(
+                        "Unable to decode transaction bytes as SignedTransaction or TxAux",
+                    )
+                }
+            }
+        }
+        Err(_) => incomplete_builder_linear_fee_argument(&mut ctx, 0)?.tx_id(),
+    };
+
+    Ok(ctx.string(hex::encode(tx_id)))
+}
+
+fn tx_id_of_bytes(tx: &[u8]) -> Option<TxId> {
+    let mut signed_transaction_bytes = tx;
+    if let Ok(signed_transaction) = SignedTransaction::decode(&mut signed_transaction_bytes) {
+        if signed_transaction_bytes.is_empty() {
+            return Some(signed_transaction_tx_id(&signed_transaction));
+        }
+    }
+
+    let mut tx_aux_bytes = tx;
+    match TxAux::decode(&mut tx_aux_bytes) {
+        Ok(tx_aux) if tx_aux_bytes.is_empty() => Some(tx_aux.tx_id()),
+        _ => None,
+    }
+}
+
+fn signed_transaction_tx_id(signed_transaction: &SignedTransaction) -> TxId {
+    match signed_transaction {
+        SignedTransaction::TransferTransaction(tx, _) => tx.id(),
+        SignedTransaction::DepositStakeTransaction(tx, _) => tx.id(),
+        SignedTransaction::WithdrawUnbondedStakeTransaction(tx, _) => tx.id(),
+    }
+}
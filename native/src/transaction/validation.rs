@@ -0,0 +1,361 @@
+use neon::prelude::*;
+
+use chain_core::common::Timespec;
+use chain_core::init::address::CroAddress;
+use chain_core::init::coin::Coin;
+use chain_core::tx::data::address::ExtendedAddr;
+use chain_core::tx::data::output::TxOut;
+use chain_core::tx::data::Tx;
+use chain_core::tx::fee::{FeeAlgorithm, LinearFee};
+use chain_core::tx::witness::TxWitness;
+use chain_core::tx::TransactionId;
+use chain_tx_validation::witness::verify_tx_address;
+use client_common::SignedTransaction;
+use client_core::signer::DummySigner;
+use client_core::transaction_builder::RawTransferTransactionBuilder;
+use parity_scale_codec::Decode;
+use serde::Serialize;
+
+use crate::common::serialize_to_js;
+use crate::error::{ClientErrorNeonExt, ErrorCode};
+use crate::function_types::*;
+use crate::transfer_transaction::{
+    incomplete_builder_linear_fee_argument, signed_transaction_of_builder,
+};
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct LocalValidation {
+    tx_id: String,
+    is_valid: bool,
+    violations: Vec<Violation>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    fee: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    required_fee: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Violation {
+    code: &'static str,
+    message: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    index: Option<usize>,
+}
+
+impl Violation {
+    fn new<M: Into<String>>(code: &'static str, message: M, index: Option<usize>) -> Violation {
+        Violation {
+            code,
+            message: message.into(),
+            index,
+        }
+    }
+}
+
+/// validate the transfer transaction offline with the checks of the node
+/// that do not need the chain state, i.e. everything but whether the inputs
+/// are unspent, so that violations are caught before broadcasting
+/// @arguments
+/// - tx: completed transfer transaction builder, or Buffer of SCALE-encoded
+///     transfer SignedTransaction
+/// - prev_outputs: [{ address, value, validFrom? }] outputs spent by the
+///     inputs, in the order of the inputs
+/// - fee_config: fee config of the network
+/// - options: (optional) { currentTime? }, currentTime is the UNIX timestamp
+///     in seconds the validFrom of the prev outputs is checked against,
+///     system time when omitted, as the currentTime option of coin selection
+/// @return { txId, isValid, violations: [{ code, message, index? }], fee?,
+///     requiredFee? }. index is the index of the input or output violating
+///     the check. Required fee of a SignedTransaction is the one of its
+///     size with the witnesses it carries, e.g. with TreeSig proofs
+pub fn validate_locally(mut ctx: FunctionContext) -> JsResult<JsValue> {
+    let fee_config = ctx.argument::<JsObject>(2)?;
+    let fee_algorithm = parse_linear_fee_config(&mut ctx, fee_config)?;
+
+    let tx = ctx.argument::<JsValue>(0)?;
+    let (signed_transaction, builder_fee) = match tx.downcast::<JsBuffer>() {
+        Ok(tx) => {
+            let tx = tx.borrow(&ctx.lock()).as_slice().to_vec();
+            let mut signed_transaction_bytes = tx.as_slice();
+            let signed_transaction = SignedTransaction::decode(&mut signed_transaction_bytes)
+                .chain_neon_with_code(
+                    &mut ctx,
+                    ErrorCode::DeserializationError,
+                    "Unable to decode SignedTransaction",
+                )?;
+            if !signed_transaction_bytes.is_empty() {
+                return ctx.throw_error("Unexpected trailing bytes after transaction");
+            }
+
+            (signed_transaction, None)
+        }
+        Err(_) => {
+            let builder = incomplete_builder_linear_fee_argument(&mut ctx, 0)?;
+            let signed_transaction = signed_transaction_of_builder(&builder)
+                .chain_neon(&mut ctx, "Unable to finish transaction of builder")?;
+            let builder_fee = RawTransferTransactionBuilder::from_incomplete(
+                builder.to_incomplete(),
+                fee_algorithm,
+            )
+            .and_then(|builder| builder.estimate_fee())
+            .map(|fee| fee.to_coin())
+            .chain_neon(&mut ctx, "Unable to estimate transaction fee")?;
+
+            (signed_transaction, Some(builder_fee))
+        }
+    };
+    let (tx, witness) = match signed_transaction {
+        SignedTransaction::TransferTransaction(tx, witness) => (tx, witness),
+        _ => return ctx.throw_error("Only transfer transaction can be validated locally"),
+    };
+
+    let mut violations = Vec::new();
+    let prev_outputs = prev_outputs_argument(&mut ctx, 1, &tx, &mut violations)?;
+    let current_time = match ctx.argument_opt(3) {
+        Some(options) if options.is_a::<JsObject>() => {
+            let options = ctx.argument::<JsObject>(3)?;
+            current_time_option(&mut ctx, options)?
+        }
+        _ => system_time(&mut ctx)?,
+    };
+
+    check_inputs_and_outputs(&tx, &witness, &mut violations);
+    let (fee, required_fee) = match prev_outputs {
+        Some(prev_outputs) => {
+            check_prev_outputs(&tx, &witness, &prev_outputs, current_time, &mut violations);
+            let required_fee = match builder_fee {
+                Some(builder_fee) => Some(builder_fee),
+                None => estimate_fee(&tx, &witness, fee_algorithm),
+            };
+            let fee = check_balance(&tx, &prev_outputs, required_fee, &mut violations);
+            (fee, required_fee)
+        }
+        None => (None, builder_fee),
+    };
+
+    let validation = LocalValidation {
+        tx_id: hex::encode(tx.id()),
+        is_valid: violations.is_empty(),
+        violations,
+        fee: fee.map(|fee| u64::from(fee).to_string()),
+        required_fee: required_fee.map(|fee| u64::from(fee).to_string()),
+    };
+
+    serialize_to_js(&mut ctx, &validation)
+}
+
+/// Returns the previous outputs when there is one valid output per input.
+/// Addresses of another network than the one of the transaction are
+/// violations
+fn prev_outputs_argument(
+    ctx: &mut FunctionContext,
+    i: i32,
+    tx: &Tx,
+    violations: &mut Vec<Violation>,
+) -> NeonResult<Option<Vec<TxOut>>> {
+    let network = network_from_chain_hex_id(tx.attributes.chain_hex_id);
+    let js_prev_outputs = ctx.argument::<JsArray>(i)?.to_vec(ctx)?;
+    if js_prev_outputs.len() != tx.inputs.len() {
+        violations.push(Violation::new(
+            "PrevOutputCount",
+            format!(
+                "Transaction has {} inputs but {} previous outputs are provided",
+                tx.inputs.len(),
+                js_prev_outputs.len()
+            ),
+            None,
+        ));
+        return Ok(None);
+    }
+
+    let mut prev_outputs = Vec::with_capacity(js_prev_outputs.len());
+    for (index, js_prev_output) in js_prev_outputs.iter().enumerate() {
+        let js_prev_output = js_prev_output
+            .downcast_or_throw::<JsObject, FunctionContext>(ctx)
+            .chain_neon(ctx, "Unable to downcast previous output")?;
+        let address = js_prev_output
+            .get(ctx, "address")?
+            .downcast_or_throw::<JsString, FunctionContext>(ctx)
+            .chain_neon(ctx, "Unable to downcast address in previous output")?
+            .value();
+        let value = coin_property(ctx, js_prev_output, "value")?;
+        let valid_from = optional_integer_option(ctx, js_prev_output, "validFrom")?
+            .map(|valid_from| valid_from as Timespec);
+
+        match ExtendedAddr::from_cro(&address, network) {
+            Ok(address) => prev_outputs.push(TxOut {
+                address,
+                value,
+                valid_from,
+            }),
+            Err(err) => violations.push(Violation::new(
+                "NetworkMismatch",
+                format!(
+                    "Previous output address {} is not an address of the transaction network: {}",
+                    address, err
+                ),
+                Some(index),
+            )),
+        }
+    }
+
+    if prev_outputs.len() == tx.inputs.len() {
+        Ok(Some(prev_outputs))
+    } else {
+        Ok(None)
+    }
+}
+
+fn check_inputs_and_outputs(tx: &Tx, witness: &TxWitness, violations: &mut Vec<Violation>) {
+    if tx.inputs.is_empty() {
+        violations.push(Violation::new(
+            "NoInputs",
+            "Transaction has no inputs",
+            None,
+        ));
+    }
+    if tx.outputs.is_empty() {
+        violations.push(Violation::new(
+            "NoOutputs",
+            "Transaction has no outputs",
+            None,
+        ));
+    }
+    if witness.len() != tx.inputs.len() {
+        violations.push(Violation::new(
+            "WitnessCount",
+            format!(
+                "Transaction has {} inputs but {} witnesses",
+                tx.inputs.len(),
+                witness.len()
+            ),
+            None,
+        ));
+    }
+    for (index, input) in tx.inputs.iter().enumerate() {
+        if tx.inputs[..index].contains(input) {
+            violations.push(Violation::new(
+                "DuplicateInput",
+                format!(
+                    "Input {}:{} is spent more than once",
+                    hex::encode(input.id),
+                    input.index
+                ),
+                Some(index),
+            ));
+        }
+    }
+    for (index, output) in tx.outputs.iter().enumerate() {
+        if u64::from(output.value) == 0 {
+            violations.push(Violation::new(
+                "ZeroOutput",
+                "Output has zero value",
+                Some(index),
+            ));
+        }
+    }
+}
+
+/// The witness of every input proves the address of its previous output,
+/// which should be spendable at the current time
+fn check_prev_outputs(
+    tx: &Tx,
+    witness: &TxWitness,
+    prev_outputs: &[TxOut],
+    current_time: Timespec,
+    violations: &mut Vec<Violation>,
+) {
+    let tx_id = tx.id();
+    for (index, (witness, prev_output)) in witness.iter().zip(prev_outputs).enumerate() {
+        if let Err(err) = verify_tx_address(witness, &tx_id, &prev_output.address) {
+            violations.push(Violation::new(
+                "InvalidWitness",
+                format!(
+                    "Witness does not prove the previous output address: {}",
+                    err
+                ),
+                Some(index),
+            ));
+        }
+    }
+    for (index, prev_output) in prev_outputs.iter().enumerate() {
+        if let Some(valid_from) = prev_output.valid_from {
+            if valid_from > current_time {
+                violations.push(Violation::new(
+                    "TimelockedInput",
+                    format!("Previous output is timelocked until {}", valid_from),
+                    Some(index),
+                ));
+            }
+        }
+    }
+}
+
+/// Returns the fee paid by the transaction, the amount of the inputs over the
+/// outputs, which should cover the required fee
+fn check_balance(
+    tx: &Tx,
+    prev_outputs: &[TxOut],
+    required_fee: Option<Coin>,
+    violations: &mut Vec<Violation>,
+) -> Option<Coin> {
+    let input_total = prev_outputs
+        .iter()
+        .try_fold(0u64, |sum, output| sum.checked_add(u64::from(output.value)));
+    let output_total = tx
+        .outputs
+        .iter()
+        .try_fold(0u64, |sum, output| sum.checked_add(u64::from(output.value)));
+    let (input_total, output_total) = match (input_total, output_total) {
+        (Some(input_total), Some(output_total)) => (input_total, output_total),
+        _ => {
+            violations.push(Violation::new(
+                "InvalidSum",
+                "Total value of inputs or outputs overflows",
+                None,
+            ));
+            return None;
+        }
+    };
+    if output_total > input_total {
+        violations.push(Violation::new(
+            "OutputsExceedInputs",
+            format!(
+                "Outputs total {} exceeds inputs total {}",
+                output_total, input_total
+            ),
+            None,
+        ));
+        return None;
+    }
+
+    let fee = Coin::new(input_total - output_total).ok()?;
+    if let Some(required_fee) = required_fee {
+        if u64::from(fee) < u64::from(required_fee) {
+            violations.push(Violation::new(
+                "InsufficientFee",
+                format!(
+                    "Fee {} is below the required fee {}",
+                    u64::from(fee),
+                    u64::from(required_fee)
+                ),
+                None,
+            ));
+        }
+    }
+
+    Some(fee)
+}
+
+/// Fee of the obfuscated transaction with the decoded witnesses, which size
+/// depends on the witness kinds and Merkle proofs of the inputs
+fn estimate_fee(tx: &Tx, witness: &TxWitness, fee_algorithm: LinearFee) -> Option<Coin> {
+    let tx_aux = DummySigner().mock_txaux_for_tx(tx.clone(), witness.clone());
+
+    fee_algorithm
+        .calculate_for_txaux(&tx_aux)
+        .map(|fee| fee.to_coin())
+        .ok()
+}
@@ -0,0 +1,252 @@
+use neon::prelude::*;
+
+use chain_core::init::address::CroAddress;
+use chain_core::init::network::Network;
+use chain_core::state::account::{StakedStateAddress, StakedStateOpWitness};
+use chain_core::tx::data::address::ExtendedAddr;
+use chain_core::tx::data::TxId;
+use chain_core::tx::witness::TxInWitness;
+use chain_core::tx::{TransactionId, TxAux, TxEnclaveAux, TxPublicAux};
+use chain_tx_validation::witness::{verify_tx_address, verify_tx_recover_address};
+use client_common::SignedTransaction;
+use parity_scale_codec::Decode;
+use serde::Serialize;
+
+use crate::common::serialize_to_js;
+use crate::error::{ClientErrorNeonExt, ErrorCode};
+use crate::function_types::*;
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct WitnessesVerification {
+    tx_id: String,
+    tx_type: &'static str,
+    is_valid: bool,
+    witnesses: Vec<WitnessVerification>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct WitnessVerification {
+    index: usize,
+    witness_type: &'static str,
+    is_valid: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    address: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    reason: Option<String>,
+}
+
+/// verify every witness of the transaction against the transaction id,
+/// offline, e.g. to audit transactions constructed by third parties
+/// @arguments
+/// - raw_tx: Buffer of SCALE-encoded SignedTransaction or broadcast-able
+///     TxAux. Input witnesses of obfuscated transfer and deposit stake TxAux
+///     are inside the obfuscated payload, verify their SignedTransaction
+///     instead
+/// @return { txId, txType, isValid, witnesses: [{ index, witnessType, isValid,
+///     address?, reason? }] }. address is the transfer address proven by the
+///     tree signature, or the staking address recovered from the ECDSA
+///     signature. It is up to the caller to check it against the address of
+///     the spent output, which is not part of the transaction
+pub fn verify_witnesses(mut ctx: FunctionContext) -> JsResult<JsValue> {
+    let raw_tx = u8_buffer_argument(&mut ctx, 0)?;
+
+    let mut signed_transaction_bytes = raw_tx.as_slice();
+    let verification = match SignedTransaction::decode(&mut signed_transaction_bytes) {
+        Ok(signed_transaction) if signed_transaction_bytes.is_empty() => {
+            verify_signed_transaction(&signed_transaction)
+        }
+        _ => {
+            let mut tx_aux_bytes = raw_tx.as_slice();
+            let tx_aux = TxAux::decode(&mut tx_aux_bytes).chain_neon_with_code(
+                &mut ctx,
+                ErrorCode::DeserializationError,
+                "Unable to decode transaction bytes as SignedTransaction or TxAux",
+            )?;
+            if !tx_aux_bytes.is_empty() {
+                return ctx.throw_error("Unexpected trailing bytes after transaction");
+            }
+
+            match verify_tx_aux(&tx_aux) {
+                Some(verification) => verification,
+                None => {
+                    return ctx.throw_error(
+                        "Input witnesses of obfuscated transaction are not public, verify its SignedTransaction instead",
+                    )
+                }
+            }
+        }
+    };
+
+    serialize_to_js(&mut ctx, &verification)
+}
+
+fn verify_signed_transaction(signed_transaction: &SignedTransaction) -> WitnessesVerification {
+    match signed_transaction {
+        SignedTransaction::TransferTransaction(tx, witness) => {
+            let network = network_from_chain_hex_id(tx.attributes.chain_hex_id);
+            let tx_id = tx.id();
+            let mut witnesses = witness
+                .iter()
+                .enumerate()
+                .map(|(index, witness)| verify_tx_in_witness(index, witness, &tx_id, network))
+                .collect::<Vec<WitnessVerification>>();
+            check_witness_count(&mut witnesses, tx.inputs.len());
+
+            witnesses_verification("Transfer", &tx_id, witnesses)
+        }
+        SignedTransaction::DepositStakeTransaction(tx, witness) => {
+            let network = network_from_chain_hex_id(tx.attributes.chain_hex_id);
+            let tx_id = tx.id();
+            let mut witnesses = witness
+                .iter()
+                .enumerate()
+                .map(|(index, witness)| verify_tx_in_witness(index, witness, &tx_id, network))
+                .collect::<Vec<WitnessVerification>>();
+            check_witness_count(&mut witnesses, tx.inputs.len());
+
+            witnesses_verification("DepositStake", &tx_id, witnesses)
+        }
+        SignedTransaction::WithdrawUnbondedStakeTransaction(tx, witness) => {
+            let tx_id = tx.id();
+            let witnesses = vec![verify_staked_state_op_witness(witness, &tx_id, None)];
+
+            witnesses_verification("WithdrawUnbondedStake", &tx_id, witnesses)
+        }
+    }
+}
+
+fn verify_tx_aux(tx_aux: &TxAux) -> Option<WitnessesVerification> {
+    let verification = match tx_aux {
+        TxAux::EnclaveTx(TxEnclaveAux::WithdrawUnbondedStakeTx {
+            witness, payload, ..
+        }) => witnesses_verification(
+            "WithdrawUnbondedStake",
+            &payload.txid,
+            vec![verify_staked_state_op_witness(witness, &payload.txid, None)],
+        ),
+        TxAux::EnclaveTx(_) => return None,
+        TxAux::PublicTx(TxPublicAux::UnbondStakeTx(tx, witness)) => witnesses_verification(
+            "UnbondStake",
+            &tx.id(),
+            vec![verify_staked_state_op_witness(
+                witness,
+                &tx.id(),
+                Some(&tx.from_staked_account),
+            )],
+        ),
+        TxAux::PublicTx(TxPublicAux::UnjailTx(tx, witness)) => witnesses_verification(
+            "Unjail",
+            &tx.id(),
+            vec![verify_staked_state_op_witness(
+                witness,
+                &tx.id(),
+                Some(&tx.address),
+            )],
+        ),
+        TxAux::PublicTx(TxPublicAux::NodeJoinTx(tx, witness)) => witnesses_verification(
+            "NodeJoin",
+            &tx.id(),
+            vec![verify_staked_state_op_witness(
+                witness,
+                &tx.id(),
+                Some(&tx.address),
+            )],
+        ),
+    };
+
+    Some(verification)
+}
+
+fn witnesses_verification(
+    tx_type: &'static str,
+    tx_id: &TxId,
+    witnesses: Vec<WitnessVerification>,
+) -> WitnessesVerification {
+    WitnessesVerification {
+        tx_id: hex::encode(tx_id),
+        tx_type,
+        is_valid: !witnesses.is_empty() && witnesses.iter().all(|witness| witness.is_valid),
+        witnesses,
+    }
+}
+
+/// The tree signature proves the transfer address as the root of its proof,
+/// the signature is verified against that address
+fn verify_tx_in_witness(
+    index: usize,
+    witness: &TxInWitness,
+    tx_id: &TxId,
+    network: Network,
+) -> WitnessVerification {
+    let TxInWitness::TreeSig(_, proof) = witness;
+    let address = ExtendedAddr::OrTree(proof.root_hash());
+    let result = verify_tx_address(witness, tx_id, &address);
+
+    WitnessVerification {
+        index,
+        witness_type: "TreeSig",
+        is_valid: result.is_ok(),
+        address: address.to_cro(network).ok(),
+        reason: result
+            .err()
+            .map(|err| format!("Invalid tree signature: {}", err)),
+    }
+}
+
+/// The staking address is recovered from the signature, and checked against
+/// the address of the transaction when it has one
+fn verify_staked_state_op_witness(
+    witness: &StakedStateOpWitness,
+    tx_id: &TxId,
+    expected_address: Option<&StakedStateAddress>,
+) -> WitnessVerification {
+    let (is_valid, address, reason) = match verify_tx_recover_address(witness, tx_id) {
+        Ok(address) => match expected_address {
+            Some(expected_address) if *expected_address != address => (
+                false,
+                Some(address.to_string()),
+                Some(format!(
+                    "Witness is signed by {} instead of {}",
+                    address, expected_address
+                )),
+            ),
+            _ => (true, Some(address.to_string()), None),
+        },
+        Err(err) => (
+            false,
+            None,
+            Some(format!("Unable to recover address from witness: {}", err)),
+        ),
+    };
+
+    WitnessVerification {
+        index: 0,
+        witness_type: "BasicRedeem",
+        is_valid,
+        address,
+        reason,
+    }
+}
+
+fn check_witness_count(witnesses: &mut Vec<WitnessVerification>, no_of_inputs: usize) {
+    if witnesses.len() == no_of_inputs {
+        return;
+    }
+
+    // Inputs without witness are reported as invalid, so are surplus witnesses
+    for witness in witnesses.iter_mut().skip(no_of_inputs) {
+        witness.is_valid = false;
+        witness.reason = Some("Witness has no corresponding input".to_owned());
+    }
+    for index in witnesses.len()..no_of_inputs {
+        witnesses.push(WitnessVerification {
+            index,
+            witness_type: "TreeSig",
+            is_valid: false,
+            address: None,
+            reason: Some("Input has no witness".to_owned()),
+        });
+    }
+}
@@ -0,0 +1,91 @@
+//! View keys allowed to decrypt a transfer transaction, e.g. the keys of
+//! auditors granted on top of the wallet ones. They are part of the
+//! transaction attributes, so they are fixed before any input is signed
+use chain_core::tx::data::access::{TxAccess, TxAccessPolicy};
+use client_common::PublicKey;
+use neon::prelude::*;
+
+use crate::common::does_js_object_has_prop;
+use crate::error::{throw_coded_error, ClientErrorNeonExt, ErrorCode};
+use crate::function_types::*;
+
+/// Default limit of the view keys of a transaction, every view key adds an
+/// encrypted copy of the transaction key
+pub const DEFAULT_MAX_VIEW_KEYS: u64 = 64;
+
+/// Reads the view keys of the transaction: the viewKeys option, with the
+/// ones of additionalViewKeys added and the ones of removedViewKeys removed,
+/// without duplicates in order of first appearance. Throws when there are
+/// more than maxViewKeys, 64 by default
+pub fn view_keys_option(
+    ctx: &mut FunctionContext,
+    options: Handle<JsObject>,
+) -> NeonResult<Vec<PublicKey>> {
+    let mut candidates = view_key_array(ctx, options, "viewKeys")?;
+    if does_js_object_has_prop(ctx, options, "additionalViewKeys")? {
+        candidates.extend(view_key_array(ctx, options, "additionalViewKeys")?);
+    }
+    let removed = if does_js_object_has_prop(ctx, options, "removedViewKeys")? {
+        view_key_array(ctx, options, "removedViewKeys")?
+            .iter()
+            .map(PublicKey::serialize)
+            .collect()
+    } else {
+        Vec::new()
+    };
+    let max_view_keys =
+        optional_integer_option(ctx, options, "maxViewKeys")?.unwrap_or(DEFAULT_MAX_VIEW_KEYS);
+
+    let mut serialized_keys: Vec<Vec<u8>> = Vec::new();
+    let mut view_keys = Vec::new();
+    for view_key in candidates {
+        let serialized = view_key.serialize();
+        if removed.contains(&serialized) || serialized_keys.contains(&serialized) {
+            continue;
+        }
+        serialized_keys.push(serialized);
+        view_keys.push(view_key);
+    }
+    if view_keys.len() as u64 > max_view_keys {
+        return throw_coded_error(
+            ctx,
+            ErrorCode::InvalidArgument,
+            format!(
+                "Transaction has {} view keys, which exceeds maxViewKeys {}",
+                view_keys.len(),
+                max_view_keys
+            ),
+        );
+    }
+
+    Ok(view_keys)
+}
+
+/// Returns the access policies granting the view keys all data of the
+/// transaction
+pub fn access_policies(view_keys: &[PublicKey]) -> Vec<TxAccessPolicy> {
+    view_keys
+        .iter()
+        .map(|view_key| TxAccessPolicy {
+            view_key: view_key.into(),
+            access: TxAccess::AllData,
+        })
+        .collect()
+}
+
+fn view_key_array(
+    ctx: &mut FunctionContext,
+    options: Handle<JsObject>,
+    key: &str,
+) -> NeonResult<Vec<PublicKey>> {
+    let view_keys = options
+        .get(ctx, key)?
+        .downcast_or_throw::<JsArray, FunctionContext>(ctx)
+        .chain_neon(ctx, format!("Unable to downcast {}", key))?
+        .to_vec(ctx)?;
+
+    view_keys
+        .iter()
+        .map(|&view_key| parse_view_key(ctx, view_key))
+        .collect()
+}
@@ -0,0 +1,235 @@
+//! Signing of many prepared transfer transactions with one key pair, e.g. the
+//! transactions of an exchange payout run. The transactions are split among
+//! worker threads so that a large batch does not block the event loop for
+//! long
+use std::sync::Arc;
+use std::thread;
+
+use chain_core::tx::fee::LinearFee;
+use client_common::{Error, ErrorKind, Result};
+use client_core::transaction_builder::RawTransferTransactionBuilder;
+use neon::prelude::*;
+
+use crate::cancellation::CancellationToken;
+use crate::error::{ClientErrorNeonExt, NativeError};
+use crate::function_types::*;
+use crate::signer::KeyPairSigner;
+
+/// Max number of worker threads signing a batch
+const MAX_SIGNING_THREADS: usize = 4;
+
+struct SignJob {
+    incomplete_hex: Vec<u8>,
+    fee_algorithm: LinearFee,
+    input_indexes: Vec<usize>,
+}
+
+/// Sign the inputs of a batch of linear fee transfer transaction builders
+/// with the provided KeyPair
+/// @arguments
+/// - jobs: [{ incompleteHex, feeConfig, inputIndexes }], inputIndexes are the
+///     indexes of the inputs to sign in each builder
+/// - key_pair: KeyPair which can unlock the inputs
+/// - callback: (optional) node-style callback, sign on background threads and
+///     pass the result to callback when provided
+/// @return [Buffer] incomplete hex of the signed builders in order. With a
+///     callback, returns the task handle { id, cancel() } instead, signing
+///     stops before the next transaction once cancelled
+pub fn sign_batch_linear_fee(mut ctx: FunctionContext) -> JsResult<JsValue> {
+    let jobs =
+        typed_argument::<JsArray>(&mut ctx, 0, "jobs", "array of signing job")?.to_vec(&mut ctx)?;
+    let (private_key, public_key) = key_pair_argument(&mut ctx, 1)?;
+    let callback = callback_argument(&mut ctx)?;
+
+    let mut sign_jobs = Vec::with_capacity(jobs.len());
+    for (i, job) in jobs.iter().enumerate() {
+        let job = job
+            .downcast_or_throw::<JsObject, FunctionContext>(&mut ctx)
+            .chain_neon(&mut ctx, format!("Unable to downcast signing job {}", i))?;
+        sign_jobs.push(parse_sign_job(&mut ctx, job, i)?);
+    }
+
+    let signer = KeyPairSigner::new(private_key, public_key)
+        .chain_neon(&mut ctx, "Unable to create KeyPair signer")?;
+
+    let cancellation = match callback {
+        Some(_) => CancellationToken::register(),
+        None => CancellationToken::default(),
+    };
+    let task = SignBatchTask {
+        signer: Arc::new(signer),
+        jobs: Arc::new(sign_jobs),
+        cancellation: cancellation.clone(),
+    };
+    match callback {
+        Some(callback) => {
+            task.schedule(callback);
+            Ok(cancellation.to_js(&mut ctx)?.upcast())
+        }
+        None => {
+            let result = task.perform();
+            sign_batch_result_to_js(&mut ctx, result)
+        }
+    }
+}
+
+fn parse_sign_job(
+    ctx: &mut FunctionContext,
+    job: Handle<JsObject>,
+    i: usize,
+) -> NeonResult<SignJob> {
+    let incomplete_hex = job.get(ctx, "incompleteHex")?;
+    let incomplete_hex = parse_bytes(ctx, incomplete_hex, &format!("incompleteHex of job {}", i))?;
+
+    let fee_config = job
+        .get(ctx, "feeConfig")?
+        .downcast_or_throw::<JsObject, FunctionContext>(ctx)
+        .chain_neon(ctx, format!("Unable to downcast feeConfig of job {}", i))?;
+    let fee_algorithm = parse_linear_fee_config(ctx, fee_config)?;
+
+    let input_indexes = job
+        .get(ctx, "inputIndexes")?
+        .downcast_or_throw::<JsArray, FunctionContext>(ctx)
+        .chain_neon(ctx, format!("Unable to downcast inputIndexes of job {}", i))?
+        .to_vec(ctx)?;
+    let input_indexes = input_indexes
+        .iter()
+        .map(|index| {
+            let index = index
+                .downcast_or_throw::<JsNumber, FunctionContext>(ctx)
+                .chain_neon(ctx, format!("Unable to downcast input index of job {}", i))?
+                .value();
+            if index < 0.0 || index.fract() != 0.0 || index > MAX_SAFE_INTEGER {
+                return ctx.throw_error(format!(
+                    "Input index of job {} should be a non-negative integer",
+                    i
+                ));
+            }
+            Ok(index as usize)
+        })
+        .collect::<NeonResult<Vec<usize>>>()?;
+
+    Ok(SignJob {
+        incomplete_hex,
+        fee_algorithm,
+        input_indexes,
+    })
+}
+
+fn sign_job(signer: &KeyPairSigner, job: &SignJob) -> Result<Vec<u8>> {
+    let mut builder = RawTransferTransactionBuilder::from_incomplete(
+        job.incomplete_hex.clone(),
+        job.fee_algorithm,
+    )?;
+
+    // All the inputs are signed by the same key, so they share one witness
+    let witness = signer.schnorr_sign_txid(&builder.tx_id())?;
+    for &input_index in job.input_indexes.iter() {
+        builder.add_witness(input_index, witness.clone())?;
+    }
+
+    Ok(builder.to_incomplete())
+}
+
+struct SignBatchTask {
+    signer: Arc<KeyPairSigner>,
+    jobs: Arc<Vec<SignJob>>,
+    cancellation: CancellationToken,
+}
+
+impl SignBatchTask {
+    fn sign(&self) -> std::result::Result<Vec<Vec<u8>>, NativeError> {
+        let jobs_len = self.jobs.len();
+        if jobs_len == 0 {
+            return Ok(Vec::new());
+        }
+        let threads = jobs_len.min(MAX_SIGNING_THREADS);
+        let chunk_size = (jobs_len + threads - 1) / threads;
+
+        let workers = (0..threads)
+            .map(|worker| {
+                let signer = Arc::clone(&self.signer);
+                let jobs = Arc::clone(&self.jobs);
+                let cancellation = self.cancellation.clone();
+                thread::spawn(move || {
+                    let start = worker * chunk_size;
+                    let end = (start + chunk_size).min(jobs.len());
+                    (start..end)
+                        .map(|i| {
+                            cancellation
+                                .check()
+                                .and_then(|_| sign_job(&signer, &jobs[i]))
+                                .map_err(|err| (i, err))
+                        })
+                        .collect::<std::result::Result<Vec<Vec<u8>>, (usize, Error)>>()
+                })
+            })
+            .collect::<Vec<_>>();
+
+        let mut signed = Vec::with_capacity(jobs_len);
+        let mut first_error: Option<(usize, Error)> = None;
+        for (worker, handle) in workers.into_iter().enumerate() {
+            match handle.join() {
+                Ok(Ok(chunk)) => signed.extend(chunk),
+                Ok(Err((i, err))) => {
+                    if first_error.is_none() {
+                        first_error = Some((i, err));
+                    }
+                }
+                Err(_) => {
+                    if first_error.is_none() {
+                        first_error = Some((
+                            worker * chunk_size,
+                            Error::new(ErrorKind::InternalError, "Signing thread panicked"),
+                        ));
+                    }
+                }
+            }
+        }
+
+        match first_error {
+            None => Ok(signed),
+            Some((i, err)) => Err(NativeError::from_client_error(
+                format!("Unable to sign transaction {}", i),
+                &err,
+            )),
+        }
+    }
+}
+
+impl Task for SignBatchTask {
+    type Output = Vec<Vec<u8>>;
+    type Error = NativeError;
+    type JsEvent = JsValue;
+
+    fn perform(&self) -> std::result::Result<Vec<Vec<u8>>, NativeError> {
+        self.sign()
+    }
+
+    fn complete(
+        self,
+        mut ctx: TaskContext,
+        result: std::result::Result<Vec<Vec<u8>>, NativeError>,
+    ) -> JsResult<JsValue> {
+        let result = self.cancellation.complete(result);
+        sign_batch_result_to_js(&mut ctx, result)
+    }
+}
+
+fn sign_batch_result_to_js<'a, C: Context<'a>>(
+    ctx: &mut C,
+    result: std::result::Result<Vec<Vec<u8>>, NativeError>,
+) -> JsResult<'a, JsValue> {
+    let result = match result {
+        Ok(result) => result,
+        Err(err) => return err.throw(ctx),
+    };
+
+    let js_result = JsArray::new(ctx, result.len() as u32);
+    for (i, incomplete_hex) in result.iter().enumerate() {
+        let buffer = bytes_to_buffer(ctx, incomplete_hex)?;
+        js_result.set(ctx, i as u32, buffer)?;
+    }
+
+    Ok(js_result.upcast())
+}
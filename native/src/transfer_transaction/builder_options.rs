@@ -1,13 +1,22 @@
+use chain_core::common::Timespec;
+use chain_core::init::address::CroAddress;
 use chain_core::init::network::Network;
+use chain_core::tx::data::address::ExtendedAddr;
+use chain_core::tx::data::attribute::TxAttributes;
 use chain_core::tx::data::input::{TxoPointer, TxoSize};
 use chain_core::tx::data::output::TxOut;
 use chain_core::tx::fee::{FeeAlgorithm, LinearFee};
 use client_common::PublicKey;
 use neon::prelude::*;
 
-use crate::error::ClientErrorNeonExt;
+use crate::error::{ClientErrorNeonExt, ErrorCode};
 use crate::function_types::*;
 
+use super::access_policy::{access_policies, view_keys_option};
+use super::coin_selection::CoinSelectionStrategy;
+use super::dust::DustOptions;
+use super::output_order::{output_order_option, OutputOrder};
+
 pub type LinearFeeBuilderOptions = BuilderOptions<LinearFee>;
 
 #[derive(Debug)]
@@ -17,6 +26,7 @@ where
 {
     pub raw_tx_options: RawTransactionOptions,
     pub fee_algorithm: F,
+    pub output_order: OutputOrder,
 }
 
 #[derive(Debug)]
@@ -25,6 +35,8 @@ pub struct RawTransactionOptions {
     pub outputs: Vec<TxOut>,
     pub view_keys: Vec<PublicKey>,
     pub chain_hex_id: u8,
+    /// Network of the addresses, which may be overridden by the network option
+    pub network: Network,
 }
 
 #[derive(Debug)]
@@ -39,6 +51,108 @@ pub struct RawTransactionOptionsInputAddressParams {
     pub total_signers: u64,
 }
 
+/// Options of the transfer transactions funded by coin selection
+pub struct CoinSelectionOptions {
+    pub fee_algorithm: LinearFee,
+    pub utxos: Vec<RawTransactionOptionsInput>,
+    pub outputs: Vec<TxOut>,
+    pub attributes: TxAttributes,
+    pub change_address: ExtendedAddr,
+    pub strategy: CoinSelectionStrategy,
+    pub output_order: OutputOrder,
+    /// UTXOs valid from after the time are not spendable yet
+    pub current_time: Timespec,
+    /// Change below the dust threshold is paid as fee
+    pub dust: DustOptions,
+}
+
+impl CoinSelectionOptions {
+    pub fn parse(
+        ctx: &mut FunctionContext,
+        options: Handle<JsObject>,
+    ) -> NeonResult<CoinSelectionOptions> {
+        let (chain_hex_id, network) = network_option(ctx, *options)?;
+
+        let fee_config = options
+            .get(ctx, "feeConfig")?
+            .downcast_or_throw::<JsObject, FunctionContext>(ctx)
+            .chain_neon(ctx, "Unable to downcast feeConfig")?;
+        let fee_algorithm = parse_linear_fee_config(ctx, fee_config)?;
+
+        let utxos = options
+            .get(ctx, "utxos")?
+            .downcast_or_throw::<JsArray, FunctionContext>(ctx)
+            .chain_neon(ctx, "Unable to downcast utxos")?
+            .to_vec(ctx)?;
+        let utxos = utxos
+            .iter()
+            .map(|&utxo| {
+                let utxo = utxo
+                    .downcast_or_throw::<JsObject, FunctionContext>(ctx)
+                    .chain_neon(ctx, "Unable to downcast utxo")?;
+                BuilderOptions::<LinearFee>::parse_input(ctx, *utxo, network)
+            })
+            .collect::<NeonResult<Vec<RawTransactionOptionsInput>>>()?;
+
+        let outputs = options
+            .get(ctx, "outputs")?
+            .downcast_or_throw::<JsArray, FunctionContext>(ctx)
+            .chain_neon(ctx, "Unable to downcast outputs")?
+            .to_vec(ctx)?;
+        let mut outputs = outputs
+            .iter()
+            .map(|&output| {
+                let output = output
+                    .downcast_or_throw::<JsObject, FunctionContext>(ctx)
+                    .chain_neon(ctx, "Unable to downcast output")?;
+                parse_output(ctx, output, network)
+            })
+            .collect::<NeonResult<Vec<TxOut>>>()?;
+        let dust = DustOptions::parse(ctx, options)?;
+        dust.apply(&mut outputs)
+            .chain_neon(ctx, "Invalid transaction outputs")?;
+
+        let view_keys = view_keys_option(ctx, options)?;
+        let attributes = TxAttributes::new_with_access(chain_hex_id, access_policies(&view_keys));
+
+        let change_address = options
+            .get(ctx, "changeAddress")?
+            .downcast_or_throw::<JsString, FunctionContext>(ctx)
+            .chain_neon(ctx, "Unable to downcast changeAddress")?
+            .value();
+        let change_address = ExtendedAddr::from_cro(&change_address, network)
+            .chain_neon_with_code(
+                ctx,
+                ErrorCode::InvalidAddress,
+                "Unable to deserialize changeAddress to CRO address",
+            )?;
+
+        let strategy = options
+            .get(ctx, "strategy")?
+            .downcast_or_throw::<JsString, FunctionContext>(ctx)
+            .chain_neon(ctx, "Unable to downcast strategy")?
+            .value();
+        let strategy = CoinSelectionStrategy::from_name(&strategy).chain_neon(
+            ctx,
+            format!("Unrecognized coin selection strategy {}", strategy),
+        )?;
+        let output_order = output_order_option(ctx, options)?;
+        let current_time = current_time_option(ctx, options)?;
+
+        Ok(CoinSelectionOptions {
+            fee_algorithm,
+            utxos,
+            outputs,
+            attributes,
+            change_address,
+            strategy,
+            output_order,
+            current_time,
+            dust,
+        })
+    }
+}
+
 impl<F> BuilderOptions<F>
 where
     F: FeeAlgorithm,
@@ -48,33 +162,30 @@ where
     ) -> NeonResult<LinearFeeBuilderOptions> {
         let options = ctx.argument::<JsObject>(0)?;
 
-        let raw_tx_options = BuilderOptions::<LinearFee>::parse_raw_tx_options(ctx, *options)?;
+        let mut raw_tx_options = BuilderOptions::<LinearFee>::parse_raw_tx_options(ctx, options)?;
+        DustOptions::parse(ctx, options)?
+            .apply(&mut raw_tx_options.outputs)
+            .chain_neon(ctx, "Invalid transaction outputs")?;
 
         let fee_config = options
             .get(ctx, "feeConfig")?
             .downcast_or_throw::<JsObject, FunctionContext>(ctx)
             .chain_neon(ctx, "Unable to downcast feeConfig")?;
         let fee_algorithm = parse_linear_fee_config(ctx, fee_config)?;
+        let output_order = output_order_option(ctx, options)?;
 
         Ok(BuilderOptions {
             raw_tx_options,
             fee_algorithm,
+            output_order,
         })
     }
 
     fn parse_raw_tx_options(
         ctx: &mut FunctionContext,
-        options: JsObject,
+        options: Handle<JsObject>,
     ) -> NeonResult<RawTransactionOptions> {
-        let chain_hex_id = options
-            .get(ctx, "chainHexId")?
-            .downcast_or_throw::<JsBuffer, FunctionContext>(ctx)
-            .chain_neon(ctx, "Unable to downcast chainHexId")?;
-        let chain_hex_id = ctx
-            .borrow(&chain_hex_id, |data| data.as_slice::<u8>())
-            .to_vec();
-        let chain_hex_id = chain_hex_id_from_vec(ctx, chain_hex_id)?;
-        let network = network_from_chain_hex_id(chain_hex_id);
+        let (chain_hex_id, network) = network_option(ctx, *options)?;
 
         let inputs = options
             .get(ctx, "inputs")?
@@ -106,30 +217,18 @@ where
             })
             .collect::<NeonResult<Vec<TxOut>>>()?;
 
-        let view_keys = options
-            .get(ctx, "viewKeys")?
-            .downcast_or_throw::<JsArray, FunctionContext>(ctx)
-            .chain_neon(ctx, "Unable to downcast viewKeys")?
-            .to_vec(ctx)?;
-        let view_keys = view_keys
-            .iter()
-            .map(|&view_key| {
-                let view_key = view_key
-                    .downcast_or_throw::<JsBuffer, FunctionContext>(ctx)
-                    .chain_neon(ctx, "Unable to downcast viewKey")?;
-                parse_view_key(ctx, view_key)
-            })
-            .collect::<NeonResult<Vec<PublicKey>>>()?;
+        let view_keys = view_keys_option(ctx, options)?;
 
         Ok(RawTransactionOptions {
             inputs,
             outputs,
             view_keys,
             chain_hex_id,
+            network,
         })
     }
 
-    fn parse_input(
+    pub fn parse_input(
         ctx: &mut FunctionContext,
         input: JsObject,
         network: Network,
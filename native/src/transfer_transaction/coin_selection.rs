@@ -0,0 +1,279 @@
+//! UTXO selection strategies used to fund transfer transaction outputs
+use chain_core::init::coin::Coin;
+use client_common::{Error, ErrorKind, Result};
+
+/// Maximum number of branches explored by branch-and-bound before falling
+/// back to largest-first selection
+const BRANCH_AND_BOUND_MAX_TRIES: usize = 100_000;
+const INSUFFICIENT_BALANCE_MESSAGE: &str = "Insufficient balance to cover outputs and fee";
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CoinSelectionStrategy {
+    LargestFirst,
+    SmallestFirst,
+    BranchAndBound,
+}
+
+impl CoinSelectionStrategy {
+    pub fn from_name(strategy: &str) -> Option<CoinSelectionStrategy> {
+        match strategy {
+            "LargestFirst" => Some(CoinSelectionStrategy::LargestFirst),
+            "SmallestFirst" => Some(CoinSelectionStrategy::SmallestFirst),
+            "BranchAndBound" => Some(CoinSelectionStrategy::BranchAndBound),
+            _ => None,
+        }
+    }
+}
+
+/// Failure of coin selection
+#[derive(Debug)]
+pub enum CoinSelectionError {
+    /// The UTXOs do not cover the outputs and fee
+    InsufficientBalance,
+    Failed(Error),
+}
+
+impl From<Error> for CoinSelectionError {
+    fn from(err: Error) -> CoinSelectionError {
+        CoinSelectionError::Failed(err)
+    }
+}
+
+impl From<CoinSelectionError> for Error {
+    fn from(err: CoinSelectionError) -> Error {
+        match err {
+            CoinSelectionError::InsufficientBalance => {
+                Error::new(ErrorKind::InvalidInput, INSUFFICIENT_BALANCE_MESSAGE)
+            }
+            CoinSelectionError::Failed(err) => err,
+        }
+    }
+}
+
+type SelectionResult<T> = std::result::Result<T, CoinSelectionError>;
+
+/// Result of coin selection
+#[derive(Debug, Clone)]
+pub struct CoinSelection {
+    /// Indexes of selected UTXOs
+    pub indexes: Vec<usize>,
+    /// Change amount, `None` when the transaction has no change output
+    pub change: Option<Coin>,
+    /// Fee paid by the transaction
+    pub fee: Coin,
+    /// Change below the dust threshold, paid as fee instead of a change
+    /// output
+    pub dust_change: Option<Coin>,
+}
+
+/// Select UTXOs covering the target amount and fee.
+///
+/// `estimate_fee` returns the fee of a transaction spending the provided UTXO
+/// indexes, with or without a change output.
+pub fn select_coins<F>(
+    values: &[Coin],
+    target: Coin,
+    strategy: CoinSelectionStrategy,
+    estimate_fee: F,
+) -> SelectionResult<CoinSelection>
+where
+    F: Fn(&[usize], bool) -> Result<Coin>,
+{
+    let total = values
+        .iter()
+        .try_fold(0u64, |sum, &value| sum.checked_add(u64::from(value)))
+        .ok_or_else(|| Error::new(ErrorKind::InvalidInput, "UTXO total value overflow"))?;
+    if total < u64::from(target) {
+        return Err(CoinSelectionError::InsufficientBalance);
+    }
+
+    match strategy {
+        CoinSelectionStrategy::LargestFirst => {
+            accumulate(values, target, sorted_indexes(values, true), &estimate_fee)
+        }
+        CoinSelectionStrategy::SmallestFirst => {
+            accumulate(values, target, sorted_indexes(values, false), &estimate_fee)
+        }
+        CoinSelectionStrategy::BranchAndBound => {
+            match branch_and_bound(values, target, &estimate_fee)? {
+                Some(selection) => Ok(selection),
+                None => accumulate(values, target, sorted_indexes(values, true), &estimate_fee),
+            }
+        }
+    }
+}
+
+fn sorted_indexes(values: &[Coin], descending: bool) -> Vec<usize> {
+    let mut indexes: Vec<usize> = (0..values.len()).collect();
+    indexes.sort_by_key(|&i| u64::from(values[i]));
+    if descending {
+        indexes.reverse();
+    }
+    indexes
+}
+
+/// Add UTXOs in the provided order until outputs and fee are covered
+fn accumulate<F>(
+    values: &[Coin],
+    target: Coin,
+    order: Vec<usize>,
+    estimate_fee: &F,
+) -> SelectionResult<CoinSelection>
+where
+    F: Fn(&[usize], bool) -> Result<Coin>,
+{
+    let target = u64::from(target);
+    let mut selected = Vec::new();
+    let mut sum = 0u64;
+
+    for index in order {
+        selected.push(index);
+        sum += u64::from(values[index]);
+        if sum < target {
+            continue;
+        }
+
+        if let Some(selection) = try_finalize(&selected, sum, target, estimate_fee)? {
+            return Ok(selection);
+        }
+    }
+
+    Err(CoinSelectionError::InsufficientBalance)
+}
+
+/// Search for an input set whose surplus over outputs and fee is smaller
+/// than the cost of creating a change output, so that no change is needed
+fn branch_and_bound<F>(
+    values: &[Coin],
+    target: Coin,
+    estimate_fee: &F,
+) -> Result<Option<CoinSelection>>
+where
+    F: Fn(&[usize], bool) -> Result<Coin>,
+{
+    let order = sorted_indexes(values, true);
+
+    // Fee of spending every UTXO bounds the fee of any subset
+    let max_fee = u64::from(estimate_fee(&order, true)?);
+    let cost_of_change = max_fee.saturating_sub(u64::from(estimate_fee(&order, false)?));
+
+    let mut remaining: Vec<u64> = vec![0; order.len() + 1];
+    for i in (0..order.len()).rev() {
+        remaining[i] = remaining[i + 1] + u64::from(values[order[i]]);
+    }
+
+    let target = u64::from(target);
+    let mut search = BranchAndBoundSearch {
+        values,
+        order,
+        remaining,
+        target,
+        upper_bound: target + max_fee + cost_of_change,
+        cost_of_change,
+        estimate_fee,
+        tries: 0,
+    };
+
+    search.search(0, &mut Vec::new(), 0)
+}
+
+struct BranchAndBoundSearch<'a, F> {
+    values: &'a [Coin],
+    order: Vec<usize>,
+    remaining: Vec<u64>,
+    target: u64,
+    upper_bound: u64,
+    cost_of_change: u64,
+    estimate_fee: &'a F,
+    tries: usize,
+}
+
+impl<'a, F> BranchAndBoundSearch<'a, F>
+where
+    F: Fn(&[usize], bool) -> Result<Coin>,
+{
+    fn search(
+        &mut self,
+        depth: usize,
+        selected: &mut Vec<usize>,
+        sum: u64,
+    ) -> Result<Option<CoinSelection>> {
+        self.tries += 1;
+        if self.tries > BRANCH_AND_BOUND_MAX_TRIES || sum > self.upper_bound {
+            return Ok(None);
+        }
+
+        if sum >= self.target {
+            let fee = u64::from((self.estimate_fee)(selected, false)?);
+            if sum >= self.target + fee {
+                if sum - self.target - fee <= self.cost_of_change {
+                    return Ok(Some(CoinSelection {
+                        indexes: selected.clone(),
+                        change: None,
+                        fee: Coin::new(sum - self.target).map_err(coin_error)?,
+                        dust_change: None,
+                    }));
+                }
+                // adding more inputs only increases the surplus
+                return Ok(None);
+            }
+        }
+
+        if depth == self.order.len() || sum + self.remaining[depth] < self.target {
+            return Ok(None);
+        }
+
+        let index = self.order[depth];
+        selected.push(index);
+        let value = u64::from(self.values[index]);
+        if let Some(selection) = self.search(depth + 1, selected, sum + value)? {
+            return Ok(Some(selection));
+        }
+        selected.pop();
+
+        self.search(depth + 1, selected, sum)
+    }
+}
+
+/// Decide whether the selected inputs cover the outputs and fee, with or
+/// without a change output
+fn try_finalize<F>(
+    selected: &[usize],
+    sum: u64,
+    target: u64,
+    estimate_fee: &F,
+) -> Result<Option<CoinSelection>>
+where
+    F: Fn(&[usize], bool) -> Result<Coin>,
+{
+    let fee_with_change = u64::from(estimate_fee(selected, true)?);
+    if sum > target + fee_with_change {
+        return Ok(Some(CoinSelection {
+            indexes: selected.to_vec(),
+            change: Some(Coin::new(sum - target - fee_with_change).map_err(coin_error)?),
+            fee: Coin::new(fee_with_change).map_err(coin_error)?,
+            dust_change: None,
+        }));
+    }
+
+    let fee_without_change = u64::from(estimate_fee(selected, false)?);
+    if sum >= target + fee_without_change {
+        // surplus too small for a change output is paid as fee
+        return Ok(Some(CoinSelection {
+            indexes: selected.to_vec(),
+            change: None,
+            fee: Coin::new(sum - target).map_err(coin_error)?,
+            dust_change: None,
+        }));
+    }
+
+    Ok(None)
+}
+
+#[inline]
+fn coin_error<E: std::fmt::Display>(err: E) -> Error {
+    Error::new(
+        ErrorKind::InvalidInput,
+        format!("Invalid coin amount: {}", err),
+    )
+}
@@ -0,0 +1,128 @@
+//! Dust outputs, whose value is below the dust threshold. The node rejects
+//! transfer transactions with outputs of zero value, so the default threshold
+//! is one basic unit; a higher one keeps outputs not worth their fee to spend
+//! out of the transactions
+use chain_core::init::coin::Coin;
+use chain_core::tx::data::output::TxOut;
+use client_common::{Error, ErrorKind, Result};
+use neon::prelude::*;
+
+use crate::common::does_js_object_has_prop;
+use crate::error::ClientErrorNeonExt;
+use crate::function_types::*;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DustPolicy {
+    /// Dust outputs are rejected
+    Reject,
+    /// Dust outputs are merged with the outputs to the same address and
+    /// validFrom, the ones still below the threshold are rejected
+    Merge,
+}
+
+impl Default for DustPolicy {
+    fn default() -> Self {
+        DustPolicy::Reject
+    }
+}
+
+impl DustPolicy {
+    pub fn from_name(policy: &str) -> Option<DustPolicy> {
+        match policy {
+            "Reject" => Some(DustPolicy::Reject),
+            "Merge" => Some(DustPolicy::Merge),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct DustOptions {
+    pub threshold: Coin,
+    pub policy: DustPolicy,
+}
+
+impl DustOptions {
+    /// Reads the dustThreshold option, amount in basic unit defaulting to 1,
+    /// and the dustPolicy option, "Reject" by default or "Merge"
+    pub fn parse(ctx: &mut FunctionContext, options: Handle<JsObject>) -> NeonResult<DustOptions> {
+        let threshold = if does_js_object_has_prop(ctx, options, "dustThreshold")? {
+            coin_property(ctx, options, "dustThreshold")?
+        } else {
+            // outputs of zero value are dust
+            Coin::unit()
+        };
+
+        let policy = if does_js_object_has_prop(ctx, options, "dustPolicy")? {
+            let policy = options
+                .get(ctx, "dustPolicy")?
+                .downcast_or_throw::<JsString, FunctionContext>(ctx)
+                .chain_neon(ctx, "Unable to downcast dustPolicy")?
+                .value();
+            DustPolicy::from_name(&policy)
+                .chain_neon(ctx, format!("Unrecognized dust policy {}", policy))?
+        } else {
+            DustPolicy::default()
+        };
+
+        Ok(DustOptions { threshold, policy })
+    }
+
+    pub fn is_dust(&self, value: Coin) -> bool {
+        u64::from(value) < u64::from(self.threshold)
+    }
+
+    /// Merge the dust outputs when the policy is Merge, then reject any dust
+    /// output left
+    pub fn apply(&self, outputs: &mut Vec<TxOut>) -> Result<()> {
+        if self.policy == DustPolicy::Merge {
+            self.merge(outputs)?;
+        }
+
+        self.check(outputs)
+    }
+
+    /// Returns an error naming the first dust output
+    pub fn check(&self, outputs: &[TxOut]) -> Result<()> {
+        match outputs.iter().position(|output| self.is_dust(output.value)) {
+            Some(index) => Err(Error::new(
+                ErrorKind::InvalidInput,
+                format!(
+                    "Output {} of {} is below the dust threshold {}",
+                    index,
+                    u64::from(outputs[index].value),
+                    u64::from(self.threshold)
+                ),
+            )),
+            None => Ok(()),
+        }
+    }
+
+    /// Merge every dust output with the first output to the same address and
+    /// validFrom, so that it is absorbed by an earlier output or absorbs a
+    /// later one
+    fn merge(&self, outputs: &mut Vec<TxOut>) -> Result<()> {
+        let mut merged: Vec<TxOut> = Vec::with_capacity(outputs.len());
+        for output in outputs.drain(..) {
+            let target = merged.iter().position(|other| {
+                other.address == output.address
+                    && other.valid_from == output.valid_from
+                    && (self.is_dust(output.value) || self.is_dust(other.value))
+            });
+            match target {
+                Some(i) => {
+                    merged[i].value = (merged[i].value + output.value).map_err(|err| {
+                        Error::new(
+                            ErrorKind::InvalidInput,
+                            format!("Unable to merge dust output: {}", err),
+                        )
+                    })?;
+                }
+                None => merged.push(output),
+            }
+        }
+        *outputs = merged;
+
+        Ok(())
+    }
+}
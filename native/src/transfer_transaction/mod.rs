@@ -1,48 +1,79 @@
+mod access_policy;
+mod batch;
 mod builder_options;
-
+mod coin_selection;
+mod dust;
+mod offline;
+mod output_order;
+mod payout;
+
+use chain_core::init::coin::Coin;
+use chain_core::init::network::Network;
 use chain_core::state::tendermint::BlockHeight;
-use chain_core::tx::data::access::{TxAccess, TxAccessPolicy};
 use chain_core::tx::data::attribute::TxAttributes;
 use chain_core::tx::data::input::TxoSize;
+use chain_core::tx::data::output::TxOut;
 use chain_core::tx::data::TxId;
-use chain_core::tx::fee::LinearFee;
+use chain_core::tx::fee::{FeeAlgorithm, LinearFee, Milli};
 use chain_core::tx::witness::TxInWitness;
 use chain_core::tx::{TransactionId, TxAux, TxEnclaveAux, TxObfuscated};
-use client_common::tendermint::WebsocketRpcClient;
-use client_common::{PrivateKey, Result, SignedTransaction, Transaction};
-use client_core::cipher::{
-    mock::MockAbciTransactionObfuscation, DefaultTransactionObfuscation, TransactionObfuscation,
+use client_common::{
+    Error, ErrorKind, PrivateKey, Result, ResultExt, SignedTransaction, Transaction,
 };
+use client_core::cipher::TransactionObfuscation;
 // use client_core::signer::{KeyPairSigner, Signer};
 use client_core::transaction_builder::RawTransferTransactionBuilder;
 use gcd::Gcd;
 use neon::prelude::*;
 use parity_scale_codec::{Decode, Encode};
 
+use crate::cancellation::CancellationToken;
 use crate::common::Features;
-use crate::error::ClientErrorNeonExt;
+use crate::error::{ClientErrorNeonExt, ErrorCode, NativeError};
 use crate::function_types::*;
+use crate::metrics;
 use crate::signer::KeyPairSigner;
+use crate::tx_aux::{
+    signed_transaction_to_tx_aux, tx_aux_to_hex, tx_aux_to_js, TxQueryObfuscation,
+};
 
-use builder_options::{BuilderOptions, LinearFeeBuilderOptions};
-
-type LinearFeeRawTransferTransactionBuilder = RawTransferTransactionBuilder<LinearFee>;
-
-/// Create a basic linear fee transfer transaction builder without witnesses
+use access_policy::{access_policies, view_keys_option};
+use batch::sign_batch_linear_fee;
+use builder_options::{BuilderOptions, CoinSelectionOptions, LinearFeeBuilderOptions};
+use coin_selection::{select_coins, CoinSelection, CoinSelectionError};
+use offline::{export_unsigned_linear_fee, finalize_unsigned_linear_fee};
+use payout::build_payout_batch_linear_fee;
+
+pub type LinearFeeRawTransferTransactionBuilder = RawTransferTransactionBuilder<LinearFee>;
+
+/// Create a basic linear fee transfer transaction builder without witnesses.
+/// The outputOrder option, "Preserved" by default, "Canonical" or "Shuffled",
+/// orders the outputs before any of them is signed. Outputs below the
+/// dustThreshold option, 1 basic unit by default as the node rejects outputs
+/// of zero value, are rejected unless the dustPolicy option is "Merge" and
+/// they merge with outputs to the same address above it.
+/// The additionalViewKeys and removedViewKeys options add and remove view
+/// keys of viewKeys, e.g. to grant an auditor access to the transaction;
+/// duplicates are dropped and there can be at most maxViewKeys, 64 by default
 pub fn build_incomplete_hex_linear_fee(mut ctx: FunctionContext) -> JsResult<JsBuffer> {
     let options: LinearFeeBuilderOptions =
         BuilderOptions::<LinearFee>::parse_linear_fee_fn_ctx(&mut ctx)?;
+    let builder = build_linear_fee_builder(&options)
+        .chain_neon(&mut ctx, "Unable to order transaction outputs")?;
 
-    let mut access_policies: Vec<TxAccessPolicy> = Vec::new();
-    for view_key in options.raw_tx_options.view_keys.iter() {
-        access_policies.push(TxAccessPolicy {
-            view_key: view_key.into(),
-            access: TxAccess::AllData,
-        });
-    }
+    let value = &builder.to_incomplete();
+    bytes_to_buffer(&mut ctx, &value)
+}
 
-    let attributes =
-        TxAttributes::new_with_access(options.raw_tx_options.chain_hex_id, access_policies);
+/// Create a linear fee transfer transaction builder with the inputs, outputs
+/// and view keys of the options
+fn build_linear_fee_builder(
+    options: &LinearFeeBuilderOptions,
+) -> Result<LinearFeeRawTransferTransactionBuilder> {
+    let attributes = TxAttributes::new_with_access(
+        options.raw_tx_options.chain_hex_id,
+        access_policies(&options.raw_tx_options.view_keys),
+    );
 
     let mut builder = RawTransferTransactionBuilder::new(attributes, options.fee_algorithm);
 
@@ -56,17 +87,289 @@ pub fn build_incomplete_hex_linear_fee(mut ctx: FunctionContext) -> JsResult<JsB
             ),
         );
     }
-    for output in options.raw_tx_options.outputs.iter() {
-        builder.add_output(output.to_owned());
+    let mut outputs = options.raw_tx_options.outputs.clone();
+    options.output_order.apply(&mut outputs)?;
+    for output in outputs {
+        builder.add_output(output);
     }
 
+    Ok(builder)
+}
+
+/// Create an empty linear fee transfer transaction builder with the chain hex
+/// id and view keys, inputs and outputs can be added afterwards. The view key
+/// options are the ones of `buildIncompleteHexLinearFee`
+pub fn new_builder_linear_fee(mut ctx: FunctionContext) -> JsResult<JsBuffer> {
+    let options = ctx.argument::<JsObject>(0)?;
+
+    let chain_hex_id = chain_hex_id_option(&mut ctx, *options)?;
+
+    let view_keys = view_keys_option(&mut ctx, options)?;
+
+    let fee_config = options
+        .get(&mut ctx, "feeConfig")?
+        .downcast_or_throw::<JsObject, FunctionContext>(&mut ctx)
+        .chain_neon(&mut ctx, "Unable to downcast feeConfig")?;
+    let fee_algorithm = parse_linear_fee_config(&mut ctx, fee_config)?;
+
+    let attributes = TxAttributes::new_with_access(chain_hex_id, access_policies(&view_keys));
+    let builder = RawTransferTransactionBuilder::new(attributes, fee_algorithm);
+
+    let value = &builder.to_incomplete();
+    bytes_to_buffer(&mut ctx, &value)
+}
+
+/// Add an input with its previous output and address params to the builder
+pub fn add_input_linear_fee(mut ctx: FunctionContext) -> JsResult<JsBuffer> {
+    let mut builder = incomplete_builder_linear_fee_argument(&mut ctx, 0)?;
+    let network = incomplete_builder_network_argument(&mut ctx, 0)?;
+    let input = ctx.argument::<JsObject>(1)?;
+    let input = BuilderOptions::<LinearFee>::parse_input(&mut ctx, *input, network)?;
+
+    let address_params = &input.address_params;
+    builder.add_input(
+        input.prev_output.clone(),
+        transfer_address_leaves(
+            address_params.total_signers,
+            address_params.required_signers,
+        ),
+    );
+
+    let value = &builder.to_incomplete();
+    bytes_to_buffer(&mut ctx, &value)
+}
+
+/// Add an output to the builder
+pub fn add_output_linear_fee(mut ctx: FunctionContext) -> JsResult<JsBuffer> {
+    let mut builder = incomplete_builder_linear_fee_argument(&mut ctx, 0)?;
+    let network = incomplete_builder_network_argument(&mut ctx, 0)?;
+    let output = ctx.argument::<JsObject>(1)?;
+    let output = parse_output(&mut ctx, output, network)?;
+
+    builder.add_output(output);
+
     let value = &builder.to_incomplete();
-    let mut buffer = ctx.buffer(value.len() as u32)?;
-    ctx.borrow_mut(&mut buffer, |data| {
-        let slice = data.as_mut_slice();
-        slice.copy_from_slice(&value);
+    bytes_to_buffer(&mut ctx, &value)
+}
+
+/// Create a linear fee transfer transaction builder funded by inputs selected
+/// automatically from the provided UTXOs, with change sent back to the change
+/// address. The outputOrder option orders the outputs and the change as in
+/// `buildIncompleteHexLinearFee`, the result has the changeIndex of the change
+/// output. UTXOs whose prevOutput validFrom is after the currentTime option,
+/// UNIX timestamp in seconds defaulting to system time, are not selected;
+/// outputs may carry validFrom to lock them until then. Outputs below the
+/// dustThreshold option, 1 basic unit by default, are rejected or, with the
+/// dustPolicy option "Merge", merged with the outputs to the same address;
+/// change below it is paid as fee, which the result warnings tell
+pub fn build_incomplete_hex_with_coin_selection_linear_fee(
+    mut ctx: FunctionContext,
+) -> JsResult<JsObject> {
+    let options = ctx.argument::<JsObject>(0)?;
+    let options = CoinSelectionOptions::parse(&mut ctx, options)?;
+
+    let available_indexes: Vec<usize> = (0..options.utxos.len()).collect();
+    let selection = select_utxos(&options, &available_indexes, &options.outputs)
+        .chain_neon(&mut ctx, "Unable to select UTXOs for transaction")?;
+
+    let (builder, permutation) =
+        build_ordered_coin_selection_builder(&options, &selection, &options.outputs)
+            .chain_neon(&mut ctx, "Unable to order transaction outputs")?;
+
+    let value = &builder.to_incomplete();
+    let buffer = bytes_to_buffer(&mut ctx, &value)?;
+
+    let selected_indexes = JsArray::new(&mut ctx, selection.indexes.len() as u32);
+    for (i, &index) in selection.indexes.iter().enumerate() {
+        let index = ctx.number(index as f64);
+        selected_indexes.set(&mut ctx, i as u32, index)?;
+    }
+
+    let return_object = ctx.empty_object();
+    return_object.set(&mut ctx, "incompleteHex", buffer)?;
+    return_object.set(&mut ctx, "selectedUtxoIndexes", selected_indexes)?;
+    if let Some(change) = selection.change {
+        let change = ctx.string(u64::from(change).to_string());
+        return_object.set(&mut ctx, "changeAmount", change)?;
+    }
+    if let Some(change_index) = change_index(&permutation, options.outputs.len()) {
+        let change_index = ctx.number(change_index as f64);
+        return_object.set(&mut ctx, "changeIndex", change_index)?;
+    }
+    let fee = ctx.string(u64::from(selection.fee).to_string());
+    return_object.set(&mut ctx, "fee", fee)?;
+    let warnings = warnings_to_js(&mut ctx, &selection_warnings(&options, &selection))?;
+    return_object.set(&mut ctx, "warnings", warnings)?;
+
+    Ok(return_object)
+}
+
+/// Create the builder of the coin selection with the outputs and change in
+/// the output order of the options, returns the permutation of the outputs
+/// followed by the change
+fn build_ordered_coin_selection_builder(
+    options: &CoinSelectionOptions,
+    selection: &CoinSelection,
+    outputs: &[TxOut],
+) -> Result<(LinearFeeRawTransferTransactionBuilder, Vec<usize>)> {
+    let mut outputs = outputs.to_vec();
+    if let Some(change) = selection.change {
+        outputs.push(TxOut::new(options.change_address.clone(), change));
+    }
+    let permutation = options.output_order.apply(&mut outputs)?;
+
+    let builder = build_coin_selection_builder(
+        options,
+        &selection.indexes,
+        &outputs,
+        None,
+        options.fee_algorithm,
+    );
+
+    Ok((builder, permutation))
+}
+
+/// Returns the position of the change, which follows the `outputs_len`
+/// outputs before ordering
+fn change_index(permutation: &[usize], outputs_len: usize) -> Option<usize> {
+    permutation.iter().position(|&i| i == outputs_len)
+}
+
+/// Create the builder spending the UTXOs of the indexes to the outputs, with
+/// the change output when provided
+fn build_coin_selection_builder<F: FeeAlgorithm>(
+    options: &CoinSelectionOptions,
+    indexes: &[usize],
+    outputs: &[TxOut],
+    change: Option<Coin>,
+    fee_algorithm: F,
+) -> RawTransferTransactionBuilder<F> {
+    let mut builder = RawTransferTransactionBuilder::new(options.attributes.clone(), fee_algorithm);
+    for &index in indexes {
+        let utxo = &options.utxos[index];
+        builder.add_input(
+            utxo.prev_output.clone(),
+            transfer_address_leaves(
+                utxo.address_params.total_signers,
+                utxo.address_params.required_signers,
+            ),
+        );
+    }
+    for output in outputs.iter() {
+        builder.add_output(output.to_owned());
+    }
+    if let Some(change) = change {
+        builder.add_output(TxOut::new(options.change_address.clone(), change));
+    }
+    builder
+}
+
+/// Select the UTXOs among the available indexes funding the outputs and the
+/// fee, selected indexes are the indexes of the options UTXOs. Timelocked
+/// UTXOs are not available before they are valid
+fn select_utxos(
+    options: &CoinSelectionOptions,
+    available_indexes: &[usize],
+    outputs: &[TxOut],
+) -> Result<CoinSelection> {
+    let (available_indexes, timelocked_indexes): (Vec<usize>, Vec<usize>) =
+        available_indexes.iter().partition(|&&index| {
+            options.utxos[index]
+                .prev_output
+                .1
+                .valid_from
+                .map_or(true, |valid_from| valid_from <= options.current_time)
+        });
+    let available_indexes = available_indexes.as_slice();
+
+    let values: Vec<Coin> = available_indexes
+        .iter()
+        .map(|&index| options.utxos[index].prev_output.1.value)
+        .collect();
+    let target = outputs
+        .iter()
+        .try_fold(0u64, |sum, output| sum.checked_add(u64::from(output.value)))
+        .chain(|| (ErrorKind::InvalidInput, "Output total value overflow"))?;
+    let target = Coin::new(target).chain(|| {
+        (
+            ErrorKind::InvalidInput,
+            "Output total value exceeds max supply",
+        )
+    })?;
+
+    let selection = select_coins(&values, target, options.strategy, |indexes, with_change| {
+        let indexes = indexes
+            .iter()
+            .map(|&i| available_indexes[i])
+            .collect::<Vec<usize>>();
+        // change value does not affect the encoded size
+        let change = if with_change {
+            Some(Coin::zero())
+        } else {
+            None
+        };
+        build_coin_selection_builder(options, &indexes, outputs, change, options.fee_algorithm)
+            .estimate_fee()
+            .map(|fee| fee.to_coin())
     });
-    Ok(buffer)
+    let mut selection = match selection {
+        Err(CoinSelectionError::InsufficientBalance) if !timelocked_indexes.is_empty() => {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                format!(
+                    "{}, {} of the UTXOs are timelocked after the current time",
+                    Error::from(CoinSelectionError::InsufficientBalance),
+                    timelocked_indexes.len()
+                ),
+            ))
+        }
+        selection => selection?,
+    };
+    selection.indexes = selection
+        .indexes
+        .iter()
+        .map(|&i| available_indexes[i])
+        .collect();
+    if let Some(change) = selection
+        .change
+        .filter(|&change| options.dust.is_dust(change))
+    {
+        selection.fee = (selection.fee + change).chain(|| {
+            (
+                ErrorKind::InvalidInput,
+                "Fee with dust change exceeds max supply",
+            )
+        })?;
+        selection.change = None;
+        selection.dust_change = Some(change);
+    }
+
+    Ok(selection)
+}
+
+/// Returns the warnings of the coin selection, e.g. dust change paid as fee
+fn selection_warnings(options: &CoinSelectionOptions, selection: &CoinSelection) -> Vec<String> {
+    selection
+        .dust_change
+        .map(|change| {
+            format!(
+                "Change of {} is below the dust threshold {} and is paid as fee",
+                u64::from(change),
+                u64::from(options.dust.threshold)
+            )
+        })
+        .into_iter()
+        .collect()
+}
+
+fn warnings_to_js<'a>(ctx: &mut FunctionContext<'a>, warnings: &[String]) -> JsResult<'a, JsArray> {
+    let js_warnings = JsArray::new(ctx, warnings.len() as u32);
+    for (i, warning) in warnings.iter().enumerate() {
+        let warning = ctx.string(warning);
+        js_warnings.set(ctx, i as u32, warning)?;
+    }
+
+    Ok(js_warnings)
 }
 
 fn transfer_address_leaves(total_signers: u64, required_signers: u64) -> u16 {
@@ -88,26 +391,25 @@ fn transfer_address_leaves(total_signers: u64, required_signers: u64) -> u16 {
 pub fn add_input_witness_linear_fee(mut ctx: FunctionContext) -> JsResult<JsBuffer> {
     let mut builder = incomplete_builder_linear_fee_argument(&mut ctx, 0)?;
     let input_index = ctx.argument::<JsNumber>(1)?.to_string(&mut ctx)?.value();
-    let mut witness = ctx.argument::<JsBuffer>(2)?;
-    let mut witness = witness.borrow_mut(&ctx.lock()).as_slice();
-    let witness = TxInWitness::decode(&mut witness)
-        .chain_neon(&mut ctx, "Unable to decode raw witness bytes")?;
+    let witness = u8_buffer_argument(&mut ctx, 2)?;
+    let witness = TxInWitness::decode(&mut witness.as_slice()).chain_neon_with_code(
+        &mut ctx,
+        ErrorCode::DeserializationError,
+        "Unable to decode raw witness bytes",
+    )?;
 
-    let input_index = input_index
-        .parse::<usize>()
-        .chain_neon(&mut ctx, "Unable to deserialize input index")?;
+    let input_index = input_index.parse::<usize>().chain_neon_with_code(
+        &mut ctx,
+        ErrorCode::DeserializationError,
+        "Unable to deserialize input index",
+    )?;
 
     builder
         .add_witness(input_index, witness)
         .chain_neon(&mut ctx, "Unable to add witness to input")?;
 
     let value = &builder.to_incomplete();
-    let mut buffer = ctx.buffer(value.len() as u32)?;
-    ctx.borrow_mut(&mut buffer, |data| {
-        let slice = data.as_mut_slice();
-        slice.copy_from_slice(&value);
-    });
-    Ok(buffer)
+    bytes_to_buffer(&mut ctx, &value)
 }
 
 /// Sign a particular input with the provided KeyPair
@@ -116,9 +418,11 @@ pub fn sign_input_linear_fee(mut ctx: FunctionContext) -> JsResult<JsBuffer> {
     let input_index = ctx.argument::<JsNumber>(1)?.to_string(&mut ctx)?.value();
     let (private_key, public_key) = key_pair_argument(&mut ctx, 2)?;
 
-    let input_index = input_index
-        .parse::<usize>()
-        .chain_neon(&mut ctx, "Unable to deserialize input index")?;
+    let input_index = input_index.parse::<usize>().chain_neon_with_code(
+        &mut ctx,
+        ErrorCode::DeserializationError,
+        "Unable to deserialize input index",
+    )?;
 
     let signer = KeyPairSigner::new(private_key, public_key)
         .chain_neon(&mut ctx, "Unable to create KeyPair signer")?;
@@ -131,12 +435,7 @@ pub fn sign_input_linear_fee(mut ctx: FunctionContext) -> JsResult<JsBuffer> {
         .chain_neon(&mut ctx, "Unable to add witness to input")?;
 
     let value = &builder.to_incomplete();
-    let mut buffer = ctx.buffer(value.len() as u32)?;
-    ctx.borrow_mut(&mut buffer, |data| {
-        let slice = data.as_mut_slice();
-        slice.copy_from_slice(&value);
-    });
-    Ok(buffer)
+    bytes_to_buffer(&mut ctx, &value)
 }
 
 /// Determine if all the transaction inputs have signature
@@ -163,105 +462,151 @@ pub fn estimate_fee_linear_fee(mut ctx: FunctionContext) -> JsResult<JsString> {
     let estimated_fee = builder
         .estimate_fee()
         .chain_neon(&mut ctx, "Unable to estimate transaction fee")?;
-    let estimated_fee = serde_json::to_string(&estimated_fee)
-        .chain_neon(&mut ctx, "Unable to serialize estimated fee to string")?;
+    let estimated_fee = serde_json::to_string(&estimated_fee).chain_neon_with_code(
+        &mut ctx,
+        ErrorCode::SerializationError,
+        "Unable to serialize estimated fee to string",
+    )?;
 
     Ok(ctx.string(estimated_fee.trim_matches('"')))
 }
 
-/// Finish the transaction and export to broadcast-able hex
-pub fn to_hex_linear_fee(mut ctx: FunctionContext) -> JsResult<JsBuffer> {
+/// Returns the estimated encoded size in bytes of the transaction of builder,
+/// so that the fee can be quoted before signing. Witnesses of the unsigned
+/// inputs are estimated from their address params, i.e. the number of leaves
+/// of their multi-sig Merkle tree
+pub fn estimate_size_linear_fee(mut ctx: FunctionContext) -> JsResult<JsNumber> {
     let builder = incomplete_builder_linear_fee_argument(&mut ctx, 0)?;
-    let tendermint_address = ctx.argument::<JsString>(1)?.value();
-    let features = Features::argument(&mut ctx, 2)?;
 
-    let tx_aux_result = match features {
-        Features::AllDefault => to_tx_aux_linear_fee(&mut ctx, &builder, &tendermint_address),
-        Features::MockAbci => {
-            to_mock_abci_tx_aux_linear_fee(&mut ctx, &builder, &tendermint_address)
-        }
-        Features::MockObfuscation => {
-            to_mock_tx_aux_linear_fee(&mut ctx, &builder, &tendermint_address)
-        }
-    };
-    let value = tx_aux_result?.encode();
+    let estimated_size =
+        estimate_tx_size(&builder).chain_neon(&mut ctx, "Unable to estimate transaction size")?;
 
-    let mut buffer = ctx.buffer(value.len() as u32)?;
-    ctx.borrow_mut(&mut buffer, |data| {
-        let slice = data.as_mut_slice();
-        slice.copy_from_slice(&value);
-    });
-    Ok(buffer)
+    Ok(ctx.number(estimated_size as f64))
 }
 
-fn to_tx_aux_linear_fee(
-    ctx: &mut FunctionContext,
-    builder: &LinearFeeRawTransferTransactionBuilder,
-    tendermint_address: &str,
-) -> NeonResult<TxAux> {
-    if tendermint_address.starts_with("ws") {
-        to_tx_aux_websocket_linear_fee(ctx, builder, tendermint_address)
-    } else {
-        ctx.throw_error("Unsupported Tendermint client protocol")
-    }
+/// Returns the estimated encoded size in bytes of the transaction of builder,
+/// see `estimateSizeLinearFee`
+pub fn estimate_tx_size(builder: &LinearFeeRawTransferTransactionBuilder) -> Result<usize> {
+    let estimated_size =
+        RawTransferTransactionBuilder::from_incomplete(builder.to_incomplete(), size_fee())
+            .and_then(|builder| builder.estimate_fee())?;
+
+    Ok(u64::from(estimated_size.to_coin()) as usize)
 }
 
-fn to_tx_aux_websocket_linear_fee(
-    ctx: &mut FunctionContext,
-    builder: &LinearFeeRawTransferTransactionBuilder,
-    tendermint_address: &str,
-) -> NeonResult<TxAux> {
-    let tendermint_client = WebsocketRpcClient::new(&tendermint_address)
-        .chain_neon(ctx, "Unable to create Tendermint client from address")?;
+/// Fee algorithm of one unit per byte, whose fee is the estimated size
+fn size_fee() -> LinearFee {
+    LinearFee::new(Milli::new(0, 0), Milli::new(1, 0))
+}
 
-    let tx_obfuscation = DefaultTransactionObfuscation::from_tx_query(&tendermint_client)
-        .chain_neon(
-            ctx,
-            "Unable to create transaction obfuscation from tx query address",
-        )?;
+/// Finish the transaction and export to broadcast-able hex. When a callback
+/// is provided as the last argument, obfuscation runs on a background thread,
+/// the result is passed to the callback and the task handle { id, cancel() }
+/// is returned. When an output Buffer is
+/// provided after the features instead, the transaction is encoded into it
+/// and the number of bytes written is returned
+pub fn to_hex_linear_fee(mut ctx: FunctionContext) -> JsResult<JsValue> {
+    metrics::measure_js("transferTransaction.toHexLinearFee", || {
+        let builder = incomplete_builder_linear_fee_argument(&mut ctx, 0)?;
+        let tendermint_address = ctx.argument::<JsString>(1)?.value();
+        let features = Features::argument(&mut ctx, 2)?;
+        let output = output_buffer_argument(&mut ctx, 3)?;
+        let callback = callback_argument(&mut ctx)?;
+
+        match callback {
+            Some(callback) => {
+                if output.is_some() {
+                    return ctx.throw_error("Output buffer is not supported with callback");
+                }
+                let cancellation = CancellationToken::register();
+                let task = ToTxAuxLinearFeeTask {
+                    builder,
+                    tendermint_address,
+                    features,
+                    cancellation: cancellation.clone(),
+                };
+                task.schedule(callback);
+
+                Ok(cancellation.to_js(&mut ctx)?.upcast())
+            }
+            None => {
+                let tx_aux = to_tx_aux_linear_fee(&builder, &tendermint_address, features)
+                    .chain_neon(&mut ctx, "Unable to finish transaction")?;
 
-    builder
-        .to_tx_aux(tx_obfuscation)
-        .chain_neon(ctx, "Unable to finish transaction")
+                tx_aux_to_js(&mut ctx, tx_aux, output)
+            }
+        }
+    })
 }
 
-fn to_mock_abci_tx_aux_linear_fee(
-    ctx: &mut FunctionContext,
+/// Finish the completed transaction of the builder, obfuscated through the
+/// tx-query enclave of the Tendermint websocket address. With the
+/// PlainTransactions features the transaction is plain, which only devnets
+/// configured for plain transactions accept
+pub fn to_tx_aux_linear_fee(
     builder: &LinearFeeRawTransferTransactionBuilder,
     tendermint_address: &str,
-) -> NeonResult<TxAux> {
-    if tendermint_address.starts_with("ws") {
-        to_mock_abci_tx_aux_websocket_linear_fee(ctx, builder, tendermint_address)
-    } else {
-        ctx.throw_error("Unsupported Tendermint client protocol")
+    features: Features,
+) -> Result<TxAux> {
+    match features {
+        Features::MockObfuscation => builder.to_tx_aux(MockTransactionCipher),
+        Features::PlainTransactions => signed_transaction_to_tx_aux(
+            signed_transaction_of_builder(builder)?,
+            tendermint_address,
+            features,
+        ),
+        _ => builder.to_tx_aux(TxQueryObfuscation::new(tendermint_address, features)),
     }
 }
 
-fn to_mock_abci_tx_aux_websocket_linear_fee(
-    ctx: &mut FunctionContext,
+/// Returns the signed transaction of the completed builder, before it is
+/// obfuscated
+pub fn signed_transaction_of_builder(
     builder: &LinearFeeRawTransferTransactionBuilder,
-    tendermint_address: &str,
-) -> NeonResult<TxAux> {
-    let tendermint_client = WebsocketRpcClient::new(&tendermint_address)
-        .chain_neon(ctx, "Unable to create Tendermint client from address")?;
-
-    let tx_obfuscation = MockAbciTransactionObfuscation::new(tendermint_client);
+) -> Result<SignedTransaction> {
+    match builder.to_tx_aux(MockTransactionCipher)? {
+        TxAux::EnclaveTx(TxEnclaveAux::TransferTx { payload, .. }) => {
+            SignedTransaction::decode(&mut payload.txpayload.as_slice()).chain(|| {
+                (
+                    ErrorKind::DeserializationError,
+                    "Unable to decode signed transaction of builder",
+                )
+            })
+        }
+        _ => Err(Error::new(
+            ErrorKind::InvalidInput,
+            "Builder transaction is not a transfer transaction",
+        )),
+    }
+}
 
-    builder
-        .to_tx_aux(tx_obfuscation)
-        .chain_neon(ctx, "Unable to finish transaction")
+struct ToTxAuxLinearFeeTask {
+    builder: LinearFeeRawTransferTransactionBuilder,
+    tendermint_address: String,
+    features: Features,
+    cancellation: CancellationToken,
 }
 
-fn to_mock_tx_aux_linear_fee(
-    ctx: &mut FunctionContext,
-    builder: &LinearFeeRawTransferTransactionBuilder,
-    _: &str,
-) -> NeonResult<TxAux> {
-    let tx_obfuscation = MockTransactionCipher;
+impl Task for ToTxAuxLinearFeeTask {
+    type Output = TxAux;
+    type Error = NativeError;
+    type JsEvent = JsBuffer;
 
-    builder
-        .to_tx_aux(tx_obfuscation)
-        .chain_neon(ctx, "Unable to finish transaction")
+    fn perform(&self) -> std::result::Result<TxAux, NativeError> {
+        to_tx_aux_linear_fee(&self.builder, &self.tendermint_address, self.features)
+            .map_err(|err| NativeError::from_client_error("Unable to finish transaction", &err))
+    }
+
+    fn complete(
+        self,
+        mut ctx: TaskContext,
+        result: std::result::Result<TxAux, NativeError>,
+    ) -> JsResult<JsBuffer> {
+        match self.cancellation.complete(result) {
+            Ok(tx_aux) => tx_aux_to_hex(&mut ctx, tx_aux),
+            Err(err) => err.throw(&mut ctx),
+        }
+    }
 }
 
 /// Verify the provided incomplete RawTransferTransaction hex is a valid
@@ -289,15 +634,39 @@ pub fn incomplete_builder_linear_fee_argument(
         .downcast_or_throw::<JsBuffer, FunctionContext>(ctx)
         .chain_neon(ctx, "Unable to downcast incompleteHex")?;
     let incomplete_hex = incomplete_hex.borrow(&ctx.lock()).as_slice().to_vec();
+    let linear_fee = incomplete_builder_linear_fee_config_argument(ctx, i)?;
+
+    RawTransferTransactionBuilder::from_incomplete(incomplete_hex, linear_fee).chain_neon_with_code(
+        ctx,
+        ErrorCode::DeserializationError,
+        "Unable to deserialize raw transfer transaction hex",
+    )
+}
 
-    let fee_config = incomplete_builder
+/// Returns the LinearFee of the feeConfig of the incomplete builder object
+#[inline]
+pub fn incomplete_builder_linear_fee_config_argument(
+    ctx: &mut FunctionContext,
+    i: i32,
+) -> NeonResult<LinearFee> {
+    let fee_config = ctx
+        .argument::<JsObject>(i)?
         .get(ctx, "feeConfig")?
         .downcast_or_throw::<JsObject, FunctionContext>(ctx)
         .chain_neon(ctx, "Unable to downcast feeConfig")?;
-    let linear_fee = parse_linear_fee_config(ctx, fee_config)?;
 
-    RawTransferTransactionBuilder::from_incomplete(incomplete_hex, linear_fee)
-        .chain_neon(ctx, "Unable to deserialize raw transfer transaction hex")
+    parse_linear_fee_config(ctx, fee_config)
+}
+
+/// Returns the network from the network or chainHexId of the incomplete
+/// builder object
+#[inline]
+fn incomplete_builder_network_argument(ctx: &mut FunctionContext, i: i32) -> NeonResult<Network> {
+    let incomplete_builder = ctx.argument::<JsObject>(i)?;
+
+    let (_, network) = network_option(ctx, *incomplete_builder)?;
+
+    Ok(network)
 }
 
 // #[cfg(feature = "mock")]
@@ -345,9 +714,36 @@ pub fn register_transfer_transaction_module(ctx: &mut ModuleContext) -> NeonResu
         build_incomplete_hex_linear_fee_fn,
     )?;
 
+    let build_incomplete_hex_with_coin_selection_linear_fee_fn =
+        JsFunction::new(ctx, build_incomplete_hex_with_coin_selection_linear_fee)?;
+    js_object.set(
+        ctx,
+        "buildIncompleteHexWithCoinSelectionLinearFee",
+        build_incomplete_hex_with_coin_selection_linear_fee_fn,
+    )?;
+
+    let build_payout_batch_linear_fee_fn = JsFunction::new(ctx, build_payout_batch_linear_fee)?;
+    js_object.set(
+        ctx,
+        "buildPayoutBatchLinearFee",
+        build_payout_batch_linear_fee_fn,
+    )?;
+
+    let new_builder_linear_fee_fn = JsFunction::new(ctx, new_builder_linear_fee)?;
+    js_object.set(ctx, "newBuilderLinearFee", new_builder_linear_fee_fn)?;
+
+    let add_input_linear_fee_fn = JsFunction::new(ctx, add_input_linear_fee)?;
+    js_object.set(ctx, "addInputLinearFee", add_input_linear_fee_fn)?;
+
+    let add_output_linear_fee_fn = JsFunction::new(ctx, add_output_linear_fee)?;
+    js_object.set(ctx, "addOutputLinearFee", add_output_linear_fee_fn)?;
+
     let sign_input_linear_fee_fn = JsFunction::new(ctx, sign_input_linear_fee)?;
     js_object.set(ctx, "signInputLinearFee", sign_input_linear_fee_fn)?;
 
+    let sign_batch_linear_fee_fn = JsFunction::new(ctx, sign_batch_linear_fee)?;
+    js_object.set(ctx, "signBatchLinearFee", sign_batch_linear_fee_fn)?;
+
     let add_input_witness_linear_fee_fn = JsFunction::new(ctx, add_input_witness_linear_fee)?;
     js_object.set(
         ctx,
@@ -370,5 +766,22 @@ pub fn register_transfer_transaction_module(ctx: &mut ModuleContext) -> NeonResu
     let estimate_fee_linear_fee_fn = JsFunction::new(ctx, estimate_fee_linear_fee)?;
     js_object.set(ctx, "estimateFeeLinearFee", estimate_fee_linear_fee_fn)?;
 
+    let estimate_size_linear_fee_fn = JsFunction::new(ctx, estimate_size_linear_fee)?;
+    js_object.set(ctx, "estimateSizeLinearFee", estimate_size_linear_fee_fn)?;
+
+    let export_unsigned_linear_fee_fn = JsFunction::new(ctx, export_unsigned_linear_fee)?;
+    js_object.set(
+        ctx,
+        "exportUnsignedLinearFee",
+        export_unsigned_linear_fee_fn,
+    )?;
+
+    let finalize_unsigned_linear_fee_fn = JsFunction::new(ctx, finalize_unsigned_linear_fee)?;
+    js_object.set(
+        ctx,
+        "finalizeUnsignedLinearFee",
+        finalize_unsigned_linear_fee_fn,
+    )?;
+
     ctx.export_value("transferTransaction", js_object)
 }
@@ -0,0 +1,214 @@
+//! Export of unsigned transfer transaction for cold signing
+use std::str::FromStr;
+
+use chain_core::tx::fee::{LinearFee, Milli};
+use chain_core::tx::witness::TxInWitness;
+use client_core::transaction_builder::RawTransferTransactionBuilder;
+use neon::prelude::*;
+use parity_scale_codec::Decode;
+use serde::{Deserialize, Serialize};
+
+use crate::error::{ClientErrorNeonExt, ErrorCode};
+use crate::function_types::*;
+use crate::offline::{
+    check_tx_id, unsigned_transaction_argument, unsigned_transaction_to_js, unsigned_tx_bytes,
+    witnesses_argument, ExportedOutput, SigningPayload, UnsignedTransaction, SCHNORR_SCHEME,
+    UNSIGNED_TRANSACTION_VERSION,
+};
+
+use super::build_linear_fee_builder;
+use super::builder_options::BuilderOptions;
+
+const TRANSFER_TX_TYPE: &str = "Transfer";
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct TransferDetails {
+    inputs: Vec<ExportedInput>,
+    outputs: Vec<ExportedOutput>,
+    view_keys: Vec<String>,
+    fee_config: ExportedFeeConfig,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ExportedInput {
+    prev_tx_id: String,
+    prev_index: u16,
+    prev_output: ExportedOutput,
+    required_signers: u64,
+    total_signers: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ExportedFeeConfig {
+    algorithm: String,
+    constant: String,
+    coefficient: String,
+}
+
+/// export unsigned linear fee transfer transaction as JSON with everything
+/// needed to sign the transaction offline
+/// @arguments
+/// - options: same as buildIncompleteHexLinearFee
+/// @return unsigned transaction JSON string { version, txType, txId,
+///     chainHexId, unsignedTx, signingPayloads: [{ signer, payload, scheme }],
+///     details: { inputs, outputs, viewKeys, feeConfig } }
+pub fn export_unsigned_linear_fee(mut ctx: FunctionContext) -> JsResult<JsString> {
+    let options = BuilderOptions::<LinearFee>::parse_linear_fee_fn_ctx(&mut ctx)?;
+    let fee_config = fee_config_argument(&mut ctx, 0)?;
+
+    let builder = build_linear_fee_builder(&options)
+        .chain_neon(&mut ctx, "Unable to order transaction outputs")?;
+    let tx_id = hex::encode(builder.tx_id());
+
+    let raw_tx_options = &options.raw_tx_options;
+    let network = raw_tx_options.network;
+    let mut inputs = Vec::with_capacity(raw_tx_options.inputs.len());
+    for input in raw_tx_options.inputs.iter() {
+        let (txo_pointer, prev_output) = &input.prev_output;
+        let prev_output = ExportedOutput::from_tx_out(prev_output, network)
+            .chain_neon(&mut ctx, "Unable to export input previous output")?;
+        inputs.push(ExportedInput {
+            prev_tx_id: hex::encode(txo_pointer.id),
+            prev_index: txo_pointer.index,
+            prev_output,
+            required_signers: input.address_params.required_signers,
+            total_signers: input.address_params.total_signers,
+        });
+    }
+    let outputs = raw_tx_options
+        .outputs
+        .iter()
+        .map(|output| ExportedOutput::from_tx_out(output, network))
+        .collect::<client_common::Result<Vec<ExportedOutput>>>()
+        .chain_neon(&mut ctx, "Unable to export outputs")?;
+    let view_keys = raw_tx_options
+        .view_keys
+        .iter()
+        .map(|view_key| hex::encode(view_key.serialize()))
+        .collect();
+
+    // Every input signs the same transaction id
+    let signing_payloads = (0..inputs.len())
+        .map(|i| SigningPayload {
+            signer: format!("input {}", i),
+            payload: tx_id.clone(),
+            scheme: SCHNORR_SCHEME.to_owned(),
+        })
+        .collect();
+
+    let unsigned_transaction = UnsignedTransaction {
+        version: UNSIGNED_TRANSACTION_VERSION,
+        tx_type: TRANSFER_TX_TYPE.to_owned(),
+        tx_id,
+        chain_hex_id: hex::encode([raw_tx_options.chain_hex_id]),
+        unsigned_tx: hex::encode(builder.to_incomplete()),
+        signing_payloads,
+        details: TransferDetails {
+            inputs,
+            outputs,
+            view_keys,
+            fee_config,
+        },
+    };
+
+    unsigned_transaction_to_js(&mut ctx, &unsigned_transaction)
+}
+
+/// finalize exported unsigned transfer transaction with the witnesses
+/// @arguments
+/// - unsigned_transaction: unsigned transaction JSON string
+/// - witnesses: [Buffer] TxInWitness of each input, in the order of
+///     signingPayloads
+/// @return incomplete builder { incompleteHex, feeConfig, chainHexId } with
+///     all witnesses, to be passed to toHexLinearFee
+pub fn finalize_unsigned_linear_fee(mut ctx: FunctionContext) -> JsResult<JsObject> {
+    let unsigned_transaction: UnsignedTransaction<TransferDetails> =
+        unsigned_transaction_argument(&mut ctx, 0, &[TRANSFER_TX_TYPE])?;
+    let witnesses = witnesses_argument(&mut ctx, 1, &unsigned_transaction)?;
+
+    let fee_config = &unsigned_transaction.details.fee_config;
+    let constant = Milli::from_str(&fee_config.constant)
+        .chain_neon(&mut ctx, "Invalid constant config in LinearFee")?;
+    let coefficient = Milli::from_str(&fee_config.coefficient)
+        .chain_neon(&mut ctx, "Invalid coefficient config in LinearFee")?;
+    let linear_fee = LinearFee::new(constant, coefficient);
+
+    let unsigned_tx = unsigned_tx_bytes(&mut ctx, &unsigned_transaction)?;
+    let mut builder = RawTransferTransactionBuilder::from_incomplete(unsigned_tx, linear_fee)
+        .chain_neon_with_code(
+            &mut ctx,
+            ErrorCode::DeserializationError,
+            "Unable to deserialize raw transfer transaction hex",
+        )?;
+    check_tx_id(&mut ctx, &unsigned_transaction, &builder.tx_id())?;
+
+    for (i, witness) in witnesses.iter().enumerate() {
+        let witness = TxInWitness::decode(&mut witness.as_slice()).chain_neon_with_code(
+            &mut ctx,
+            ErrorCode::DeserializationError,
+            format!("Unable to decode witness {}", i),
+        )?;
+        builder
+            .add_witness(i, witness)
+            .chain_neon(&mut ctx, format!("Unable to add witness to input {}", i))?;
+    }
+    builder.verify().chain_neon(
+        &mut ctx,
+        "Error when trying to verify raw transfer transaction",
+    )?;
+
+    let chain_hex_id = hex::decode(&unsigned_transaction.chain_hex_id).chain_neon_with_code(
+        &mut ctx,
+        ErrorCode::DeserializationError,
+        "Unable to decode chainHexId hex",
+    )?;
+
+    let incomplete_builder = ctx.empty_object();
+    let incomplete_hex = bytes_to_buffer(&mut ctx, &builder.to_incomplete())?;
+    incomplete_builder.set(&mut ctx, "incompleteHex", incomplete_hex)?;
+    let js_fee_config = ctx.empty_object();
+    let algorithm = ctx.string(&fee_config.algorithm);
+    js_fee_config.set(&mut ctx, "algorithm", algorithm)?;
+    let constant = ctx.string(&fee_config.constant);
+    js_fee_config.set(&mut ctx, "constant", constant)?;
+    let coefficient = ctx.string(&fee_config.coefficient);
+    js_fee_config.set(&mut ctx, "coefficient", coefficient)?;
+    incomplete_builder.set(&mut ctx, "feeConfig", js_fee_config)?;
+    let chain_hex_id = bytes_to_buffer(&mut ctx, &chain_hex_id)?;
+    incomplete_builder.set(&mut ctx, "chainHexId", chain_hex_id)?;
+
+    Ok(incomplete_builder)
+}
+
+/// Returns the fee config strings of the options argument, which has been
+/// validated when parsing the builder options
+fn fee_config_argument(ctx: &mut FunctionContext, i: i32) -> NeonResult<ExportedFeeConfig> {
+    let options = ctx.argument::<JsObject>(i)?;
+    let fee_config = options
+        .get(ctx, "feeConfig")?
+        .downcast_or_throw::<JsObject, FunctionContext>(ctx)
+        .chain_neon(ctx, "Unable to downcast feeConfig")?;
+
+    Ok(ExportedFeeConfig {
+        algorithm: fee_config_string(ctx, fee_config, "algorithm")?,
+        constant: fee_config_string(ctx, fee_config, "constant")?,
+        coefficient: fee_config_string(ctx, fee_config, "coefficient")?,
+    })
+}
+
+fn fee_config_string(
+    ctx: &mut FunctionContext,
+    fee_config: Handle<JsObject>,
+    key: &str,
+) -> NeonResult<String> {
+    let value = fee_config
+        .get(ctx, key)?
+        .downcast_or_throw::<JsString, FunctionContext>(ctx)
+        .chain_neon(ctx, format!("Unable to downcast {} in feeConfig", key))?
+        .value();
+
+    Ok(value)
+}
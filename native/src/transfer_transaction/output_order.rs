@@ -0,0 +1,85 @@
+//! Order of the transfer transaction outputs, fixed before the transaction is
+//! signed as the transaction id commits to it
+use chain_core::tx::data::output::TxOut;
+use client_common::Result;
+use neon::prelude::*;
+use parity_scale_codec::Encode;
+use rand::seq::SliceRandom;
+
+use crate::common::does_js_object_has_prop;
+use crate::error::ClientErrorNeonExt;
+use crate::rng::seeded_rng;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum OutputOrder {
+    /// Outputs in the order provided, change last
+    Preserved,
+    /// Outputs sorted by value then address, so that the same outputs always
+    /// encode to the same transaction
+    Canonical,
+    /// Outputs shuffled with the nodelib RNG, so that the change position does
+    /// not tell the change apart
+    Shuffled,
+}
+
+impl Default for OutputOrder {
+    fn default() -> Self {
+        OutputOrder::Preserved
+    }
+}
+
+impl OutputOrder {
+    pub fn from_name(order: &str) -> Option<OutputOrder> {
+        match order {
+            "Preserved" => Some(OutputOrder::Preserved),
+            "Canonical" => Some(OutputOrder::Canonical),
+            "Shuffled" => Some(OutputOrder::Shuffled),
+            _ => None,
+        }
+    }
+
+    /// Returns the permutation of the outputs in this order, i.e. the index
+    /// of the provided output at each position
+    pub fn permutation(self, outputs: &[TxOut]) -> Result<Vec<usize>> {
+        let mut permutation: Vec<usize> = (0..outputs.len()).collect();
+        match self {
+            OutputOrder::Preserved => (),
+            OutputOrder::Canonical => permutation.sort_by_key(|&i| {
+                let output = &outputs[i];
+                (
+                    u64::from(output.value),
+                    output.address.encode(),
+                    output.valid_from,
+                )
+            }),
+            OutputOrder::Shuffled => permutation.shuffle(&mut seeded_rng()?),
+        }
+
+        Ok(permutation)
+    }
+
+    /// Reorder the outputs, returns the permutation applied
+    pub fn apply(self, outputs: &mut Vec<TxOut>) -> Result<Vec<usize>> {
+        let permutation = self.permutation(outputs)?;
+        *outputs = permutation.iter().map(|&i| outputs[i].clone()).collect();
+
+        Ok(permutation)
+    }
+}
+
+/// Reads the outputOrder option, Preserved when absent
+pub fn output_order_option(
+    ctx: &mut FunctionContext,
+    options: Handle<JsObject>,
+) -> NeonResult<OutputOrder> {
+    if !does_js_object_has_prop(ctx, options, "outputOrder")? {
+        return Ok(OutputOrder::default());
+    }
+
+    let order = options
+        .get(ctx, "outputOrder")?
+        .downcast_or_throw::<JsString, FunctionContext>(ctx)
+        .chain_neon(ctx, "Unable to downcast outputOrder")?
+        .value();
+    OutputOrder::from_name(&order).chain_neon(ctx, format!("Unrecognized output order {}", order))
+}
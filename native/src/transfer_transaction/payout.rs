@@ -0,0 +1,202 @@
+//! Payouts to many recipients, e.g. payroll, built as few transfer
+//! transactions as the size limit allows. Every transaction is funded by its
+//! own coin selection, with its fee recalculated for its outputs and inputs
+use chain_core::init::coin::Coin;
+use chain_core::tx::data::output::TxOut;
+use client_common::{Error, ErrorKind, Result};
+use neon::prelude::*;
+
+use crate::error::ClientErrorNeonExt;
+use crate::function_types::*;
+
+use super::builder_options::CoinSelectionOptions;
+use super::coin_selection::CoinSelection;
+use super::dust::DustPolicy;
+use super::{
+    build_coin_selection_builder, build_ordered_coin_selection_builder, change_index, select_utxos,
+    selection_warnings, warnings_to_js,
+};
+
+/// Default limit of the estimated encoded size of a payout transaction
+const DEFAULT_MAX_TX_SIZE: u64 = 64 * 1024;
+
+struct PayoutTransaction {
+    /// Range of the payout outputs paid by the transaction
+    output_range: (usize, usize),
+    selection: CoinSelection,
+    estimated_size: u64,
+}
+
+/// Create the linear fee transfer transaction builders paying the outputs in
+/// order, each one funded by coin selection from the UTXOs not spent by the
+/// previous ones and with change sent back to the change address. Outputs
+/// are split among the transactions so that the estimated encoded size of
+/// each one is within the limit
+/// @arguments
+/// - options: options of `buildIncompleteHexWithCoinSelectionLinearFee`, and
+///     - maxTxSize: (optional) limit of the estimated encoded size of a
+///         transaction in bytes, default 65536
+/// @return [{ incompleteHex, selectedUtxoIndexes, outputIndexes,
+///     changeAmount?, changeIndex?, fee, estimatedSize, warnings }], dust
+///     outputs are rejected whatever the dustPolicy, outputIndexes are
+///     the indexes of the options outputs paid by the transaction in the order
+///     of its outputs, and changeIndex the position of its change output.
+///     Change of a transaction is not spent by the following ones as it is
+///     not committed yet
+pub fn build_payout_batch_linear_fee(mut ctx: FunctionContext) -> JsResult<JsArray> {
+    let js_options = ctx.argument::<JsObject>(0)?;
+    let options = CoinSelectionOptions::parse(&mut ctx, js_options)?;
+    let max_tx_size =
+        optional_integer_option(&mut ctx, js_options, "maxTxSize")?.unwrap_or(DEFAULT_MAX_TX_SIZE);
+    if options.outputs.is_empty() {
+        return ctx.throw_error("Payout should have at least one output");
+    }
+    // outputIndexes refer to the options outputs, which merging would shift
+    if options.dust.policy == DustPolicy::Merge {
+        return ctx.throw_error("Payout does not support dustPolicy Merge");
+    }
+
+    let transactions = plan_payout(&options, max_tx_size)
+        .chain_neon(&mut ctx, "Unable to build payout transactions")?;
+
+    let js_transactions = JsArray::new(&mut ctx, transactions.len() as u32);
+    for (i, transaction) in transactions.iter().enumerate() {
+        let (start, end) = transaction.output_range;
+        let (builder, permutation) = build_ordered_coin_selection_builder(
+            &options,
+            &transaction.selection,
+            &options.outputs[start..end],
+        )
+        .chain_neon(&mut ctx, "Unable to order transaction outputs")?;
+
+        let value = &builder.to_incomplete();
+        let buffer = bytes_to_buffer(&mut ctx, &value)?;
+
+        let selected_indexes = JsArray::new(&mut ctx, transaction.selection.indexes.len() as u32);
+        for (j, &index) in transaction.selection.indexes.iter().enumerate() {
+            let index = ctx.number(index as f64);
+            selected_indexes.set(&mut ctx, j as u32, index)?;
+        }
+        let output_indexes = JsArray::new(&mut ctx, (end - start) as u32);
+        let paid_indexes = permutation.iter().filter(|&&i| i < end - start);
+        for (j, &i) in paid_indexes.enumerate() {
+            let index = ctx.number((start + i) as f64);
+            output_indexes.set(&mut ctx, j as u32, index)?;
+        }
+
+        let js_transaction = ctx.empty_object();
+        js_transaction.set(&mut ctx, "incompleteHex", buffer)?;
+        js_transaction.set(&mut ctx, "selectedUtxoIndexes", selected_indexes)?;
+        js_transaction.set(&mut ctx, "outputIndexes", output_indexes)?;
+        if let Some(change) = transaction.selection.change {
+            let change = ctx.string(u64::from(change).to_string());
+            js_transaction.set(&mut ctx, "changeAmount", change)?;
+        }
+        if let Some(change_index) = change_index(&permutation, end - start) {
+            let change_index = ctx.number(change_index as f64);
+            js_transaction.set(&mut ctx, "changeIndex", change_index)?;
+        }
+        let fee = ctx.string(u64::from(transaction.selection.fee).to_string());
+        js_transaction.set(&mut ctx, "fee", fee)?;
+        let estimated_size = ctx.number(transaction.estimated_size as f64);
+        js_transaction.set(&mut ctx, "estimatedSize", estimated_size)?;
+        let warnings = selection_warnings(&options, &transaction.selection);
+        let warnings = warnings_to_js(&mut ctx, &warnings)?;
+        js_transaction.set(&mut ctx, "warnings", warnings)?;
+
+        js_transactions.set(&mut ctx, i as u32, js_transaction)?;
+    }
+
+    Ok(js_transactions)
+}
+
+/// Split the outputs into transactions taking as many outputs as fit in the
+/// size limit. The size grows with the outputs and the inputs funding them,
+/// so the largest fitting range is searched by doubling then bisecting
+fn plan_payout(options: &CoinSelectionOptions, max_tx_size: u64) -> Result<Vec<PayoutTransaction>> {
+    let mut available_indexes: Vec<usize> = (0..options.utxos.len()).collect();
+    let mut transactions = Vec::new();
+
+    let mut start = 0;
+    while start < options.outputs.len() {
+        let mut transaction = plan_transaction(options, &available_indexes, start, start + 1)?;
+        if transaction.estimated_size > max_tx_size {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                format!(
+                    "Transaction paying output {} is {} bytes, which exceeds maxTxSize",
+                    start, transaction.estimated_size
+                ),
+            ));
+        }
+
+        // Only the size limit makes a range not fit. The other errors, e.g.
+        // insufficient balance, would fail the remaining outputs anyway, as
+        // paying them in more transactions costs more fee
+        let fits = |end: usize| -> Result<Option<PayoutTransaction>> {
+            let transaction = plan_transaction(options, &available_indexes, start, end)?;
+
+            Ok(Some(transaction).filter(|transaction| transaction.estimated_size <= max_tx_size))
+        };
+        // Lengths up to fitting_len fit, from failing_len on they do not or
+        // run past the outputs
+        let remaining = options.outputs.len() - start;
+        let mut fitting_len = 1;
+        let mut failing_len = remaining + 1;
+        while fitting_len * 2 <= remaining {
+            let len = fitting_len * 2;
+            match fits(start + len)? {
+                Some(fitting) => {
+                    fitting_len = len;
+                    transaction = fitting;
+                }
+                None => {
+                    failing_len = len;
+                    break;
+                }
+            }
+        }
+        while failing_len - fitting_len > 1 {
+            let len = fitting_len + (failing_len - fitting_len) / 2;
+            match fits(start + len)? {
+                Some(fitting) => {
+                    fitting_len = len;
+                    transaction = fitting;
+                }
+                None => failing_len = len,
+            }
+        }
+
+        available_indexes.retain(|index| !transaction.selection.indexes.contains(index));
+        start = transaction.output_range.1;
+        transactions.push(transaction);
+    }
+
+    Ok(transactions)
+}
+
+fn plan_transaction(
+    options: &CoinSelectionOptions,
+    available_indexes: &[usize],
+    start: usize,
+    end: usize,
+) -> Result<PayoutTransaction> {
+    let outputs: &[TxOut] = &options.outputs[start..end];
+    let selection = select_utxos(options, available_indexes, outputs)?;
+
+    let estimated_size = build_coin_selection_builder(
+        options,
+        &selection.indexes,
+        outputs,
+        selection.change.map(|_| Coin::zero()),
+        size_fee(),
+    )
+    .estimate_fee()
+    .map(|fee| u64::from(fee.to_coin()))?;
+
+    Ok(PayoutTransaction {
+        output_range: (start, end),
+        selection,
+        estimated_size,
+    })
+}
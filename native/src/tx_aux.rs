@@ -6,124 +6,404 @@ use chain_core::tx::TxAux;
 use chain_core::tx::TxEnclaveAux;
 use chain_core::tx::{TransactionId, TxObfuscated};
 use client_common::tendermint::WebsocketRpcClient;
-use client_common::{PrivateKey, Result, SignedTransaction, Transaction};
+use client_common::{
+    Error, ErrorKind, PrivateKey, PublicKey, Result, ResultExt, SignedTransaction, Transaction,
+};
 use client_core::cipher::mock::MockAbciTransactionObfuscation;
 use client_core::cipher::{DefaultTransactionObfuscation, TransactionObfuscation};
 
-use parity_scale_codec::Encode;
+use parity_scale_codec::{Decode, Encode};
+use zeroize::Zeroizing;
 
+use crate::cancellation::CancellationToken;
+use crate::client_config::ClientConfig;
 use crate::common::Features;
-use crate::error::ClientErrorNeonExt;
+use crate::error::{ClientErrorNeonExt, NativeError};
+use crate::function_types::{encode_into_buffer, encode_to_buffer};
+#[cfg(feature = "mock-tx-query")]
+use crate::mock_tx_query;
+use crate::network::allows_plain_transactions;
+use crate::tx_query::{attestation_policy, AttestedTxQueryClient};
 
+/// Obfuscate the signed transaction and export to broadcast-able hex. When a
+/// callback is provided, obfuscation runs on a background thread because it
+/// connects to the tx-query enclave, the result is passed to the callback and
+/// the task handle { id, cancel() } is returned. When an output Buffer is provided instead, the transaction is encoded into
+/// it and the number of bytes written is returned
 pub fn signed_transaction_to_hex<'a>(
     ctx: &mut FunctionContext<'a>,
     signed_transaction: SignedTransaction,
     tendermint_address: &str,
     features: Features,
-) -> JsResult<'a, JsBuffer> {
-    let tx_aux =
-        signed_transaction_to_tx_aux(ctx, signed_transaction, tendermint_address, features)?;
+    output: Option<Handle<'a, JsBuffer>>,
+    callback: Option<Handle<JsFunction>>,
+) -> JsResult<'a, JsValue> {
+    match callback {
+        Some(callback) => {
+            if output.is_some() {
+                return ctx.throw_error("Output buffer is not supported with callback");
+            }
+            let cancellation = CancellationToken::register();
+            let task = ObfuscateTransactionTask {
+                signed_transaction,
+                tendermint_address: tendermint_address.to_owned(),
+                features,
+                cancellation: cancellation.clone(),
+            };
+            task.schedule(callback);
+
+            Ok(cancellation.to_js(ctx)?.upcast())
+        }
+        None => {
+            let tx_aux =
+                signed_transaction_to_tx_aux(signed_transaction, tendermint_address, features)
+                    .chain_neon(ctx, "Unable to obfuscate transaction")?;
 
-    tx_aux_to_hex(ctx, tx_aux)
+            tx_aux_to_js(ctx, tx_aux, output)
+        }
+    }
 }
 
-pub fn signed_transaction_to_tx_aux<'a>(
-    ctx: &mut FunctionContext<'a>,
+pub fn signed_transaction_to_tx_aux(
     signed_transaction: SignedTransaction,
     tendermint_address: &str,
     features: Features,
-) -> NeonResult<TxAux> {
+) -> Result<TxAux> {
     match features {
-        Features::AllDefault => to_tx_aux(ctx, signed_transaction, &tendermint_address),
-        Features::MockAbci => to_mock_abci_tx_aux(ctx, signed_transaction, &tendermint_address),
-        Features::MockObfuscation => to_mock_tx_aux(ctx, signed_transaction, &tendermint_address),
+        Features::MockObfuscation => MockTransactionCipher.encrypt(signed_transaction),
+        Features::PlainTransactions => {
+            ensure_plain_transactions_allowed(&signed_transaction)?;
+
+            MockTransactionCipher.encrypt(signed_transaction)
+        }
+        _ => TxQueryObfuscation::new(tendermint_address, features).encrypt(signed_transaction),
     }
 }
 
-pub fn tx_aux_to_hex<'a>(ctx: &mut FunctionContext<'a>, tx_aux: TxAux) -> JsResult<'a, JsBuffer> {
-    let tx_aux = tx_aux.encode();
+/// Returns an error unless the network of the transaction accepts plain
+/// transactions, which anyone can read without enclave obfuscation
+pub fn ensure_plain_transactions_allowed(signed_transaction: &SignedTransaction) -> Result<()> {
+    let chain_hex_id = signed_transaction_chain_hex_id(signed_transaction);
+    if !allows_plain_transactions(chain_hex_id) {
+        return Err(Error::new(
+            ErrorKind::InvalidInput,
+            format!(
+                "Network of chain hex id {} does not accept plain transactions without obfuscation",
+                hex::encode_upper([chain_hex_id])
+            ),
+        ));
+    }
 
-    let mut buffer = ctx.buffer(tx_aux.len() as u32)?;
-    ctx.borrow_mut(&mut buffer, |data| {
-        let slice = data.as_mut_slice();
-        slice.copy_from_slice(&tx_aux);
-    });
-    Ok(buffer)
+    Ok(())
 }
 
-fn to_tx_aux(
-    ctx: &mut FunctionContext,
-    signed_transaction: SignedTransaction,
+fn signed_transaction_chain_hex_id(signed_transaction: &SignedTransaction) -> u8 {
+    match signed_transaction {
+        SignedTransaction::TransferTransaction(tx, _) => tx.attributes.chain_hex_id,
+        SignedTransaction::DepositStakeTransaction(tx, _) => tx.attributes.chain_hex_id,
+        SignedTransaction::WithdrawUnbondedStakeTransaction(tx, _) => tx.attributes.chain_hex_id,
+    }
+}
+
+/// Decrypt the enclave transactions through the tx-query enclave of the
+/// connected node. Transactions which the view key has no access to are left
+/// out of the result
+pub fn decrypt_transactions(
+    enclave_txs: &[TxEnclaveAux],
+    view_private_key: &PrivateKey,
     tendermint_address: &str,
-) -> NeonResult<TxAux> {
-    if tendermint_address.starts_with("ws") {
-        to_tx_aux_websocket(ctx, signed_transaction, tendermint_address)
-    } else {
-        ctx.throw_error("Unsupported Tendermint client protocol")
+    features: Features,
+) -> Result<Vec<Transaction>> {
+    if enclave_txs.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let tx_ids = enclave_txs
+        .iter()
+        .map(|enclave_tx| enclave_tx_payload(enclave_tx).txid)
+        .collect::<Vec<TxId>>();
+    match features {
+        Features::MockObfuscation | Features::PlainTransactions => {
+            let view_key = PublicKey::from(view_private_key);
+            enclave_txs
+                .iter()
+                .filter_map(|enclave_tx| mock_decrypt(enclave_tx, &view_key).transpose())
+                .collect()
+        }
+        #[cfg(feature = "mock-tx-query")]
+        Features::MockTxQuery => mock_tx_query::decrypt_enclave_txs(enclave_txs, view_private_key),
+        _ => fetch_transactions(&tx_ids, view_private_key, tendermint_address, features),
     }
 }
 
-fn to_tx_aux_websocket(
-    ctx: &mut FunctionContext,
-    signed_transaction: SignedTransaction,
+/// Query the transactions of the ids from the tx-query enclave of the
+/// connected node, decrypted with the view key. Transactions which the view
+/// key has no access to are left out of the result
+pub fn fetch_transactions(
+    tx_ids: &[TxId],
+    view_private_key: &PrivateKey,
     tendermint_address: &str,
-) -> NeonResult<TxAux> {
-    let tendermint_client = WebsocketRpcClient::new(&tendermint_address)
-        .chain_neon(ctx, "Unable to create Tendermint client from address")?;
+    features: Features,
+) -> Result<Vec<Transaction>> {
+    if tx_ids.is_empty() {
+        return Ok(Vec::new());
+    }
 
-    let tx_obfuscation = DefaultTransactionObfuscation::from_tx_query(&tendermint_client)
-        .chain_neon(
-            ctx,
-            "Unable to create transaction obfuscation from tx query address",
-        )?;
+    match features {
+        // Mock obfuscated payloads are only in the blocks, there is no
+        // enclave to query them from
+        Features::MockObfuscation | Features::PlainTransactions => Err(Error::new(
+            ErrorKind::InvalidInput,
+            "Transactions cannot be fetched by id without enclave obfuscation",
+        )),
+        _ => {
+            TxQueryObfuscation::new(tendermint_address, features).decrypt(tx_ids, view_private_key)
+        }
+    }
+}
 
-    tx_obfuscation
-        .encrypt(signed_transaction)
-        .chain_neon(ctx, "Unable to encrypt transaction")
+pub fn enclave_tx_payload(enclave_tx: &TxEnclaveAux) -> &TxObfuscated {
+    match enclave_tx {
+        TxEnclaveAux::TransferTx { payload, .. } => payload,
+        TxEnclaveAux::DepositStakeTx { payload, .. } => payload,
+        TxEnclaveAux::WithdrawUnbondedStakeTx { payload, .. } => payload,
+    }
 }
 
-fn to_mock_abci_tx_aux(
-    ctx: &mut FunctionContext,
-    signed_transaction: SignedTransaction,
-    tendermint_address: &str,
-) -> NeonResult<TxAux> {
-    if tendermint_address.starts_with("ws") {
-        to_mock_abci_tx_aux_websocket(ctx, signed_transaction, tendermint_address)
+/// Mock obfuscated payload is the plain signed transaction, see
+/// `MockTransactionCipher`
+fn mock_decrypt(enclave_tx: &TxEnclaveAux, view_key: &PublicKey) -> Result<Option<Transaction>> {
+    plain_payload_transaction(&enclave_tx_payload(enclave_tx).txpayload, view_key)
+}
+
+/// Returns the transaction of the plain signed transaction payload when the
+/// view key has access to it, the access policy is checked as the enclave
+/// would do
+pub fn plain_payload_transaction(
+    txpayload: &[u8],
+    view_key: &PublicKey,
+) -> Result<Option<Transaction>> {
+    let mut txpayload = txpayload;
+    let signed_transaction = SignedTransaction::decode(&mut txpayload).chain(|| {
+        (
+            ErrorKind::DeserializationError,
+            "Unable to decode mock obfuscated transaction payload",
+        )
+    })?;
+
+    let (allowed_view, transaction) = match signed_transaction {
+        SignedTransaction::TransferTransaction(tx, _) => (
+            tx.attributes.allowed_view.clone(),
+            Transaction::TransferTransaction(tx),
+        ),
+        SignedTransaction::WithdrawUnbondedStakeTransaction(tx, _) => (
+            tx.attributes.allowed_view.clone(),
+            Transaction::WithdrawUnbondedStakeTransaction(tx),
+        ),
+        SignedTransaction::DepositStakeTransaction(..) => return Ok(None),
+    };
+
+    let view_key: secp256k1::PublicKey = view_key.into();
+    if allowed_view
+        .iter()
+        .any(|access_policy| access_policy.view_key == view_key)
+    {
+        Ok(Some(transaction))
     } else {
-        ctx.throw_error("Unsupported Tendermint client protocol")
+        Ok(None)
     }
 }
 
-fn to_mock_abci_tx_aux_websocket(
-    ctx: &mut FunctionContext,
-    signed_transaction: SignedTransaction,
-    tendermint_address: &str,
-) -> NeonResult<TxAux> {
-    let tendermint_client = WebsocketRpcClient::new(&tendermint_address)
-        .chain_neon(ctx, "Unable to create Tendermint client from address")?;
+pub fn tx_aux_to_hex<'a, C: Context<'a>>(ctx: &mut C, tx_aux: TxAux) -> JsResult<'a, JsBuffer> {
+    encode_to_buffer(ctx, &tx_aux)
+}
+
+/// Encode the TxAux into the output Buffer when provided and return the
+/// number of bytes written, otherwise into a new Buffer
+pub fn tx_aux_to_js<'a, C: Context<'a>>(
+    ctx: &mut C,
+    tx_aux: TxAux,
+    output: Option<Handle<'a, JsBuffer>>,
+) -> JsResult<'a, JsValue> {
+    match output {
+        Some(output) => {
+            let size = encode_into_buffer(ctx, &tx_aux, output)?;
+
+            Ok(ctx.number(size as f64).upcast())
+        }
+        None => Ok(tx_aux_to_hex(ctx, tx_aux)?.upcast()),
+    }
+}
+
+pub fn websocket_rpc_client(tendermint_address: &str) -> Result<WebsocketRpcClient> {
+    if !tendermint_address.starts_with("ws") {
+        return Err(Error::new(
+            ErrorKind::InvalidInput,
+            "Unsupported Tendermint client protocol",
+        ));
+    }
+
+    WebsocketRpcClient::new(tendermint_address)
+}
+
+/// Obfuscation through the tx-query enclave of the Tendermint websocket
+/// address, with the timeout and retries configured for the address. Every
+/// attempt connects anew as the connection of a failed attempt is unusable
+#[derive(Debug, Clone)]
+pub struct TxQueryObfuscation {
+    tendermint_address: String,
+    features: Features,
+    config: ClientConfig,
+}
 
-    let tx_obfuscation = MockAbciTransactionObfuscation::new(tendermint_client);
+impl TxQueryObfuscation {
+    /// Features should be AllDefault, MockAbci or MockTxQuery, mock
+    /// obfuscation does not connect to tx-query and neither does the mock
+    /// tx-query backend
+    pub fn new(tendermint_address: &str, features: Features) -> TxQueryObfuscation {
+        TxQueryObfuscation {
+            tendermint_address: tendermint_address.to_owned(),
+            features,
+            config: ClientConfig::of(tendermint_address),
+        }
+    }
+
+    /// Returns the tendermint client of the address. The client-core
+    /// tx-query client connects to the enclave on its own so it cannot go
+    /// through a proxy, which is an error rather than connecting directly.
+    /// The attested client tunnels through the proxy instead
+    fn tendermint_client(&self) -> Result<WebsocketRpcClient> {
+        if self.config.proxy.is_some() {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "Proxy of the tx-query client requires an attestation policy, see setAttestationPolicy",
+            ));
+        }
 
-    tx_obfuscation
-        .encrypt(signed_transaction)
-        .chain_neon(ctx, "Unable to encrypt transaction")
+        websocket_rpc_client(&self.tendermint_address)
+    }
+
+    /// When an attestation policy is set for the address, requests go
+    /// through the attested connection instead, so that they reach the
+    /// enclave which is attested. Mock ABCI has no enclave to attest
+    fn attested_client(&self) -> Option<AttestedTxQueryClient> {
+        match self.features {
+            Features::MockAbci => None,
+            _ => attestation_policy(&self.tendermint_address)
+                .map(|policy| AttestedTxQueryClient::new(&self.tendermint_address, policy)),
+        }
+    }
+
+    fn encrypt_once(&self, signed_transaction: SignedTransaction) -> Result<TxAux> {
+        #[cfg(feature = "mock-tx-query")]
+        {
+            if let Features::MockTxQuery = self.features {
+                return mock_tx_query::encrypt(signed_transaction);
+            }
+        }
+        if let Some(client) = self.attested_client() {
+            return client.encrypt(signed_transaction);
+        }
+        let tendermint_client = self.tendermint_client()?;
+        match self.features {
+            Features::MockAbci => {
+                MockAbciTransactionObfuscation::new(tendermint_client).encrypt(signed_transaction)
+            }
+            _ => DefaultTransactionObfuscation::from_tx_query(&tendermint_client)?
+                .encrypt(signed_transaction),
+        }
+    }
+
+    fn decrypt_once(
+        &self,
+        transaction_ids: &[TxId],
+        private_key: &PrivateKey,
+    ) -> Result<Vec<Transaction>> {
+        #[cfg(feature = "mock-tx-query")]
+        {
+            if let Features::MockTxQuery = self.features {
+                return mock_tx_query::decrypt(transaction_ids, private_key);
+            }
+        }
+        if let Some(client) = self.attested_client() {
+            return client.decrypt(transaction_ids, private_key);
+        }
+        let tendermint_client = self.tendermint_client()?;
+        match self.features {
+            Features::MockAbci => MockAbciTransactionObfuscation::new(tendermint_client)
+                .decrypt(transaction_ids, private_key),
+            _ => DefaultTransactionObfuscation::from_tx_query(&tendermint_client)?
+                .decrypt(transaction_ids, private_key),
+        }
+    }
 }
 
-fn to_mock_tx_aux(
-    ctx: &mut FunctionContext,
+impl TransactionObfuscation for TxQueryObfuscation {
+    fn decrypt(
+        &self,
+        transaction_ids: &[TxId],
+        private_key: &PrivateKey,
+    ) -> Result<Vec<Transaction>> {
+        let obfuscation = self.clone();
+        let transaction_ids = transaction_ids.to_vec();
+        // The attempt may outlive the call on timeout so it owns a copy,
+        // zeroized once the last attempt returns
+        let private_key = Zeroizing::new(private_key.serialize());
+
+        self.config.run(move || {
+            let private_key = PrivateKey::deserialize_from(&private_key)?;
+            obfuscation.decrypt_once(&transaction_ids, &private_key)
+        })
+    }
+
+    fn encrypt(&self, signed_transaction: SignedTransaction) -> Result<TxAux> {
+        let obfuscation = self.clone();
+
+        self.config
+            .run(move || obfuscation.encrypt_once(signed_transaction.clone()))
+    }
+}
+
+#[derive(Clone)]
+struct ObfuscateTransactionTask {
     signed_transaction: SignedTransaction,
-    _: &str,
-) -> NeonResult<TxAux> {
-    let tx_obfuscation = MockTransactionCipher;
+    tendermint_address: String,
+    features: Features,
+    cancellation: CancellationToken,
+}
+
+impl Task for ObfuscateTransactionTask {
+    type Output = TxAux;
+    type Error = NativeError;
+    type JsEvent = JsBuffer;
+
+    fn perform(&self) -> std::result::Result<TxAux, NativeError> {
+        let task = self.clone();
+        self.cancellation.run(move || {
+            signed_transaction_to_tx_aux(
+                task.signed_transaction,
+                &task.tendermint_address,
+                task.features,
+            )
+            .map_err(|err| NativeError::from_client_error("Unable to obfuscate transaction", &err))
+        })
+    }
 
-    tx_obfuscation
-        .encrypt(signed_transaction)
-        .chain_neon(ctx, "Unable to encrypt transaction")
+    fn complete(
+        self,
+        mut ctx: TaskContext,
+        result: std::result::Result<TxAux, NativeError>,
+    ) -> JsResult<JsBuffer> {
+        match self.cancellation.complete(result) {
+            Ok(tx_aux) => tx_aux_to_hex(&mut ctx, tx_aux),
+            Err(err) => err.throw(&mut ctx),
+        }
+    }
 }
 
 // #[cfg(feature = "mock")]
 #[derive(Debug, Clone)]
-struct MockTransactionCipher;
+pub struct MockTransactionCipher;
 
 // #[cfg(feature = "mock")]
 impl TransactionObfuscation for MockTransactionCipher {
@@ -139,6 +419,18 @@ impl TransactionObfuscation for MockTransactionCipher {
         let txpayload = transaction.encode();
 
         match transaction {
+            SignedTransaction::TransferTransaction(tx, _) => {
+                Ok(TxAux::EnclaveTx(TxEnclaveAux::TransferTx {
+                    inputs: tx.inputs.clone(),
+                    no_of_outputs: tx.outputs.len() as u16,
+                    payload: TxObfuscated {
+                        txid: tx.id(),
+                        key_from: BlockHeight::new(0),
+                        init_vector: [0u8; 12],
+                        txpayload,
+                    },
+                }))
+            }
             SignedTransaction::DepositStakeTransaction(tx, _) => {
                 Ok(TxAux::EnclaveTx(TxEnclaveAux::DepositStakeTx {
                     tx: tx.clone(),
@@ -162,7 +454,6 @@ impl TransactionObfuscation for MockTransactionCipher {
                     },
                 }))
             }
-            _ => unreachable!(),
         }
     }
 }
@@ -0,0 +1,467 @@
+//! Verification of the remote attestation of the tx-query enclave. The
+//! enclave certificate embeds the attestation report of Intel Attestation
+//! Service, which signs the quote of the enclave. The quote binds the
+//! certificate public key to the measurement of the enclave
+use std::convert::TryFrom;
+use std::net::TcpStream;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use chrono::NaiveDateTime;
+use client_common::{Error, ErrorKind, Result, ResultExt};
+use rustls::{
+    Certificate, ClientSession, RootCertStore, ServerCertVerified, ServerCertVerifier, Session,
+    Stream, TLSError,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use webpki::DNSNameRef;
+
+use super::certificate::EnclaveCertificate;
+use crate::client_config::ClientConfig;
+use crate::rpc::TendermintRpcClient;
+
+/// DER encoded OID 2.16.840.1.113730.1.13 of the attestation report extension
+const ATTESTATION_REPORT_OID: &[u8] = &[0x60, 0x86, 0x48, 0x01, 0x86, 0xf8, 0x42, 0x01, 0x0d];
+/// Offsets in the quote: 48 bytes of header then the report body
+const MR_ENCLAVE_OFFSET: usize = 48 + 64;
+const MR_SIGNER_OFFSET: usize = 48 + 128;
+const REPORT_DATA_OFFSET: usize = 48 + 320;
+const MEASUREMENT_SIZE: usize = 32;
+const QUOTE_BODY_MIN_SIZE: usize = REPORT_DATA_OFFSET + 64;
+/// ABCI query path of the tx-query address advertised by the node
+const TX_QUERY_PATH: &str = "txquery";
+const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(10);
+const FALLBACK_SERVER_NAME: &str = "localhost";
+
+static SUPPORTED_SIGNATURE_ALGORITHMS: &[&webpki::SignatureAlgorithm] = &[
+    &webpki::RSA_PKCS1_2048_8192_SHA256,
+    &webpki::RSA_PKCS1_2048_8192_SHA384,
+    &webpki::RSA_PKCS1_2048_8192_SHA512,
+];
+
+#[derive(Debug, Clone)]
+pub struct AttestationPolicy {
+    /// DER certificate of the CA of the report signing certificate, i.e. the
+    /// Intel Attestation Service root CA
+    pub ca_certificate: Vec<u8>,
+    /// Accepted MRENCLAVE, any when empty
+    pub mr_enclaves: Vec<[u8; MEASUREMENT_SIZE]>,
+    /// Accepted MRSIGNER, any when empty
+    pub mr_signers: Vec<[u8; MEASUREMENT_SIZE]>,
+    /// Accepted isvEnclaveQuoteStatus, e.g. OK
+    pub quote_statuses: Vec<String>,
+    /// Maximum age of the attestation report
+    pub max_report_age: Option<Duration>,
+    /// Address of the tx-query enclave, looked up from the chain when not
+    /// provided
+    pub tx_query_address: Option<String>,
+}
+
+/// Attestation report as embedded in the enclave certificate
+#[derive(Deserialize)]
+struct AttestationReport {
+    body: Vec<u8>,
+    signature: Vec<u8>,
+    signing_cert: Vec<u8>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct AttestationReportBody {
+    timestamp: String,
+    isv_enclave_quote_status: String,
+    isv_enclave_quote_body: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AttestationVerification {
+    pub is_valid: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mr_enclave: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mr_signer: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub quote_status: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub report_timestamp: Option<String>,
+}
+
+impl AttestationVerification {
+    pub fn invalid(reason: &str) -> AttestationVerification {
+        AttestationVerification {
+            reason: Some(reason.to_owned()),
+            ..Default::default()
+        }
+    }
+}
+
+/// Verify the attestation report of the DER enclave certificate against the
+/// policy. The fields of the report are returned as far as the verification
+/// goes
+pub fn verify_enclave_certificate(
+    certificate: &[u8],
+    policy: &AttestationPolicy,
+) -> AttestationVerification {
+    let mut verification = AttestationVerification::default();
+    match verify(certificate, policy, &mut verification) {
+        Ok(()) => verification.is_valid = true,
+        Err(reason) => verification.reason = Some(reason),
+    }
+
+    verification
+}
+
+fn verify(
+    certificate: &[u8],
+    policy: &AttestationPolicy,
+    verification: &mut AttestationVerification,
+) -> std::result::Result<(), String> {
+    let certificate = EnclaveCertificate::parse(certificate).map_err(|err| err.to_string())?;
+    let report = certificate
+        .extension(ATTESTATION_REPORT_OID)
+        .ok_or_else(|| "Enclave certificate has no attestation report".to_owned())?;
+    let report: AttestationReport = serde_json::from_slice(report)
+        .map_err(|err| format!("Unable to deserialize attestation report: {}", err))?;
+
+    verify_report_signature(&report, policy)?;
+
+    let body: AttestationReportBody = serde_json::from_slice(&report.body)
+        .map_err(|err| format!("Unable to deserialize attestation report body: {}", err))?;
+    verification.quote_status = Some(body.isv_enclave_quote_status.clone());
+    verification.report_timestamp = Some(body.timestamp.clone());
+    let quote = base64::decode(&body.isv_enclave_quote_body)
+        .map_err(|_| "Enclave quote body should be base64 string".to_owned())?;
+    if quote.len() < QUOTE_BODY_MIN_SIZE {
+        return Err("Enclave quote body is too short".to_owned());
+    }
+    let mr_enclave = &quote[MR_ENCLAVE_OFFSET..MR_ENCLAVE_OFFSET + MEASUREMENT_SIZE];
+    let mr_signer = &quote[MR_SIGNER_OFFSET..MR_SIGNER_OFFSET + MEASUREMENT_SIZE];
+    verification.mr_enclave = Some(hex::encode(mr_enclave));
+    verification.mr_signer = Some(hex::encode(mr_signer));
+
+    if !policy
+        .quote_statuses
+        .iter()
+        .any(|status| *status == body.isv_enclave_quote_status)
+    {
+        return Err(format!(
+            "Enclave quote status {} is not accepted",
+            body.isv_enclave_quote_status
+        ));
+    }
+    if let Some(max_report_age) = policy.max_report_age {
+        verify_report_age(&body.timestamp, max_report_age)?;
+    }
+    if !policy.mr_enclaves.is_empty()
+        && !policy
+            .mr_enclaves
+            .iter()
+            .any(|accepted| &accepted[..] == mr_enclave)
+    {
+        return Err("MRENCLAVE of the enclave is not accepted".to_owned());
+    }
+    if !policy.mr_signers.is_empty()
+        && !policy
+            .mr_signers
+            .iter()
+            .any(|accepted| &accepted[..] == mr_signer)
+    {
+        return Err("MRSIGNER of the enclave is not accepted".to_owned());
+    }
+
+    // The report data should commit to the public key of the certificate,
+    // otherwise the report could be replayed in another certificate
+    let public_key_hash = Sha256::digest(certificate.public_key);
+    if quote[REPORT_DATA_OFFSET..REPORT_DATA_OFFSET + public_key_hash.len()] != public_key_hash[..]
+    {
+        return Err("Attestation report is not for the certificate public key".to_owned());
+    }
+
+    Ok(())
+}
+
+fn verify_report_signature(
+    report: &AttestationReport,
+    policy: &AttestationPolicy,
+) -> std::result::Result<(), String> {
+    let signing_cert = webpki::EndEntityCert::from(&report.signing_cert)
+        .map_err(|err| format!("Invalid report signing certificate: {:?}", err))?;
+    let trust_anchor = webpki::trust_anchor_util::cert_der_as_trust_anchor(&policy.ca_certificate)
+        .map_err(|err| format!("Invalid attestation CA certificate: {:?}", err))?;
+    let time = webpki::Time::try_from(SystemTime::now())
+        .map_err(|_| "System time is before UNIX epoch".to_owned())?;
+
+    signing_cert
+        .verify_is_valid_tls_server_cert(
+            SUPPORTED_SIGNATURE_ALGORITHMS,
+            &webpki::TLSServerTrustAnchors(&[trust_anchor]),
+            &[],
+            time,
+        )
+        .map_err(|err| {
+            format!(
+                "Report signing certificate is not issued by the attestation CA: {:?}",
+                err
+            )
+        })?;
+    signing_cert
+        .verify_signature(
+            &webpki::RSA_PKCS1_2048_8192_SHA256,
+            &report.body,
+            &report.signature,
+        )
+        .map_err(|_| "Attestation report signature is invalid".to_owned())
+}
+
+/// Report timestamps are UTC without timezone, e.g. 2020-06-01T12:00:00.123456
+fn verify_report_age(timestamp: &str, max_report_age: Duration) -> std::result::Result<(), String> {
+    let report_time = NaiveDateTime::parse_from_str(timestamp, "%Y-%m-%dT%H:%M:%S%.f")
+        .map_err(|_| format!("Invalid attestation report timestamp {}", timestamp))?;
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_err(|_| "System time is before UNIX epoch".to_owned())?;
+
+    let age = (now.as_secs() as i64).saturating_sub(report_time.timestamp());
+    if age > max_report_age.as_secs() as i64 {
+        return Err(format!("Attestation report is {} seconds old", age));
+    }
+
+    Ok(())
+}
+
+/// TLS connection to the tx-query enclave which certificate passed the
+/// attestation verification during the handshake
+pub struct AttestedConnection {
+    pub tx_query_address: String,
+    session: ClientSession,
+    stream: TcpStream,
+}
+
+impl AttestedConnection {
+    /// Returns the TLS stream of the connection
+    pub fn stream(&mut self) -> Stream<ClientSession, TcpStream> {
+        Stream::new(&mut self.session, &mut self.stream)
+    }
+}
+
+/// Connect to the tx-query enclave of the Tendermint websocket address and
+/// verify the attestation report of its certificate
+pub fn attest(
+    tendermint_address: &str,
+    policy: &AttestationPolicy,
+) -> Result<AttestationVerification> {
+    handshake(tendermint_address, policy).map(|(verification, _)| verification)
+}
+
+/// Connect to the tx-query enclave of the Tendermint websocket address and
+/// returns the connection once the attestation report of its certificate is
+/// verified, so that tx-query requests go to the very enclave attested
+pub fn connect_attested(
+    tendermint_address: &str,
+    policy: &AttestationPolicy,
+) -> Result<AttestedConnection> {
+    match handshake(tendermint_address, policy)? {
+        (_, Some(connection)) => Ok(connection),
+        (verification, None) => Err(Error::new(
+            ErrorKind::VerifyError,
+            format!(
+                "tx-query enclave attestation is invalid: {}",
+                verification.reason.unwrap_or_default()
+            ),
+        )),
+    }
+}
+
+/// Complete the TLS handshake with the tx-query enclave. The attestation
+/// verifier is the certificate verifier of the connection, which is returned
+/// only when the attestation is valid
+fn handshake(
+    tendermint_address: &str,
+    policy: &AttestationPolicy,
+) -> Result<(AttestationVerification, Option<AttestedConnection>)> {
+    let tx_query_address = match &policy.tx_query_address {
+        Some(tx_query_address) => tx_query_address.clone(),
+        None => query_tx_query_address(tendermint_address)?,
+    };
+
+    log::debug!("Attesting tx-query enclave {}", tx_query_address);
+    let verifier = Arc::new(AttestationVerifier {
+        policy: policy.clone(),
+        verification: Mutex::new(None),
+    });
+    let mut tls_config = rustls::ClientConfig::new();
+    tls_config
+        .dangerous()
+        .set_certificate_verifier(Arc::clone(&verifier) as Arc<dyn ServerCertVerifier>);
+
+    // The enclave certificate is not issued to any name, the name only goes
+    // to SNI so fall back when the host is an IP address
+    let host = tx_query_address
+        .rsplitn(2, ':')
+        .last()
+        .unwrap_or_default()
+        .to_owned();
+    let dns_name = DNSNameRef::try_from_ascii_str(&host)
+        .or_else(|_| DNSNameRef::try_from_ascii_str(FALLBACK_SERVER_NAME))
+        .chain(|| (ErrorKind::InvalidInput, "Invalid tx-query server name"))?;
+    let mut session = ClientSession::new(&Arc::new(tls_config), dns_name);
+
+    // Through the proxy configured for the Tendermint address, the TLS
+    // session runs in the CONNECT tunnel so the proxy never sees the requests
+    let config = ClientConfig::of(tendermint_address);
+    let timeout = config.timeout.unwrap_or(HANDSHAKE_TIMEOUT);
+    let mut stream = match &config.proxy {
+        Some(proxy) => proxy.connect(&tx_query_address, timeout)?,
+        None => TcpStream::connect(&tx_query_address).chain(|| {
+            (
+                ErrorKind::ConnectionError,
+                format!("Unable to connect to tx-query {}", tx_query_address),
+            )
+        })?,
+    };
+    stream
+        .set_read_timeout(Some(timeout))
+        .and_then(|_| stream.set_write_timeout(Some(timeout)))
+        .chain(|| {
+            (
+                ErrorKind::ConnectionError,
+                "Unable to set tx-query connection timeout",
+            )
+        })?;
+
+    // The handshake fails when the attestation is invalid, in which case the
+    // verification result tells why
+    let mut handshake_result = Ok(());
+    while session.is_handshaking() {
+        if let Err(err) = session.complete_io(&mut stream) {
+            handshake_result = Err(err);
+            break;
+        }
+    }
+
+    let verification = verifier
+        .verification
+        .lock()
+        .ok()
+        .and_then(|mut verification| verification.take());
+    match (verification, handshake_result) {
+        (Some(verification), Ok(())) if verification.is_valid => {
+            log::info!("tx-query enclave {} is attested", tx_query_address);
+            let connection = AttestedConnection {
+                tx_query_address,
+                session,
+                stream,
+            };
+            Ok((verification, Some(connection)))
+        }
+        (Some(verification), Err(err)) if verification.is_valid => Err(Error::new(
+            ErrorKind::ConnectionError,
+            format!("Unable to complete TLS handshake with tx-query: {}", err),
+        )),
+        (Some(verification), _) => {
+            log::warn!(
+                "tx-query enclave {} attestation is invalid: {}",
+                tx_query_address,
+                verification
+                    .reason
+                    .as_deref()
+                    .unwrap_or("TLS handshake failed")
+            );
+            Ok((verification, None))
+        }
+        (None, Err(err)) => Err(Error::new(
+            ErrorKind::ConnectionError,
+            format!("Unable to complete TLS handshake with tx-query: {}", err),
+        )),
+        (None, Ok(())) => Err(Error::new(
+            ErrorKind::VerifyError,
+            "tx-query presents no certificate",
+        )),
+    }
+}
+
+/// Look up the tx-query address the node advertises through ABCI query
+fn query_tx_query_address(tendermint_address: &str) -> Result<String> {
+    let mut parts = tendermint_address.splitn(2, "://");
+    let authority = match (parts.next(), parts.next()) {
+        (Some("ws"), Some(rest)) | (Some("wss"), Some(rest)) => {
+            rest.split('/').next().unwrap_or_default()
+        }
+        _ => {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "Unsupported Tendermint client protocol",
+            ))
+        }
+    };
+
+    let result = TendermintRpcClient::new(&format!("http://{}", authority))?.abci_query(
+        TX_QUERY_PATH,
+        &[],
+        None,
+        false,
+    )?;
+    let value = result
+        .get("response")
+        .and_then(|response| response.get("value"))
+        .and_then(Value::as_str)
+        .unwrap_or_default();
+    let value = base64::decode(value).chain(|| {
+        (
+            ErrorKind::DeserializationError,
+            "tx-query address should be base64 string",
+        )
+    })?;
+    let tx_query_address = String::from_utf8(value).chain(|| {
+        (
+            ErrorKind::DeserializationError,
+            "tx-query address should be utf8 string",
+        )
+    })?;
+    if tx_query_address.is_empty() {
+        return Err(Error::new(
+            ErrorKind::InvalidInput,
+            "Node does not advertise a tx-query address",
+        ));
+    }
+
+    Ok(tx_query_address)
+}
+
+/// Accepts the TLS connection only when the attestation report of the
+/// enclave certificate is valid, the verification is kept for the caller
+struct AttestationVerifier {
+    policy: AttestationPolicy,
+    verification: Mutex<Option<AttestationVerification>>,
+}
+
+impl ServerCertVerifier for AttestationVerifier {
+    fn verify_server_cert(
+        &self,
+        _roots: &RootCertStore,
+        presented_certs: &[Certificate],
+        _dns_name: DNSNameRef,
+        _ocsp_response: &[u8],
+    ) -> std::result::Result<ServerCertVerified, TLSError> {
+        let verification = match presented_certs.first() {
+            Some(certificate) => verify_enclave_certificate(&certificate.0, &self.policy),
+            None => AttestationVerification::invalid("tx-query presents no certificate"),
+        };
+        let is_valid = verification.is_valid;
+        let reason = verification.reason.clone().unwrap_or_default();
+        if let Ok(mut result) = self.verification.lock() {
+            *result = Some(verification);
+        }
+
+        if is_valid {
+            Ok(ServerCertVerified::assertion())
+        } else {
+            Err(TLSError::General(reason))
+        }
+    }
+}
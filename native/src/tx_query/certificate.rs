@@ -0,0 +1,125 @@
+//! Minimal DER reader of the parts of the enclave certificate attestation
+//! needs: the public key and the extensions. The certificate signature is not
+//! checked, the enclave certificate is self-signed and is trusted through the
+//! attestation report it embeds
+use client_common::{Error, ErrorKind, Result};
+
+const TAG_BOOLEAN: u8 = 0x01;
+const TAG_BIT_STRING: u8 = 0x03;
+const TAG_OCTET_STRING: u8 = 0x04;
+const TAG_OID: u8 = 0x06;
+const TAG_SEQUENCE: u8 = 0x30;
+const TAG_VERSION: u8 = 0xa0;
+const TAG_EXTENSIONS: u8 = 0xa3;
+
+pub struct EnclaveCertificate<'a> {
+    /// Content of the subject public key bit string
+    pub public_key: &'a [u8],
+    /// (OID, value) of the extensions
+    extensions: Vec<(&'a [u8], &'a [u8])>,
+}
+
+impl<'a> EnclaveCertificate<'a> {
+    pub fn parse(der: &'a [u8]) -> Result<EnclaveCertificate<'a>> {
+        let (certificate, _) = read_expected(der, TAG_SEQUENCE, "certificate")?;
+        let (tbs_certificate, _) = read_expected(certificate, TAG_SEQUENCE, "tbsCertificate")?;
+
+        let mut rest = tbs_certificate;
+        if rest.first() == Some(&TAG_VERSION) {
+            rest = read_tlv(rest)?.2;
+        }
+        // serialNumber, signature, issuer, validity and subject
+        for _ in 0..5 {
+            rest = read_tlv(rest)?.2;
+        }
+
+        let (public_key_info, mut rest) =
+            read_expected(rest, TAG_SEQUENCE, "subjectPublicKeyInfo")?;
+        let (_, public_key_info) = read_expected(public_key_info, TAG_SEQUENCE, "algorithm")?;
+        let (public_key, _) = read_expected(public_key_info, TAG_BIT_STRING, "subjectPublicKey")?;
+        let public_key = match public_key.split_first() {
+            Some((0, public_key)) => public_key,
+            _ => return Err(malformed("subjectPublicKey should have no unused bit")),
+        };
+
+        let mut extensions = Vec::new();
+        while !rest.is_empty() {
+            let (tag, content, next) = read_tlv(rest)?;
+            rest = next;
+            if tag != TAG_EXTENSIONS {
+                continue;
+            }
+
+            let (mut extension_list, _) = read_expected(content, TAG_SEQUENCE, "extensions")?;
+            while !extension_list.is_empty() {
+                let (extension, next) = read_expected(extension_list, TAG_SEQUENCE, "extension")?;
+                extension_list = next;
+
+                let (oid, mut extension) = read_expected(extension, TAG_OID, "extnID")?;
+                if extension.first() == Some(&TAG_BOOLEAN) {
+                    extension = read_tlv(extension)?.2;
+                }
+                let (value, _) = read_expected(extension, TAG_OCTET_STRING, "extnValue")?;
+                extensions.push((oid, value));
+            }
+        }
+
+        Ok(EnclaveCertificate {
+            public_key,
+            extensions,
+        })
+    }
+
+    /// Returns the value of the extension of the DER encoded OID
+    pub fn extension(&self, oid: &[u8]) -> Option<&'a [u8]> {
+        self.extensions
+            .iter()
+            .find(|(extension_oid, _)| *extension_oid == oid)
+            .map(|(_, value)| *value)
+    }
+}
+
+/// Returns the tag, content and the rest after the element
+fn read_tlv(data: &[u8]) -> Result<(u8, &[u8], &[u8])> {
+    let (&tag, data) = data
+        .split_first()
+        .ok_or_else(|| malformed("unexpected end of certificate"))?;
+    let (&first_length_byte, mut data) = data
+        .split_first()
+        .ok_or_else(|| malformed("unexpected end of certificate"))?;
+
+    let length = if first_length_byte < 0x80 {
+        usize::from(first_length_byte)
+    } else {
+        let length_size = usize::from(first_length_byte & 0x7f);
+        if length_size == 0 || length_size > 4 || data.len() < length_size {
+            return Err(malformed("invalid element length"));
+        }
+        let length = data[..length_size]
+            .iter()
+            .fold(0usize, |length, &byte| (length << 8) | usize::from(byte));
+        data = &data[length_size..];
+        length
+    };
+    if data.len() < length {
+        return Err(malformed("element length exceeds certificate"));
+    }
+
+    Ok((tag, &data[..length], &data[length..]))
+}
+
+fn read_expected<'a>(data: &'a [u8], expected_tag: u8, name: &str) -> Result<(&'a [u8], &'a [u8])> {
+    let (tag, content, rest) = read_tlv(data)?;
+    if tag != expected_tag {
+        return Err(malformed(&format!("unexpected {}", name)));
+    }
+
+    Ok((content, rest))
+}
+
+fn malformed(reason: &str) -> Error {
+    Error::new(
+        ErrorKind::DeserializationError,
+        format!("Malformed enclave certificate: {}", reason),
+    )
+}
@@ -0,0 +1,156 @@
+//! tx-query client speaking the enclave protocol on the attested connection,
+//! so that the transactions and view keys only go to the enclave which
+//! attestation is verified. Every request connects and attests anew
+use std::io::{Read, Write};
+
+use chain_core::tx::data::TxId;
+use chain_core::tx::{TxAux, TxWithOutputs};
+use client_common::{
+    Error, ErrorKind, PrivateKey, Result, ResultExt, SignedTransaction, Transaction,
+};
+use client_core::cipher::TransactionObfuscation;
+use enclave_protocol::{
+    DecryptionRequest, DecryptionResponse, EncryptionRequest, EncryptionResponse,
+    TxQueryInitRequest, TxQueryInitResponse,
+};
+use parity_scale_codec::{Decode, Encode};
+use secp256k1::SecretKey;
+
+use super::attestation::{connect_attested, AttestationPolicy, AttestedConnection};
+use crate::common::with_secp;
+
+/// Upper bound of a tx-query response
+const MAX_RESPONSE_SIZE: usize = 16 * 1024 * 1024;
+const READ_CHUNK_SIZE: usize = 16 * 1024;
+
+#[derive(Debug, Clone)]
+pub struct AttestedTxQueryClient {
+    tendermint_address: String,
+    policy: AttestationPolicy,
+}
+
+impl AttestedTxQueryClient {
+    pub fn new(tendermint_address: &str, policy: AttestationPolicy) -> AttestedTxQueryClient {
+        AttestedTxQueryClient {
+            tendermint_address: tendermint_address.to_owned(),
+            policy,
+        }
+    }
+
+    fn connect(&self) -> Result<AttestedConnection> {
+        connect_attested(&self.tendermint_address, &self.policy)
+    }
+}
+
+impl TransactionObfuscation for AttestedTxQueryClient {
+    fn decrypt(
+        &self,
+        transaction_ids: &[TxId],
+        private_key: &PrivateKey,
+    ) -> Result<Vec<Transaction>> {
+        if transaction_ids.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let mut connection = self.connect()?;
+        let challenge = match exchange(&mut connection, &TxQueryInitRequest::DecryptChallenge)? {
+            TxQueryInitResponse::DecryptChallenge(challenge) => challenge,
+            _ => return Err(unexpected_response()),
+        };
+
+        let secret_key = SecretKey::from(private_key);
+        let request = with_secp(|secp| {
+            DecryptionRequest::create(secp, transaction_ids.to_owned(), challenge, &secret_key)
+        });
+        let response: DecryptionResponse = exchange(&mut connection, &request)?;
+
+        Ok(response
+            .txs
+            .into_iter()
+            .map(|tx| match tx {
+                TxWithOutputs::Transfer(tx) => Transaction::TransferTransaction(tx),
+                TxWithOutputs::StakeWithdraw(tx) => {
+                    Transaction::WithdrawUnbondedStakeTransaction(tx)
+                }
+            })
+            .collect())
+    }
+
+    fn encrypt(&self, transaction: SignedTransaction) -> Result<TxAux> {
+        let request = match transaction {
+            SignedTransaction::TransferTransaction(tx, witness) => {
+                EncryptionRequest::TransferTx(tx, witness)
+            }
+            SignedTransaction::DepositStakeTransaction(tx, witness) => {
+                EncryptionRequest::DepositStake(tx, witness)
+            }
+            SignedTransaction::WithdrawUnbondedStakeTransaction(tx, witness) => {
+                EncryptionRequest::WithdrawStake(tx, witness)
+            }
+        };
+
+        let mut connection = self.connect()?;
+        match exchange(
+            &mut connection,
+            &TxQueryInitRequest::Encrypt(Box::new(request)),
+        )? {
+            TxQueryInitResponse::Encrypt(EncryptionResponse { resp: Ok(tx_aux) }) => Ok(tx_aux),
+            TxQueryInitResponse::Encrypt(EncryptionResponse { resp: Err(err) }) => Err(Error::new(
+                ErrorKind::VerifyError,
+                format!("tx-query rejects the transaction: {}", err),
+            )),
+            _ => Err(unexpected_response()),
+        }
+    }
+}
+
+/// Send the request and read until the response decodes
+fn exchange<Q: Encode, R: Decode>(connection: &mut AttestedConnection, request: &Q) -> Result<R> {
+    let mut stream = connection.stream();
+    stream.write_all(&request.encode()).chain(|| {
+        (
+            ErrorKind::ConnectionError,
+            "Unable to send request to tx-query",
+        )
+    })?;
+    stream.flush().chain(|| {
+        (
+            ErrorKind::ConnectionError,
+            "Unable to send request to tx-query",
+        )
+    })?;
+
+    let mut response = Vec::new();
+    let mut chunk = [0u8; READ_CHUNK_SIZE];
+    loop {
+        let len = stream.read(&mut chunk).chain(|| {
+            (
+                ErrorKind::ConnectionError,
+                "Unable to read response of tx-query",
+            )
+        })?;
+        if len == 0 {
+            return Err(Error::new(
+                ErrorKind::DeserializationError,
+                "tx-query closes the connection before a complete response",
+            ));
+        }
+        response.extend_from_slice(&chunk[..len]);
+        if let Ok(decoded) = R::decode(&mut response.as_slice()) {
+            return Ok(decoded);
+        }
+        if response.len() > MAX_RESPONSE_SIZE {
+            return Err(Error::new(
+                ErrorKind::DeserializationError,
+                "tx-query response is too large",
+            ));
+        }
+    }
+}
+
+fn unexpected_response() -> Error {
+    Error::new(
+        ErrorKind::DeserializationError,
+        "Unexpected response of tx-query",
+    )
+}
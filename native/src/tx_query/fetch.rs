@@ -0,0 +1,113 @@
+use neon::prelude::*;
+
+use chain_core::tx::data::TxId;
+use client_common::{PrivateKey, Transaction};
+
+use crate::cancellation::CancellationToken;
+use crate::common::Features;
+use crate::error::{ClientErrorNeonExt, NativeError};
+use crate::function_types::*;
+use crate::transaction::transaction_to_js;
+use crate::tx_aux::fetch_transactions as fetch_decrypted_transactions;
+
+/// fetch the transactions of the ids from the tx-query enclave, decrypted
+/// with the view key, e.g. to recover the history on a fresh device
+/// @arguments
+/// - tendermint_address: Tendermint websocket address, used to look up the
+///     tx-query enclave address
+/// - tx_ids: transaction id hex strings
+/// - view_private_key: view private key Buffer
+/// - features: (optional) native features, default AllDefault
+/// - callback: node-style callback, fetch on background thread and pass the
+///     result to callback
+/// @return task handle { id, cancel() }, the result passed to callback is
+///     [{ txId, txType, inputs, outputs }] of the transactions the view key
+///     has access to, the others are left out
+pub fn fetch_transactions(mut ctx: FunctionContext) -> JsResult<JsValue> {
+    let tendermint_address = ctx.argument::<JsString>(0)?.value();
+    let tx_ids = ctx.argument::<JsArray>(1)?.to_vec(&mut ctx)?;
+    let view_private_key = private_key_argument(&mut ctx, 2)?;
+    let callback = required_callback_argument(&mut ctx)?;
+    let features = match ctx.argument_opt(3) {
+        Some(features) if features.is_a::<JsString>() => Features::argument(&mut ctx, 3)?,
+        _ => Features::AllDefault,
+    };
+
+    let tx_ids = tx_ids
+        .into_iter()
+        .map(|tx_id| {
+            let tx_id = tx_id
+                .downcast_or_throw::<JsString, FunctionContext>(&mut ctx)
+                .chain_neon(&mut ctx, "Unable to downcast transaction id")?
+                .value();
+            txid_from_str(&mut ctx, &tx_id)
+        })
+        .collect::<NeonResult<Vec<TxId>>>()?;
+
+    let cancellation = CancellationToken::register();
+    let task = FetchTransactionsTask {
+        tx_ids,
+        view_private_key,
+        tendermint_address,
+        features,
+        cancellation: cancellation.clone(),
+    };
+    task.schedule(callback);
+
+    Ok(cancellation.to_js(&mut ctx)?.upcast())
+}
+
+#[derive(Clone)]
+struct FetchTransactionsTask {
+    tx_ids: Vec<TxId>,
+    view_private_key: PrivateKey,
+    tendermint_address: String,
+    features: Features,
+    cancellation: CancellationToken,
+}
+
+impl Task for FetchTransactionsTask {
+    type Output = Vec<Transaction>;
+    type Error = NativeError;
+    type JsEvent = JsValue;
+
+    fn perform(&self) -> std::result::Result<Vec<Transaction>, NativeError> {
+        let task = self.clone();
+        self.cancellation.run(move || {
+            fetch_decrypted_transactions(
+                &task.tx_ids,
+                &task.view_private_key,
+                &task.tendermint_address,
+                task.features,
+            )
+            .map_err(|err| NativeError::from_client_error("Unable to fetch transactions", &err))
+        })
+    }
+
+    fn complete(
+        self,
+        mut ctx: TaskContext,
+        result: std::result::Result<Vec<Transaction>, NativeError>,
+    ) -> JsResult<JsValue> {
+        let result = self.cancellation.complete(result);
+        transactions_to_js(&mut ctx, result)
+    }
+}
+
+fn transactions_to_js<'a, C: Context<'a>>(
+    ctx: &mut C,
+    result: std::result::Result<Vec<Transaction>, NativeError>,
+) -> JsResult<'a, JsValue> {
+    let transactions = match result {
+        Ok(transactions) => transactions,
+        Err(err) => return err.throw(ctx),
+    };
+
+    let js_transactions = JsArray::new(ctx, transactions.len() as u32);
+    for (i, transaction) in transactions.iter().enumerate() {
+        let js_transaction = transaction_to_js(ctx, transaction)?;
+        js_transactions.set(ctx, i as u32, js_transaction)?;
+    }
+
+    Ok(js_transactions.upcast())
+}
@@ -0,0 +1,303 @@
+mod attestation;
+mod certificate;
+mod client;
+mod fetch;
+
+use std::collections::HashMap;
+use std::sync::RwLock;
+use std::time::Duration;
+
+use lazy_static::lazy_static;
+use neon::prelude::*;
+
+use crate::cancellation::CancellationToken;
+use crate::common::{does_js_object_has_prop, serialize_to_js};
+use crate::error::{ClientErrorNeonExt, ErrorCode, NativeError};
+use crate::function_types::*;
+
+pub use attestation::{attest as attest_tx_query, AttestationPolicy, AttestationVerification};
+pub use client::AttestedTxQueryClient;
+use fetch::fetch_transactions;
+
+const MEASUREMENT_SIZE: usize = 32;
+const DEFAULT_QUOTE_STATUS: &str = "OK";
+
+lazy_static! {
+    static ref ATTESTATION_POLICIES: RwLock<HashMap<String, AttestationPolicy>> =
+        RwLock::new(HashMap::new());
+}
+
+/// Returns the attestation policy registered for the Tendermint websocket
+/// address, tx-query connections of the address are not attested without one
+pub fn attestation_policy(tendermint_address: &str) -> Option<AttestationPolicy> {
+    ATTESTATION_POLICIES
+        .read()
+        .ok()
+        .and_then(|policies| policies.get(tendermint_address).cloned())
+}
+
+/// set the policy the tx-query enclave of the Tendermint websocket address is
+/// attested against, tx-query clients of the address fail to connect when the
+/// attestation is invalid
+/// @arguments
+/// - tendermint_address: Tendermint websocket address, e.g.
+///     ws://127.0.0.1:26657/websocket
+/// - policy: (optional) { caCertificate, mrEnclaves?, mrSigners?,
+///     quoteStatuses?, maxReportAgeSecs?, txQueryAddress? }, removes the
+///     policy of the address when omitted.
+///     - caCertificate: DER Buffer or PEM string of the CA of the attestation
+///         report signing certificate
+///     - mrEnclaves / mrSigners: accepted measurement hex strings of 32 bytes,
+///         any when empty
+///     - quoteStatuses: accepted enclave quote statuses, default ["OK"]
+///     - maxReportAgeSecs: maximum age of the attestation report
+///     - txQueryAddress: host:port of the enclave, looked up from the node
+///         when omitted
+pub fn set_attestation_policy(mut ctx: FunctionContext) -> JsResult<JsUndefined> {
+    let tendermint_address = ctx.argument::<JsString>(0)?.value();
+    let policy = match ctx.argument_opt(1) {
+        Some(policy) if !policy.is_a::<JsUndefined>() && !policy.is_a::<JsNull>() => {
+            let policy = policy
+                .downcast_or_throw::<JsObject, FunctionContext>(&mut ctx)
+                .chain_neon(&mut ctx, "Unable to downcast attestation policy")?;
+            Some(parse_attestation_policy(&mut ctx, policy)?)
+        }
+        _ => None,
+    };
+
+    let mut policies = ATTESTATION_POLICIES
+        .write()
+        .chain_neon(&mut ctx, "Unable to acquire attestation policy lock")?;
+    match policy {
+        Some(policy) => policies.insert(tendermint_address, policy),
+        None => policies.remove(&tendermint_address),
+    };
+
+    Ok(ctx.undefined())
+}
+
+/// connect to the tx-query enclave of the Tendermint websocket address and
+/// verify its attestation
+/// @arguments
+/// - tendermint_address: Tendermint websocket address, e.g.
+///     ws://127.0.0.1:26657/websocket
+/// - policy: (optional) attestation policy, see `setAttestationPolicy`. The
+///     registered policy of the address when omitted
+/// - callback: node-style callback, runs on background thread and pass the
+///     result to callback
+/// @return task handle { id, cancel() }, the result passed to callback is
+///     { isValid, reason?, mrEnclave?, mrSigner?, quoteStatus?,
+///     reportTimestamp? }, the fields are returned as far as the verification
+///     goes
+pub fn attest(mut ctx: FunctionContext) -> JsResult<JsValue> {
+    let tendermint_address = ctx.argument::<JsString>(0)?.value();
+    let callback = required_callback_argument(&mut ctx)?;
+
+    let policy = match ctx.argument_opt(1) {
+        Some(policy)
+            if policy.is_a::<JsObject>()
+                && !policy.is_a::<JsFunction>()
+                && !policy.is_a::<JsNull>() =>
+        {
+            let policy = policy
+                .downcast_or_throw::<JsObject, FunctionContext>(&mut ctx)
+                .chain_neon(&mut ctx, "Unable to downcast attestation policy")?;
+            parse_attestation_policy(&mut ctx, policy)?
+        }
+        _ => match attestation_policy(&tendermint_address) {
+            Some(policy) => policy,
+            None => {
+                return NativeError::new(
+                    ErrorCode::InvalidArgument,
+                    "No attestation policy is set for the Tendermint address",
+                )
+                .throw(&mut ctx)
+            }
+        },
+    };
+
+    let cancellation = CancellationToken::register();
+    let task = AttestTask {
+        tendermint_address,
+        policy,
+        cancellation: cancellation.clone(),
+    };
+    task.schedule(callback);
+
+    Ok(cancellation.to_js(&mut ctx)?.upcast())
+}
+
+#[derive(Clone)]
+struct AttestTask {
+    tendermint_address: String,
+    policy: AttestationPolicy,
+    cancellation: CancellationToken,
+}
+
+impl AttestTask {
+    fn result_to_js<'a, C: Context<'a>>(
+        &self,
+        ctx: &mut C,
+        result: std::result::Result<AttestationVerification, NativeError>,
+    ) -> JsResult<'a, JsValue> {
+        match result {
+            Ok(verification) => serialize_to_js(ctx, &verification),
+            Err(err) => err.throw(ctx),
+        }
+    }
+}
+
+impl Task for AttestTask {
+    type Output = AttestationVerification;
+    type Error = NativeError;
+    type JsEvent = JsValue;
+
+    fn perform(&self) -> std::result::Result<AttestationVerification, NativeError> {
+        let task = self.clone();
+        self.cancellation.run(move || {
+            attest_tx_query(&task.tendermint_address, &task.policy)
+                .map_err(|err| NativeError::from_client_error("Unable to attest tx-query", &err))
+        })
+    }
+
+    fn complete(
+        self,
+        mut ctx: TaskContext,
+        result: std::result::Result<AttestationVerification, NativeError>,
+    ) -> JsResult<JsValue> {
+        let result = self.cancellation.complete(result);
+        self.result_to_js(&mut ctx, result)
+    }
+}
+
+fn parse_attestation_policy(
+    ctx: &mut FunctionContext,
+    policy: Handle<JsObject>,
+) -> NeonResult<AttestationPolicy> {
+    let ca_certificate = policy.get(ctx, "caCertificate")?;
+    let ca_certificate = if let Ok(pem) = ca_certificate.downcast::<JsString>() {
+        pem_to_der(&pem.value()).chain_neon_with_code(
+            ctx,
+            ErrorCode::DeserializationError,
+            "Unable to decode PEM caCertificate",
+        )?
+    } else if let Ok(der) = ca_certificate.downcast::<JsBuffer>() {
+        der.borrow(&ctx.lock()).as_slice::<u8>().to_vec()
+    } else {
+        return ctx.throw_error("caCertificate should be a DER Buffer or PEM string");
+    };
+
+    let mr_enclaves = measurements_option(ctx, policy, "mrEnclaves")?;
+    let mr_signers = measurements_option(ctx, policy, "mrSigners")?;
+    let quote_statuses = if does_js_object_has_prop(ctx, policy, "quoteStatuses")? {
+        let quote_statuses = policy
+            .get(ctx, "quoteStatuses")?
+            .downcast_or_throw::<JsArray, FunctionContext>(ctx)
+            .chain_neon(ctx, "Unable to downcast quoteStatuses")?
+            .to_vec(ctx)?;
+        quote_statuses
+            .into_iter()
+            .map(|quote_status| {
+                Ok(quote_status
+                    .downcast_or_throw::<JsString, FunctionContext>(ctx)
+                    .chain_neon(ctx, "Unable to downcast quote status")?
+                    .value())
+            })
+            .collect::<NeonResult<Vec<String>>>()?
+    } else {
+        vec![DEFAULT_QUOTE_STATUS.to_owned()]
+    };
+
+    let max_report_age = if does_js_object_has_prop(ctx, policy, "maxReportAgeSecs")? {
+        let max_report_age = policy
+            .get(ctx, "maxReportAgeSecs")?
+            .downcast_or_throw::<JsNumber, FunctionContext>(ctx)
+            .chain_neon(ctx, "Unable to downcast maxReportAgeSecs")?
+            .value();
+        if max_report_age < 0.0 || max_report_age.fract() != 0.0 {
+            return ctx.throw_error("maxReportAgeSecs should be a non-negative integer");
+        }
+        Some(Duration::from_secs(max_report_age as u64))
+    } else {
+        None
+    };
+    let tx_query_address = if does_js_object_has_prop(ctx, policy, "txQueryAddress")? {
+        Some(
+            policy
+                .get(ctx, "txQueryAddress")?
+                .downcast_or_throw::<JsString, FunctionContext>(ctx)
+                .chain_neon(ctx, "Unable to downcast txQueryAddress")?
+                .value(),
+        )
+    } else {
+        None
+    };
+
+    Ok(AttestationPolicy {
+        ca_certificate,
+        mr_enclaves,
+        mr_signers,
+        quote_statuses,
+        max_report_age,
+        tx_query_address,
+    })
+}
+
+fn measurements_option(
+    ctx: &mut FunctionContext,
+    policy: Handle<JsObject>,
+    key: &str,
+) -> NeonResult<Vec<[u8; MEASUREMENT_SIZE]>> {
+    if !does_js_object_has_prop(ctx, policy, key)? {
+        return Ok(Vec::new());
+    }
+
+    let measurements = policy
+        .get(ctx, key)?
+        .downcast_or_throw::<JsArray, FunctionContext>(ctx)
+        .chain_neon(ctx, format!("Unable to downcast {}", key))?
+        .to_vec(ctx)?;
+    measurements
+        .into_iter()
+        .enumerate()
+        .map(|(i, measurement)| {
+            let name = format!("{}[{}]", key, i);
+            let bytes = parse_bytes(ctx, measurement, &name)?;
+            if bytes.len() != MEASUREMENT_SIZE {
+                return ctx.throw_error(format!("{} should be 32 bytes", name));
+            }
+            let mut measurement = [0u8; MEASUREMENT_SIZE];
+            measurement.copy_from_slice(&bytes);
+
+            Ok(measurement)
+        })
+        .collect()
+}
+
+/// Decode the first certificate of the PEM string
+fn pem_to_der(pem: &str) -> std::result::Result<Vec<u8>, base64::DecodeError> {
+    let encoded = pem
+        .lines()
+        .map(str::trim)
+        .skip_while(|line| !line.starts_with("-----BEGIN"))
+        .skip(1)
+        .take_while(|line| !line.starts_with("-----END"))
+        .collect::<String>();
+
+    base64::decode(&encoded)
+}
+
+pub fn register_tx_query_module(ctx: &mut ModuleContext) -> NeonResult<()> {
+    let js_object = JsObject::new(ctx);
+
+    let set_attestation_policy_fn = JsFunction::new(ctx, set_attestation_policy)?;
+    js_object.set(ctx, "setAttestationPolicy", set_attestation_policy_fn)?;
+
+    let attest_fn = JsFunction::new(ctx, attest)?;
+    js_object.set(ctx, "attest", attest_fn)?;
+
+    let fetch_transactions_fn = JsFunction::new(ctx, fetch_transactions)?;
+    js_object.set(ctx, "fetchTransactions", fetch_transactions_fn)?;
+
+    ctx.export_value("txQuery", js_object)
+}
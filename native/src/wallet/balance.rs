@@ -0,0 +1,94 @@
+//! Wallet balance over tracked UTXOs and pending transactions
+use std::collections::HashSet;
+
+use chain_core::common::Timespec;
+use chain_core::init::coin::Coin;
+use chain_core::tx::data::input::TxoPointer;
+use client_common::{Error, ErrorKind, Result};
+
+/// Unspent transaction output tracked by the wallet
+#[derive(Debug, Clone)]
+pub struct TrackedUtxo {
+    pub pointer: TxoPointer,
+    pub value: Coin,
+    pub valid_from: Option<Timespec>,
+}
+
+/// Transaction broadcasted by the wallet but not yet included in a block
+#[derive(Debug, Clone)]
+pub struct PendingTransaction {
+    /// UTXOs spent by the transaction
+    pub inputs: Vec<TxoPointer>,
+    /// Amount returned to the wallet, e.g. change
+    pub return_amount: Coin,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct WalletBalance {
+    /// Sum of available and pending balance
+    pub total: Coin,
+    /// UTXOs which can be spent now
+    pub available: Coin,
+    /// Amount returned by pending transactions and UTXOs not yet valid
+    pub pending: Coin,
+}
+
+/// Compute wallet balance. UTXOs spent by pending transactions are excluded
+/// from the balance, their return amounts are pending until confirmed
+pub fn compute_balance(
+    utxos: &[TrackedUtxo],
+    pending_transactions: &[PendingTransaction],
+    current_time: Timespec,
+) -> Result<WalletBalance> {
+    let mut tracked_pointers = HashSet::new();
+    for utxo in utxos.iter() {
+        if !tracked_pointers.insert(&utxo.pointer) {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                format!(
+                    "Duplicated UTXO {}:{}",
+                    hex::encode(utxo.pointer.id),
+                    utxo.pointer.index
+                ),
+            ));
+        }
+    }
+
+    let pending_inputs = pending_transactions
+        .iter()
+        .flat_map(|pending_transaction| pending_transaction.inputs.iter())
+        .collect::<HashSet<&TxoPointer>>();
+
+    let mut available = Coin::zero();
+    let mut pending = Coin::zero();
+    for utxo in utxos
+        .iter()
+        .filter(|utxo| !pending_inputs.contains(&utxo.pointer))
+    {
+        match utxo.valid_from {
+            Some(valid_from) if valid_from > current_time => {
+                pending = add_coins(pending, utxo.value)?
+            }
+            _ => available = add_coins(available, utxo.value)?,
+        }
+    }
+    for pending_transaction in pending_transactions.iter() {
+        pending = add_coins(pending, pending_transaction.return_amount)?;
+    }
+
+    Ok(WalletBalance {
+        total: add_coins(available, pending)?,
+        available,
+        pending,
+    })
+}
+
+/// Add coins with overflow and max supply checks
+fn add_coins(a: Coin, b: Coin) -> Result<Coin> {
+    (a + b).map_err(|err| {
+        Error::new(
+            ErrorKind::InvalidInput,
+            format!("Balance exceeds maximum coin supply: {}", err),
+        )
+    })
+}
@@ -0,0 +1,257 @@
+//! Management of multiple isolated HD wallets in one storage. Wallets are kept
+//! in the client-core wallet format, so they are readable by client-cli too
+use chain_core::init::address::CroAddress;
+use client_common::{Error, ErrorKind, PublicKey, Result};
+use client_core::types::WalletKind;
+use client_core::wallet::{DefaultWalletClient, WalletClient};
+use neon::prelude::*;
+use secstr::SecUtf8;
+
+use crate::error::{ClientErrorNeonExt, ErrorCode};
+use crate::function_types::*;
+use crate::storage::{
+    create_wallet_secrets, remove_wallet_secrets, secure_storage_argument, storage_argument,
+    wallet_name_argument, with_unlocked_enckey,
+};
+
+const DEFAULT_MNEMONIC_WORD_COUNT: u32 = 24;
+
+/// create a new HD wallet in the storage, the wallet is unlocked after
+/// creation
+/// @arguments
+/// - storage: storage handle
+/// - name: wallet name, which should not be used by another wallet
+/// - passphrase: wallet passphrase
+/// - word_count: (optional) mnemonic word count, 12, 15, 18, 21 or 24
+///     (default)
+/// @return mnemonic phrase of the wallet, which should be backed up
+pub fn create(mut ctx: FunctionContext) -> JsResult<JsString> {
+    let (path, storage) = secure_storage_argument(&mut ctx, 0)?;
+    let name = wallet_name_argument(&mut ctx, 1)?;
+    let passphrase = SecUtf8::from(ctx.argument::<JsString>(2)?.value());
+    let word_count = match ctx.argument_opt(3) {
+        Some(word_count) if !word_count.is_a::<JsUndefined>() => word_count
+            .downcast_or_throw::<JsNumber, FunctionContext>(&mut ctx)
+            .chain_neon(&mut ctx, "Unable to downcast word count")?
+            .value() as u32,
+        _ => DEFAULT_MNEMONIC_WORD_COUNT,
+    };
+
+    let wallet_client = DefaultWalletClient::new_read_only(storage.clone());
+    let (enckey, mnemonic) = wallet_client
+        .new_wallet(&name, &passphrase, WalletKind::HD, Some(word_count))
+        .chain_neon(&mut ctx, "Unable to create wallet")?;
+    let mnemonic = mnemonic.chain_neon(&mut ctx, "Unable to generate wallet mnemonic")?;
+    create_wallet_secrets(&storage, path, name, enckey)
+        .chain_neon(&mut ctx, "Unable to set wallet passphrase")?;
+
+    Ok(ctx.string(mnemonic.unsecure_phrase()))
+}
+
+/// list the names of the wallets in the storage
+/// @arguments
+/// - storage: storage handle
+/// @return [string]
+pub fn list(mut ctx: FunctionContext) -> JsResult<JsArray> {
+    let storage = storage_argument(&mut ctx, 0)?;
+
+    let names = DefaultWalletClient::new_read_only(storage)
+        .wallets()
+        .chain_neon(&mut ctx, "Unable to list wallets")?;
+
+    let js_names = JsArray::new(&mut ctx, names.len() as u32);
+    for (i, name) in names.iter().enumerate() {
+        let js_name = ctx.string(name);
+        js_names.set(&mut ctx, i as u32, js_name)?;
+    }
+
+    Ok(js_names)
+}
+
+/// delete the wallet and its keys from the storage
+/// @arguments
+/// - storage: storage handle
+/// - name: wallet name
+/// - passphrase: wallet passphrase
+pub fn delete(mut ctx: FunctionContext) -> JsResult<JsUndefined> {
+    let (path, storage) = secure_storage_argument(&mut ctx, 0)?;
+    let name = wallet_name_argument(&mut ctx, 1)?;
+    let passphrase = SecUtf8::from(ctx.argument::<JsString>(2)?.value());
+
+    DefaultWalletClient::new_read_only(storage.clone())
+        .delete_wallet(&name, &passphrase)
+        .chain_neon(&mut ctx, "Unable to delete wallet")?;
+    remove_wallet_secrets(&storage, path, &name)
+        .chain_neon(&mut ctx, "Unable to delete wallet secure records")?;
+
+    Ok(ctx.undefined())
+}
+
+/// list the transfer addresses of an unlocked wallet
+/// @arguments
+/// - storage: storage handle
+/// - name: wallet name
+/// - network: network handle or name of the addresses
+/// @return [string]
+pub fn transfer_addresses(mut ctx: FunctionContext) -> JsResult<JsArray> {
+    let (path, storage) = secure_storage_argument(&mut ctx, 0)?;
+    let name = wallet_name_argument(&mut ctx, 1)?;
+    let network = network_argument(&mut ctx, 2)?;
+
+    let addresses = with_unlocked_enckey(&path, &name, |enckey| {
+        let addresses =
+            DefaultWalletClient::new_read_only(storage).transfer_addresses(&name, enckey)?;
+        addresses
+            .iter()
+            .map(|address| {
+                address.to_cro(network).map_err(|err| {
+                    Error::new(
+                        ErrorKind::SerializationError,
+                        format!("Unable to serialize transfer address: {}", err),
+                    )
+                })
+            })
+            .collect::<Result<Vec<String>>>()
+    })
+    .chain_neon_with_code(
+        &mut ctx,
+        ErrorCode::InvalidAddress,
+        "Unable to list transfer addresses",
+    )?;
+
+    strings_to_js(&mut ctx, &addresses)
+}
+
+/// list the staking addresses of an unlocked wallet
+/// @arguments
+/// - storage: storage handle
+/// - name: wallet name
+/// @return [string]
+pub fn staking_addresses(mut ctx: FunctionContext) -> JsResult<JsArray> {
+    let (path, storage) = secure_storage_argument(&mut ctx, 0)?;
+    let name = wallet_name_argument(&mut ctx, 1)?;
+
+    let addresses = with_unlocked_enckey(&path, &name, |enckey| {
+        let addresses =
+            DefaultWalletClient::new_read_only(storage).staking_addresses(&name, enckey)?;
+        Ok(addresses
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<String>>())
+    })
+    .chain_neon_with_code(
+        &mut ctx,
+        ErrorCode::InvalidAddress,
+        "Unable to list staking addresses",
+    )?;
+
+    strings_to_js(&mut ctx, &addresses)
+}
+
+/// list the public keys of an unlocked wallet
+/// @arguments
+/// - storage: storage handle
+/// - name: wallet name
+/// @return { transferKeys: [Buffer], stakingKeys: [Buffer], viewKey: Buffer }
+pub fn public_keys(mut ctx: FunctionContext) -> JsResult<JsObject> {
+    let (path, storage) = secure_storage_argument(&mut ctx, 0)?;
+    let name = wallet_name_argument(&mut ctx, 1)?;
+
+    let (transfer_keys, staking_keys, view_key) = with_unlocked_enckey(&path, &name, |enckey| {
+        let wallet_client = DefaultWalletClient::new_read_only(storage);
+        let transfer_keys = wallet_client.public_keys(&name, enckey)?;
+        let staking_keys = wallet_client.staking_keys(&name, enckey)?;
+        let view_key = wallet_client.view_key(&name, enckey)?;
+
+        Ok((transfer_keys, staking_keys, view_key))
+    })
+    .chain_neon_with_code(
+        &mut ctx,
+        ErrorCode::InvalidPublicKey,
+        "Unable to list public keys",
+    )?;
+
+    let js_keys = ctx.empty_object();
+    let js_transfer_keys = public_keys_to_js(&mut ctx, transfer_keys.iter())?;
+    js_keys.set(&mut ctx, "transferKeys", js_transfer_keys)?;
+    let js_staking_keys = public_keys_to_js(&mut ctx, staking_keys.iter())?;
+    js_keys.set(&mut ctx, "stakingKeys", js_staking_keys)?;
+    let js_view_key = public_key_to_js(&mut ctx, &view_key)?;
+    js_keys.set(&mut ctx, "viewKey", js_view_key)?;
+
+    Ok(js_keys)
+}
+
+/// derive the next transfer address of an unlocked wallet
+/// @arguments
+/// - storage: storage handle
+/// - name: wallet name
+/// - network: network handle or name of the address
+/// @return string
+pub fn new_transfer_address(mut ctx: FunctionContext) -> JsResult<JsString> {
+    let (path, storage) = secure_storage_argument(&mut ctx, 0)?;
+    let name = wallet_name_argument(&mut ctx, 1)?;
+    let network = network_argument(&mut ctx, 2)?;
+
+    let address = with_unlocked_enckey(&path, &name, |enckey| {
+        DefaultWalletClient::new_read_only(storage).new_transfer_address(&name, enckey)
+    })
+    .chain_neon_with_code(
+        &mut ctx,
+        ErrorCode::InvalidAddress,
+        "Unable to create transfer address",
+    )?
+    .to_cro(network)
+    .chain_neon_with_code(
+        &mut ctx,
+        ErrorCode::InvalidAddress,
+        "Unable to serialize transfer address",
+    )?;
+
+    Ok(ctx.string(address))
+}
+
+/// derive the next staking address of an unlocked wallet
+/// @arguments
+/// - storage: storage handle
+/// - name: wallet name
+/// @return string
+pub fn new_staking_address(mut ctx: FunctionContext) -> JsResult<JsString> {
+    let (path, storage) = secure_storage_argument(&mut ctx, 0)?;
+    let name = wallet_name_argument(&mut ctx, 1)?;
+
+    let address = with_unlocked_enckey(&path, &name, |enckey| {
+        DefaultWalletClient::new_read_only(storage).new_staking_address(&name, enckey)
+    })
+    .chain_neon_with_code(
+        &mut ctx,
+        ErrorCode::InvalidAddress,
+        "Unable to create staking address",
+    )?;
+
+    Ok(ctx.string(address.to_string()))
+}
+
+fn public_keys_to_js<'a, 'b, I>(
+    ctx: &mut FunctionContext<'a>,
+    public_keys: I,
+) -> JsResult<'a, JsArray>
+where
+    I: ExactSizeIterator<Item = &'b PublicKey>,
+{
+    let js_public_keys = JsArray::new(ctx, public_keys.len() as u32);
+    for (i, public_key) in public_keys.enumerate() {
+        let js_public_key = public_key_to_js(ctx, public_key)?;
+        js_public_keys.set(ctx, i as u32, js_public_key)?;
+    }
+
+    Ok(js_public_keys)
+}
+
+fn public_key_to_js<'a>(
+    ctx: &mut FunctionContext<'a>,
+    public_key: &PublicKey,
+) -> JsResult<'a, JsBuffer> {
+    let value = public_key.serialize();
+    bytes_to_buffer(ctx, &value)
+}
@@ -0,0 +1,179 @@
+mod balance;
+mod manager;
+mod snapshot;
+
+use std::str::FromStr;
+
+use neon::prelude::*;
+
+use chain_core::common::Timespec;
+use chain_core::init::coin::Coin;
+
+use crate::common::does_js_object_has_prop;
+use crate::error::{ClientErrorNeonExt, ErrorCode};
+use crate::function_types::*;
+
+use balance::{PendingTransaction, TrackedUtxo};
+use manager::{
+    create, delete, list, new_staking_address, new_transfer_address, public_keys,
+    staking_addresses, transfer_addresses,
+};
+use snapshot::{export_snapshot, import_snapshot};
+
+/// compute wallet balance over tracked UTXOs with the chain Coin arithmetic
+/// @arguments
+/// - utxos: [{ prevTxId, prevIndex, value, validFrom? }] unspent outputs of
+///     the wallet
+/// - pending_txs: [{ inputs: [{ prevTxId, prevIndex }], returnAmount }]
+///     broadcasted but unconfirmed transactions of the wallet
+/// - current_time: (optional) current UNIX timestamp in seconds to check
+///     against validFrom, system time when omitted. Throws INVALID_ARGUMENT
+///     unless it is a non-negative integer
+/// @return { total, available, pending } in basic unit string
+pub fn compute_balance(mut ctx: FunctionContext) -> JsResult<JsObject> {
+    let utxos = ctx.argument::<JsArray>(0)?.to_vec(&mut ctx)?;
+    let utxos = utxos
+        .iter()
+        .map(|&utxo| {
+            let utxo = utxo
+                .downcast_or_throw::<JsObject, FunctionContext>(&mut ctx)
+                .chain_neon(&mut ctx, "Unable to downcast UTXO")?;
+            parse_tracked_utxo(&mut ctx, utxo)
+        })
+        .collect::<NeonResult<Vec<TrackedUtxo>>>()?;
+
+    let pending_txs = ctx.argument::<JsArray>(1)?.to_vec(&mut ctx)?;
+    let pending_txs = pending_txs
+        .iter()
+        .map(|&pending_tx| {
+            let pending_tx = pending_tx
+                .downcast_or_throw::<JsObject, FunctionContext>(&mut ctx)
+                .chain_neon(&mut ctx, "Unable to downcast pending transaction")?;
+            parse_pending_transaction(&mut ctx, pending_tx)
+        })
+        .collect::<NeonResult<Vec<PendingTransaction>>>()?;
+
+    let current_time = current_time_argument(&mut ctx, 2)?;
+
+    let balance = balance::compute_balance(&utxos, &pending_txs, current_time)
+        .chain_neon(&mut ctx, "Unable to compute balance")?;
+
+    let return_object = ctx.empty_object();
+    for (key, value) in &[
+        ("total", balance.total),
+        ("available", balance.available),
+        ("pending", balance.pending),
+    ] {
+        let value = ctx.string(u64::from(*value).to_string());
+        return_object
+            .set(&mut ctx, *key, value)
+            .chain_neon(&mut ctx, format!("Unable to set {} of return object", key))?;
+    }
+
+    Ok(return_object)
+}
+
+fn parse_tracked_utxo(
+    ctx: &mut FunctionContext,
+    utxo: Handle<JsObject>,
+) -> NeonResult<TrackedUtxo> {
+    let pointer = parse_prev_output_pointer(ctx, utxo)?;
+
+    let value = utxo
+        .get(ctx, "value")?
+        .downcast_or_throw::<JsString, FunctionContext>(ctx)
+        .chain_neon(ctx, "Unable to downcast value in UTXO")?
+        .value();
+    let value = Coin::from_str(&value).chain_neon_with_code(
+        ctx,
+        ErrorCode::DeserializationError,
+        "Unable to deserialize UTXO Coin value",
+    )?;
+
+    let valid_from = if does_js_object_has_prop(ctx, utxo, "validFrom")? {
+        let value = utxo
+            .get(ctx, "validFrom")?
+            .downcast_or_throw::<JsNumber, FunctionContext>(ctx)
+            .chain_neon(ctx, "Unable to downcast validFrom in UTXO")?
+            .value();
+        Some(value as Timespec)
+    } else {
+        None
+    };
+
+    Ok(TrackedUtxo {
+        pointer,
+        value,
+        valid_from,
+    })
+}
+
+fn parse_pending_transaction(
+    ctx: &mut FunctionContext,
+    pending_tx: Handle<JsObject>,
+) -> NeonResult<PendingTransaction> {
+    let inputs = pending_tx
+        .get(ctx, "inputs")?
+        .downcast_or_throw::<JsArray, FunctionContext>(ctx)
+        .chain_neon(ctx, "Unable to downcast inputs in pending transaction")?
+        .to_vec(ctx)?;
+    let inputs = parse_prev_output_pointer_vec(ctx, inputs)?;
+
+    let return_amount = pending_tx
+        .get(ctx, "returnAmount")?
+        .downcast_or_throw::<JsString, FunctionContext>(ctx)
+        .chain_neon(
+            ctx,
+            "Unable to downcast returnAmount in pending transaction",
+        )?
+        .value();
+    let return_amount = Coin::from_str(&return_amount).chain_neon_with_code(
+        ctx,
+        ErrorCode::DeserializationError,
+        "Unable to deserialize returnAmount Coin value",
+    )?;
+
+    Ok(PendingTransaction {
+        inputs,
+        return_amount,
+    })
+}
+
+pub fn register_wallet_module(ctx: &mut ModuleContext) -> NeonResult<()> {
+    let js_object = JsObject::new(ctx);
+
+    let compute_balance_fn = JsFunction::new(ctx, compute_balance)?;
+    js_object.set(ctx, "computeBalance", compute_balance_fn)?;
+
+    let create_fn = JsFunction::new(ctx, create)?;
+    js_object.set(ctx, "create", create_fn)?;
+
+    let list_fn = JsFunction::new(ctx, list)?;
+    js_object.set(ctx, "list", list_fn)?;
+
+    let delete_fn = JsFunction::new(ctx, delete)?;
+    js_object.set(ctx, "delete", delete_fn)?;
+
+    let transfer_addresses_fn = JsFunction::new(ctx, transfer_addresses)?;
+    js_object.set(ctx, "transferAddresses", transfer_addresses_fn)?;
+
+    let staking_addresses_fn = JsFunction::new(ctx, staking_addresses)?;
+    js_object.set(ctx, "stakingAddresses", staking_addresses_fn)?;
+
+    let public_keys_fn = JsFunction::new(ctx, public_keys)?;
+    js_object.set(ctx, "publicKeys", public_keys_fn)?;
+
+    let new_transfer_address_fn = JsFunction::new(ctx, new_transfer_address)?;
+    js_object.set(ctx, "newTransferAddress", new_transfer_address_fn)?;
+
+    let new_staking_address_fn = JsFunction::new(ctx, new_staking_address)?;
+    js_object.set(ctx, "newStakingAddress", new_staking_address_fn)?;
+
+    let export_snapshot_fn = JsFunction::new(ctx, export_snapshot)?;
+    js_object.set(ctx, "exportSnapshot", export_snapshot_fn)?;
+
+    let import_snapshot_fn = JsFunction::new(ctx, import_snapshot)?;
+    js_object.set(ctx, "importSnapshot", import_snapshot_fn)?;
+
+    ctx.export_value("wallet", js_object)
+}
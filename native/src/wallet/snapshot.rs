@@ -0,0 +1,242 @@
+//! Versioned, passphrase encrypted snapshot of a wallet for backup and
+//! migration between hosts. The snapshot carries the wallet keys in the
+//! client-core export format together with the addresses, the tracked UTXO
+//! set and the sync height provided by the caller
+use chain_core::init::address::CroAddress;
+use chain_core::init::network::Network;
+use client_common::seckey::derive_enckey;
+use client_common::storage::SledStorage;
+use client_common::{Error, ErrorKind, Result, SecKey};
+use client_core::types::WalletInfo;
+use client_core::wallet::{DefaultWalletClient, WalletClient};
+use neon::prelude::*;
+use secstr::SecUtf8;
+use serde::{Deserialize, Serialize};
+use zeroize::Zeroizing;
+
+use super::balance::TrackedUtxo;
+use super::parse_tracked_utxo;
+use crate::common::{does_js_object_has_prop, serialize_to_js};
+use crate::error::{ClientErrorNeonExt, ErrorCode};
+use crate::function_types::*;
+use crate::keystore::KeystoreCrypto;
+use crate::storage::{create_wallet_secrets, secure_storage_argument, wallet_name_argument};
+
+const SNAPSHOT_VERSION: u32 = 1;
+
+#[derive(Debug, Serialize, Deserialize)]
+struct WalletSnapshot {
+    version: u32,
+    crypto: KeystoreCrypto,
+}
+
+/// Plain content of the snapshot, encrypted as JSON
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SnapshotPayload {
+    wallet: WalletInfo,
+    #[serde(flatten)]
+    state: SnapshotState,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SnapshotState {
+    name: String,
+    transfer_addresses: Vec<String>,
+    staking_addresses: Vec<String>,
+    utxos: Vec<SnapshotUtxo>,
+    sync_height: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SnapshotUtxo {
+    prev_tx_id: String,
+    prev_index: u16,
+    value: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    valid_from: Option<u64>,
+}
+
+impl From<&TrackedUtxo> for SnapshotUtxo {
+    fn from(utxo: &TrackedUtxo) -> Self {
+        SnapshotUtxo {
+            prev_tx_id: hex::encode(utxo.pointer.id),
+            prev_index: utxo.pointer.index,
+            value: u64::from(utxo.value).to_string(),
+            valid_from: utxo.valid_from,
+        }
+    }
+}
+
+/// export the wallet as a single encrypted snapshot
+/// @arguments
+/// - storage: storage handle
+/// - name: wallet name
+/// - passphrase: wallet passphrase, which also encrypts the snapshot
+/// - network: network handle or name of the transfer addresses
+/// - state: (optional) { utxos: [{ prevTxId, prevIndex, value, validFrom? }],
+///     syncHeight } tracked by the caller, empty UTXO set and height 0 when
+///     omitted
+/// @return snapshot JSON string { version, crypto }
+pub fn export_snapshot(mut ctx: FunctionContext) -> JsResult<JsString> {
+    let (_, storage) = secure_storage_argument(&mut ctx, 0)?;
+    let name = wallet_name_argument(&mut ctx, 1)?;
+    let passphrase = SecUtf8::from(ctx.argument::<JsString>(2)?.value());
+    let network = network_argument(&mut ctx, 3)?;
+    let (utxos, sync_height) = parse_snapshot_state(&mut ctx, 4)?;
+
+    let enckey = derive_enckey(&passphrase, &name)
+        .chain_neon(&mut ctx, "Unable to derive encryption key from passphrase")?;
+    let payload = read_snapshot_payload(storage, name, &enckey, network, utxos, sync_height)
+        .chain_neon(&mut ctx, "Unable to export wallet")?;
+    let plain_text = Zeroizing::new(serde_json::to_vec(&payload).chain_neon_with_code(
+        &mut ctx,
+        ErrorCode::SerializationError,
+        "Unable to serialize snapshot",
+    )?);
+
+    let snapshot = WalletSnapshot {
+        version: SNAPSHOT_VERSION,
+        crypto: KeystoreCrypto::encrypt(&plain_text, &passphrase)
+            .chain_neon(&mut ctx, "Unable to encrypt snapshot")?,
+    };
+    let json = serde_json::to_string(&snapshot).chain_neon_with_code(
+        &mut ctx,
+        ErrorCode::SerializationError,
+        "Unable to serialize snapshot",
+    )?;
+
+    Ok(ctx.string(json))
+}
+
+/// import the wallet of the snapshot into the storage, the wallet is unlocked
+/// after import
+/// @arguments
+/// - storage: storage handle
+/// - snapshot: snapshot JSON string
+/// - passphrase: passphrase of the snapshot
+/// - name: (optional) wallet name to import as, name in the snapshot when
+///     omitted
+/// @return { name, transferAddresses, stakingAddresses, utxos, syncHeight }
+pub fn import_snapshot(mut ctx: FunctionContext) -> JsResult<JsValue> {
+    let (path, storage) = secure_storage_argument(&mut ctx, 0)?;
+    let snapshot = typed_argument::<JsString>(&mut ctx, 1, "snapshot", "JSON string")?.value();
+    let passphrase = SecUtf8::from(ctx.argument::<JsString>(2)?.value());
+    let name = match ctx.argument_opt(3) {
+        Some(name) if !name.is_a::<JsUndefined>() => Some(wallet_name_argument(&mut ctx, 3)?),
+        _ => None,
+    };
+
+    let snapshot: WalletSnapshot = serde_json::from_str(&snapshot).chain_neon_with_code(
+        &mut ctx,
+        ErrorCode::DeserializationError,
+        "Unable to deserialize snapshot",
+    )?;
+    if snapshot.version != SNAPSHOT_VERSION {
+        return ctx.throw_error(format!("Unsupported snapshot version {}", snapshot.version));
+    }
+    let plain_text = Zeroizing::new(
+        snapshot
+            .crypto
+            .decrypt(&passphrase)
+            .chain_neon(&mut ctx, "Unable to decrypt snapshot")?,
+    );
+    let mut payload: SnapshotPayload = serde_json::from_slice(&plain_text).chain_neon_with_code(
+        &mut ctx,
+        ErrorCode::DeserializationError,
+        "Unable to deserialize snapshot content",
+    )?;
+    if let Some(name) = name {
+        payload.state.name = name;
+    }
+
+    let state = payload.state;
+    let enckey = DefaultWalletClient::new_read_only(storage.clone())
+        .import_wallet(&state.name, &passphrase, payload.wallet)
+        .chain_neon(&mut ctx, "Unable to import wallet")?;
+    create_wallet_secrets(&storage, path, state.name.clone(), enckey)
+        .chain_neon(&mut ctx, "Unable to set wallet passphrase")?;
+
+    serialize_to_js(&mut ctx, &state)
+}
+
+fn read_snapshot_payload(
+    storage: SledStorage,
+    name: String,
+    enckey: &SecKey,
+    network: Network,
+    utxos: Vec<TrackedUtxo>,
+    sync_height: u64,
+) -> Result<SnapshotPayload> {
+    let wallet_client = DefaultWalletClient::new_read_only(storage);
+    let wallet = wallet_client.export_wallet(&name, enckey)?;
+
+    let mut transfer_addresses = Vec::new();
+    for address in wallet_client.transfer_addresses(&name, enckey)?.iter() {
+        let address = address.to_cro(network).map_err(|err| {
+            Error::new(
+                ErrorKind::SerializationError,
+                format!("Unable to serialize transfer address: {}", err),
+            )
+        })?;
+        transfer_addresses.push(address);
+    }
+    let staking_addresses = wallet_client
+        .staking_addresses(&name, enckey)?
+        .iter()
+        .map(ToString::to_string)
+        .collect();
+
+    Ok(SnapshotPayload {
+        wallet,
+        state: SnapshotState {
+            name,
+            transfer_addresses,
+            staking_addresses,
+            utxos: utxos.iter().map(SnapshotUtxo::from).collect(),
+            sync_height,
+        },
+    })
+}
+
+fn parse_snapshot_state(ctx: &mut FunctionContext, i: i32) -> NeonResult<(Vec<TrackedUtxo>, u64)> {
+    let state = match ctx.argument_opt(i) {
+        Some(state) if !state.is_a::<JsUndefined>() => state
+            .downcast_or_throw::<JsObject, FunctionContext>(ctx)
+            .chain_neon(ctx, "Unable to downcast snapshot state")?,
+        _ => return Ok((Vec::new(), 0)),
+    };
+
+    let utxos = if does_js_object_has_prop(ctx, state, "utxos")? {
+        let utxos = state
+            .get(ctx, "utxos")?
+            .downcast_or_throw::<JsArray, FunctionContext>(ctx)
+            .chain_neon(ctx, "Unable to downcast utxos in snapshot state")?
+            .to_vec(ctx)?;
+        utxos
+            .iter()
+            .map(|&utxo| {
+                let utxo = utxo
+                    .downcast_or_throw::<JsObject, FunctionContext>(ctx)
+                    .chain_neon(ctx, "Unable to downcast UTXO")?;
+                parse_tracked_utxo(ctx, utxo)
+            })
+            .collect::<NeonResult<Vec<TrackedUtxo>>>()?
+    } else {
+        Vec::new()
+    };
+
+    let sync_height = if does_js_object_has_prop(ctx, state, "syncHeight")? {
+        state
+            .get(ctx, "syncHeight")?
+            .downcast_or_throw::<JsNumber, FunctionContext>(ctx)
+            .chain_neon(ctx, "Unable to downcast syncHeight in snapshot state")?
+            .value() as u64
+    } else {
+        0
+    };
+
+    Ok((utxos, sync_height))
+}